@@ -0,0 +1,73 @@
+//! Cost of updating a parsed file whose `.text` was patched, parsing it again from
+//! scratch then through `ElfParser::refresh`, which only reads the segments again.
+//!
+//! Run with `cargo bench --bench refresh`
+
+#[path = "../tests/fixtures.rs"]
+mod fixtures;
+
+use elf_parser::bytes::Address;
+use elf_parser::elf::ehdr::{ElfHClass, ElfHData};
+use elf_parser::elf::sym::{Elf64Sym, STB_GLOBAL, STT_FUNC};
+use elf_parser::parser::ElfParser;
+use fixtures::ImageBuilder;
+use std::hint::black_box;
+use std::time::Instant;
+
+const SYMBOLS: usize = 300_000;
+const ROUNDS: u32 = 20;
+
+fn main() {
+    let names = (0..SYMBOLS)
+        .map(|idx| format!("function_{}", idx))
+        .collect::<Vec<_>>();
+    let symbols = names
+        .iter()
+        .enumerate()
+        .map(|(idx, name)| {
+            let symbol = Elf64Sym {
+                name: 0,
+                info: STB_GLOBAL << 4 | STT_FUNC,
+                other: 0,
+                shndx: 1,
+                value: Address(idx as u64 * 4),
+                size: 4,
+            };
+            (name.as_str(), symbol)
+        })
+        .collect::<Vec<_>>();
+    let image = ImageBuilder::minimal(ElfHClass::ElfClass64, ElfHData::ElfData2Lsb)
+        .symbols(&symbols)
+        .build();
+
+    // every round flips an instruction of `.text`
+    let off = image.section(".text").offset as usize;
+    let rounds = (0..ROUNDS)
+        .map(|round| {
+            let mut bytes = image.bytes.clone();
+            bytes[off] ^= round as u8;
+            bytes
+        })
+        .collect::<Vec<_>>();
+
+    let start = Instant::now();
+    for bytes in &rounds {
+        black_box(ElfParser::parse(bytes).unwrap());
+    }
+    let parsed = start.elapsed();
+
+    let mut parser = ElfParser::parse(&image.bytes).unwrap();
+    let start = Instant::now();
+    for bytes in &rounds {
+        black_box(parser.refresh(bytes).unwrap());
+    }
+    let refreshed = start.elapsed();
+
+    println!(
+        "{} byte file with {} symbols, .text patched",
+        image.bytes.len(),
+        SYMBOLS
+    );
+    println!("  parse:   {:>10.3?} per round", parsed / ROUNDS);
+    println!("  refresh: {:>10.3?} per round", refreshed / ROUNDS);
+}
@@ -0,0 +1,84 @@
+//! Cost of looking symbols up by name in a large symbol table, scanning the tables
+//! as done before the name index, then through `ElfParser::find_symbol`.
+//!
+//! Run with `cargo bench --bench symbol_lookup`
+
+#[path = "../tests/fixtures.rs"]
+mod fixtures;
+
+use elf_parser::bytes::Address;
+use elf_parser::elf::ehdr::{ElfHClass, ElfHData};
+use elf_parser::elf::sym::{Elf64Sym, STB_GLOBAL, STT_FUNC};
+use elf_parser::parser::ElfParser;
+use fixtures::ImageBuilder;
+use std::hint::black_box;
+use std::time::Instant;
+
+const SYMBOLS: usize = 300_000;
+const LOOKUPS: usize = 1_000;
+
+fn scan<'a>(parser: &'a ElfParser, name: &str) -> Option<&'a Elf64Sym> {
+    parser.symbol_tables.iter().find_map(|table| {
+        table
+            .symbols
+            .iter()
+            .find(|sym| parser.symbol_name(table, sym).is_ok_and(|n| n == name))
+    })
+}
+
+fn main() {
+    let names = (0..SYMBOLS)
+        .map(|idx| format!("function_{}", idx))
+        .collect::<Vec<_>>();
+    let symbols = names
+        .iter()
+        .enumerate()
+        .map(|(idx, name)| {
+            let symbol = Elf64Sym {
+                name: 0,
+                info: STB_GLOBAL << 4 | STT_FUNC,
+                other: 0,
+                shndx: 1,
+                value: Address(idx as u64 * 4),
+                size: 4,
+            };
+            (name.as_str(), symbol)
+        })
+        .collect::<Vec<_>>();
+    let image = ImageBuilder::minimal(ElfHClass::ElfClass64, ElfHData::ElfData2Lsb)
+        .symbols(&symbols)
+        .build();
+    let parser = image.parse().unwrap();
+
+    // names spread over the whole table, the scan finding them halfway on average
+    let queries = (0..LOOKUPS)
+        .map(|idx| names[idx * 7919 % SYMBOLS].as_str())
+        .collect::<Vec<_>>();
+
+    let start = Instant::now();
+    for name in &queries {
+        black_box(scan(&parser, name));
+    }
+    let scanned = start.elapsed();
+
+    let start = Instant::now();
+    black_box(parser.find_symbol("function_0"));
+    let built = start.elapsed();
+
+    let start = Instant::now();
+    for name in &queries {
+        black_box(parser.find_symbol(name));
+    }
+    let indexed = start.elapsed();
+
+    println!("{} lookups among {} symbols", LOOKUPS, SYMBOLS);
+    println!(
+        "  scan:        {:>10.3?} per lookup",
+        scanned / LOOKUPS as u32
+    );
+    println!("  index build: {:>10.3?}", built);
+    println!(
+        "  index:       {:>10.3?} per lookup",
+        indexed / LOOKUPS as u32
+    );
+}
@@ -0,0 +1,26 @@
+//! Prints the GNU build ID of a file in hex, as debuginfod and symbol servers expect
+//! it
+//!
+//! cargo run --example extract_buildid -- <elf-file>
+
+use elf_parser::parser::ElfParser;
+
+fn main() {
+    let Some(file) = std::env::args().nth(1) else {
+        eprintln!("usage: extract_buildid <elf-file>");
+        std::process::exit(2);
+    };
+    let data = std::fs::read(&file).expect("failed to read the file");
+    let parser = ElfParser::parse(&data).expect("failed to parse the file");
+
+    match parser.build_id().expect("failed to read the notes") {
+        Some(id) => println!(
+            "{}",
+            id.iter().map(|b| format!("{:02x}", b)).collect::<String>()
+        ),
+        None => {
+            eprintln!("{} has no build ID", file);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,33 @@
+//! Lists the sections of a file with their type, address, size and flags, much like
+//! `readelf -S`
+//!
+//! cargo run --example list_sections -- <elf-file>
+
+use elf_parser::elf::shdr::{flags_name, type_name};
+use elf_parser::parser::ElfParser;
+
+fn main() {
+    let Some(file) = std::env::args().nth(1) else {
+        eprintln!("usage: list_sections <elf-file>");
+        std::process::exit(2);
+    };
+    let data = std::fs::read(&file).expect("failed to read the file");
+    let parser = ElfParser::parse(&data).expect("failed to parse the file");
+
+    println!(
+        "{:>4}  {:<24} {:<14} {:>18} {:>10}  flags",
+        "idx", "name", "type", "address", "size"
+    );
+    for section in parser.sections().without_null() {
+        let sh = section.header();
+        println!(
+            "{:>4}  {:<24} {:<14} {:#18x} {:#10x}  {}",
+            section.index(),
+            section.name_or_guess().to_string(),
+            type_name(sh.s_type),
+            sh.addr.0,
+            sh.size,
+            flags_name(sh.flags)
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! Loads the segments of a file into the memory of a toy emulator, and dumps the
+//! first bytes found at the entry point
+//!
+//! cargo run --example load_into_emulator -- <elf-file>
+
+use elf_parser::memory::MemoryImage;
+use elf_parser::parser::ElfParser;
+
+/// Memory of the emulator: the segments copied into page aligned regions, with their
+/// permissions
+struct Emulator {
+    regions: Vec<Region>,
+    pc: u64,
+}
+
+struct Region {
+    base: u64,
+    bytes: Vec<u8>,
+    executable: bool,
+}
+
+const PAGE_SIZE: u64 = 0x1000;
+
+impl Emulator {
+    fn load(image: &MemoryImage) -> Self {
+        let regions = image
+            .segments
+            .iter()
+            .map(|segment| {
+                let base = segment.vaddr & !(PAGE_SIZE - 1);
+                let size = (segment.end() - base).div_ceil(PAGE_SIZE) * PAGE_SIZE;
+                let mut bytes = vec![0; size as usize];
+                let start = (segment.vaddr - base) as usize;
+                bytes[start..start + segment.data.len()].copy_from_slice(&segment.data);
+
+                Region {
+                    base,
+                    bytes,
+                    executable: segment.is_executable(),
+                }
+            })
+            .collect();
+
+        Emulator {
+            regions,
+            pc: image.entry,
+        }
+    }
+
+    /// Fetches up to `len` bytes of code at `addr`
+    fn fetch(&self, addr: u64, len: usize) -> Option<&[u8]> {
+        let region = self
+            .regions
+            .iter()
+            .find(|r| r.executable && addr >= r.base && addr - r.base < r.bytes.len() as u64)?;
+        let start = (addr - region.base) as usize;
+
+        Some(&region.bytes[start..(start + len).min(region.bytes.len())])
+    }
+}
+
+fn main() {
+    let Some(file) = std::env::args().nth(1) else {
+        eprintln!("usage: load_into_emulator <elf-file>");
+        std::process::exit(2);
+    };
+    let data = std::fs::read(&file).expect("failed to read the file");
+    let parser = ElfParser::parse(&data).expect("failed to parse the file");
+    let image = parser.memory_image().expect("failed to load the segments");
+
+    for segment in &image.segments {
+        println!(
+            "segment {:#x}..{:#x}, {} bytes",
+            segment.vaddr,
+            segment.end(),
+            segment.data.len()
+        );
+    }
+
+    let emulator = Emulator::load(&image);
+    let mapped = emulator
+        .regions
+        .iter()
+        .map(|r| r.bytes.len())
+        .sum::<usize>();
+    println!("{} bytes mapped, {} bytes loaded", mapped, image.size());
+    match emulator.fetch(emulator.pc, 16) {
+        Some(code) => println!("code at entry {:#x}: {:02x?}", emulator.pc, code),
+        None => println!("entry {:#x} isn't executable", emulator.pc),
+    }
+}
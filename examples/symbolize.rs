@@ -0,0 +1,35 @@
+//! Resolves an address to the function or object covering it, as `symbol+offset`
+//!
+//! cargo run --example symbolize -- <elf-file> <address>
+
+use elf_parser::parser::ElfParser;
+
+fn parse_address(s: &str) -> Option<u64> {
+    match s.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    }
+}
+
+fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    let (Some(file), Some(addr)) = (args.get(1), args.get(2).and_then(|a| parse_address(a))) else {
+        eprintln!("usage: symbolize <elf-file> <address>");
+        std::process::exit(2);
+    };
+    let data = std::fs::read(file).expect("failed to read the file");
+    let parser = ElfParser::parse(&data).expect("failed to parse the file");
+
+    let Some((r, offset)) = parser.symbolize(addr) else {
+        println!("{:#x}: ??", addr);
+        std::process::exit(1);
+    };
+    let table = &parser.symbol_tables[r.table];
+    let symbol = &table.symbols[r.index];
+    let name = parser.symbol_name(table, symbol).unwrap_or_default();
+
+    match offset {
+        0 => println!("{:#x}: {}", addr, name),
+        _ => println!("{:#x}: {}+{:#x}", addr, name, offset),
+    }
+}
@@ -0,0 +1,97 @@
+//! Compares the memory held by the resolved names of 1,000 parses of the same file,
+//! as `String`s through `ElfParser` and as `NameId`s through a `Workspace`
+//!
+//! cargo run --release --example workspace_memory [elf-file]
+
+use elf_parser::parser::ElfParser;
+use elf_parser::workspace::Workspace;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const PARSES: usize = 1000;
+
+/// Keeps track of the number of bytes currently allocated
+struct Counter;
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counter {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        LIVE.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counter = Counter;
+
+/// Number of bytes still allocated by `f` once it returns, along with its result
+fn measure<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = LIVE.load(Ordering::Relaxed);
+    let result = f();
+
+    (result, LIVE.load(Ordering::Relaxed) - before)
+}
+
+fn main() {
+    let file = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "out/rv64i-test".to_string());
+    let data = std::fs::read(&file).expect("failed to read the file");
+
+    let (names, strings) = measure(|| {
+        (0..PARSES)
+            .map(|_| {
+                let parser = ElfParser::parse(&data).expect("failed to parse the file");
+                let sections: Vec<String> = parser
+                    .section_headers
+                    .iter()
+                    .map(|sh| parser.section_name(sh).unwrap_or_default())
+                    .collect();
+                let symbols: Vec<Vec<String>> = parser
+                    .symbol_tables
+                    .iter()
+                    .map(|t| {
+                        t.symbols
+                            .iter()
+                            .map(|s| parser.symbol_name(t, s).unwrap_or_default())
+                            .collect()
+                    })
+                    .collect();
+
+                (sections, symbols)
+            })
+            .collect::<Vec<_>>()
+    });
+    drop(names);
+
+    let ((workspace, parsed), interned) = measure(|| {
+        let mut workspace = Workspace::new();
+        let parsed: Vec<_> = (0..PARSES)
+            .map(|_| {
+                let p = workspace.parse(&data).expect("failed to parse the file");
+                (p.section_names, p.symbol_names)
+            })
+            .collect();
+
+        (workspace, parsed)
+    });
+
+    println!("{} parses of {}:", PARSES, file);
+    println!("  String names:   {:>10} bytes", strings);
+    println!(
+        "  Interned names: {:>10} bytes ({} distinct strings)",
+        interned,
+        workspace.len()
+    );
+    println!(
+        "  reduction:      {:>9.1}%",
+        100.0 - interned as f64 * 100.0 / strings.max(1) as f64
+    );
+    drop(parsed);
+}
@@ -0,0 +1,89 @@
+use crate::elf::version::VER_FLG_BASE;
+use crate::exports::Export;
+use crate::parser::{ElfParser, ParseError};
+use std::collections::BTreeMap;
+
+/// An exported symbol present in both files whose size, binding or default version
+/// changed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolChange {
+    pub old: Export,
+    pub new: Export,
+}
+
+/// Differences between the exported symbols and version definitions of two builds
+/// of a shared object
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AbiDiff {
+    pub removed: Vec<Export>,
+    pub added: Vec<Export>,
+    pub changed: Vec<SymbolChange>,
+    pub removed_versions: Vec<String>,
+    pub added_versions: Vec<String>,
+}
+
+impl AbiDiff {
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty()
+            && self.added.is_empty()
+            && self.changed.is_empty()
+            && self.removed_versions.is_empty()
+            && self.added_versions.is_empty()
+    }
+
+    /// Whether a file linked against the old build may fail to load against the new
+    /// one, as a symbol or version node it could depend on is gone
+    pub fn has_removals(&self) -> bool {
+        !self.removed.is_empty() || !self.removed_versions.is_empty()
+    }
+}
+
+/// Names of the versions defined by the file, without the one naming the file itself
+fn defined_versions(parser: &ElfParser) -> Result<Vec<String>, ParseError> {
+    parser
+        .version_definitions()?
+        .iter()
+        .filter(|def| def.flags & VER_FLG_BASE == 0)
+        .filter_map(|def| def.names.first())
+        .map(|name| parser.dynamic_string(*name))
+        .collect()
+}
+
+/// Compares the exports of `old` and `new`, keyed on their (name, version) pairs
+pub fn abi_diff(old: &ElfParser, new: &ElfParser) -> Result<AbiDiff, ParseError> {
+    let key = |e: &Export| (e.name.clone(), e.version.clone());
+    let old_exports: BTreeMap<_, _> = old.exports()?.into_iter().map(|e| (key(&e), e)).collect();
+    let mut new_exports: BTreeMap<_, _> =
+        new.exports()?.into_iter().map(|e| (key(&e), e)).collect();
+
+    let mut diff = AbiDiff::default();
+    for (key, old) in old_exports {
+        match new_exports.remove(&key) {
+            None => diff.removed.push(old),
+            Some(new)
+                if old.size != new.size
+                    || old.binding != new.binding
+                    || old.default_version != new.default_version =>
+            {
+                diff.changed.push(SymbolChange { old, new })
+            }
+            Some(_) => {}
+        }
+    }
+    diff.added = new_exports.into_values().collect();
+
+    let old_versions = defined_versions(old)?;
+    let new_versions = defined_versions(new)?;
+    diff.removed_versions = old_versions
+        .iter()
+        .filter(|v| !new_versions.contains(v))
+        .cloned()
+        .collect();
+    diff.added_versions = new_versions
+        .iter()
+        .filter(|v| !old_versions.contains(v))
+        .cloned()
+        .collect();
+
+    Ok(diff)
+}
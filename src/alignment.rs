@@ -0,0 +1,308 @@
+use crate::elf::phdr::{PType, PF_EXEC, PF_READ, PF_WRITE};
+use crate::parser::ElfParser;
+use std::fmt;
+use std::ops::Range;
+
+/// A loadable segment that can't be mapped with pages of the size it was checked
+/// against
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlignmentIssue {
+    /// `p_align` is smaller than the page size
+    AlignBelowPageSize { segment: usize, align: u64 },
+    /// `p_vaddr` and `p_offset` land at different offsets within their pages, so the
+    /// file pages can't be mapped at the requested address
+    OffsetNotCongruent {
+        segment: usize,
+        vaddr: u64,
+        offset: u64,
+    },
+    /// The segment shares a page with an earlier one requesting other permissions
+    ConflictingPermissions {
+        segment: usize,
+        other: usize,
+        /// Address of the first shared page
+        page: u64,
+    },
+    /// `p_memsz` is smaller than `p_filesz`
+    MemszBelowFilesz {
+        segment: usize,
+        filesz: u64,
+        memsz: u64,
+    },
+    /// The file image of the segment reaches past the last page of the file, which
+    /// can't be backed by it
+    BeyondLastFilePage {
+        segment: usize,
+        end: u64,
+        file_size: u64,
+    },
+    /// `p_vaddr + p_memsz` overflows the address space
+    AddressOverflow {
+        segment: usize,
+        vaddr: u64,
+        memsz: u64,
+    },
+}
+
+impl AlignmentIssue {
+    /// Index of the program header of the offending segment
+    pub fn segment(&self) -> usize {
+        match self {
+            AlignmentIssue::AlignBelowPageSize { segment, .. }
+            | AlignmentIssue::OffsetNotCongruent { segment, .. }
+            | AlignmentIssue::ConflictingPermissions { segment, .. }
+            | AlignmentIssue::MemszBelowFilesz { segment, .. }
+            | AlignmentIssue::BeyondLastFilePage { segment, .. }
+            | AlignmentIssue::AddressOverflow { segment, .. } => *segment,
+        }
+    }
+}
+
+impl fmt::Display for AlignmentIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlignmentIssue::AlignBelowPageSize { segment, align } => write!(
+                f,
+                "segment [{}] alignment {:#x} is below the page size",
+                segment, align
+            ),
+            AlignmentIssue::OffsetNotCongruent {
+                segment,
+                vaddr,
+                offset,
+            } => write!(
+                f,
+                "segment [{}] vaddr {:#x} and offset {:#x} are not congruent modulo the page size",
+                segment, vaddr, offset
+            ),
+            AlignmentIssue::ConflictingPermissions {
+                segment,
+                other,
+                page,
+            } => write!(
+                f,
+                "segment [{}] shares the page at {:#x} with segment [{}] with other permissions",
+                segment, page, other
+            ),
+            AlignmentIssue::MemszBelowFilesz {
+                segment,
+                filesz,
+                memsz,
+            } => write!(
+                f,
+                "segment [{}] memsz {:#x} is below its filesz {:#x}",
+                segment, memsz, filesz
+            ),
+            AlignmentIssue::BeyondLastFilePage {
+                segment,
+                end,
+                file_size,
+            } => write!(
+                f,
+                "segment [{}] file image ends at {:#x}, past the last page of the {:#x} bytes file",
+                segment, end, file_size
+            ),
+            AlignmentIssue::AddressOverflow {
+                segment,
+                vaddr,
+                memsz,
+            } => write!(
+                f,
+                "segment [{}] of {:#x} bytes at {:#x} overflows the address space",
+                segment, memsz, vaddr
+            ),
+        }
+    }
+}
+
+/// Permissions of a range of pages, merged from every loadable segment mapping them
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct Perms {
+    pub read: bool,
+    pub write: bool,
+    pub execute: bool,
+    /// The pages are both writable and executable, which loaders enforcing W^X refuse
+    /// to map
+    pub write_execute: bool,
+}
+
+impl Perms {
+    /// Permissions granted by the `PF_*` flags of a program header
+    pub fn from_flags(flags: u32) -> Self {
+        let (write, execute) = (flags & PF_WRITE != 0, flags & PF_EXEC != 0);
+
+        Perms {
+            read: flags & PF_READ != 0,
+            write,
+            execute,
+            write_execute: write && execute,
+        }
+    }
+
+    /// Union of both permissions, as granted to a page mapped by two segments
+    pub fn union(&self, other: &Perms) -> Self {
+        let (write, execute) = (self.write || other.write, self.execute || other.execute);
+
+        Perms {
+            read: self.read || other.read,
+            write,
+            execute,
+            write_execute: write && execute,
+        }
+    }
+}
+
+/// Same notation as the `Flg` column of the program headers
+impl fmt::Display for Perms {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}{}",
+            if self.read { "R" } else { " " },
+            if self.write { "W" } else { " " },
+            if self.execute { "E" } else { " " }
+        )
+    }
+}
+
+fn page_down(value: u64, page_size: u64) -> u64 {
+    value - value % page_size
+}
+
+fn page_up(value: u64, page_size: u64) -> Option<u64> {
+    value.checked_next_multiple_of(page_size)
+}
+
+impl ElfParser<'_> {
+    /// Checks that every `PT_LOAD` segment can be mapped with pages of `page_size`
+    /// bytes, which is expected to be a power of two, without mapping anything nor
+    /// reading the segments
+    pub fn check_load_alignment(&self, page_size: u64) -> Vec<AlignmentIssue> {
+        let page_size = page_size.max(1);
+        let file_size = self.data().len() as u64;
+        let mut issues = vec![];
+        // page range spanned by each segment checked so far, with its flags
+        let mut spans: Vec<(usize, u64, u64, u32)> = vec![];
+
+        for (segment, ph) in self.program_headers.iter().enumerate() {
+            if ph.p_type != PType::PtLoad {
+                continue;
+            }
+
+            let vaddr = ph.vaddr.0;
+            if ph.align < page_size {
+                issues.push(AlignmentIssue::AlignBelowPageSize {
+                    segment,
+                    align: ph.align,
+                });
+            }
+
+            if vaddr % page_size != ph.offset % page_size {
+                issues.push(AlignmentIssue::OffsetNotCongruent {
+                    segment,
+                    vaddr,
+                    offset: ph.offset,
+                });
+            }
+
+            if ph.memsz < ph.filesz {
+                issues.push(AlignmentIssue::MemszBelowFilesz {
+                    segment,
+                    filesz: ph.filesz,
+                    memsz: ph.memsz,
+                });
+            }
+
+            let file_end = ph.offset.saturating_add(ph.filesz);
+            let last_file_page = page_up(file_size, page_size).unwrap_or(u64::MAX);
+            if ph.filesz > 0 && page_up(file_end, page_size).is_none_or(|end| end > last_file_page)
+            {
+                issues.push(AlignmentIssue::BeyondLastFilePage {
+                    segment,
+                    end: file_end,
+                    file_size,
+                });
+            }
+
+            let Some(end) = vaddr
+                .checked_add(ph.memsz)
+                .and_then(|end| page_up(end, page_size))
+            else {
+                issues.push(AlignmentIssue::AddressOverflow {
+                    segment,
+                    vaddr,
+                    memsz: ph.memsz,
+                });
+                continue;
+            };
+            let start = page_down(vaddr, page_size);
+
+            let conflict = spans.iter().find(|(_, s, e, flags)| {
+                ph.memsz > 0 && *flags != ph.flags && start < *e && *s < end
+            });
+            if let Some((other, other_start, _, _)) = conflict {
+                issues.push(AlignmentIssue::ConflictingPermissions {
+                    segment,
+                    other: *other,
+                    page: start.max(*other_start),
+                });
+            }
+            spans.push((segment, start, end, ph.flags));
+        }
+
+        issues
+    }
+
+    /// Maps the pages of `page_size` bytes spanned by the `PT_LOAD` segments to the
+    /// permissions they get, each segment being rounded out to whole pages. Pages
+    /// mapped by several segments get the union of their permissions. The ranges are
+    /// sorted, don't overlap, and adjacent ones have different permissions
+    pub fn permission_map(&self, page_size: u64) -> Vec<(Range<u64>, Perms)> {
+        let page_size = page_size.max(1);
+        let spans = self
+            .program_headers
+            .iter()
+            .filter(|ph| ph.p_type == PType::PtLoad && ph.memsz > 0)
+            .map(|ph| {
+                let start = page_down(ph.vaddr.0, page_size);
+                let end = ph
+                    .vaddr
+                    .0
+                    .checked_add(ph.memsz)
+                    .and_then(|end| page_up(end, page_size))
+                    .unwrap_or(u64::MAX);
+
+                (start..end, Perms::from_flags(ph.flags))
+            })
+            .collect::<Vec<_>>();
+
+        let mut bounds = spans
+            .iter()
+            .flat_map(|(range, _)| [range.start, range.end])
+            .collect::<Vec<_>>();
+        bounds.sort_unstable();
+        bounds.dedup();
+
+        let mut map: Vec<(Range<u64>, Perms)> = vec![];
+        for window in bounds.windows(2) {
+            let range = window[0]..window[1];
+            let mut covering = spans
+                .iter()
+                .filter(|(span, _)| span.start <= range.start && range.end <= span.end)
+                .map(|(_, perms)| *perms);
+            let Some(first) = covering.next() else {
+                continue;
+            };
+            let perms = covering.fold(first, |perms, other| perms.union(&other));
+
+            match map.last_mut() {
+                Some((last, last_perms)) if last.end == range.start && *last_perms == perms => {
+                    last.end = range.end;
+                }
+                _ => map.push((range, perms)),
+            }
+        }
+
+        map
+    }
+}
@@ -0,0 +1,261 @@
+//! Reader of `ar` archives, the container of static libraries, covering the System V
+//! (GNU) and BSD variants written by binutils and LLVM.
+//!
+//! Members start with a 60 bytes header whose name is either inline and ended by a
+//! `/`, an offset into the long names table `//` as in `/<offset>`, or for BSD archives
+//! `#1/<len>` with the name stored at the start of the member data. Member data is
+//! padded to an even offset. The symbol index is the `/` member, or `/SYM64/` for
+//! archives over 4 GiB, and `__.SYMDEF` in BSD archives.
+
+use crate::parser::{ElfParser, ParseError};
+use log::debug;
+use std::ops::Range;
+
+pub const AR_MAGIC: &[u8; 8] = b"!<arch>\n";
+/// Magic number of thin archives, which only reference their members by path
+pub const AR_THIN_MAGIC: &[u8; 8] = b"!<thin>\n";
+const HEADER_SIZE: usize = 60;
+
+/// A file stored in the archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    pub name: String,
+    /// Range of the archive holding the contents of the member
+    pub data: Range<usize>,
+    /// Offset of the member header, which the symbol index refers to
+    pub header_offset: usize,
+}
+
+/// An `ar` archive, borrowing the bytes it was parsed from
+#[derive(Debug, Clone)]
+pub struct Archive<'a> {
+    data: &'a [u8],
+    members: Vec<Member>,
+    /// Symbols of the index, along with the index of the member defining them
+    symbols: Vec<(String, usize)>,
+}
+
+/// Parses a decimal field of a member header, padded with spaces
+fn decimal(field: &[u8]) -> Result<usize, ParseError> {
+    std::str::from_utf8(field)
+        .ok()
+        .and_then(|s| s.trim_end().parse().ok())
+        .ok_or(ParseError::InvalidArchive(
+            "bad decimal field in member header",
+        ))
+}
+
+/// NUL separated strings of a symbol index, `count` of them at most
+fn index_names(strings: &[u8], count: usize) -> Vec<String> {
+    strings
+        .split(|&c| c == 0)
+        .take(count)
+        .map(|name| String::from_utf8_lossy(name).into_owned())
+        .collect()
+}
+
+/// Decodes the System V symbol index, a big endian count followed by as many member
+/// header offsets and then as many names, with words of `word` bytes
+fn sysv_index(data: &[u8], word: usize) -> Result<Vec<(String, usize)>, ParseError> {
+    let read = |off: usize| -> Result<usize, ParseError> {
+        let bytes = data
+            .get(off..off + word)
+            .ok_or(ParseError::InvalidArchive("truncated symbol index"))?;
+
+        Ok(bytes.iter().fold(0u64, |v, b| v << 8 | *b as u64) as usize)
+    };
+
+    let count = read(0)?;
+    let offsets = (1..=count)
+        .map(|idx| read(idx.checked_mul(word).ok_or(ParseError::InvalidLength)?))
+        .collect::<Result<Vec<_>, _>>()?;
+    let strings = &data[(count + 1) * word..];
+
+    Ok(index_names(strings, count)
+        .into_iter()
+        .zip(offsets)
+        .collect())
+}
+
+/// Decodes the BSD symbol index: the size of an array of little endian (name offset,
+/// member header offset) pairs, the array, then the size of the strings and the
+/// strings
+fn bsd_index(data: &[u8]) -> Result<Vec<(String, usize)>, ParseError> {
+    let read = |off: usize| -> Result<usize, ParseError> {
+        let bytes = data
+            .get(off..off + 4)
+            .ok_or(ParseError::InvalidArchive("truncated symbol index"))?;
+
+        Ok(u32::from_le_bytes(bytes.try_into().unwrap()) as usize)
+    };
+
+    let ranlib_size = read(0)?;
+    let strings_off = 4 + ranlib_size + 4;
+    let strings = data
+        .get(strings_off..strings_off + read(4 + ranlib_size)?)
+        .ok_or(ParseError::InvalidArchive("truncated symbol index"))?;
+
+    (0..ranlib_size / 8)
+        .map(|idx| {
+            let name_off = read(4 + idx * 8)?;
+            let name = strings
+                .get(name_off..)
+                .and_then(|s| s.split(|&c| c == 0).next())
+                .ok_or(ParseError::InvalidArchive("symbol name out of the index"))?;
+
+            Ok((
+                String::from_utf8_lossy(name).into_owned(),
+                read(8 + idx * 8)?,
+            ))
+        })
+        .collect()
+}
+
+impl<'a> Archive<'a> {
+    /// Whether `data` starts like an archive this module can read
+    pub fn is_archive(data: &[u8]) -> bool {
+        data.starts_with(AR_MAGIC)
+    }
+
+    /// Walks the member headers of the archive, resolving the long names and decoding
+    /// the symbol index. Thin archives are refused, as their members aren't held by
+    /// the archive
+    pub fn parse(data: &'a [u8]) -> Result<Self, ParseError> {
+        if data.starts_with(AR_THIN_MAGIC) {
+            return Err(ParseError::InvalidArchive("thin archives hold no members"));
+        }
+        if !Archive::is_archive(data) {
+            return Err(ParseError::InvalidArchive("missing !<arch> magic"));
+        }
+
+        let mut members = vec![];
+        let mut long_names: &[u8] = &[];
+        let mut index = vec![];
+        let mut off = AR_MAGIC.len();
+
+        while off < data.len() {
+            let header = data
+                .get(off..off + HEADER_SIZE)
+                .ok_or(ParseError::InvalidArchive("truncated member header"))?;
+            if &header[58..60] != b"`\n" {
+                return Err(ParseError::InvalidArchive("bad member header terminator"));
+            }
+
+            let size = decimal(&header[48..58])?;
+            let start = off + HEADER_SIZE;
+            let end = start
+                .checked_add(size)
+                .filter(|end| *end <= data.len())
+                .ok_or(ParseError::InvalidArchive("member runs past the end"))?;
+            let name_field = std::str::from_utf8(&header[..16])
+                .map_err(|_| ParseError::InvalidArchive("bad member name"))?
+                .trim_end();
+
+            let (name, data_start) = match name_field {
+                "/" | "/SYM64/" | "__.SYMDEF" | "__.SYMDEF SORTED" => {
+                    let word = if name_field == "/SYM64/" { 8 } else { 4 };
+                    index = match name_field {
+                        "/" | "/SYM64/" => sysv_index(&data[start..end], word)?,
+                        _ => bsd_index(&data[start..end])?,
+                    };
+                    (None, start)
+                }
+                "//" => {
+                    long_names = &data[start..end];
+                    (None, start)
+                }
+                n if n.starts_with("#1/") => {
+                    // BSD long names take the start of the member data
+                    let len = decimal(&n.as_bytes()[3..])?;
+                    let name = data
+                        .get(start..start + len)
+                        .filter(|_| len <= size)
+                        .ok_or(ParseError::InvalidArchive("BSD name runs past the member"))?;
+                    let name = String::from_utf8_lossy(name);
+
+                    // the BSD symbol index may also have its name stored this way
+                    let name = name.trim_end_matches('\0');
+                    if name.starts_with("__.SYMDEF") {
+                        index = bsd_index(&data[start + len..end])?;
+                        (None, start + len)
+                    } else {
+                        (Some(name.to_string()), start + len)
+                    }
+                }
+                n if n.starts_with('/') => {
+                    let name_off = decimal(&n.as_bytes()[1..])?;
+                    let name = long_names
+                        .get(name_off..)
+                        .and_then(|names| names.split(|&c| c == b'\n').next())
+                        .ok_or(ParseError::InvalidArchive("long name out of the table"))?;
+                    let name = String::from_utf8_lossy(name);
+
+                    (Some(name.trim_end_matches('/').to_string()), start)
+                }
+                n => (Some(n.trim_end_matches('/').to_string()), start),
+            };
+
+            if let Some(name) = name {
+                members.push(Member {
+                    name,
+                    data: data_start..end,
+                    header_offset: off,
+                });
+            }
+
+            // members are aligned to 2 bytes
+            off = end + end % 2;
+        }
+
+        let symbols = index
+            .into_iter()
+            .filter_map(|(name, header_offset)| {
+                let member = members
+                    .iter()
+                    .position(|m| m.header_offset == header_offset)?;
+                Some((name, member))
+            })
+            .collect::<Vec<_>>();
+
+        debug!(
+            "parsed archive of {} members and {} indexed symbols",
+            members.len(),
+            symbols.len()
+        );
+
+        Ok(Archive {
+            data,
+            members,
+            symbols,
+        })
+    }
+
+    /// Files stored in the archive, leaving out the symbol index and long names table
+    pub fn members(&self) -> &[Member] {
+        &self.members
+    }
+
+    pub fn member_data(&self, idx: usize) -> Result<&'a [u8], ParseError> {
+        let member = self.members.get(idx).ok_or(ParseError::InvalidLength)?;
+
+        Ok(&self.data[member.data.clone()])
+    }
+
+    /// Parses the member at `idx` as an ELF file, borrowing it from the archive
+    pub fn parse_member(&self, idx: usize) -> Result<ElfParser<'a>, ParseError> {
+        ElfParser::parse(self.member_data(idx)?)
+    }
+
+    /// Symbols of the index, with the index of the member defining each of them
+    pub fn symbols(&self) -> &[(String, usize)] {
+        &self.symbols
+    }
+
+    /// Index of the member defining `name` according to the symbol index
+    pub fn find_symbol(&self, name: &str) -> Option<usize> {
+        self.symbols
+            .iter()
+            .find(|(symbol, _)| symbol == name)
+            .map(|(_, member)| *member)
+    }
+}
@@ -1,4 +1,6 @@
-use crate::elf::ehdr::ElfHData;
+use crate::elf::ehdr::{Elf64Ident, ElfHClass, ElfHData};
+use crate::layout::FieldSpec;
+use crate::parser::ParseError;
 
 #[repr(C)]
 #[derive(Default, Copy, Clone, PartialEq)]
@@ -10,6 +12,12 @@ impl std::fmt::Debug for Address {
     }
 }
 
+impl std::fmt::LowerHex for Address {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::LowerHex::fmt(&self.0, f)
+    }
+}
+
 pub fn convert<T: GenericBytes<N>, const N: usize>(bytes: [u8; N], endianness: ElfHData) -> T {
     if endianness == ElfHData::ElfData2Msb {
         T::from_be_bytes(bytes)
@@ -18,9 +26,283 @@ pub fn convert<T: GenericBytes<N>, const N: usize>(bytes: [u8; N], endianness: E
     }
 }
 
+/// Encodes `value` in the byte order `endianness`, the inverse of `convert`
+pub fn encode<T: GenericBytes<N>, const N: usize>(value: T, endianness: ElfHData) -> [u8; N] {
+    if endianness == ElfHData::ElfData2Msb {
+        value.to_be_bytes()
+    } else {
+        value.to_le_bytes()
+    }
+}
+
+/// Encoding of the file as declared by its ident, needed to decode every multi-byte
+/// field: the byte order, and the class which decides the width of word-sized fields
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ParseContext {
+    pub endianness: ElfHData,
+    pub class: ElfHClass,
+}
+
+impl From<&Elf64Ident> for ParseContext {
+    fn from(ident: &Elf64Ident) -> Self {
+        ParseContext {
+            endianness: ident.data,
+            class: ident.class,
+        }
+    }
+}
+
+impl ParseContext {
+    pub fn new(endianness: ElfHData, class: ElfHClass) -> Self {
+        ParseContext { endianness, class }
+    }
+
+    /// Width in bytes of addresses, offsets and sizes for the file class
+    pub fn word_size(&self) -> usize {
+        if self.class == ElfHClass::ElfClass64 {
+            8
+        } else {
+            4
+        }
+    }
+
+    fn read<T: GenericBytes<N>, const N: usize>(
+        &self,
+        bytes: &[u8],
+        off: usize,
+    ) -> Result<T, ParseError> {
+        let field = bytes
+            .get(off..off.checked_add(N).ok_or(ParseError::InvalidLength)?)
+            .ok_or(ParseError::InvalidLength)?;
+
+        Ok(convert(field.try_into().unwrap(), self.endianness))
+    }
+
+    pub fn read_u8(&self, bytes: &[u8], off: usize) -> Result<u8, ParseError> {
+        bytes.get(off).copied().ok_or(ParseError::InvalidLength)
+    }
+
+    pub fn read_u16(&self, bytes: &[u8], off: usize) -> Result<u16, ParseError> {
+        self.read(bytes, off)
+    }
+
+    pub fn read_u32(&self, bytes: &[u8], off: usize) -> Result<u32, ParseError> {
+        self.read(bytes, off)
+    }
+
+    pub fn read_u64(&self, bytes: &[u8], off: usize) -> Result<u64, ParseError> {
+        self.read(bytes, off)
+    }
+
+    pub fn read_i64(&self, bytes: &[u8], off: usize) -> Result<i64, ParseError> {
+        self.read(bytes, off)
+    }
+
+    /// Reads an unsigned word-sized field, 4 or 8 bytes depending on the class,
+    /// widened to 64 bits
+    pub fn read_uword(&self, bytes: &[u8], off: usize) -> Result<u64, ParseError> {
+        if self.class == ElfHClass::ElfClass64 {
+            self.read_u64(bytes, off)
+        } else {
+            Ok(self.read_u32(bytes, off)? as u64)
+        }
+    }
+
+    /// Reads a signed word-sized field, 4 or 8 bytes depending on the class,
+    /// sign-extended to 64 bits
+    pub fn read_sword(&self, bytes: &[u8], off: usize) -> Result<i64, ParseError> {
+        if self.class == ElfHClass::ElfClass64 {
+            self.read_i64(bytes, off)
+        } else {
+            Ok(self.read_u32(bytes, off)? as i32 as i64)
+        }
+    }
+
+    pub fn read_addr(&self, bytes: &[u8], off: usize) -> Result<Address, ParseError> {
+        Ok(Address(self.read_uword(bytes, off)?))
+    }
+
+    pub fn put_u16(&self, out: &mut Vec<u8>, value: u16) {
+        out.extend_from_slice(&encode(value, self.endianness));
+    }
+
+    pub fn put_u32(&self, out: &mut Vec<u8>, value: u32) {
+        out.extend_from_slice(&encode(value, self.endianness));
+    }
+
+    pub fn put_u64(&self, out: &mut Vec<u8>, value: u64) {
+        out.extend_from_slice(&encode(value, self.endianness));
+    }
+
+    /// Appends a word-sized field, 4 or 8 bytes depending on the class. The value is
+    /// truncated on 32-bit files
+    pub fn put_uword(&self, out: &mut Vec<u8>, value: u64) {
+        if self.class == ElfHClass::ElfClass64 {
+            self.put_u64(out, value)
+        } else {
+            self.put_u32(out, value as u32)
+        }
+    }
+
+    /// Cursor over `bytes`, decoding fields in this encoding
+    pub fn reader<'a>(&self, bytes: &'a [u8]) -> Reader<'a> {
+        Reader::new(bytes, *self)
+    }
+
+    /// Reads the field described by `spec` out of the structure held by `bytes`,
+    /// widened to 64 bits
+    pub fn read_field(&self, bytes: &[u8], spec: FieldSpec) -> Result<u64, ParseError> {
+        let mut field = self.reader(bytes).at(spec.offset);
+        match spec.size {
+            1 => Ok(field.u8()? as u64),
+            2 => Ok(field.u16()? as u64),
+            4 => Ok(field.u32()? as u64),
+            _ => field.u64(),
+        }
+    }
+
+    /// Overwrites the field described by `spec` in the structure held by `bytes`. The
+    /// value is truncated to the width of the field
+    pub fn write_field(
+        &self,
+        bytes: &mut [u8],
+        spec: FieldSpec,
+        value: u64,
+    ) -> Result<(), ParseError> {
+        let mut field = vec![];
+        match spec.size {
+            1 => field.push(value as u8),
+            2 => self.put_u16(&mut field, value as u16),
+            4 => self.put_u32(&mut field, value as u32),
+            _ => self.put_u64(&mut field, value),
+        }
+
+        bytes
+            .get_mut(spec.range())
+            .ok_or(ParseError::InvalidLength)?
+            .copy_from_slice(&field);
+
+        Ok(())
+    }
+}
+
+/// Cursor over the bytes of a structure, decoding its fields in the encoding of the
+/// file as it moves forward. Every read is checked against the end of the bytes: a
+/// short one fails with `ParseError::InvalidLength` and leaves the cursor in place
+#[derive(Debug, Clone)]
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+    ctx: ParseContext,
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(bytes: &'a [u8], ctx: ParseContext) -> Self {
+        Reader { bytes, ctx, pos: 0 }
+    }
+
+    /// Reader over the same bytes with its cursor at `offset`, which may be past the
+    /// end, the reads then failing
+    pub fn at(&self, offset: usize) -> Self {
+        Reader {
+            pos: offset,
+            ..*self
+        }
+    }
+
+    /// Offset of the cursor from the start of the bytes
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Number of bytes left past the cursor
+    pub fn remaining(&self) -> usize {
+        self.bytes.len().saturating_sub(self.pos)
+    }
+
+    /// Returns the next `len` bytes
+    pub fn bytes(&mut self, len: usize) -> Result<&'a [u8], ParseError> {
+        let bytes = self
+            .pos
+            .checked_add(len)
+            .and_then(|end| self.bytes.get(self.pos..end))
+            .ok_or(ParseError::InvalidLength)?;
+        self.pos += len;
+
+        Ok(bytes)
+    }
+
+    /// Moves the cursor `len` bytes forward
+    pub fn skip(&mut self, len: usize) -> Result<(), ParseError> {
+        self.bytes(len).map(|_| ())
+    }
+
+    /// Moves the cursor to the next multiple of `align` from the start of the bytes,
+    /// over padding that must be held by the bytes
+    pub fn align(&mut self, align: usize) -> Result<(), ParseError> {
+        let align = align.max(1);
+        let padding = (align - self.pos % align) % align;
+
+        self.skip(padding)
+    }
+
+    fn read<T: GenericBytes<N>, const N: usize>(&mut self) -> Result<T, ParseError> {
+        let value = self.ctx.read(self.bytes, self.pos)?;
+        self.pos += N;
+
+        Ok(value)
+    }
+
+    pub fn u8(&mut self) -> Result<u8, ParseError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub fn u16(&mut self) -> Result<u16, ParseError> {
+        self.read()
+    }
+
+    pub fn u32(&mut self) -> Result<u32, ParseError> {
+        self.read()
+    }
+
+    pub fn u64(&mut self) -> Result<u64, ParseError> {
+        self.read()
+    }
+
+    pub fn i64(&mut self) -> Result<i64, ParseError> {
+        self.read()
+    }
+
+    /// Reads an unsigned word-sized field, 4 or 8 bytes depending on the class,
+    /// widened to 64 bits
+    pub fn uword(&mut self) -> Result<u64, ParseError> {
+        if self.ctx.class == ElfHClass::ElfClass64 {
+            self.u64()
+        } else {
+            Ok(self.u32()? as u64)
+        }
+    }
+
+    /// Reads a signed word-sized field, 4 or 8 bytes depending on the class,
+    /// sign-extended to 64 bits
+    pub fn sword(&mut self) -> Result<i64, ParseError> {
+        if self.ctx.class == ElfHClass::ElfClass64 {
+            self.i64()
+        } else {
+            Ok(self.u32()? as i32 as i64)
+        }
+    }
+
+    pub fn addr(&mut self) -> Result<Address, ParseError> {
+        Ok(Address(self.uword()?))
+    }
+}
+
 pub trait GenericBytes<const N: usize> {
     fn from_le_bytes(bytes: [u8; N]) -> Self;
     fn from_be_bytes(bytes: [u8; N]) -> Self;
+    fn to_le_bytes(self) -> [u8; N];
+    fn to_be_bytes(self) -> [u8; N];
 }
 
 impl GenericBytes<2> for u16 {
@@ -30,6 +312,12 @@ impl GenericBytes<2> for u16 {
     fn from_be_bytes(bytes: [u8; 2]) -> Self {
         u16::from_be_bytes(bytes)
     }
+    fn to_le_bytes(self) -> [u8; 2] {
+        u16::to_le_bytes(self)
+    }
+    fn to_be_bytes(self) -> [u8; 2] {
+        u16::to_be_bytes(self)
+    }
 }
 
 impl GenericBytes<4> for u32 {
@@ -39,6 +327,12 @@ impl GenericBytes<4> for u32 {
     fn from_be_bytes(bytes: [u8; 4]) -> Self {
         u32::from_be_bytes(bytes)
     }
+    fn to_le_bytes(self) -> [u8; 4] {
+        u32::to_le_bytes(self)
+    }
+    fn to_be_bytes(self) -> [u8; 4] {
+        u32::to_be_bytes(self)
+    }
 }
 
 impl GenericBytes<8> for u64 {
@@ -48,6 +342,12 @@ impl GenericBytes<8> for u64 {
     fn from_be_bytes(bytes: [u8; 8]) -> Self {
         u64::from_be_bytes(bytes)
     }
+    fn to_le_bytes(self) -> [u8; 8] {
+        u64::to_le_bytes(self)
+    }
+    fn to_be_bytes(self) -> [u8; 8] {
+        u64::to_be_bytes(self)
+    }
 }
 
 impl GenericBytes<8> for i64 {
@@ -57,6 +357,12 @@ impl GenericBytes<8> for i64 {
     fn from_be_bytes(bytes: [u8; 8]) -> Self {
         i64::from_be_bytes(bytes)
     }
+    fn to_le_bytes(self) -> [u8; 8] {
+        i64::to_le_bytes(self)
+    }
+    fn to_be_bytes(self) -> [u8; 8] {
+        i64::to_be_bytes(self)
+    }
 }
 
 impl GenericBytes<8> for Address {
@@ -67,10 +373,18 @@ impl GenericBytes<8> for Address {
     fn from_be_bytes(bytes: [u8; 8]) -> Self {
         Address(u64::from_be_bytes(bytes))
     }
+
+    fn to_le_bytes(self) -> [u8; 8] {
+        self.0.to_le_bytes()
+    }
+
+    fn to_be_bytes(self) -> [u8; 8] {
+        self.0.to_be_bytes()
+    }
 }
 
-pub fn str_from_u8(src: &[u8]) -> Result<String, ()> {
+pub fn str_from_u8(src: &[u8]) -> Result<String, ParseError> {
     let nul_range_end = src.iter().position(|&c| c == b'\0').unwrap_or(src.len());
 
-    Ok(String::from_utf8(src[0..nul_range_end].to_vec()).unwrap())
+    String::from_utf8(src[0..nul_range_end].to_vec()).map_err(|_| ParseError::InvalidUtf8)
 }
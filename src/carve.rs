@@ -0,0 +1,134 @@
+use crate::bytes::ParseContext;
+use crate::elf::ehdr::{Elf64Hdr, ELF_MAGIC};
+use crate::elf::shdr::SHT_NOBITS;
+use crate::layout;
+use crate::parser::ParseError;
+use log::debug;
+
+/// An ELF image found inside a larger blob
+#[derive(Debug, Clone)]
+pub struct EmbeddedElf {
+    /// Offset of the image from the start of the blob
+    pub offset: u64,
+    /// Number of bytes spanned by the image, as described by its own headers. It can go
+    /// past the end of the blob when the image is truncated
+    pub size_estimate: u64,
+    pub header: Elf64Hdr,
+}
+
+/// End of the furthest byte referenced by the entries of the table at `table`, as
+/// computed by `entry_end` for each of them. Entries past the end of `data` are
+/// ignored, so that truncated images can still be estimated
+fn table_extent(
+    data: &[u8],
+    table: u64,
+    count: u16,
+    ent_size: u16,
+    entry_end: impl Fn(&[u8]) -> Result<u64, ParseError>,
+) -> Result<u64, ParseError> {
+    let mut end = 0;
+
+    for idx in 0..count as u64 {
+        let off = table.saturating_add(idx * ent_size as u64);
+        let Some(entry) = usize::try_from(off)
+            .ok()
+            .and_then(|off| data.get(off..off.checked_add(ent_size as usize)?))
+        else {
+            break;
+        };
+        end = end.max(entry_end(entry)?);
+    }
+
+    Ok(end)
+}
+
+/// Estimates the number of bytes spanned by the ELF image at the start of `data` from
+/// its header tables and the sections and segments they describe
+fn estimate_size(data: &[u8], header: &Elf64Hdr) -> Result<u64, ParseError> {
+    let ctx = ParseContext::from(&header.ident);
+    let shdr = layout::shdr::for_class(ctx.class);
+    let phdr = layout::phdr::for_class(ctx.class);
+    let sh_table = header
+        .sh_off
+        .saturating_add(header.sh_num as u64 * header.sh_ent_size as u64);
+    let ph_table = header
+        .ph_off
+        .saturating_add(header.ph_num as u64 * header.ph_ent_size as u64);
+
+    let sections = table_extent(
+        data,
+        header.sh_off,
+        header.sh_num,
+        header.sh_ent_size,
+        |sh| match ctx.read_field(sh, shdr.sh_type)? as u32 {
+            SHT_NOBITS => Ok(0),
+            _ => Ok(ctx
+                .read_field(sh, shdr.sh_offset)?
+                .saturating_add(ctx.read_field(sh, shdr.sh_size)?)),
+        },
+    )?;
+
+    let segments = table_extent(
+        data,
+        header.ph_off,
+        header.ph_num,
+        header.ph_ent_size,
+        |ph| {
+            Ok(ctx
+                .read_field(ph, phdr.p_offset)?
+                .saturating_add(ctx.read_field(ph, phdr.p_filesz)?))
+        },
+    )?;
+
+    Ok([
+        header.eh_size as u64,
+        sh_table,
+        ph_table,
+        sections,
+        segments,
+    ]
+    .into_iter()
+    .max()
+    .unwrap_or_default())
+}
+
+/// Parses and validates the ELF header at the start of `data`, rejecting the ones
+/// whose table entry sizes don't match the class
+fn parse_candidate(data: &[u8]) -> Result<EmbeddedElf, ParseError> {
+    let header = *Elf64Hdr::parse(data)?.validate()?;
+    let class = header.ident.class;
+
+    let table_sizes_match = (header.ph_num == 0
+        || header.ph_ent_size as usize == layout::phdr::for_class(class).size)
+        && (header.sh_num == 0
+            || header.sh_ent_size as usize == layout::shdr::for_class(class).size);
+    if header.eh_size as usize != layout::ehdr::for_class(class).size || !table_sizes_match {
+        return Err(ParseError::InvalidLength);
+    }
+
+    Ok(EmbeddedElf {
+        offset: 0,
+        size_estimate: estimate_size(data, &header)?,
+        header,
+    })
+}
+
+/// Looks for every ELF image held by `data`, such as the ones concatenated in firmware
+/// update containers. Occurrences of the magic number that aren't followed by a valid
+/// header are skipped
+pub fn scan_blob(data: &[u8]) -> Vec<EmbeddedElf> {
+    data.windows(ELF_MAGIC.len())
+        .enumerate()
+        .filter(|(_, window)| *window == ELF_MAGIC)
+        .filter_map(|(offset, _)| match parse_candidate(&data[offset..]) {
+            Ok(elf) => Some(EmbeddedElf {
+                offset: offset as u64,
+                ..elf
+            }),
+            Err(e) => {
+                debug!("rejected ELF magic at {:#x}: {:?}", offset, e);
+                None
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,293 @@
+use crate::elf::ehdr::{EF_RISCV_RVC, EM_RISCV};
+use crate::elf::phdr::PType;
+use crate::elf::shdr::SHF_ALLOC;
+use crate::parser::{ElfParser, ParseError};
+use log::debug;
+use std::fmt;
+use std::ops::Range;
+
+/// Lowest value taken for an address when looking for pointers
+const MIN_POINTER: u64 = 0x1000;
+
+/// Rough guess of what a section holds, from statistics of its bytes
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ContentClass {
+    /// RISC-V instructions
+    Code,
+    /// Printable text, as in string literals separated by NUL bytes
+    AsciiStrings,
+    /// Aligned words of `stride` bytes pointing into the mapped memory, as in jump
+    /// tables, vtables or `.init_array`
+    PointerTable { stride: usize },
+    /// Zeros, as in `SHT_NOBITS` sections
+    ZeroFill,
+    /// Bytes too evenly distributed for anything but compressed or encrypted data
+    HighEntropy,
+    /// None of the above stands out
+    Mixed,
+}
+
+impl fmt::Display for ContentClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContentClass::Code => write!(f, "code"),
+            ContentClass::AsciiStrings => write!(f, "strings"),
+            ContentClass::PointerTable { stride } => write!(f, "pointers ({} bytes)", stride),
+            ContentClass::ZeroFill => write!(f, "zeros"),
+            ContentClass::HighEntropy => write!(f, "high entropy"),
+            ContentClass::Mixed => write!(f, "mixed"),
+        }
+    }
+}
+
+/// Thresholds of `ElfParser::classify_section_with`, each one the share of the section
+/// a kind of content must reach for the section to be classified as such
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassifyOptions {
+    /// Share of zero bytes
+    pub zero_ratio: f64,
+    /// Share of printable ASCII characters, whitespace and NUL bytes
+    pub printable_ratio: f64,
+    /// Share of the instructions decoded from the start of the section that are valid
+    pub code_ratio: f64,
+    /// Share of aligned words pointing into the mapped memory
+    pub pointer_ratio: f64,
+    /// Shannon entropy in bits per byte, out of 8, above which the section is
+    /// `HighEntropy`
+    pub entropy: f64,
+}
+
+impl Default for ClassifyOptions {
+    fn default() -> Self {
+        ClassifyOptions {
+            zero_ratio: 0.95,
+            printable_ratio: 0.95,
+            code_ratio: 0.9,
+            pointer_ratio: 0.75,
+            entropy: 7.0,
+        }
+    }
+}
+
+impl ClassifyOptions {
+    pub fn new() -> Self {
+        ClassifyOptions::default()
+    }
+
+    pub fn zero_ratio(mut self, zero_ratio: f64) -> Self {
+        self.zero_ratio = zero_ratio;
+        self
+    }
+
+    pub fn printable_ratio(mut self, printable_ratio: f64) -> Self {
+        self.printable_ratio = printable_ratio;
+        self
+    }
+
+    pub fn code_ratio(mut self, code_ratio: f64) -> Self {
+        self.code_ratio = code_ratio;
+        self
+    }
+
+    pub fn pointer_ratio(mut self, pointer_ratio: f64) -> Self {
+        self.pointer_ratio = pointer_ratio;
+        self
+    }
+
+    pub fn entropy(mut self, entropy: f64) -> Self {
+        self.entropy = entropy;
+        self
+    }
+}
+
+/// Shannon entropy of the bytes, in bits per byte
+fn entropy(data: &[u8]) -> f64 {
+    let mut histogram = [0usize; 256];
+    data.iter().for_each(|b| histogram[*b as usize] += 1);
+
+    let len = data.len() as f64;
+    histogram
+        .iter()
+        .filter(|count| **count > 0)
+        .map(|count| {
+            let p = *count as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Whether `insn` is a 32-bit RISC-V instruction of the base ISA or of the M, A, F
+/// and D extensions, from its opcode and the `funct3` values the opcode allows
+fn is_valid_insn(insn: u32) -> bool {
+    let funct3 = (insn >> 12) & 0x7;
+    let funct7 = insn >> 25;
+
+    match insn & 0x7f {
+        // LOAD, STORE
+        0x03 => funct3 != 7,
+        0x23 => funct3 <= 3,
+        // LOAD-FP, STORE-FP
+        0x07 | 0x27 => matches!(funct3, 2 | 3),
+        // MISC-MEM, OP-IMM, OP-IMM-32
+        0x0f => funct3 <= 1,
+        0x13 => true,
+        0x1b => matches!(funct3, 0 | 1 | 5),
+        // AUIPC, LUI, JAL
+        0x17 | 0x37 | 0x6f => true,
+        // AMO
+        0x2f => matches!(funct3, 2 | 3),
+        // OP, OP-32
+        0x33 => matches!(funct7, 0x00 | 0x01 | 0x20),
+        0x3b => matches!(funct7, 0x00 | 0x01 | 0x20),
+        // FMADD, FMSUB, FNMSUB, FNMADD, OP-FP
+        0x43 | 0x47 | 0x4b | 0x4f | 0x53 => true,
+        // BRANCH, JALR
+        0x63 => !matches!(funct3, 2 | 3),
+        0x67 => funct3 == 0,
+        // SYSTEM
+        0x73 => funct3 != 4,
+        _ => false,
+    }
+}
+
+/// Whether `insn` is a compressed RISC-V instruction, leaving out the all-zeros
+/// illegal instruction and the reserved encodings of quadrant 0
+fn is_valid_compressed(insn: u16) -> bool {
+    insn != 0 && !(insn & 0x3 == 0 && insn >> 13 == 4)
+}
+
+/// Share of the instructions decoded back to back from the start of `data` that are
+/// valid, compressed ones only being accepted when `rvc` is set
+fn code_density(data: &[u8], rvc: bool) -> f64 {
+    let (mut valid, mut total, mut off) = (0, 0, 0);
+
+    while off + 2 <= data.len() {
+        let low = u16::from_le_bytes([data[off], data[off + 1]]);
+        let (is_valid, len) = if low & 0x3 != 0x3 {
+            (rvc && is_valid_compressed(low), 2)
+        } else if let Some(bytes) = data.get(off..off + 4) {
+            (
+                is_valid_insn(u32::from_le_bytes(bytes.try_into().unwrap())),
+                4,
+            )
+        } else {
+            (false, 2)
+        };
+
+        valid += is_valid as usize;
+        total += 1;
+        off += len;
+    }
+
+    match total {
+        0 => 0.0,
+        _ => valid as f64 / total as f64,
+    }
+}
+
+impl ElfParser<'_> {
+    /// Address ranges mapped in memory: those of the loadable segments, or of the
+    /// allocated sections for files without segments
+    fn mapped_ranges(&self) -> Vec<Range<u64>> {
+        let segments = self
+            .program_headers
+            .iter()
+            .filter(|ph| ph.p_type == PType::PtLoad)
+            .map(|ph| ph.vaddr.0..ph.vaddr.0.saturating_add(ph.memsz))
+            .collect::<Vec<_>>();
+        if !segments.is_empty() {
+            return segments;
+        }
+
+        self.section_headers
+            .iter()
+            .filter(|sh| sh.flags & SHF_ALLOC != 0 && sh.size > 0)
+            .map(|sh| sh.addr.0..sh.addr.0.saturating_add(sh.size))
+            .collect()
+    }
+
+    /// Share of the aligned words of `stride` bytes in `data` holding an address in
+    /// `mapped`. Values within the first page aren't taken for addresses, as small
+    /// integers would point into files mapped at 0. None if `data` isn't made of such
+    /// words
+    fn pointer_density(&self, data: &[u8], stride: usize, mapped: &[Range<u64>]) -> Option<f64> {
+        if data.is_empty() || !data.len().is_multiple_of(stride) {
+            return None;
+        }
+
+        let words = data.len() / stride;
+        let pointers = (0..words)
+            .filter_map(|idx| match stride {
+                4 => self.ctx.read_u32(data, idx * 4).ok().map(u64::from),
+                _ => self.ctx.read_u64(data, idx * 8).ok(),
+            })
+            .filter(|value| *value >= MIN_POINTER && mapped.iter().any(|r| r.contains(value)))
+            .count();
+
+        Some(pointers as f64 / words as f64)
+    }
+
+    /// Classifies `data` by the first kind of content reaching its threshold
+    fn classify(&self, data: &[u8], options: &ClassifyOptions) -> ContentClass {
+        let ratio = |count: usize| count as f64 / data.len() as f64;
+
+        if data.is_empty() || ratio(data.iter().filter(|b| **b == 0).count()) >= options.zero_ratio
+        {
+            return ContentClass::ZeroFill;
+        }
+
+        // NUL bytes separate strings, but tables of small integers are mostly made of
+        // them too, so characters must make at least half of the section
+        let chars = data
+            .iter()
+            .filter(|b| b.is_ascii_graphic() || b" \t\r\n".contains(b))
+            .count();
+        let nuls = data.iter().filter(|b| **b == 0).count();
+        if ratio(chars + nuls) >= options.printable_ratio && ratio(chars) >= 0.5 {
+            return ContentClass::AsciiStrings;
+        }
+
+        let mapped = self.mapped_ranges();
+        for stride in [self.ctx.word_size(), 4] {
+            if self
+                .pointer_density(data, stride, &mapped)
+                .is_some_and(|density| density >= options.pointer_ratio)
+            {
+                return ContentClass::PointerTable { stride };
+            }
+        }
+
+        let rvc = self.headers.flags & EF_RISCV_RVC != 0;
+        if self.headers.machine == EM_RISCV && code_density(data, rvc) >= options.code_ratio {
+            return ContentClass::Code;
+        }
+
+        if entropy(data) >= options.entropy {
+            ContentClass::HighEntropy
+        } else {
+            ContentClass::Mixed
+        }
+    }
+
+    /// Guesses what the section at `idx` holds with the default thresholds, see
+    /// `classify_section_with`
+    pub fn classify_section(&self, idx: usize) -> Result<ContentClass, ParseError> {
+        self.classify_section_with(idx, &ClassifyOptions::default())
+    }
+
+    /// Guesses what the section at `idx` holds from statistics of its bytes. Sections
+    /// are tested for zeros, printable text, pointers into the mapped memory and, in
+    /// RISC-V files, valid instructions, the first kind of content reaching its
+    /// threshold in `options` winning. Sections matching none of them are
+    /// `HighEntropy` or `Mixed`, by the entropy of their bytes
+    pub fn classify_section_with(
+        &self,
+        idx: usize,
+        options: &ClassifyOptions,
+    ) -> Result<ContentClass, ParseError> {
+        let class = self.classify(self.section_data(idx)?, options);
+        debug!("section [{}] classified as {}", idx, class);
+
+        Ok(class)
+    }
+}
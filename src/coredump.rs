@@ -0,0 +1,64 @@
+//! State of the crashed process recorded by a core dump, in the notes of its
+//! `PT_NOTE` segments.
+
+use crate::bytes::ParseContext;
+use crate::elf::ehdr::ElfHType;
+use crate::elf::note::{AuxKey, AuxvEntry, Elf64Note, NT_AUXV};
+use crate::parser::{ElfParser, ParseError};
+
+/// Notes of a core dump, decoded on demand
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoreInfo {
+    /// Notes of every note segment, in file order
+    pub notes: Vec<Elf64Note>,
+    /// Encoding of the dump, which is the one of the process
+    pub ctx: ParseContext,
+}
+
+impl CoreInfo {
+    /// The first note of type `n_type` written by the kernel
+    fn core_note(&self, n_type: u32) -> Option<&Elf64Note> {
+        self.notes
+            .iter()
+            .find(|n| n.name == "CORE" && n.n_type == n_type)
+    }
+
+    /// Entries of the auxiliary vector the kernel handed to the process, in the
+    /// order it wrote them, empty when the dump has no `NT_AUXV` note
+    pub fn auxv(&self) -> Result<Vec<AuxvEntry>, ParseError> {
+        match self.core_note(NT_AUXV) {
+            Some(note) => AuxvEntry::parse(note, &self.ctx),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Value of the first entry of the auxiliary vector keyed `key`, None when there's
+    /// none or the vector can't be read
+    pub fn auxv_get(&self, key: AuxKey) -> Option<u64> {
+        self.auxv()
+            .ok()?
+            .into_iter()
+            .find(|e| e.kind() == key)
+            .map(|e| e.value)
+    }
+}
+
+impl ElfParser<'_> {
+    /// Notes of the file if it's a core dump, None for any other kind of file
+    pub fn core_info(&self) -> Result<Option<CoreInfo>, ParseError> {
+        if self.headers.e_type != ElfHType::Core {
+            return Ok(None);
+        }
+
+        let notes = self
+            .note_groups()?
+            .into_iter()
+            .flat_map(|g| g.notes)
+            .collect();
+
+        Ok(Some(CoreInfo {
+            notes,
+            ctx: self.ctx,
+        }))
+    }
+}
@@ -0,0 +1,138 @@
+//! Summary of the debug information of a file: the DWARF sections it holds, the bytes
+//! they take in the file and once uncompressed, and where else debug information may
+//! be found.
+//!
+//! Sections may be compressed either with `SHF_COMPRESSED`, their contents starting
+//! with an `Elf64_Chdr`, or the legacy GNU way, renamed to `.zdebug_*` and starting
+//! with `ZLIB` and the uncompressed size. Only these headers are read, the sections
+//! are never inflated.
+
+use crate::elf::chdr::{zdebug_size, Elf64Chdr, ELFCOMPRESS_ZLIB, ELFCOMPRESS_ZSTD};
+use crate::elf::shdr::{SHF_COMPRESSED, SHT_SYMTAB};
+use crate::parser::ElfParser;
+use std::fmt;
+
+/// How the contents of a debug section are compressed
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Compression {
+    /// `SHF_COMPRESSED` with zlib
+    Zlib,
+    /// `SHF_COMPRESSED` with Zstandard
+    Zstd,
+    /// `SHF_COMPRESSED` with an algorithm this crate doesn't know
+    Unknown(u32),
+    /// Legacy GNU `.zdebug_*` section, compressed with zlib
+    GnuZlib,
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Compression::Zlib => write!(f, "zlib"),
+            Compression::Zstd => write!(f, "zstd"),
+            Compression::Unknown(ch_type) => write!(f, "unknown ({:#x})", ch_type),
+            Compression::GnuZlib => write!(f, "zlib-gnu"),
+        }
+    }
+}
+
+/// A `.debug_*` or `.zdebug_*` section
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugSection {
+    pub section: usize,
+    pub name: String,
+    /// Bytes the section takes in the file
+    pub file_size: u64,
+    pub compression: Option<Compression>,
+    /// Size of the contents once uncompressed, as stored by the compression header.
+    /// None if the header can't be read
+    pub uncompressed_size: Option<u64>,
+}
+
+/// Whether a file is debuggable, and how much of it is debug information
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DebugInfoSummary {
+    pub sections: Vec<DebugSection>,
+    /// Debug file named by `.gnu_debuglink`, which the debug information was split
+    /// into
+    pub debuglink: Option<String>,
+    /// Whether the file has a `.symtab`, which stripping removes
+    pub has_symtab: bool,
+}
+
+impl DebugInfoSummary {
+    /// Whether the file holds any debug section
+    pub fn has_debug_info(&self) -> bool {
+        !self.sections.is_empty()
+    }
+
+    /// Bytes taken by the debug sections in the file
+    pub fn file_size(&self) -> u64 {
+        self.sections.iter().map(|s| s.file_size).sum()
+    }
+
+    /// Bytes of the debug sections once uncompressed, taking the file size of the
+    /// sections whose uncompressed size isn't known
+    pub fn uncompressed_size(&self) -> u64 {
+        self.sections
+            .iter()
+            .map(|s| s.uncompressed_size.unwrap_or(s.file_size))
+            .sum()
+    }
+
+    /// Whether any debug section is compressed
+    pub fn is_compressed(&self) -> bool {
+        self.sections.iter().any(|s| s.compression.is_some())
+    }
+}
+
+impl ElfParser<'_> {
+    /// Summarizes the debug information of the file: its `.debug_*` and `.zdebug_*`
+    /// sections, along with the size of the compressed ones read from their headers,
+    /// the file `.gnu_debuglink` points at and whether `.symtab` is there
+    pub fn debug_info_summary(&self) -> DebugInfoSummary {
+        let sections = self
+            .sections()
+            .filter(|s| s.name().starts_with(".debug_") || s.name().starts_with(".zdebug_"))
+            .map(|s| {
+                let sh = s.header();
+                let data = s.data().unwrap_or_default();
+
+                let (compression, uncompressed_size) = if sh.flags & SHF_COMPRESSED != 0 {
+                    let chdr = Elf64Chdr::parse(data, &self.ctx).ok();
+                    let compression = chdr.map(|chdr| match chdr.ch_type {
+                        ELFCOMPRESS_ZLIB => Compression::Zlib,
+                        ELFCOMPRESS_ZSTD => Compression::Zstd,
+                        t => Compression::Unknown(t),
+                    });
+                    (compression, chdr.map(|chdr| chdr.size))
+                } else if s.name().starts_with(".zdebug_") {
+                    match zdebug_size(data) {
+                        Some(size) => (Some(Compression::GnuZlib), Some(size)),
+                        // the contents may have been left uncompressed
+                        None => (None, Some(sh.file_size())),
+                    }
+                } else {
+                    (None, Some(sh.file_size()))
+                };
+
+                DebugSection {
+                    section: s.index(),
+                    name: s.name().to_string(),
+                    file_size: sh.file_size(),
+                    compression,
+                    uncompressed_size,
+                }
+            })
+            .collect();
+
+        DebugInfoSummary {
+            sections,
+            debuglink: self.gnu_debuglink().map(|(name, _)| name),
+            has_symtab: self
+                .section_headers
+                .iter()
+                .any(|sh| sh.s_type == SHT_SYMTAB),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! GNU conventions pointing at files and messages outside of the file itself.
+//!
+//! `.gnu_debuglink` names the file the debug information was split into: its base
+//! name, NUL-terminated and padded to 4 bytes, followed by the CRC-32 of the debug file
+//! in the byte order of the file. `.gnu.warning.SYMBOL` sections hold the text the
+//! linker prints when an object referencing `SYMBOL` is linked.
+
+use crate::parser::ElfParser;
+
+const DEBUGLINK_SECTION: &str = ".gnu_debuglink";
+const WARNING_PREFIX: &str = ".gnu.warning.";
+
+/// Table of the reflected CRC-32 polynomial 0xedb88320, one entry per byte value
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                0xedb88320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// CRC-32 of `data` as computed by zlib, which `.gnu_debuglink` stores for the debug
+/// file
+pub fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, b| {
+        CRC32_TABLE[((crc ^ *b as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+impl ElfParser<'_> {
+    /// Base name of the separate debug file and its CRC-32, read from
+    /// `.gnu_debuglink`. None without the section, or if it's truncated
+    pub fn gnu_debuglink(&self) -> Option<(String, u32)> {
+        let (idx, _) = self.section_by_name(DEBUGLINK_SECTION)?;
+        let data = self.section_data(idx).ok()?;
+
+        let len = data.iter().position(|b| *b == 0)?;
+        // the CRC is aligned to 4 bytes, past the NUL ending the name
+        let crc_off = (len + 1).next_multiple_of(4);
+        let crc = self.ctx.read_u32(data, crc_off).ok()?;
+
+        Some((String::from_utf8_lossy(&data[..len]).into_owned(), crc))
+    }
+
+    /// Whether `debug_file` matches the CRC-32 of `.gnu_debuglink`. False without the
+    /// section
+    pub fn matches_debuglink(&self, debug_file: &[u8]) -> bool {
+        self.gnu_debuglink()
+            .is_some_and(|(_, crc)| crc == crc32(debug_file))
+    }
+
+    /// Linker warnings of the `.gnu.warning.SYMBOL` sections, as symbol name and text
+    /// in section order
+    pub fn linker_warnings(&self) -> Vec<(String, String)> {
+        self.sections()
+            .with_prefix(WARNING_PREFIX)
+            .filter_map(|section| {
+                let symbol = section.name().strip_prefix(WARNING_PREFIX)?;
+                let text = section.data().ok()?;
+                let text = text.split(|b| *b == 0).next().unwrap_or(text);
+
+                Some((
+                    symbol.to_string(),
+                    String::from_utf8_lossy(text).into_owned(),
+                ))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,278 @@
+//! Load-time dependency graph of a set of files, linking each file to the libraries
+//! its `DT_NEEDED` entries name.
+//!
+//! Needed names are matched against the files of the set with the first of these
+//! rules that applies, in file order:
+//!
+//! 1. a file whose `DT_SONAME` is the needed name
+//! 2. a file named as the needed name
+//! 3. a file whose `DT_SONAME` or name extends the needed name with more numeric
+//!    version components, the way `libfoo.so.1` is usually a symlink to
+//!    `libfoo.so.1.2.3`. `libfoo.so.1` doesn't match `libfoo.so.10`
+//!
+//! Needed names matching none of the files are kept as unresolved edges.
+
+use crate::parser::ElfParser;
+use log::debug;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// A file of the set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyNode {
+    /// `DT_SONAME` of the file, or its file name if it has none
+    pub name: String,
+    /// Path the file was given with
+    pub file: String,
+    pub soname: Option<String>,
+}
+
+/// A `DT_NEEDED` entry of the file at `from`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyEdge {
+    pub from: usize,
+    pub needed: String,
+    /// Index of the node providing the library, None if no file of the set does
+    pub to: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DependencyGraph {
+    pub nodes: Vec<DependencyNode>,
+    /// Edges of every node, in node order then in the order of the `DT_NEEDED` entries
+    pub edges: Vec<DependencyEdge>,
+}
+
+/// Whether `name` is `needed` followed by one or more `.<number>` components
+fn extends_version(name: &str, needed: &str) -> bool {
+    name.strip_prefix(needed)
+        .and_then(|rest| rest.strip_prefix('.'))
+        .is_some_and(|rest| {
+            rest.split('.')
+                .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+        })
+}
+
+/// Quotes `s` as a DOT identifier
+fn dot_id(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+impl DependencyGraph {
+    /// Builds the graph of `files`, given as their path along with their parsed
+    /// contents. Files whose dynamic segment can't be read get no edges
+    pub fn build(files: &[(String, ElfParser)]) -> DependencyGraph {
+        let nodes = files
+            .iter()
+            .map(|(file, parser)| {
+                let soname = parser.soname().ok().flatten();
+                let file_name = Path::new(file)
+                    .file_name()
+                    .map_or(file.clone(), |n| n.to_string_lossy().into_owned());
+
+                DependencyNode {
+                    name: soname.clone().unwrap_or(file_name),
+                    file: file.clone(),
+                    soname,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut graph = DependencyGraph {
+            nodes,
+            edges: vec![],
+        };
+        for (from, (_, parser)) in files.iter().enumerate() {
+            for needed in parser.needed_libraries().unwrap_or_default() {
+                let to = graph.resolve(&needed);
+                graph.edges.push(DependencyEdge { from, needed, to });
+            }
+        }
+
+        debug!(
+            "dependency graph of {} files and {} edges, {} unresolved",
+            graph.nodes.len(),
+            graph.edges.len(),
+            graph.edges.iter().filter(|e| e.to.is_none()).count()
+        );
+
+        graph
+    }
+
+    /// Node providing the library `needed`, following the rules of the module
+    pub fn resolve(&self, needed: &str) -> Option<usize> {
+        let file_name = |node: &DependencyNode| {
+            Path::new(&node.file)
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+        };
+        let nodes = || self.nodes.iter().enumerate();
+
+        nodes()
+            .find(|(_, n)| n.soname.as_deref() == Some(needed))
+            .or_else(|| nodes().find(|(_, n)| file_name(n).as_deref() == Some(needed)))
+            .or_else(|| {
+                nodes().find(|(_, n)| {
+                    n.soname
+                        .as_deref()
+                        .is_some_and(|s| extends_version(s, needed))
+                        || file_name(n).is_some_and(|f| extends_version(&f, needed))
+                })
+            })
+            .map(|(idx, _)| idx)
+    }
+
+    /// Indices of the nodes the node at `idx` needs, without duplicates
+    fn dependencies(&self, idx: usize) -> BTreeSet<usize> {
+        self.edges
+            .iter()
+            .filter(|e| e.from == idx)
+            .filter_map(|e| e.to)
+            .collect()
+    }
+
+    /// Groups of nodes depending on each other, directly or not, each sorted by node
+    /// index. A node needing itself makes a group of its own
+    pub fn cycles(&self) -> Vec<Vec<usize>> {
+        // Tarjan's strongly connected components, iteratively
+        let count = self.nodes.len();
+        let mut index = vec![None; count];
+        let mut low = vec![0; count];
+        let mut on_stack = vec![false; count];
+        let mut stack = vec![];
+        let mut next = 0;
+        let mut cycles = vec![];
+
+        for root in 0..count {
+            if index[root].is_some() {
+                continue;
+            }
+
+            let mut work = vec![(
+                root,
+                self.dependencies(root).into_iter().collect::<Vec<_>>(),
+            )];
+            index[root] = Some(next);
+            low[root] = next;
+            next += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some((node, pending)) = work.last_mut() {
+                let node = *node;
+                if let Some(dep) = pending.pop() {
+                    match index[dep] {
+                        None => {
+                            index[dep] = Some(next);
+                            low[dep] = next;
+                            next += 1;
+                            stack.push(dep);
+                            on_stack[dep] = true;
+                            work.push((dep, self.dependencies(dep).into_iter().collect()));
+                        }
+                        Some(dep_index) if on_stack[dep] => low[node] = low[node].min(dep_index),
+                        Some(_) => {}
+                    }
+                    continue;
+                }
+
+                work.pop();
+                if let Some((parent, _)) = work.last() {
+                    low[*parent] = low[*parent].min(low[node]);
+                }
+                if Some(low[node]) == index[node] {
+                    let mut component = vec![];
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == node {
+                            break;
+                        }
+                    }
+
+                    if component.len() > 1 || self.dependencies(node).contains(&node) {
+                        component.sort_unstable();
+                        cycles.push(component);
+                    }
+                }
+            }
+        }
+
+        cycles.sort();
+        cycles
+    }
+
+    /// Order to load the nodes in, every node coming after the ones it needs, ties
+    /// being broken by node index. Fails with the cycles if there's any
+    pub fn load_order(&self) -> Result<Vec<usize>, Vec<Vec<usize>>> {
+        let cycles = self.cycles();
+        if !cycles.is_empty() {
+            return Err(cycles);
+        }
+
+        let dependencies = (0..self.nodes.len())
+            .map(|idx| self.dependencies(idx))
+            .collect::<Vec<_>>();
+        let mut pending = dependencies.iter().map(|d| d.len()).collect::<Vec<_>>();
+        let mut ready = (0..self.nodes.len())
+            .filter(|idx| pending[*idx] == 0)
+            .collect::<BTreeSet<_>>();
+        let mut order = vec![];
+
+        while let Some(idx) = ready.pop_first() {
+            order.push(idx);
+            for (dependent, deps) in dependencies.iter().enumerate() {
+                if deps.contains(&idx) {
+                    pending[dependent] -= 1;
+                    if pending[dependent] == 0 {
+                        ready.insert(dependent);
+                    }
+                }
+            }
+        }
+
+        Ok(order)
+    }
+
+    /// Renders the graph in the DOT language of Graphviz. Unresolved libraries are
+    /// drawn as dashed nodes, reached through dashed edges
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph dependencies {\n");
+
+        for node in &self.nodes {
+            dot.push_str(&format!("  {};\n", dot_id(&node.name)));
+        }
+
+        let unresolved = self
+            .edges
+            .iter()
+            .filter(|e| e.to.is_none())
+            .map(|e| e.needed.as_str())
+            .collect::<BTreeSet<_>>();
+        for needed in &unresolved {
+            dot.push_str(&format!(
+                "  {} [style=dashed, color=red];\n",
+                dot_id(needed)
+            ));
+        }
+
+        for edge in &self.edges {
+            let from = dot_id(&self.nodes[edge.from].name);
+            match edge.to {
+                Some(to) => dot.push_str(&format!(
+                    "  {} -> {};\n",
+                    from,
+                    dot_id(&self.nodes[to].name)
+                )),
+                None => dot.push_str(&format!(
+                    "  {} -> {} [style=dashed, color=red];\n",
+                    from,
+                    dot_id(&edge.needed)
+                )),
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
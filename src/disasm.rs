@@ -0,0 +1,467 @@
+use crate::elf::ehdr::{ElfHClass, EM_RISCV};
+use crate::parser::{ElfParser, ParseError};
+use std::fmt;
+
+const REGS: [&str; 32] = [
+    "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "s0", "s1", "a0", "a1", "a2", "a3", "a4",
+    "a5", "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11", "t3", "t4",
+    "t5", "t6",
+];
+
+const FREGS: [&str; 32] = [
+    "ft0", "ft1", "ft2", "ft3", "ft4", "ft5", "ft6", "ft7", "fs0", "fs1", "fa0", "fa1", "fa2",
+    "fa3", "fa4", "fa5", "fa6", "fa7", "fs2", "fs3", "fs4", "fs5", "fs6", "fs7", "fs8", "fs9",
+    "fs10", "fs11", "ft8", "ft9", "ft10", "ft11",
+];
+
+/// A decoded RISC-V instruction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub address: u64,
+    /// Encoding of the instruction, 2 bytes long for compressed instructions and 4 for
+    /// the rest
+    pub bytes: Vec<u8>,
+    /// Mnemonic of the instruction, using the pseudo-instruction when one applies and
+    /// the expanded form of compressed ones. Unknown encodings show up as `.insn`
+    pub mnemonic: String,
+    pub operands: String,
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let raw = match self.bytes[..] {
+            [a, b] => format!("{:04x}", u16::from_le_bytes([a, b])),
+            [a, b, c, d] => format!("{:08x}", u32::from_le_bytes([a, b, c, d])),
+            _ => String::new(),
+        };
+
+        write!(
+            f,
+            "{:>10x}: {:<8}  {:<8}{}",
+            self.address, raw, self.mnemonic, self.operands
+        )
+    }
+}
+
+fn reg(r: u32) -> &'static str {
+    REGS[r as usize & 0x1f]
+}
+
+fn freg(r: u32) -> &'static str {
+    FREGS[r as usize & 0x1f]
+}
+
+/// Register of the 3-bit fields of compressed instructions, which cover x8 to x15
+fn creg(r: u32) -> &'static str {
+    REGS[8 + (r as usize & 0x7)]
+}
+
+fn cfreg(r: u32) -> &'static str {
+    FREGS[8 + (r as usize & 0x7)]
+}
+
+fn bits(word: u32, hi: u32, lo: u32) -> u32 {
+    (word >> lo) & ((1 << (hi - lo + 1)) - 1)
+}
+
+/// Sign-extends the low `width` bits of `value`
+fn sext(value: u32, width: u32) -> i64 {
+    ((value as i64) << (64 - width)) >> (64 - width)
+}
+
+fn insn(mnemonic: &str, operands: String) -> (String, String) {
+    (mnemonic.to_string(), operands)
+}
+
+fn unknown(word: u32) -> (String, String) {
+    insn(".insn", format!("{:#x}", word))
+}
+
+/// Decodes a 32-bit instruction of the base integer set or of the M extension
+fn decode_32(word: u32, address: u64, xlen: u32) -> (String, String) {
+    let rd = bits(word, 11, 7);
+    let rs1 = bits(word, 19, 15);
+    let rs2 = bits(word, 24, 20);
+    let funct3 = bits(word, 14, 12);
+    let funct7 = bits(word, 31, 25);
+    let imm_i = sext(bits(word, 31, 20), 12);
+    let imm_s = sext((bits(word, 31, 25) << 5) | bits(word, 11, 7), 12);
+    let imm_b = sext(
+        (bits(word, 31, 31) << 12)
+            | (bits(word, 7, 7) << 11)
+            | (bits(word, 30, 25) << 5)
+            | (bits(word, 11, 8) << 1),
+        13,
+    );
+    let imm_j = sext(
+        (bits(word, 31, 31) << 20)
+            | (bits(word, 19, 12) << 12)
+            | (bits(word, 20, 20) << 11)
+            | (bits(word, 30, 21) << 1),
+        21,
+    );
+    let target = |imm: i64| address.wrapping_add(imm as u64);
+
+    match bits(word, 6, 0) {
+        0x37 => insn("lui", format!("{},{:#x}", reg(rd), word >> 12)),
+        0x17 => insn("auipc", format!("{},{:#x}", reg(rd), word >> 12)),
+        0x6f => match rd {
+            0 => insn("j", format!("{:x}", target(imm_j))),
+            1 => insn("jal", format!("{:x}", target(imm_j))),
+            _ => insn("jal", format!("{},{:x}", reg(rd), target(imm_j))),
+        },
+        0x67 if funct3 == 0 => match (rd, rs1, imm_i) {
+            (0, 1, 0) => insn("ret", String::new()),
+            (0, _, 0) => insn("jr", reg(rs1).to_string()),
+            (1, _, 0) => insn("jalr", reg(rs1).to_string()),
+            _ => insn("jalr", format!("{},{}({})", reg(rd), imm_i, reg(rs1))),
+        },
+        0x63 => {
+            let mnemonic = match funct3 {
+                0 => "beq",
+                1 => "bne",
+                4 => "blt",
+                5 => "bge",
+                6 => "bltu",
+                7 => "bgeu",
+                _ => return unknown(word),
+            };
+            match (funct3, rs2) {
+                (0, 0) => insn("beqz", format!("{},{:x}", reg(rs1), target(imm_b))),
+                (1, 0) => insn("bnez", format!("{},{:x}", reg(rs1), target(imm_b))),
+                _ => insn(
+                    mnemonic,
+                    format!("{},{},{:x}", reg(rs1), reg(rs2), target(imm_b)),
+                ),
+            }
+        }
+        0x03 => {
+            let mnemonic = match (funct3, xlen) {
+                (0, _) => "lb",
+                (1, _) => "lh",
+                (2, _) => "lw",
+                (3, 64) => "ld",
+                (4, _) => "lbu",
+                (5, _) => "lhu",
+                (6, 64) => "lwu",
+                _ => return unknown(word),
+            };
+            insn(mnemonic, format!("{},{}({})", reg(rd), imm_i, reg(rs1)))
+        }
+        0x07 if funct3 == 2 || funct3 == 3 => insn(
+            if funct3 == 2 { "flw" } else { "fld" },
+            format!("{},{}({})", freg(rd), imm_i, reg(rs1)),
+        ),
+        0x23 => {
+            let mnemonic = match (funct3, xlen) {
+                (0, _) => "sb",
+                (1, _) => "sh",
+                (2, _) => "sw",
+                (3, 64) => "sd",
+                _ => return unknown(word),
+            };
+            insn(mnemonic, format!("{},{}({})", reg(rs2), imm_s, reg(rs1)))
+        }
+        0x27 if funct3 == 2 || funct3 == 3 => insn(
+            if funct3 == 2 { "fsw" } else { "fsd" },
+            format!("{},{}({})", freg(rs2), imm_s, reg(rs1)),
+        ),
+        0x13 => {
+            let shamt = bits(word, 25, 20) & (xlen - 1);
+            match (funct3, bits(word, 31, 26)) {
+                (0, _) if rd == 0 && rs1 == 0 && imm_i == 0 => insn("nop", String::new()),
+                (0, _) if rs1 == 0 => insn("li", format!("{},{}", reg(rd), imm_i)),
+                (0, _) if imm_i == 0 => insn("mv", format!("{},{}", reg(rd), reg(rs1))),
+                (0, _) => insn("addi", format!("{},{},{}", reg(rd), reg(rs1), imm_i)),
+                (2, _) => insn("slti", format!("{},{},{}", reg(rd), reg(rs1), imm_i)),
+                (3, _) if imm_i == 1 => insn("seqz", format!("{},{}", reg(rd), reg(rs1))),
+                (3, _) => insn("sltiu", format!("{},{},{}", reg(rd), reg(rs1), imm_i)),
+                (4, _) if imm_i == -1 => insn("not", format!("{},{}", reg(rd), reg(rs1))),
+                (4, _) => insn("xori", format!("{},{},{}", reg(rd), reg(rs1), imm_i)),
+                (6, _) => insn("ori", format!("{},{},{}", reg(rd), reg(rs1), imm_i)),
+                (7, _) => insn("andi", format!("{},{},{}", reg(rd), reg(rs1), imm_i)),
+                (1, 0) => insn("slli", format!("{},{},{:#x}", reg(rd), reg(rs1), shamt)),
+                (5, 0) => insn("srli", format!("{},{},{:#x}", reg(rd), reg(rs1), shamt)),
+                (5, 0x10) => insn("srai", format!("{},{},{:#x}", reg(rd), reg(rs1), shamt)),
+                _ => unknown(word),
+            }
+        }
+        0x1b if xlen == 64 => {
+            let shamt = bits(word, 24, 20);
+            match (funct3, funct7) {
+                (0, _) if imm_i == 0 => insn("sext.w", format!("{},{}", reg(rd), reg(rs1))),
+                (0, _) => insn("addiw", format!("{},{},{}", reg(rd), reg(rs1), imm_i)),
+                (1, 0) => insn("slliw", format!("{},{},{:#x}", reg(rd), reg(rs1), shamt)),
+                (5, 0) => insn("srliw", format!("{},{},{:#x}", reg(rd), reg(rs1), shamt)),
+                (5, 0x20) => insn("sraiw", format!("{},{},{:#x}", reg(rd), reg(rs1), shamt)),
+                _ => unknown(word),
+            }
+        }
+        0x33 => {
+            let mnemonic = match (funct7, funct3) {
+                (0, 0) => "add",
+                (0x20, 0) if rs1 == 0 => {
+                    return insn("neg", format!("{},{}", reg(rd), reg(rs2)));
+                }
+                (0x20, 0) => "sub",
+                (0, 1) => "sll",
+                (0, 2) => "slt",
+                (0, 3) if rs1 == 0 => {
+                    return insn("snez", format!("{},{}", reg(rd), reg(rs2)));
+                }
+                (0, 3) => "sltu",
+                (0, 4) => "xor",
+                (0, 5) => "srl",
+                (0x20, 5) => "sra",
+                (0, 6) => "or",
+                (0, 7) => "and",
+                (1, 0) => "mul",
+                (1, 1) => "mulh",
+                (1, 2) => "mulhsu",
+                (1, 3) => "mulhu",
+                (1, 4) => "div",
+                (1, 5) => "divu",
+                (1, 6) => "rem",
+                (1, 7) => "remu",
+                _ => return unknown(word),
+            };
+            insn(mnemonic, format!("{},{},{}", reg(rd), reg(rs1), reg(rs2)))
+        }
+        0x3b if xlen == 64 => {
+            let mnemonic = match (funct7, funct3) {
+                (0, 0) => "addw",
+                (0x20, 0) if rs1 == 0 => {
+                    return insn("negw", format!("{},{}", reg(rd), reg(rs2)));
+                }
+                (0x20, 0) => "subw",
+                (0, 1) => "sllw",
+                (0, 5) => "srlw",
+                (0x20, 5) => "sraw",
+                (1, 0) => "mulw",
+                (1, 4) => "divw",
+                (1, 5) => "divuw",
+                (1, 6) => "remw",
+                (1, 7) => "remuw",
+                _ => return unknown(word),
+            };
+            insn(mnemonic, format!("{},{},{}", reg(rd), reg(rs1), reg(rs2)))
+        }
+        0x0f => match funct3 {
+            0 => insn("fence", String::new()),
+            1 => insn("fence.i", String::new()),
+            _ => unknown(word),
+        },
+        0x73 => {
+            let csr = bits(word, 31, 20);
+            match (funct3, word) {
+                (0, 0x00000073) => insn("ecall", String::new()),
+                (0, 0x00100073) => insn("ebreak", String::new()),
+                (1, _) => insn("csrrw", format!("{},{:#x},{}", reg(rd), csr, reg(rs1))),
+                (2, _) if rs1 == 0 => insn("csrr", format!("{},{:#x}", reg(rd), csr)),
+                (2, _) => insn("csrrs", format!("{},{:#x},{}", reg(rd), csr, reg(rs1))),
+                (3, _) => insn("csrrc", format!("{},{:#x},{}", reg(rd), csr, reg(rs1))),
+                (5, _) => insn("csrrwi", format!("{},{:#x},{}", reg(rd), csr, rs1)),
+                (6, _) => insn("csrrsi", format!("{},{:#x},{}", reg(rd), csr, rs1)),
+                (7, _) => insn("csrrci", format!("{},{:#x},{}", reg(rd), csr, rs1)),
+                _ => unknown(word),
+            }
+        }
+        _ => unknown(word),
+    }
+}
+
+/// Decodes a compressed instruction of the C extension into its expanded form
+fn decode_16(half: u32, address: u64, xlen: u32) -> (String, String) {
+    let rd = bits(half, 11, 7);
+    let rs2 = bits(half, 6, 2);
+    let rd_c = bits(half, 4, 2);
+    let rs1_c = bits(half, 9, 7);
+    let funct3 = bits(half, 15, 13);
+    let imm6 = sext((bits(half, 12, 12) << 5) | bits(half, 6, 2), 6);
+    let uimm6 = (bits(half, 12, 12) << 5) | bits(half, 6, 2);
+    // offsets of c.lw/c.sw and of c.ld/c.sd
+    let off_w = (bits(half, 5, 5) << 6) | (bits(half, 12, 10) << 3) | (bits(half, 6, 6) << 2);
+    let off_d = (bits(half, 6, 5) << 6) | (bits(half, 12, 10) << 3);
+    let target = |imm: i64| address.wrapping_add(imm as u64);
+
+    match (bits(half, 1, 0), funct3) {
+        (0, 0) if half == 0 => insn("unimp", String::new()),
+        (0, 0) => {
+            let imm = (bits(half, 10, 7) << 6)
+                | (bits(half, 12, 11) << 4)
+                | (bits(half, 5, 5) << 3)
+                | (bits(half, 6, 6) << 2);
+            insn("addi", format!("{},sp,{}", creg(rd_c), imm))
+        }
+        (0, 1) => insn("fld", format!("{},{}({})", cfreg(rd_c), off_d, creg(rs1_c))),
+        (0, 2) => insn("lw", format!("{},{}({})", creg(rd_c), off_w, creg(rs1_c))),
+        (0, 3) if xlen == 64 => insn("ld", format!("{},{}({})", creg(rd_c), off_d, creg(rs1_c))),
+        (0, 3) => insn("flw", format!("{},{}({})", cfreg(rd_c), off_w, creg(rs1_c))),
+        (0, 5) => insn("fsd", format!("{},{}({})", cfreg(rd_c), off_d, creg(rs1_c))),
+        (0, 6) => insn("sw", format!("{},{}({})", creg(rd_c), off_w, creg(rs1_c))),
+        (0, 7) if xlen == 64 => insn("sd", format!("{},{}({})", creg(rd_c), off_d, creg(rs1_c))),
+        (0, 7) => insn("fsw", format!("{},{}({})", cfreg(rd_c), off_w, creg(rs1_c))),
+        (1, 0) if rd == 0 => insn("nop", String::new()),
+        (1, 0) => insn("addi", format!("{},{},{}", reg(rd), reg(rd), imm6)),
+        // c.addiw, c.lwsp and c.ldsp are reserved with rd = zero
+        (1, 1) | (2, 2) | (2, 3) if rd == 0 && (xlen == 64 || funct3 == 2) => unknown(half),
+        (1, 1) if xlen == 64 && imm6 == 0 => insn("sext.w", format!("{},{}", reg(rd), reg(rd))),
+        (1, 1) if xlen == 64 => insn("addiw", format!("{},{},{}", reg(rd), reg(rd), imm6)),
+        (1, 2) => insn("li", format!("{},{}", reg(rd), imm6)),
+        (1, 3) if rd == 2 => {
+            let imm = sext(
+                (bits(half, 12, 12) << 9)
+                    | (bits(half, 4, 3) << 7)
+                    | (bits(half, 5, 5) << 6)
+                    | (bits(half, 2, 2) << 5)
+                    | (bits(half, 6, 6) << 4),
+                10,
+            );
+            insn("addi", format!("sp,sp,{}", imm))
+        }
+        (1, 3) => insn("lui", format!("{},{:#x}", reg(rd), imm6 as u64 & 0xfffff)),
+        (1, 4) => {
+            let rs1 = creg(rs1_c);
+            match (bits(half, 11, 10), bits(half, 12, 12), bits(half, 6, 5)) {
+                (0, _, _) => insn("srli", format!("{},{},{:#x}", rs1, rs1, uimm6)),
+                (1, _, _) => insn("srai", format!("{},{},{:#x}", rs1, rs1, uimm6)),
+                (2, _, _) => insn("andi", format!("{},{},{}", rs1, rs1, imm6)),
+                (3, 0, op) => {
+                    let mnemonic = ["sub", "xor", "or", "and"][op as usize];
+                    insn(mnemonic, format!("{},{},{}", rs1, rs1, creg(rd_c)))
+                }
+                (3, 1, 0) if xlen == 64 => insn("subw", format!("{},{},{}", rs1, rs1, creg(rd_c))),
+                (3, 1, 1) if xlen == 64 => insn("addw", format!("{},{},{}", rs1, rs1, creg(rd_c))),
+                _ => unknown(half),
+            }
+        }
+        (1, 1) | (1, 5) => {
+            let imm = sext(
+                (bits(half, 12, 12) << 11)
+                    | (bits(half, 8, 8) << 10)
+                    | (bits(half, 10, 9) << 8)
+                    | (bits(half, 6, 6) << 7)
+                    | (bits(half, 7, 7) << 6)
+                    | (bits(half, 2, 2) << 5)
+                    | (bits(half, 11, 11) << 4)
+                    | (bits(half, 5, 3) << 1),
+                12,
+            );
+            // c.jal only exists on RV32, where it takes the place of c.addiw
+            let mnemonic = if funct3 == 1 { "jal" } else { "j" };
+            insn(mnemonic, format!("{:x}", target(imm)))
+        }
+        (1, 6) | (1, 7) => {
+            let imm = sext(
+                (bits(half, 12, 12) << 8)
+                    | (bits(half, 6, 5) << 6)
+                    | (bits(half, 2, 2) << 5)
+                    | (bits(half, 11, 10) << 3)
+                    | (bits(half, 4, 3) << 1),
+                9,
+            );
+            let mnemonic = if funct3 == 6 { "beqz" } else { "bnez" };
+            insn(mnemonic, format!("{},{:x}", creg(rs1_c), target(imm)))
+        }
+        (2, 0) => insn("slli", format!("{},{},{:#x}", reg(rd), reg(rd), uimm6)),
+        (2, 1) => {
+            let off = (bits(half, 4, 2) << 6) | (bits(half, 12, 12) << 5) | (bits(half, 6, 5) << 3);
+            insn("fld", format!("{},{}(sp)", freg(rd), off))
+        }
+        (2, 2) => {
+            let off = (bits(half, 3, 2) << 6) | (bits(half, 12, 12) << 5) | (bits(half, 6, 4) << 2);
+            insn("lw", format!("{},{}(sp)", reg(rd), off))
+        }
+        (2, 3) if xlen == 64 => {
+            let off = (bits(half, 4, 2) << 6) | (bits(half, 12, 12) << 5) | (bits(half, 6, 5) << 3);
+            insn("ld", format!("{},{}(sp)", reg(rd), off))
+        }
+        (2, 4) => match (bits(half, 12, 12), rd, rs2) {
+            (0, 1, 0) => insn("ret", String::new()),
+            (0, _, 0) => insn("jr", reg(rd).to_string()),
+            (0, _, _) => insn("mv", format!("{},{}", reg(rd), reg(rs2))),
+            (1, 0, 0) => insn("ebreak", String::new()),
+            (1, _, 0) => insn("jalr", reg(rd).to_string()),
+            (1, _, _) => insn("add", format!("{},{},{}", reg(rd), reg(rd), reg(rs2))),
+            _ => unknown(half),
+        },
+        (2, 5) => {
+            let off = (bits(half, 9, 7) << 6) | (bits(half, 12, 10) << 3);
+            insn("fsd", format!("{},{}(sp)", freg(rs2), off))
+        }
+        (2, 6) => {
+            let off = (bits(half, 8, 7) << 6) | (bits(half, 12, 9) << 2);
+            insn("sw", format!("{},{}(sp)", reg(rs2), off))
+        }
+        (2, 7) if xlen == 64 => {
+            let off = (bits(half, 9, 7) << 6) | (bits(half, 12, 10) << 3);
+            insn("sd", format!("{},{}(sp)", reg(rs2), off))
+        }
+        _ => unknown(half),
+    }
+}
+
+impl ElfParser<'_> {
+    /// Decodes `n_insns` RISC-V instructions starting at the virtual address `addr`,
+    /// reading them from the file image of the loadable segments. Decoding stops
+    /// early when the code runs past the end of a segment
+    pub fn read_code(&self, addr: u64, n_insns: usize) -> Result<Vec<Instruction>, ParseError> {
+        if self.headers.machine != EM_RISCV {
+            return Err(ParseError::UnsupportedMachine(self.headers.machine));
+        }
+
+        let xlen = if self.ctx.class == ElfHClass::ElfClass64 {
+            64
+        } else {
+            32
+        };
+        let mut instructions = vec![];
+        let mut address = addr;
+
+        while instructions.len() < n_insns {
+            let Some(off) = self
+                .vaddr_to_offset(address)
+                .ok()
+                .and_then(|off| usize::try_from(off).ok())
+            else {
+                break;
+            };
+            let Ok(low) = self.ctx.reader(self.data()).at(off).u16() else {
+                break;
+            };
+            let len: usize = if low & 0x3 != 0x3 { 2 } else { 4 };
+
+            // the last byte must be loaded by the same segment as the first one, right
+            // after it in the file
+            let last = address.checked_add(len as u64 - 1);
+            let last_off = last.and_then(|last| self.vaddr_to_offset(last).ok());
+            if last_off != Some(off as u64 + len as u64 - 1) {
+                break;
+            }
+            let Ok(bytes) = self.ctx.reader(self.data()).at(off).bytes(len) else {
+                break;
+            };
+
+            let (mnemonic, operands) = match len {
+                2 => decode_16(low as u32, address, xlen),
+                _ => match self.ctx.reader(bytes).u32() {
+                    Ok(word) => decode_32(word, address, xlen),
+                    Err(_) => break,
+                },
+            };
+
+            instructions.push(Instruction {
+                address,
+                bytes: bytes.to_vec(),
+                mnemonic,
+                operands,
+            });
+            match address.checked_add(len as u64) {
+                Some(next) => address = next,
+                None => break,
+            }
+        }
+
+        Ok(instructions)
+    }
+}
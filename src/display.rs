@@ -0,0 +1,53 @@
+use std::fmt;
+
+/// Pairs a structure with the name resolved for it from a string table, so that it
+/// can be rendered as a single table row
+pub struct Named<'a, T> {
+    pub name: &'a str,
+    pub inner: &'a T,
+}
+
+impl<'a, T> Named<'a, T> {
+    pub fn new(name: &'a str, inner: &'a T) -> Self {
+        Named { name, inner }
+    }
+}
+
+/// A structure that can be rendered as a single row of a `DisplayTable`
+pub trait TableRow: fmt::Display {
+    /// Column titles, aligned with the row output
+    const HEADER: &'static str;
+}
+
+impl<T: TableRow> TableRow for &T {
+    const HEADER: &'static str = T::HEADER;
+}
+
+/// Renders a whole table of rows under a title and the column headers of `T`,
+/// prefixing each row with its index in the table
+pub struct DisplayTable<T: TableRow> {
+    pub title: String,
+    pub rows: Vec<T>,
+}
+
+impl<T: TableRow> DisplayTable<T> {
+    pub fn new(title: impl Into<String>, rows: Vec<T>) -> Self {
+        DisplayTable {
+            title: title.into(),
+            rows,
+        }
+    }
+}
+
+impl<T: TableRow> fmt::Display for DisplayTable<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}:", self.title)?;
+        writeln!(f, "  [Nr] {}", T::HEADER)?;
+
+        for (idx, row) in self.rows.iter().enumerate() {
+            writeln!(f, "  [{:>2}] {}", idx, row)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,254 @@
+use crate::bytes::str_from_u8;
+use crate::elf::phdr::{
+    ELF64Dyn, DT_GNU_HASH, DT_HASH, DT_JMPREL, DT_PLTREL, DT_PLTRELSZ, DT_REL, DT_RELA, DT_RELAENT,
+    DT_RELASZ, DT_RELENT, DT_RELSZ, DT_STRSZ, DT_STRTAB, DT_SYMENT, DT_SYMTAB, DT_VERDEF,
+    DT_VERDEFNUM, DT_VERNEED, DT_VERNEEDNUM, DT_VERSYM,
+};
+use crate::elf::reloc::Elf64Rela;
+use crate::elf::sym::Elf64Sym;
+use crate::elf::version::{Verdef, Verneed, VERSYM_HIDDEN, VER_FLG_BASE, VER_NDX_GLOBAL};
+use crate::layout;
+use crate::loader;
+use crate::parser::{ElfParser, ParseError};
+use log::debug;
+use std::collections::BTreeMap;
+
+/// Version a dynamic symbol is bound to, as resolved through `.gnu.version`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolVersion {
+    pub name: String,
+    /// File the version is required from, or None if the file defines it
+    pub library: Option<String>,
+    /// The symbol can only be reached by naming this version explicitly, as in
+    /// `name@VERSION` rather than the default `name@@VERSION`
+    pub hidden: bool,
+}
+
+impl ElfParser<'_> {
+    /// Translates a virtual address into the offset of the file byte loaded at it
+    pub fn vaddr_to_offset(&self, vaddr: u64) -> Result<u64, ParseError> {
+        loader::vaddr_to_offset(&self.program_headers, vaddr)
+    }
+
+    /// Returns the file offset pointed to by a dynamic entry holding an address
+    pub fn resolve_dyn_ptr(&self, entry: &ELF64Dyn) -> Result<u64, ParseError> {
+        self.vaddr_to_offset(entry.value())
+    }
+
+    fn required_entry(&self, d_tag: i64) -> Result<&ELF64Dyn, ParseError> {
+        self.dynamic_entry(d_tag)
+            .ok_or(ParseError::MissingDynamicEntry(d_tag))
+    }
+
+    /// Reads `size` bytes of the file starting at the address held by the `d_tag` entry
+    pub(crate) fn dynamic_table(&self, d_tag: i64, size: u64) -> Result<&[u8], ParseError> {
+        let off = self.resolve_dyn_ptr(self.required_entry(d_tag)?)?;
+        let end = off.checked_add(size).ok_or(ParseError::InvalidLength)?;
+
+        self.data()
+            .get(off as usize..end as usize)
+            .ok_or(ParseError::InvalidLength)
+    }
+
+    /// Reads the file from the address held by the `d_tag` entry to its end, for tables
+    /// whose size isn't known up front
+    fn dynamic_table_from(&self, d_tag: i64) -> Result<&[u8], ParseError> {
+        let off = self.resolve_dyn_ptr(self.required_entry(d_tag)?)? as usize;
+
+        self.data().get(off..).ok_or(ParseError::InvalidLength)
+    }
+
+    /// Returns the string of the dynamic string table at `off`
+    pub fn dynamic_string(&self, off: u32) -> Result<String, ParseError> {
+        str_from_u8(
+            self.dynamic_string_table()?
+                .get(off as usize..)
+                .ok_or(ParseError::InvalidLength)?,
+        )
+    }
+
+    /// Returns the string table used by the dynamic linker, located through `DT_STRTAB`
+    /// and `DT_STRSZ` without relying on section headers
+    pub fn dynamic_string_table(&self) -> Result<&[u8], ParseError> {
+        let size = self.required_entry(DT_STRSZ)?.value();
+
+        self.dynamic_table(DT_STRTAB, size)
+    }
+
+    /// Number of entries of the dynamic symbol table, which isn't held by any dynamic
+    /// entry but can be recovered from the `DT_HASH` or `DT_GNU_HASH` tables
+    fn dynamic_symbol_count(&self) -> Result<u64, ParseError> {
+        let ctx = &self.ctx;
+        // offsets are read from the file, so adding to them may overflow
+        let at = |off: usize, delta: usize| off.checked_add(delta).ok_or(ParseError::InvalidLength);
+
+        if let Some(hash) = self.dynamic_entry(DT_HASH) {
+            // the nchain word matches the number of symbols
+            let off = self.resolve_dyn_ptr(hash)? as usize;
+            return Ok(ctx.read_u32(self.data(), at(off, 4)?)? as u64);
+        }
+
+        let gnu_hash = self.required_entry(DT_GNU_HASH)?;
+        let off = self.resolve_dyn_ptr(gnu_hash)? as usize;
+        let data = self.data();
+
+        let nbuckets = ctx.read_u32(data, off)? as usize;
+        let symoffset = ctx.read_u32(data, at(off, 4)?)?;
+        let bloom_size = ctx.read_u32(data, at(off, 8)?)? as usize;
+        let buckets = at(off, 16 + bloom_size * ctx.word_size())?;
+        let chains = at(buckets, nbuckets * 4)?;
+
+        let mut last = 0;
+        for bucket in 0..nbuckets {
+            last = last.max(ctx.read_u32(data, at(buckets, bucket * 4)?)?);
+        }
+        if last < symoffset {
+            // no symbol is hashed, so the table only bounds the count from below. The
+            // linker usually places the string table right after the symbol table
+            let symtab = self.required_entry(DT_SYMTAB)?.value();
+            let strtab = self.required_entry(DT_STRTAB)?.value();
+            let syment = layout::sym::for_class(ctx.class).size as u64;
+            let gap = strtab.saturating_sub(symtab) / syment;
+
+            return Ok(gap.max(symoffset as u64));
+        }
+
+        // the chain of the last bucket ends at the last symbol, flagged by its low bit
+        while ctx.read_u32(data, at(chains, (last - symoffset) as usize * 4)?)? & 1 == 0 {
+            last += 1;
+        }
+
+        Ok(last as u64 + 1)
+    }
+
+    /// Returns the dynamic symbol table, located through `DT_SYMTAB` and `DT_SYMENT`
+    /// and sized from the hash tables, without relying on section headers
+    pub fn dynamic_symbol_table(&self) -> Result<Vec<Elf64Sym>, ParseError> {
+        let syment = self.required_entry(DT_SYMENT)?.value();
+        if syment != layout::sym::for_class(self.ctx.class).size as u64 {
+            return Err(ParseError::InvalidLength);
+        }
+
+        let count = self.dynamic_symbol_count()?;
+        let size = count.checked_mul(syment).ok_or(ParseError::InvalidLength)?;
+        let table = self.dynamic_table(DT_SYMTAB, size)?;
+
+        debug!("read {} dynamic symbols from the dynamic segment", count);
+
+        Elf64Sym::parse_table(table, &self.ctx)
+    }
+
+    /// Returns the relocations applied by the dynamic linker, the ones of `DT_RELA` or
+    /// `DT_REL` followed by the PLT ones of `DT_JMPREL`, without relying on section
+    /// headers
+    pub fn dynamic_relocations(&self) -> Result<Vec<Elf64Rela>, ParseError> {
+        let layout = layout::rela::for_class(self.ctx.class);
+        let mut relocations = vec![];
+        let mut range = 0..0;
+
+        for (d_tag, d_size, d_ent, rela) in [
+            (DT_RELA, DT_RELASZ, DT_RELAENT, true),
+            (DT_REL, DT_RELSZ, DT_RELENT, false),
+        ] {
+            if let Some(entry) = self.dynamic_entry(d_tag) {
+                let size = self.required_entry(d_size)?.value();
+                let ent = self.required_entry(d_ent)?.value();
+                let expected = if rela { layout.size } else { layout.rel_size() };
+                if ent != expected as u64 {
+                    return Err(ParseError::InvalidLength);
+                }
+
+                let table = self.dynamic_table(d_tag, size)?;
+                relocations.extend(Elf64Rela::parse_table(table, &self.ctx, rela)?);
+                let end = entry
+                    .value()
+                    .checked_add(size)
+                    .ok_or(ParseError::InvalidLength)?;
+                range = entry.value()..end;
+            }
+        }
+
+        if let Some(jmprel) = self.dynamic_entry(DT_JMPREL) {
+            let size = self.required_entry(DT_PLTRELSZ)?.value();
+            let rela = self.required_entry(DT_PLTREL)?.value() == DT_RELA as u64;
+
+            // linkers may account the PLT relocations within DT_RELASZ too
+            if !range.contains(&jmprel.value()) {
+                let table = self.dynamic_table(DT_JMPREL, size)?;
+                relocations.extend(Elf64Rela::parse_table(table, &self.ctx, rela)?);
+            }
+        }
+
+        Ok(relocations)
+    }
+
+    /// Returns the versions defined by the file, located through `DT_VERDEF` and
+    /// `DT_VERDEFNUM`, or an empty list if it defines none
+    pub fn version_definitions(&self) -> Result<Vec<Verdef>, ParseError> {
+        if self.dynamic_entry(DT_VERDEF).is_none() {
+            return Ok(vec![]);
+        }
+
+        let count = self.required_entry(DT_VERDEFNUM)?.value();
+        Verdef::parse(self.dynamic_table_from(DT_VERDEF)?, &self.ctx, count)
+    }
+
+    /// Returns the versions required from other files, located through `DT_VERNEED`
+    /// and `DT_VERNEEDNUM`, or an empty list if it requires none
+    pub fn version_dependencies(&self) -> Result<Vec<Verneed>, ParseError> {
+        if self.dynamic_entry(DT_VERNEED).is_none() {
+            return Ok(vec![]);
+        }
+
+        let count = self.required_entry(DT_VERNEEDNUM)?.value();
+        Verneed::parse(self.dynamic_table_from(DT_VERNEED)?, &self.ctx, count)
+    }
+
+    /// Returns the version of every dynamic symbol, indexed like
+    /// `dynamic_symbol_table`. Symbols that are local or not bound to any version map
+    /// to None, as do all of them when the file has no `DT_VERSYM` table
+    pub fn symbol_versions(&self) -> Result<Vec<Option<SymbolVersion>>, ParseError> {
+        let count = self.dynamic_symbol_count()?;
+        if self.dynamic_entry(DT_VERSYM).is_none() {
+            return Ok(vec![None; count as usize]);
+        }
+
+        let mut versions = BTreeMap::new();
+        for def in self.version_definitions()? {
+            if def.flags & VER_FLG_BASE != 0 {
+                continue;
+            }
+            if let Some(name) = def.names.first() {
+                versions.insert(def.ndx, (self.dynamic_string(*name)?, None));
+            }
+        }
+        for need in self.version_dependencies()? {
+            let library = self.dynamic_string(need.file)?;
+            for aux in need.versions {
+                versions.insert(
+                    aux.other,
+                    (self.dynamic_string(aux.name)?, Some(library.clone())),
+                );
+            }
+        }
+
+        let size = count.checked_mul(2).ok_or(ParseError::InvalidLength)?;
+        let table = self.dynamic_table(DT_VERSYM, size)?;
+        table
+            .chunks_exact(2)
+            .map(|entry| {
+                let versym = self.ctx.read_u16(entry, 0)?;
+                let ndx = versym & !VERSYM_HIDDEN;
+
+                Ok(match versions.get(&ndx) {
+                    Some((name, library)) if ndx > VER_NDX_GLOBAL => Some(SymbolVersion {
+                        name: name.clone(),
+                        library: library.clone(),
+                        hidden: versym & VERSYM_HIDDEN != 0,
+                    }),
+                    _ => None,
+                })
+            })
+            .collect()
+    }
+}
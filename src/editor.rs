@@ -0,0 +1,540 @@
+use crate::bytes::{Address, ParseContext};
+use crate::elf::ehdr::Elf64Hdr;
+use crate::elf::phdr::{Elf64PHdr, PType, PTypeData, PF_EXEC};
+use crate::elf::shdr::{Elf64SHdr, SHF_ALLOC, SHN_LORESERVE, SHT_PROGBITS, SHT_STRTAB, SHT_SYMTAB};
+use crate::elf::sym::{Elf64Sym, STB_LOCAL};
+use crate::layout;
+use crate::links::resolve_link;
+use crate::parser::{ElfParser, ParseError};
+use crate::signature::signed_message;
+use crate::strtab::StringTableBuilder;
+use log::debug;
+use std::collections::HashMap;
+
+#[derive(Debug)]
+pub enum EditError {
+    Parse(ParseError),
+    /// No section is named as requested
+    SectionNotFound(String),
+    /// No symbol is named as requested
+    SymbolNotFound(String),
+    /// The section at the given index is loaded into memory, so moving its contents
+    /// would break the segments mapping it
+    AllocatedSection(usize),
+    /// The edit would need more section headers than the ELF header can describe
+    TooManySections,
+    /// The `len` bytes at `addr` aren't all mapped by a single loadable segment
+    UnmappedRange {
+        addr: u64,
+        len: usize,
+    },
+    /// The `len` bytes at `addr` reach past the file image of their segment, into
+    /// the zero-filled tail the file holds no bytes for
+    BeyondFileImage {
+        addr: u64,
+        len: usize,
+    },
+    /// The segment mapping `addr` isn't executable
+    NotExecutable(u64),
+}
+
+impl From<ParseError> for EditError {
+    fn from(e: ParseError) -> Self {
+        EditError::Parse(e)
+    }
+}
+
+fn align_up(value: usize, align: usize) -> usize {
+    value.div_ceil(align.max(1)) * align.max(1)
+}
+
+/// Copy of a parsed file whose non-allocated sections can be replaced or added.
+/// New contents and the rewritten section header table are appended at the end of the
+/// file, so nothing mapped by the program headers ever moves. Loaded bytes can only be
+/// patched in place, through their virtual address
+#[derive(Debug)]
+pub struct ElfEditor {
+    ctx: ParseContext,
+    headers: Elf64Hdr,
+    /// Program headers of the file, without the contents of the segments
+    program_headers: Vec<Elf64PHdr>,
+    section_headers: Vec<Elf64SHdr>,
+    /// Contents of the section header string table, grown as sections are added
+    shstrtab: StringTableBuilder,
+    /// New names of the renamed sections, by index, applied at once by `save`
+    renames: HashMap<usize, String>,
+    data: Vec<u8>,
+}
+
+impl ElfEditor {
+    pub fn new(parser: &ElfParser) -> Self {
+        ElfEditor {
+            ctx: parser.ctx,
+            headers: parser.headers,
+            program_headers: parser
+                .program_headers
+                .iter()
+                .map(|ph| Elf64PHdr {
+                    section: PTypeData::Skipped,
+                    ..ph.clone()
+                })
+                .collect(),
+            section_headers: parser.section_headers.clone(),
+            shstrtab: parser
+                .header_string_table_idx
+                .map(|idx| StringTableBuilder::from_table(&parser.string_tables[idx].table))
+                .unwrap_or_default(),
+            renames: HashMap::new(),
+            data: parser.data().to_vec(),
+        }
+    }
+
+    pub fn section_headers(&self) -> &[Elf64SHdr] {
+        &self.section_headers
+    }
+
+    pub fn section_header_mut(&mut self, idx: usize) -> Option<&mut Elf64SHdr> {
+        self.section_headers.get_mut(idx)
+    }
+
+    /// Name of the section at `idx`, accounting for the pending renames
+    pub fn section_name(&self, idx: usize) -> Option<&str> {
+        match self.renames.get(&idx) {
+            Some(name) => Some(name),
+            None => self.shstrtab.lookup(self.section_headers.get(idx)?.name),
+        }
+    }
+
+    /// Returns the index of the section named `name`
+    pub fn section_index(&self, name: &str) -> Option<usize> {
+        (0..self.section_headers.len()).find(|idx| self.section_name(*idx) == Some(name))
+    }
+
+    /// Renames every section named `old` to `new`. The section header string table is
+    /// rebuilt once by `save`, however many sections were renamed
+    pub fn rename_section(&mut self, old: &str, new: &str) -> Result<(), EditError> {
+        let indices = (0..self.section_headers.len())
+            .filter(|idx| self.section_name(*idx) == Some(old))
+            .collect::<Vec<_>>();
+
+        if indices.is_empty() {
+            return Err(EditError::SectionNotFound(old.to_string()));
+        }
+
+        for idx in indices {
+            self.renames.insert(idx, new.to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Builds a new section header string table out of the current names, updating
+    /// the name of every section. The table is written over the old one when it fits,
+    /// and appended to the file otherwise
+    fn rebuild_shstrtab(&mut self) -> Result<(), EditError> {
+        let shstrndx = ElfParser::header_string_table_index(&self.headers, &self.section_headers)
+            .ok_or(ParseError::NameUnavailable)?;
+
+        let names = (0..self.section_headers.len())
+            .map(|idx| {
+                self.section_name(idx)
+                    .map(str::to_string)
+                    .ok_or(ParseError::NameUnavailable)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // longest names first, so that the shorter ones can point into them
+        let mut order = (0..names.len()).collect::<Vec<_>>();
+        order.sort_by_key(|idx| std::cmp::Reverse(names[*idx].len()));
+
+        let mut shstrtab = StringTableBuilder::new().tail_merge(true);
+        for idx in order {
+            self.section_headers[idx].name = shstrtab.insert(&names[idx]);
+        }
+
+        let sh = self.section_headers[shstrndx];
+        let (offset, size) = (sh.offset as usize, sh.size as usize);
+        match self.data.get_mut(offset..offset.saturating_add(size)) {
+            Some(old) if shstrtab.len() <= size => {
+                old.fill(0);
+                old[..shstrtab.len()].copy_from_slice(shstrtab.as_bytes());
+                self.section_headers[shstrndx].size = shstrtab.len() as u64;
+            }
+            _ => self.replace_section(shstrndx, shstrtab.as_bytes())?,
+        }
+
+        debug!(
+            "renamed {} sections, section header string table of {} bytes",
+            self.renames.len(),
+            shstrtab.len()
+        );
+
+        self.shstrtab = shstrtab;
+        self.renames.clear();
+
+        Ok(())
+    }
+
+    /// Translates the `len` bytes at `addr` into the file offset they're loaded from,
+    /// along with the segment loading them
+    fn vaddr_to_offset(&self, addr: u64, len: usize) -> Result<(usize, &Elf64PHdr), EditError> {
+        let end = addr
+            .checked_add(len as u64)
+            .ok_or(EditError::UnmappedRange { addr, len })?;
+        let ph = self
+            .program_headers
+            .iter()
+            .filter(|ph| ph.p_type == PType::PtLoad)
+            .find(|ph| addr >= ph.vaddr.0 && end <= ph.vaddr.0.saturating_add(ph.memsz))
+            .ok_or(EditError::UnmappedRange { addr, len })?;
+
+        if end > ph.vaddr.0.saturating_add(ph.filesz) {
+            return Err(EditError::BeyondFileImage { addr, len });
+        }
+
+        let offset = ph.offset.saturating_add(addr - ph.vaddr.0);
+        match offset.checked_add(len as u64) {
+            Some(end) if end <= self.data.len() as u64 => Ok((offset as usize, ph)),
+            _ => Err(ParseError::InvalidLength.into()),
+        }
+    }
+
+    /// Returns the `len` bytes loaded at `addr`, which must all be held by the file
+    /// image of a single loadable segment
+    pub fn read_vaddr(&self, addr: u64, len: usize) -> Result<&[u8], EditError> {
+        let (offset, _) = self.vaddr_to_offset(addr, len)?;
+
+        Ok(&self.data[offset..offset + len])
+    }
+
+    /// Overwrites the bytes loaded at `addr` with `bytes`, which must all land in the
+    /// file image of a single loadable segment. Nothing is written when they don't
+    pub fn write_vaddr(&mut self, addr: u64, bytes: &[u8]) -> Result<(), EditError> {
+        let (offset, _) = self.vaddr_to_offset(addr, bytes.len())?;
+        self.data[offset..offset + bytes.len()].copy_from_slice(bytes);
+
+        debug!(
+            "wrote {} bytes at {:#x}, file offset {:#x}",
+            bytes.len(),
+            addr,
+            offset
+        );
+
+        Ok(())
+    }
+
+    /// Overwrites the bytes loaded at `addr` as `write_vaddr`, only if the segment
+    /// loading them is executable
+    pub fn write_vaddr_exec(&mut self, addr: u64, bytes: &[u8]) -> Result<(), EditError> {
+        let (_, ph) = self.vaddr_to_offset(addr, bytes.len())?;
+        if ph.flags & PF_EXEC == 0 {
+            return Err(EditError::NotExecutable(addr));
+        }
+
+        self.write_vaddr(addr, bytes)
+    }
+
+    /// Replaces the contents of the section at `idx` with `contents`, which are
+    /// appended to the file honoring the section alignment
+    pub fn replace_section(&mut self, idx: usize, contents: &[u8]) -> Result<(), EditError> {
+        let sh = self
+            .section_headers
+            .get_mut(idx)
+            .ok_or(ParseError::InvalidLength)?;
+
+        if sh.flags & SHF_ALLOC != 0 {
+            return Err(EditError::AllocatedSection(idx));
+        }
+
+        let offset = align_up(self.data.len(), sh.addr_align as usize);
+        self.data.resize(offset, 0);
+        self.data.extend_from_slice(contents);
+
+        sh.offset = offset as u64;
+        sh.size = contents.len() as u64;
+
+        debug!(
+            "replaced section [{}] with {} bytes at {:#x}",
+            idx,
+            contents.len(),
+            offset
+        );
+
+        Ok(())
+    }
+
+    /// Appends a new section named `name`, described by `header` and holding
+    /// `contents`. The name, offset and size of `header` are filled in by the editor.
+    /// Returns the index of the new section
+    pub fn add_section(
+        &mut self,
+        name: &str,
+        header: Elf64SHdr,
+        contents: &[u8],
+    ) -> Result<usize, EditError> {
+        let shstrndx = ElfParser::header_string_table_index(&self.headers, &self.section_headers)
+            .ok_or(ParseError::NameUnavailable)?;
+        if self.section_headers.len() + 1 >= SHN_LORESERVE as usize {
+            return Err(EditError::TooManySections);
+        }
+
+        let name_off = self.shstrtab.insert(name);
+        let shstrtab = self.shstrtab.as_bytes().to_vec();
+        self.replace_section(shstrndx, &shstrtab)?;
+
+        let idx = self.section_headers.len();
+        self.section_headers.push(Elf64SHdr {
+            name: name_off,
+            ..header
+        });
+        self.replace_section(idx, contents)?;
+
+        Ok(idx)
+    }
+
+    /// The message a signature embedded into the section named `section` is made over,
+    /// see `signature::signed_message`
+    pub fn signed_message(&self, section: &str) -> Result<Vec<u8>, EditError> {
+        let idx = self.section_index(section);
+
+        Ok(signed_message(&self.section_headers, &self.data, idx)?)
+    }
+
+    /// Embeds the signature `signer` makes over `signed_message` into the section named
+    /// `section`, which is added as a non-allocated `SHT_PROGBITS` section when the
+    /// file doesn't have it yet. Returns the index of the section
+    pub fn sign(
+        &mut self,
+        section: &str,
+        signer: impl FnOnce(&[u8]) -> Vec<u8>,
+    ) -> Result<usize, EditError> {
+        let signature = signer(&self.signed_message(section)?);
+
+        match self.section_index(section) {
+            Some(idx) => self.replace_section(idx, &signature).map(|_| idx),
+            None => {
+                let header = Elf64SHdr {
+                    s_type: SHT_PROGBITS,
+                    addr_align: 1,
+                    ..Elf64SHdr::NULL
+                };
+                self.add_section(section, header, &signature)
+            }
+        }
+    }
+
+    /// Produces the edited file, appending the updated section header table
+    pub fn save(mut self) -> Result<Vec<u8>, EditError> {
+        if !self.renames.is_empty() {
+            self.rebuild_shstrtab()?;
+        }
+
+        let sh_off = align_up(self.data.len(), 8);
+        self.data.resize(sh_off, 0);
+
+        for sh in &self.section_headers {
+            sh.write(&self.ctx, &mut self.data);
+        }
+
+        let ctx = self.ctx;
+        let ehdr = layout::ehdr::for_class(ctx.class);
+        let sh_num = self.section_headers.len() as u16;
+        let sh_ent_size = layout::shdr::for_class(ctx.class).size as u64;
+        ctx.write_field(&mut self.data, ehdr.e_shoff, sh_off as u64)?;
+        ctx.write_field(&mut self.data, ehdr.e_shentsize, sh_ent_size)?;
+        ctx.write_field(&mut self.data, ehdr.e_shnum, sh_num as u64)?;
+
+        debug!(
+            "wrote {} section headers at {:#x}, {} bytes in total",
+            sh_num,
+            sh_off,
+            self.data.len()
+        );
+
+        Ok(self.data)
+    }
+}
+
+/// Serialized symbol table along with its string table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymtabImage {
+    pub symtab: Vec<u8>,
+    pub strtab: Vec<u8>,
+    /// Index of the first non-local symbol, to be held by `sh_info`
+    pub first_global: u32,
+}
+
+/// Builds a `.symtab`/`.strtab` pair out of named symbols
+#[derive(Debug, Clone, Default)]
+pub struct SymtabEditor {
+    /// Symbols along with their names, without the leading null symbol
+    symbols: Vec<(String, Elf64Sym)>,
+}
+
+impl SymtabEditor {
+    pub fn new() -> Self {
+        SymtabEditor::default()
+    }
+
+    /// Starts from the symbols of the `SHT_SYMTAB` table of the file, if it has one
+    pub fn from_parser(parser: &ElfParser) -> Result<Self, ParseError> {
+        let Some(table) = parser
+            .symbol_tables
+            .iter()
+            .find(|t| t.sh_type == SHT_SYMTAB)
+        else {
+            return Ok(SymtabEditor::new());
+        };
+
+        let symbols = table
+            .symbols
+            .iter()
+            .skip(1)
+            .map(|sym| Ok((parser.symbol_name(table, sym)?, *sym)))
+            .collect::<Result<_, ParseError>>()?;
+
+        Ok(SymtabEditor { symbols })
+    }
+
+    pub fn symbols(&self) -> &[(String, Elf64Sym)] {
+        &self.symbols
+    }
+
+    pub fn add(
+        &mut self,
+        name: &str,
+        value: u64,
+        size: u64,
+        binding: u8,
+        sym_type: u8,
+        shndx: u16,
+    ) -> &mut Self {
+        self.symbols.push((
+            name.to_string(),
+            Elf64Sym {
+                name: 0,
+                info: (binding << 4) | (sym_type & 0xf),
+                other: 0,
+                shndx,
+                value: Address(value),
+                size,
+            },
+        ));
+
+        self
+    }
+
+    /// Renames every symbol named `old` to `new`
+    pub fn rename(&mut self, old: &str, new: &str) -> Result<&mut Self, EditError> {
+        let mut found = false;
+        for (name, _) in self.symbols.iter_mut().filter(|(n, _)| n == old) {
+            *name = new.to_string();
+            found = true;
+        }
+
+        if !found {
+            return Err(EditError::SymbolNotFound(old.to_string()));
+        }
+
+        Ok(self)
+    }
+
+    /// Serializes the symbols, null symbol first and locals before every other binding,
+    /// with each distinct name stored once in the string table
+    pub fn build(&self, ctx: &ParseContext) -> SymtabImage {
+        let mut strtab = StringTableBuilder::new();
+
+        let mut symtab = vec![];
+        Elf64Sym {
+            name: 0,
+            info: 0,
+            other: 0,
+            shndx: 0,
+            value: Address(0),
+            size: 0,
+        }
+        .write(ctx, &mut symtab);
+
+        let locals = self.symbols.iter().filter(|(_, s)| s.bind() == STB_LOCAL);
+        let globals = self.symbols.iter().filter(|(_, s)| s.bind() != STB_LOCAL);
+        let first_global = 1 + locals.clone().count() as u32;
+
+        for (name, sym) in locals.chain(globals) {
+            Elf64Sym {
+                name: strtab.insert(name),
+                ..*sym
+            }
+            .write(ctx, &mut symtab);
+        }
+
+        SymtabImage {
+            symtab,
+            strtab: strtab.build(),
+            first_global,
+        }
+    }
+
+    /// Writes the symbols into `editor`, replacing its `.symtab` and the string table
+    /// it links to, or adding both sections if the file has no symbol table
+    pub fn apply(&self, editor: &mut ElfEditor) -> Result<(), EditError> {
+        let image = self.build(&editor.ctx);
+
+        let (symtab_idx, strtab_idx) = match editor.section_index(".symtab") {
+            Some(idx) => {
+                let strtab_idx = resolve_link(&editor.section_headers, idx, &[SHT_STRTAB])?
+                    .ok_or(ParseError::NameUnavailable)?;
+                editor.replace_section(strtab_idx, &image.strtab)?;
+                editor.replace_section(idx, &image.symtab)?;
+                (idx, strtab_idx)
+            }
+            None => {
+                let strtab_idx = editor.add_section(
+                    ".strtab",
+                    Elf64SHdr {
+                        name: 0,
+                        s_type: SHT_STRTAB,
+                        flags: 0,
+                        addr: Address(0),
+                        offset: 0,
+                        size: 0,
+                        link: 0,
+                        info: 0,
+                        addr_align: 1,
+                        ent_size: 0,
+                    },
+                    &image.strtab,
+                )?;
+                let idx = editor.add_section(
+                    ".symtab",
+                    Elf64SHdr {
+                        name: 0,
+                        s_type: SHT_SYMTAB,
+                        flags: 0,
+                        addr: Address(0),
+                        offset: 0,
+                        size: 0,
+                        link: 0,
+                        info: 0,
+                        addr_align: editor.ctx.word_size() as u64,
+                        ent_size: layout::sym::for_class(editor.ctx.class).size as u64,
+                    },
+                    &image.symtab,
+                )?;
+                (idx, strtab_idx)
+            }
+        };
+
+        let sh = &mut editor.section_headers[symtab_idx];
+        sh.link = strtab_idx as u32;
+        sh.info = image.first_global;
+
+        debug!(
+            "wrote {} symbols into section [{}], first global at {}",
+            self.symbols.len() + 1,
+            symtab_idx,
+            image.first_global
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+use crate::bytes::ParseContext;
+use crate::layout;
+use crate::parser::ParseError;
+
+/// Compressed with zlib
+pub const ELFCOMPRESS_ZLIB: u32 = 1;
+/// Compressed with Zstandard
+pub const ELFCOMPRESS_ZSTD: u32 = 2;
+
+/// Magic number of sections compressed the legacy GNU way, named `.zdebug_*`, which
+/// is followed by the uncompressed size as a big endian 64-bit integer
+pub const ZDEBUG_MAGIC: &[u8; 4] = b"ZLIB";
+
+/// Header of the contents of a `SHF_COMPRESSED` section, describing them once
+/// uncompressed. The compressed stream follows it
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Elf64Chdr {
+    /// Compression algorithm, one of `ELFCOMPRESS_*`
+    pub ch_type: u32,
+    /// Size of the uncompressed contents
+    pub size: u64,
+    /// Alignment of the uncompressed contents
+    pub addr_align: u64,
+}
+
+impl Elf64Chdr {
+    /// Reads the header at the start of `data`, the contents of a compressed section
+    pub fn parse(data: &[u8], ctx: &ParseContext) -> Result<Self, ParseError> {
+        let layout = layout::chdr::for_class(ctx.class);
+        let field = |spec| ctx.read_field(data, spec);
+
+        Ok(Elf64Chdr {
+            ch_type: field(layout.ch_type)? as u32,
+            size: field(layout.ch_size)?,
+            addr_align: field(layout.ch_addralign)?,
+        })
+    }
+}
+
+/// Uncompressed size stored by the legacy GNU header at the start of `data`, the
+/// contents of a `.zdebug_*` section
+pub fn zdebug_size(data: &[u8]) -> Option<u64> {
+    let size = data.strip_prefix(ZDEBUG_MAGIC)?.get(..8)?;
+
+    Some(u64::from_be_bytes(size.try_into().unwrap()))
+}
@@ -0,0 +1,192 @@
+use std::fmt;
+
+/// Object may use DF_ORIGIN
+pub const DF_ORIGIN: u64 = 0x1;
+/// Symbol resolutions starts here
+pub const DF_SYMBOLIC: u64 = 0x2;
+/// Object contains text relocations
+pub const DF_TEXTREL: u64 = 0x4;
+/// No lazy binding for this object
+pub const DF_BIND_NOW: u64 = 0x8;
+/// Module uses the static TLS model
+pub const DF_STATIC_TLS: u64 = 0x10;
+
+pub const DF_1_NOW: u64 = 0x1;
+pub const DF_1_GLOBAL: u64 = 0x2;
+pub const DF_1_GROUP: u64 = 0x4;
+pub const DF_1_NODELETE: u64 = 0x8;
+pub const DF_1_LOADFLTR: u64 = 0x10;
+pub const DF_1_INITFIRST: u64 = 0x20;
+pub const DF_1_NOOPEN: u64 = 0x40;
+pub const DF_1_ORIGIN: u64 = 0x80;
+pub const DF_1_DIRECT: u64 = 0x100;
+pub const DF_1_TRANS: u64 = 0x200;
+pub const DF_1_INTERPOSE: u64 = 0x400;
+pub const DF_1_NODEFLIB: u64 = 0x800;
+pub const DF_1_NODUMP: u64 = 0x1000;
+pub const DF_1_CONFALT: u64 = 0x2000;
+pub const DF_1_ENDFILTEE: u64 = 0x4000;
+pub const DF_1_DISPRELDNE: u64 = 0x8000;
+pub const DF_1_DISPRELPND: u64 = 0x10000;
+pub const DF_1_NODIRECT: u64 = 0x20000;
+pub const DF_1_IGNMULDEF: u64 = 0x40000;
+pub const DF_1_NOKSYMS: u64 = 0x80000;
+pub const DF_1_NOHDR: u64 = 0x100000;
+pub const DF_1_EDITED: u64 = 0x200000;
+pub const DF_1_NORELOC: u64 = 0x400000;
+pub const DF_1_SYMINTPOSE: u64 = 0x800000;
+pub const DF_1_GLOBAUDIT: u64 = 0x1000000;
+pub const DF_1_SINGLETON: u64 = 0x2000000;
+pub const DF_1_STUB: u64 = 0x4000000;
+pub const DF_1_PIE: u64 = 0x8000000;
+
+const DF_NAMES: &[(u64, &str)] = &[
+    (DF_ORIGIN, "ORIGIN"),
+    (DF_SYMBOLIC, "SYMBOLIC"),
+    (DF_TEXTREL, "TEXTREL"),
+    (DF_BIND_NOW, "BIND_NOW"),
+    (DF_STATIC_TLS, "STATIC_TLS"),
+];
+
+const DF_1_NAMES: &[(u64, &str)] = &[
+    (DF_1_NOW, "NOW"),
+    (DF_1_GLOBAL, "GLOBAL"),
+    (DF_1_GROUP, "GROUP"),
+    (DF_1_NODELETE, "NODELETE"),
+    (DF_1_LOADFLTR, "LOADFLTR"),
+    (DF_1_INITFIRST, "INITFIRST"),
+    (DF_1_NOOPEN, "NOOPEN"),
+    (DF_1_ORIGIN, "ORIGIN"),
+    (DF_1_DIRECT, "DIRECT"),
+    (DF_1_TRANS, "TRANS"),
+    (DF_1_INTERPOSE, "INTERPOSE"),
+    (DF_1_NODEFLIB, "NODEFLIB"),
+    (DF_1_NODUMP, "NODUMP"),
+    (DF_1_CONFALT, "CONFALT"),
+    (DF_1_ENDFILTEE, "ENDFILTEE"),
+    (DF_1_DISPRELDNE, "DISPRELDNE"),
+    (DF_1_DISPRELPND, "DISPRELPND"),
+    (DF_1_NODIRECT, "NODIRECT"),
+    (DF_1_IGNMULDEF, "IGNMULDEF"),
+    (DF_1_NOKSYMS, "NOKSYMS"),
+    (DF_1_NOHDR, "NOHDR"),
+    (DF_1_EDITED, "EDITED"),
+    (DF_1_NORELOC, "NORELOC"),
+    (DF_1_SYMINTPOSE, "SYMINTPOSE"),
+    (DF_1_GLOBAUDIT, "GLOBAUDIT"),
+    (DF_1_SINGLETON, "SINGLETON"),
+    (DF_1_STUB, "STUB"),
+    (DF_1_PIE, "PIE"),
+];
+
+/// Writes the mnemonic of every set flag separated by spaces, followed by the
+/// remaining unknown bits in hex
+fn fmt_flags(f: &mut fmt::Formatter<'_>, bits: u64, names: &[(u64, &str)]) -> fmt::Result {
+    let mut known = 0;
+    let mut out = vec![];
+
+    for (flag, name) in names {
+        if bits & flag != 0 {
+            known |= flag;
+            out.push(name.to_string());
+        }
+    }
+
+    if bits & !known != 0 {
+        out.push(format!("{:#x}", bits & !known));
+    }
+
+    write!(f, "{}", out.join(" "))
+}
+
+/// Flags held by the `DT_FLAGS` dynamic entry. Bits this crate doesn't know
+/// about are preserved
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct DynFlags(pub u64);
+
+impl DynFlags {
+    pub fn origin(&self) -> bool {
+        self.0 & DF_ORIGIN != 0
+    }
+
+    pub fn symbolic(&self) -> bool {
+        self.0 & DF_SYMBOLIC != 0
+    }
+
+    pub fn textrel(&self) -> bool {
+        self.0 & DF_TEXTREL != 0
+    }
+
+    pub fn bind_now(&self) -> bool {
+        self.0 & DF_BIND_NOW != 0
+    }
+
+    pub fn static_tls(&self) -> bool {
+        self.0 & DF_STATIC_TLS != 0
+    }
+
+    /// Bits that don't map to any known flag
+    pub fn unknown_bits(&self) -> u64 {
+        self.0 & !DF_NAMES.iter().fold(0, |acc, (flag, _)| acc | flag)
+    }
+}
+
+impl fmt::Display for DynFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_flags(f, self.0, DF_NAMES)
+    }
+}
+
+/// Flags held by the `DT_FLAGS_1` dynamic entry. Bits this crate doesn't know
+/// about are preserved
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct DynFlags1(pub u64);
+
+impl DynFlags1 {
+    pub fn now(&self) -> bool {
+        self.0 & DF_1_NOW != 0
+    }
+
+    pub fn global(&self) -> bool {
+        self.0 & DF_1_GLOBAL != 0
+    }
+
+    pub fn nodelete(&self) -> bool {
+        self.0 & DF_1_NODELETE != 0
+    }
+
+    pub fn initfirst(&self) -> bool {
+        self.0 & DF_1_INITFIRST != 0
+    }
+
+    pub fn noopen(&self) -> bool {
+        self.0 & DF_1_NOOPEN != 0
+    }
+
+    pub fn origin(&self) -> bool {
+        self.0 & DF_1_ORIGIN != 0
+    }
+
+    pub fn interpose(&self) -> bool {
+        self.0 & DF_1_INTERPOSE != 0
+    }
+
+    pub fn nodeflib(&self) -> bool {
+        self.0 & DF_1_NODEFLIB != 0
+    }
+
+    pub fn pie(&self) -> bool {
+        self.0 & DF_1_PIE != 0
+    }
+
+    /// Bits that don't map to any known flag
+    pub fn unknown_bits(&self) -> u64 {
+        self.0 & !DF_1_NAMES.iter().fold(0, |acc, (flag, _)| acc | flag)
+    }
+}
+
+impl fmt::Display for DynFlags1 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_flags(f, self.0, DF_1_NAMES)
+    }
+}
@@ -0,0 +1,119 @@
+use crate::bytes::{Address, ParseContext};
+use crate::parser::ParseError;
+use log::debug;
+
+/// The value is a pointer-sized absolute value
+pub const DW_EH_PE_ABSPTR: u8 = 0x00;
+pub const DW_EH_PE_UDATA2: u8 = 0x02;
+pub const DW_EH_PE_UDATA4: u8 = 0x03;
+pub const DW_EH_PE_UDATA8: u8 = 0x04;
+pub const DW_EH_PE_SDATA2: u8 = 0x0a;
+pub const DW_EH_PE_SDATA4: u8 = 0x0b;
+pub const DW_EH_PE_SDATA8: u8 = 0x0c;
+/// The value is relative to the address of the field holding it
+pub const DW_EH_PE_PCREL: u8 = 0x10;
+/// The value is relative to the start of the `.eh_frame_hdr` section
+pub const DW_EH_PE_DATAREL: u8 = 0x30;
+/// The value isn't present
+pub const DW_EH_PE_OMIT: u8 = 0xff;
+
+/// Decoded `.eh_frame_hdr` section, whose search table maps the initial location of
+/// every function to its FDE in `.eh_frame`
+#[derive(Debug, Clone, PartialEq)]
+pub struct EhFrameHdr {
+    pub version: u8,
+    pub eh_frame_ptr_enc: u8,
+    pub fde_count_enc: u8,
+    pub table_enc: u8,
+    /// Address of the `.eh_frame` section
+    pub eh_frame_ptr: Address,
+    /// (initial location, FDE address) pairs, sorted by initial location. Empty when
+    /// the linker didn't emit a search table
+    pub table: Vec<(Address, Address)>,
+}
+
+/// Reads a value encoded with a `DW_EH_PE_*` encoding at `off`, advancing it past the
+/// value. `vaddr` is the address the section is loaded at, needed to resolve the
+/// relative encodings
+fn read_encoded(
+    ctx: &ParseContext,
+    data: &[u8],
+    off: &mut usize,
+    enc: u8,
+    vaddr: u64,
+) -> Result<u64, ParseError> {
+    let field_addr = vaddr.wrapping_add(*off as u64);
+
+    let (value, size) = match enc & 0x0f {
+        DW_EH_PE_ABSPTR => (ctx.read_uword(data, *off)?, ctx.word_size()),
+        DW_EH_PE_UDATA2 => (ctx.read_u16(data, *off)? as u64, 2),
+        DW_EH_PE_UDATA4 => (ctx.read_u32(data, *off)? as u64, 4),
+        DW_EH_PE_UDATA8 => (ctx.read_u64(data, *off)?, 8),
+        DW_EH_PE_SDATA2 => (ctx.read_u16(data, *off)? as i16 as u64, 2),
+        DW_EH_PE_SDATA4 => (ctx.read_u32(data, *off)? as i32 as u64, 4),
+        DW_EH_PE_SDATA8 => (ctx.read_u64(data, *off)?, 8),
+        _ => return Err(ParseError::UnsupportedPointerEncoding(enc)),
+    };
+    *off += size;
+
+    match enc & 0x70 {
+        0 => Ok(value),
+        DW_EH_PE_PCREL => Ok(field_addr.wrapping_add(value)),
+        DW_EH_PE_DATAREL => Ok(vaddr.wrapping_add(value)),
+        _ => Err(ParseError::UnsupportedPointerEncoding(enc)),
+    }
+}
+
+impl EhFrameHdr {
+    /// Parses the contents of a `.eh_frame_hdr` section loaded at `vaddr`
+    pub fn parse(data: &[u8], ctx: &ParseContext, vaddr: u64) -> Result<Self, ParseError> {
+        let version = ctx.read_u8(data, 0)?;
+        let eh_frame_ptr_enc = ctx.read_u8(data, 1)?;
+        let fde_count_enc = ctx.read_u8(data, 2)?;
+        let table_enc = ctx.read_u8(data, 3)?;
+
+        let mut off = 4;
+        let eh_frame_ptr = read_encoded(ctx, data, &mut off, eh_frame_ptr_enc, vaddr)?;
+
+        let mut table = vec![];
+        if fde_count_enc != DW_EH_PE_OMIT && table_enc != DW_EH_PE_OMIT {
+            let fde_count = read_encoded(ctx, data, &mut off, fde_count_enc, vaddr)?;
+            // every value of the table takes two bytes at least, so a count beyond what
+            // the section can hold is bogus and mustn't drive the loop
+            if fde_count > (data.len().saturating_sub(off) / 4) as u64 {
+                return Err(ParseError::InvalidLength);
+            }
+
+            for _ in 0..fde_count {
+                let initial_location = read_encoded(ctx, data, &mut off, table_enc, vaddr)?;
+                let fde = read_encoded(ctx, data, &mut off, table_enc, vaddr)?;
+                table.push((Address(initial_location), Address(fde)));
+            }
+        }
+
+        debug!(
+            "parsed eh_frame_hdr version {} with {} FDEs, eh_frame at {:#x}",
+            version,
+            table.len(),
+            eh_frame_ptr
+        );
+
+        Ok(EhFrameHdr {
+            version,
+            eh_frame_ptr_enc,
+            fde_count_enc,
+            table_enc,
+            eh_frame_ptr: Address(eh_frame_ptr),
+            table,
+        })
+    }
+
+    /// Returns the address of the FDE of the function covering `pc`, that is the one
+    /// with the closest initial location at or below it. Whether `pc` is within the
+    /// range of that FDE can only be told by decoding the FDE itself
+    pub fn lookup(&self, pc: u64) -> Option<Address> {
+        let idx = self.table.partition_point(|(loc, _)| loc.0 <= pc);
+
+        idx.checked_sub(1).map(|idx| self.table[idx].1)
+    }
+}
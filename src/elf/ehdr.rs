@@ -1,22 +1,51 @@
-use crate::bytes::{convert, Address};
+use crate::bytes::{Address, ParseContext};
+use crate::layout;
+use crate::options::ParseOptions;
 use crate::parser::ParseError;
+use std::fmt;
+use std::ops::Range;
 
 /// Size of the first batch of information on the file, which contains
 /// the data needed to parse the rest of the file
 pub const IDENT_SZ: usize = 16;
 
+/// Magic number every ELF file starts with
+pub const ELF_MAGIC: [u8; 4] = [0x7f, b'E', b'L', b'F'];
+
+/// Machine value for Intel 80386
+pub const EM_386: u16 = 3;
+/// Machine value for ARM 32-bit
+pub const EM_ARM: u16 = 40;
+/// Machine value for AMD x86-64
+pub const EM_X86_64: u16 = 62;
+/// Machine value for ARM 64-bit
+pub const EM_AARCH64: u16 = 183;
+/// Machine value for RISC-V
+pub const EM_RISCV: u16 = 243;
+
+/// RISC-V: the file uses the compressed instruction extension
+pub const EF_RISCV_RVC: u32 = 0x1;
+/// RISC-V: mask for the floating point ABI bits
+pub const EF_RISCV_FLOAT_ABI: u32 = 0x6;
+/// RISC-V: the file targets the RV32E base integer instruction set
+pub const EF_RISCV_RVE: u32 = 0x8;
+/// RISC-V: the file requires the RVTSO memory consistency model
+pub const EF_RISCV_TSO: u32 = 0x10;
+
 #[repr(u8)]
+#[non_exhaustive]
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub enum ElfHClass {
     /// Identifies the ELF class as invalid
     _ElfClassIn = 0,
     /// Identifies the ELF class as 32-bit
-    _ElfClass32 = 1,
+    ElfClass32 = 1,
     /// Identifies the ELF class as 64-bit
     ElfClass64 = 2,
 }
 
 #[repr(u8)]
+#[non_exhaustive]
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub enum ElfHData {
     /// Identifies the ELF data as 2's complement, with the least significant byte
@@ -28,6 +57,7 @@ pub enum ElfHData {
 }
 
 #[repr(u8)]
+#[non_exhaustive]
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub enum ElfHVersion {
     /// Identifies the ELF version as invalid
@@ -36,6 +66,135 @@ pub enum ElfHVersion {
     ElfEvCurr = 1,
 }
 
+/// Value of `e_version`, the version of the object file format, which takes the
+/// values of `ElfHVersion` over 32 bits
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum ObjectVersion {
+    Known(ElfHVersion),
+    /// A value no `ElfHVersion` stands for
+    Unknown(u32),
+}
+
+impl ObjectVersion {
+    /// The value of the field
+    pub fn value(&self) -> u32 {
+        match self {
+            ObjectVersion::Known(version) => *version as u32,
+            ObjectVersion::Unknown(value) => *value,
+        }
+    }
+}
+
+impl From<u32> for ObjectVersion {
+    fn from(value: u32) -> Self {
+        u8::try_from(value)
+            .ok()
+            .and_then(|v| ElfHVersion::try_from(v).ok())
+            .map_or(ObjectVersion::Unknown(value), ObjectVersion::Known)
+    }
+}
+
+impl ElfHClass {
+    /// Every variant, in the order of their values
+    pub const VARIANTS: [ElfHClass; 3] = [
+        ElfHClass::_ElfClassIn,
+        ElfHClass::ElfClass32,
+        ElfHClass::ElfClass64,
+    ];
+}
+
+impl ElfHData {
+    pub const VARIANTS: [ElfHData; 2] = [ElfHData::ElfData2Lsb, ElfHData::ElfData2Msb];
+}
+
+impl ElfHVersion {
+    pub const VARIANTS: [ElfHVersion; 2] = [ElfHVersion::ElfEvNone, ElfHVersion::ElfEvCurr];
+}
+
+// the variants must keep the values the specification gives them, as they're read
+// from and written to files through these. Adding a variant breaks the build here
+// until its value is listed
+const _: () = {
+    const fn class(class: ElfHClass) -> u8 {
+        match class {
+            ElfHClass::_ElfClassIn => 0,
+            ElfHClass::ElfClass32 => 1,
+            ElfHClass::ElfClass64 => 2,
+        }
+    }
+    const fn data(data: ElfHData) -> u8 {
+        match data {
+            ElfHData::ElfData2Lsb => 1,
+            ElfHData::ElfData2Msb => 2,
+        }
+    }
+    const fn version(version: ElfHVersion) -> u8 {
+        match version {
+            ElfHVersion::ElfEvNone => 0,
+            ElfHVersion::ElfEvCurr => 1,
+        }
+    }
+    const fn e_type(e_type: ElfHType) -> u16 {
+        match e_type {
+            ElfHType::_None => 0,
+            ElfHType::Relocatable => 1,
+            ElfHType::Executable => 2,
+            ElfHType::SharedObject => 3,
+            ElfHType::Core => 4,
+        }
+    }
+
+    let mut idx = 0;
+    while idx < ElfHClass::VARIANTS.len() {
+        let v = ElfHClass::VARIANTS[idx];
+        assert!(class(v) == v as u8);
+        idx += 1;
+    }
+    let mut idx = 0;
+    while idx < ElfHData::VARIANTS.len() {
+        let v = ElfHData::VARIANTS[idx];
+        assert!(data(v) == v as u8);
+        idx += 1;
+    }
+    let mut idx = 0;
+    while idx < ElfHVersion::VARIANTS.len() {
+        let v = ElfHVersion::VARIANTS[idx];
+        assert!(version(v) == v as u8);
+        idx += 1;
+    }
+    let mut idx = 0;
+    while idx < ElfHType::VARIANTS.len() {
+        let v = ElfHType::VARIANTS[idx];
+        assert!(e_type(v) == v as u16);
+        idx += 1;
+    }
+};
+
+impl TryFrom<u8> for ElfHClass {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(ElfHClass::_ElfClassIn),
+            1 => Ok(ElfHClass::ElfClass32),
+            2 => Ok(ElfHClass::ElfClass64),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryFrom<u8> for ElfHData {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(ElfHData::ElfData2Lsb),
+            2 => Ok(ElfHData::ElfData2Msb),
+            _ => Err(()),
+        }
+    }
+}
+
 impl TryFrom<u8> for ElfHVersion {
     type Error = ();
 
@@ -75,13 +234,120 @@ pub struct Elf64Ident {
     _pad: [u8; IDENT_SZ - 9],
 }
 
+impl Elf64Ident {
+    /// An identification with the ELF magic number and zeroed padding
+    pub fn new(
+        class: ElfHClass,
+        data: ElfHData,
+        version: ElfHVersion,
+        os_abi: u8,
+        abi_version: u8,
+    ) -> Self {
+        Elf64Ident {
+            mag: ELF_MAGIC,
+            class,
+            data,
+            version,
+            os_abi,
+            abi_version,
+            _pad: [0; IDENT_SZ - 9],
+        }
+    }
+
+    /// Decodes the identification held by `bytes`. Fails on a wrong magic number and
+    /// on a class, data encoding or version no variant stands for, but accepts the
+    /// invalid ones that have a variant, which `validate` rejects
+    pub fn from_bytes(bytes: &[u8; IDENT_SZ]) -> Result<Self, ParseError> {
+        if bytes[..4] != ELF_MAGIC {
+            return Err(ParseError::InvalidMagic);
+        }
+
+        Ok(Elf64Ident {
+            mag: ELF_MAGIC,
+            class: ElfHClass::try_from(bytes[4])
+                .map_err(|_| ParseError::UnsupportedClass(bytes[4]))?,
+            data: ElfHData::try_from(bytes[5])
+                .map_err(|_| ParseError::UnsupportedEncoding(bytes[5]))?,
+            version: ElfHVersion::try_from(bytes[6])
+                .map_err(|_| ParseError::UnsupportedVersion(bytes[6]))?,
+            os_abi: bytes[7],
+            abi_version: bytes[8],
+            _pad: bytes[9..].try_into().unwrap(),
+        })
+    }
+
+    /// Encoding of the identification, as found at the start of the file
+    pub fn to_bytes(&self) -> [u8; IDENT_SZ] {
+        let mut bytes = [0; IDENT_SZ];
+        bytes[..4].copy_from_slice(&self.mag);
+        bytes[4] = self.class as u8;
+        bytes[5] = self.data as u8;
+        bytes[6] = self.version as u8;
+        bytes[7] = self.os_abi;
+        bytes[8] = self.abi_version;
+        bytes[9..].copy_from_slice(&self._pad);
+
+        bytes
+    }
+
+    /// Checks the identification describes an ELF file of a known class and of the
+    /// current version, whichever its data encoding
+    pub fn validate(&self) -> Result<&Self, ParseError> {
+        if self.mag != ELF_MAGIC {
+            return Err(ParseError::InvalidMagic);
+        }
+        if self.class == ElfHClass::_ElfClassIn {
+            return Err(ParseError::UnsupportedClass(self.class as u8));
+        }
+        if self.version != ElfHVersion::ElfEvCurr {
+            return Err(ParseError::UnsupportedVersion(self.version as u8));
+        }
+
+        Ok(self)
+    }
+
+    /// Bytes following `abi_version`, reserved by the specification and expected to
+    /// be zero
+    pub fn padding(&self) -> &[u8; IDENT_SZ - 9] {
+        &self._pad
+    }
+
+    /// Replaces the bytes following `abi_version`, which only files relying on
+    /// extensions of the specification set
+    pub fn with_padding(mut self, padding: [u8; IDENT_SZ - 9]) -> Self {
+        self._pad = padding;
+        self
+    }
+
+    pub fn is_padding_clean(&self) -> bool {
+        self._pad.iter().all(|b| *b == 0)
+    }
+}
+
 #[repr(u16)]
-#[derive(Debug, Copy, Clone)]
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum ElfHType {
     /// No file type
     _None = 0,
+    /// A relocatable object, such as the members of static libraries
+    Relocatable = 1,
     /// An executable file
     Executable = 2,
+    /// A shared object, or a position independent executable
+    SharedObject = 3,
+    /// A core dump, the memory and state of a process as it crashed
+    Core = 4,
+}
+
+impl ElfHType {
+    pub const VARIANTS: [ElfHType; 5] = [
+        ElfHType::_None,
+        ElfHType::Relocatable,
+        ElfHType::Executable,
+        ElfHType::SharedObject,
+        ElfHType::Core,
+    ];
 }
 
 impl TryFrom<u16> for ElfHType {
@@ -89,7 +355,11 @@ impl TryFrom<u16> for ElfHType {
 
     fn try_from(value: u16) -> Result<Self, Self::Error> {
         match value {
+            0 => Ok(ElfHType::_None),
+            1 => Ok(ElfHType::Relocatable),
             2 => Ok(ElfHType::Executable),
+            3 => Ok(ElfHType::SharedObject),
+            4 => Ok(ElfHType::Core),
             _ => Err(()),
         }
     }
@@ -105,8 +375,9 @@ pub struct Elf64Hdr {
     pub e_type: ElfHType,
     /// Indicates the architecture needed for the file
     pub machine: u16,
-    /// Indicates the ELF header version for this file.
-    pub version: u32,
+    /// Indicates the version of the object file format, which should match the one of
+    /// the identification
+    pub version: ObjectVersion,
     /// Specifies the virtual address to which the system will handle control.
     /// If there's no entry point for this file, this field holds 0.
     pub entry: Address,
@@ -140,43 +411,376 @@ pub struct Elf64Hdr {
 }
 
 impl Elf64Hdr {
-    pub fn validate(&self) -> &Self {
-        assert_eq!(self.ident.mag, [0x7f, b'E', b'L', b'F']);
-        assert_eq!(self.ident.class, ElfHClass::ElfClass64);
-        assert_eq!(self.ident.data, ElfHData::ElfData2Lsb);
-        assert_eq!(self.ident.version, ElfHVersion::ElfEvCurr);
+    /// Checks that the file is one this crate can parse, in either byte order. An
+    /// identification of version 0 is let through, as some tools emit it, and reported
+    /// along with `version` by `ParseWarning::VersionMismatch`
+    pub fn validate(&self) -> Result<&Self, ParseError> {
+        match self.ident.validate() {
+            Err(ParseError::UnsupportedVersion(0)) => {}
+            result => {
+                result?;
+            }
+        }
 
-        self
+        Ok(self)
+    }
+
+    /// Size of the fields of the header for the class of the file, which are decoded
+    /// whatever `eh_size` says
+    pub fn class_size(&self) -> usize {
+        layout::ehdr::for_class(self.ident.class).size
+    }
+
+    /// End of the header region: the `eh_size` bytes declared by the header, never
+    /// less than the fields of its class. A header claiming fewer bytes than its class
+    /// holds still has every field decoded, and one claiming more extends the region
+    /// with the bytes returned by `extra_bytes`
+    pub fn header_end(&self) -> u64 {
+        (self.eh_size as u64).max(self.class_size() as u64)
+    }
+
+    /// Bytes of the header region past the fields of the class, reserved for vendor
+    /// extensions. The header doesn't keep a copy of them, so they're read from `data`,
+    /// the file it was parsed from, and cut short if the file ends before
+    pub fn extra_bytes<'d>(&self, data: &'d [u8]) -> &'d [u8] {
+        let start = self.class_size().min(data.len());
+        let end = (self.header_end() as usize).min(data.len());
+
+        &data[start..end]
+    }
+
+    /// Bytes of the section header table, `sh_num` entries of `sh_ent_size` bytes at
+    /// `sh_off`. None when the file has no section header table, and fails with
+    /// `ParseError::SectionHeadersOutOfBounds` when the table runs past the
+    /// `file_len` bytes of the file
+    pub fn shdr_table_range(&self, file_len: u64) -> Result<Option<Range<u64>>, ParseError> {
+        table_range(self.sh_off, self.sh_num as u64, self.sh_ent_size, file_len)
+            .map_err(|_| ParseError::SectionHeadersOutOfBounds)
+    }
+
+    /// Bytes of the program header table, `ph_num` entries of `ph_ent_size` bytes at
+    /// `ph_off`. None when the file has no program header table, and fails with
+    /// `ParseError::ProgramHeadersOutOfBounds` when the table runs past the
+    /// `file_len` bytes of the file. Files holding their count in the initial section
+    /// header through `PN_XNUM` need `phdr_table_range_of`
+    pub fn phdr_table_range(&self, file_len: u64) -> Result<Option<Range<u64>>, ParseError> {
+        self.phdr_table_range_of(self.ph_num as usize, file_len)
+    }
+
+    /// Bytes of the program header table holding `count` entries, as returned by
+    /// `Elf64PHdr::count`, see `phdr_table_range`
+    pub fn phdr_table_range_of(
+        &self,
+        count: usize,
+        file_len: u64,
+    ) -> Result<Option<Range<u64>>, ParseError> {
+        table_range(self.ph_off, count as u64, self.ph_ent_size, file_len)
+            .map_err(|_| ParseError::ProgramHeadersOutOfBounds)
+    }
+
+    /// Whether a table of `size` bytes at `offset` overlaps the header region
+    pub fn overlaps_header(&self, offset: u64, size: u64) -> bool {
+        size > 0 && offset < self.header_end()
     }
 
     pub fn parse_ident(data: &[u8]) -> Result<Elf64Ident, ParseError> {
-        let mut ident = [0u8; IDENT_SZ];
-        ident
-            .copy_from_slice(&(data.get(..IDENT_SZ).ok_or(ParseError::InvalidLength)?)[..IDENT_SZ]);
+        let ident = data.get(..IDENT_SZ).ok_or(ParseError::InvalidLength)?;
+
+        Elf64Ident::from_bytes(ident.try_into().unwrap())
+    }
+
+    /// Reads the identification without validating it, taking the class and the data
+    /// encoding from `class` and `endianness` when set instead of from the file. Fails
+    /// only when one of those is invalid in the file and not overridden
+    pub fn parse_ident_raw(
+        data: &[u8],
+        class: Option<ElfHClass>,
+        endianness: Option<ElfHData>,
+    ) -> Result<Elf64Ident, ParseError> {
+        let ident = data.get(..IDENT_SZ).ok_or(ParseError::InvalidLength)?;
 
-        Ok(unsafe { std::mem::transmute::<[u8; IDENT_SZ], Elf64Ident>(ident) })
+        let class = match (class, ident[4]) {
+            (Some(class), _) => class,
+            (None, 1) => ElfHClass::ElfClass32,
+            (None, 2) => ElfHClass::ElfClass64,
+            (None, c) => return Err(ParseError::UnsupportedClass(c)),
+        };
+        let data = match endianness {
+            Some(endianness) => endianness,
+            None => ElfHData::try_from(ident[5])
+                .map_err(|_| ParseError::UnsupportedEncoding(ident[5]))?,
+        };
+
+        Ok(Elf64Ident {
+            mag: ident[..4].try_into().unwrap(),
+            class,
+            data,
+            version: ElfHVersion::try_from(ident[6]).unwrap_or(ElfHVersion::ElfEvNone),
+            os_abi: ident[7],
+            abi_version: ident[8],
+            _pad: ident[9..].try_into().unwrap(),
+        })
     }
 
     pub fn parse(data: &[u8]) -> Result<Self, ParseError> {
-        let ident = Self::parse_ident(&data).unwrap();
+        Self::parse_fields(data, Self::parse_ident(data)?)
+    }
+
+    /// Parses the header as `options` require: validating the identification unless
+    /// `skip_ident_validation` is set, and decoding the rest of the fields with the
+    /// class and data encoding overrides applied
+    pub fn parse_with(data: &[u8], options: &ParseOptions) -> Result<Self, ParseError> {
+        if !options.skip_ident_validation {
+            let header = *Self::parse(data)?.validate()?;
+            if options.force_class.is_none() && options.force_endianness.is_none() {
+                return Ok(header);
+            }
+        }
+
+        let ident = Self::parse_ident_raw(data, options.force_class, options.force_endianness)?;
+        Self::parse_fields(data, ident)
+    }
+
+    /// Appends the encoding of this header to `out`: the identification followed by
+    /// the fields of its class, in the encoding it declares
+    pub fn write(&self, out: &mut Vec<u8>) {
+        let ctx = ParseContext::from(&self.ident);
+        let layout = layout::ehdr::for_class(self.ident.class);
+        let mut eh = vec![0u8; layout.size];
+        eh[..IDENT_SZ].copy_from_slice(&self.ident.to_bytes());
+
+        [
+            (layout.e_type, self.e_type as u64),
+            (layout.e_machine, self.machine as u64),
+            (layout.e_version, self.version.value() as u64),
+            (layout.e_entry, self.entry.0),
+            (layout.e_phoff, self.ph_off),
+            (layout.e_shoff, self.sh_off),
+            (layout.e_flags, self.flags as u64),
+            (layout.e_ehsize, self.eh_size as u64),
+            (layout.e_phentsize, self.ph_ent_size as u64),
+            (layout.e_phnum, self.ph_num as u64),
+            (layout.e_shentsize, self.sh_ent_size as u64),
+            (layout.e_shnum, self.sh_num as u64),
+            (layout.e_shstrndx, self.sh_str_ndx as u64),
+        ]
+        .into_iter()
+        .for_each(|(spec, value)| ctx.write_field(&mut eh, spec, value).unwrap());
+
+        out.extend_from_slice(&eh);
+    }
+
+    /// Decodes the fields following the identification, using the encoding declared
+    /// by `ident`
+    fn parse_fields(data: &[u8], ident: Elf64Ident) -> Result<Self, ParseError> {
+        let mut fields = ParseContext::from(&ident).reader(data).at(IDENT_SZ);
 
         Ok(Elf64Hdr {
             ident,
-            e_type: convert::<u16, 2>(data[16..=17].try_into().unwrap(), ident.data)
-                .try_into()
-                .unwrap(),
-            machine: convert(data[18..=19].try_into().unwrap(), ident.data),
-            version: convert(data[20..=23].try_into().unwrap(), ident.data),
-            entry: convert(data[24..=31].try_into().unwrap(), ident.data),
-            ph_off: convert(data[32..=39].try_into().unwrap(), ident.data),
-            sh_off: convert(data[40..=47].try_into().unwrap(), ident.data),
-            flags: convert(data[48..=51].try_into().unwrap(), ident.data),
-            eh_size: convert(data[52..=53].try_into().unwrap(), ident.data),
-            ph_ent_size: convert(data[54..=55].try_into().unwrap(), ident.data),
-            ph_num: convert(data[56..=57].try_into().unwrap(), ident.data),
-            sh_ent_size: convert(data[58..=59].try_into().unwrap(), ident.data),
-            sh_num: convert(data[60..=61].try_into().unwrap(), ident.data),
-            sh_str_ndx: convert(data[62..=63].try_into().unwrap(), ident.data),
+            e_type: ElfHType::try_from(fields.u16()?)
+                .ok()
+                .filter(|e_type| *e_type != ElfHType::_None)
+                .ok_or(ParseError::UnsupportedFileType)?,
+            machine: fields.u16()?,
+            version: ObjectVersion::from(fields.u32()?),
+            entry: fields.addr()?,
+            ph_off: fields.uword()?,
+            sh_off: fields.uword()?,
+            flags: fields.u32()?,
+            eh_size: fields.u16()?,
+            ph_ent_size: fields.u16()?,
+            ph_num: fields.u16()?,
+            sh_ent_size: fields.u16()?,
+            sh_num: fields.u16()?,
+            sh_str_ndx: fields.u16()?,
         })
     }
 }
+
+pub fn machine_name(machine: u16) -> String {
+    match machine {
+        0 => "None".to_string(),
+        EM_386 => "Intel 80386".to_string(),
+        8 => "MIPS R3000".to_string(),
+        EM_ARM => "ARM".to_string(),
+        EM_X86_64 => "Advanced Micro Devices X86-64".to_string(),
+        EM_AARCH64 => "AArch64".to_string(),
+        EM_RISCV => "RISC-V".to_string(),
+        m => format!("<unknown>: {:#x}", m),
+    }
+}
+
+pub fn os_abi_name(os_abi: u8) -> String {
+    match os_abi {
+        0 => "UNIX - System V".to_string(),
+        3 => "UNIX - GNU".to_string(),
+        9 => "UNIX - FreeBSD".to_string(),
+        255 => "Standalone App".to_string(),
+        o => format!("<unknown: {:#x}>", o),
+    }
+}
+
+impl fmt::Display for ElfHClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ElfHClass::_ElfClassIn => write!(f, "none"),
+            ElfHClass::ElfClass32 => write!(f, "ELF32"),
+            ElfHClass::ElfClass64 => write!(f, "ELF64"),
+        }
+    }
+}
+
+impl fmt::Display for ElfHData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ElfHData::ElfData2Lsb => write!(f, "2's complement, little endian"),
+            ElfHData::ElfData2Msb => write!(f, "2's complement, big endian"),
+        }
+    }
+}
+
+impl fmt::Display for ElfHVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ElfHVersion::ElfEvNone => write!(f, "0"),
+            ElfHVersion::ElfEvCurr => write!(f, "1 (current)"),
+        }
+    }
+}
+
+impl fmt::Display for ElfHType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ElfHType::_None => write!(f, "NONE (None)"),
+            ElfHType::Relocatable => write!(f, "REL (Relocatable file)"),
+            ElfHType::Executable => write!(f, "EXEC (Executable file)"),
+            ElfHType::SharedObject => write!(f, "DYN (Shared object file)"),
+            ElfHType::Core => write!(f, "CORE (Core file)"),
+        }
+    }
+}
+
+impl Elf64Hdr {
+    /// Renders `flags` along with the meaning of the machine-specific bits
+    pub fn flags_description(&self) -> String {
+        flags_description(self.machine, self.flags)
+    }
+}
+
+/// Renders the `e_flags` value `flags` of a file for `machine`, along with the
+/// meaning of the machine-specific bits. Flags of 0 are left undescribed, as readelf
+/// does, even where they'd mean something such as the soft-float ABI on RISC-V
+pub fn flags_description(machine: u16, flags: u32) -> String {
+    let mut out = format!("{:#x}", flags);
+
+    if machine == EM_RISCV && flags != 0 {
+        if flags & EF_RISCV_RVC != 0 {
+            out.push_str(", RVC");
+        }
+
+        out.push_str(match flags & EF_RISCV_FLOAT_ABI {
+            0x0 => ", soft-float ABI",
+            0x2 => ", single-float ABI",
+            0x4 => ", double-float ABI",
+            _ => ", quad-float ABI",
+        });
+
+        if flags & EF_RISCV_RVE != 0 {
+            out.push_str(", RVE");
+        }
+
+        if flags & EF_RISCV_TSO != 0 {
+            out.push_str(", TSO");
+        }
+    }
+
+    out
+}
+
+impl fmt::Display for Elf64Hdr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ident = &self.ident;
+
+        writeln!(f, "ELF Header:")?;
+        write!(
+            f,
+            "  Magic:   {:02x} {:02x} {:02x} {:02x} {:02x} {:02x} {:02x} {:02x} {:02x}",
+            ident.mag[0],
+            ident.mag[1],
+            ident.mag[2],
+            ident.mag[3],
+            ident.class as u8,
+            ident.data as u8,
+            ident.version as u8,
+            ident.os_abi,
+            ident.abi_version
+        )?;
+        for b in ident.padding() {
+            write!(f, " {:02x}", b)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "  {:<35}{}", "Class:", ident.class)?;
+        writeln!(f, "  {:<35}{}", "Data:", ident.data)?;
+        writeln!(f, "  {:<35}{}", "Version:", ident.version)?;
+        writeln!(f, "  {:<35}{}", "OS/ABI:", os_abi_name(ident.os_abi))?;
+        writeln!(f, "  {:<35}{}", "ABI Version:", ident.abi_version)?;
+        writeln!(f, "  {:<35}{}", "Type:", self.e_type)?;
+        writeln!(f, "  {:<35}{}", "Machine:", machine_name(self.machine))?;
+        writeln!(f, "  {:<35}{:#x}", "Version:", self.version.value())?;
+        writeln!(f, "  {:<35}{:#x}", "Entry point address:", self.entry)?;
+        writeln!(
+            f,
+            "  {:<35}{} (bytes into file)",
+            "Start of program headers:", self.ph_off
+        )?;
+        writeln!(
+            f,
+            "  {:<35}{} (bytes into file)",
+            "Start of section headers:", self.sh_off
+        )?;
+        writeln!(f, "  {:<35}{}", "Flags:", self.flags_description())?;
+        writeln!(
+            f,
+            "  {:<35}{} (bytes)",
+            "Size of this header:", self.eh_size
+        )?;
+        writeln!(
+            f,
+            "  {:<35}{} (bytes)",
+            "Size of program headers:", self.ph_ent_size
+        )?;
+        writeln!(f, "  {:<35}{}", "Number of program headers:", self.ph_num)?;
+        writeln!(
+            f,
+            "  {:<35}{} (bytes)",
+            "Size of section headers:", self.sh_ent_size
+        )?;
+        writeln!(f, "  {:<35}{}", "Number of section headers:", self.sh_num)?;
+        write!(
+            f,
+            "  {:<35}{}",
+            "Section header string table index:", self.sh_str_ndx
+        )
+    }
+}
+
+/// Bytes of a header table of `count` entries of `ent_size` bytes at `off`, None when
+/// it has no entry. Fails when its end overflows or is past `file_len`
+fn table_range(
+    off: u64,
+    count: u64,
+    ent_size: u16,
+    file_len: u64,
+) -> Result<Option<Range<u64>>, ()> {
+    if count == 0 {
+        return Ok(None);
+    }
+
+    let end = (ent_size as u64)
+        .checked_mul(count)
+        .and_then(|size| off.checked_add(size))
+        .filter(|end| *end <= file_len)
+        .ok_or(())?;
+
+    Ok(Some(off..end))
+}
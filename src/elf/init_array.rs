@@ -0,0 +1,29 @@
+use crate::bytes::{Address, ParseContext};
+use crate::parser::ParseError;
+use log::debug;
+
+/// Decodes an array of function pointers as held by `SHT_INIT_ARRAY`,
+/// `SHT_FINI_ARRAY` and `SHT_PREINIT_ARRAY` sections, padding entries included
+pub fn parse(data: &[u8], ctx: &ParseContext) -> Result<Vec<Address>, ParseError> {
+    let word = ctx.word_size();
+    if !data.len().is_multiple_of(word) {
+        return Err(ParseError::InvalidLength);
+    }
+
+    let entries = (0..data.len())
+        .step_by(word)
+        .map(|off| ctx.read_uword(data, off).map(Address))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    debug!("parsed {} function pointers", entries.len());
+
+    Ok(entries)
+}
+
+/// Whether the entry is padding rather than a function, as linkers fill unused
+/// entries with 0 or with all bits set
+pub fn is_padding(entry: Address, ctx: &ParseContext) -> bool {
+    let all_ones = u64::MAX >> (64 - 8 * ctx.word_size());
+
+    entry.0 == 0 || entry.0 == all_ones
+}
@@ -1,3 +1,11 @@
+pub mod chdr;
+pub mod dynamic;
+pub mod eh_frame;
 pub mod ehdr;
+pub mod init_array;
+pub mod note;
 pub mod phdr;
+pub mod reloc;
 pub mod shdr;
+pub mod sym;
+pub mod version;
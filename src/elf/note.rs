@@ -0,0 +1,475 @@
+use crate::bytes::{str_from_u8, ParseContext};
+use crate::elf::ehdr::{ElfHClass, EM_AARCH64, EM_RISCV, EM_X86_64};
+use crate::parser::ParseError;
+use log::debug;
+use std::fmt;
+
+/// Size in bytes of the fixed part of a note entry: `namesz`, `descsz` and `type`
+pub const NOTE_HDR_SZ: usize = 12;
+
+/// GNU: ABI information, the descriptor holds the target OS and minimum kernel version
+pub const NT_GNU_ABI_TAG: u32 = 1;
+/// GNU: unique build ID bitstring generated by the linker
+pub const NT_GNU_BUILD_ID: u32 = 3;
+/// GNU: program properties, the descriptor holds an array of properties
+pub const NT_GNU_PROPERTY_TYPE_0: u32 = 5;
+
+/// Android: target API level of the file and the NDK it was built with
+pub const NT_ANDROID_TYPE_IDENT: u32 = 1;
+
+/// Core: process status, the registers of a thread of the dumped process
+pub const NT_PRSTATUS: u32 = 1;
+/// Core: process information, such as its name and arguments
+pub const NT_PRPSINFO: u32 = 3;
+/// Core: auxiliary vector the kernel handed to the process
+pub const NT_AUXV: u32 = 6;
+
+/// The minimum stack size required by the program
+pub const GNU_PROPERTY_STACK_SIZE: u32 = 1;
+/// The program doesn't rely on copy relocations of protected symbols
+pub const GNU_PROPERTY_NO_COPY_ON_PROTECTED: u32 = 2;
+/// AArch64: bitwise AND of the features supported by every input object
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_AND: u32 = 0xc0000000;
+/// RISC-V: bitwise AND of the features supported by every input object
+pub const GNU_PROPERTY_RISCV_FEATURE_1_AND: u32 = 0xc0000000;
+/// x86: bitwise AND of the features supported by every input object
+pub const GNU_PROPERTY_X86_FEATURE_1_AND: u32 = 0xc0000002;
+
+pub const GNU_PROPERTY_X86_FEATURE_1_IBT: u32 = 0x1;
+pub const GNU_PROPERTY_X86_FEATURE_1_SHSTK: u32 = 0x2;
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_BTI: u32 = 0x1;
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_PAC: u32 = 0x2;
+/// RISC-V: forward-edge CFI through unlabeled landing pads (Zicfilp)
+pub const GNU_PROPERTY_RISCV_FEATURE_1_CFI_LP_UNLABELED: u32 = 0x1;
+/// RISC-V: backward-edge CFI through shadow stacks (Zicfiss)
+pub const GNU_PROPERTY_RISCV_FEATURE_1_CFI_SS: u32 = 0x2;
+/// RISC-V: forward-edge CFI through function signature labeled landing pads (Zicfilp)
+pub const GNU_PROPERTY_RISCV_FEATURE_1_CFI_LP_FUNC_SIG: u32 = 0x4;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Elf64Note {
+    /// Owner of the note, e.g. "GNU"
+    pub name: String,
+    /// Type of the note, whose interpretation is dependant on `name`
+    pub n_type: u32,
+    /// Descriptor of the note, its contents are dependant on `name` and `n_type`
+    pub desc: Vec<u8>,
+}
+
+impl Elf64Note {
+    /// Parses every note held by a note section or segment. Both the name and the
+    /// descriptor are padded to `align` bytes, which is 4 on most files, but can be 8 for
+    /// some 64-bit notes
+    pub fn parse(data: &[u8], ctx: &ParseContext, align: usize) -> Result<Vec<Self>, ParseError> {
+        let align = align.max(4);
+        let mut reader = ctx.reader(data);
+        let mut notes = vec![];
+
+        while reader.remaining() >= NOTE_HDR_SZ {
+            let namesz = reader.u32()?;
+            let descsz = reader.u32()?;
+            let n_type = reader.u32()?;
+            let name = reader.bytes(namesz as usize)?;
+            reader.align(align)?;
+            let desc = reader.bytes(descsz as usize)?;
+
+            notes.push(Elf64Note {
+                name: str_from_u8(name)?,
+                n_type,
+                desc: desc.to_vec(),
+            });
+
+            // the padding of the last note may be left out
+            if reader.align(align).is_err() {
+                break;
+            }
+        }
+
+        debug!("parsed {} notes from {} bytes", notes.len(), data.len());
+
+        Ok(notes)
+    }
+}
+
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GnuAbiOs {
+    Linux,
+    Hurd,
+    Solaris,
+    FreeBsd,
+    Unknown(u32),
+}
+
+impl From<u32> for GnuAbiOs {
+    fn from(value: u32) -> Self {
+        match value {
+            0 => GnuAbiOs::Linux,
+            1 => GnuAbiOs::Hurd,
+            2 => GnuAbiOs::Solaris,
+            3 => GnuAbiOs::FreeBsd,
+            v => GnuAbiOs::Unknown(v),
+        }
+    }
+}
+
+impl fmt::Display for GnuAbiOs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GnuAbiOs::Linux => write!(f, "GNU/Linux"),
+            GnuAbiOs::Hurd => write!(f, "GNU/Hurd"),
+            GnuAbiOs::Solaris => write!(f, "Solaris"),
+            GnuAbiOs::FreeBsd => write!(f, "FreeBSD"),
+            GnuAbiOs::Unknown(v) => write!(f, "Unknown OS ({})", v),
+        }
+    }
+}
+
+/// Contents of a `NT_GNU_ABI_TAG` note
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct AbiTag {
+    /// Operating system the file targets
+    pub os: GnuAbiOs,
+    /// Minimum kernel version as (major, minor, patch)
+    pub kernel_version: (u32, u32, u32),
+}
+
+impl AbiTag {
+    pub fn parse(note: &Elf64Note, ctx: &ParseContext) -> Result<Self, ParseError> {
+        let mut desc = ctx.reader(&note.desc);
+
+        Ok(AbiTag {
+            os: desc.u32()?.into(),
+            kernel_version: (desc.u32()?, desc.u32()?, desc.u32()?),
+        })
+    }
+}
+
+/// Size of each of the NUL-padded NDK strings following the API level of a
+/// `NT_ANDROID_TYPE_IDENT` note
+const ANDROID_NDK_STRING_SZ: usize = 64;
+
+/// Contents of a `NT_ANDROID_TYPE_IDENT` note, as found in `.note.android.ident`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AndroidIdent {
+    /// Minimum API level of the Android releases the file runs on
+    pub api_level: u32,
+    /// Release of the NDK the file was built with, e.g. "r27", only written by NDK
+    /// r14 and later
+    pub ndk_version: Option<String>,
+    /// Build number of that NDK release
+    pub ndk_build_number: Option<String>,
+}
+
+impl AndroidIdent {
+    pub fn parse(note: &Elf64Note, ctx: &ParseContext) -> Result<Self, ParseError> {
+        let desc = &note.desc;
+        let ndk_string = |idx: usize| -> Result<Option<String>, ParseError> {
+            let start = 4 + idx * ANDROID_NDK_STRING_SZ;
+            match desc.get(start..start + ANDROID_NDK_STRING_SZ) {
+                Some(bytes) => Ok(Some(str_from_u8(bytes)?).filter(|s| !s.is_empty())),
+                None => Ok(None),
+            }
+        };
+
+        Ok(AndroidIdent {
+            api_level: ctx.read_u32(desc, 0)?,
+            ndk_version: ndk_string(0)?,
+            ndk_build_number: ndk_string(1)?,
+        })
+    }
+}
+
+/// A single program property of a `NT_GNU_PROPERTY_TYPE_0` note
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GnuProperty {
+    /// Type of the property, processor-specific values depend on the machine
+    pub pr_type: u32,
+    /// Raw property data, without its trailing padding
+    pub data: Vec<u8>,
+}
+
+/// Meaning of a `GnuProperty` for the types this crate knows about
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GnuPropertyKind {
+    StackSize(u64),
+    NoCopyOnProtected,
+    X86Feature1And {
+        ibt: bool,
+        shstk: bool,
+        raw: u32,
+    },
+    Aarch64Feature1And {
+        bti: bool,
+        pac: bool,
+        raw: u32,
+    },
+    RiscvFeature1And {
+        /// Zicfilp with unlabeled landing pads
+        lp_unlabeled: bool,
+        /// Zicfiss shadow stack
+        shadow_stack: bool,
+        /// Zicfilp with function signature labeled landing pads
+        lp_func_sig: bool,
+        raw: u32,
+    },
+    Unknown,
+}
+
+impl GnuProperty {
+    /// Parses the property array held by the descriptor of a `NT_GNU_PROPERTY_TYPE_0`
+    /// note. Each entry is a (pr_type, pr_datasz, data) triplet, whose data is padded to
+    /// 8 bytes on 64-bit files and to 4 bytes on 32-bit ones
+    pub fn parse(desc: &[u8], ctx: &ParseContext) -> Result<Vec<Self>, ParseError> {
+        let align = if ctx.class == ElfHClass::ElfClass64 {
+            8
+        } else {
+            4
+        };
+        let mut reader = ctx.reader(desc);
+        let mut properties = vec![];
+
+        while reader.remaining() >= 8 {
+            let pr_type = reader.u32()?;
+            let pr_datasz = reader.u32()?;
+            let data = reader.bytes(pr_datasz as usize)?;
+
+            properties.push(GnuProperty {
+                pr_type,
+                data: data.to_vec(),
+            });
+
+            if reader.align(align).is_err() {
+                break;
+            }
+        }
+
+        Ok(properties)
+    }
+
+    /// Decodes the property for a file of the given machine, as the meaning of
+    /// processor-specific property types depends on it
+    pub fn kind(&self, machine: u16, ctx: &ParseContext) -> GnuPropertyKind {
+        let word = || ctx.read_u32(&self.data, 0).ok();
+
+        match (self.pr_type, machine) {
+            (GNU_PROPERTY_STACK_SIZE, _) => match ctx.read_uword(&self.data, 0) {
+                Ok(size) => GnuPropertyKind::StackSize(size),
+                Err(_) => GnuPropertyKind::Unknown,
+            },
+            (GNU_PROPERTY_NO_COPY_ON_PROTECTED, _) => GnuPropertyKind::NoCopyOnProtected,
+            (GNU_PROPERTY_X86_FEATURE_1_AND, EM_X86_64) => match word() {
+                Some(raw) => GnuPropertyKind::X86Feature1And {
+                    ibt: raw & GNU_PROPERTY_X86_FEATURE_1_IBT != 0,
+                    shstk: raw & GNU_PROPERTY_X86_FEATURE_1_SHSTK != 0,
+                    raw,
+                },
+                None => GnuPropertyKind::Unknown,
+            },
+            (GNU_PROPERTY_AARCH64_FEATURE_1_AND, EM_AARCH64) => match word() {
+                Some(raw) => GnuPropertyKind::Aarch64Feature1And {
+                    bti: raw & GNU_PROPERTY_AARCH64_FEATURE_1_BTI != 0,
+                    pac: raw & GNU_PROPERTY_AARCH64_FEATURE_1_PAC != 0,
+                    raw,
+                },
+                None => GnuPropertyKind::Unknown,
+            },
+            (GNU_PROPERTY_RISCV_FEATURE_1_AND, EM_RISCV) => match word() {
+                Some(raw) => GnuPropertyKind::RiscvFeature1And {
+                    lp_unlabeled: raw & GNU_PROPERTY_RISCV_FEATURE_1_CFI_LP_UNLABELED != 0,
+                    shadow_stack: raw & GNU_PROPERTY_RISCV_FEATURE_1_CFI_SS != 0,
+                    lp_func_sig: raw & GNU_PROPERTY_RISCV_FEATURE_1_CFI_LP_FUNC_SIG != 0,
+                    raw,
+                },
+                None => GnuPropertyKind::Unknown,
+            },
+            _ => GnuPropertyKind::Unknown,
+        }
+    }
+}
+
+/// Key of an auxiliary vector entry, as the `AT_*` constants of the Linux ABI
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AuxKey {
+    /// `AT_NULL`, ending the vector
+    Null,
+    Ignore,
+    /// File descriptor of the program, when the interpreter was handed one
+    ExecFd,
+    /// Address of the program headers of the program
+    Phdr,
+    /// Size of a program header entry
+    Phent,
+    /// Number of program headers
+    Phnum,
+    /// Size of a page
+    Pagesz,
+    /// Address the interpreter is loaded at
+    Base,
+    Flags,
+    /// Entry point of the program
+    Entry,
+    NotElf,
+    Uid,
+    Euid,
+    Gid,
+    Egid,
+    /// Address of the string naming the platform
+    Platform,
+    /// Hardware capabilities of the processor
+    Hwcap,
+    /// Frequency of `times`
+    Clktck,
+    /// Whether the program runs with elevated privileges
+    Secure,
+    /// Address of the string naming the real platform
+    BasePlatform,
+    /// Address of 16 random bytes
+    Random,
+    Hwcap2,
+    /// Address of the file name the program was executed as
+    Execfn,
+    /// Address of the vDSO
+    SysinfoEhdr,
+    /// Minimal stack size for signal delivery
+    MinSigStkSz,
+    Unknown(u64),
+}
+
+impl From<u64> for AuxKey {
+    fn from(value: u64) -> Self {
+        match value {
+            0 => AuxKey::Null,
+            1 => AuxKey::Ignore,
+            2 => AuxKey::ExecFd,
+            3 => AuxKey::Phdr,
+            4 => AuxKey::Phent,
+            5 => AuxKey::Phnum,
+            6 => AuxKey::Pagesz,
+            7 => AuxKey::Base,
+            8 => AuxKey::Flags,
+            9 => AuxKey::Entry,
+            10 => AuxKey::NotElf,
+            11 => AuxKey::Uid,
+            12 => AuxKey::Euid,
+            13 => AuxKey::Gid,
+            14 => AuxKey::Egid,
+            15 => AuxKey::Platform,
+            16 => AuxKey::Hwcap,
+            17 => AuxKey::Clktck,
+            23 => AuxKey::Secure,
+            24 => AuxKey::BasePlatform,
+            25 => AuxKey::Random,
+            26 => AuxKey::Hwcap2,
+            31 => AuxKey::Execfn,
+            33 => AuxKey::SysinfoEhdr,
+            51 => AuxKey::MinSigStkSz,
+            v => AuxKey::Unknown(v),
+        }
+    }
+}
+
+impl From<AuxKey> for u64 {
+    fn from(key: AuxKey) -> Self {
+        match key {
+            AuxKey::Null => 0,
+            AuxKey::Ignore => 1,
+            AuxKey::ExecFd => 2,
+            AuxKey::Phdr => 3,
+            AuxKey::Phent => 4,
+            AuxKey::Phnum => 5,
+            AuxKey::Pagesz => 6,
+            AuxKey::Base => 7,
+            AuxKey::Flags => 8,
+            AuxKey::Entry => 9,
+            AuxKey::NotElf => 10,
+            AuxKey::Uid => 11,
+            AuxKey::Euid => 12,
+            AuxKey::Gid => 13,
+            AuxKey::Egid => 14,
+            AuxKey::Platform => 15,
+            AuxKey::Hwcap => 16,
+            AuxKey::Clktck => 17,
+            AuxKey::Secure => 23,
+            AuxKey::BasePlatform => 24,
+            AuxKey::Random => 25,
+            AuxKey::Hwcap2 => 26,
+            AuxKey::Execfn => 31,
+            AuxKey::SysinfoEhdr => 33,
+            AuxKey::MinSigStkSz => 51,
+            AuxKey::Unknown(v) => v,
+        }
+    }
+}
+
+/// An entry of the auxiliary vector held by a `NT_AUXV` note
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct AuxvEntry {
+    pub key: u64,
+    pub value: u64,
+}
+
+impl AuxvEntry {
+    pub fn kind(&self) -> AuxKey {
+        AuxKey::from(self.key)
+    }
+
+    /// Parses the descriptor of a `NT_AUXV` note, pairs of words of the class of the
+    /// file, up to the `AT_NULL` entry, which isn't returned. A vector missing it
+    /// ends with the descriptor
+    pub fn parse(note: &Elf64Note, ctx: &ParseContext) -> Result<Vec<Self>, ParseError> {
+        let word = ctx.word_size();
+        let mut entries = vec![];
+
+        for pair in note.desc.chunks(2 * word) {
+            let key = ctx.read_uword(pair, 0)?;
+            if key == u64::from(AuxKey::Null) {
+                break;
+            }
+
+            entries.push(AuxvEntry {
+                key,
+                value: ctx.read_uword(pair, word)?,
+            });
+        }
+
+        Ok(entries)
+    }
+}
+
+impl fmt::Display for AuxKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuxKey::Unknown(v) => write!(f, "AT_{:#x}", v),
+            key => f.pad(match key {
+                AuxKey::Null => "AT_NULL",
+                AuxKey::Ignore => "AT_IGNORE",
+                AuxKey::ExecFd => "AT_EXECFD",
+                AuxKey::Phdr => "AT_PHDR",
+                AuxKey::Phent => "AT_PHENT",
+                AuxKey::Phnum => "AT_PHNUM",
+                AuxKey::Pagesz => "AT_PAGESZ",
+                AuxKey::Base => "AT_BASE",
+                AuxKey::Flags => "AT_FLAGS",
+                AuxKey::Entry => "AT_ENTRY",
+                AuxKey::NotElf => "AT_NOTELF",
+                AuxKey::Uid => "AT_UID",
+                AuxKey::Euid => "AT_EUID",
+                AuxKey::Gid => "AT_GID",
+                AuxKey::Egid => "AT_EGID",
+                AuxKey::Platform => "AT_PLATFORM",
+                AuxKey::Hwcap => "AT_HWCAP",
+                AuxKey::Clktck => "AT_CLKTCK",
+                AuxKey::Secure => "AT_SECURE",
+                AuxKey::BasePlatform => "AT_BASE_PLATFORM",
+                AuxKey::Random => "AT_RANDOM",
+                AuxKey::Hwcap2 => "AT_HWCAP2",
+                AuxKey::Execfn => "AT_EXECFN",
+                AuxKey::SysinfoEhdr => "AT_SYSINFO_EHDR",
+                AuxKey::MinSigStkSz => "AT_MINSIGSTKSZ",
+                AuxKey::Unknown(_) => unreachable!(),
+            }),
+        }
+    }
+}
@@ -1,81 +1,241 @@
-use crate::bytes::{convert, Address};
-use crate::elf::ehdr::Elf64Hdr;
+//! Program headers and the contents of the segments they describe. The contents read
+//! while parsing are held by `Elf64PHdr::section`, and carry what is needed to use
+//! them apart from their header: the file image of a loadable segment comes along
+//! with its memory size and the address, permissions and alignment it's mapped with
+
+use crate::bytes::{Address, ParseContext};
+use crate::display::TableRow;
+use crate::elf::dynamic::{DynFlags, DynFlags1};
+use crate::elf::ehdr::{Elf64Hdr, ElfHClass};
 use crate::elf::phdr::PTypeData::Ignorable;
-use crate::parser::ParseError;
+use crate::elf::shdr::Elf64SHdr;
+use crate::layout;
+use crate::mapped;
+use crate::options::ParseOptions;
+use crate::parser::{ParseError, ParseWarning};
+use log::{debug, log_enabled, trace, warn, Level};
+use std::fmt;
+use std::ops::Range;
+
+/// Value of `ph_num` indicating that the real number of program headers doesn't fit in
+/// the ELF header, and is instead held by the `info` field of the initial section header
+pub const PN_XNUM: u16 = 0xffff;
 
 pub const PF_EXEC: u32 = 0x1;
 pub const PF_WRITE: u32 = 0x2;
 pub const PF_READ: u32 = 0x4;
 
+pub const PT_NULL: u32 = 0;
+pub const PT_LOAD: u32 = 1;
+pub const PT_DYNAMIC: u32 = 2;
+pub const PT_INTERP: u32 = 3;
+pub const PT_NOTE: u32 = 4;
+pub const PT_SHLIB: u32 = 5;
+pub const PT_PHDR: u32 = 6;
+pub const PT_TLS: u32 = 7;
+pub const PT_LOOS: u32 = 0x60000000;
+pub const PT_HIOS: u32 = 0x6fffffff;
+pub const PT_LOPROC: u32 = 0x70000000;
+pub const PT_HIPROC: u32 = 0x7fffffff;
+pub const PT_GNU_EH_FRAME: u32 = 0x6474e550;
+pub const PT_GNU_STACK: u32 = 0x6474e551;
+pub const PT_GNU_RELRO: u32 = 0x6474e552;
+pub const PT_GNU_PROPERTY: u32 = 0x6474e553;
+pub const PT_RISCV_ATTRIBUTES: u32 = 0x70000003;
+
+pub const DT_NULL: i64 = 0;
+pub const DT_NEEDED: i64 = 1;
+pub const DT_PLTRELSZ: i64 = 2;
+pub const DT_PLTGOT: i64 = 3;
+pub const DT_HASH: i64 = 4;
+pub const DT_STRTAB: i64 = 5;
+pub const DT_SYMTAB: i64 = 6;
+pub const DT_RELA: i64 = 7;
+pub const DT_RELASZ: i64 = 8;
+pub const DT_RELAENT: i64 = 9;
+pub const DT_STRSZ: i64 = 10;
+pub const DT_SYMENT: i64 = 11;
+pub const DT_INIT: i64 = 12;
+pub const DT_FINI: i64 = 13;
+pub const DT_SONAME: i64 = 14;
+pub const DT_RPATH: i64 = 15;
+pub const DT_SYMBOLIC: i64 = 16;
+pub const DT_REL: i64 = 17;
+pub const DT_RELSZ: i64 = 18;
+pub const DT_RELENT: i64 = 19;
+pub const DT_PLTREL: i64 = 20;
+pub const DT_DEBUG: i64 = 21;
+pub const DT_TEXTREL: i64 = 22;
+pub const DT_JMPREL: i64 = 23;
+pub const DT_BIND_NOW: i64 = 24;
+pub const DT_INIT_ARRAY: i64 = 25;
+pub const DT_FINI_ARRAY: i64 = 26;
+pub const DT_INIT_ARRAYSZ: i64 = 27;
+pub const DT_FINI_ARRAYSZ: i64 = 28;
+pub const DT_RUNPATH: i64 = 29;
+pub const DT_FLAGS: i64 = 30;
 pub const DT_ENCODING: i64 = 32;
+pub const DT_PREINIT_ARRAY: i64 = 32;
+pub const DT_PREINIT_ARRAYSZ: i64 = 33;
+pub const DT_LOOS: i64 = 0x6000000d;
+pub const DT_GNU_HASH: i64 = 0x6ffffef5;
+pub const DT_VERSYM: i64 = 0x6ffffff0;
+pub const DT_RELACOUNT: i64 = 0x6ffffff9;
+pub const DT_RELCOUNT: i64 = 0x6ffffffa;
+pub const DT_FLAGS_1: i64 = 0x6ffffffb;
+pub const DT_VERDEF: i64 = 0x6ffffffc;
+pub const DT_VERDEFNUM: i64 = 0x6ffffffd;
+pub const DT_VERNEED: i64 = 0x6ffffffe;
+pub const DT_VERNEEDNUM: i64 = 0x6fffffff;
 pub const DT_HIOS: i64 = 0x6ffff000;
 pub const DT_LOPROC: i64 = 0x70000000;
+/// RISC-V: some symbols use the variant calling convention, marked `STO_RISCV_VARIANT_CC`
+pub const DT_RISCV_VARIANT_CC: i64 = 0x70000001;
 
-#[repr(u32)]
-#[derive(Debug, PartialEq)]
+/// Segment types, see the `PT_*` constants
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum PType {
-    PtNull = 0,
-    PtLoad = 1,
-    PtDynamic = 2,
-    PtInterp = 3,
-    PtNote = 4,
-    PtShlib = 5,
-    PtPhdr = 6,
-    PtTls = 7,
-    PtLoos = 8,
-    PtHios = 9,
-    PtLoProc = 10,
-    PtHiProc = 11,
-}
-
-#[derive(Debug, PartialEq)]
+    PtNull,
+    PtLoad,
+    PtDynamic,
+    PtInterp,
+    PtNote,
+    PtShlib,
+    PtPhdr,
+    PtTls,
+    PtLoos,
+    PtHios,
+    PtLoProc,
+    PtHiProc,
+    /// GNU: location of the `.eh_frame_hdr` unwinding lookup table
+    PtGnuEhFrame,
+    /// GNU: the flags of this segment indicate whether the stack must be executable
+    PtGnuStack,
+    /// GNU: range that can be made read-only after relocations have been applied
+    PtGnuRelro,
+    /// GNU: location of the `.note.gnu.property` program properties notes
+    PtGnuProperty,
+    /// RISC-V: location of the `.riscv.attributes` section
+    PtRiscvAttributes,
+    /// Any other value, in the OS or processor-specific ranges or otherwise, kept as
+    /// read so it's written back unchanged
+    Other(u32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum PTypeData {
-    PtLoadData(Vec<u8>),
+    /// File image of a loadable segment, and the mapping it's loaded with. The memory
+    /// image is the file image followed by zeros up to `memsz`, which aren't allocated
+    /// while parsing, consumers such as `ElfParser::memory_image` produce them when they
+    /// need them
+    PtLoadData {
+        bytes: Vec<u8>,
+        memsz: u64,
+        vaddr: Address,
+        flags: u32,
+        align: u64,
+    },
+    /// Loadable segment with no file image, such as a dedicated `.bss` segment, whose
+    /// `memsz` bytes are zeros. Nothing is allocated for it while parsing, consumers
+    /// such as `ElfParser::memory_image` produce the zeros when they need them
+    ZeroFill {
+        memsz: u64,
+        vaddr: Address,
+        flags: u32,
+        align: u64,
+    },
     PtDynamicData(Vec<ELF64Dyn>),
+    /// The segment data wasn't read, as requested through `ParseOptions` or because
+    /// the program header table overlaps the ELF header
+    Skipped,
     Ignorable,
 }
 
 impl PTypeData {
+    /// Bytes of the file image of the segment described by `ph`, checked to lie within
+    /// `data` before anything is allocated for them
+    fn file_image<'d>(
+        ph: &Elf64PHdr,
+        ctx: &ParseContext,
+        data: &'d [u8],
+    ) -> Result<&'d [u8], ParseError> {
+        let out_of_bounds = ParseError::SegmentOutOfBounds {
+            offset: ph.offset,
+            filesz: ph.filesz,
+        };
+        let (Ok(offset), Ok(filesz)) = (usize::try_from(ph.offset), usize::try_from(ph.filesz))
+        else {
+            return Err(out_of_bounds);
+        };
+
+        ctx.reader(data)
+            .at(offset)
+            .bytes(filesz)
+            .map_err(|_| out_of_bounds)
+    }
+
+    /// Reads the contents of the segment described by `ph`, whose own `section` is
+    /// ignored
     pub fn parse_section(
-        p_type: &PType,
-        headers: &Elf64Hdr,
-        filesz: u64,
-        memsz: u64,
-        offset: u64,
+        ph: &Elf64PHdr,
+        ctx: &ParseContext,
         data: &[u8],
+        options: &ParseOptions,
     ) -> Result<Self, ParseError> {
-        match p_type {
-            PType::PtLoad => {
-                if filesz > memsz {
-                    panic!();
-                }
+        let (filesz, memsz, offset) = (ph.filesz, ph.memsz, ph.offset);
 
-                // initialize the data vector with len `memsz`, as that's the total length that
-                // it should occupy on the process memory
-                let mut bytes = vec![0u8; memsz as usize];
-
-                let section = &data[offset as usize..(offset + filesz) as usize];
-                bytes[0..filesz as usize].copy_from_slice(section);
+        match ph.p_type {
+            PType::PtLoad if !options.load_segment_data => Ok(PTypeData::Skipped),
+            PType::PtLoad if filesz == 0 => Ok(PTypeData::ZeroFill {
+                memsz,
+                vaddr: ph.vaddr,
+                flags: ph.flags,
+                align: ph.align,
+            }),
+            // the file image wouldn't fit in the memory image it's copied into
+            PType::PtLoad if filesz > memsz => {
+                Err(ParseError::SegmentFileSizeExceedsMemSize { filesz, memsz })
+            }
+            PType::PtLoad => {
+                let section = PTypeData::file_image(ph, ctx, data)?;
+                options.check_alloc(filesz)?;
 
-                Ok(PTypeData::PtLoadData(bytes))
+                Ok(PTypeData::PtLoadData {
+                    bytes: section.to_vec(),
+                    memsz,
+                    vaddr: ph.vaddr,
+                    flags: ph.flags,
+                    align: ph.align,
+                })
             }
             PType::PtDynamic => {
-                let section = &data[offset as usize..(offset + filesz) as usize];
+                let section = PTypeData::file_image(ph, ctx, data)?;
+                let word = ctx.word_size();
+
+                debug!(
+                    "parsed dynamic section of {} entries from {:#x}..{:#x}",
+                    filesz / (2 * word as u64),
+                    offset,
+                    offset.saturating_add(filesz)
+                );
+
+                options.check_entries_alloc::<ELF64Dyn>(section.len() / (2 * word))?;
 
                 Ok(PTypeData::PtDynamicData(
                     section
-                        .chunks(16)
+                        .chunks_exact(2 * word)
                         .map(|s| {
-                            let d_tag: i64 =
-                                convert(s[0..=7].try_into().unwrap(), headers.ident.data);
-                            let d_un = convert(s[8..=15].try_into().unwrap(), headers.ident.data);
+                            let mut entry = ctx.reader(s);
+                            let d_tag = entry.sword()?;
+                            let d_un = entry.uword()?;
 
-                            ELF64Dyn {
+                            Ok(ELF64Dyn {
                                 d_tag,
                                 d_un: ELF64Dyn::d_un(d_tag, d_un),
-                            }
+                            })
                         })
-                        .collect(),
+                        .collect::<Result<_, ParseError>>()?,
                 ))
             }
             _ => Ok(Ignorable),
@@ -83,57 +243,310 @@ impl PTypeData {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum DynValue {
     DVal(u64),
     DPtr(Address),
+    /// Value of a tag this crate doesn't know, in the OS or processor-specific ranges
+    /// or otherwise, which can't be told to be an address or not
+    Raw(u64),
+}
+
+/// How the `d_un` of a dynamic entry is to be read
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DynKind {
+    /// An integer, or nothing for the tags only marking a property
+    Val,
+    /// A virtual address
+    Ptr,
+}
+
+/// Name and kind of every tag this crate knows. `DT_RISCV_VARIANT_CC` only means
+/// that for RISC-V files, but no other processor this crate handles uses its value
+const DYN_TAGS: &[(i64, &str, DynKind)] = &[
+    (DT_NULL, "NULL", DynKind::Val),
+    (DT_NEEDED, "NEEDED", DynKind::Val),
+    (DT_PLTRELSZ, "PLTRELSZ", DynKind::Val),
+    (DT_PLTGOT, "PLTGOT", DynKind::Ptr),
+    (DT_HASH, "HASH", DynKind::Ptr),
+    (DT_STRTAB, "STRTAB", DynKind::Ptr),
+    (DT_SYMTAB, "SYMTAB", DynKind::Ptr),
+    (DT_RELA, "RELA", DynKind::Ptr),
+    (DT_RELASZ, "RELASZ", DynKind::Val),
+    (DT_RELAENT, "RELAENT", DynKind::Val),
+    (DT_STRSZ, "STRSZ", DynKind::Val),
+    (DT_SYMENT, "SYMENT", DynKind::Val),
+    (DT_INIT, "INIT", DynKind::Ptr),
+    (DT_FINI, "FINI", DynKind::Ptr),
+    (DT_SONAME, "SONAME", DynKind::Val),
+    (DT_RPATH, "RPATH", DynKind::Val),
+    (DT_SYMBOLIC, "SYMBOLIC", DynKind::Val),
+    (DT_REL, "REL", DynKind::Ptr),
+    (DT_RELSZ, "RELSZ", DynKind::Val),
+    (DT_RELENT, "RELENT", DynKind::Val),
+    (DT_PLTREL, "PLTREL", DynKind::Val),
+    (DT_DEBUG, "DEBUG", DynKind::Ptr),
+    (DT_TEXTREL, "TEXTREL", DynKind::Val),
+    (DT_JMPREL, "JMPREL", DynKind::Ptr),
+    (DT_BIND_NOW, "BIND_NOW", DynKind::Val),
+    (DT_INIT_ARRAY, "INIT_ARRAY", DynKind::Ptr),
+    (DT_FINI_ARRAY, "FINI_ARRAY", DynKind::Ptr),
+    (DT_INIT_ARRAYSZ, "INIT_ARRAYSZ", DynKind::Val),
+    (DT_FINI_ARRAYSZ, "FINI_ARRAYSZ", DynKind::Val),
+    (DT_RUNPATH, "RUNPATH", DynKind::Val),
+    (DT_FLAGS, "FLAGS", DynKind::Val),
+    (DT_PREINIT_ARRAY, "PREINIT_ARRAY", DynKind::Ptr),
+    (DT_PREINIT_ARRAYSZ, "PREINIT_ARRAYSZ", DynKind::Val),
+    (DT_GNU_HASH, "GNU_HASH", DynKind::Ptr),
+    (DT_VERSYM, "VERSYM", DynKind::Ptr),
+    (DT_RELACOUNT, "RELACOUNT", DynKind::Val),
+    (DT_RELCOUNT, "RELCOUNT", DynKind::Val),
+    (DT_FLAGS_1, "FLAGS_1", DynKind::Val),
+    (DT_VERDEF, "VERDEF", DynKind::Ptr),
+    (DT_VERDEFNUM, "VERDEFNUM", DynKind::Val),
+    (DT_VERNEED, "VERNEED", DynKind::Ptr),
+    (DT_VERNEEDNUM, "VERNEEDNUM", DynKind::Val),
+    (DT_RISCV_VARIANT_CC, "RISCV_VARIANT_CC", DynKind::Val),
+];
+
+/// Kind of the value of the entries tagged `d_tag`. Tags from `DT_ENCODING` up to
+/// `DT_LOOS` this crate doesn't know follow the rule of the specification, even tags
+/// holding addresses. None for the other unknown tags, which follow no rule
+pub fn dyn_tag_kind(d_tag: i64) -> Option<DynKind> {
+    match DYN_TAGS.iter().find(|(tag, _, _)| *tag == d_tag) {
+        Some((_, _, kind)) => Some(*kind),
+        None if (DT_ENCODING..DT_LOOS).contains(&d_tag) => match d_tag % 2 {
+            0 => Some(DynKind::Ptr),
+            _ => Some(DynKind::Val),
+        },
+        None => None,
+    }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ELF64Dyn {
     pub d_tag: i64,
     pub d_un: DynValue,
 }
 
 impl ELF64Dyn {
+    /// Raw value of the entry, regardless of it being a pointer or a value
+    pub fn value(&self) -> u64 {
+        match self.d_un {
+            DynValue::DVal(v) | DynValue::Raw(v) => v,
+            DynValue::DPtr(Address(p)) => p,
+        }
+    }
+
+    /// Reads `d_un` as the kind of `d_tag`, see `dyn_tag_kind`
     pub fn d_un(d_tag: i64, d_un: u64) -> DynValue {
-        let is_ptr = d_tag % 2 == 0;
-
-        match is_ptr {
-            true => DynValue::DPtr(Address(d_un)),
-            false => {
-                if d_tag < DT_ENCODING || (d_tag > DT_HIOS && d_tag < DT_LOPROC) {
-                    // TODO for now we don't care about this special case, but as the spec
-                    // says `d_tags` that fall into these values are not ruled by the same
-                    // rules as the other ones.
-                    return DynValue::DVal(d_un);
-                }
-
-                DynValue::DVal(d_un)
-            }
+        match dyn_tag_kind(d_tag) {
+            Some(DynKind::Val) => DynValue::DVal(d_un),
+            Some(DynKind::Ptr) => DynValue::DPtr(Address(d_un)),
+            None => DynValue::Raw(d_un),
         }
     }
 }
 
-impl TryFrom<u32> for PType {
-    type Error = ();
+pub fn dyn_tag_name(d_tag: i64) -> String {
+    match DYN_TAGS.iter().find(|(tag, _, _)| *tag == d_tag) {
+        Some((_, name, _)) => name.to_string(),
+        None => format!("{:#x}", d_tag),
+    }
+}
 
-    fn try_from(v: u32) -> Result<Self, Self::Error> {
-        if v < 8 {
-            return Ok(unsafe { std::mem::transmute::<u32, PType>(v) });
+impl TableRow for ELF64Dyn {
+    const HEADER: &'static str = "Tag                Type                 Name/Value";
+}
+
+impl fmt::Display for ELF64Dyn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = format!("({})", dyn_tag_name(self.d_tag));
+
+        match self.d_tag {
+            DT_FLAGS => write!(
+                f,
+                "0x{:016x} {:<20} {}",
+                self.d_tag,
+                name,
+                DynFlags(self.value())
+            ),
+            DT_FLAGS_1 => write!(
+                f,
+                "0x{:016x} {:<20} Flags: {}",
+                self.d_tag,
+                name,
+                DynFlags1(self.value())
+            ),
+            _ => match self.d_un {
+                DynValue::Raw(v) => write!(f, "0x{:016x} {:<20} raw {:#x}", self.d_tag, name, v),
+                _ => write!(f, "0x{:016x} {:<20} {:#x}", self.d_tag, name, self.value()),
+            },
         }
+    }
+}
 
-        Ok(match v {
-            0x60000000..0x6fffffff => PType::PtLoos,
-            0x6fffffff..0x70000000 => PType::PtHios,
-            0x70000000..0x7fffffff => PType::PtLoProc,
-            0x7fffffff..=0xffffffff => PType::PtHiProc,
-            _ => unreachable!(),
-        })
+impl fmt::Display for PType {
+    /// Names the types this crate doesn't know by their offset in the OS or
+    /// processor-specific range, as `readelf -l` does
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match *self {
+            PType::PtNull => "NULL",
+            PType::PtLoad => "LOAD",
+            PType::PtDynamic => "DYNAMIC",
+            PType::PtInterp => "INTERP",
+            PType::PtNote => "NOTE",
+            PType::PtShlib => "SHLIB",
+            PType::PtPhdr => "PHDR",
+            PType::PtTls => "TLS",
+            PType::PtLoos => "LOOS",
+            PType::PtHios => "HIOS",
+            PType::PtLoProc => "LOPROC",
+            PType::PtHiProc => "HIPROC",
+            PType::PtGnuEhFrame => "GNU_EH_FRAME",
+            PType::PtGnuStack => "GNU_STACK",
+            PType::PtGnuRelro => "GNU_RELRO",
+            PType::PtGnuProperty => "GNU_PROPERTY",
+            PType::PtRiscvAttributes => "RISCV_ATTRIBUT",
+            PType::Other(v @ PT_LOOS..=PT_HIOS) => {
+                return f.pad(&format!("LOOS+{:#x}", v - PT_LOOS))
+            }
+            PType::Other(v @ PT_LOPROC..=PT_HIPROC) => {
+                return f.pad(&format!("LOPROC+{:#x}", v - PT_LOPROC))
+            }
+            PType::Other(v) => return f.pad(&format!("{:#x}", v)),
+        };
+
+        f.pad(name)
+    }
+}
+
+/// Renders segment flags the same way as `readelf -l`
+pub fn flags_name(flags: u32) -> String {
+    [(PF_READ, 'R'), (PF_WRITE, 'W'), (PF_EXEC, 'E')]
+        .iter()
+        .map(|(flag, key)| if flags & flag != 0 { *key } else { ' ' })
+        .collect()
+}
+
+impl TableRow for Elf64PHdr {
+    const HEADER: &'static str = "Type           Offset             VirtAddr           PhysAddr           FileSiz            MemSiz             Flg Align";
+}
+
+impl fmt::Display for Elf64PHdr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<14} 0x{:016x} 0x{:016x} 0x{:016x} 0x{:016x} 0x{:016x} {} {:#x}",
+            self.p_type,
+            self.offset,
+            self.vaddr,
+            self.paddr,
+            self.filesz,
+            self.memsz,
+            flags_name(self.flags),
+            self.align
+        )
+    }
+}
+
+impl PType {
+    /// Every variant but `Other`, in the order they're declared
+    pub const VARIANTS: [PType; 17] = [
+        PType::PtNull,
+        PType::PtLoad,
+        PType::PtDynamic,
+        PType::PtInterp,
+        PType::PtNote,
+        PType::PtShlib,
+        PType::PtPhdr,
+        PType::PtTls,
+        PType::PtLoos,
+        PType::PtHios,
+        PType::PtLoProc,
+        PType::PtHiProc,
+        PType::PtGnuEhFrame,
+        PType::PtGnuStack,
+        PType::PtGnuRelro,
+        PType::PtGnuProperty,
+        PType::PtRiscvAttributes,
+    ];
+
+    /// Value of `p_type` this type is read from and written as
+    pub const fn value(self) -> u32 {
+        match self {
+            PType::PtNull => PT_NULL,
+            PType::PtLoad => PT_LOAD,
+            PType::PtDynamic => PT_DYNAMIC,
+            PType::PtInterp => PT_INTERP,
+            PType::PtNote => PT_NOTE,
+            PType::PtShlib => PT_SHLIB,
+            PType::PtPhdr => PT_PHDR,
+            PType::PtTls => PT_TLS,
+            PType::PtLoos => PT_LOOS,
+            PType::PtHios => PT_HIOS,
+            PType::PtLoProc => PT_LOPROC,
+            PType::PtHiProc => PT_HIPROC,
+            PType::PtGnuEhFrame => PT_GNU_EH_FRAME,
+            PType::PtGnuStack => PT_GNU_STACK,
+            PType::PtGnuRelro => PT_GNU_RELRO,
+            PType::PtGnuProperty => PT_GNU_PROPERTY,
+            PType::PtRiscvAttributes => PT_RISCV_ATTRIBUTES,
+            PType::Other(v) => v,
+        }
+    }
+
+    const fn decode(v: u32) -> Self {
+        match v {
+            PT_NULL => PType::PtNull,
+            PT_LOAD => PType::PtLoad,
+            PT_DYNAMIC => PType::PtDynamic,
+            PT_INTERP => PType::PtInterp,
+            PT_NOTE => PType::PtNote,
+            PT_SHLIB => PType::PtShlib,
+            PT_PHDR => PType::PtPhdr,
+            PT_TLS => PType::PtTls,
+            PT_LOOS => PType::PtLoos,
+            PT_HIOS => PType::PtHios,
+            PT_LOPROC => PType::PtLoProc,
+            PT_HIPROC => PType::PtHiProc,
+            PT_GNU_EH_FRAME => PType::PtGnuEhFrame,
+            PT_GNU_STACK => PType::PtGnuStack,
+            PT_GNU_RELRO => PType::PtGnuRelro,
+            PT_GNU_PROPERTY => PType::PtGnuProperty,
+            PT_RISCV_ATTRIBUTES => PType::PtRiscvAttributes,
+            v => PType::Other(v),
+        }
+    }
+
+    /// Whether the type is neither one this crate knows nor in the OS or
+    /// processor-specific ranges, where the specification defines none
+    pub fn is_unknown(self) -> bool {
+        matches!(self, PType::Other(v) if !(PT_LOOS..=PT_HIPROC).contains(&v))
+    }
+}
+
+// every variant but `Other` must decode from its own value, so none is written as a
+// value that reads back as another type. Adding a variant breaks the build in `value`
+// until it's given one
+const _: () = {
+    let mut idx = 0;
+    while idx < PType::VARIANTS.len() {
+        let v = PType::VARIANTS[idx];
+        let decoded = PType::decode(v.value());
+        assert!(!matches!(decoded, PType::Other(_)) && decoded.value() == v.value());
+        idx += 1;
+    }
+};
+
+impl From<u32> for PType {
+    /// Decodes `p_type`, the types this crate doesn't know about decoding to `Other`
+    fn from(v: u32) -> Self {
+        PType::decode(v)
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Elf64PHdr {
     /// Segment type
     pub p_type: PType,
@@ -161,38 +574,222 @@ pub struct Elf64PHdr {
 }
 
 impl Elf64PHdr {
-    pub fn parse(data: &[u8], headers: &Elf64Hdr) -> Result<Vec<Self>, ParseError> {
-        let nth = headers.ph_num as usize;
+    /// Whether the segment breaks the `vaddr` and `offset` congruence modulo `align`
+    /// required by the specification
+    pub fn is_misaligned(&self) -> bool {
+        self.align > 1 && self.vaddr.0 % self.align != self.offset % self.align
+    }
+
+    /// Number of entries in the program header table, following the `PN_XNUM` escape
+    /// to the initial section header when needed
+    pub fn count(data: &[u8], ctx: &ParseContext, headers: &Elf64Hdr) -> Result<usize, ParseError> {
+        if headers.ph_num != PN_XNUM {
+            return Ok(headers.ph_num as usize);
+        }
+
+        let count = Elf64SHdr::parse_initial(data, ctx, headers)?.info as usize;
+        debug!(
+            "ph_num is PN_XNUM, read {} program headers from section 0",
+            count
+        );
+
+        Ok(count)
+    }
+
+    /// Appends the encoding of this header to `out`
+    pub fn write(&self, ctx: &ParseContext, out: &mut Vec<u8>) {
+        let layout = layout::phdr::for_class(ctx.class);
+        let mut ph = vec![0u8; layout.size];
+
+        [
+            (layout.p_type, self.p_type.value() as u64),
+            (layout.p_flags, self.flags as u64),
+            (layout.p_offset, self.offset),
+            (layout.p_vaddr, self.vaddr.0),
+            (layout.p_paddr, self.paddr.0),
+            (layout.p_filesz, self.filesz),
+            (layout.p_memsz, self.memsz),
+            (layout.p_align, self.align),
+        ]
+        .into_iter()
+        .for_each(|(spec, value)| ctx.write_field(&mut ph, spec, value).unwrap());
+
+        out.extend_from_slice(&ph);
+    }
+
+    /// Bytes of the program header table holding `count` entries, as returned by
+    /// `count`, in a file of `file_len` bytes. It's checked as `parse` does before
+    /// reading any entry. None when the file has no program headers
+    pub fn table_range(
+        headers: &Elf64Hdr,
+        count: usize,
+        options: &ParseOptions,
+        file_len: u64,
+    ) -> Result<Option<Range<u64>>, ParseError> {
+        // relocatable objects have no program header table
+        if count == 0 {
+            return Ok(None);
+        }
+
+        // entries can't be walked without a size
+        if headers.ph_ent_size == 0 {
+            return Err(ParseError::ZeroEntrySize { table: "program" });
+        }
+        options.check_table_entries("program", count)?;
+
+        // the size is checked before any entry is read, so a bogus count or entry size
+        // can't make the parser walk past the end of the file
+        headers
+            .phdr_table_range_of(count, file_len)?
+            .ok_or(ParseError::ProgramHeadersOutOfBounds)
+            .map(Some)
+    }
+
+    /// Decodes the entry `ph`, leaving its contents `Ignorable`
+    pub(crate) fn parse_entry(ph: &[u8], ctx: &ParseContext) -> Result<Self, ParseError> {
+        let mut fields = ctx.reader(ph);
+        let p_type = PType::from(fields.u32()?);
+        // 64-bit entries hold the flags right after the type, keeping the words that
+        // follow aligned, 32-bit ones after the sizes
+        let flags = match ctx.class {
+            ElfHClass::ElfClass64 => Some(fields.u32()?),
+            _ => None,
+        };
+        let offset = fields.uword()?;
+        let vaddr = fields.addr()?;
+        let paddr = fields.addr()?;
+        let filesz = fields.uword()?;
+        let memsz = fields.uword()?;
+        let flags = match flags {
+            Some(flags) => flags,
+            None => fields.u32()?,
+        };
+
+        Ok(Elf64PHdr {
+            p_type,
+            flags,
+            offset,
+            vaddr,
+            paddr,
+            filesz,
+            memsz,
+            align: fields.uword()?,
+            section: Ignorable,
+        })
+    }
+
+    /// Parses the program header table, along with the contents of the segments as
+    /// `ParseOptions` asks for. The table is checked against the file before any entry
+    /// is read.
+    ///
+    /// A segment whose file image doesn't fit in the file is kept with `Skipped`
+    /// contents, and reported as `ParseWarning::SegmentOutOfBounds`. With
+    /// `ParseOptions::strict`, the first one fails the parse instead. So does a loadable
+    /// segment whose file image is larger than its memory image, reported as
+    /// `ParseWarning::SegmentFileSizeExceedsMemSize`. Segments of an
+    /// unknown type are kept as well, and reported as `ParseWarning::UnknownSegmentType`
+    pub fn parse(
+        data: &[u8],
+        ctx: &ParseContext,
+        headers: &Elf64Hdr,
+        options: &ParseOptions,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Result<Vec<Self>, ParseError> {
+        let nth = Elf64PHdr::count(data, ctx, headers)?;
         let off = headers.ph_off as usize;
         let siz = headers.ph_ent_size as usize;
+        let Some(table) = Elf64PHdr::table_range(headers, nth, options, data.len() as u64)? else {
+            return Ok(vec![]);
+        };
+        options.check_entries_alloc::<Elf64PHdr>(nth)?;
 
-        let headers: Vec<Elf64PHdr> = data[off..]
-            .chunks(siz)
-            .take(nth)
-            .map(|sh| {
-                let p_type = convert::<u32, 4>(sh[0..=3].try_into().unwrap(), headers.ident.data)
-                    .try_into()
-                    .unwrap();
-                let filesz = convert::<u64, 8>(sh[32..=39].try_into().unwrap(), headers.ident.data);
-                let memsz = convert::<u64, 8>(sh[40..=47].try_into().unwrap(), headers.ident.data);
-                let offset = convert::<u64, 8>(sh[8..=15].try_into().unwrap(), headers.ident.data);
-                let section =
-                    PTypeData::parse_section(&p_type, headers, filesz, memsz, offset, data)
-                        .unwrap();
-
-                Elf64PHdr {
-                    p_type,
-                    flags: convert(sh[4..=7].try_into().unwrap(), headers.ident.data),
-                    offset,
-                    vaddr: convert(sh[16..=23].try_into().unwrap(), headers.ident.data),
-                    paddr: convert(sh[24..=31].try_into().unwrap(), headers.ident.data),
-                    filesz,
-                    memsz,
-                    align: convert(sh[48..=55].try_into().unwrap(), headers.ident.data),
-                    section,
-                }
-            })
-            .collect();
+        // a table overlapping the ELF header decodes header bytes as segments, which
+        // are kept for inspection but taken for nothing: the contents of the segments
+        // aren't read, nor their types reported
+        let suspicious = headers.overlaps_header(headers.ph_off, table.end - table.start);
+        if suspicious {
+            warn!(
+                "program header table at {:#x} overlaps the ELF header",
+                headers.ph_off
+            );
+        }
+
+        let mut decode = |idx: usize, ph: &[u8]| -> Result<Elf64PHdr, ParseError> {
+            let mut header = Elf64PHdr::parse_entry(ph, ctx)?;
+            if header.p_type.is_unknown() && !suspicious {
+                let warning = ParseWarning::UnknownSegmentType {
+                    idx,
+                    p_type: header.p_type.value(),
+                };
+                warn!("{}", warning);
+                warnings.push(warning);
+            }
+            if let Some(load_bias) = options.load_bias {
+                header.offset = mapped::segment_offset(&header, load_bias, data.len() as u64)?;
+            }
+            header.section = match suspicious {
+                true => PTypeData::Skipped,
+                false => match PTypeData::parse_section(&header, ctx, data, options) {
+                    // the headers of a truncated file are still of use to a loader
+                    Err(ParseError::SegmentOutOfBounds { offset, filesz }) if !options.strict => {
+                        let warning = ParseWarning::SegmentOutOfBounds {
+                            idx,
+                            offset,
+                            filesz,
+                            file_size: data.len() as u64,
+                        };
+                        warn!("{}", warning);
+                        warnings.push(warning);
+                        PTypeData::Skipped
+                    }
+                    Err(ParseError::SegmentFileSizeExceedsMemSize { filesz, memsz })
+                        if !options.strict =>
+                    {
+                        let warning =
+                            ParseWarning::SegmentFileSizeExceedsMemSize { idx, filesz, memsz };
+                        warn!("{}", warning);
+                        warnings.push(warning);
+                        PTypeData::Skipped
+                    }
+                    result => result?,
+                },
+            };
+
+            Ok(header)
+        };
+        let headers: Vec<Elf64PHdr> = data[off..table.end as usize]
+            .chunks_exact(siz)
+            .enumerate()
+            .map(|(idx, ph)| decode(idx, ph).map_err(|e| e.in_segment(idx)))
+            .collect::<Result<_, ParseError>>()?;
+
+        debug!(
+            "parsed {} program headers from {:#x}..{:#x}",
+            headers.len(),
+            off,
+            off + headers.len() * siz
+        );
+
+        for (idx, ph) in headers.iter().enumerate() {
+            if log_enabled!(Level::Trace) {
+                trace!(
+                    "program header [{}]: {:?} offset={:#x} vaddr={:?} filesz={:#x} memsz={:#x}",
+                    idx,
+                    ph.p_type,
+                    ph.offset,
+                    ph.vaddr,
+                    ph.filesz,
+                    ph.memsz
+                );
+            }
+
+            if ph.is_misaligned() {
+                warn!(
+                    "segment [{}] vaddr {:?} and offset {:#x} are not congruent modulo {}",
+                    idx, ph.vaddr, ph.offset, ph.align
+                );
+            }
+        }
 
         Ok(headers)
     }
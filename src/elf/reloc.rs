@@ -0,0 +1,134 @@
+use crate::bytes::{Address, ParseContext};
+use crate::elf::ehdr::{ElfHClass, EM_RISCV};
+use crate::layout;
+use crate::parser::ParseError;
+use log::debug;
+
+/// A relocation entry. Entries read from `SHT_REL`/`DT_REL` tables have no explicit
+/// addend, and hold 0 in `addend`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Elf64Rela {
+    /// Location to apply the relocation to, a virtual address for executable and
+    /// shared object files
+    pub offset: Address,
+    /// Symbol index and relocation type, packed differently depending on the class
+    pub info: u64,
+    pub addend: i64,
+    /// Index into the linked symbol table of the symbol the relocation refers to
+    pub sym: u32,
+    /// Processor-specific relocation type
+    pub r_type: u32,
+}
+
+impl Elf64Rela {
+    /// Parses a table of relocations, with explicit addends if `rela` is set
+    pub fn parse_table(
+        data: &[u8],
+        ctx: &ParseContext,
+        rela: bool,
+    ) -> Result<Vec<Self>, ParseError> {
+        let layout = layout::rela::for_class(ctx.class);
+        let size = if rela { layout.size } else { layout.rel_size() };
+
+        let relocations: Vec<Elf64Rela> = data
+            .chunks_exact(size)
+            .enumerate()
+            .map(|(idx, r)| {
+                let field = |spec| ctx.read_field(r, spec).map_err(|e| e.in_entry(idx));
+                let info = field(layout.r_info)?;
+                let addend = match rela {
+                    true if ctx.class == ElfHClass::ElfClass64 => field(layout.r_addend)? as i64,
+                    true => field(layout.r_addend)? as i32 as i64,
+                    false => 0,
+                };
+                let (sym, r_type) = match ctx.class {
+                    ElfHClass::ElfClass64 => ((info >> 32) as u32, info as u32),
+                    _ => ((info >> 8) as u32, info as u32 & 0xff),
+                };
+
+                Ok(Elf64Rela {
+                    offset: Address(field(layout.r_offset)?),
+                    info,
+                    addend,
+                    sym,
+                    r_type,
+                })
+            })
+            .collect::<Result<_, ParseError>>()?;
+
+        debug!(
+            "parsed {} relocations from {} bytes",
+            relocations.len(),
+            data.len()
+        );
+
+        Ok(relocations)
+    }
+}
+
+/// Name of the relocation type `r_type` of `machine`, as `readelf -r` prints it. Only
+/// RISC-V types are known, other ones print as `UNKNOWN(<type>)`
+pub fn type_name(machine: u16, r_type: u32) -> String {
+    let name = match (machine, r_type) {
+        (EM_RISCV, 0) => "R_RISCV_NONE",
+        (EM_RISCV, 1) => "R_RISCV_32",
+        (EM_RISCV, 2) => "R_RISCV_64",
+        (EM_RISCV, 3) => "R_RISCV_RELATIVE",
+        (EM_RISCV, 4) => "R_RISCV_COPY",
+        (EM_RISCV, 5) => "R_RISCV_JUMP_SLOT",
+        (EM_RISCV, 6) => "R_RISCV_TLS_DTPMOD32",
+        (EM_RISCV, 7) => "R_RISCV_TLS_DTPMOD64",
+        (EM_RISCV, 8) => "R_RISCV_TLS_DTPREL32",
+        (EM_RISCV, 9) => "R_RISCV_TLS_DTPREL64",
+        (EM_RISCV, 10) => "R_RISCV_TLS_TPREL32",
+        (EM_RISCV, 11) => "R_RISCV_TLS_TPREL64",
+        (EM_RISCV, 12) => "R_RISCV_TLSDESC",
+        (EM_RISCV, 16) => "R_RISCV_BRANCH",
+        (EM_RISCV, 17) => "R_RISCV_JAL",
+        (EM_RISCV, 18) => "R_RISCV_CALL",
+        (EM_RISCV, 19) => "R_RISCV_CALL_PLT",
+        (EM_RISCV, 20) => "R_RISCV_GOT_HI20",
+        (EM_RISCV, 21) => "R_RISCV_TLS_GOT_HI20",
+        (EM_RISCV, 22) => "R_RISCV_TLS_GD_HI20",
+        (EM_RISCV, 23) => "R_RISCV_PCREL_HI20",
+        (EM_RISCV, 24) => "R_RISCV_PCREL_LO12_I",
+        (EM_RISCV, 25) => "R_RISCV_PCREL_LO12_S",
+        (EM_RISCV, 26) => "R_RISCV_HI20",
+        (EM_RISCV, 27) => "R_RISCV_LO12_I",
+        (EM_RISCV, 28) => "R_RISCV_LO12_S",
+        (EM_RISCV, 29) => "R_RISCV_TPREL_HI20",
+        (EM_RISCV, 30) => "R_RISCV_TPREL_LO12_I",
+        (EM_RISCV, 31) => "R_RISCV_TPREL_LO12_S",
+        (EM_RISCV, 32) => "R_RISCV_TPREL_ADD",
+        (EM_RISCV, 33) => "R_RISCV_ADD8",
+        (EM_RISCV, 34) => "R_RISCV_ADD16",
+        (EM_RISCV, 35) => "R_RISCV_ADD32",
+        (EM_RISCV, 36) => "R_RISCV_ADD64",
+        (EM_RISCV, 37) => "R_RISCV_SUB8",
+        (EM_RISCV, 38) => "R_RISCV_SUB16",
+        (EM_RISCV, 39) => "R_RISCV_SUB32",
+        (EM_RISCV, 40) => "R_RISCV_SUB64",
+        (EM_RISCV, 41) => "R_RISCV_GOT32_PCREL",
+        (EM_RISCV, 43) => "R_RISCV_ALIGN",
+        (EM_RISCV, 44) => "R_RISCV_RVC_BRANCH",
+        (EM_RISCV, 45) => "R_RISCV_RVC_JUMP",
+        (EM_RISCV, 51) => "R_RISCV_RELAX",
+        (EM_RISCV, 52) => "R_RISCV_SUB6",
+        (EM_RISCV, 53) => "R_RISCV_SET6",
+        (EM_RISCV, 54) => "R_RISCV_SET8",
+        (EM_RISCV, 55) => "R_RISCV_SET16",
+        (EM_RISCV, 56) => "R_RISCV_SET32",
+        (EM_RISCV, 57) => "R_RISCV_32_PCREL",
+        (EM_RISCV, 58) => "R_RISCV_IRELATIVE",
+        (EM_RISCV, 59) => "R_RISCV_PLT32",
+        (EM_RISCV, 60) => "R_RISCV_SET_ULEB128",
+        (EM_RISCV, 61) => "R_RISCV_SUB_ULEB128",
+        (EM_RISCV, 62) => "R_RISCV_TLSDESC_HI20",
+        (EM_RISCV, 63) => "R_RISCV_TLSDESC_LOAD_LO12",
+        (EM_RISCV, 64) => "R_RISCV_TLSDESC_ADD_LO12",
+        (EM_RISCV, 65) => "R_RISCV_TLSDESC_CALL",
+        _ => return format!("UNKNOWN({:#x})", r_type),
+    };
+
+    name.to_string()
+}
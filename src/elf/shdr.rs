@@ -1,12 +1,144 @@
-use crate::bytes::{convert, Address};
-use crate::elf::ehdr::Elf64Hdr;
-use crate::parser::ParseError;
+use crate::bytes::{Address, ParseContext};
+use crate::display::{Named, TableRow};
+use crate::elf::ehdr::{Elf64Hdr, ElfHClass};
+use crate::elf::phdr::PN_XNUM;
+use crate::layout;
+use crate::options::ParseOptions;
+use crate::parser::{ParseError, ParseWarning};
+use log::{debug, log_enabled, trace, warn, Level};
+use std::borrow::Cow;
+use std::fmt;
+use std::ops::Range;
 
 /// Indicates the lower bound of the range of reserved indices
 pub const SHN_LORESERVE: u16 = 0xff00;
+/// Value of `sh_str_ndx` indicating that the real index of the section header string
+/// table doesn't fit in the ELF header, and is instead held by the `link` field of the
+/// initial section header
+pub const SHN_XINDEX: u16 = 0xffff;
 
+/// Size in bytes of a single section header of a 64-bit file
+pub const SHDR_SZ: usize = 64;
+
+pub const SHT_NULL: u32 = 0x0;
+pub const SHT_PROGBITS: u32 = 0x1;
+/// Indicates sections that hold a full symbol table, usually for link editing
+pub const SHT_SYMTAB: u32 = 0x2;
 /// Indicates sections that store string tables
 pub const SHT_STRTAB: u32 = 0x3;
+pub const SHT_RELA: u32 = 0x4;
+pub const SHT_HASH: u32 = 0x5;
+pub const SHT_DYNAMIC: u32 = 0x6;
+pub const SHT_NOTE: u32 = 0x7;
+pub const SHT_NOBITS: u32 = 0x8;
+pub const SHT_REL: u32 = 0x9;
+pub const SHT_SHLIB: u32 = 0xa;
+/// Indicates sections that hold the minimal set of dynamic linking symbols
+pub const SHT_DYNSYM: u32 = 0xb;
+pub const SHT_INIT_ARRAY: u32 = 0xe;
+pub const SHT_FINI_ARRAY: u32 = 0xf;
+pub const SHT_PREINIT_ARRAY: u32 = 0x10;
+pub const SHT_GROUP: u32 = 0x11;
+/// Indicates the last section type defined by the generic ABI
+pub const SHT_SYMTAB_SHNDX: u32 = 0x12;
+/// Indicates the lower bound of the range reserved for OS-specific semantics
+pub const SHT_LOOS: u32 = 0x60000000;
+pub const SHT_GNU_HASH: u32 = 0x6ffffff6;
+pub const SHT_GNU_VERDEF: u32 = 0x6ffffffd;
+pub const SHT_GNU_VERNEED: u32 = 0x6ffffffe;
+pub const SHT_GNU_VERSYM: u32 = 0x6fffffff;
+/// Indicates the upper bound of the range reserved for OS-specific semantics
+pub const SHT_HIOS: u32 = 0x6fffffff;
+/// Indicates the lower bound of the range reserved for processor-specific semantics
+pub const SHT_LOPROC: u32 = 0x70000000;
+pub const SHT_RISCV_ATTRIBUTES: u32 = 0x70000003;
+/// Indicates the upper bound of the range reserved for processor-specific semantics
+pub const SHT_HIPROC: u32 = 0x7fffffff;
+/// Indicates the lower bound of the range reserved for application programs
+pub const SHT_LOUSER: u32 = 0x80000000;
+
+/// The section contains data that should be writable during process execution
+pub const SHF_WRITE: u64 = 0x1;
+/// The section occupies memory during process execution
+pub const SHF_ALLOC: u64 = 0x2;
+/// The section contains executable machine instructions
+pub const SHF_EXECINSTR: u64 = 0x4;
+pub const SHF_MERGE: u64 = 0x10;
+pub const SHF_STRINGS: u64 = 0x20;
+pub const SHF_INFO_LINK: u64 = 0x40;
+pub const SHF_LINK_ORDER: u64 = 0x80;
+pub const SHF_OS_NONCONFORMING: u64 = 0x100;
+pub const SHF_GROUP: u64 = 0x200;
+pub const SHF_TLS: u64 = 0x400;
+pub const SHF_COMPRESSED: u64 = 0x800;
+pub const SHF_EXCLUDE: u64 = 0x80000000;
+
+/// Section types this crate knows about, see the `SHT_*` constants
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SType {
+    Null,
+    ProgBits,
+    SymTab,
+    StrTab,
+    Rela,
+    Hash,
+    Dynamic,
+    Note,
+    NoBits,
+    Rel,
+    ShLib,
+    DynSym,
+    InitArray,
+    FiniArray,
+    PreinitArray,
+    Group,
+    SymTabShndx,
+    GnuHash,
+    GnuVerdef,
+    GnuVerneed,
+    GnuVersym,
+    RiscvAttributes,
+    /// Any other value of the OS-specific range, as the `SHT_SUNW_*` types of Solaris
+    OsSpecific(u32),
+    /// Any other value of the processor-specific range, as the `SHT_MIPS_*` types of
+    /// Irix
+    ProcSpecific(u32),
+    /// Any other value, generic or from the user-specific range
+    Unknown(u32),
+}
+
+impl From<u32> for SType {
+    fn from(s_type: u32) -> Self {
+        match s_type {
+            SHT_NULL => SType::Null,
+            SHT_PROGBITS => SType::ProgBits,
+            SHT_SYMTAB => SType::SymTab,
+            SHT_STRTAB => SType::StrTab,
+            SHT_RELA => SType::Rela,
+            SHT_HASH => SType::Hash,
+            SHT_DYNAMIC => SType::Dynamic,
+            SHT_NOTE => SType::Note,
+            SHT_NOBITS => SType::NoBits,
+            SHT_REL => SType::Rel,
+            SHT_SHLIB => SType::ShLib,
+            SHT_DYNSYM => SType::DynSym,
+            SHT_INIT_ARRAY => SType::InitArray,
+            SHT_FINI_ARRAY => SType::FiniArray,
+            SHT_PREINIT_ARRAY => SType::PreinitArray,
+            SHT_GROUP => SType::Group,
+            SHT_SYMTAB_SHNDX => SType::SymTabShndx,
+            SHT_GNU_HASH => SType::GnuHash,
+            SHT_GNU_VERDEF => SType::GnuVerdef,
+            SHT_GNU_VERNEED => SType::GnuVerneed,
+            SHT_GNU_VERSYM => SType::GnuVersym,
+            SHT_RISCV_ATTRIBUTES => SType::RiscvAttributes,
+            t @ SHT_LOOS..=SHT_HIOS => SType::OsSpecific(t),
+            t @ SHT_LOPROC..=SHT_HIPROC => SType::ProcSpecific(t),
+            t => SType::Unknown(t),
+        }
+    }
+}
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum StringTableType {
@@ -15,12 +147,13 @@ pub enum StringTableType {
     /// Section Header String Table
     ShStrTab,
     /// Dynamic Symbols String Table
-    /// TODO: doesn't parse DynStr sections yet
-    _DynSym,
+    DynStr,
 }
 
 #[derive(Debug, Clone)]
 pub struct StringTable {
+    /// Index of the section header this table was read from
+    pub section_idx: usize,
     /// Offset to the first byte of the table
     pub offset: u64,
     /// Size of the table
@@ -31,6 +164,51 @@ pub struct StringTable {
     pub sh_type: StringTableType,
 }
 
+impl StringTable {
+    /// Strings of the table along with their offset, by increasing offset, one per
+    /// NUL byte ending a non-empty run, so the leading NUL byte and padding yield nothing. Bytes past
+    /// the last NUL byte aren't a string of the table and are left out. Strings that
+    /// aren't valid UTF-8 are replaced with `String::from_utf8_lossy` when `lossy` is
+    /// set, and skipped otherwise
+    pub fn iter(&self, lossy: bool) -> impl Iterator<Item = (u32, Cow<'_, str>)> + '_ {
+        let end = self.table.iter().rposition(|&c| c == 0).unwrap_or(0);
+        let mut off = 0;
+
+        self.table[..end]
+            .split(|&c| c == 0)
+            .filter_map(move |bytes| {
+                let start = off;
+                off += bytes.len() + 1;
+                if bytes.is_empty() {
+                    return None;
+                }
+
+                let s = match std::str::from_utf8(bytes) {
+                    Ok(s) => Cow::Borrowed(s),
+                    Err(_) if lossy => String::from_utf8_lossy(bytes),
+                    Err(_) => return None,
+                };
+                Some((start as u32, s))
+            })
+    }
+
+    /// Number of strings `iter` yields when `lossy` is set
+    pub fn len_strings(&self) -> usize {
+        self.iter(true).count()
+    }
+
+    /// Offset at which `s` can be read from the table, which may be within a longer
+    /// string it ends, as tables merging the tails of their strings point there
+    pub fn contains(&self, s: &str) -> Option<u32> {
+        let needle = [s.as_bytes(), &[0]].concat();
+
+        self.table
+            .windows(needle.len())
+            .position(|w| w == needle)
+            .map(|off| off as u32)
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Debug, Copy, Clone)]
 pub struct Elf64SHdr {
@@ -59,39 +237,166 @@ pub struct Elf64SHdr {
 }
 
 impl Elf64SHdr {
-    #[allow(dead_code)]
+    /// Header whose every field is zero, the `SHT_NULL` entry that starts the table
+    pub const NULL: Elf64SHdr = Elf64SHdr {
+        name: 0,
+        s_type: SHT_NULL,
+        flags: 0,
+        addr: Address(0),
+        offset: 0,
+        size: 0,
+        link: 0,
+        info: 0,
+        addr_align: 0,
+        ent_size: 0,
+    };
+
+    /// Whether the section holds a table of fixed-size entries, as declared by a
+    /// non-zero `ent_size`
     pub fn has_table(&self) -> bool {
         self.ent_size != 0
     }
 
-    #[allow(dead_code)]
+    /// Whether the section address must be a multiple of `addr_align`, 0 and 1 both
+    /// meaning the section has no alignment constraints
     pub fn has_align_constraints(&self) -> bool {
         self.addr_align != 0 && self.addr_align != 1
     }
 
+    /// Whether `addr_align` is 0 or a power of two, the only values the specification
+    /// allows
+    pub fn has_valid_alignment(&self) -> bool {
+        self.addr_align == 0 || self.addr_align.is_power_of_two()
+    }
+
+    /// Anomalies of the header that don't prevent reading the section, as the reasons
+    /// reported by `ParseWarning::SectionLint`. Misaligned addresses are reported on
+    /// their own, see `is_misaligned`
+    pub fn lint(&self) -> Vec<&'static str> {
+        let mut reasons = vec![];
+
+        if self.has_table() && !self.size.is_multiple_of(self.ent_size) {
+            reasons.push("size isn't a multiple of the entry size");
+        }
+        if !self.has_valid_alignment() {
+            reasons.push("alignment isn't a power of two");
+        }
+        if self.s_type == SHT_PROGBITS && self.offset == 0 {
+            reasons.push("PROGBITS section at offset 0, over the ELF header");
+        }
+        if self.flags & SHF_ALLOC == 0 && self.addr.0 != 0 {
+            reasons.push("non-allocated section has an address");
+        }
+
+        reasons
+    }
+
+    /// Whether every field is zero, as expected from the initial entry of the section
+    /// header table when no count overflows the ELF header
+    pub fn is_null(&self) -> bool {
+        self.name == 0
+            && self.s_type == SHT_NULL
+            && self.flags == 0
+            && self.addr.0 == 0
+            && self.offset == 0
+            && self.size == 0
+            && self.link == 0
+            && self.info == 0
+            && self.addr_align == 0
+            && self.ent_size == 0
+    }
+
+    /// Fields of the initial entry of the section header table holding a value other
+    /// than zero, as name and value. `size`, `link` and `info` may hold the number of
+    /// sections, the index of the section header string table and the number of
+    /// program headers, and are only reported when `headers` doesn't defer to them
+    pub fn initial_entry_anomalies(&self, headers: &Elf64Hdr) -> Vec<(&'static str, u64)> {
+        [
+            ("name", self.name as u64, false),
+            ("type", self.s_type as u64, false),
+            ("flags", self.flags, false),
+            ("addr", self.addr.0, false),
+            ("offset", self.offset, false),
+            ("size", self.size, headers.sh_num == 0),
+            ("link", self.link as u64, headers.sh_str_ndx == SHN_XINDEX),
+            ("info", self.info as u64, headers.ph_num == PN_XNUM),
+            ("addralign", self.addr_align, false),
+            ("entsize", self.ent_size, false),
+        ]
+        .into_iter()
+        .filter(|(_, value, extended)| *value != 0 && !extended)
+        .map(|(field, value, _)| (field, value))
+        .collect()
+    }
+
+    /// Whether the section contents are held by the file. `SHT_NOBITS` sections, such as
+    /// `.bss`, have a non-zero `size` but occupy no bytes in the file, so their `offset`
+    /// may coincide with the one of the next section
+    pub fn occupies_file_space(&self) -> bool {
+        self.s_type != SHT_NOBITS
+    }
+
+    /// Number of bytes the section occupies in the file
+    pub fn file_size(&self) -> u64 {
+        if self.occupies_file_space() {
+            self.size
+        } else {
+            0
+        }
+    }
+
+    /// Number of bytes the section occupies in the process memory, or 0 if the section
+    /// isn't loaded
+    pub fn mem_size(&self) -> u64 {
+        if self.flags & SHF_ALLOC != 0 {
+            self.size
+        } else {
+            0
+        }
+    }
+
+    /// Type of the section, those this crate doesn't know about being tagged with
+    /// the range they fall in
+    pub fn sh_type(&self) -> SType {
+        self.s_type.into()
+    }
+
+    /// Whether `s_type` falls outside of every range defined by the specification,
+    /// OS/processor/user specific ranges included
+    pub fn has_unknown_type(&self) -> bool {
+        self.s_type > SHT_SYMTAB_SHNDX && self.s_type < SHT_LOOS
+    }
+
+    /// Whether the section address doesn't honor its own alignment constraints
+    pub fn is_misaligned(&self) -> bool {
+        self.has_align_constraints() && !self.addr.0.is_multiple_of(self.addr_align)
+    }
+
+    /// Reads the section as a string table, whatever its type. The table isn't checked
+    /// to start and end with a NUL byte, lookups past the last NUL read up to its end
     pub fn parse_str_table(
         data: &[u8],
         section_header: &Elf64SHdr,
-        is_header_table: bool,
+        section_idx: usize,
+        sh_type: StringTableType,
     ) -> Result<StringTable, ParseError> {
         let off = section_header.offset as usize;
-        let siz = section_header.size as usize;
-
-        assert_eq!(section_header.s_type, SHT_STRTAB);
+        let siz = section_header.file_size() as usize;
 
-        let table: Vec<u8> = data[off..off + siz].try_into().unwrap();
+        let table: Vec<u8> = data
+            .get(off..off.checked_add(siz).ok_or(ParseError::InvalidLength)?)
+            .ok_or(ParseError::InvalidLength)?
+            .to_vec();
 
-        assert_eq!(table.len(), siz);
-        assert_eq!(*table.first().unwrap(), 0u8);
-        assert_eq!(*table.last().unwrap(), 0u8);
-
-        let sh_type = if is_header_table {
-            StringTableType::ShStrTab
-        } else {
-            StringTableType::StrTab
-        };
+        debug!(
+            "parsed string table of {} bytes from {:#x}..{:#x}",
+            siz,
+            off,
+            off + siz
+        );
 
         Ok(StringTable {
+            section_idx,
             offset: section_header.offset,
             size: section_header.size,
             table,
@@ -99,40 +404,292 @@ impl Elf64SHdr {
         })
     }
 
-    pub fn parse(data: &[u8], headers: &Elf64Hdr) -> Result<Vec<Self>, ParseError> {
+    /// Decodes the entry `sh` of a section header table
+    pub(crate) fn parse_entry(sh: &[u8], ctx: &ParseContext) -> Result<Self, ParseError> {
+        let mut fields = ctx.reader(sh);
+
+        Ok(Elf64SHdr {
+            name: fields.u32()?,
+            s_type: fields.u32()?,
+            flags: fields.uword()?,
+            addr: fields.addr()?,
+            offset: fields.uword()?,
+            size: fields.uword()?,
+            link: fields.u32()?,
+            info: fields.u32()?,
+            addr_align: fields.uword()?,
+            ent_size: fields.uword()?,
+        })
+    }
+
+    /// Decodes an entry of the section header table, rejecting it when its contents
+    /// would end past the largest offset of the class, so they can't be located at all
+    fn decode_entry(sh: &[u8], ctx: &ParseContext) -> Result<Self, ParseError> {
+        let header = Elf64SHdr::parse_entry(sh, ctx)?;
+        let max_offset = match ctx.class {
+            ElfHClass::ElfClass32 => u32::MAX as u64,
+            _ => u64::MAX,
+        };
+
+        match header.offset.checked_add(header.file_size()) {
+            Some(end) if end <= max_offset => Ok(header),
+            _ => Err(ParseError::ContentsOverflow {
+                offset: header.offset,
+                size: header.size,
+            }),
+        }
+    }
+
+    /// Appends the encoding of this header to `out`
+    pub fn write(&self, ctx: &ParseContext, out: &mut Vec<u8>) {
+        let layout = layout::shdr::for_class(ctx.class);
+        let mut sh = vec![0u8; layout.size];
+
+        [
+            (layout.sh_name, self.name as u64),
+            (layout.sh_type, self.s_type as u64),
+            (layout.sh_flags, self.flags),
+            (layout.sh_addr, self.addr.0),
+            (layout.sh_offset, self.offset),
+            (layout.sh_size, self.size),
+            (layout.sh_link, self.link as u64),
+            (layout.sh_info, self.info as u64),
+            (layout.sh_addralign, self.addr_align),
+            (layout.sh_entsize, self.ent_size),
+        ]
+        .into_iter()
+        .for_each(|(spec, value)| ctx.write_field(&mut sh, spec, value).unwrap());
+
+        out.extend_from_slice(&sh);
+    }
+
+    /// Parses only the initial entry of the section header table, which holds the real
+    /// number of sections and program headers when they don't fit in the ELF header
+    pub fn parse_initial(
+        data: &[u8],
+        ctx: &ParseContext,
+        headers: &Elf64Hdr,
+    ) -> Result<Self, ParseError> {
+        if headers.sh_off == 0 {
+            return Err(ParseError::NoSectionHeaderTable);
+        }
+
+        let off = headers.sh_off as usize;
+        let sh = data
+            .get(off..off.saturating_add(layout::shdr::for_class(ctx.class).size))
+            .ok_or(ParseError::InvalidLength)?;
+
+        Elf64SHdr::parse_entry(sh, ctx)
+    }
+
+    /// Bytes of the section header table of a file of `file_len` bytes, checked as
+    /// `parse` does before reading any entry. None when the file has no section headers
+    pub fn table_range(
+        headers: &Elf64Hdr,
+        options: &ParseOptions,
+        file_len: u64,
+    ) -> Result<Option<Range<u64>>, ParseError> {
         let nth = headers.sh_num as usize;
+
+        // larger counts are stored in the initial entry, with e_shnum set to 0
+        if nth >= SHN_LORESERVE as usize {
+            return Err(ParseError::TooManyEntries {
+                table: "section",
+                count: nth,
+                max: SHN_LORESERVE as usize - 1,
+            });
+        }
+
+        // files stripped of their section headers may also zero `sh_ent_size`
+        if nth == 0 {
+            return Ok(None);
+        }
+
+        // entries can't be walked without a size
+        if headers.sh_ent_size == 0 {
+            return Err(ParseError::ZeroEntrySize { table: "section" });
+        }
+        options.check_table_entries("section", nth)?;
+
+        let table_size = nth as u64 * headers.sh_ent_size as u64;
+        if headers.overlaps_header(headers.sh_off, table_size) {
+            return Err(ParseError::TableOverlapsHeader {
+                table: "section",
+                offset: headers.sh_off,
+            });
+        }
+
+        headers
+            .shdr_table_range(file_len)?
+            .ok_or(ParseError::SectionHeadersOutOfBounds)
+            .map(Some)
+    }
+
+    /// Decodes the entries of `table`, the bytes of a section header table holding
+    /// entries of `ent_size` bytes
+    pub(crate) fn parse_table(
+        table: &[u8],
+        ent_size: usize,
+        ctx: &ParseContext,
+    ) -> Result<Vec<Self>, ParseError> {
+        table
+            .chunks_exact(ent_size)
+            .enumerate()
+            .map(|(idx, sh)| Elf64SHdr::parse_entry(sh, ctx).map_err(|e| e.in_section(idx, None)))
+            .collect()
+    }
+
+    /// Parses the section header table. Its size is checked against the file before
+    /// any entry is read, so a bogus count or entry size can't make the parser walk
+    /// past the end of the file.
+    ///
+    /// An entry that can't be decoded is replaced by `Elf64SHdr::NULL`, keeping the
+    /// indices of the others, and reported as `ParseWarning::UndecodableSection`.
+    /// With `ParseOptions::strict`, the first one fails the parse instead
+    pub fn parse(
+        data: &[u8],
+        ctx: &ParseContext,
+        headers: &Elf64Hdr,
+        options: &ParseOptions,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Result<Vec<Self>, ParseError> {
         let off = headers.sh_off as usize;
         let siz = headers.sh_ent_size as usize;
+        let Some(table) = Elf64SHdr::table_range(headers, options, data.len() as u64)? else {
+            return Ok(vec![]);
+        };
+        options.check_entries_alloc::<Elf64SHdr>(headers.sh_num as usize)?;
 
-        if nth >= SHN_LORESERVE as usize {
-            unimplemented!(
-                "If the number of entries in the section header table is
-              larger than or equal to SHN_LORESERVE (0xff00), e_shnum
-              holds the value zero and the real number of entries in the
-              section header table is held in the sh_size member of the
-              initial entry in section header table.  Otherwise, the
-              sh_size member of the initial entry in the section header
-              table holds the value zero."
-            );
+        let entries = data[off..table.end as usize]
+            .chunks_exact(siz)
+            .map(|sh| Elf64SHdr::decode_entry(sh, ctx))
+            .collect::<Vec<_>>();
+        let mut headers = Vec::with_capacity(entries.len());
+        for (idx, entry) in entries.into_iter().enumerate() {
+            match entry {
+                Ok(sh) => headers.push(sh),
+                Err(e) if options.strict => return Err(e.in_section(idx, None)),
+                Err(e) => {
+                    let warning = ParseWarning::UndecodableSection {
+                        idx,
+                        offset: (off + idx * siz) as u64,
+                        error: e.to_string(),
+                    };
+                    warn!("{}", warning);
+                    warnings.push(warning);
+                    headers.push(Elf64SHdr::NULL);
+                }
+            }
         }
 
-        let headers: Vec<Elf64SHdr> = data[off..]
-            .chunks(siz)
-            .take(nth)
-            .map(|sh| Elf64SHdr {
-                name: convert(sh[0..=3].try_into().unwrap(), headers.ident.data),
-                s_type: convert(sh[4..=7].try_into().unwrap(), headers.ident.data),
-                flags: convert(sh[8..=15].try_into().unwrap(), headers.ident.data),
-                addr: convert(sh[16..=23].try_into().unwrap(), headers.ident.data),
-                offset: convert(sh[24..=31].try_into().unwrap(), headers.ident.data),
-                size: convert(sh[32..=39].try_into().unwrap(), headers.ident.data),
-                link: convert(sh[40..=43].try_into().unwrap(), headers.ident.data),
-                info: convert(sh[44..=47].try_into().unwrap(), headers.ident.data),
-                addr_align: convert(sh[48..=55].try_into().unwrap(), headers.ident.data),
-                ent_size: convert(sh[56..=63].try_into().unwrap(), headers.ident.data),
-            })
-            .collect();
+        debug!(
+            "parsed {} section headers from {:#x}..{:#x}",
+            headers.len(),
+            off,
+            off + headers.len() * siz
+        );
+
+        for (idx, sh) in headers.iter().enumerate() {
+            if log_enabled!(Level::Trace) {
+                trace!("section header [{}]: {:?}", idx, sh);
+            }
+
+            if sh.has_unknown_type() {
+                warn!("section [{}] has unknown type {:#x}", idx, sh.s_type);
+            }
+
+            if sh.is_misaligned() {
+                warn!(
+                    "section [{}] address {:#x} is not aligned to {}",
+                    idx, sh.addr.0, sh.addr_align
+                );
+            }
+        }
 
         Ok(headers)
     }
 }
+
+pub fn type_name(s_type: u32) -> String {
+    match s_type {
+        SHT_NULL => "NULL".to_string(),
+        SHT_PROGBITS => "PROGBITS".to_string(),
+        SHT_SYMTAB => "SYMTAB".to_string(),
+        SHT_STRTAB => "STRTAB".to_string(),
+        SHT_RELA => "RELA".to_string(),
+        SHT_HASH => "HASH".to_string(),
+        SHT_DYNAMIC => "DYNAMIC".to_string(),
+        SHT_NOTE => "NOTE".to_string(),
+        SHT_NOBITS => "NOBITS".to_string(),
+        SHT_REL => "REL".to_string(),
+        SHT_SHLIB => "SHLIB".to_string(),
+        SHT_DYNSYM => "DYNSYM".to_string(),
+        SHT_INIT_ARRAY => "INIT_ARRAY".to_string(),
+        SHT_FINI_ARRAY => "FINI_ARRAY".to_string(),
+        SHT_PREINIT_ARRAY => "PREINIT_ARRAY".to_string(),
+        SHT_GROUP => "GROUP".to_string(),
+        SHT_SYMTAB_SHNDX => "SYMTAB SECTION INDICES".to_string(),
+        SHT_GNU_HASH => "GNU_HASH".to_string(),
+        SHT_GNU_VERDEF => "VERDEF".to_string(),
+        SHT_GNU_VERNEED => "VERNEED".to_string(),
+        SHT_GNU_VERSYM => "VERSYM".to_string(),
+        SHT_RISCV_ATTRIBUTES => "RISCV_ATTRIBUTES".to_string(),
+        t if (SHT_LOOS..SHT_LOPROC).contains(&t) => format!("LOOS+{:#x}", t - SHT_LOOS),
+        t if (SHT_LOPROC..SHT_LOUSER).contains(&t) => format!("LOPROC+{:#x}", t - SHT_LOPROC),
+        t if t >= SHT_LOUSER => format!("LOUSER+{:#x}", t - SHT_LOUSER),
+        t => format!("{:08x}: <unknown>", t),
+    }
+}
+
+/// Renders section flags using the same key letters as `readelf -S`
+pub fn flags_name(flags: u64) -> String {
+    [
+        (SHF_WRITE, 'W'),
+        (SHF_ALLOC, 'A'),
+        (SHF_EXECINSTR, 'X'),
+        (SHF_MERGE, 'M'),
+        (SHF_STRINGS, 'S'),
+        (SHF_INFO_LINK, 'I'),
+        (SHF_LINK_ORDER, 'L'),
+        (SHF_OS_NONCONFORMING, 'O'),
+        (SHF_GROUP, 'G'),
+        (SHF_TLS, 'T'),
+        (SHF_COMPRESSED, 'C'),
+        (SHF_EXCLUDE, 'E'),
+    ]
+    .iter()
+    .filter(|(flag, _)| flags & flag != 0)
+    .map(|(_, key)| key)
+    .collect()
+}
+
+impl TableRow for Named<'_, Elf64SHdr> {
+    const HEADER: &'static str = "Name              Type             Address          Off      Size             EntSize          Flg  Lk  Inf  Al";
+}
+
+impl fmt::Display for Named<'_, Elf64SHdr> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sh = self.inner;
+
+        write!(
+            f,
+            "{:<17.17} {:<16.16} {:016x} {:08x} {:016x} {:016x} {:>3} {:>3} {:>4} {:>3}",
+            self.name,
+            type_name(sh.s_type),
+            sh.addr,
+            sh.offset,
+            sh.size,
+            sh.ent_size,
+            flags_name(sh.flags),
+            sh.link,
+            sh.info,
+            sh.addr_align
+        )
+    }
+}
+
+impl fmt::Display for Elf64SHdr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Named::new("", self).fmt(f)
+    }
+}
@@ -0,0 +1,233 @@
+use crate::bytes::{Address, ParseContext};
+use crate::display::{Named, TableRow};
+use crate::elf::shdr::Elf64SHdr;
+use crate::layout;
+use crate::parser::ParseError;
+use log::{debug, log_enabled, trace};
+use std::fmt;
+
+/// Size in bytes of a single symbol table entry of a 64-bit file
+pub const SYM_SZ: usize = 24;
+
+/// Undefined section index, the symbol is referenced but not defined in this file
+pub const SHN_UNDEF: u16 = 0;
+/// The symbol has an absolute value that won't change because of relocation
+pub const SHN_ABS: u16 = 0xfff1;
+/// The symbol labels a common block that has not yet been allocated
+pub const SHN_COMMON: u16 = 0xfff2;
+
+pub const STB_LOCAL: u8 = 0;
+pub const STB_GLOBAL: u8 = 1;
+pub const STB_WEAK: u8 = 2;
+/// GNU: global symbol the dynamic linker keeps unique in the whole process
+pub const STB_GNU_UNIQUE: u8 = 10;
+
+pub const STT_NOTYPE: u8 = 0;
+pub const STT_OBJECT: u8 = 1;
+pub const STT_FUNC: u8 = 2;
+pub const STT_SECTION: u8 = 3;
+pub const STT_FILE: u8 = 4;
+pub const STT_COMMON: u8 = 5;
+pub const STT_TLS: u8 = 6;
+/// GNU: function whose address is picked at load time by the resolver it points to
+pub const STT_GNU_IFUNC: u8 = 10;
+
+pub const STV_DEFAULT: u8 = 0;
+pub const STV_INTERNAL: u8 = 1;
+pub const STV_HIDDEN: u8 = 2;
+pub const STV_PROTECTED: u8 = 3;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Elf64Sym {
+    /// Index into the symbol string table holding the symbol name, or 0 if it has no name
+    pub name: u32,
+    /// Symbol binding (high nibble) and type (low nibble)
+    pub info: u8,
+    /// Symbol visibility, held in the low 2 bits
+    pub other: u8,
+    /// Index of the section this symbol is defined in relation to
+    pub shndx: u16,
+    /// Value of the symbol, usually an address
+    pub value: Address,
+    /// Size of the object the symbol refers to, or 0 if unknown
+    pub size: u64,
+}
+
+impl Elf64Sym {
+    pub fn bind(&self) -> u8 {
+        self.info >> 4
+    }
+
+    pub fn sym_type(&self) -> u8 {
+        self.info & 0xf
+    }
+
+    pub fn visibility(&self) -> u8 {
+        self.other & 0x3
+    }
+
+    pub fn is_undefined(&self) -> bool {
+        self.shndx == SHN_UNDEF
+    }
+
+    /// Appends the encoding of this symbol to `out`
+    pub fn write(&self, ctx: &ParseContext, out: &mut Vec<u8>) {
+        let layout = layout::sym::for_class(ctx.class);
+        let mut sym = vec![0u8; layout.size];
+
+        [
+            (layout.st_name, self.name as u64),
+            (layout.st_info, self.info as u64),
+            (layout.st_other, self.other as u64),
+            (layout.st_shndx, self.shndx as u64),
+            (layout.st_value, self.value.0),
+            (layout.st_size, self.size),
+        ]
+        .into_iter()
+        .for_each(|(spec, value)| ctx.write_field(&mut sym, spec, value).unwrap());
+
+        out.extend_from_slice(&sym);
+    }
+
+    pub fn parse(
+        data: &[u8],
+        ctx: &ParseContext,
+        section_header: &Elf64SHdr,
+    ) -> Result<Vec<Self>, ParseError> {
+        let off = section_header.offset as usize;
+        let siz = section_header.file_size() as usize;
+        let end = off.checked_add(siz).ok_or(ParseError::InvalidLength)?;
+
+        let section = data.get(off..end).ok_or(ParseError::InvalidLength)?;
+        let symbols = Elf64Sym::parse_table(section, ctx)?;
+
+        debug!(
+            "parsed {} symbols from {:#x}..{:#x}",
+            symbols.len(),
+            off,
+            end
+        );
+
+        if log_enabled!(log::Level::Trace) {
+            for (idx, sym) in symbols.iter().enumerate() {
+                trace!("symbol [{}]: {:?}", idx, sym);
+            }
+        }
+
+        Ok(symbols)
+    }
+
+    /// Parses every entry held by `table`, the contents of a symbol table
+    pub fn parse_table(table: &[u8], ctx: &ParseContext) -> Result<Vec<Self>, ParseError> {
+        Elf64Sym::parse_entries(table, ctx, 0)
+    }
+
+    /// Parses every entry held by `entries`, a slice of a symbol table starting with
+    /// entry `first`, which errors are reported against
+    pub fn parse_entries(
+        entries: &[u8],
+        ctx: &ParseContext,
+        first: usize,
+    ) -> Result<Vec<Self>, ParseError> {
+        let layout = layout::sym::for_class(ctx.class);
+
+        entries
+            .chunks_exact(layout.size)
+            .enumerate()
+            .map(|(idx, s)| {
+                let field = |spec| ctx.read_field(s, spec).map_err(|e| e.in_entry(first + idx));
+
+                Ok(Elf64Sym {
+                    name: field(layout.st_name)? as u32,
+                    info: field(layout.st_info)? as u8,
+                    other: field(layout.st_other)? as u8,
+                    shndx: field(layout.st_shndx)? as u16,
+                    value: Address(field(layout.st_value)?),
+                    size: field(layout.st_size)?,
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SymbolTable {
+    /// Index of the section header this table was read from
+    pub section_idx: usize,
+    /// Index of the section header holding the string table for the symbol names
+    pub link: u32,
+    /// Type of the section, either `SHT_SYMTAB` or `SHT_DYNSYM`
+    pub sh_type: u32,
+    /// Entries of the table, including the leading null symbol
+    pub symbols: Vec<Elf64Sym>,
+}
+
+pub fn bind_name(bind: u8) -> String {
+    match bind {
+        STB_LOCAL => "LOCAL".to_string(),
+        STB_GLOBAL => "GLOBAL".to_string(),
+        STB_WEAK => "WEAK".to_string(),
+        STB_GNU_UNIQUE => "UNIQUE".to_string(),
+        b => format!("<{}>", b),
+    }
+}
+
+pub fn type_name(sym_type: u8) -> String {
+    match sym_type {
+        STT_NOTYPE => "NOTYPE".to_string(),
+        STT_OBJECT => "OBJECT".to_string(),
+        STT_FUNC => "FUNC".to_string(),
+        STT_SECTION => "SECTION".to_string(),
+        STT_FILE => "FILE".to_string(),
+        STT_COMMON => "COMMON".to_string(),
+        STT_TLS => "TLS".to_string(),
+        STT_GNU_IFUNC => "IFUNC".to_string(),
+        t => format!("<{}>", t),
+    }
+}
+
+pub fn visibility_name(visibility: u8) -> &'static str {
+    match visibility {
+        STV_DEFAULT => "DEFAULT",
+        STV_INTERNAL => "INTERNAL",
+        STV_HIDDEN => "HIDDEN",
+        _ => "PROTECTED",
+    }
+}
+
+pub fn shndx_name(shndx: u16) -> String {
+    match shndx {
+        SHN_UNDEF => "UND".to_string(),
+        SHN_ABS => "ABS".to_string(),
+        SHN_COMMON => "COM".to_string(),
+        n => n.to_string(),
+    }
+}
+
+impl TableRow for Named<'_, Elf64Sym> {
+    const HEADER: &'static str = "Value            Size  Type    Bind   Vis      Ndx Name";
+}
+
+impl fmt::Display for Named<'_, Elf64Sym> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sym = self.inner;
+
+        write!(
+            f,
+            "{:016x} {:>5} {:<7} {:<6} {:<8} {:>3} {}",
+            sym.value,
+            sym.size,
+            type_name(sym.sym_type()),
+            bind_name(sym.bind()),
+            visibility_name(sym.visibility()),
+            shndx_name(sym.shndx),
+            self.name
+        )
+    }
+}
+
+impl fmt::Display for Elf64Sym {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Named::new("", self).fmt(f)
+    }
+}
@@ -0,0 +1,150 @@
+use crate::bytes::ParseContext;
+use crate::parser::ParseError;
+use log::debug;
+
+/// Version index of local symbols
+pub const VER_NDX_LOCAL: u16 = 0;
+/// Version index of global symbols not bound to any version
+pub const VER_NDX_GLOBAL: u16 = 1;
+/// Bit of a `.gnu.version` entry marking the symbol as hidden, only reachable through
+/// an explicit version
+pub const VERSYM_HIDDEN: u16 = 0x8000;
+
+/// The version definition describes the file itself rather than a version
+pub const VER_FLG_BASE: u16 = 0x1;
+/// The version dependency is weak
+pub const VER_FLG_WEAK: u16 = 0x2;
+
+/// A version defined by the file, entry of `.gnu.version_d`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verdef {
+    pub flags: u16,
+    /// Index `.gnu.version` entries refer to this version by
+    pub ndx: u16,
+    pub hash: u32,
+    /// Offsets into the dynamic string table of the version name, followed by the
+    /// names of the versions it inherits from
+    pub names: Vec<u32>,
+}
+
+/// A version required from another file, auxiliary entry of `.gnu.version_r`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vernaux {
+    pub hash: u32,
+    pub flags: u16,
+    /// Index `.gnu.version` entries refer to this version by
+    pub other: u16,
+    /// Offset into the dynamic string table of the version name
+    pub name: u32,
+}
+
+/// The versions required from a single file, entry of `.gnu.version_r`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verneed {
+    /// Offset into the dynamic string table of the name of the needed file
+    pub file: u32,
+    pub versions: Vec<Vernaux>,
+}
+
+/// Returns the offset `next` bytes past `off`, failing on chains that end early or
+/// run past the end of `data`
+fn advance(data: &[u8], off: usize, next: u32) -> Result<usize, ParseError> {
+    match off.checked_add(next as usize) {
+        Some(next_off) if next != 0 && next_off < data.len() => Ok(next_off),
+        _ => Err(ParseError::InvalidLength),
+    }
+}
+
+impl Verdef {
+    /// Parses the `count` chained definitions starting at the beginning of `data`
+    pub fn parse(data: &[u8], ctx: &ParseContext, count: u64) -> Result<Vec<Self>, ParseError> {
+        let reader = ctx.reader(data);
+        let mut defs = vec![];
+        let mut off = 0;
+
+        for idx in 0..count {
+            let mut def = reader.at(off);
+            // vd_version, only version 1 exists
+            def.skip(2)?;
+            let flags = def.u16()?;
+            let ndx = def.u16()?;
+            let cnt = def.u16()?;
+            let hash = def.u32()?;
+            let aux = def.u32()?;
+            let next = def.u32()?;
+
+            let mut names = vec![];
+            let mut aux_off = off
+                .checked_add(aux as usize)
+                .ok_or(ParseError::InvalidLength)?;
+            for aux_idx in 0..cnt {
+                let mut verdaux = reader.at(aux_off);
+                names.push(verdaux.u32()?);
+                if aux_idx + 1 < cnt {
+                    aux_off = advance(data, aux_off, verdaux.u32()?)?;
+                }
+            }
+
+            defs.push(Verdef {
+                flags,
+                ndx,
+                hash,
+                names,
+            });
+
+            if idx + 1 < count {
+                off = advance(data, off, next)?;
+            }
+        }
+
+        debug!("parsed {} version definitions", defs.len());
+
+        Ok(defs)
+    }
+}
+
+impl Verneed {
+    /// Parses the `count` chained dependencies starting at the beginning of `data`
+    pub fn parse(data: &[u8], ctx: &ParseContext, count: u64) -> Result<Vec<Self>, ParseError> {
+        let reader = ctx.reader(data);
+        let mut needs = vec![];
+        let mut off = 0;
+
+        for idx in 0..count {
+            let mut need = reader.at(off);
+            // vn_version, only version 1 exists
+            need.skip(2)?;
+            let cnt = need.u16()?;
+            let file = need.u32()?;
+            let aux = need.u32()?;
+            let next = need.u32()?;
+
+            let mut versions = vec![];
+            let mut aux_off = off
+                .checked_add(aux as usize)
+                .ok_or(ParseError::InvalidLength)?;
+            for aux_idx in 0..cnt {
+                let mut vernaux = reader.at(aux_off);
+                versions.push(Vernaux {
+                    hash: vernaux.u32()?,
+                    flags: vernaux.u16()?,
+                    other: vernaux.u16()?,
+                    name: vernaux.u32()?,
+                });
+                if aux_idx + 1 < cnt {
+                    aux_off = advance(data, aux_off, vernaux.u32()?)?;
+                }
+            }
+
+            needs.push(Verneed { file, versions });
+
+            if idx + 1 < count {
+                off = advance(data, off, next)?;
+            }
+        }
+
+        debug!("parsed {} version dependencies", needs.len());
+
+        Ok(needs)
+    }
+}
@@ -0,0 +1,142 @@
+//! Heuristic check of the encoding declared by the ident against the contents of the
+//! ELF header.
+//!
+//! Broken generators may write the ident of one encoding and the structures in
+//! another. The fields following the ident are decoded under every class and byte
+//! order, and each decoding is scored on how plausible the values it yields are: a
+//! known file type, machine and version, the header size of the class, and header
+//! tables of the expected entry size lying within the file. A declared encoding
+//! scoring well below another one most likely isn't the one the file was written in,
+//! and the file can be parsed again with `ParseOptions::force_class` and
+//! `ParseOptions::force_endianness` set to the suspected one.
+
+use crate::bytes::ParseContext;
+use crate::elf::ehdr::{machine_name, ElfHClass, ElfHData};
+use crate::elf::shdr::SHN_XINDEX;
+use crate::layout;
+use crate::parser::ElfParser;
+use log::debug;
+
+/// Score of a decoding yielding plausible values for every field checked
+pub const MAX_SCORE: u8 = 7;
+
+/// How much better than the declared encoding another one must score to be suspected,
+/// so that a single odd field doesn't trigger it
+const MISMATCH_MARGIN: u8 = 2;
+
+/// `ET_CORE`, the last of the generic file types
+const ET_CORE: u64 = 4;
+
+/// The declared encoding of a file, along with the one its header decodes best under
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct EncodingMismatch {
+    pub declared: ParseContext,
+    pub declared_score: u8,
+    pub suspected: ParseContext,
+    pub suspected_score: u8,
+}
+
+/// Whether a table of `num` entries of `ent_size` bytes at `off` is what a table with
+/// entries of `expected` bytes in a file of `len` bytes would look like
+fn plausible_table(off: u64, num: u64, ent_size: u64, expected: usize, len: usize) -> bool {
+    num == 0
+        || (ent_size == expected as u64
+            && off
+                .checked_add(num * ent_size)
+                .is_some_and(|end| end <= len as u64))
+}
+
+/// Scores out of `MAX_SCORE` how plausible the fields of the header of `data` are when
+/// decoded with `ctx`. 0 if `data` is too short for the header of the class
+pub fn plausibility(data: &[u8], ctx: ParseContext) -> u8 {
+    let eh = layout::ehdr::for_class(ctx.class);
+    if data.len() < eh.size {
+        return 0;
+    }
+    let field = |spec| ctx.read_field(data, spec).unwrap();
+    let ph_size = layout::phdr::for_class(ctx.class).size;
+    let sh_size = layout::shdr::for_class(ctx.class).size;
+    let sh_num = field(eh.e_shnum);
+    let sh_str_ndx = field(eh.e_shstrndx);
+    let machine = field(eh.e_machine) as u16;
+
+    [
+        (1..=ET_CORE).contains(&field(eh.e_type)),
+        machine != 0 && !machine_name(machine).starts_with('<'),
+        field(eh.e_version) == 1,
+        field(eh.e_ehsize) == eh.size as u64,
+        plausible_table(
+            field(eh.e_phoff),
+            field(eh.e_phnum),
+            field(eh.e_phentsize),
+            ph_size,
+            data.len(),
+        ),
+        plausible_table(
+            field(eh.e_shoff),
+            sh_num,
+            field(eh.e_shentsize),
+            sh_size,
+            data.len(),
+        ),
+        sh_str_ndx < sh_num.max(1) || sh_str_ndx == SHN_XINDEX as u64,
+    ]
+    .into_iter()
+    .filter(|plausible| *plausible)
+    .count() as u8
+}
+
+/// Scores the header of `data` under every encoding, and returns the best one if it
+/// scores well above `declared`. Ties are settled in favor of `declared`, then of the
+/// declared class
+pub fn detect_encoding_mismatch(data: &[u8], declared: ParseContext) -> Option<EncodingMismatch> {
+    let declared_score = plausibility(data, declared);
+
+    let candidates = [declared.class, other_class(declared.class)]
+        .into_iter()
+        .flat_map(|class| {
+            [ElfHData::ElfData2Lsb, ElfHData::ElfData2Msb]
+                .into_iter()
+                .map(move |endianness| ParseContext::new(endianness, class))
+        })
+        .filter(|ctx| *ctx != declared);
+
+    let (suspected, suspected_score) = candidates.map(|ctx| (ctx, plausibility(data, ctx))).fold(
+        None,
+        |best: Option<(ParseContext, u8)>, (ctx, score)| match best {
+            Some((_, best_score)) if best_score >= score => best,
+            _ => Some((ctx, score)),
+        },
+    )?;
+    debug!(
+        "header scores {}/{} as declared, {}/{} as {} {:?}",
+        declared_score,
+        MAX_SCORE,
+        suspected_score,
+        MAX_SCORE,
+        suspected.class,
+        suspected.endianness
+    );
+
+    (suspected_score >= declared_score + MISMATCH_MARGIN).then_some(EncodingMismatch {
+        declared,
+        declared_score,
+        suspected,
+        suspected_score,
+    })
+}
+
+fn other_class(class: ElfHClass) -> ElfHClass {
+    match class {
+        ElfHClass::ElfClass32 => ElfHClass::ElfClass64,
+        _ => ElfHClass::ElfClass32,
+    }
+}
+
+impl ElfParser<'_> {
+    /// Checks the encoding the file was parsed with against the contents of its
+    /// header, see `detect_encoding_mismatch`
+    pub fn detect_encoding_mismatch(&self) -> Option<EncodingMismatch> {
+        detect_encoding_mismatch(self.data(), self.ctx)
+    }
+}
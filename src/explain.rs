@@ -0,0 +1,403 @@
+//! Field by field account of the ELF header, for telling why a file is rejected.
+//!
+//! Every field of the identification and of the header is read on its own, through
+//! the same layouts the parser uses, so a field that can't be decoded doesn't
+//! prevent the others from being explained. Only when the identification gives no
+//! usable class or data encoding are the fields following it left out, as they
+//! can't be located nor decoded without them.
+
+use crate::bytes::ParseContext;
+use crate::elf::ehdr::{
+    flags_description, machine_name, os_abi_name, ElfHClass, ElfHData, ElfHType, ELF_MAGIC, EM_386,
+    EM_AARCH64, EM_ARM, EM_RISCV, EM_X86_64, IDENT_SZ,
+};
+use crate::elf::phdr::PN_XNUM;
+use crate::elf::shdr::SHN_XINDEX;
+use crate::elf::sym::SHN_UNDEF;
+use crate::layout::{self, FieldSpec};
+use crate::parser::ElfParser;
+use std::fmt;
+
+/// How a field fares against the specification
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Valid,
+    /// The value is allowed but unusual, or the parser only accepts it with some
+    /// `ParseOptions`
+    Suspicious,
+    /// The value breaks the specification, or the file ends before the field
+    Invalid,
+}
+
+/// What a field of the header holds and whether it's valid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    /// Name of the field, as the specification writes it
+    pub field: &'static str,
+    /// Offset of the field in the file
+    pub offset: usize,
+    /// Bytes of the field in file order, fewer than its size if the file ends first
+    pub raw: Vec<u8>,
+    /// Meaning of the value
+    pub decoded: String,
+    pub verdict: Verdict,
+    /// What the specification requires, for fields that aren't valid, or a remark
+    pub note: Option<String>,
+}
+
+impl Explanation {
+    pub fn is_valid(&self) -> bool {
+        self.verdict == Verdict::Valid
+    }
+}
+
+/// Outcome of checking a field: its meaning, verdict and note
+type Check = (String, Verdict, Option<String>);
+
+fn valid(decoded: impl Into<String>) -> Check {
+    (decoded.into(), Verdict::Valid, None)
+}
+
+fn suspicious(decoded: impl Into<String>, note: impl Into<String>) -> Check {
+    (decoded.into(), Verdict::Suspicious, Some(note.into()))
+}
+
+fn invalid(decoded: impl Into<String>, note: impl Into<String>) -> Check {
+    (decoded.into(), Verdict::Invalid, Some(note.into()))
+}
+
+/// Explanations of the fields of one file
+struct Explainer<'d> {
+    data: &'d [u8],
+    explanations: Vec<Explanation>,
+}
+
+impl Explainer<'_> {
+    /// Explains the field at `spec` through `check`, which is handed its bytes. A
+    /// field the file ends before is invalid without being checked
+    fn bytes(&mut self, field: &'static str, spec: FieldSpec, check: impl FnOnce(&[u8]) -> Check) {
+        let end = spec.end().min(self.data.len());
+        let raw = self.data.get(spec.offset..end).unwrap_or_default();
+
+        let (decoded, verdict, note) = match raw.len() == spec.size {
+            true => check(raw),
+            false => invalid(
+                "",
+                format!("the file ends at byte {} before the field", self.data.len()),
+            ),
+        };
+
+        self.explanations.push(Explanation {
+            field,
+            offset: spec.offset,
+            raw: raw.to_vec(),
+            decoded,
+            verdict,
+            note,
+        });
+    }
+
+    /// Explains the field at `spec` through `check`, which is handed its value
+    /// decoded with `ctx`
+    fn value(
+        &mut self,
+        ctx: &ParseContext,
+        field: &'static str,
+        spec: FieldSpec,
+        check: impl FnOnce(u64) -> Check,
+    ) {
+        let data = self.data;
+        self.bytes(field, spec, |_| match ctx.read_field(data, spec) {
+            Ok(value) => check(value),
+            Err(e) => invalid("", e.to_string()),
+        });
+    }
+
+    /// Value of the field at `spec`, if the file holds it
+    fn read(&self, ctx: &ParseContext, spec: FieldSpec) -> Option<u64> {
+        ctx.read_field(self.data, spec).ok()
+    }
+}
+
+/// Checks the table of `count` entries of `ent_size` bytes at `off` lies within a
+/// file of `file_len` bytes
+fn table_check(kind: &str, off: u64, count: u64, ent_size: u64, file_len: u64) -> Check {
+    let decoded = format!("{:#x}", off);
+    if count == 0 {
+        return match off {
+            0 => valid(format!("{}, no {} header table", decoded, kind)),
+            _ => suspicious(
+                decoded,
+                format!("set although the {} header count is 0", kind),
+            ),
+        };
+    }
+
+    match count
+        .checked_mul(ent_size)
+        .and_then(|size| size.checked_add(off))
+    {
+        Some(end) if end <= file_len => valid(format!("{}..{:#x}", decoded, end)),
+        _ => invalid(
+            decoded,
+            format!(
+                "the {} header table must lie within the {} bytes of the file",
+                kind, file_len
+            ),
+        ),
+    }
+}
+
+/// Checks the entry size of a table whose entries of its class take `expected` bytes
+fn entry_size_check(kind: &str, ent_size: u64, count: u64, expected: usize) -> Check {
+    let decoded = format!("{} bytes", ent_size);
+
+    match ent_size {
+        _ if count == 0 => valid(decoded),
+        0 => invalid(
+            decoded,
+            format!(
+                "must be {}, entries can't be walked without a size",
+                expected
+            ),
+        ),
+        s if s < expected as u64 => invalid(
+            decoded,
+            format!(
+                "must be at least {}, the size of a {} header of the class",
+                expected, kind
+            ),
+        ),
+        s if s > expected as u64 => suspicious(
+            decoded,
+            format!(
+                "{} header entries of the class take {} bytes",
+                kind, expected
+            ),
+        ),
+        _ => valid(decoded),
+    }
+}
+
+impl ElfParser<'_> {
+    /// Explains every field of the ELF identification and header of the file held by
+    /// `data`, in file order. Works on any file, including the ones the parser
+    /// rejects, see the module documentation
+    pub fn explain(data: &[u8]) -> Vec<Explanation> {
+        let mut ex = Explainer {
+            data,
+            explanations: vec![],
+        };
+        let byte = |offset| FieldSpec::new(offset, 1);
+
+        ex.bytes("e_ident[EI_MAG]", FieldSpec::new(0, 4), |mag| {
+            match mag == ELF_MAGIC {
+                true => valid("ELF magic number"),
+                false => invalid("not an ELF file", "must be 7f 45 4c 46 (\\x7fELF)"),
+            }
+        });
+        ex.bytes(
+            "e_ident[EI_CLASS]",
+            byte(4),
+            |b| match ElfHClass::try_from(b[0]) {
+                Ok(ElfHClass::_ElfClassIn) | Err(_) => {
+                    invalid("invalid class", "must be 1 (ELF32) or 2 (ELF64)")
+                }
+                Ok(class) => valid(class.to_string()),
+            },
+        );
+        ex.bytes("e_ident[EI_DATA]", byte(5), |b| {
+            match ElfHData::try_from(b[0]) {
+                Ok(data) => valid(data.to_string()),
+                _ => invalid("invalid encoding", "must be 1 (LSB) or 2 (MSB)"),
+            }
+        });
+        ex.bytes("e_ident[EI_VERSION]", byte(6), |b| match b[0] {
+            1 => valid("current"),
+            v => invalid(v.to_string(), "must be 1 (EV_CURRENT)"),
+        });
+        ex.bytes("e_ident[EI_OSABI]", byte(7), |b| {
+            let name = os_abi_name(b[0]);
+            match name.starts_with("<unknown") {
+                true => suspicious(name, "not an OS/ABI the parser knows of"),
+                false => valid(name),
+            }
+        });
+        let os_abi = data.get(7).copied();
+        ex.bytes("e_ident[EI_ABIVERSION]", byte(8), |b| {
+            match (os_abi, b[0]) {
+                (Some(0), v) if v != 0 => suspicious(
+                    v.to_string(),
+                    "should be 0, as EI_OSABI declares no OS/ABI extensions",
+                ),
+                (_, v) => valid(v.to_string()),
+            }
+        });
+        ex.bytes(
+            "e_ident[EI_PAD]",
+            FieldSpec::new(9, IDENT_SZ - 9),
+            |pad| match pad.iter().all(|b| *b == 0) {
+                true => valid("zero"),
+                false => suspicious("not zero", "reserved, should be zero"),
+            },
+        );
+
+        let class = data
+            .get(4)
+            .and_then(|b| ElfHClass::try_from(*b).ok())
+            .filter(|c| *c != ElfHClass::_ElfClassIn);
+        let endianness = data.get(5).and_then(|b| ElfHData::try_from(*b).ok());
+        let (Some(class), Some(endianness)) = (class, endianness) else {
+            return ex.explanations;
+        };
+
+        let ctx = ParseContext { endianness, class };
+        let layout = layout::ehdr::for_class(class);
+        let file_len = data.len() as u64;
+        let read = |spec| ex.read(&ctx, spec);
+        let machine = read(layout.e_machine);
+        let (ph_ent_size, ph_num) = (read(layout.e_phentsize), read(layout.e_phnum));
+        let (sh_off, sh_ent_size, sh_num) = (
+            read(layout.e_shoff),
+            read(layout.e_shentsize),
+            read(layout.e_shnum),
+        );
+
+        ex.value(&ctx, "e_type", layout.e_type, |v| match v {
+            0 => invalid("NONE", "must be 1 (REL), 2 (EXEC), 3 (DYN) or 4 (CORE)"),
+            0xfe00..=0xfeff => suspicious(
+                format!("OS specific ({:#x})", v),
+                "OS specific types aren't parsed",
+            ),
+            0xff00..=0xffff => suspicious(
+                format!("processor specific ({:#x})", v),
+                "processor specific types aren't parsed",
+            ),
+            v => match ElfHType::try_from(v as u16) {
+                Ok(e_type) => valid(e_type.to_string()),
+                Err(_) => invalid(
+                    format!("{:#x}", v),
+                    "must be 1 (REL), 2 (EXEC), 3 (DYN) or 4 (CORE)",
+                ),
+            },
+        });
+        ex.value(&ctx, "e_machine", layout.e_machine, |v| {
+            let name = machine_name(v as u16);
+            match v as u16 {
+                EM_RISCV => valid(name),
+                EM_386 | EM_ARM | EM_X86_64 | EM_AARCH64 | 8 => {
+                    suspicious(name, "only the headers of non RISC-V files are decoded")
+                }
+                0 => invalid(name, "must name the architecture of the file"),
+                _ => suspicious(name, "not a machine the parser knows of"),
+            }
+        });
+        ex.value(&ctx, "e_version", layout.e_version, |v| match v {
+            1 => valid("current"),
+            v => suspicious(v.to_string(), "should be 1 (EV_CURRENT), as EI_VERSION"),
+        });
+        ex.value(&ctx, "e_entry", layout.e_entry, |v| match v {
+            0 => valid("0, no entry point"),
+            v => valid(format!("{:#x}", v)),
+        });
+        ex.value(&ctx, "e_phoff", layout.e_phoff, |off| {
+            let ent_size = ph_ent_size.unwrap_or(0);
+            match ph_num {
+                Some(n) if n == PN_XNUM as u64 => {
+                    valid(format!("{:#x}, count held by section 0", off))
+                }
+                n => table_check("program", off, n.unwrap_or(0), ent_size, file_len),
+            }
+        });
+        ex.value(&ctx, "e_shoff", layout.e_shoff, |off| {
+            table_check(
+                "section",
+                off,
+                sh_num.unwrap_or(0),
+                sh_ent_size.unwrap_or(0),
+                file_len,
+            )
+        });
+        ex.value(&ctx, "e_flags", layout.e_flags, |v| {
+            valid(flags_description(machine.unwrap_or(0) as u16, v as u32))
+        });
+        ex.value(&ctx, "e_ehsize", layout.e_ehsize, |v| {
+            let decoded = format!("{} bytes", v);
+            match v.cmp(&(layout.size as u64)) {
+                std::cmp::Ordering::Equal => valid(decoded),
+                _ => suspicious(
+                    decoded,
+                    format!("the header of the class takes {} bytes", layout.size),
+                ),
+            }
+        });
+        let phdr_size = layout::phdr::for_class(class).size;
+        ex.value(&ctx, "e_phentsize", layout.e_phentsize, |v| {
+            entry_size_check("program", v, ph_num.unwrap_or(0), phdr_size)
+        });
+        ex.value(&ctx, "e_phnum", layout.e_phnum, |v| match v {
+            0 => valid("0, no program header table"),
+            v if v == PN_XNUM as u64 => valid("PN_XNUM, held by the sh_info of section 0"),
+            v => valid(v.to_string()),
+        });
+        let shdr_size = layout::shdr::for_class(class).size;
+        ex.value(&ctx, "e_shentsize", layout.e_shentsize, |v| {
+            entry_size_check("section", v, sh_num.unwrap_or(0), shdr_size)
+        });
+        ex.value(&ctx, "e_shnum", layout.e_shnum, |v| match (v, sh_off) {
+            (0, Some(off)) if off != 0 => valid("0, count held by the sh_size of section 0"),
+            (0, _) => valid("0, no section header table"),
+            (v, _) => valid(v.to_string()),
+        });
+        ex.value(&ctx, "e_shstrndx", layout.e_shstrndx, |v| match v as u16 {
+            SHN_UNDEF => valid("SHN_UNDEF, no section names"),
+            SHN_XINDEX => valid("SHN_XINDEX, held by the sh_link of section 0"),
+            _ => match sh_num {
+                Some(n) if n != 0 && v >= n => invalid(
+                    v.to_string(),
+                    format!("must be below e_shnum ({}) or SHN_XINDEX", n),
+                ),
+                _ => valid(v.to_string()),
+            },
+        });
+
+        ex.explanations
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Verdict::Valid => "valid",
+            Verdict::Suspicious => "suspicious",
+            Verdict::Invalid => "invalid",
+        })
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let raw = match self.raw.as_slice() {
+            [b] => format!("{:#04x}", b),
+            bytes => bytes
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<Vec<_>>()
+                .join(" "),
+        };
+
+        write!(f, "{}", self.field)?;
+        if !raw.is_empty() {
+            write!(f, " = {}", raw)?;
+        }
+        if !self.decoded.is_empty() {
+            write!(f, " ({})", self.decoded)?;
+        }
+        write!(f, ": {}", self.verdict)?;
+        if let Some(note) = &self.note {
+            write!(f, ", {}", note)?;
+        }
+
+        Ok(())
+    }
+}
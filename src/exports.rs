@@ -0,0 +1,105 @@
+use crate::dynamic::SymbolVersion;
+use crate::elf::sym::{
+    Elf64Sym, SHN_ABS, STB_GLOBAL, STB_GNU_UNIQUE, STB_WEAK, STV_DEFAULT, STV_PROTECTED,
+};
+use crate::parser::{ElfParser, ParseError};
+
+/// A symbol the file provides to the files linking against it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Export {
+    pub name: String,
+    pub address: u64,
+    pub size: u64,
+    pub sym_type: u8,
+    pub binding: u8,
+    /// Version the symbol is defined at, if the file versions its symbols
+    pub version: Option<String>,
+    /// The version is the default one, used when linking without naming a version
+    pub default_version: bool,
+}
+
+/// A symbol the file expects another file to provide
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Import {
+    pub name: String,
+    pub sym_type: u8,
+    pub binding: u8,
+    /// Version required for the symbol, if any
+    pub version: Option<String>,
+    /// File the version is required from, the best hint of which of the needed
+    /// libraries provides the symbol
+    pub library: Option<String>,
+}
+
+impl ElfParser<'_> {
+    /// Dynamic symbols along with their names and versions, skipping the null one
+    fn versioned_dynamic_symbols(
+        &self,
+    ) -> Result<Vec<(String, Elf64Sym, Option<SymbolVersion>)>, ParseError> {
+        let symbols = self.dynamic_symbol_table()?;
+        let versions = self.symbol_versions()?;
+
+        symbols
+            .into_iter()
+            .zip(versions)
+            .skip(1)
+            .map(|(sym, version)| Ok((self.dynamic_string(sym.name)?, sym, version)))
+            .collect()
+    }
+
+    /// Returns the defined global and weak dynamic symbols with default or protected
+    /// visibility, sorted by name and version and without duplicates. The absolute
+    /// symbols the linker emits to name each version definition are left out
+    pub fn exports(&self) -> Result<Vec<Export>, ParseError> {
+        let mut exports: Vec<Export> = self
+            .versioned_dynamic_symbols()?
+            .into_iter()
+            .filter(|(name, sym, version)| {
+                let names_version =
+                    sym.shndx == SHN_ABS && version.as_ref().is_some_and(|v| v.name == *name);
+
+                !name.is_empty()
+                    && !names_version
+                    && !sym.is_undefined()
+                    && [STB_GLOBAL, STB_WEAK, STB_GNU_UNIQUE].contains(&sym.bind())
+                    && [STV_DEFAULT, STV_PROTECTED].contains(&sym.visibility())
+            })
+            .map(|(name, sym, version)| Export {
+                name,
+                address: sym.value.0,
+                size: sym.size,
+                sym_type: sym.sym_type(),
+                binding: sym.bind(),
+                default_version: version.as_ref().is_some_and(|v| !v.hidden),
+                version: version.map(|v| v.name),
+            })
+            .collect();
+
+        exports.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+        exports.dedup();
+
+        Ok(exports)
+    }
+
+    /// Returns the undefined dynamic symbols, sorted by name and version and without
+    /// duplicates
+    pub fn imports(&self) -> Result<Vec<Import>, ParseError> {
+        let mut imports: Vec<Import> = self
+            .versioned_dynamic_symbols()?
+            .into_iter()
+            .filter(|(name, sym, _)| !name.is_empty() && sym.is_undefined())
+            .map(|(name, sym, version)| Import {
+                name,
+                sym_type: sym.sym_type(),
+                binding: sym.bind(),
+                library: version.as_ref().and_then(|v| v.library.clone()),
+                version: version.map(|v| v.name),
+            })
+            .collect();
+
+        imports.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+        imports.dedup();
+
+        Ok(imports)
+    }
+}
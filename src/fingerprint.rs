@@ -0,0 +1,262 @@
+//! Identity of a file by its contents, ignoring the bytes that change between builds of
+//! the same code without changing what it does.
+//!
+//! The fingerprint is the SHA-256 digest of, in this order:
+//!
+//! - the identification and the header fields: class, data encoding, OS/ABI and its
+//!   version, `e_type`, `e_machine`, `e_version`, `e_entry` and `e_flags`. The table
+//!   offsets, entry sizes and counts and `e_shstrndx` are left out, as they move
+//!   with the sections that aren't hashed
+//! - every `SHF_ALLOC` section, in table order: its name, type, flags, address, size,
+//!   alignment and contents, but no contents for `SHT_NOBITS` sections. The notes of
+//!   `SHT_NOTE` sections are hashed one by one, owner, type and descriptor, leaving
+//!   out `NT_GNU_BUILD_ID` notes, which are derived from the whole file
+//! - the file image of every `PT_LOAD` segment, only for files without section headers
+//! - the dynamic entries, tag and value, in file order
+//! - the symbols of every symbol table, in table order: name, value, size, type,
+//!   binding, visibility and whether it's defined. `STT_FILE` and `STT_SECTION`
+//!   symbols are left out, as they name build paths and sections that aren't hashed
+//!
+//! Sections without `SHF_ALLOC`, such as `.comment`, the `.debug_*` sections and the
+//! string tables, only count through the names they give, so rebuilding the same
+//! objects from another directory or with another compiler banner yields the same
+//! fingerprint. Every value is hashed along with its size, so that moving bytes from
+//! one field to the next changes the fingerprint.
+
+use crate::elf::note::{Elf64Note, NT_GNU_BUILD_ID};
+use crate::elf::phdr::PType;
+use crate::elf::shdr::{SHF_ALLOC, SHT_NOBITS, SHT_NOTE};
+use crate::elf::sym::{STT_FILE, STT_SECTION};
+use crate::parser::{ElfParser, ParseError};
+use std::fmt;
+
+/// Digest of the contents of a file, see the module documentation
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Fingerprint(pub [u8; 32]);
+
+const SHA256_K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// SHA-256 of the bytes fed to it, which `Fingerprint` is computed with
+struct Sha256 {
+    state: [u32; 8],
+    block: Vec<u8>,
+    len: u64,
+}
+
+impl Sha256 {
+    fn new() -> Self {
+        Sha256 {
+            state: [
+                0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+                0x5be0cd19,
+            ],
+            block: Vec::with_capacity(64),
+            len: 0,
+        }
+    }
+
+    fn compress(&mut self, block: &[u8]) {
+        let mut w = [0u32; 64];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes(word.try_into().unwrap());
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = self.state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(SHA256_K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+
+        for (s, v) in self.state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *s = s.wrapping_add(v);
+        }
+    }
+
+    fn update(&mut self, mut data: &[u8]) {
+        self.len += data.len() as u64;
+
+        while !data.is_empty() {
+            let take = (64 - self.block.len()).min(data.len());
+            self.block.extend_from_slice(&data[..take]);
+            data = &data[take..];
+
+            if self.block.len() == 64 {
+                let block = std::mem::take(&mut self.block);
+                self.compress(&block);
+                self.block = block;
+                self.block.clear();
+            }
+        }
+    }
+
+    fn finish(mut self) -> [u8; 32] {
+        let bits = self.len.wrapping_mul(8);
+        self.update(&[0x80]);
+        while self.block.len() != 56 {
+            self.update(&[0]);
+        }
+        self.update(&bits.to_be_bytes());
+
+        let mut digest = [0u8; 32];
+        for (out, s) in digest.chunks_exact_mut(4).zip(self.state) {
+            out.copy_from_slice(&s.to_be_bytes());
+        }
+        digest
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.update(&value.to_le_bytes());
+    }
+
+    /// Feeds `bytes` preceded by their length, so that consecutive values can't be
+    /// confused with one another
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.u64(bytes.len() as u64);
+        self.update(bytes);
+    }
+}
+
+/// SHA-256 digest of `data`
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    hasher.finish()
+}
+
+impl ElfParser<'_> {
+    /// Digest of what the file does, stable across rebuilds that only change the bytes
+    /// the module documentation lists as left out
+    pub fn fingerprint(&self) -> Result<Fingerprint, ParseError> {
+        let mut hasher = Sha256::new();
+        let headers = &self.headers;
+        let ident = &headers.ident;
+
+        for value in [
+            ident.class as u64,
+            ident.data as u64,
+            ident.os_abi as u64,
+            ident.abi_version as u64,
+            headers.e_type as u64,
+            headers.machine as u64,
+            headers.version.value() as u64,
+            headers.entry.0,
+            headers.flags as u64,
+        ] {
+            hasher.u64(value);
+        }
+
+        for (idx, sh) in self.section_headers.iter().enumerate() {
+            if sh.flags & SHF_ALLOC == 0 {
+                continue;
+            }
+
+            hasher.bytes(self.section_name(sh).unwrap_or_default().as_bytes());
+            for value in [
+                sh.s_type as u64,
+                sh.flags,
+                sh.addr.0,
+                sh.size,
+                sh.addr_align,
+            ] {
+                hasher.u64(value);
+            }
+
+            match sh.s_type {
+                SHT_NOBITS => {}
+                SHT_NOTE => {
+                    let data = self.section_data(idx)?;
+                    let notes = Elf64Note::parse(data, &self.ctx, sh.addr_align as usize)?;
+                    for note in notes
+                        .iter()
+                        .filter(|n| !(n.name == "GNU" && n.n_type == NT_GNU_BUILD_ID))
+                    {
+                        hasher.bytes(note.name.as_bytes());
+                        hasher.u64(note.n_type as u64);
+                        hasher.bytes(&note.desc);
+                    }
+                }
+                _ => hasher.bytes(self.section_data(idx)?),
+            }
+        }
+
+        if self.section_headers.is_empty() {
+            for (idx, ph) in self.program_headers.iter().enumerate() {
+                if ph.p_type == PType::PtLoad {
+                    hasher.u64(ph.vaddr.0);
+                    hasher.u64(ph.memsz);
+                    hasher.bytes(self.segment_data(idx)?);
+                }
+            }
+        }
+
+        for entry in self.dynamic_entries().unwrap_or_default() {
+            hasher.u64(entry.d_tag as u64);
+            hasher.u64(entry.value());
+        }
+
+        for table in &self.symbol_tables {
+            for sym in &table.symbols {
+                if matches!(sym.sym_type(), STT_FILE | STT_SECTION) {
+                    continue;
+                }
+
+                hasher.bytes(self.symbol_name(table, sym).unwrap_or_default().as_bytes());
+                for value in [
+                    sym.value.0,
+                    sym.size,
+                    sym.info as u64,
+                    sym.other as u64,
+                    sym.is_undefined() as u64,
+                ] {
+                    hasher.u64(value);
+                }
+            }
+        }
+
+        Ok(Fingerprint(hasher.finish()))
+    }
+}
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for b in self.0 {
+            write!(f, "{:02x}", b)?;
+        }
+
+        Ok(())
+    }
+}
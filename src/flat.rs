@@ -0,0 +1,120 @@
+use crate::elf::phdr::PType;
+use crate::parser::{ElfParser, ParseError};
+use log::debug;
+use std::ops::Range;
+
+/// Largest image `ElfParser::to_flat_binary` builds by default, beyond which the
+/// loadable segments are most likely meant for distinct memories
+pub const DEFAULT_MAX_FLAT_SIZE: u64 = 256 << 20;
+
+/// Controls how `ElfParser::to_flat_binary_with` lays out the image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlatOptions {
+    /// Byte written between segments, e.g. 0xff to leave NOR flash erased
+    pub gap_fill: u8,
+    /// Physical address range to restrict the image to, segments are clipped to it
+    pub range: Option<Range<u64>>,
+    /// Largest span of physical addresses the image may cover, in bytes
+    pub max_size: u64,
+}
+
+impl Default for FlatOptions {
+    fn default() -> Self {
+        FlatOptions {
+            gap_fill: 0,
+            range: None,
+            max_size: DEFAULT_MAX_FLAT_SIZE,
+        }
+    }
+}
+
+impl FlatOptions {
+    pub fn new() -> Self {
+        FlatOptions::default()
+    }
+
+    pub fn gap_fill(mut self, gap_fill: u8) -> Self {
+        self.gap_fill = gap_fill;
+        self
+    }
+
+    pub fn range(mut self, range: Range<u64>) -> Self {
+        self.range = Some(range);
+        self
+    }
+
+    pub fn max_size(mut self, max_size: u64) -> Self {
+        self.max_size = max_size;
+        self
+    }
+}
+
+/// Memory image laid out by physical address, as written to flash
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlatImage {
+    /// Physical address of the first byte of `data`
+    pub base: u64,
+    pub data: Vec<u8>,
+}
+
+impl ElfParser<'_> {
+    pub fn to_flat_binary(&self) -> Result<FlatImage, ParseError> {
+        self.to_flat_binary_with(&FlatOptions::default())
+    }
+
+    /// Places the file image of every loadable segment at its physical address. The
+    /// zero-initialized tail of the segments isn't part of the image. Unlike
+    /// `objcopy -O binary`, which copies sections, the image starts with the first
+    /// segment, along with the ELF header when the segment maps it
+    pub fn to_flat_binary_with(&self, options: &FlatOptions) -> Result<FlatImage, ParseError> {
+        let range = options.range.clone().unwrap_or(0..u64::MAX);
+
+        // physical range and file offset of the part of each segment within `range`
+        let mut pieces = vec![];
+        for ph in &self.program_headers {
+            if ph.p_type != PType::PtLoad || ph.filesz == 0 {
+                continue;
+            }
+
+            let paddr = ph.paddr.0;
+            let end = paddr
+                .checked_add(ph.filesz)
+                .ok_or(ParseError::InvalidLength)?;
+            let start = paddr.max(range.start);
+            let end = end.min(range.end);
+            if start < end {
+                let offset = ph
+                    .offset
+                    .checked_add(start - paddr)
+                    .ok_or(ParseError::InvalidLength)?;
+                pieces.push((start, end, offset));
+            }
+        }
+
+        let Some(base) = pieces.iter().map(|p| p.0).min() else {
+            return Ok(FlatImage {
+                base: range.start,
+                data: vec![],
+            });
+        };
+        let end = pieces.iter().map(|p| p.1).max().unwrap_or(base);
+        if end - base > options.max_size {
+            return Err(ParseError::FlatImageTooLarge { base, end });
+        }
+
+        let mut data = vec![options.gap_fill; (end - base) as usize];
+        for (start, end, offset) in pieces {
+            let offset = offset as usize;
+            let bytes = offset
+                .checked_add((end - start) as usize)
+                .and_then(|end| self.data().get(offset..end))
+                .ok_or(ParseError::InvalidLength)?;
+            let at = (start - base) as usize;
+            data[at..at + bytes.len()].copy_from_slice(bytes);
+        }
+
+        debug!("flat image of {} bytes from paddr {:#x}", data.len(), base);
+
+        Ok(FlatImage { base, data })
+    }
+}
@@ -0,0 +1,145 @@
+use crate::bytes::ParseContext;
+use crate::elf::ehdr::Elf64Hdr;
+use crate::elf::shdr::Elf64SHdr;
+use crate::parser::{ElfParser, ParseError};
+use crate::section::glob_match;
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+
+/// Value produced by a `SectionHandler`
+pub type ParsedSection = Box<dyn Any + Send + Sync>;
+
+/// Decodes the contents of a section into a value of its own type
+pub type SectionHandler = fn(&SectionInput) -> Result<ParsedSection, ParseError>;
+
+/// Everything a handler gets to decode a section with
+#[derive(Debug, Copy, Clone)]
+pub struct SectionInput<'a> {
+    /// Index of the section header
+    pub idx: usize,
+    pub header: &'a Elf64SHdr,
+    /// File image of the section, empty for sections that occupy no file space
+    pub data: &'a [u8],
+    pub ctx: &'a ParseContext,
+    /// Headers of the file and every section, for handlers whose decoding depends on
+    /// other sections
+    pub headers: &'a Elf64Hdr,
+    pub section_headers: &'a [Elf64SHdr],
+}
+
+/// Sections a handler is invoked on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SectionMatcher {
+    /// Sections whose name matches a glob pattern, as `glob_match` does
+    Name(String),
+    /// Sections of a `SHT_*` type
+    Type(u32),
+}
+
+impl SectionMatcher {
+    pub fn matches(&self, name: Option<&str>, header: &Elf64SHdr) -> bool {
+        match self {
+            SectionMatcher::Name(pattern) => name.is_some_and(|n| glob_match(pattern, n)),
+            SectionMatcher::Type(s_type) => header.s_type == *s_type,
+        }
+    }
+}
+
+/// Handlers invoked on the matching sections while a file is parsed, in the order they
+/// were registered. Every handler matching a section is invoked on it
+#[derive(Clone, Default)]
+pub struct SectionHandlers {
+    handlers: Vec<(SectionMatcher, SectionHandler)>,
+}
+
+impl SectionHandlers {
+    pub fn new() -> Self {
+        SectionHandlers::default()
+    }
+
+    pub fn register(mut self, matcher: SectionMatcher, handler: SectionHandler) -> Self {
+        self.handlers.push((matcher, handler));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+
+    /// Handlers matching a section, by its name, if it resolves, and header
+    pub fn matching<'a>(
+        &'a self,
+        name: Option<&'a str>,
+        header: &'a Elf64SHdr,
+    ) -> impl Iterator<Item = SectionHandler> + 'a {
+        self.handlers
+            .iter()
+            .filter(move |(matcher, _)| matcher.matches(name, header))
+            .map(|(_, handler)| *handler)
+    }
+}
+
+impl fmt::Debug for SectionHandlers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.handlers.iter().map(|(matcher, _)| matcher))
+            .finish()
+    }
+}
+
+/// Compares the matchers only, as function pointers can't be compared reliably
+impl PartialEq for SectionHandlers {
+    fn eq(&self, other: &Self) -> bool {
+        self.handlers
+            .iter()
+            .map(|(matcher, _)| matcher)
+            .eq(other.handlers.iter().map(|(matcher, _)| matcher))
+    }
+}
+
+impl Eq for SectionHandlers {}
+
+/// Value decoded by a handler, along with the section it was decoded from
+#[derive(Debug, Clone)]
+pub struct SectionValue {
+    pub idx: usize,
+    pub value: Arc<dyn Any + Send + Sync>,
+}
+
+impl ElfParser<'_> {
+    /// Invokes `handler` on the matching sections of an already parsed file, keeping
+    /// its results along with those of the handlers given through `ParseOptions`
+    pub fn register_section_handler(&mut self, matcher: SectionMatcher, handler: SectionHandler) {
+        let mut warnings = vec![];
+        let values = ElfParser::run_section_handlers(
+            self.data(),
+            &self.ctx,
+            &self.headers,
+            &self.section_headers,
+            self.header_string_table_idx
+                .and_then(|idx| self.string_tables.get(idx)),
+            &SectionHandlers::new().register(matcher, handler),
+            &mut warnings,
+        );
+
+        self.parsed_sections.extend(values);
+        self.warnings.extend(warnings);
+    }
+
+    /// Value of type `T` decoded from the section at `idx` by a registered handler
+    pub fn parsed_section<T: Any>(&self, idx: usize) -> Option<&T> {
+        self.parsed_sections
+            .iter()
+            .filter(|v| v.idx == idx)
+            .find_map(|v| v.value.downcast_ref())
+    }
+
+    /// Every value of type `T` decoded by the registered handlers, with the index of
+    /// the section it was decoded from
+    pub fn parsed_sections_of<T: Any>(&self) -> impl Iterator<Item = (usize, &T)> {
+        self.parsed_sections
+            .iter()
+            .filter_map(|v| Some((v.idx, v.value.downcast_ref()?)))
+    }
+}
@@ -0,0 +1,168 @@
+use crate::elf::phdr::PType;
+use crate::parser::ElfParser;
+use log::debug;
+use std::fmt::Write;
+
+/// Number of data bytes per record emitted by `to_ihex` and `to_srec`
+pub const DEFAULT_RECORD_LEN: usize = 16;
+
+/// Two's complement of the sum of the bytes, the checksum of Intel HEX records
+fn ihex_checksum(bytes: &[u8]) -> u8 {
+    bytes
+        .iter()
+        .fold(0u8, |sum, b| sum.wrapping_add(*b))
+        .wrapping_neg()
+}
+
+/// Ones' complement of the sum of the bytes, the checksum of S-records
+fn srec_checksum(bytes: &[u8]) -> u8 {
+    !bytes.iter().fold(0u8, |sum, b| sum.wrapping_add(*b))
+}
+
+/// Appends `:LLAAAATT<data>CC`
+fn ihex_record(out: &mut String, address: u16, r_type: u8, data: &[u8]) {
+    let mut record = vec![data.len() as u8];
+    record.extend(address.to_be_bytes());
+    record.push(r_type);
+    record.extend(data);
+    record.push(ihex_checksum(&record));
+
+    out.push(':');
+    record
+        .iter()
+        .for_each(|b| write!(out, "{:02X}", b).unwrap());
+    out.push('\n');
+}
+
+/// Appends `S<type><count><address><data><checksum>`, with an address of `addr_len`
+/// bytes
+fn srec_record(out: &mut String, s_type: u8, addr_len: usize, address: u64, data: &[u8]) {
+    let mut record = vec![(addr_len + data.len() + 1) as u8];
+    record.extend(&address.to_be_bytes()[8 - addr_len..]);
+    record.extend(data);
+    record.push(srec_checksum(&record));
+
+    write!(out, "S{}", s_type).unwrap();
+    record
+        .iter()
+        .for_each(|b| write!(out, "{:02X}", b).unwrap());
+    out.push('\n');
+}
+
+impl ElfParser<'_> {
+    /// Physical address and file bytes of every loadable segment holding any, the part
+    /// of a segment reaching past the end of the file being left out
+    fn load_images(&self) -> Vec<(u64, &[u8])> {
+        self.program_headers
+            .iter()
+            .filter(|ph| ph.p_type == PType::PtLoad && ph.filesz > 0)
+            .filter_map(|ph| {
+                let start = (ph.offset as usize).min(self.data().len());
+                let end = start
+                    .saturating_add(ph.filesz as usize)
+                    .min(self.data().len());
+
+                Some((ph.paddr.0, self.data().get(start..end)?))
+            })
+            .collect()
+    }
+
+    pub fn to_ihex(&self) -> String {
+        self.to_ihex_with(DEFAULT_RECORD_LEN)
+    }
+
+    /// Emits the file image of every loadable segment at its physical address as Intel
+    /// HEX records of up to `record_len` bytes, between 1 and 255. Addresses above
+    /// 64 KiB are reached through extended linear address records, and addresses are
+    /// truncated to the 32 bits the format can hold. The start linear address record
+    /// carries the entry point
+    pub fn to_ihex_with(&self, record_len: usize) -> String {
+        let record_len = record_len.clamp(1, 0xff);
+        let mut out = String::new();
+        let mut upper = None;
+
+        for (paddr, bytes) in self.load_images() {
+            let mut off = 0;
+            while off < bytes.len() {
+                let address = paddr.wrapping_add(off as u64) as u32;
+                if upper != Some(address >> 16) {
+                    upper = Some(address >> 16);
+                    ihex_record(&mut out, 0, 0x04, &((address >> 16) as u16).to_be_bytes());
+                }
+
+                // records don't cross 64 KiB boundaries, as the offset would wrap
+                let to_boundary = 0x10000 - (address & 0xffff) as usize;
+                let len = record_len.min(to_boundary).min(bytes.len() - off);
+                ihex_record(&mut out, address as u16, 0x00, &bytes[off..off + len]);
+                off += len;
+            }
+        }
+
+        ihex_record(
+            &mut out,
+            0,
+            0x05,
+            &(self.headers.entry.0 as u32).to_be_bytes(),
+        );
+        ihex_record(&mut out, 0, 0x01, &[]);
+
+        debug!("emitted {} bytes of Intel HEX", out.len());
+
+        out
+    }
+
+    pub fn to_srec(&self) -> String {
+        self.to_srec_with(DEFAULT_RECORD_LEN)
+    }
+
+    /// Emits the file image of every loadable segment at its physical address as
+    /// Motorola S-records of up to `record_len` bytes. Data records use the smallest of
+    /// the 16, 24 and 32-bit address forms fitting every address, and addresses are
+    /// truncated to 32 bits. The file ends with a record count and a termination record
+    /// carrying the entry point
+    pub fn to_srec_with(&self, record_len: usize) -> String {
+        let images = self.load_images();
+        let highest = images
+            .iter()
+            .map(|(paddr, bytes)| paddr.saturating_add(bytes.len() as u64).saturating_sub(1))
+            .chain(std::iter::once(self.headers.entry.0))
+            .max()
+            .unwrap_or(0);
+        let (data_type, end_type, addr_len) = match highest {
+            0..=0xffff => (1, 9, 2),
+            0x10000..=0xffffff => (2, 8, 3),
+            _ => (3, 7, 4),
+        };
+        // the count byte covers the address and the checksum too
+        let record_len = record_len.clamp(1, 0xff - addr_len - 1);
+
+        let mut out = String::new();
+        srec_record(&mut out, 0, 2, 0, &[]);
+
+        let mut count = 0u64;
+        for (paddr, bytes) in images {
+            for (idx, chunk) in bytes.chunks(record_len).enumerate() {
+                let address = paddr.wrapping_add((idx * record_len) as u64) & 0xffff_ffff;
+                srec_record(&mut out, data_type, addr_len, address, chunk);
+                count += 1;
+            }
+        }
+
+        match count {
+            0..=0xffff => srec_record(&mut out, 5, 2, count, &[]),
+            0x10000..=0xffffff => srec_record(&mut out, 6, 3, count, &[]),
+            _ => {}
+        }
+        srec_record(
+            &mut out,
+            end_type,
+            addr_len,
+            self.headers.entry.0 & 0xffff_ffff,
+            &[],
+        );
+
+        debug!("emitted {} S-records", count);
+
+        out
+    }
+}
@@ -0,0 +1,101 @@
+use crate::bytes::Address;
+use crate::elf::init_array;
+use crate::elf::phdr::{
+    DT_FINI_ARRAY, DT_FINI_ARRAYSZ, DT_INIT_ARRAY, DT_INIT_ARRAYSZ, DT_PREINIT_ARRAY,
+    DT_PREINIT_ARRAYSZ,
+};
+use crate::elf::shdr::{SHT_FINI_ARRAY, SHT_INIT_ARRAY, SHT_PREINIT_ARRAY};
+use crate::parser::{ElfParser, ParseError};
+use log::debug;
+
+/// Arrays of functions run by the dynamic linker or the C runtime around `main`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FunctionArray {
+    /// Run before the initializers of any shared object, only for executables
+    PreInit,
+    Init,
+    Fini,
+}
+
+impl FunctionArray {
+    /// Section type, and dynamic tags of the address and size of the array
+    fn tags(self) -> (u32, i64, i64) {
+        match self {
+            FunctionArray::PreInit => (SHT_PREINIT_ARRAY, DT_PREINIT_ARRAY, DT_PREINIT_ARRAYSZ),
+            FunctionArray::Init => (SHT_INIT_ARRAY, DT_INIT_ARRAY, DT_INIT_ARRAYSZ),
+            FunctionArray::Fini => (SHT_FINI_ARRAY, DT_FINI_ARRAY, DT_FINI_ARRAYSZ),
+        }
+    }
+}
+
+impl ElfParser<'_> {
+    pub fn preinit_array(&self) -> Result<Vec<Address>, ParseError> {
+        self.function_array(FunctionArray::PreInit)
+    }
+
+    pub fn init_array(&self) -> Result<Vec<Address>, ParseError> {
+        self.function_array(FunctionArray::Init)
+    }
+
+    pub fn fini_array(&self) -> Result<Vec<Address>, ParseError> {
+        self.function_array(FunctionArray::Fini)
+    }
+
+    /// Returns the functions of the array in the order they appear, read from its
+    /// section, or through the dynamic segment for files without section headers. When
+    /// both locate different arrays the section is trusted, `verify_dynamic` reporting
+    /// the mismatch.
+    ///
+    /// Linkers may leave the entries of position-independent files as 0 and rely on
+    /// the relative relocations targeting them, whose addend is used instead
+    pub fn function_array(&self, kind: FunctionArray) -> Result<Vec<Address>, ParseError> {
+        let (s_type, d_tag, d_size) = kind.tags();
+
+        let from_section = match self
+            .section_headers
+            .iter()
+            .position(|sh| sh.s_type == s_type)
+        {
+            Some(idx) => Some((
+                self.section_headers[idx].addr.0,
+                init_array::parse(self.section_data(idx)?, &self.ctx)?,
+            )),
+            None => None,
+        };
+
+        let from_dynamic = match (self.dynamic_entry(d_tag), self.dynamic_entry(d_size)) {
+            (Some(addr), Some(size)) => Some((
+                addr.value(),
+                init_array::parse(self.dynamic_table(d_tag, size.value())?, &self.ctx)?,
+            )),
+            _ => None,
+        };
+
+        let Some((addr, mut entries)) = from_section.or(from_dynamic) else {
+            return Ok(vec![]);
+        };
+
+        if self.dynamic_entries().is_some() {
+            let word = self.ctx.word_size() as u64;
+            let end = (entries.len() as u64)
+                .checked_mul(word)
+                .and_then(|size| addr.checked_add(size))
+                .ok_or(ParseError::InvalidLength)?;
+            for rela in self.dynamic_relocations().unwrap_or_default() {
+                let offset = rela.offset.0;
+                if rela.sym == 0
+                    && rela.addend != 0
+                    && (addr..end).contains(&offset)
+                    && (offset - addr).is_multiple_of(word)
+                {
+                    entries[((offset - addr) / word) as usize] = Address(rela.addend as u64);
+                }
+            }
+        }
+
+        entries.retain(|e| !init_array::is_padding(*e, &self.ctx));
+        debug!("{:?} array holds {} functions", kind, entries.len());
+
+        Ok(entries)
+    }
+}
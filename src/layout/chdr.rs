@@ -0,0 +1,38 @@
+use crate::elf::ehdr::ElfHClass;
+use crate::layout::FieldSpec;
+
+pub const CH_TYPE: FieldSpec = FieldSpec::new(0, 4);
+pub const CH_SIZE: FieldSpec = FieldSpec::new(8, 8);
+pub const CH_ADDRALIGN: FieldSpec = FieldSpec::new(16, 8);
+
+/// Fields of the header starting the contents of `SHF_COMPRESSED` sections. 64-bit
+/// files pad `ch_type` with a reserved word, which 32-bit ones don't have
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ChdrLayout {
+    pub ch_type: FieldSpec,
+    pub ch_size: FieldSpec,
+    pub ch_addralign: FieldSpec,
+    /// Size in bytes of the whole header
+    pub size: usize,
+}
+
+pub const ELF64: ChdrLayout = ChdrLayout {
+    ch_type: CH_TYPE,
+    ch_size: CH_SIZE,
+    ch_addralign: CH_ADDRALIGN,
+    size: 24,
+};
+
+pub const ELF32: ChdrLayout = ChdrLayout {
+    ch_type: FieldSpec::new(0, 4),
+    ch_size: FieldSpec::new(4, 4),
+    ch_addralign: FieldSpec::new(8, 4),
+    size: 12,
+};
+
+pub fn for_class(class: ElfHClass) -> &'static ChdrLayout {
+    match class {
+        ElfHClass::ElfClass32 => &ELF32,
+        _ => &ELF64,
+    }
+}
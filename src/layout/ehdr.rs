@@ -0,0 +1,98 @@
+use crate::elf::ehdr::ElfHClass;
+use crate::layout::FieldSpec;
+
+pub const E_TYPE: FieldSpec = FieldSpec::new(16, 2);
+pub const E_MACHINE: FieldSpec = FieldSpec::new(18, 2);
+pub const E_VERSION: FieldSpec = FieldSpec::new(20, 4);
+pub const E_ENTRY: FieldSpec = FieldSpec::new(24, 8);
+pub const E_PHOFF: FieldSpec = FieldSpec::new(32, 8);
+pub const E_SHOFF: FieldSpec = FieldSpec::new(40, 8);
+pub const E_FLAGS: FieldSpec = FieldSpec::new(48, 4);
+pub const E_EHSIZE: FieldSpec = FieldSpec::new(52, 2);
+pub const E_PHENTSIZE: FieldSpec = FieldSpec::new(54, 2);
+pub const E_PHNUM: FieldSpec = FieldSpec::new(56, 2);
+pub const E_SHENTSIZE: FieldSpec = FieldSpec::new(58, 2);
+pub const E_SHNUM: FieldSpec = FieldSpec::new(60, 2);
+pub const E_SHSTRNDX: FieldSpec = FieldSpec::new(62, 2);
+
+/// Fields of the ELF header that follow the identification
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct EhdrLayout {
+    pub e_type: FieldSpec,
+    pub e_machine: FieldSpec,
+    pub e_version: FieldSpec,
+    pub e_entry: FieldSpec,
+    pub e_phoff: FieldSpec,
+    pub e_shoff: FieldSpec,
+    pub e_flags: FieldSpec,
+    pub e_ehsize: FieldSpec,
+    pub e_phentsize: FieldSpec,
+    pub e_phnum: FieldSpec,
+    pub e_shentsize: FieldSpec,
+    pub e_shnum: FieldSpec,
+    pub e_shstrndx: FieldSpec,
+    /// Size in bytes of the whole header
+    pub size: usize,
+}
+
+impl EhdrLayout {
+    /// Every field of the header but the identification
+    pub fn fields(&self) -> [FieldSpec; 13] {
+        [
+            self.e_type,
+            self.e_machine,
+            self.e_version,
+            self.e_entry,
+            self.e_phoff,
+            self.e_shoff,
+            self.e_flags,
+            self.e_ehsize,
+            self.e_phentsize,
+            self.e_phnum,
+            self.e_shentsize,
+            self.e_shnum,
+            self.e_shstrndx,
+        ]
+    }
+}
+
+pub const ELF64: EhdrLayout = EhdrLayout {
+    e_type: E_TYPE,
+    e_machine: E_MACHINE,
+    e_version: E_VERSION,
+    e_entry: E_ENTRY,
+    e_phoff: E_PHOFF,
+    e_shoff: E_SHOFF,
+    e_flags: E_FLAGS,
+    e_ehsize: E_EHSIZE,
+    e_phentsize: E_PHENTSIZE,
+    e_phnum: E_PHNUM,
+    e_shentsize: E_SHENTSIZE,
+    e_shnum: E_SHNUM,
+    e_shstrndx: E_SHSTRNDX,
+    size: 64,
+};
+
+pub const ELF32: EhdrLayout = EhdrLayout {
+    e_type: FieldSpec::new(16, 2),
+    e_machine: FieldSpec::new(18, 2),
+    e_version: FieldSpec::new(20, 4),
+    e_entry: FieldSpec::new(24, 4),
+    e_phoff: FieldSpec::new(28, 4),
+    e_shoff: FieldSpec::new(32, 4),
+    e_flags: FieldSpec::new(36, 4),
+    e_ehsize: FieldSpec::new(40, 2),
+    e_phentsize: FieldSpec::new(42, 2),
+    e_phnum: FieldSpec::new(44, 2),
+    e_shentsize: FieldSpec::new(46, 2),
+    e_shnum: FieldSpec::new(48, 2),
+    e_shstrndx: FieldSpec::new(50, 2),
+    size: 52,
+};
+
+pub fn for_class(class: ElfHClass) -> &'static EhdrLayout {
+    match class {
+        ElfHClass::ElfClass32 => &ELF32,
+        _ => &ELF64,
+    }
+}
@@ -0,0 +1,34 @@
+//! Byte offset and width of every field of the ELF structures, for both classes. The
+//! parsers read through these specs, so they can be relied upon by code patching files
+//! in place
+
+pub mod chdr;
+pub mod ehdr;
+pub mod phdr;
+pub mod rela;
+pub mod shdr;
+pub mod sym;
+
+use std::ops::Range;
+
+/// Location of a field relative to the start of the structure holding it
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FieldSpec {
+    pub offset: usize,
+    pub size: usize,
+}
+
+impl FieldSpec {
+    pub const fn new(offset: usize, size: usize) -> Self {
+        FieldSpec { offset, size }
+    }
+
+    /// Offset of the first byte past the field
+    pub const fn end(&self) -> usize {
+        self.offset + self.size
+    }
+
+    pub fn range(&self) -> Range<usize> {
+        self.offset..self.end()
+    }
+}
@@ -0,0 +1,74 @@
+use crate::elf::ehdr::ElfHClass;
+use crate::layout::FieldSpec;
+
+pub const P_TYPE: FieldSpec = FieldSpec::new(0, 4);
+pub const P_FLAGS: FieldSpec = FieldSpec::new(4, 4);
+pub const P_OFFSET: FieldSpec = FieldSpec::new(8, 8);
+pub const P_VADDR: FieldSpec = FieldSpec::new(16, 8);
+pub const P_PADDR: FieldSpec = FieldSpec::new(24, 8);
+pub const P_FILESZ: FieldSpec = FieldSpec::new(32, 8);
+pub const P_MEMSZ: FieldSpec = FieldSpec::new(40, 8);
+pub const P_ALIGN: FieldSpec = FieldSpec::new(48, 8);
+
+/// Fields of a program header table entry. Note that `p_flags` moves after `p_memsz`
+/// on 32-bit files
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PhdrLayout {
+    pub p_type: FieldSpec,
+    pub p_flags: FieldSpec,
+    pub p_offset: FieldSpec,
+    pub p_vaddr: FieldSpec,
+    pub p_paddr: FieldSpec,
+    pub p_filesz: FieldSpec,
+    pub p_memsz: FieldSpec,
+    pub p_align: FieldSpec,
+    /// Size in bytes of the whole entry
+    pub size: usize,
+}
+
+impl PhdrLayout {
+    /// Every field of the entry
+    pub fn fields(&self) -> [FieldSpec; 8] {
+        [
+            self.p_type,
+            self.p_flags,
+            self.p_offset,
+            self.p_vaddr,
+            self.p_paddr,
+            self.p_filesz,
+            self.p_memsz,
+            self.p_align,
+        ]
+    }
+}
+
+pub const ELF64: PhdrLayout = PhdrLayout {
+    p_type: P_TYPE,
+    p_flags: P_FLAGS,
+    p_offset: P_OFFSET,
+    p_vaddr: P_VADDR,
+    p_paddr: P_PADDR,
+    p_filesz: P_FILESZ,
+    p_memsz: P_MEMSZ,
+    p_align: P_ALIGN,
+    size: 56,
+};
+
+pub const ELF32: PhdrLayout = PhdrLayout {
+    p_type: FieldSpec::new(0, 4),
+    p_offset: FieldSpec::new(4, 4),
+    p_vaddr: FieldSpec::new(8, 4),
+    p_paddr: FieldSpec::new(12, 4),
+    p_filesz: FieldSpec::new(16, 4),
+    p_memsz: FieldSpec::new(20, 4),
+    p_flags: FieldSpec::new(24, 4),
+    p_align: FieldSpec::new(28, 4),
+    size: 32,
+};
+
+pub fn for_class(class: ElfHClass) -> &'static PhdrLayout {
+    match class {
+        ElfHClass::ElfClass32 => &ELF32,
+        _ => &ELF64,
+    }
+}
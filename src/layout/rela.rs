@@ -0,0 +1,45 @@
+use crate::elf::ehdr::ElfHClass;
+use crate::layout::FieldSpec;
+
+pub const R_OFFSET: FieldSpec = FieldSpec::new(0, 8);
+pub const R_INFO: FieldSpec = FieldSpec::new(8, 8);
+pub const R_ADDEND: FieldSpec = FieldSpec::new(16, 8);
+
+/// Fields of a relocation entry with an explicit addend. Entries without one use the
+/// same layout, ending right before `r_addend`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RelaLayout {
+    pub r_offset: FieldSpec,
+    pub r_info: FieldSpec,
+    pub r_addend: FieldSpec,
+    /// Size in bytes of the whole entry
+    pub size: usize,
+}
+
+impl RelaLayout {
+    /// Size in bytes of an entry without an explicit addend
+    pub const fn rel_size(&self) -> usize {
+        self.r_addend.offset
+    }
+}
+
+pub const ELF64: RelaLayout = RelaLayout {
+    r_offset: R_OFFSET,
+    r_info: R_INFO,
+    r_addend: R_ADDEND,
+    size: 24,
+};
+
+pub const ELF32: RelaLayout = RelaLayout {
+    r_offset: FieldSpec::new(0, 4),
+    r_info: FieldSpec::new(4, 4),
+    r_addend: FieldSpec::new(8, 4),
+    size: 12,
+};
+
+pub fn for_class(class: ElfHClass) -> &'static RelaLayout {
+    match class {
+        ElfHClass::ElfClass32 => &ELF32,
+        _ => &ELF64,
+    }
+}
@@ -0,0 +1,83 @@
+use crate::elf::ehdr::ElfHClass;
+use crate::layout::FieldSpec;
+
+pub const SH_NAME: FieldSpec = FieldSpec::new(0, 4);
+pub const SH_TYPE: FieldSpec = FieldSpec::new(4, 4);
+pub const SH_FLAGS: FieldSpec = FieldSpec::new(8, 8);
+pub const SH_ADDR: FieldSpec = FieldSpec::new(16, 8);
+pub const SH_OFFSET: FieldSpec = FieldSpec::new(24, 8);
+pub const SH_SIZE: FieldSpec = FieldSpec::new(32, 8);
+pub const SH_LINK: FieldSpec = FieldSpec::new(40, 4);
+pub const SH_INFO: FieldSpec = FieldSpec::new(44, 4);
+pub const SH_ADDRALIGN: FieldSpec = FieldSpec::new(48, 8);
+pub const SH_ENTSIZE: FieldSpec = FieldSpec::new(56, 8);
+
+/// Fields of a section header table entry
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ShdrLayout {
+    pub sh_name: FieldSpec,
+    pub sh_type: FieldSpec,
+    pub sh_flags: FieldSpec,
+    pub sh_addr: FieldSpec,
+    pub sh_offset: FieldSpec,
+    pub sh_size: FieldSpec,
+    pub sh_link: FieldSpec,
+    pub sh_info: FieldSpec,
+    pub sh_addralign: FieldSpec,
+    pub sh_entsize: FieldSpec,
+    /// Size in bytes of the whole entry
+    pub size: usize,
+}
+
+impl ShdrLayout {
+    /// Every field of the entry
+    pub fn fields(&self) -> [FieldSpec; 10] {
+        [
+            self.sh_name,
+            self.sh_type,
+            self.sh_flags,
+            self.sh_addr,
+            self.sh_offset,
+            self.sh_size,
+            self.sh_link,
+            self.sh_info,
+            self.sh_addralign,
+            self.sh_entsize,
+        ]
+    }
+}
+
+pub const ELF64: ShdrLayout = ShdrLayout {
+    sh_name: SH_NAME,
+    sh_type: SH_TYPE,
+    sh_flags: SH_FLAGS,
+    sh_addr: SH_ADDR,
+    sh_offset: SH_OFFSET,
+    sh_size: SH_SIZE,
+    sh_link: SH_LINK,
+    sh_info: SH_INFO,
+    sh_addralign: SH_ADDRALIGN,
+    sh_entsize: SH_ENTSIZE,
+    size: 64,
+};
+
+pub const ELF32: ShdrLayout = ShdrLayout {
+    sh_name: FieldSpec::new(0, 4),
+    sh_type: FieldSpec::new(4, 4),
+    sh_flags: FieldSpec::new(8, 4),
+    sh_addr: FieldSpec::new(12, 4),
+    sh_offset: FieldSpec::new(16, 4),
+    sh_size: FieldSpec::new(20, 4),
+    sh_link: FieldSpec::new(24, 4),
+    sh_info: FieldSpec::new(28, 4),
+    sh_addralign: FieldSpec::new(32, 4),
+    sh_entsize: FieldSpec::new(36, 4),
+    size: 40,
+};
+
+pub fn for_class(class: ElfHClass) -> &'static ShdrLayout {
+    match class {
+        ElfHClass::ElfClass32 => &ELF32,
+        _ => &ELF64,
+    }
+}
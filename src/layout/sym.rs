@@ -0,0 +1,50 @@
+use crate::elf::ehdr::ElfHClass;
+use crate::layout::FieldSpec;
+
+pub const ST_NAME: FieldSpec = FieldSpec::new(0, 4);
+pub const ST_INFO: FieldSpec = FieldSpec::new(4, 1);
+pub const ST_OTHER: FieldSpec = FieldSpec::new(5, 1);
+pub const ST_SHNDX: FieldSpec = FieldSpec::new(6, 2);
+pub const ST_VALUE: FieldSpec = FieldSpec::new(8, 8);
+pub const ST_SIZE: FieldSpec = FieldSpec::new(16, 8);
+
+/// Fields of a symbol table entry. The value and size come right after the name on
+/// 32-bit files
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SymLayout {
+    pub st_name: FieldSpec,
+    pub st_info: FieldSpec,
+    pub st_other: FieldSpec,
+    pub st_shndx: FieldSpec,
+    pub st_value: FieldSpec,
+    pub st_size: FieldSpec,
+    /// Size in bytes of the whole entry
+    pub size: usize,
+}
+
+pub const ELF64: SymLayout = SymLayout {
+    st_name: ST_NAME,
+    st_info: ST_INFO,
+    st_other: ST_OTHER,
+    st_shndx: ST_SHNDX,
+    st_value: ST_VALUE,
+    st_size: ST_SIZE,
+    size: 24,
+};
+
+pub const ELF32: SymLayout = SymLayout {
+    st_name: FieldSpec::new(0, 4),
+    st_value: FieldSpec::new(4, 4),
+    st_size: FieldSpec::new(8, 4),
+    st_info: FieldSpec::new(12, 1),
+    st_other: FieldSpec::new(13, 1),
+    st_shndx: FieldSpec::new(14, 2),
+    size: 16,
+};
+
+pub fn for_class(class: ElfHClass) -> &'static SymLayout {
+    match class {
+        ElfHClass::ElfClass32 => &ELF32,
+        _ => &ELF64,
+    }
+}
@@ -1,5 +1,63 @@
-#![feature(exclusive_range_pattern)]
-
+pub mod abi;
+pub mod alignment;
+pub mod archive;
 pub mod bytes;
+pub mod carve;
+pub mod classify;
+pub mod coredump;
+pub mod debug_info;
+pub mod debuglink;
+pub mod deps;
+#[cfg(feature = "disasm")]
+pub mod disasm;
+pub mod display;
+pub mod dynamic;
+pub mod editor;
 pub mod elf;
+pub mod encoding;
+pub mod explain;
+pub mod exports;
+pub mod fingerprint;
+pub mod flat;
+pub mod handlers;
+pub mod hexfile;
+pub mod init_array;
+pub mod layout;
+pub mod linkage;
+pub mod links;
+pub mod lint;
+pub mod lint_report;
+pub mod loader;
+pub mod mapped;
+pub mod memory;
+pub mod notes;
+pub mod object;
+#[cfg(feature = "object-compat")]
+pub mod object_compat;
+pub mod options;
 pub mod parser;
+pub mod provenance;
+pub mod refresh;
+pub mod regions;
+pub mod relocs;
+pub mod remote;
+pub mod riscv;
+pub mod rpath;
+pub mod section;
+pub mod signature;
+pub mod size;
+pub mod sorted_symbols;
+pub mod source;
+pub mod source_info;
+pub mod stats;
+pub mod strtab;
+pub mod swab;
+pub mod symbol_index;
+pub mod symbol_summary;
+pub mod textrel;
+pub mod tls;
+pub mod toolchain;
+pub mod workspace;
+
+pub use carve::{scan_blob, EmbeddedElf};
+pub use object::{parse, ElfObject};
@@ -0,0 +1,143 @@
+use crate::bytes::str_from_u8;
+use crate::elf::ehdr::ElfHType;
+use crate::elf::phdr::{PType, DT_NEEDED, DT_SONAME};
+use crate::parser::{ElfParser, ParseError};
+use log::debug;
+
+/// How a file gets the code of the libraries it depends on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Linkage {
+    /// Position-dependent executable holding every library it needs
+    Static,
+    /// Position-independent executable holding every library it needs, relocating
+    /// itself through its own dynamic segment as no interpreter is requested
+    StaticPie,
+    /// File needing shared libraries loaded at run time. Shared objects have an empty
+    /// `interpreter`, as they're loaded by the one of the executable using them
+    Dynamic {
+        interpreter: String,
+        needed: Vec<String>,
+    },
+}
+
+/// C library a file was linked against
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LibcFlavor {
+    Glibc,
+    Musl,
+}
+
+/// Symbols only defined by the start files and internals of each C library, which
+/// static files keep in their symbol table
+const GLIBC_SYMBOLS: &[&str] = &["__libc_setup_tls", "_IO_stdin_used", "__libc_csu_init"];
+const MUSL_SYMBOLS: &[&str] = &["__init_libc", "__libc_start_init", "__dls2", "__dls3"];
+
+impl ElfParser<'_> {
+    /// Path of the program interpreter requested through `PT_INTERP`
+    pub fn interpreter(&self) -> Result<Option<String>, ParseError> {
+        let Some(idx) = self
+            .program_headers
+            .iter()
+            .position(|ph| ph.p_type == PType::PtInterp)
+        else {
+            return Ok(None);
+        };
+
+        str_from_u8(self.segment_data(idx)?).map(Some)
+    }
+
+    /// Names of the libraries listed by the `DT_NEEDED` entries, in the order of the
+    /// entries, which is the order the dynamic linker loads them in
+    pub fn needed_libraries(&self) -> Result<Vec<String>, ParseError> {
+        self.dynamic_entries()
+            .unwrap_or_default()
+            .iter()
+            .filter(|d| d.d_tag == DT_NEEDED)
+            .map(|d| self.dynamic_string(d.value() as u32))
+            .collect()
+    }
+
+    /// Name the file is known by to the files needing it, held by `DT_SONAME`
+    pub fn soname(&self) -> Result<Option<String>, ParseError> {
+        self.dynamic_entry(DT_SONAME)
+            .map(|d| self.dynamic_string(d.value() as u32))
+            .transpose()
+    }
+
+    /// Tells statically and dynamically linked files apart. A file without interpreter
+    /// nor needed libraries is static, even if it has a dynamic segment, which for
+    /// `ET_DYN` files makes it a static PIE
+    pub fn linkage(&self) -> Result<Linkage, ParseError> {
+        let interpreter = self.interpreter()?;
+        let needed = self.needed_libraries()?;
+        let has_dynamic = self.dynamic_entries().is_some();
+
+        debug!(
+            "linkage from interpreter {:?}, {} needed libraries, dynamic segment: {}",
+            interpreter,
+            needed.len(),
+            has_dynamic
+        );
+
+        Ok(match interpreter {
+            None if needed.is_empty() => {
+                if has_dynamic && self.headers.e_type == ElfHType::SharedObject {
+                    Linkage::StaticPie
+                } else {
+                    Linkage::Static
+                }
+            }
+            interpreter => Linkage::Dynamic {
+                interpreter: interpreter.unwrap_or_default(),
+                needed,
+            },
+        })
+    }
+
+    /// Guesses the C library the file was linked against, from the interpreter and
+    /// needed libraries of dynamic files, then from the `GLIBC_*` versions they
+    /// require, and last from symbols characteristic of either library. None if the
+    /// file shows no trace of either
+    pub fn libc_flavor(&self) -> Result<Option<LibcFlavor>, ParseError> {
+        if let Linkage::Dynamic {
+            interpreter,
+            needed,
+        } = self.linkage()?
+        {
+            let names = std::iter::once(&interpreter).chain(&needed);
+            for name in names {
+                if name.contains("ld-musl") || name.starts_with("libc.musl") {
+                    return Ok(Some(LibcFlavor::Musl));
+                }
+                if name.contains("ld-linux") || name.contains("libc.so.6") {
+                    return Ok(Some(LibcFlavor::Glibc));
+                }
+            }
+
+            // musl doesn't version its symbols
+            if self.dynamic_entries().is_some()
+                && self
+                    .version_dependencies()
+                    .unwrap_or_default()
+                    .iter()
+                    .flat_map(|need| &need.versions)
+                    .any(|v| {
+                        self.dynamic_string(v.name)
+                            .is_ok_and(|n| n.starts_with("GLIBC_"))
+                    })
+            {
+                return Ok(Some(LibcFlavor::Glibc));
+            }
+        }
+
+        let has_any = |names: &[&str]| names.iter().any(|n| self.find_symbol(n).is_some());
+        if has_any(GLIBC_SYMBOLS) {
+            return Ok(Some(LibcFlavor::Glibc));
+        }
+        if has_any(MUSL_SYMBOLS) {
+            return Ok(Some(LibcFlavor::Musl));
+        }
+
+        Ok(None)
+    }
+}
@@ -0,0 +1,210 @@
+use crate::elf::shdr::{
+    Elf64SHdr, StringTable, SHF_INFO_LINK, SHF_LINK_ORDER, SHT_DYNAMIC, SHT_DYNSYM, SHT_GNU_HASH,
+    SHT_GNU_VERDEF, SHT_GNU_VERNEED, SHT_GNU_VERSYM, SHT_GROUP, SHT_HASH, SHT_REL, SHT_RELA,
+    SHT_STRTAB, SHT_SYMTAB, SHT_SYMTAB_SHNDX,
+};
+use crate::parser::{ElfParser, ParseError, ParseWarning};
+use log::warn;
+
+/// Meaning of the `link` and `info` fields of a section header, which depends on the
+/// type of the section. Section indices of 0 mean no section is referenced
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LinkInfo {
+    /// `SHT_SYMTAB` and `SHT_DYNSYM`
+    Symtab {
+        strtab: usize,
+        first_global: u32,
+    },
+    /// `SHT_RELA` and `SHT_REL`: the symbol table the entries refer to, and the
+    /// section they apply to
+    Rela {
+        symtab: usize,
+        target: usize,
+    },
+    Dynamic {
+        strtab: usize,
+    },
+    /// `SHT_HASH` and `SHT_GNU_HASH`
+    Hash {
+        symtab: usize,
+    },
+    Versym {
+        dynsym: usize,
+    },
+    /// `SHT_GNU_VERDEF` and `SHT_GNU_VERNEED`
+    Version {
+        strtab: usize,
+        count: u32,
+    },
+    /// `SHT_GROUP`: the symbol table holding the group signature symbol
+    Group {
+        symtab: usize,
+        signature: u32,
+    },
+    SymtabShndx {
+        symtab: usize,
+    },
+    /// Sections flagged `SHF_LINK_ORDER` must be ordered along the linked one
+    LinkOrder {
+        section: usize,
+    },
+    None,
+}
+
+/// Link information of every section along with the anomalies found validating it
+#[derive(Debug, Clone)]
+pub struct SectionLinks {
+    pub links: Vec<LinkInfo>,
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Follows the `link` fields of `section_headers` from the section at `idx` up to the
+/// first section of one of the `target` types, whose index is returned. Every section
+/// is visited at most once and no more links are followed than there are sections, so
+/// crafted files whose links loop fail with `ParseError::LinkCycle` instead of being
+/// followed forever. None when the links end first, at a link of 0 or past the table
+pub fn resolve_link(
+    section_headers: &[Elf64SHdr],
+    idx: usize,
+    target: &[u32],
+) -> Result<Option<usize>, ParseError> {
+    let mut path = vec![idx];
+
+    while path.len() <= section_headers.len() {
+        let current = path[path.len() - 1];
+        let link = match section_headers.get(current) {
+            Some(sh) if sh.link != 0 => sh.link as usize,
+            _ => return Ok(None),
+        };
+
+        let seen = path.contains(&link);
+        path.push(link);
+        if seen {
+            break;
+        }
+
+        match section_headers.get(link) {
+            Some(sh) if target.contains(&sh.s_type) => return Ok(Some(link)),
+            Some(_) => continue,
+            None => return Ok(None),
+        }
+    }
+
+    warn!("section links loop through {:?}", path);
+    Err(ParseError::LinkCycle { path })
+}
+
+impl ElfParser<'_> {
+    /// Follows the `link` fields from the section at `idx` up to a section of one of
+    /// the `target` types, see `resolve_link`
+    pub fn resolve_link(&self, idx: usize, target: &[u32]) -> Result<Option<usize>, ParseError> {
+        resolve_link(&self.section_headers, idx, target)
+    }
+
+    /// Returns the string table reached by following the `link` fields from the
+    /// section at `idx`, as the names of a symbol table or of versions are found
+    pub fn linked_string_table(&self, idx: usize) -> Result<Option<&StringTable>, ParseError> {
+        Ok(self
+            .resolve_link(idx, &[SHT_STRTAB])?
+            .and_then(|link| self.string_table_at(link)))
+    }
+
+    /// Checks that `link` references a section of one of the `expected` types
+    fn check_link(
+        &self,
+        idx: usize,
+        field: &'static str,
+        link: u32,
+        expected: &[u32],
+        warnings: &mut Vec<ParseWarning>,
+    ) -> usize {
+        let valid = match self.section_headers.get(link as usize) {
+            Some(sh) => expected.is_empty() || expected.contains(&sh.s_type),
+            None => false,
+        };
+
+        if !valid {
+            let warning = ParseWarning::InvalidLink {
+                idx,
+                field,
+                value: link,
+            };
+            warn!("{}", warning);
+            warnings.push(warning);
+        }
+
+        link as usize
+    }
+
+    /// Decodes the `link` and `info` fields of every section header, validating that
+    /// the sections they reference exist and are of the expected type
+    pub fn section_links(&self) -> SectionLinks {
+        let mut warnings = vec![];
+        let symtabs = [SHT_SYMTAB, SHT_DYNSYM];
+
+        let links = self
+            .section_headers
+            .iter()
+            .enumerate()
+            .map(|(idx, sh)| {
+                let w = &mut warnings;
+
+                match sh.s_type {
+                    SHT_SYMTAB | SHT_DYNSYM => {
+                        let count = sh.size.checked_div(sh.ent_size).unwrap_or(0);
+                        if sh.info as u64 > count {
+                            let warning = ParseWarning::InvalidLink {
+                                idx,
+                                field: "info",
+                                value: sh.info,
+                            };
+                            warn!("{}", warning);
+                            w.push(warning);
+                        }
+
+                        LinkInfo::Symtab {
+                            strtab: self.check_link(idx, "link", sh.link, &[SHT_STRTAB], w),
+                            first_global: sh.info,
+                        }
+                    }
+                    SHT_RELA | SHT_REL => LinkInfo::Rela {
+                        symtab: match sh.link {
+                            0 => 0,
+                            link => self.check_link(idx, "link", link, &symtabs, w),
+                        },
+                        target: match sh.info {
+                            0 if sh.flags & SHF_INFO_LINK == 0 => 0,
+                            info => self.check_link(idx, "info", info, &[], w),
+                        },
+                    },
+                    SHT_DYNAMIC => LinkInfo::Dynamic {
+                        strtab: self.check_link(idx, "link", sh.link, &[SHT_STRTAB], w),
+                    },
+                    SHT_HASH | SHT_GNU_HASH => LinkInfo::Hash {
+                        symtab: self.check_link(idx, "link", sh.link, &symtabs, w),
+                    },
+                    SHT_GNU_VERSYM => LinkInfo::Versym {
+                        dynsym: self.check_link(idx, "link", sh.link, &[SHT_DYNSYM], w),
+                    },
+                    SHT_GNU_VERDEF | SHT_GNU_VERNEED => LinkInfo::Version {
+                        strtab: self.check_link(idx, "link", sh.link, &[SHT_STRTAB], w),
+                        count: sh.info,
+                    },
+                    SHT_GROUP => LinkInfo::Group {
+                        symtab: self.check_link(idx, "link", sh.link, &[SHT_SYMTAB], w),
+                        signature: sh.info,
+                    },
+                    SHT_SYMTAB_SHNDX => LinkInfo::SymtabShndx {
+                        symtab: self.check_link(idx, "link", sh.link, &[SHT_SYMTAB], w),
+                    },
+                    _ if sh.flags & SHF_LINK_ORDER != 0 => LinkInfo::LinkOrder {
+                        section: self.check_link(idx, "link", sh.link, &[], w),
+                    },
+                    _ => LinkInfo::None,
+                }
+            })
+            .collect();
+
+        SectionLinks { links, warnings }
+    }
+}
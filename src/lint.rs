@@ -0,0 +1,166 @@
+//! Consistency checks of the section headers and of the dynamic entries, whose
+//! results are part of `ElfParser::warnings` and are printed by `--lint`.
+
+use crate::elf::phdr::{
+    DT_FINI_ARRAY, DT_FINI_ARRAYSZ, DT_GNU_HASH, DT_HASH, DT_INIT_ARRAY, DT_INIT_ARRAYSZ,
+    DT_JMPREL, DT_PLTRELSZ, DT_PREINIT_ARRAY, DT_PREINIT_ARRAYSZ, DT_RELAENT, DT_RELASZ, DT_STRSZ,
+    DT_STRTAB, DT_SYMENT,
+};
+use crate::elf::shdr::{Elf64SHdr, SHF_ALLOC, SHT_FINI_ARRAY, SHT_INIT_ARRAY, SHT_PREINIT_ARRAY};
+use crate::layout;
+use crate::parser::{ElfParser, ParseWarning};
+
+impl ElfParser<'_> {
+    /// Checks every section header but the initial one, which has checks of its own:
+    /// allocated sections must honor their alignment, and the header must pass
+    /// `Elf64SHdr::lint`
+    pub fn lint_section_headers(section_headers: &[Elf64SHdr]) -> Vec<ParseWarning> {
+        let mut warnings = vec![];
+
+        for (idx, sh) in section_headers.iter().enumerate().skip(1) {
+            if sh.flags & SHF_ALLOC != 0 && sh.is_misaligned() {
+                warnings.push(ParseWarning::MisalignedSection {
+                    idx,
+                    addr: sh.addr.0,
+                    align: sh.addr_align,
+                });
+            }
+
+            warnings.extend(
+                sh.lint()
+                    .into_iter()
+                    .map(|reason| ParseWarning::SectionLint { idx, reason }),
+            );
+        }
+
+        warnings
+    }
+
+    /// Anomalies of the section headers, see `lint_section_headers`
+    pub fn lint_sections(&self) -> Vec<ParseWarning> {
+        ElfParser::lint_section_headers(&self.section_headers)
+    }
+
+    /// Cross-checks the dynamic entries describing tables against the sections
+    /// holding them and the entry sizes of the class:
+    ///
+    /// - `DT_STRTAB` and `DT_STRSZ` against the address and size of `.dynstr`
+    /// - `DT_SYMENT` and `DT_RELAENT` against the symbol and relocation sizes
+    /// - `DT_RELASZ` against a multiple of `DT_RELAENT`
+    /// - `DT_JMPREL` and `DT_PLTRELSZ` against the address and size of `.rela.plt`,
+    ///   or `.rel.plt`
+    /// - `DT_PREINIT_ARRAY`, `DT_INIT_ARRAY` and `DT_FINI_ARRAY`, and their sizes,
+    ///   against the address and size of the section of the array
+    /// - `DT_HASH` and `DT_GNU_HASH` against the loadable segments
+    ///
+    /// Sections that can't be found by name, or by type for the arrays of functions,
+    /// aren't checked against
+    pub fn verify_dynamic(&self) -> Vec<ParseWarning> {
+        let mut warnings = vec![];
+        let Some(entries) = self.dynamic_entries() else {
+            return warnings;
+        };
+        let value = |d_tag| entries.iter().find(|d| d.d_tag == d_tag).map(|d| d.value());
+        let mut check = |tag, found: Option<u64>, expected, against| {
+            if let Some(found) = found.filter(|found| *found != expected) {
+                warnings.push(ParseWarning::DynamicMismatch {
+                    tag,
+                    found,
+                    expected,
+                    against,
+                });
+            }
+        };
+
+        if let Some((_, dynstr)) = self.section_by_name(".dynstr") {
+            check(
+                DT_STRTAB,
+                value(DT_STRTAB),
+                dynstr.addr.0,
+                "address of .dynstr",
+            );
+            check(DT_STRSZ, value(DT_STRSZ), dynstr.size, "size of .dynstr");
+        }
+
+        let class = self.headers.ident.class;
+        check(
+            DT_SYMENT,
+            value(DT_SYMENT),
+            layout::sym::for_class(class).size as u64,
+            "size of a symbol of the class",
+        );
+        let rela_size = layout::rela::for_class(class).size as u64;
+        check(
+            DT_RELAENT,
+            value(DT_RELAENT),
+            rela_size,
+            "size of a relocation of the class",
+        );
+        let relaent = value(DT_RELAENT)
+            .filter(|ent| *ent != 0)
+            .unwrap_or(rela_size);
+        if let Some(relasz) = value(DT_RELASZ) {
+            check(
+                DT_RELASZ,
+                Some(relasz),
+                relasz - relasz % relaent,
+                "a multiple of DT_RELAENT",
+            );
+        }
+
+        let plt = self
+            .section_by_name(".rela.plt")
+            .or_else(|| self.section_by_name(".rel.plt"));
+        if let Some((_, plt)) = plt {
+            check(
+                DT_JMPREL,
+                value(DT_JMPREL),
+                plt.addr.0,
+                "address of the PLT relocations",
+            );
+            check(
+                DT_PLTRELSZ,
+                value(DT_PLTRELSZ),
+                plt.size,
+                "size of the PLT relocations",
+            );
+        }
+
+        for (s_type, addr_tag, size_tag, addr_of, size_of) in [
+            (
+                SHT_PREINIT_ARRAY,
+                DT_PREINIT_ARRAY,
+                DT_PREINIT_ARRAYSZ,
+                "address of .preinit_array",
+                "size of .preinit_array",
+            ),
+            (
+                SHT_INIT_ARRAY,
+                DT_INIT_ARRAY,
+                DT_INIT_ARRAYSZ,
+                "address of .init_array",
+                "size of .init_array",
+            ),
+            (
+                SHT_FINI_ARRAY,
+                DT_FINI_ARRAY,
+                DT_FINI_ARRAYSZ,
+                "address of .fini_array",
+                "size of .fini_array",
+            ),
+        ] {
+            if let Some(sh) = self.section_headers.iter().find(|sh| sh.s_type == s_type) {
+                check(addr_tag, value(addr_tag), sh.addr.0, addr_of);
+                check(size_tag, value(size_tag), sh.size, size_of);
+            }
+        }
+
+        for tag in [DT_HASH, DT_GNU_HASH] {
+            if let Some(addr) = value(tag).filter(|addr| self.vaddr_to_offset(*addr).is_err()) {
+                warnings.push(ParseWarning::DynamicUnmapped { tag, addr });
+            }
+        }
+
+        warnings
+    }
+}
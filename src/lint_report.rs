@@ -0,0 +1,245 @@
+//! Machine-readable report of the anomalies found in a file, for code-scanning
+//! dashboards and other tools, printed by `--lint --format json` or `sarif`.
+//!
+//! The SARIF output is a single run of the 2.1.0 format, limited to the subset below,
+//! every other property of the format being left out:
+//!
+//! ```text
+//! {
+//!   "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+//!   "version": "2.1.0",
+//!   "runs": [{
+//!     "tool": { "driver": { "name", "version", "rules": [{ "id" }] } },
+//!     "artifacts": [{ "location": { "uri" }, "length" }],
+//!     "results": [{
+//!       "ruleId", "level": "warning", "message": { "text" },
+//!       "locations": [{ "physicalLocation": {
+//!         "artifactLocation": { "uri" },
+//!         "region": { "byteOffset", "byteLength" }
+//!       } }]
+//!     }]
+//!   }]
+//! }
+//! ```
+//!
+//! The artifact also holds the `length` of the file when the parser was opened from
+//! one, see `ElfParser::open`.
+//!
+//! `ruleId` is the stable code of the warning, as returned by `ParseWarning::code`, and
+//! each rule is listed once in `rules`. The region is left out for anomalies that
+//! aren't tied to any bytes of the file, and `byteLength` when only their offset is
+//! known.
+
+use crate::parser::ElfParser;
+use crate::source_info::SourceInfo;
+use std::time::UNIX_EPOCH;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Anomaly of the report, located in the file when possible
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    /// Stable code of the anomaly, see `ParseWarning::code`
+    pub code: &'static str,
+    pub message: String,
+    /// File offset the anomaly is at, or of the header of the section or segment it's
+    /// about
+    pub offset: Option<u64>,
+    /// Number of bytes at `offset` the anomaly covers, when it's a whole header
+    pub length: Option<u64>,
+    /// Index of the section header the anomaly is about, if any
+    pub section: Option<usize>,
+    /// Index of the program header the anomaly is about, if any
+    pub segment: Option<usize>,
+}
+
+/// Every anomaly found while parsing a file, see `ElfParser::lint_report`
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintReport {
+    /// Location of the file the findings are about, as given to `lint_report`
+    pub uri: String,
+    /// File the parser was opened from, None when it was parsed from bytes
+    pub source: Option<SourceInfo>,
+    pub findings: Vec<Finding>,
+}
+
+impl ElfParser<'_> {
+    /// Report of `warnings`, the anomalies found in the file at `uri`, which include
+    /// the checks of `lint_sections` and `verify_dynamic`
+    pub fn lint_report(&self, uri: &str) -> LintReport {
+        let h = &self.headers;
+        let entry = |table: u64, idx: usize, size: u16| {
+            (
+                Some(table.saturating_add(idx as u64 * size as u64)),
+                Some(size as u64),
+            )
+        };
+        let findings = self
+            .warnings
+            .iter()
+            .map(|warning| {
+                let (offset, length) = match (warning.offset(), warning.section()) {
+                    (Some(offset), _) => (Some(offset), None),
+                    (None, Some(idx)) if idx < self.section_headers.len() => {
+                        entry(h.sh_off, idx, h.sh_ent_size)
+                    }
+                    _ => match warning.segment() {
+                        Some(idx) if idx < self.program_headers.len() => {
+                            entry(h.ph_off, idx, h.ph_ent_size)
+                        }
+                        _ => (None, None),
+                    },
+                };
+
+                Finding {
+                    code: warning.code(),
+                    message: warning.to_string(),
+                    offset,
+                    length,
+                    section: warning.section(),
+                    segment: warning.segment(),
+                }
+            })
+            .collect();
+
+        LintReport {
+            uri: uri.to_string(),
+            source: self.source().cloned(),
+            findings,
+        }
+    }
+}
+
+impl LintReport {
+    /// Renders the report as a single JSON object, the missing values being null.
+    /// `modified` is in seconds since the Unix epoch:
+    ///
+    /// ```text
+    /// { "tool": { "name", "version" }, "uri", "source": { "path", "size", "modified" },
+    ///   "findings": [{ "code", "message", "offset", "length", "section", "segment" }] }
+    /// ```
+    pub fn to_json(&self) -> String {
+        let number = |value: Option<u64>| value.map_or("null".to_string(), |v| v.to_string());
+        let findings = self
+            .findings
+            .iter()
+            .map(|f| {
+                format!(
+                    "{{\"code\":{},\"message\":{},\"offset\":{},\"length\":{},\"section\":{},\
+                     \"segment\":{}}}",
+                    json_string(f.code),
+                    json_string(&f.message),
+                    number(f.offset),
+                    number(f.length),
+                    number(f.section.map(|idx| idx as u64)),
+                    number(f.segment.map(|idx| idx as u64)),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let source = self.source.as_ref().map_or("null".to_string(), |source| {
+            let modified = source
+                .modified
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs());
+            format!(
+                "{{\"path\":{},\"size\":{},\"modified\":{}}}",
+                json_string(&source.path.to_string_lossy()),
+                source.size,
+                number(modified)
+            )
+        });
+
+        format!(
+            "{{\"tool\":{},\"uri\":{},\"source\":{},\"findings\":[{}]}}",
+            tool_json(),
+            json_string(&self.uri),
+            source,
+            findings.join(",")
+        )
+    }
+
+    /// Renders the report as a SARIF log, see the module documentation for the subset
+    /// of the format used
+    pub fn to_sarif(&self) -> String {
+        let mut rules = self.findings.iter().map(|f| f.code).collect::<Vec<_>>();
+        rules.sort_unstable();
+        rules.dedup();
+        let rules = rules
+            .iter()
+            .map(|code| format!("{{\"id\":{}}}", json_string(code)))
+            .collect::<Vec<_>>();
+
+        let uri = json_string(&self.uri);
+        let results = self
+            .findings
+            .iter()
+            .map(|f| {
+                let region = match (f.offset, f.length) {
+                    (Some(offset), Some(length)) => format!(
+                        ",\"region\":{{\"byteOffset\":{},\"byteLength\":{}}}",
+                        offset, length
+                    ),
+                    (Some(offset), None) => format!(",\"region\":{{\"byteOffset\":{}}}", offset),
+                    _ => String::new(),
+                };
+
+                format!(
+                    "{{\"ruleId\":{},\"level\":\"warning\",\"message\":{{\"text\":{}}},\
+                     \"locations\":[{{\"physicalLocation\":{{\"artifactLocation\":\
+                     {{\"uri\":{}}}{}}}}}]}}",
+                    json_string(f.code),
+                    json_string(&f.message),
+                    uri,
+                    region
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let length = self.source.as_ref().map_or(String::new(), |source| {
+            format!(",\"length\":{}", source.size)
+        });
+
+        format!(
+            "{{\"$schema\":{},\"version\":\"2.1.0\",\"runs\":[{{\"tool\":{{\"driver\":{{{},\
+             \"rules\":[{}]}}}},\"artifacts\":[{{\"location\":{{\"uri\":{}}}{}}}],\
+             \"results\":[{}]}}]}}",
+            json_string(SARIF_SCHEMA),
+            tool_fields(),
+            rules.join(","),
+            uri,
+            length,
+            results.join(",")
+        )
+    }
+}
+
+/// Name and version of this crate, as JSON members
+fn tool_fields() -> String {
+    format!(
+        "\"name\":{},\"version\":{}",
+        json_string(env!("CARGO_PKG_NAME")),
+        json_string(env!("CARGO_PKG_VERSION"))
+    )
+}
+
+fn tool_json() -> String {
+    format!("{{{}}}", tool_fields())
+}
+
+/// Quotes `s` as a JSON string
+pub fn json_string(s: &str) -> String {
+    let mut out = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+
+    out
+}
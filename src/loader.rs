@@ -0,0 +1,155 @@
+//! The part of a file a loader reads: the ELF header, the program headers and the
+//! dynamic entries, without any of the section machinery.
+//!
+//! `ElfParser::parse_loader_view` never reads the section header table, the string
+//! tables nor the symbols, and copies no segment contents, so a `LoaderView` costs
+//! the two header tables and the dynamic entries. The only section header it may
+//! read is the initial one, when the program header count escapes to it through
+//! `PN_XNUM`. The view only relies on the header and segment decoding, but still
+//! builds against `std` like the rest of the crate.
+
+use crate::bytes::ParseContext;
+use crate::elf::ehdr::Elf64Hdr;
+use crate::elf::phdr::{ELF64Dyn, Elf64PHdr, PType, PTypeData};
+use crate::memory::{self, MemoryImage, DEFAULT_MAX_MEMORY_SIZE};
+use crate::options::ParseOptions;
+use crate::parser::{ElfParser, ParseError};
+use log::debug;
+
+/// The ELF header and program headers of a file, borrowing it to read segment
+/// contents on demand
+#[derive(Debug, Clone)]
+pub struct LoaderView<'a> {
+    pub headers: Elf64Hdr,
+    /// Encoding of the file, used to decode every multi-byte field
+    pub ctx: ParseContext,
+    /// Program headers, in table order. The contents of loadable segments aren't
+    /// read, they're `PTypeData::Skipped`
+    pub program_headers: Vec<Elf64PHdr>,
+    data: &'a [u8],
+}
+
+/// Translates `vaddr` into the offset of the file byte loaded at it through the
+/// loadable segments of `program_headers`
+pub(crate) fn vaddr_to_offset(
+    program_headers: &[Elf64PHdr],
+    vaddr: u64,
+) -> Result<u64, ParseError> {
+    program_headers
+        .iter()
+        .filter(|ph| ph.p_type == PType::PtLoad)
+        .find(|ph| vaddr >= ph.vaddr.0 && vaddr - ph.vaddr.0 < ph.filesz)
+        .and_then(|ph| ph.offset.checked_add(vaddr - ph.vaddr.0))
+        .ok_or(ParseError::UnmappedAddress(vaddr))
+}
+
+impl LoaderView<'_> {
+    /// Returns the contents of the whole file
+    pub fn data(&self) -> &[u8] {
+        self.data
+    }
+
+    /// Virtual address execution starts at
+    pub fn entry(&self) -> u64 {
+        self.headers.entry.0
+    }
+
+    /// Returns the entries of the dynamic segment in file order, if the file has one
+    pub fn dynamic_entries(&self) -> Option<&[ELF64Dyn]> {
+        self.program_headers
+            .iter()
+            .find_map(|ph| match &ph.section {
+                PTypeData::PtDynamicData(entries) => Some(entries.as_slice()),
+                _ => None,
+            })
+    }
+
+    /// Returns the first dynamic entry tagged `d_tag`
+    pub fn dynamic_entry(&self, d_tag: i64) -> Option<&ELF64Dyn> {
+        self.dynamic_entries()?.iter().find(|d| d.d_tag == d_tag)
+    }
+
+    /// Returns the interpreter requested through `PT_INTERP`, without its terminator
+    pub fn interpreter(&self) -> Option<&[u8]> {
+        let ph = self
+            .program_headers
+            .iter()
+            .find(|ph| ph.p_type == PType::PtInterp)?;
+        let bytes = self.segment_data(ph)?;
+
+        Some(bytes.split(|b| *b == 0).next().unwrap_or(bytes))
+    }
+
+    /// Returns the file image of the segment `ph`
+    pub fn segment_data(&self, ph: &Elf64PHdr) -> Option<&[u8]> {
+        let offset = ph.offset as usize;
+
+        self.data
+            .get(offset..offset.checked_add(ph.filesz as usize)?)
+    }
+
+    /// Translates a virtual address into the offset of the file byte loaded at it
+    pub fn vaddr_to_offset(&self, vaddr: u64) -> Result<u64, ParseError> {
+        vaddr_to_offset(&self.program_headers, vaddr)
+    }
+
+    /// Translates the offset of a file byte into the virtual address it's loaded at,
+    /// if a loadable segment maps it
+    pub fn offset_to_vaddr(&self, offset: u64) -> Option<u64> {
+        self.program_headers
+            .iter()
+            .filter(|ph| ph.p_type == PType::PtLoad)
+            .find(|ph| offset >= ph.offset && offset - ph.offset < ph.filesz)
+            .and_then(|ph| ph.vaddr.0.checked_add(offset - ph.offset))
+    }
+
+    pub fn memory_image(&self) -> Result<MemoryImage, ParseError> {
+        self.memory_image_with(DEFAULT_MAX_MEMORY_SIZE)
+    }
+
+    /// Loads every `PT_LOAD` segment of the file as `ElfParser::memory_image_with`
+    pub fn memory_image_with(&self, max_size: u64) -> Result<MemoryImage, ParseError> {
+        memory::memory_image_of(&self.program_headers, self.data, self.entry(), max_size)
+    }
+
+    /// Loads every `PT_LOAD` segment of the file into `memory` mapped at `base`, as
+    /// `ElfParser::load_into`
+    pub fn load_into(&self, memory: &mut [u8], base: u64) -> Result<(), ParseError> {
+        memory::load_segments_into(&self.program_headers, self.data, memory, base)
+    }
+}
+
+impl ElfParser<'_> {
+    /// Reads only what a loader needs of the file held by `data`, see `LoaderView`
+    pub fn parse_loader_view(data: &[u8]) -> Result<LoaderView<'_>, ParseError> {
+        ElfParser::parse_loader_view_with(data, &ParseOptions::default())
+    }
+
+    /// Reads only what a loader needs of the file held by `data`, honoring the
+    /// options about the ELF header and the program header table. Segment contents
+    /// are never copied, whatever `ParseOptions::load_segment_data` says
+    pub fn parse_loader_view_with<'d>(
+        data: &'d [u8],
+        options: &ParseOptions,
+    ) -> Result<LoaderView<'d>, ParseError> {
+        let options = options.clone().load_segment_data(false);
+
+        let headers = Elf64Hdr::parse_with(data, &options)?;
+        let ctx = ParseContext::from(&headers.ident);
+        // segment contents aren't read, so no anomaly is found in them
+        let program_headers = Elf64PHdr::parse(data, &ctx, &headers, &options, &mut vec![])?;
+
+        debug!(
+            "loader view of {} program headers, entry {:?}",
+            program_headers.len(),
+            headers.entry
+        );
+
+        Ok(LoaderView {
+            headers,
+            ctx,
+            program_headers,
+            data,
+        })
+    }
+}
@@ -0,0 +1,1374 @@
+use elf_parser::abi::{abi_diff, AbiDiff};
+use elf_parser::deps::DependencyGraph;
+use elf_parser::display::{DisplayTable, Named};
+use elf_parser::elf::ehdr::machine_name;
+use elf_parser::elf::note::GnuPropertyKind;
+use elf_parser::elf::phdr::{PType, PTypeData, PF_EXEC};
+use elf_parser::elf::sym::{bind_name, type_name};
+use elf_parser::explain::Verdict;
+use elf_parser::exports::Export;
+use elf_parser::flat::FlatOptions;
+use elf_parser::linkage::{LibcFlavor, Linkage};
+use elf_parser::lint_report::json_string;
+use elf_parser::options::ParseOptions;
+use elf_parser::parser::{ElfParser, ParseError, ParseWarning};
+use elf_parser::section::Section;
+use std::ops::Range;
+use std::process::exit;
+
+const USAGE: &str = "Usage: elf-parser <option(s)> elf-file
+       elf-parser abidiff old-elf-file new-elf-file
+       elf-parser deps [--dot=<out>] [--order] directory
+       elf-parser explain elf-file
+ Display information about the contents of ELF format files
+ Options are:
+  -a --all               Equivalent to: -h -l -S -s -d
+  -h --file-header       Display the ELF file header
+  -l --program-headers   Display the program headers
+  -S --section-headers   Display the sections' header
+  -s --syms              Display the symbol tables
+  -r --relocs            Display the relocations
+  -d --dynamic           Display the dynamic section
+  -n --notes             Display the core notes
+     --size              Display how the file size is split among its structures
+     --bloat             Display the largest functions and objects, and how much
+                         of each section they account for
+     --top=<n>           Limit --bloat to the <n> largest symbols, 20 by default
+     --regions           Display the memory range and load address of the
+                         allocated sections
+     --classify          Display a guess of what each section holds, from the
+                         statistics of its bytes
+     --exports           Display the symbols the file provides to others
+     --imports           Display the symbols the file needs from others
+     --undefined         List the undefined symbols of both symbol tables, one
+                         name per line, sorted
+     --linkage           Display whether the file is statically or dynamically
+                         linked, and against which C library
+     --entry-disasm=<n>  Disassemble the first <n> instructions at the entry point,
+                         needs the disasm feature
+     --checksec          Display the hardening features of the file and the
+                         anomalies found in it
+     --lint              Display the inconsistencies of the section headers and
+                         of the dynamic entries
+     --format=<format>   Print --lint as text, the default, or as a json or sarif
+                         report of every anomaly found while parsing
+  -o --output=<out>      Write --lint into <out> instead of printing it
+     --debug-info        Display the debug sections, their size and whether the
+                         file was stripped
+  -x --hex-dump=<glob>   Dump the contents of the matching sections as bytes
+  -p --strings=<glob>    Dump the contents of the matching sections as strings
+     --dump-section=<glob>
+                         Write the contents of the matching sections into
+                         <elf-file>.<section>
+     --output-binary=<out>
+                         Write the loadable segments into <out> laid out by
+                         physical address
+     --output-ihex=<out> Write the loadable segments into <out> as Intel HEX
+     --output-srec=<out> Write the loadable segments into <out> as S-records
+     --gap-fill=<byte>   Fill the gaps between segments of --output-binary with
+                         <byte> instead of 0
+     --range=<start>..<end>
+                         Restrict --output-binary to the given physical addresses
+     --carve             Extract every ELF image embedded in the file into
+                         <elf-file>.<offset>.elf
+     --strict            Fail if any anomaly is found while parsing, printing them
+                         as one JSON object per line
+     --provenance        Show the file bytes each anomaly was read from
+  -H --help              Display this information
+ abidiff lists the exported symbols and versions removed, added or changed between
+ both files, exiting with 1 when anything was removed
+ deps lists the libraries needed by each ELF file of the directory and which file
+ of the directory provides them. --dot writes the graph into <out> for Graphviz,
+ and --order prints the order to load the files in, exiting with 1 on cycles
+ explain walks the fields of the ELF header, printing their bytes, meaning and
+ whether they're valid, exiting with 1 when any is invalid";
+
+/// How `--lint` is printed, picked by `--format`
+#[derive(Default)]
+enum LintFormat {
+    #[default]
+    Text,
+    Json,
+    Sarif,
+}
+
+#[derive(Default)]
+struct Options {
+    file_header: bool,
+    program_headers: bool,
+    section_headers: bool,
+    symbols: bool,
+    relocs: bool,
+    dynamic: bool,
+    notes: bool,
+    size: bool,
+    bloat: bool,
+    top: Option<usize>,
+    regions: bool,
+    classify: bool,
+    checksec: bool,
+    lint: bool,
+    lint_format: LintFormat,
+    lint_output: Option<String>,
+    debug_info: bool,
+    entry_disasm: Option<usize>,
+    exports: bool,
+    imports: bool,
+    undefined: bool,
+    linkage: bool,
+    carve: bool,
+    strict: bool,
+    provenance: bool,
+    hex_dump: Vec<String>,
+    strings: Vec<String>,
+    dump_section: Vec<String>,
+    output_binary: Option<String>,
+    output_ihex: Option<String>,
+    output_srec: Option<String>,
+    flat: FlatOptions,
+    file: Option<String>,
+}
+
+/// Splits `--flag=value` into its parts, taking the value from the next argument for
+/// `--flag value` and the short forms
+fn option_value(
+    arg: &str,
+    args: &mut impl Iterator<Item = String>,
+) -> Result<(String, String), String> {
+    if let Some((flag, value)) = arg.split_once('=') {
+        return Ok((flag.to_string(), value.to_string()));
+    }
+
+    match args.next() {
+        Some(value) => Ok((arg.to_string(), value)),
+        None => Err(format!("option '{}' requires an argument\n{}", arg, USAGE)),
+    }
+}
+
+/// Parses a decimal number, or a hexadecimal one prefixed by `0x`
+fn parse_number(s: &str) -> Option<u64> {
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    }
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-a" | "--all" => {
+                options.file_header = true;
+                options.program_headers = true;
+                options.section_headers = true;
+                options.symbols = true;
+                options.dynamic = true;
+            }
+            "-h" | "--file-header" => options.file_header = true,
+            "-l" | "--program-headers" | "--segments" => options.program_headers = true,
+            "-S" | "--section-headers" | "--sections" => options.section_headers = true,
+            "-s" | "--syms" | "--symbols" => options.symbols = true,
+            "-r" | "--relocs" => options.relocs = true,
+            "-d" | "--dynamic" => options.dynamic = true,
+            "-n" | "--notes" => options.notes = true,
+            "--size" => options.size = true,
+            "--bloat" => options.bloat = true,
+            "--regions" => options.regions = true,
+            "--classify" => options.classify = true,
+            "--checksec" => options.checksec = true,
+            "--lint" => options.lint = true,
+            "--debug-info" => options.debug_info = true,
+            "--exports" => options.exports = true,
+            "--imports" => options.imports = true,
+            "--undefined" => options.undefined = true,
+            "--linkage" => options.linkage = true,
+            "--carve" => options.carve = true,
+            "--strict" => options.strict = true,
+            "--provenance" => options.provenance = true,
+            a if a.split('=').next() == Some("--top") => {
+                let (_, value) = option_value(a, &mut args)?;
+                match value.parse() {
+                    Ok(n) => options.top = Some(n),
+                    Err(_) => return Err(format!("invalid symbol count '{}'", value)),
+                }
+            }
+            a if a.split('=').next() == Some("--entry-disasm") => {
+                let (_, value) = option_value(a, &mut args)?;
+                match value.parse() {
+                    Ok(n) => options.entry_disasm = Some(n),
+                    Err(_) => return Err(format!("invalid instruction count '{}'", value)),
+                }
+            }
+            a if [
+                "--output-binary",
+                "--output-ihex",
+                "--output-srec",
+                "--gap-fill",
+                "--range",
+            ]
+            .contains(&a.split('=').next().unwrap_or_default()) =>
+            {
+                let (flag, value) = option_value(a, &mut args)?;
+                match flag.as_str() {
+                    "--output-binary" => options.output_binary = Some(value),
+                    "--output-ihex" => options.output_ihex = Some(value),
+                    "--output-srec" => options.output_srec = Some(value),
+                    "--gap-fill" => match parse_number(&value) {
+                        Some(byte) if byte <= 0xff => options.flat.gap_fill = byte as u8,
+                        _ => return Err(format!("invalid gap fill byte '{}'", value)),
+                    },
+                    _ => {
+                        let range = value.split_once("..").and_then(|(start, end)| {
+                            Some(parse_number(start)?..parse_number(end)?)
+                        });
+                        match range {
+                            Some(range) if range.start < range.end => {
+                                options.flat.range = Some(range)
+                            }
+                            _ => return Err(format!("invalid address range '{}'", value)),
+                        }
+                    }
+                }
+            }
+            a if ["--format", "-o", "--output"]
+                .contains(&a.split('=').next().unwrap_or_default()) =>
+            {
+                let (flag, value) = option_value(a, &mut args)?;
+                match (flag.as_str(), value.as_str()) {
+                    ("--format", "text") => options.lint_format = LintFormat::Text,
+                    ("--format", "json") => options.lint_format = LintFormat::Json,
+                    ("--format", "sarif") => options.lint_format = LintFormat::Sarif,
+                    ("--format", _) => return Err(format!("invalid format '{}'", value)),
+                    _ => options.lint_output = Some(value),
+                }
+            }
+            a if ["-x", "--hex-dump", "-p", "--strings", "--dump-section"]
+                .contains(&a.split('=').next().unwrap_or_default()) =>
+            {
+                let (flag, value) = option_value(a, &mut args)?;
+                match flag.as_str() {
+                    "-x" | "--hex-dump" => options.hex_dump.push(value),
+                    "-p" | "--strings" => options.strings.push(value),
+                    _ => options.dump_section.push(value),
+                }
+            }
+            "-H" | "--help" => return Err(USAGE.to_string()),
+            a if a.starts_with('-') => {
+                return Err(format!("unrecognized option '{}'\n{}", a, USAGE))
+            }
+            _ if options.file.is_some() => return Err(USAGE.to_string()),
+            _ => options.file = Some(arg),
+        }
+    }
+
+    Ok(options)
+}
+
+fn print_sections(parser: &ElfParser) {
+    let sections: Vec<_> = parser.sections().collect();
+    // sections whose name can't be resolved show a guess in brackets
+    let names: Vec<String> = sections
+        .iter()
+        .map(|s| s.name_or_guess().to_string())
+        .collect();
+
+    let rows = sections
+        .iter()
+        .zip(names.iter())
+        .map(|(s, name)| Named::new(name, s.header()))
+        .collect();
+
+    println!("{}", DisplayTable::new("Section Headers", rows));
+}
+
+fn print_segments(parser: &ElfParser) {
+    let rows = parser.program_headers.iter().collect::<Vec<_>>();
+
+    println!("{}", DisplayTable::new("Program Headers", rows));
+}
+
+fn print_symbols(parser: &ElfParser) {
+    for table in &parser.symbol_tables {
+        let section = &parser.section_headers[table.section_idx];
+        let names: Vec<String> = table
+            .symbols
+            .iter()
+            .map(|sym| parser.symbol_display_name(table, sym).unwrap_or_default())
+            .collect();
+
+        let rows = table
+            .symbols
+            .iter()
+            .zip(names.iter())
+            .map(|(sym, name)| Named::new(name, sym))
+            .collect();
+
+        let title = format!(
+            "Symbol table '{}' contains {} entries",
+            parser.section_name(section).unwrap_or_default(),
+            table.symbols.len()
+        );
+
+        println!("{}", DisplayTable::new(title, rows));
+    }
+}
+
+fn print_relocs(parser: &ElfParser) -> Result<(), ParseError> {
+    let sections = parser.relocations_named()?;
+    if sections.is_empty() {
+        println!("There are no relocations in this file.\n");
+    }
+
+    for section in sections {
+        let title = format!(
+            "Relocation section '{}' at offset {:#x} contains {} entries",
+            section.name,
+            parser.section_headers[section.section_idx].offset,
+            section.relocations.len()
+        );
+
+        println!("{}", DisplayTable::new(title, section.relocations));
+    }
+
+    Ok(())
+}
+
+fn print_dynamic(parser: &ElfParser) {
+    for ph in &parser.program_headers {
+        if let PTypeData::PtDynamicData(entries) = &ph.section {
+            let title = format!(
+                "Dynamic section at offset {:#x} contains {} entries",
+                ph.offset,
+                entries.len()
+            );
+
+            println!("{}", DisplayTable::new(title, entries.iter().collect()));
+        }
+    }
+}
+
+fn print_size(parser: &ElfParser) {
+    let breakdown = parser.size_breakdown();
+    let total = breakdown.file_size.max(1) as f64;
+
+    let mut rows: Vec<(&str, String, u64, u64)> = std::iter::once(&breakdown.header)
+        .chain(breakdown.program_header_table.iter())
+        .chain(breakdown.section_header_table.iter())
+        .map(|e| ("table", e.name.clone(), e.range.start, e.size()))
+        .chain(
+            breakdown
+                .sections
+                .iter()
+                .map(|e| ("section", e.name.clone(), e.range.start, e.size())),
+        )
+        .chain(
+            breakdown
+                .segments
+                .iter()
+                .map(|e| ("segment", e.name.clone(), e.range.start, e.size())),
+        )
+        .chain(
+            breakdown
+                .residual
+                .iter()
+                .map(|r| ("slack", String::new(), r.start, r.end - r.start)),
+        )
+        .collect();
+    rows.sort_by(|a, b| b.3.cmp(&a.3).then(a.2.cmp(&b.2)));
+
+    println!("File size breakdown ({} bytes):", breakdown.file_size);
+    println!(
+        "  {:<8} {:<24} {:>10} {:>10} {:>7}",
+        "Kind", "Name", "Offset", "Size", "%"
+    );
+    for (kind, name, offset, size) in rows {
+        println!(
+            "  {:<8} {:<24} {:>#10x} {:>10} {:>6.2}%",
+            kind,
+            name,
+            offset,
+            size,
+            size as f64 * 100.0 / total
+        );
+    }
+    println!(
+        "  covered: {} bytes ({:.2}%), slack: {} bytes ({:.2}%)\n",
+        breakdown.covered,
+        breakdown.covered as f64 * 100.0 / total,
+        breakdown.residual_size(),
+        breakdown.residual_size() as f64 * 100.0 / total
+    );
+}
+
+fn print_bloat(parser: &ElfParser, top: usize) {
+    let report = parser.size_report();
+    let file_size = parser.data().len().max(1) as f64;
+
+    println!("Largest functions and objects:");
+    println!(
+        "  {:>7} {:>8} {:>10}  {:<20} Name",
+        "File", "Sect.", "Size", "Section"
+    );
+    for (section, symbol) in report.largest(top) {
+        println!(
+            "  {:>6.2}% {:>7.2}% {:>10}  {:<20} {}",
+            symbol.size as f64 * 100.0 / file_size,
+            symbol.size as f64 * 100.0 / section.size.max(1) as f64,
+            symbol.size,
+            section.name,
+            symbol.name
+        );
+    }
+
+    println!("\nSection totals:");
+    println!(
+        "  {:<20} {:>10} {:>10} {:>10}",
+        "Section", "Size", "Symbols", "Residual"
+    );
+    for section in &report.sections {
+        println!(
+            "  {:<20} {:>10} {:>10} {:>10}",
+            section.name,
+            section.size,
+            section.symbols_size(),
+            section.residual()
+        );
+    }
+
+    let debug = &report.debug_info;
+    if debug.has_debug_info() {
+        println!(
+            "  {:<20} {:>10} ({:.2}% of the file, {} uncompressed)",
+            "debug info",
+            debug.file_size(),
+            debug.file_size() as f64 * 100.0 / file_size,
+            debug.uncompressed_size()
+        );
+    }
+    println!();
+}
+
+fn print_regions(parser: &ElfParser) {
+    let regions = parser.region_map();
+
+    println!("Memory regions ({}):", regions.len());
+    println!(
+        "  {:<24} {:<18} {:<18} {:<18} Kind",
+        "Name", "Start", "End", "Load address"
+    );
+    for region in regions {
+        println!(
+            "  {:<24} {:#018x} {:#018x} {:#018x} {}",
+            region.name,
+            region.start,
+            region.end,
+            region.lma,
+            if region.initialized { "init" } else { "zero" }
+        );
+    }
+    println!();
+}
+
+fn print_classes(parser: &ElfParser) {
+    println!("Section contents:");
+    println!("  [Nr] {:<24} {:>10}  Guess", "Name", "Size");
+    for section in parser.sections() {
+        let guess = match parser.classify_section(section.index()) {
+            Ok(class) => class.to_string(),
+            Err(_) => "out of the file".to_string(),
+        };
+
+        println!(
+            "  [{:>2}] {:<24} {:>10}  {}",
+            section.index(),
+            section.name_or_guess().to_string(),
+            section.header().size,
+            guess
+        );
+    }
+    println!();
+}
+
+/// Renders the name of an export the way the linker refers to it, `name@@VERSION`
+/// for default versions and `name@VERSION` for the rest
+fn versioned_name(export: &Export) -> String {
+    match (&export.version, export.default_version) {
+        (Some(v), true) => format!("{}@@{}", export.name, v),
+        (Some(v), false) => format!("{}@{}", export.name, v),
+        (None, _) => export.name.clone(),
+    }
+}
+
+fn print_exports(parser: &ElfParser) -> Result<(), ParseError> {
+    let exports = parser.exports()?;
+
+    println!("Exported symbols ({}):", exports.len());
+    println!(
+        "  {:<18} {:>6} {:<7} {:<6} Name",
+        "Address", "Size", "Type", "Bind"
+    );
+    for export in exports {
+        println!(
+            "  {:#018x} {:>6} {:<7} {:<6} {}",
+            export.address,
+            export.size,
+            type_name(export.sym_type),
+            bind_name(export.binding),
+            versioned_name(&export)
+        );
+    }
+    println!();
+
+    Ok(())
+}
+
+fn print_imports(parser: &ElfParser) -> Result<(), ParseError> {
+    let imports = parser.imports()?;
+
+    println!("Imported symbols ({}):", imports.len());
+    println!("  {:<7} {:<6} {:<40} Library", "Type", "Bind", "Name");
+    for import in imports {
+        let name = match &import.version {
+            Some(v) => format!("{}@{}", import.name, v),
+            None => import.name.clone(),
+        };
+
+        println!(
+            "  {:<7} {:<6} {:<40} {}",
+            type_name(import.sym_type),
+            bind_name(import.binding),
+            name,
+            import.library.as_deref().unwrap_or("")
+        );
+    }
+    println!();
+
+    Ok(())
+}
+
+/// Lists the undefined symbols bare, so they can be compared against the defined
+/// symbols of another file with the usual text tools
+fn print_undefined(parser: &ElfParser) -> Result<(), ParseError> {
+    for name in parser.symbol_summary()?.undefined() {
+        println!("{}", name);
+    }
+
+    Ok(())
+}
+
+fn print_linkage(parser: &ElfParser) -> Result<(), ParseError> {
+    match parser.linkage()? {
+        Linkage::Static => println!("Linkage: static"),
+        Linkage::StaticPie => println!("Linkage: static-pie"),
+        Linkage::Dynamic {
+            interpreter,
+            needed,
+        } => {
+            println!("Linkage: dynamic");
+            if !interpreter.is_empty() {
+                println!("  Interpreter: {}", interpreter);
+            }
+            for library in needed {
+                println!("  Needed: {}", library);
+            }
+        }
+    }
+
+    let libc = match parser.libc_flavor()? {
+        Some(LibcFlavor::Glibc) => "glibc",
+        Some(LibcFlavor::Musl) => "musl",
+        None => "unknown",
+    };
+    println!("C library: {}\n", libc);
+
+    Ok(())
+}
+
+fn print_checksec(parser: &ElfParser) {
+    let segment = |p_type| parser.program_headers.iter().find(|ph| ph.p_type == p_type);
+
+    let nx = match segment(PType::PtGnuStack) {
+        Some(ph) if ph.flags & PF_EXEC == 0 => "enabled",
+        Some(_) => "disabled",
+        None => "unknown (no PT_GNU_STACK)",
+    };
+    let bind_now = parser.dyn_flags().is_some_and(|f| f.bind_now())
+        || parser.dyn_flags_1().is_some_and(|f| f.now());
+    let relro = match (segment(PType::PtGnuRelro), bind_now) {
+        (Some(_), true) => "full",
+        (Some(_), false) => "partial",
+        (None, _) => "none",
+    };
+
+    let cfi: Vec<String> = parser
+        .gnu_properties()
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|p| match p.kind(parser.headers.machine, &parser.ctx) {
+            GnuPropertyKind::X86Feature1And { ibt, shstk, .. } => {
+                Some(format!("IBT: {}, SHSTK: {}", ibt, shstk))
+            }
+            GnuPropertyKind::Aarch64Feature1And { bti, pac, .. } => {
+                Some(format!("BTI: {}, PAC: {}", bti, pac))
+            }
+            GnuPropertyKind::RiscvFeature1And {
+                lp_unlabeled,
+                shadow_stack,
+                lp_func_sig,
+                ..
+            } => Some(format!(
+                "Zicfilp: {}, Zicfiss: {}",
+                lp_unlabeled || lp_func_sig,
+                shadow_stack
+            )),
+            _ => None,
+        })
+        .collect();
+
+    println!("Security features:");
+    println!("  {:<12}{}", "NX:", nx);
+    println!("  {:<12}{}", "RELRO:", relro);
+    println!(
+        "  {:<12}{}",
+        "CFI:",
+        if cfi.is_empty() {
+            "none".to_string()
+        } else {
+            cfi.join(", ")
+        }
+    );
+
+    let textrel = match (
+        parser.has_text_relocations(),
+        parser.text_relocation_targets(),
+    ) {
+        (false, _) => "none".to_string(),
+        (true, targets) if targets.is_empty() => "declared".to_string(),
+        (true, targets) => format!("{} relocations in read-only segments", targets.len()),
+    };
+    println!("  {:<12}{}", "TEXTREL:", textrel);
+
+    let rpath_issues = parser.rpath_issues().unwrap_or_default();
+    let rpath = match parser.rpath_entries().unwrap_or_default().len() {
+        0 => "none".to_string(),
+        n => format!("{} entries, {} insecure", n, rpath_issues.len()),
+    };
+    println!("  {:<12}{}", "RPATH:", rpath);
+    for (entry, issues) in &rpath_issues {
+        for issue in issues {
+            println!("    {} {:?}: {}", entry.tag, entry.raw, issue);
+        }
+    }
+
+    println!("Anomalies:");
+    if parser.warnings.is_empty() {
+        println!("  none");
+    }
+    for warning in &parser.warnings {
+        match parser.warning_provenance(warning) {
+            Some(source) => println!(
+                "  {} (from bytes {:#x}..{:#x})",
+                warning, source.start, source.end
+            ),
+            None => println!("  {}", warning),
+        }
+    }
+    println!();
+}
+
+fn lint_text(parser: &ElfParser) -> String {
+    let mut out = String::new();
+
+    for (title, warnings) in [
+        ("Section lint", parser.lint_sections()),
+        ("Dynamic lint", parser.verify_dynamic()),
+    ] {
+        out.push_str(&format!("{}:\n", title));
+        if warnings.is_empty() {
+            out.push_str("  no issues found\n");
+        }
+        for warning in &warnings {
+            out.push_str(&format!("  {:<28}{}\n", warning.code(), warning));
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+fn print_lint(parser: &ElfParser, options: &Options, file: &str) -> std::io::Result<()> {
+    // located by canonical path, so reports of several runs agree
+    let uri = parser
+        .source()
+        .map_or(file.to_string(), |s| s.path.display().to_string());
+    let report = match options.lint_format {
+        LintFormat::Text => lint_text(parser),
+        LintFormat::Json => parser.lint_report(&uri).to_json() + "\n",
+        LintFormat::Sarif => parser.lint_report(&uri).to_sarif() + "\n",
+    };
+
+    match &options.lint_output {
+        Some(output) => std::fs::write(output, report),
+        None => {
+            print!("{}", report);
+            Ok(())
+        }
+    }
+}
+
+fn print_debug_info(parser: &ElfParser) {
+    let summary = parser.debug_info_summary();
+    let file_size = parser.data().len().max(1) as f64;
+
+    println!("Debug info:");
+    println!(
+        "  debug sections: {}",
+        if summary.has_debug_info() {
+            "yes"
+        } else {
+            "no"
+        }
+    );
+    println!(
+        "  symbol table:   {}",
+        if summary.has_symtab {
+            "yes"
+        } else {
+            "no (stripped)"
+        }
+    );
+    println!(
+        "  debug link:     {}",
+        summary.debuglink.as_deref().unwrap_or("none")
+    );
+
+    if summary.has_debug_info() {
+        println!(
+            "\n  {:<24} {:>10} {:>12}  Compression",
+            "Section", "Size", "Uncompressed"
+        );
+        for section in &summary.sections {
+            println!(
+                "  {:<24} {:>10} {:>12}  {}",
+                section.name,
+                section.file_size,
+                section
+                    .uncompressed_size
+                    .map_or("?".to_string(), |size| size.to_string()),
+                section
+                    .compression
+                    .map_or("none".to_string(), |c| c.to_string())
+            );
+        }
+        println!(
+            "  {:<24} {:>10} {:>12}  {:.2}% of the file",
+            "total",
+            summary.file_size(),
+            summary.uncompressed_size(),
+            summary.file_size() as f64 * 100.0 / file_size
+        );
+    }
+    println!();
+}
+
+#[cfg(feature = "disasm")]
+fn print_entry_disasm(parser: &ElfParser, n_insns: usize) -> Result<(), ParseError> {
+    let entry = parser.headers.entry.0;
+
+    println!("Disassembly at entry point {:#x}:", entry);
+    for instruction in parser.read_code(entry, n_insns)? {
+        println!("{}", instruction);
+    }
+    println!();
+
+    Ok(())
+}
+
+#[cfg(not(feature = "disasm"))]
+fn print_entry_disasm(_: &ElfParser, _: usize) -> Result<(), ParseError> {
+    eprintln!("elf-parser: --entry-disasm needs elf-parser built with the disasm feature");
+    exit(1);
+}
+
+/// Sections matching `pattern`, warning when there are none
+fn matching_sections<'a>(parser: &'a ElfParser, pattern: &'a str) -> Vec<Section<'a>> {
+    let sections: Vec<_> = parser.sections().without_null().matching(pattern).collect();
+    if sections.is_empty() {
+        eprintln!(
+            "elf-parser: Warning: Section '{}' was not dumped because it does not exist",
+            pattern
+        );
+    }
+
+    sections
+}
+
+fn hex_dump(parser: &ElfParser, pattern: &str) {
+    for section in matching_sections(parser, pattern) {
+        let data = match section.data() {
+            Ok(data) if !data.is_empty() => data,
+            _ => {
+                println!("Section '{}' has no data to dump.\n", section.name());
+                continue;
+            }
+        };
+
+        println!("Hex dump of section '{}':", section.name());
+        let addr = section.header().addr.0;
+        for (idx, line) in data.chunks(16).enumerate() {
+            let words: Vec<String> = line
+                .chunks(4)
+                .map(|w| w.iter().map(|b| format!("{:02x}", b)).collect())
+                .collect();
+            let ascii: String = line
+                .iter()
+                .map(|b| match b {
+                    0x20..=0x7e => *b as char,
+                    _ => '.',
+                })
+                .collect();
+
+            println!(
+                "  {:#010x} {:<35} {}",
+                addr + idx as u64 * 16,
+                words.join(" "),
+                ascii
+            );
+        }
+        println!();
+    }
+}
+
+fn string_dump(parser: &ElfParser, pattern: &str) {
+    for section in matching_sections(parser, pattern) {
+        let data = section.data().unwrap_or_default();
+
+        println!("String dump of section '{}':", section.name());
+        let mut off = 0;
+        for s in data.split(|b| *b == 0) {
+            if !s.is_empty() {
+                println!("  [{:>6x}]  {}", off, String::from_utf8_lossy(s));
+            }
+            off += s.len() + 1;
+        }
+        println!();
+    }
+}
+
+fn dump_section(file: &str, parser: &ElfParser, pattern: &str) -> std::io::Result<()> {
+    for section in matching_sections(parser, pattern) {
+        let name = section.name().trim_start_matches('.').replace('/', "_");
+        let output = format!("{}.{}", file, name);
+
+        std::fs::write(&output, section.data().unwrap_or_default())?;
+        println!("Wrote section '{}' into {}", section.name(), output);
+    }
+
+    Ok(())
+}
+
+fn output_binary(parser: &ElfParser, options: &FlatOptions, output: &str) -> Result<(), String> {
+    let image = match parser.to_flat_binary_with(options) {
+        Ok(image) => image,
+        Err(ParseError::FlatImageTooLarge { base, end }) => {
+            return Err(format!(
+                "loadable segments span {:#x}..{:#x} ({} bytes), more than the {} bytes allowed",
+                base,
+                end,
+                end - base,
+                options.max_size
+            ))
+        }
+        Err(e) => return Err(format!("{:?}", e)),
+    };
+
+    std::fs::write(output, &image.data).map_err(|e| e.to_string())?;
+    println!(
+        "Wrote {} bytes from physical address {:#x} into {}",
+        image.data.len(),
+        image.base,
+        output
+    );
+
+    Ok(())
+}
+
+fn write_records(output: &str, records: String) {
+    if let Err(e) = std::fs::write(output, records) {
+        eprintln!("elf-parser: {}: {}", output, e);
+        exit(1);
+    }
+    println!("Wrote the loadable segments into {}", output);
+}
+
+fn carve(file: &str, data: &[u8]) -> std::io::Result<()> {
+    let candidates = elf_parser::scan_blob(data);
+
+    println!("Found {} embedded ELF images:", candidates.len());
+    println!("  {:>10} {:>10} {:<16} Output", "Offset", "Size", "Machine");
+    for elf in candidates {
+        let start = elf.offset as usize;
+        let end = elf
+            .offset
+            .saturating_add(elf.size_estimate)
+            .min(data.len() as u64) as usize;
+        let output = format!("{}.{:#x}.elf", file, elf.offset);
+
+        std::fs::write(&output, &data[start..end])?;
+        println!(
+            "  {:>#10x} {:>10} {:<16} {}{}",
+            elf.offset,
+            end - start,
+            machine_name(elf.header.machine),
+            output,
+            if (end - start) as u64 != elf.size_estimate {
+                " (truncated)"
+            } else {
+                ""
+            }
+        );
+    }
+
+    Ok(())
+}
+
+fn print_abi_diff(diff: &AbiDiff) {
+    if diff.is_empty() {
+        println!("No ABI changes");
+        return;
+    }
+
+    if !diff.removed.is_empty() {
+        println!("Removed symbols ({}):", diff.removed.len());
+        for export in &diff.removed {
+            println!("  - {}", versioned_name(export));
+        }
+    }
+
+    if !diff.added.is_empty() {
+        println!("Added symbols ({}):", diff.added.len());
+        for export in &diff.added {
+            println!("  + {}", versioned_name(export));
+        }
+    }
+
+    if !diff.changed.is_empty() {
+        println!("Changed symbols ({}):", diff.changed.len());
+        for change in &diff.changed {
+            let (old, new) = (&change.old, &change.new);
+            let mut changes = vec![];
+            if old.size != new.size {
+                changes.push(format!("size {} -> {}", old.size, new.size));
+            }
+            if old.binding != new.binding {
+                changes.push(format!(
+                    "binding {} -> {}",
+                    bind_name(old.binding),
+                    bind_name(new.binding)
+                ));
+            }
+            if old.default_version != new.default_version {
+                changes.push(format!(
+                    "{} -> {}",
+                    versioned_name(old),
+                    versioned_name(new)
+                ));
+            }
+
+            println!("  ~ {}: {}", versioned_name(old), changes.join(", "));
+        }
+    }
+
+    for (title, versions) in [
+        ("Removed versions", &diff.removed_versions),
+        ("Added versions", &diff.added_versions),
+    ] {
+        if !versions.is_empty() {
+            println!("{} ({}): {}", title, versions.len(), versions.join(", "));
+        }
+    }
+}
+
+/// Runs the `explain` subcommand, returning the exit code
+fn explain(args: &[String]) -> i32 {
+    let [file] = args else {
+        eprintln!("{}", USAGE);
+        return 2;
+    };
+
+    let data = match std::fs::read(file) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("elf-parser: {}: {}", file, e);
+            return 2;
+        }
+    };
+
+    let explanations = ElfParser::explain(&data);
+    for explanation in &explanations {
+        println!("{}", explanation);
+    }
+    if let Err(e) = ElfParser::parse(&data) {
+        println!("\nThe file is rejected: {}", e);
+    }
+
+    explanations.iter().any(|e| e.verdict == Verdict::Invalid) as i32
+}
+
+/// Runs the `abidiff` subcommand, returning the exit code
+fn abidiff(args: &[String]) -> i32 {
+    let [old, new] = args else {
+        eprintln!("{}", USAGE);
+        return 2;
+    };
+
+    let mut parsers = vec![];
+    for file in [old, new] {
+        match ElfParser::open(file) {
+            Ok(parser) => parsers.push(parser),
+            Err(e) => {
+                eprintln!("elf-parser: {}: {}", file, e);
+                return 2;
+            }
+        }
+    }
+    for (prefix, parser) in ["---", "+++"].iter().zip(&parsers) {
+        if let Some(source) = parser.source() {
+            println!("{} {}", prefix, source);
+        }
+    }
+
+    match abi_diff(&parsers[0], &parsers[1]) {
+        Ok(diff) => {
+            print_abi_diff(&diff);
+            diff.has_removals() as i32
+        }
+        Err(e) => {
+            eprintln!("elf-parser: {} {}: {:?}", old, new, e);
+            2
+        }
+    }
+}
+
+/// Runs the `deps` subcommand, returning the exit code
+fn deps(args: &[String]) -> i32 {
+    let mut dot = None;
+    let mut order = false;
+    let mut dir = None;
+    let mut args = args.iter().cloned();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--order" => order = true,
+            a if a.split('=').next() == Some("--dot") => match option_value(a, &mut args) {
+                Ok((_, value)) => dot = Some(value),
+                Err(message) => {
+                    eprintln!("{}", message);
+                    return 2;
+                }
+            },
+            _ if dir.is_none() && !arg.starts_with('-') => dir = Some(arg),
+            _ => {
+                eprintln!("{}", USAGE);
+                return 2;
+            }
+        }
+    }
+    let Some(dir) = dir else {
+        eprintln!("{}", USAGE);
+        return 2;
+    };
+
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("elf-parser: {}: {}", dir, e);
+            return 2;
+        }
+    };
+    let mut paths = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    // files that aren't ELF files are skipped
+    let contents = paths
+        .iter()
+        .filter_map(|p| Some((p.to_string_lossy().into_owned(), std::fs::read(p).ok()?)))
+        .collect::<Vec<_>>();
+    let files = contents
+        .iter()
+        .filter_map(|(path, data)| Some((path.clone(), ElfParser::parse(data).ok()?)))
+        .collect::<Vec<_>>();
+    let graph = DependencyGraph::build(&files);
+
+    if let Some(out) = dot {
+        if let Err(e) = std::fs::write(&out, graph.to_dot()) {
+            eprintln!("elf-parser: {}: {}", out, e);
+            return 2;
+        }
+    }
+
+    if order {
+        return match graph.load_order() {
+            Ok(order) => {
+                order
+                    .iter()
+                    .for_each(|idx| println!("{}", graph.nodes[*idx].file));
+                0
+            }
+            Err(cycles) => {
+                for cycle in cycles {
+                    let names = cycle
+                        .iter()
+                        .map(|idx| graph.nodes[*idx].name.as_str())
+                        .collect::<Vec<_>>();
+                    eprintln!("elf-parser: dependency cycle: {}", names.join(", "));
+                }
+                1
+            }
+        };
+    }
+
+    for (idx, node) in graph.nodes.iter().enumerate() {
+        println!("{} ({})", node.name, node.file);
+        for edge in graph.edges.iter().filter(|e| e.from == idx) {
+            match edge.to {
+                Some(to) => println!("  {} => {}", edge.needed, graph.nodes[to].file),
+                None => println!("  {} => not found", edge.needed),
+            }
+        }
+    }
+
+    0
+}
+
+/// Renders a warning as a single-line JSON object, along with the bytes of the
+/// structure it was found in, the missing locations being null
+fn diagnostic_json(warning: &ParseWarning, source: Option<Range<u64>>) -> String {
+    let number = |value: Option<u64>| value.map_or("null".to_string(), |v| v.to_string());
+    let source = source.map_or("null".to_string(), |r| {
+        format!("{{\"offset\":{},\"len\":{}}}", r.start, r.end - r.start)
+    });
+
+    format!(
+        "{{\"code\":{},\"message\":{},\"offset\":{},\"section\":{},\"segment\":{},\"source\":{}}}",
+        json_string(warning.code()),
+        json_string(&warning.to_string()),
+        number(warning.offset()),
+        number(warning.section().map(|idx| idx as u64)),
+        number(warning.segment().map(|idx| idx as u64)),
+        source,
+    )
+}
+
+fn main() {
+    if std::env::args().nth(1).as_deref() == Some("abidiff") {
+        let args: Vec<String> = std::env::args().skip(2).collect();
+        exit(abidiff(&args));
+    }
+    if std::env::args().nth(1).as_deref() == Some("explain") {
+        let args: Vec<String> = std::env::args().skip(2).collect();
+        exit(explain(&args));
+    }
+    if std::env::args().nth(1).as_deref() == Some("deps") {
+        let args: Vec<String> = std::env::args().skip(2).collect();
+        exit(deps(&args));
+    }
+
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            exit(1);
+        }
+    };
+
+    let Some(file) = &options.file else {
+        eprintln!("{}", USAGE);
+        exit(1);
+    };
+
+    if options.carve {
+        let data = match std::fs::read(file) {
+            Ok(data) => data,
+            Err(e) => {
+                eprintln!("elf-parser: {}: {}", file, e);
+                exit(1);
+            }
+        };
+        if let Err(e) = carve(file, &data) {
+            eprintln!("elf-parser: {}: {}", file, e);
+            exit(1);
+        }
+        return;
+    }
+
+    let parse_options = ParseOptions::new()
+        .strict(options.strict)
+        .track_provenance(options.provenance);
+    let parser = match ElfParser::open_with(file, &parse_options) {
+        Ok(parser) => parser,
+        Err(ParseError::StrictViolation(warnings)) => {
+            // a failed parse yields no parser to locate the anomalies with, so they're
+            // located through a lenient one
+            let lenient = match options.provenance {
+                true => ElfParser::open_with(file, &parse_options.clone().strict(false)).ok(),
+                false => None,
+            };
+            for warning in &warnings {
+                let source = lenient.as_ref().and_then(|p| p.warning_provenance(warning));
+                println!("{}", diagnostic_json(warning, source));
+            }
+            exit(1);
+        }
+        Err(e) => {
+            eprintln!("elf-parser: {}: {}", file, e);
+            exit(1);
+        }
+    };
+
+    // reports printed as JSON or SARIF carry the file themselves
+    let machine_readable = options.lint
+        && options.lint_output.is_none()
+        && !matches!(options.lint_format, LintFormat::Text);
+    if let (Some(source), false) = (parser.source(), machine_readable) {
+        println!("File: {}\n", source);
+    }
+
+    if options.file_header {
+        println!("{}\n", parser.headers);
+    }
+
+    if options.section_headers {
+        print_sections(&parser);
+    }
+
+    if options.program_headers {
+        print_segments(&parser);
+    }
+
+    if options.symbols {
+        print_symbols(&parser);
+    }
+
+    if options.relocs {
+        if let Err(e) = print_relocs(&parser) {
+            eprintln!("elf-parser: {}: {}", file, e);
+            exit(1);
+        }
+    }
+
+    if options.dynamic {
+        print_dynamic(&parser);
+    }
+
+    if options.notes {
+        match parser.note_groups() {
+            Ok(groups) => groups.iter().for_each(|g| print!("{}", g)),
+            Err(e) => {
+                eprintln!("elf-parser: {}: {}", file, e);
+                exit(1);
+            }
+        }
+    }
+
+    if options.size {
+        print_size(&parser);
+    }
+
+    if options.bloat {
+        print_bloat(&parser, options.top.unwrap_or(20));
+    }
+
+    if options.regions {
+        print_regions(&parser);
+    }
+
+    if options.classify {
+        print_classes(&parser);
+    }
+
+    if options.exports {
+        if let Err(e) = print_exports(&parser) {
+            eprintln!("elf-parser: {}: {}", file, e);
+            exit(1);
+        }
+    }
+
+    if options.imports {
+        if let Err(e) = print_imports(&parser) {
+            eprintln!("elf-parser: {}: {}", file, e);
+            exit(1);
+        }
+    }
+
+    if options.undefined {
+        if let Err(e) = print_undefined(&parser) {
+            eprintln!("elf-parser: {}: {}", file, e);
+            exit(1);
+        }
+    }
+
+    if options.linkage {
+        if let Err(e) = print_linkage(&parser) {
+            eprintln!("elf-parser: {}: {}", file, e);
+            exit(1);
+        }
+    }
+
+    if let Some(n_insns) = options.entry_disasm {
+        if let Err(e) = print_entry_disasm(&parser, n_insns) {
+            eprintln!("elf-parser: {}: {}", file, e);
+            exit(1);
+        }
+    }
+
+    if options.checksec {
+        print_checksec(&parser);
+    }
+
+    if options.lint {
+        if let Err(e) = print_lint(&parser, &options, file) {
+            eprintln!("elf-parser: {}: {}", file, e);
+            exit(1);
+        }
+    }
+
+    if options.debug_info {
+        print_debug_info(&parser);
+    }
+
+    for pattern in &options.hex_dump {
+        hex_dump(&parser, pattern);
+    }
+
+    for pattern in &options.strings {
+        string_dump(&parser, pattern);
+    }
+
+    if let Some(output) = &options.output_binary {
+        if let Err(e) = output_binary(&parser, &options.flat, output) {
+            eprintln!("elf-parser: {}: {}", file, e);
+            exit(1);
+        }
+    }
+
+    if let Some(output) = &options.output_ihex {
+        write_records(output, parser.to_ihex());
+    }
+
+    if let Some(output) = &options.output_srec {
+        write_records(output, parser.to_srec());
+    }
+
+    for pattern in &options.dump_section {
+        if let Err(e) = dump_section(file, &parser, pattern) {
+            eprintln!("elf-parser: {}: {}", file, e);
+            exit(1);
+        }
+    }
+}
@@ -0,0 +1,55 @@
+//! Parsing of images captured from memory rather than read from a file, such as the
+//! vDSO a process gets mapped by the kernel.
+//!
+//! The bytes of a mapped image sit at their virtual address minus the address the
+//! image starts at, so the `p_offset` and `sh_offset` fields locating them in the
+//! file don't apply. With `ParseOptions::load_bias` set, the offsets of segments and
+//! allocated sections are taken from their addresses instead, which everything read
+//! through them then follows: the segment contents, the dynamic entries, and the
+//! dynamic symbol and string tables they point to. The section header table is
+//! optional, without one the symbols are found through `PT_DYNAMIC` alone.
+
+use crate::elf::phdr::{Elf64PHdr, PType};
+use crate::elf::shdr::{Elf64SHdr, SHF_ALLOC};
+use crate::options::ParseOptions;
+use crate::parser::{ElfParser, ParseError};
+
+/// Offset into an image mapped at `load_bias` of the segment `ph`, which must lie
+/// within its `len` bytes when loaded. Segments that occupy no memory, as
+/// `PT_GNU_STACK`, and the ones the loader doesn't map keep their file offset
+pub(crate) fn segment_offset(ph: &Elf64PHdr, load_bias: u64, len: u64) -> Result<u64, ParseError> {
+    let mapped = ph
+        .vaddr
+        .0
+        .checked_sub(load_bias)
+        .filter(|off| off.checked_add(ph.filesz).is_some_and(|end| end <= len));
+
+    match (mapped, ph.p_type) {
+        (_, _) if ph.filesz == 0 => Ok(ph.offset),
+        (Some(off), _) => Ok(off),
+        (None, PType::PtLoad | PType::PtDynamic) => Err(ParseError::UnmappedAddress(ph.vaddr.0)),
+        (None, _) => Ok(ph.offset),
+    }
+}
+
+/// Points the allocated sections of an image mapped at `load_bias` at their address.
+/// The others aren't loaded and keep their file offset, which holds when the whole
+/// file was mapped, as for the vDSO
+pub(crate) fn map_sections(section_headers: &mut [Elf64SHdr], load_bias: u64) {
+    for sh in section_headers
+        .iter_mut()
+        .filter(|sh| sh.flags & SHF_ALLOC != 0)
+    {
+        if let Some(off) = sh.addr.0.checked_sub(load_bias) {
+            sh.offset = off;
+        }
+    }
+}
+
+impl<'a> ElfParser<'a> {
+    /// Parses an image captured from memory, whose first byte was mapped at
+    /// `load_bias` in the address space its headers use, see `ParseOptions::load_bias`
+    pub fn parse_memory_image(data: &'a [u8], load_bias: u64) -> Result<Self, ParseError> {
+        ElfParser::parse_with(data, &ParseOptions::default().load_bias(load_bias))
+    }
+}
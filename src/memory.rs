@@ -0,0 +1,189 @@
+//! Loadable segments laid out by virtual address, as a loader or an emulator maps
+//! them.
+//!
+//! Unlike `FlatImage`, which packs the file image of the segments by physical address
+//! for flashing, every segment of a `MemoryImage` is kept on its own, spans its whole
+//! `p_memsz` with the tail past `p_filesz` zeroed, and carries its permissions.
+
+use crate::elf::phdr::{Elf64PHdr, PType, PF_EXEC, PF_READ, PF_WRITE};
+use crate::parser::{ElfParser, ParseError};
+use log::debug;
+
+/// Largest memory `ElfParser::memory_image` allocates by default for all the segments
+pub const DEFAULT_MAX_MEMORY_SIZE: u64 = 256 << 20;
+
+/// Contents of a loadable segment once loaded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemorySegment {
+    /// Virtual address of the first byte of `data`
+    pub vaddr: u64,
+    /// `p_memsz` bytes, the file image of the segment followed by zeros
+    pub data: Vec<u8>,
+    /// Permissions of the segment, see the `PF_*` constants
+    pub flags: u32,
+}
+
+impl MemorySegment {
+    pub fn end(&self) -> u64 {
+        self.vaddr + self.data.len() as u64
+    }
+
+    pub fn is_readable(&self) -> bool {
+        self.flags & PF_READ != 0
+    }
+
+    pub fn is_writable(&self) -> bool {
+        self.flags & PF_WRITE != 0
+    }
+
+    pub fn is_executable(&self) -> bool {
+        self.flags & PF_EXEC != 0
+    }
+}
+
+/// Memory of a process right after the file is loaded, before any relocation is
+/// applied
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryImage {
+    /// Virtual address execution starts at
+    pub entry: u64,
+    /// Loadable segments, in the order of the program header table
+    pub segments: Vec<MemorySegment>,
+}
+
+impl MemoryImage {
+    /// The segment mapping the byte at `addr`
+    pub fn segment_at(&self, addr: u64) -> Option<&MemorySegment> {
+        self.segments
+            .iter()
+            .find(|s| addr >= s.vaddr && addr < s.end())
+    }
+
+    /// The `len` bytes at `addr`, if a single segment maps all of them
+    pub fn read(&self, addr: u64, len: usize) -> Option<&[u8]> {
+        let segment = self.segment_at(addr)?;
+        let start = (addr - segment.vaddr) as usize;
+
+        segment.data.get(start..start.checked_add(len)?)
+    }
+
+    /// Total size of the segments in bytes
+    pub fn size(&self) -> u64 {
+        self.segments.iter().map(|s| s.data.len() as u64).sum()
+    }
+}
+
+impl ElfParser<'_> {
+    pub fn memory_image(&self) -> Result<MemoryImage, ParseError> {
+        self.memory_image_with(DEFAULT_MAX_MEMORY_SIZE)
+    }
+
+    /// Loads every `PT_LOAD` segment of the file, failing if they would take more
+    /// than `max_size` bytes of memory in total. Segments without file image, parsed
+    /// as `PTypeData::ZeroFill`, only get their zeros here
+    pub fn memory_image_with(&self, max_size: u64) -> Result<MemoryImage, ParseError> {
+        memory_image_of(
+            &self.program_headers,
+            self.data(),
+            self.headers.entry.0,
+            max_size,
+        )
+    }
+
+    /// Loads every `PT_LOAD` segment of the file into `memory`, a buffer mapped at
+    /// `base`, as a loader without allocator does. Past the file image of each segment
+    /// its bytes are zeroed, the other bytes of `memory` are left untouched
+    pub fn load_into(&self, memory: &mut [u8], base: u64) -> Result<(), ParseError> {
+        load_segments_into(&self.program_headers, self.data(), memory, base)
+    }
+}
+
+/// Loadable segments of `program_headers` with some memory image
+fn loads(program_headers: &[Elf64PHdr]) -> impl Iterator<Item = &Elf64PHdr> + Clone {
+    program_headers
+        .iter()
+        .filter(|ph| ph.p_type == PType::PtLoad && ph.memsz > 0)
+}
+
+/// File image of the loadable segment `ph`, up to `p_memsz` bytes of it
+fn file_image<'d>(ph: &Elf64PHdr, data: &'d [u8]) -> Result<&'d [u8], ParseError> {
+    let offset = ph.offset as usize;
+
+    data.get(offset..offset.saturating_add(ph.filesz.min(ph.memsz) as usize))
+        .ok_or(ParseError::InvalidLength)
+}
+
+/// Builds the memory image of the loadable segments of `program_headers`, read from
+/// `data`, for `ElfParser` and `LoaderView` alike
+pub(crate) fn memory_image_of(
+    program_headers: &[Elf64PHdr],
+    data: &[u8],
+    entry: u64,
+    max_size: u64,
+) -> Result<MemoryImage, ParseError> {
+    let size = loads(program_headers)
+        .try_fold(0u64, |size, ph| size.checked_add(ph.memsz))
+        .ok_or(ParseError::InvalidLength)?;
+    if size > max_size {
+        return Err(ParseError::AllocationTooLarge(size));
+    }
+
+    let mut segments = vec![];
+    for ph in loads(program_headers) {
+        let mut data = file_image(ph, data)?.to_vec();
+        data.resize(ph.memsz as usize, 0);
+        segments.push(MemorySegment {
+            vaddr: ph.vaddr.0,
+            data,
+            flags: ph.flags,
+        });
+    }
+
+    debug!(
+        "memory image of {} segments, {} bytes",
+        segments.len(),
+        size
+    );
+
+    Ok(MemoryImage { entry, segments })
+}
+
+/// Copies the loadable segments of `program_headers`, read from `data`, into
+/// `memory` mapped at `base`. Every segment is checked to fit before any is copied
+pub(crate) fn load_segments_into(
+    program_headers: &[Elf64PHdr],
+    data: &[u8],
+    memory: &mut [u8],
+    base: u64,
+) -> Result<(), ParseError> {
+    let len = memory.len() as u64;
+    let placed = |ph: &Elf64PHdr| {
+        let start = ph
+            .vaddr
+            .0
+            .checked_sub(base)
+            .ok_or(ParseError::UnmappedAddress(ph.vaddr.0))?;
+        let end = start
+            .checked_add(ph.memsz)
+            .filter(|end| *end <= len)
+            .ok_or(ParseError::UnmappedAddress(ph.vaddr.0))?;
+
+        Ok::<_, ParseError>(start as usize..end as usize)
+    };
+    for ph in loads(program_headers) {
+        placed(ph)?;
+        file_image(ph, data)?;
+    }
+
+    for ph in loads(program_headers) {
+        let range = placed(ph)?;
+        let image = file_image(ph, data)?;
+        let (file, zeros) = memory[range].split_at_mut(image.len());
+        file.copy_from_slice(image);
+        zeros.fill(0);
+    }
+
+    debug!("loaded segments into {} bytes at {:#x}", memory.len(), base);
+
+    Ok(())
+}
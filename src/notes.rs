@@ -0,0 +1,279 @@
+use crate::bytes::ParseContext;
+use crate::elf::note::{
+    AbiTag, AndroidIdent, AuxvEntry, Elf64Note, GnuAbiOs, GnuProperty, GnuPropertyKind,
+    NT_ANDROID_TYPE_IDENT, NT_AUXV, NT_GNU_ABI_TAG, NT_GNU_BUILD_ID, NT_GNU_PROPERTY_TYPE_0,
+    NT_PRPSINFO, NT_PRSTATUS,
+};
+use crate::elf::phdr::PType;
+use crate::elf::shdr::SHT_NOTE;
+use crate::parser::{ranges_overlap, ElfParser, ParseError};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Where a group of notes was read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NoteSource {
+    /// A `SHT_NOTE` section, by name
+    Section(String),
+    /// A `PT_NOTE` segment whose bytes aren't held by any note section, by index
+    Segment(usize),
+}
+
+/// The notes held by a single note section or segment, rendered as `readelf -n` does
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoteGroup {
+    pub source: NoteSource,
+    /// File offset and size of the section or segment
+    pub offset: u64,
+    pub size: u64,
+    pub notes: Vec<Elf64Note>,
+    /// Machine and encoding of the file, which decide the meaning of some notes
+    pub machine: u16,
+    pub ctx: ParseContext,
+}
+
+/// Name of the note type, which depends on the owner of the note
+pub fn note_type_name(owner: &str, n_type: u32) -> String {
+    match (owner, n_type) {
+        ("GNU", NT_GNU_ABI_TAG) => "NT_GNU_ABI_TAG (ABI version tag)".to_string(),
+        ("GNU", NT_GNU_BUILD_ID) => "NT_GNU_BUILD_ID (unique build ID bitstring)".to_string(),
+        ("GNU", NT_GNU_PROPERTY_TYPE_0) => "NT_GNU_PROPERTY_TYPE_0".to_string(),
+        ("Android", NT_ANDROID_TYPE_IDENT) => "NT_VERSION (version)".to_string(),
+        ("CORE", NT_PRSTATUS) => "NT_PRSTATUS (prstatus structure)".to_string(),
+        ("CORE", NT_PRPSINFO) => "NT_PRPSINFO (prpsinfo structure)".to_string(),
+        ("CORE", NT_AUXV) => "NT_AUXV (auxiliary vector)".to_string(),
+        (_, t) => format!("Unknown note type: ({:#010x})", t),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Names of the features whose flag is set, or `<None>` if there are none
+fn features(flags: &[(bool, &str)]) -> String {
+    let set: Vec<&str> = flags
+        .iter()
+        .filter(|(set, _)| *set)
+        .map(|(_, name)| *name)
+        .collect();
+
+    if set.is_empty() {
+        "<None>".to_string()
+    } else {
+        set.join(", ")
+    }
+}
+
+fn property_description(property: &GnuProperty, machine: u16, ctx: &ParseContext) -> String {
+    match property.kind(machine, ctx) {
+        GnuPropertyKind::StackSize(size) => format!("stack size: {:#x}", size),
+        GnuPropertyKind::NoCopyOnProtected => "no copy on protected".to_string(),
+        GnuPropertyKind::X86Feature1And { ibt, shstk, .. } => format!(
+            "x86 feature: {}",
+            features(&[(ibt, "IBT"), (shstk, "SHSTK")])
+        ),
+        GnuPropertyKind::Aarch64Feature1And { bti, pac, .. } => format!(
+            "AArch64 feature: {}",
+            features(&[(bti, "BTI"), (pac, "PAC")])
+        ),
+        GnuPropertyKind::RiscvFeature1And {
+            lp_unlabeled,
+            shadow_stack,
+            lp_func_sig,
+            ..
+        } => format!(
+            "RISC-V AND feature: {}",
+            features(&[
+                (lp_unlabeled, "CFI_LP_UNLABELED"),
+                (shadow_stack, "CFI_SS"),
+                (lp_func_sig, "CFI_LP_FUNC_SIG"),
+            ])
+        ),
+        GnuPropertyKind::Unknown => format!(
+            "<type {:#x} data: {}>",
+            property.pr_type,
+            hex(&property.data)
+        ),
+    }
+}
+
+impl NoteGroup {
+    /// Type-specific rendering of the descriptor of `note`, falling back to a hex dump
+    /// for the types this crate doesn't decode
+    pub fn describe(&self, note: &Elf64Note) -> String {
+        let ctx = &self.ctx;
+
+        match (note.name.as_str(), note.n_type) {
+            ("GNU", NT_GNU_BUILD_ID) => format!("Build ID: {}", hex(&note.desc)),
+            ("GNU", NT_GNU_ABI_TAG) => match AbiTag::parse(note, ctx) {
+                Ok(tag) => {
+                    let os = match tag.os {
+                        GnuAbiOs::Linux => "Linux".to_string(),
+                        GnuAbiOs::Hurd => "Hurd".to_string(),
+                        os => os.to_string(),
+                    };
+                    let (major, minor, patch) = tag.kernel_version;
+
+                    format!("OS: {}, ABI: {}.{}.{}", os, major, minor, patch)
+                }
+                Err(_) => format!("<corrupt GNU_ABI_TAG> {}", hex(&note.desc)),
+            },
+            ("GNU", NT_GNU_PROPERTY_TYPE_0) => match GnuProperty::parse(&note.desc, ctx) {
+                Ok(properties) => {
+                    let descriptions: Vec<String> = properties
+                        .iter()
+                        .map(|p| property_description(p, self.machine, ctx))
+                        .collect();
+
+                    format!("Properties: {}", descriptions.join("\n\t"))
+                }
+                Err(_) => format!("<corrupt GNU_PROPERTY_TYPE_0> {}", hex(&note.desc)),
+            },
+            ("Android", NT_ANDROID_TYPE_IDENT) => match AndroidIdent::parse(note, ctx) {
+                Ok(ident) => {
+                    let mut description = format!("API level: {}", ident.api_level);
+                    if let Some(version) = ident.ndk_version {
+                        description += &format!(", NDK: {}", version);
+                    }
+                    if let Some(build) = ident.ndk_build_number {
+                        description += &format!(" ({})", build);
+                    }
+
+                    description
+                }
+                Err(_) => format!("<corrupt ANDROID_TYPE_IDENT> {}", hex(&note.desc)),
+            },
+            ("CORE", NT_AUXV) => match AuxvEntry::parse(note, ctx) {
+                Ok(entries) => entries
+                    .iter()
+                    .map(|e| format!("{}: {:#x}", e.kind(), e.value))
+                    .collect::<Vec<_>>()
+                    .join("\n    "),
+                Err(_) => format!("<corrupt AUXV> {}", hex(&note.desc)),
+            },
+            _ => format!("description data: {}", hex(&note.desc)),
+        }
+    }
+}
+
+impl fmt::Display for NoteGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f)?;
+        match &self.source {
+            NoteSource::Section(name) => writeln!(f, "Displaying notes found in: {}", name)?,
+            NoteSource::Segment(_) => writeln!(
+                f,
+                "Displaying notes found at file offset {:#010x} with length {:#010x}:",
+                self.offset, self.size
+            )?,
+        }
+        writeln!(f, "  {:<20} {:<9} \tDescription", "Owner", "Data size")?;
+
+        for note in &self.notes {
+            writeln!(
+                f,
+                "  {:<20} {:#010x}\t{}",
+                note.name,
+                note.desc.len(),
+                note_type_name(&note.name, note.n_type)
+            )?;
+            // properties are indented further, as readelf does
+            let indent = match (note.name.as_str(), note.n_type) {
+                ("GNU", NT_GNU_PROPERTY_TYPE_0) => 6,
+                _ => 4,
+            };
+            writeln!(f, "{:indent$}{}", "", self.describe(note), indent = indent)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ElfParser<'_> {
+    /// Reads the notes of every note section, along with those of the note segments
+    /// whose bytes aren't held by a note section, as happens with files stripped of
+    /// their section headers and with core dumps
+    pub fn note_groups(&self) -> Result<Vec<NoteGroup>, ParseError> {
+        let group = |source, offset, size, data, align: u64| -> Result<NoteGroup, ParseError> {
+            Ok(NoteGroup {
+                source,
+                offset,
+                size,
+                notes: Elf64Note::parse(data, &self.ctx, align as usize)?,
+                machine: self.headers.machine,
+                ctx: self.ctx,
+            })
+        };
+
+        let mut groups = vec![];
+        for (idx, sh) in self.section_headers.iter().enumerate() {
+            if sh.s_type == SHT_NOTE {
+                let name = self.section_name(sh).unwrap_or_default();
+                groups.push(group(
+                    NoteSource::Section(name),
+                    sh.offset,
+                    sh.size,
+                    self.section_data(idx)?,
+                    sh.addr_align,
+                )?);
+            }
+        }
+
+        for (idx, ph) in self.program_headers.iter().enumerate() {
+            let range = ph.offset..ph.offset.saturating_add(ph.filesz);
+            let covered = groups
+                .iter()
+                .any(|g| ranges_overlap(&(g.offset..g.offset.saturating_add(g.size)), &range));
+            if ph.p_type == PType::PtNote && !covered {
+                groups.push(group(
+                    NoteSource::Segment(idx),
+                    ph.offset,
+                    ph.filesz,
+                    self.segment_data(idx)?,
+                    ph.align,
+                )?);
+            }
+        }
+
+        Ok(groups)
+    }
+
+    /// The notes of every group keyed by owner name, each in the order of the groups,
+    /// so OS-specific consumers can pick theirs
+    pub fn notes_by_owner(&self) -> Result<BTreeMap<String, Vec<Elf64Note>>, ParseError> {
+        let mut owners = BTreeMap::<String, Vec<Elf64Note>>::new();
+        for note in self.note_groups()?.into_iter().flat_map(|g| g.notes) {
+            owners.entry(note.name.clone()).or_default().push(note);
+        }
+
+        Ok(owners)
+    }
+
+    /// The first note of type `n_type` owned by `owner`
+    fn find_note(&self, owner: &str, n_type: u32) -> Result<Option<Elf64Note>, ParseError> {
+        Ok(self
+            .notes_by_owner()?
+            .remove(owner)
+            .and_then(|notes| notes.into_iter().find(|n| n.n_type == n_type)))
+    }
+
+    /// Descriptor of the first `NT_GNU_BUILD_ID` note, the identifier debuggers and
+    /// symbol servers match the file and its separate debug info by
+    pub fn build_id(&self) -> Result<Option<Vec<u8>>, ParseError> {
+        Ok(self.find_note("GNU", NT_GNU_BUILD_ID)?.map(|n| n.desc))
+    }
+
+    /// Contents of the `.note.android.ident` note of files built with the Android NDK
+    pub fn android_ident(&self) -> Result<Option<AndroidIdent>, ParseError> {
+        self.find_note("Android", NT_ANDROID_TYPE_IDENT)?
+            .map(|note| AndroidIdent::parse(&note, &self.ctx))
+            .transpose()
+    }
+
+    /// Minimum Android API level the file targets, None when it has no Android note
+    /// or its notes can't be read
+    pub fn android_api_level(&self) -> Option<u32> {
+        self.android_ident().ok().flatten().map(|i| i.api_level)
+    }
+}
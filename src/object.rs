@@ -0,0 +1,104 @@
+//! Class-agnostic view of a parsed file. `ElfParser` decodes both classes and byte
+//! orders into the same structures, widening 32-bit fields, so `ElfObject` is what
+//! code consuming files of any class can depend on without naming the parser.
+
+use crate::bytes::Address;
+use crate::elf::ehdr::{ElfHClass, ElfHData, ElfHType};
+use crate::elf::phdr::Elf64PHdr;
+use crate::elf::sym::Elf64Sym;
+use crate::parser::{ElfParser, ParseError};
+use crate::section::Sections;
+
+/// Common interface of parsed files, whatever their class and byte order. The trait
+/// is object safe, so files can be handled as `dyn ElfObject`
+pub trait ElfObject {
+    fn class(&self) -> ElfHClass;
+
+    fn endianness(&self) -> ElfHData;
+
+    fn e_type(&self) -> ElfHType;
+
+    fn machine(&self) -> u16;
+
+    fn entry(&self) -> Address;
+
+    /// Sections along with their index and resolved name
+    fn sections(&self) -> Sections<'_>;
+
+    fn segments(&self) -> &[Elf64PHdr];
+
+    /// Named symbols of every symbol table, static ones first as laid out in the file.
+    /// Files without section headers list the dynamic symbols of `PT_DYNAMIC`
+    fn symbols(&self) -> Result<Vec<(String, Elf64Sym)>, ParseError>;
+
+    /// Names of the libraries listed by the `DT_NEEDED` entries, in load order
+    fn needed_libraries(&self) -> Result<Vec<String>, ParseError>;
+}
+
+impl ElfObject for ElfParser<'_> {
+    fn class(&self) -> ElfHClass {
+        self.headers.ident.class
+    }
+
+    fn endianness(&self) -> ElfHData {
+        self.headers.ident.data
+    }
+
+    fn e_type(&self) -> ElfHType {
+        self.headers.e_type
+    }
+
+    fn machine(&self) -> u16 {
+        self.headers.machine
+    }
+
+    fn entry(&self) -> Address {
+        self.headers.entry
+    }
+
+    fn sections(&self) -> Sections<'_> {
+        ElfParser::sections(self)
+    }
+
+    fn segments(&self) -> &[Elf64PHdr] {
+        &self.program_headers
+    }
+
+    fn symbols(&self) -> Result<Vec<(String, Elf64Sym)>, ParseError> {
+        // without section headers, the dynamic symbols are still reached through the
+        // dynamic segment, as in the images captured from memory
+        if self.section_headers.is_empty() && self.dynamic_entries().is_some() {
+            let mut symbols = vec![];
+            for symbol in self.dynamic_symbol_table()?.into_iter().skip(1) {
+                let name = self.dynamic_string(symbol.name)?;
+                if !name.is_empty() {
+                    symbols.push((name, symbol));
+                }
+            }
+
+            return Ok(symbols);
+        }
+
+        let mut symbols = vec![];
+        for table in &self.symbol_tables {
+            for symbol in table.symbols.iter().skip(1) {
+                let name = self.symbol_name(table, symbol)?;
+                if !name.is_empty() {
+                    symbols.push((name, *symbol));
+                }
+            }
+        }
+
+        Ok(symbols)
+    }
+
+    fn needed_libraries(&self) -> Result<Vec<String>, ParseError> {
+        ElfParser::needed_libraries(self)
+    }
+}
+
+/// Parses the file held by `data`, whatever its class, into a handle exposing only
+/// the class-agnostic interface of `ElfObject`
+pub fn parse(data: &[u8]) -> Result<Box<dyn ElfObject + '_>, ParseError> {
+    Ok(Box::new(ElfParser::parse(data)?))
+}
@@ -0,0 +1,111 @@
+//! Interoperability with the `object` crate, which most of the binary analysis
+//! ecosystem (gimli, addr2line, symbolic) is built upon.
+//!
+//! The read traits of `object`, `Object`, `ObjectSection`, `ObjectSymbol` and the
+//! like, are sealed, so they can't be implemented on top of `ElfParser`. What this
+//! module offers instead is the mapping of the values decoded by this crate to the
+//! types of `object`, matching what `object` itself reports for ELF files, and
+//! `ElfParser::to_object_file`, which hands the buffer of the parser over to
+//! `object::read::File` for consumers that need the traits.
+
+use crate::elf::ehdr::{
+    ElfHClass, ElfHData, ElfHType, EM_386, EM_AARCH64, EM_ARM, EM_RISCV, EM_X86_64,
+};
+use crate::elf::shdr::{
+    Elf64SHdr, SHF_ALLOC, SHF_EXECINSTR, SHF_STRINGS, SHF_TLS, SHF_WRITE, SHT_DYNAMIC, SHT_DYNSYM,
+    SHT_GROUP, SHT_HASH, SHT_NOBITS, SHT_NOTE, SHT_NULL, SHT_PROGBITS, SHT_REL, SHT_RELA,
+    SHT_STRTAB, SHT_SYMTAB, SHT_SYMTAB_SHNDX,
+};
+use crate::elf::sym::{
+    Elf64Sym, STT_COMMON, STT_FILE, STT_FUNC, STT_GNU_IFUNC, STT_OBJECT, STT_SECTION, STT_TLS,
+};
+use crate::parser::ElfParser;
+use ::object::{Architecture, Endianness, ObjectKind, SectionKind, SymbolKind};
+
+/// `SHT_RELR`, relative relocations packed as a bitmap
+const SHT_RELR: u32 = 19;
+
+/// Kind of the section, as `object::read::ObjectSection::kind` reports it
+pub fn section_kind(sh: &Elf64SHdr) -> SectionKind {
+    let flags = sh.flags;
+
+    match sh.s_type {
+        SHT_PROGBITS if flags & SHF_ALLOC != 0 => {
+            if flags & SHF_EXECINSTR != 0 {
+                SectionKind::Text
+            } else if flags & SHF_TLS != 0 {
+                SectionKind::Tls
+            } else if flags & SHF_WRITE != 0 {
+                SectionKind::Data
+            } else if flags & SHF_STRINGS != 0 {
+                SectionKind::ReadOnlyString
+            } else {
+                SectionKind::ReadOnlyData
+            }
+        }
+        SHT_PROGBITS if flags & SHF_STRINGS != 0 => SectionKind::OtherString,
+        SHT_PROGBITS => SectionKind::Other,
+        SHT_NOBITS if flags & SHF_TLS != 0 => SectionKind::UninitializedTls,
+        SHT_NOBITS => SectionKind::UninitializedData,
+        SHT_NOTE => SectionKind::Note,
+        SHT_NULL | SHT_SYMTAB | SHT_STRTAB | SHT_RELA | SHT_HASH | SHT_DYNAMIC | SHT_REL
+        | SHT_DYNSYM | SHT_GROUP | SHT_SYMTAB_SHNDX | SHT_RELR => SectionKind::Metadata,
+        s_type => SectionKind::Elf(s_type),
+    }
+}
+
+/// Kind of the symbol, as `object::read::ObjectSymbol::kind` reports it
+pub fn symbol_kind(sym: &Elf64Sym) -> SymbolKind {
+    match sym.sym_type() {
+        STT_OBJECT | STT_COMMON => SymbolKind::Data,
+        STT_FUNC | STT_GNU_IFUNC => SymbolKind::Text,
+        STT_SECTION => SymbolKind::Section,
+        STT_FILE => SymbolKind::File,
+        STT_TLS => SymbolKind::Tls,
+        _ => SymbolKind::Unknown,
+    }
+}
+
+impl ElfParser<'_> {
+    /// Architecture of the file, from its machine and class
+    pub fn object_architecture(&self) -> Architecture {
+        let is_64 = self.headers.ident.class == ElfHClass::ElfClass64;
+
+        match (self.headers.machine, is_64) {
+            (EM_AARCH64, true) => Architecture::Aarch64,
+            (EM_AARCH64, false) => Architecture::Aarch64_Ilp32,
+            (EM_ARM, _) => Architecture::Arm,
+            (EM_386, _) => Architecture::I386,
+            (EM_X86_64, true) => Architecture::X86_64,
+            (EM_X86_64, false) => Architecture::X86_64_X32,
+            (EM_RISCV, true) => Architecture::Riscv64,
+            (EM_RISCV, false) => Architecture::Riscv32,
+            _ => Architecture::Unknown,
+        }
+    }
+
+    pub fn object_endianness(&self) -> Endianness {
+        match self.headers.ident.data == ElfHData::ElfData2Msb {
+            true => Endianness::Big,
+            false => Endianness::Little,
+        }
+    }
+
+    /// Kind of the file
+    pub fn object_kind(&self) -> ObjectKind {
+        match self.headers.e_type {
+            ElfHType::Relocatable => ObjectKind::Relocatable,
+            ElfHType::Executable => ObjectKind::Executable,
+            ElfHType::SharedObject => ObjectKind::Dynamic,
+            ElfHType::Core => ObjectKind::Core,
+            _ => ObjectKind::Unknown,
+        }
+    }
+
+    /// Parses the buffer of the parser with `object`, borrowing it from the parser, so
+    /// that DWARF readers and other consumers of the `object` traits can be fed the
+    /// same file without reading or copying it again
+    pub fn to_object_file(&self) -> ::object::Result<::object::File<'_>> {
+        ::object::File::parse(self.data())
+    }
+}
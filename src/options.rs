@@ -0,0 +1,214 @@
+use crate::elf::ehdr::{ElfHClass, ElfHData};
+use crate::handlers::{SectionHandler, SectionHandlers, SectionMatcher};
+use crate::parser::ParseError;
+use crate::stats::Arena;
+use std::time::Duration;
+
+/// Controls how much of the file `ElfParser::parse_with` reads, and how it reacts to
+/// anomalies. The defaults match `ElfParser::parse`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Copy the file image of loadable segments into `PTypeData::PtLoadData`
+    pub load_segment_data: bool,
+    /// Read the entries of the symbol tables
+    pub parse_symbols: bool,
+    /// Read the string tables, without which no section or symbol name resolves
+    pub parse_string_tables: bool,
+    /// Largest single buffer the parser is allowed to allocate, in bytes
+    pub max_alloc: usize,
+    /// Fail with `ParseError::StrictViolation` once the whole file was read if any
+    /// anomaly was found, instead of recording them as `ParseWarning`s
+    pub strict: bool,
+    /// Decode the file with this class instead of the one declared by its ident
+    pub force_class: Option<ElfHClass>,
+    /// Decode the file with this byte order instead of the one declared by its ident
+    pub force_endianness: Option<ElfHData>,
+    /// Accept an ident with a bad magic number, class, data encoding or version, for
+    /// images whose ident bytes were damaged but whose structures are intact. Invalid
+    /// class and data encoding values must be overridden through `force_class` and
+    /// `force_endianness`
+    pub skip_ident_validation: bool,
+    /// Handlers decoding the contents of matching sections, whose results are read
+    /// through `ElfParser::parsed_section`. None are registered by default
+    pub section_handlers: SectionHandlers,
+    /// Record the byte range every header, symbol and dynamic entry was read from,
+    /// read through `ElfParser::provenance`
+    pub track_provenance: bool,
+    /// Time budget of the whole parse, past which it fails with
+    /// `ParseError::DeadlineExceeded`. Checked between phases and between the entries
+    /// of the symbol tables
+    pub deadline: Option<Duration>,
+    /// Largest number of entries the program and section header tables may declare,
+    /// past which parsing fails with `ParseError::TooManyEntries` rather than walking
+    /// them
+    pub max_table_entries: usize,
+    /// Score the header under every class and byte order before parsing, reporting
+    /// `ParseWarning::EncodingMismatch` when the declared encoding is much less
+    /// plausible than another one, see `encoding::detect_encoding_mismatch`
+    pub detect_encoding_mismatch: bool,
+    /// Budget every buffer sized by the file is taken from: the header tables, the
+    /// segment contents and dynamic entries, and the string and symbol tables. Parsing
+    /// fails with `ParseError::ArenaExhausted` once it runs out, rather than growing
+    /// past it. None by default
+    pub arena: Option<Arena>,
+    /// Read the buffer as an image mapped in memory starting at this address, locating
+    /// segments and allocated sections by address rather than by file offset, see
+    /// `mapped`. None for files
+    pub load_bias: Option<u64>,
+}
+
+/// Default of `ParseOptions::max_table_entries`, far above the counts of real files
+pub const DEFAULT_MAX_TABLE_ENTRIES: usize = 1 << 20;
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            load_segment_data: true,
+            parse_symbols: true,
+            parse_string_tables: true,
+            max_alloc: usize::MAX,
+            strict: false,
+            force_class: None,
+            force_endianness: None,
+            skip_ident_validation: false,
+            section_handlers: SectionHandlers::default(),
+            track_provenance: false,
+            deadline: None,
+            max_table_entries: DEFAULT_MAX_TABLE_ENTRIES,
+            detect_encoding_mismatch: false,
+            arena: None,
+            load_bias: None,
+        }
+    }
+}
+
+impl ParseOptions {
+    pub fn new() -> Self {
+        ParseOptions::default()
+    }
+
+    pub fn load_segment_data(mut self, load_segment_data: bool) -> Self {
+        self.load_segment_data = load_segment_data;
+        self
+    }
+
+    pub fn parse_symbols(mut self, parse_symbols: bool) -> Self {
+        self.parse_symbols = parse_symbols;
+        self
+    }
+
+    pub fn parse_string_tables(mut self, parse_string_tables: bool) -> Self {
+        self.parse_string_tables = parse_string_tables;
+        self
+    }
+
+    pub fn max_alloc(mut self, max_alloc: usize) -> Self {
+        self.max_alloc = max_alloc;
+        self
+    }
+
+    pub fn arena(mut self, arena: Arena) -> Self {
+        self.arena = Some(arena);
+        self
+    }
+
+    pub fn load_bias(mut self, load_bias: u64) -> Self {
+        self.load_bias = Some(load_bias);
+        self
+    }
+
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    pub fn force_class(mut self, class: ElfHClass) -> Self {
+        self.force_class = Some(class);
+        self
+    }
+
+    pub fn force_endianness(mut self, endianness: ElfHData) -> Self {
+        self.force_endianness = Some(endianness);
+        self
+    }
+
+    pub fn skip_ident_validation(mut self, skip_ident_validation: bool) -> Self {
+        self.skip_ident_validation = skip_ident_validation;
+        self
+    }
+
+    pub fn section_handlers(mut self, section_handlers: SectionHandlers) -> Self {
+        self.section_handlers = section_handlers;
+        self
+    }
+
+    pub fn track_provenance(mut self, track_provenance: bool) -> Self {
+        self.track_provenance = track_provenance;
+        self
+    }
+
+    pub fn deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    pub fn max_table_entries(mut self, max_table_entries: usize) -> Self {
+        self.max_table_entries = max_table_entries;
+        self
+    }
+
+    pub fn detect_encoding_mismatch(mut self, detect_encoding_mismatch: bool) -> Self {
+        self.detect_encoding_mismatch = detect_encoding_mismatch;
+        self
+    }
+
+    /// Adds a handler invoked on the sections matching `matcher`
+    pub fn register_section_handler(
+        mut self,
+        matcher: SectionMatcher,
+        handler: SectionHandler,
+    ) -> Self {
+        self.section_handlers = self.section_handlers.register(matcher, handler);
+        self
+    }
+
+    /// Whether the ident of the file may be disregarded, partially or as a whole
+    pub fn overrides_ident(&self) -> bool {
+        self.force_class.is_some() || self.force_endianness.is_some() || self.skip_ident_validation
+    }
+
+    /// Checks that the `table` header table, either "program" or "section", declares
+    /// no more than `max_table_entries` entries
+    pub fn check_table_entries(&self, table: &'static str, count: usize) -> Result<(), ParseError> {
+        match count <= self.max_table_entries {
+            true => Ok(()),
+            false => Err(ParseError::TooManyEntries {
+                table,
+                count,
+                max: self.max_table_entries,
+            }),
+        }
+    }
+
+    /// Checks that a buffer of `size` bytes is within `max_alloc`, taking it from
+    /// `arena` when set
+    pub fn check_alloc(&self, size: u64) -> Result<usize, ParseError> {
+        let size = match usize::try_from(size) {
+            Ok(size) if size <= self.max_alloc => size,
+            _ => return Err(ParseError::AllocationTooLarge(size)),
+        };
+        if let Some(arena) = &self.arena {
+            arena.take(size as u64)?;
+        }
+
+        Ok(size)
+    }
+
+    /// Checks the buffer holding `count` decoded entries of type `T` as `check_alloc`,
+    /// returning `count`
+    pub fn check_entries_alloc<T>(&self, count: usize) -> Result<usize, ParseError> {
+        let size = (count as u64).saturating_mul(std::mem::size_of::<T>() as u64);
+
+        self.check_alloc(size).map(|_| count)
+    }
+}
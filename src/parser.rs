@@ -1,71 +1,1671 @@
-use crate::bytes::str_from_u8;
-use crate::elf::ehdr::Elf64Hdr;
-use crate::elf::phdr::Elf64PHdr;
-use crate::elf::shdr::{Elf64SHdr, StringTable, StringTableType, SHT_STRTAB};
+use crate::bytes::{str_from_u8, ParseContext};
+use crate::elf::dynamic::{DynFlags, DynFlags1};
+use crate::elf::eh_frame::EhFrameHdr;
+use crate::elf::ehdr::{Elf64Hdr, ElfHClass, ElfHData, ElfHVersion, IDENT_SZ};
+use crate::elf::phdr::{dyn_tag_name, ELF64Dyn, Elf64PHdr, PType, PTypeData, DT_FLAGS, DT_FLAGS_1};
+use crate::elf::shdr::{
+    Elf64SHdr, StringTable, StringTableType, SHN_XINDEX, SHT_DYNAMIC, SHT_DYNSYM, SHT_STRTAB,
+    SHT_SYMTAB,
+};
+use crate::elf::sym::{Elf64Sym, SymbolTable, SHN_UNDEF, STT_SECTION};
+use crate::encoding::{detect_encoding_mismatch, EncodingMismatch, MAX_SCORE};
+use crate::handlers::{SectionHandlers, SectionInput, SectionValue};
+use crate::layout;
+use crate::mapped;
+use crate::options::ParseOptions;
+use crate::provenance::Provenance;
+use crate::sorted_symbols::SortedSymbols;
+use crate::source::SourceError;
+use crate::source_info::SourceInfo;
+use crate::stats::{Arena, Deadline, ParseStats, PhaseDurations};
+use crate::symbol_index::SymbolIndex;
+use log::{debug, warn};
+use std::fmt;
+use std::ops::{Deref, Range};
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
+
+/// Number of symbols read between two checks of `ParseOptions::deadline`
+const DEADLINE_STRIDE: usize = 4096;
 
 /// Based of:
 /// [System V Application Binary Interface - DRAFT - 10 June 2013](http://www.sco.com/developers/gabi/latest/contents.html)
-
+///
 /// Implementation Constraints List:
 /// + This implementation only handles RISC-V machines
-/// + This implementation only handles 64-bit class
+/// + Both 32-bit and 64-bit classes are decoded, using the field layouts of `layout`
 #[allow(dead_code)]
-#[derive(Debug)]
-pub struct ElfParser {
+#[derive(Debug, Clone)]
+pub struct ElfParser<'a> {
     pub headers: Elf64Hdr,
+    /// Encoding of the file, used to decode every multi-byte field
+    pub ctx: ParseContext,
+    /// Program headers, in table order
     pub program_headers: Vec<Elf64PHdr>,
+    /// Section headers, in table order so that indices match the ones the file uses
     pub section_headers: Vec<Elf64SHdr>,
-    pub header_string_table_idx: usize,
+    /// Index into `string_tables` of the section header string table, if it was found
+    pub header_string_table_idx: Option<usize>,
+    /// String tables, in the order of their section headers
     pub string_tables: Vec<StringTable>,
+    /// Symbol tables read from `SHT_SYMTAB` and `SHT_DYNSYM` sections, in the order
+    /// of their section headers, each holding its symbols in table order. Edit them
+    /// through `symbol_tables_mut`, which keeps the name index up to date
+    pub symbol_tables: Vec<SymbolTable>,
+    /// Anomalies found while parsing, which didn't prevent the file from being read,
+    /// in the order they were found
+    pub warnings: Vec<ParseWarning>,
+    /// Values decoded by the section handlers, read through `parsed_section`
+    pub(crate) parsed_sections: Vec<SectionValue>,
+    /// Where each structure was read from, read through `provenance`
+    pub(crate) provenance: Option<Provenance>,
+    /// Index of the symbols by name, built on the first lookup
+    pub(crate) symbol_index: OnceLock<SymbolIndex>,
+    /// Symbols sorted by address, built on the first range query
+    pub(crate) sorted_symbols: OnceLock<SortedSymbols>,
+    /// Counters and timings of the parse, read through `stats`
+    pub(crate) stats: ParseStats,
+    /// Options the file was parsed with, which `refresh` parses it again with
+    pub(crate) options: ParseOptions,
+    /// File the parser was opened from, read through `source`
+    pub(crate) source_info: Option<SourceInfo>,
+    /// Contents of the parsed file, kept around to read section and segment data on
+    /// demand. It borrows the caller's buffer when built through `parse`, owns it when
+    /// built through `from_owned` and shares it when built through `parse_shared`
+    data: FileData<'a>,
 }
 
+/// Buffer holding the parsed file
+#[derive(Debug, Clone)]
+enum FileData<'a> {
+    Borrowed(&'a [u8]),
+    Owned(Vec<u8>),
+    Shared(Arc<[u8]>),
+}
+
+impl Deref for FileData<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            FileData::Borrowed(data) => data,
+            FileData::Owned(data) => data,
+            FileData::Shared(data) => data,
+        }
+    }
+}
+
+// the parser is meant to be queried from several threads at once, which must keep
+// holding for every type it contains
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<ElfParser<'static>>();
+};
+
 #[derive(Debug)]
 pub enum ParseError {
     InvalidLength,
+    InvalidUtf8,
+    /// The file doesn't start with the ELF magic number
+    InvalidMagic,
+    /// The class held by `ident.class` isn't supported
+    UnsupportedClass(u8),
+    /// The data encoding held by `ident.data` isn't supported
+    UnsupportedEncoding(u8),
+    /// The version held by `ident.version` isn't supported
+    UnsupportedVersion(u8),
+    /// The file has no section header table, but the ELF header defers to it
+    NoSectionHeaderTable,
+    /// The file type held by `e_type` isn't supported
+    UnsupportedFileType,
+    /// Reading the file would need a buffer of the given size, beyond
+    /// `ParseOptions::max_alloc`
+    AllocationTooLarge(u64),
+    /// The dynamic segment lacks the entry with the given tag, needed to locate or
+    /// size one of its tables
+    MissingDynamicEntry(i64),
+    /// The virtual address isn't backed by the file image of any loadable segment
+    UnmappedAddress(u64),
+    /// The section header table doesn't fit in the file
+    SectionHeadersOutOfBounds,
+    /// The `size` bytes of contents at `offset` of a section would end past the
+    /// largest offset of the class
+    ContentsOverflow {
+        offset: u64,
+        size: u64,
+    },
+    /// The program or section header table at the given offset overlaps the header
+    /// region, which ends at `Elf64Hdr::header_end`
+    TableOverlapsHeader {
+        table: &'static str,
+        offset: u64,
+    },
+    /// The program header table doesn't fit in the file
+    ProgramHeadersOutOfBounds,
+    /// The `filesz` bytes of the file image of a segment at `offset` run past the end
+    /// of the file
+    SegmentOutOfBounds {
+        offset: u64,
+        filesz: u64,
+    },
+    /// The file image of a loadable segment is larger than its memory image
+    SegmentFileSizeExceedsMemSize {
+        filesz: u64,
+        memsz: u64,
+    },
+    /// The program or section header table declares entries of 0 bytes, which can't
+    /// be walked
+    ZeroEntrySize {
+        table: &'static str,
+    },
+    /// The program or section header table declares `count` entries, more than `max`
+    TooManyEntries {
+        table: &'static str,
+        count: usize,
+        max: usize,
+    },
+    /// A `DW_EH_PE_*` pointer encoding this crate can't decode
+    UnsupportedPointerEncoding(u8),
+    /// The flat image would span the physical addresses `base..end`, more than
+    /// `FlatOptions::max_size` allows
+    FlatImageTooLarge {
+        base: u64,
+        end: u64,
+    },
+    /// The operation isn't available for the machine held by `e_machine`
+    UnsupportedMachine(u16),
+    /// The `ar` archive is malformed, for the given reason
+    InvalidArchive(&'static str),
+    /// The string table holding the requested name couldn't be read, as happens when
+    /// `sh_str_ndx` or the `link` of a symbol table doesn't point at one
+    NameUnavailable,
+    /// Parsing took longer than `ParseOptions::deadline`
+    DeadlineExceeded,
+    /// A buffer of `requested` bytes was needed with only `remaining` left in
+    /// `ParseOptions::arena`
+    ArenaExhausted {
+        requested: u64,
+        remaining: usize,
+    },
+    /// The anomalies found while parsing with `ParseOptions::strict`, which fails once
+    /// the whole file was read if there's any
+    StrictViolation(Vec<ParseWarning>),
+    /// `source` happened while reading the section at `index`, whose name is known
+    /// when the section header string table could be read
+    InSection {
+        index: usize,
+        name: Option<String>,
+        source: Box<ParseError>,
+    },
+    /// `source` happened while reading the segment at `index`
+    InSegment {
+        index: usize,
+        source: Box<ParseError>,
+    },
+    /// `source` happened while reading entry `index` of a table, as a symbol or a
+    /// relocation
+    InEntry {
+        index: usize,
+        source: Box<ParseError>,
+    },
+    /// `source` happened while parsing a file whose header looks written in another
+    /// encoding than the declared one, with `ParseOptions::detect_encoding_mismatch`
+    InMismatchedEncoding {
+        mismatch: EncodingMismatch,
+        source: Box<ParseError>,
+    },
+    /// The `ElfSource` the file is read through failed
+    Source(SourceError),
+    /// Following the `link` fields of the sections in `path` leads back to the last
+    /// one, which appears twice
+    LinkCycle {
+        path: Vec<usize>,
+    },
+}
+
+impl ParseError {
+    /// Whether the error concerns the parse as a whole rather than a structure of the
+    /// file, and so isn't given the structure it happened in
+    fn is_global(&self) -> bool {
+        matches!(
+            self,
+            ParseError::DeadlineExceeded
+                | ParseError::ArenaExhausted { .. }
+                | ParseError::StrictViolation(_)
+        )
+    }
+
+    /// Wraps the error as happening in the section at `index`
+    pub fn in_section(self, index: usize, name: Option<String>) -> Self {
+        match self.is_global() {
+            true => self,
+            false => ParseError::InSection {
+                index,
+                name,
+                source: Box::new(self),
+            },
+        }
+    }
+
+    /// Wraps the error as happening in the segment at `index`
+    pub fn in_segment(self, index: usize) -> Self {
+        match self.is_global() {
+            true => self,
+            false => ParseError::InSegment {
+                index,
+                source: Box::new(self),
+            },
+        }
+    }
+
+    /// Wraps the error as happening in entry `index` of a table
+    pub fn in_entry(self, index: usize) -> Self {
+        match self.is_global() {
+            true => self,
+            false => ParseError::InEntry {
+                index,
+                source: Box::new(self),
+            },
+        }
+    }
+
+    /// The error at the end of the chain, stripped of the structures it happened in
+    pub fn root_cause(&self) -> &ParseError {
+        match self {
+            ParseError::InSection { source, .. }
+            | ParseError::InSegment { source, .. }
+            | ParseError::InEntry { source, .. }
+            | ParseError::InMismatchedEncoding { source, .. } => source.root_cause(),
+            e => e,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidLength => write!(f, "data is too short"),
+            ParseError::InvalidUtf8 => write!(f, "string isn't valid UTF-8"),
+            ParseError::InvalidMagic => write!(f, "not an ELF file"),
+            ParseError::UnsupportedClass(class) => write!(f, "unsupported class {}", class),
+            ParseError::UnsupportedEncoding(data) => {
+                write!(f, "unsupported data encoding {}", data)
+            }
+            ParseError::UnsupportedVersion(version) => {
+                write!(f, "unsupported version {}", version)
+            }
+            ParseError::NoSectionHeaderTable => write!(f, "no section header table"),
+            ParseError::UnsupportedFileType => write!(f, "unsupported file type"),
+            ParseError::AllocationTooLarge(size) => {
+                write!(f, "allocation of {:#x} bytes exceeds the limit", size)
+            }
+            ParseError::MissingDynamicEntry(tag) => {
+                write!(f, "missing dynamic entry with tag {:#x}", tag)
+            }
+            ParseError::UnmappedAddress(addr) => {
+                write!(f, "address {:#x} isn't mapped by any segment", addr)
+            }
+            ParseError::SectionHeadersOutOfBounds => {
+                write!(f, "section header table runs past the end of the file")
+            }
+            ParseError::ContentsOverflow { offset, size } => write!(
+                f,
+                "contents of {:#x} bytes at {:#x} end past the largest offset",
+                size, offset
+            ),
+            ParseError::TableOverlapsHeader { table, offset } => write!(
+                f,
+                "{} header table at {:#x} overlaps the ELF header",
+                table, offset
+            ),
+            ParseError::ProgramHeadersOutOfBounds => {
+                write!(f, "program header table runs past the end of the file")
+            }
+            ParseError::SegmentOutOfBounds { offset, filesz } => write!(
+                f,
+                "file image of {:#x} bytes at {:#x} runs past the end of the file",
+                filesz, offset
+            ),
+            ParseError::SegmentFileSizeExceedsMemSize { filesz, memsz } => write!(
+                f,
+                "file image of {:#x} bytes is larger than the memory image of {:#x} bytes",
+                filesz, memsz
+            ),
+            ParseError::ZeroEntrySize { table } => {
+                write!(f, "{} header table has entries of 0 bytes", table)
+            }
+            ParseError::TooManyEntries { table, count, max } => write!(
+                f,
+                "{} header table has {} entries, over the limit of {}",
+                table, count, max
+            ),
+            ParseError::UnsupportedPointerEncoding(encoding) => {
+                write!(f, "unsupported pointer encoding {:#x}", encoding)
+            }
+            ParseError::FlatImageTooLarge { base, end } => {
+                write!(f, "flat image {:#x}..{:#x} is too large", base, end)
+            }
+            ParseError::UnsupportedMachine(machine) => {
+                write!(f, "unsupported machine {}", machine)
+            }
+            ParseError::InvalidArchive(reason) => write!(f, "invalid archive: {}", reason),
+            ParseError::NameUnavailable => write!(f, "name unavailable"),
+            ParseError::DeadlineExceeded => write!(f, "deadline exceeded"),
+            ParseError::ArenaExhausted {
+                requested,
+                remaining,
+            } => write!(
+                f,
+                "arena exhausted: {:#x} bytes needed, {:#x} left",
+                requested, remaining
+            ),
+            ParseError::StrictViolation(warnings) => {
+                write!(f, "{} warnings in strict mode", warnings.len())
+            }
+            ParseError::InSection {
+                index,
+                name: Some(name),
+                source,
+            } => write!(f, "section [{}] ({}): {}", index, name, source),
+            ParseError::InSection { index, source, .. } => {
+                write!(f, "section [{}]: {}", index, source)
+            }
+            ParseError::InSegment { index, source } => {
+                write!(f, "segment [{}]: {}", index, source)
+            }
+            ParseError::InEntry { index, source } => write!(f, "entry {}: {}", index, source),
+            ParseError::InMismatchedEncoding { mismatch, source } => write!(
+                f,
+                "header looks {} {:?} rather than {} {:?}: {}",
+                mismatch.suspected.class,
+                mismatch.suspected.endianness,
+                mismatch.declared.class,
+                mismatch.declared.endianness,
+                source
+            ),
+            ParseError::Source(e) => write!(f, "reading the file: {}", e),
+            ParseError::LinkCycle { path } => write!(
+                f,
+                "section links loop: {}",
+                path.iter()
+                    .map(|idx| format!("[{}]", idx))
+                    .collect::<Vec<_>>()
+                    .join(" -> ")
+            ),
+        }
+    }
+}
+
+impl From<SourceError> for ParseError {
+    fn from(e: SourceError) -> Self {
+        ParseError::Source(e)
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseError::InSection { source, .. }
+            | ParseError::InSegment { source, .. }
+            | ParseError::InEntry { source, .. }
+            | ParseError::InMismatchedEncoding { source, .. } => Some(source.as_ref()),
+            ParseError::Source(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Anomaly that doesn't prevent the file from being parsed, but points at a malformed
+/// or unusual file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseWarning {
+    /// The section header table doesn't fit in the file, so it was ignored
+    SectionHeadersOutOfBounds {
+        sh_off: u64,
+        sh_num: u16,
+        sh_ent_size: u16,
+        file_size: u64,
+    },
+    /// No string table is found at `sh_str_ndx`, so section names can't be resolved
+    MissingHeaderStringTable {
+        sh_str_ndx: u16,
+    },
+    /// The section at `sh_str_ndx` isn't a string table, its contents are read as one
+    /// anyway
+    HeaderStringTableWrongType {
+        sh_str_ndx: u16,
+        s_type: u32,
+    },
+    /// The string table doesn't fit in the file, in which case it was ignored, or isn't
+    /// delimited by NUL bytes
+    MalformedStringTable {
+        idx: usize,
+        reason: &'static str,
+    },
+    /// The initial entry of the section header table holds a value in a field that
+    /// must be zero
+    NonNullInitialSection {
+        field: &'static str,
+        value: u64,
+    },
+    UnknownSectionType {
+        idx: usize,
+        s_type: u32,
+    },
+    /// The section address isn't a multiple of its alignment
+    MisalignedSection {
+        idx: usize,
+        addr: u64,
+        align: u64,
+    },
+    /// The segment virtual address and offset aren't congruent modulo its alignment
+    MisalignedSegment {
+        idx: usize,
+        vaddr: u64,
+        offset: u64,
+        align: u64,
+    },
+    /// The reserved bytes at the end of `e_ident` aren't zero, which may hide data
+    IdentPadding {
+        padding: [u8; IDENT_SZ - 9],
+    },
+    /// `abi_version` is set although `os_abi` declares no OS/ABI extensions
+    AbiVersionWithoutOsAbi {
+        abi_version: u8,
+    },
+    /// `e_ehsize` is larger than the header of the class, leaving extra bytes after it
+    OversizedHeader {
+        eh_size: u16,
+        expected: u16,
+    },
+    /// `e_ehsize` is smaller than the header of the class, whose fields are decoded
+    /// anyway
+    UndersizedHeader {
+        eh_size: u16,
+        expected: u16,
+    },
+    /// The section header table overlaps the header region, so it was ignored
+    SectionHeadersOverlapHeader {
+        sh_off: u64,
+        header_end: u64,
+    },
+    /// The ident of the file was disregarded as requested through `ParseOptions`, so
+    /// the class and data encoding used may not match the ones it declares
+    IdentOverridden {
+        class: ElfHClass,
+        endianness: ElfHData,
+        skipped_validation: bool,
+    },
+    /// The `link` or `info` field of the section is out of range or references a
+    /// section of the wrong type
+    InvalidLink {
+        idx: usize,
+        field: &'static str,
+        value: u32,
+    },
+    /// A registered section handler failed to decode the section, or the section
+    /// doesn't fit in the file
+    SectionHandlerFailed {
+        idx: usize,
+        error: String,
+    },
+    /// `sh_str_ndx`, or the index it defers to through `SHN_XINDEX`, is past the end
+    /// of the section header table, so section names can't be resolved
+    HeaderStringTableOutOfRange {
+        sh_str_ndx: u32,
+        sh_num: usize,
+    },
+    /// The `PT_PHDR` segment doesn't describe the program header table, or isn't
+    /// mapped by a loadable segment
+    InvalidPhdrSegment {
+        idx: usize,
+        reason: &'static str,
+    },
+    /// The section header holds inconsistent values, see `Elf64SHdr::lint`
+    SectionLint {
+        idx: usize,
+        reason: &'static str,
+    },
+    /// The program header table overlaps the header region, so its entries were
+    /// decoded from header bytes. They're kept, but their contents aren't read
+    ProgramHeadersOverlapHeader {
+        ph_off: u64,
+        header_end: u64,
+    },
+    /// The program and section header tables overlap, so entries of one of them were
+    /// decoded from the bytes of the other
+    HeaderTablesOverlap {
+        ph_off: u64,
+        sh_off: u64,
+    },
+    /// The header decodes much more plausibly in the `suspected` encoding than in the
+    /// `declared` one, as scored by `encoding::plausibility`
+    EncodingMismatch {
+        declared: ParseContext,
+        declared_score: u8,
+        suspected: ParseContext,
+        suspected_score: u8,
+    },
+    /// The `STT_TLS` symbol at `idx` of the symbol table held by `section` spans past
+    /// the `memsz` bytes of the TLS block described by `PT_TLS`
+    TlsSymbolOutOfBlock {
+        section: usize,
+        idx: usize,
+        offset: u64,
+        size: u64,
+        memsz: u64,
+    },
+    /// The dynamic entry tagged `tag` holds `found`, which disagrees with `expected`,
+    /// the value `against` describes
+    DynamicMismatch {
+        tag: i64,
+        found: u64,
+        expected: u64,
+        against: &'static str,
+    },
+    /// The dynamic entry tagged `tag` holds an address no loadable segment maps
+    DynamicUnmapped {
+        tag: i64,
+        addr: u64,
+    },
+    /// The version of the identification and `e_version` aren't both the current one
+    VersionMismatch {
+        ident: ElfHVersion,
+        e_version: u32,
+    },
+    /// The entry of the section header table at `offset` couldn't be decoded, so it
+    /// was replaced by a null entry
+    UndecodableSection {
+        idx: usize,
+        offset: u64,
+        error: String,
+    },
+    /// The file image of the segment doesn't fit in the file, so its contents weren't
+    /// read
+    SegmentOutOfBounds {
+        idx: usize,
+        offset: u64,
+        filesz: u64,
+        file_size: u64,
+    },
+    /// The segment type is neither one this crate knows nor in the OS or
+    /// processor-specific ranges
+    UnknownSegmentType {
+        idx: usize,
+        p_type: u32,
+    },
+    /// The file image of the loadable segment is larger than its memory image, so its
+    /// contents weren't read
+    SegmentFileSizeExceedsMemSize {
+        idx: usize,
+        filesz: u64,
+        memsz: u64,
+    },
+}
+
+impl ParseWarning {
+    /// Stable identifier of the kind of anomaly, for tools to filter on. Codes are
+    /// never renumbered nor reused, new kinds take the next number
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParseWarning::SectionHeadersOutOfBounds { .. } => "W0001_SHDRS_OUT_OF_BOUNDS",
+            ParseWarning::MissingHeaderStringTable { .. } => "W0002_MISSING_SHSTRTAB",
+            ParseWarning::HeaderStringTableWrongType { .. } => "W0003_SHSTRTAB_WRONG_TYPE",
+            ParseWarning::MalformedStringTable { .. } => "W0004_BAD_STRTAB",
+            ParseWarning::NonNullInitialSection { .. } => "W0005_NON_NULL_SECTION_0",
+            ParseWarning::UnknownSectionType { .. } => "W0006_UNKNOWN_SECTION_TYPE",
+            ParseWarning::MisalignedSection { .. } => "W0007_BAD_ALIGN",
+            ParseWarning::MisalignedSegment { .. } => "W0008_BAD_SEGMENT_ALIGN",
+            ParseWarning::IdentPadding { .. } => "W0009_IDENT_PADDING",
+            ParseWarning::AbiVersionWithoutOsAbi { .. } => "W0010_ABI_VERSION_WITHOUT_OSABI",
+            ParseWarning::OversizedHeader { .. } => "W0011_OVERSIZED_HEADER",
+            ParseWarning::UndersizedHeader { .. } => "W0012_UNDERSIZED_HEADER",
+            ParseWarning::SectionHeadersOverlapHeader { .. } => "W0013_SHDRS_OVERLAP_HEADER",
+            ParseWarning::IdentOverridden { .. } => "W0014_IDENT_OVERRIDDEN",
+            ParseWarning::InvalidLink { .. } => "W0015_BAD_LINK",
+            ParseWarning::SectionHandlerFailed { .. } => "W0016_SECTION_HANDLER_FAILED",
+            ParseWarning::InvalidPhdrSegment { .. } => "W0017_BAD_PHDR_SEGMENT",
+            ParseWarning::HeaderStringTableOutOfRange { .. } => "W0018_SHSTRNDX_OUT_OF_RANGE",
+            ParseWarning::SectionLint { .. } => "W0019_SECTION_LINT",
+            ParseWarning::TlsSymbolOutOfBlock { .. } => "W0020_TLS_SYMBOL_OUT_OF_BLOCK",
+            ParseWarning::ProgramHeadersOverlapHeader { .. } => "W0021_PHDRS_OVERLAP_HEADER",
+            ParseWarning::HeaderTablesOverlap { .. } => "W0022_HEADER_TABLES_OVERLAP",
+            ParseWarning::EncodingMismatch { .. } => "W0023_ENCODING_MISMATCH",
+            ParseWarning::DynamicMismatch { .. } => "W0024_DYNAMIC_MISMATCH",
+            ParseWarning::DynamicUnmapped { .. } => "W0025_DYNAMIC_UNMAPPED",
+            ParseWarning::VersionMismatch { .. } => "W0026_VERSION_MISMATCH",
+            ParseWarning::UndecodableSection { .. } => "W0027_UNDECODABLE_SECTION",
+            ParseWarning::SegmentOutOfBounds { .. } => "W0028_SEGMENT_OUT_OF_BOUNDS",
+            ParseWarning::UnknownSegmentType { .. } => "W0029_UNKNOWN_SEGMENT_TYPE",
+            ParseWarning::SegmentFileSizeExceedsMemSize { .. } => "W0030_SEGMENT_FILESZ_OVER_MEMSZ",
+        }
+    }
+
+    /// File offset the anomaly is at, when it's tied to one
+    pub fn offset(&self) -> Option<u64> {
+        match self {
+            ParseWarning::SectionHeadersOutOfBounds { sh_off, .. }
+            | ParseWarning::SectionHeadersOverlapHeader { sh_off, .. } => Some(*sh_off),
+            ParseWarning::ProgramHeadersOverlapHeader { ph_off, .. } => Some(*ph_off),
+            // where the second table starts, within the first one
+            ParseWarning::HeaderTablesOverlap { ph_off, sh_off } => Some(*ph_off.max(sh_off)),
+            ParseWarning::MisalignedSegment { offset, .. }
+            | ParseWarning::UndecodableSection { offset, .. }
+            | ParseWarning::SegmentOutOfBounds { offset, .. } => Some(*offset),
+            // offsets of the fields within `e_ident`
+            ParseWarning::IdentPadding { .. } => Some(9),
+            ParseWarning::AbiVersionWithoutOsAbi { .. } => Some(8),
+            ParseWarning::VersionMismatch { .. } => Some(6),
+            ParseWarning::IdentOverridden { .. } => Some(4),
+            // the extra bytes follow the fields of the class
+            ParseWarning::OversizedHeader { expected, .. } => Some(*expected as u64),
+            ParseWarning::UndersizedHeader { .. } => Some(0),
+            _ => None,
+        }
+    }
+
+    /// Index of the section header the anomaly is about, if any
+    pub fn section(&self) -> Option<usize> {
+        match self {
+            ParseWarning::MissingHeaderStringTable { sh_str_ndx }
+            | ParseWarning::HeaderStringTableWrongType { sh_str_ndx, .. } => {
+                Some(*sh_str_ndx as usize)
+            }
+            ParseWarning::MalformedStringTable { idx, .. }
+            | ParseWarning::UnknownSectionType { idx, .. }
+            | ParseWarning::MisalignedSection { idx, .. }
+            | ParseWarning::InvalidLink { idx, .. }
+            | ParseWarning::SectionHandlerFailed { idx, .. }
+            | ParseWarning::SectionLint { idx, .. }
+            | ParseWarning::UndecodableSection { idx, .. } => Some(*idx),
+            ParseWarning::TlsSymbolOutOfBlock { section, .. } => Some(*section),
+            ParseWarning::NonNullInitialSection { .. } => Some(0),
+            _ => None,
+        }
+    }
+
+    /// Index of the program header the anomaly is about, if any
+    pub fn segment(&self) -> Option<usize> {
+        match self {
+            ParseWarning::MisalignedSegment { idx, .. }
+            | ParseWarning::InvalidPhdrSegment { idx, .. }
+            | ParseWarning::SegmentOutOfBounds { idx, .. }
+            | ParseWarning::UnknownSegmentType { idx, .. }
+            | ParseWarning::SegmentFileSizeExceedsMemSize { idx, .. } => Some(*idx),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseWarning::SectionHeadersOutOfBounds {
+                sh_off,
+                sh_num,
+                sh_ent_size,
+                file_size,
+            } => write!(
+                f,
+                "section header table of {} entries of {} bytes at {:#x} doesn't fit in the \
+                 {} bytes of the file, ignoring it",
+                sh_num, sh_ent_size, sh_off, file_size
+            ),
+            ParseWarning::HeaderStringTableWrongType { sh_str_ndx, s_type } => write!(
+                f,
+                "section header string table (sh_str_ndx = {}) has type {:#x} instead of SHT_STRTAB",
+                sh_str_ndx, s_type
+            ),
+            ParseWarning::MalformedStringTable { idx, reason } => {
+                write!(f, "string table [{}] {}", idx, reason)
+            }
+            ParseWarning::MissingHeaderStringTable { sh_str_ndx } => write!(
+                f,
+                "section header string table not found (sh_str_ndx = {})",
+                sh_str_ndx
+            ),
+            ParseWarning::UnknownSectionType { idx, s_type } => {
+                write!(f, "section [{}] has unknown type {:#x}", idx, s_type)
+            }
+            ParseWarning::MisalignedSection { idx, addr, align } => write!(
+                f,
+                "section [{}] address {:#x} is not aligned to {}",
+                idx, addr, align
+            ),
+            ParseWarning::MisalignedSegment {
+                idx,
+                vaddr,
+                offset,
+                align,
+            } => write!(
+                f,
+                "segment [{}] vaddr {:#x} and offset {:#x} are not congruent modulo {}",
+                idx, vaddr, offset, align
+            ),
+            ParseWarning::IdentPadding { padding } => {
+                write!(f, "e_ident padding holds non-zero bytes {:02x?}", padding)
+            }
+            ParseWarning::AbiVersionWithoutOsAbi { abi_version } => write!(
+                f,
+                "ABI version {} is set without an OS/ABI (os_abi = 0)",
+                abi_version
+            ),
+            ParseWarning::OversizedHeader { eh_size, expected } => write!(
+                f,
+                "ELF header size {} is larger than the {} bytes of the class",
+                eh_size, expected
+            ),
+            ParseWarning::UndersizedHeader { eh_size, expected } => write!(
+                f,
+                "ELF header size {} is smaller than the {} bytes of the class",
+                eh_size, expected
+            ),
+            ParseWarning::SectionHeadersOverlapHeader { sh_off, header_end } => write!(
+                f,
+                "section header table at {:#x} overlaps the ELF header ending at {:#x}, \
+                 ignoring it",
+                sh_off, header_end
+            ),
+            ParseWarning::IdentOverridden {
+                class,
+                endianness,
+                skipped_validation,
+            } => write!(
+                f,
+                "{} the ident, decoding the file as {}, {}",
+                if *skipped_validation {
+                    "ignoring"
+                } else {
+                    "overriding"
+                },
+                class,
+                endianness
+            ),
+            ParseWarning::NonNullInitialSection { field, value } => write!(
+                f,
+                "initial section header has {} {:#x} instead of 0",
+                field, value
+            ),
+            ParseWarning::InvalidLink { idx, field, value } => {
+                write!(f, "section [{}] has invalid {} value {}", idx, field, value)
+            }
+            ParseWarning::SectionHandlerFailed { idx, error } => {
+                write!(f, "section [{}] handler failed: {}", idx, error)
+            }
+            ParseWarning::HeaderStringTableOutOfRange { sh_str_ndx, sh_num } => write!(
+                f,
+                "section header string table index {} is past the {} section headers, \
+                 section names are unavailable",
+                sh_str_ndx, sh_num
+            ),
+            ParseWarning::InvalidPhdrSegment { idx, reason } => {
+                write!(f, "PT_PHDR segment [{}] {}", idx, reason)
+            }
+            ParseWarning::SectionLint { idx, reason } => {
+                write!(f, "section [{}]: {}", idx, reason)
+            }
+            ParseWarning::ProgramHeadersOverlapHeader { ph_off, header_end } => write!(
+                f,
+                "program header table at {:#x} overlaps the ELF header ending at {:#x}, \
+                 its segments are suspicious",
+                ph_off, header_end
+            ),
+            ParseWarning::EncodingMismatch {
+                declared,
+                declared_score,
+                suspected,
+                suspected_score,
+            } => write!(
+                f,
+                "header scores {}/{} as the declared {} {:?} but {}/{} as {} {:?}",
+                declared_score,
+                MAX_SCORE,
+                declared.class,
+                declared.endianness,
+                suspected_score,
+                MAX_SCORE,
+                suspected.class,
+                suspected.endianness
+            ),
+            ParseWarning::HeaderTablesOverlap { ph_off, sh_off } => write!(
+                f,
+                "program header table at {:#x} and section header table at {:#x} overlap",
+                ph_off, sh_off
+            ),
+            ParseWarning::TlsSymbolOutOfBlock {
+                section,
+                idx,
+                offset,
+                size,
+                memsz,
+            } => write!(
+                f,
+                "TLS symbol [{}] of section [{}] spans {:#x}..{:#x}, past the {:#x} bytes \
+                 of the TLS block",
+                idx,
+                section,
+                offset,
+                offset.saturating_add(*size),
+                memsz
+            ),
+            ParseWarning::DynamicMismatch {
+                tag,
+                found,
+                expected,
+                against,
+            } => write!(
+                f,
+                "dynamic entry DT_{} holds {:#x}, expected {:#x} ({})",
+                dyn_tag_name(*tag),
+                found,
+                expected,
+                against
+            ),
+            ParseWarning::DynamicUnmapped { tag, addr } => write!(
+                f,
+                "dynamic entry DT_{} points at {:#x}, outside every loadable segment",
+                dyn_tag_name(*tag),
+                addr
+            ),
+            ParseWarning::VersionMismatch { ident, e_version } => write!(
+                f,
+                "ELF version {} in the identification and {:#x} in e_version, expected 1 \
+                 in both",
+                *ident as u8, e_version
+            ),
+            ParseWarning::UndecodableSection { idx, error, .. } => write!(
+                f,
+                "section header [{}] can't be decoded ({}), replacing it with a null entry",
+                idx, error
+            ),
+            ParseWarning::SegmentOutOfBounds {
+                idx,
+                offset,
+                filesz,
+                file_size,
+            } => write!(
+                f,
+                "segment [{}] file image of {:#x} bytes at {:#x} doesn't fit in the {} bytes \
+                 of the file, skipping its contents",
+                idx, filesz, offset, file_size
+            ),
+            ParseWarning::UnknownSegmentType { idx, p_type } => {
+                write!(f, "segment [{}] has unknown type {:#x}", idx, p_type)
+            }
+            ParseWarning::SegmentFileSizeExceedsMemSize { idx, filesz, memsz } => write!(
+                f,
+                "segment [{}] file image of {:#x} bytes is larger than its memory image of \
+                 {:#x} bytes, skipping its contents",
+                idx, filesz, memsz
+            ),
+        }
+    }
+}
+
+/// Whether both ranges hold bytes in common, empty ranges overlapping nothing
+pub(crate) fn ranges_overlap(a: &Range<u64>, b: &Range<u64>) -> bool {
+    !a.is_empty() && !b.is_empty() && a.start < b.end && b.start < a.end
 }
 
-impl ElfParser {
+/// Checks the `PT_PHDR` segment at `idx` against the program header table declared
+/// by the ELF header, whose real number of entries is that of `program_headers` when
+/// `ph_num` is `PN_XNUM`, and the loadable segment mapping it
+fn phdr_segment_anomaly(
+    headers: &Elf64Hdr,
+    program_headers: &[Elf64PHdr],
+    idx: usize,
+) -> Option<&'static str> {
+    let phdr = &program_headers[idx];
+    if phdr.offset != headers.ph_off {
+        return Some("offset doesn't match e_phoff");
+    }
+    if phdr.filesz != program_headers.len() as u64 * headers.ph_ent_size as u64 {
+        return Some("size doesn't match the program header table");
+    }
+
+    let end = phdr.offset.saturating_add(phdr.filesz);
+    let Some(load) = program_headers.iter().find(|ph| {
+        ph.p_type == PType::PtLoad
+            && phdr.offset >= ph.offset
+            && end <= ph.offset.saturating_add(ph.filesz)
+    }) else {
+        return Some("isn't mapped by a loadable segment");
+    };
+    if phdr.vaddr.0 != load.vaddr.0.wrapping_add(phdr.offset - load.offset) {
+        return Some("vaddr doesn't match the loadable segment mapping it");
+    }
+
+    None
+}
+
+impl<'a> ElfParser<'a> {
+    /// Reads every `SHT_STRTAB` section, along with the section at `sh_str_ndx` whatever
+    /// its type. Tables that don't fit in the file are dropped, and those along with
+    /// malformed tables are reported in `warnings`. `deadline` is checked before each
+    /// table
     pub fn parse_string_tables(
         data: &[u8],
         headers: &Elf64Hdr,
-        section_headers: &Vec<Elf64SHdr>,
+        section_headers: &[Elf64SHdr],
+        options: &ParseOptions,
+        deadline: &Deadline,
+        warnings: &mut Vec<ParseWarning>,
     ) -> Result<Vec<StringTable>, ParseError> {
-        Ok(section_headers
+        let sh_str_ndx = ElfParser::header_string_table_index(headers, section_headers);
+        let mut string_tables = vec![];
+
+        for (idx, str_sh) in section_headers.iter().enumerate() {
+            deadline.check()?;
+
+            let is_header_table = Some(idx) == sh_str_ndx;
+            if str_sh.s_type != SHT_STRTAB && !is_header_table {
+                continue;
+            }
+
+            if str_sh.s_type != SHT_STRTAB {
+                warnings.push(ParseWarning::HeaderStringTableWrongType {
+                    sh_str_ndx: headers.sh_str_ndx,
+                    s_type: str_sh.s_type,
+                });
+                // the section holds no bytes to read names from
+                if !str_sh.occupies_file_space() {
+                    continue;
+                }
+            }
+
+            let sh_type = ElfParser::string_table_type(idx, headers, section_headers);
+
+            options.check_alloc(str_sh.file_size())?;
+            let table = match Elf64SHdr::parse_str_table(data, str_sh, idx, sh_type) {
+                Ok(table) => table,
+                Err(_) => {
+                    warnings.push(ParseWarning::MalformedStringTable {
+                        idx,
+                        reason: "runs past the end of the file",
+                    });
+                    continue;
+                }
+            };
+
+            if table.table.first().is_some_and(|&c| c != 0) {
+                warnings.push(ParseWarning::MalformedStringTable {
+                    idx,
+                    reason: "doesn't start with a NUL byte",
+                });
+            }
+            if table.table.last().is_some_and(|&c| c != 0) {
+                warnings.push(ParseWarning::MalformedStringTable {
+                    idx,
+                    reason: "doesn't end with a NUL byte",
+                });
+            }
+
+            string_tables.push(table);
+        }
+
+        debug!("parsed {} string tables", string_tables.len());
+
+        if !string_tables
+            .iter()
+            .any(|st| st.sh_type == StringTableType::ShStrTab)
+        {
+            warn!(
+                "section header string table not found (sh_str_ndx = {})",
+                headers.sh_str_ndx
+            );
+        }
+
+        Ok(string_tables)
+    }
+
+    /// Index of the section header string table: `sh_str_ndx`, or the `link` of the
+    /// initial section header when it's `SHN_XINDEX`. None for `SHN_UNDEF`, when the
+    /// file has no section names. The index isn't checked against the section header
+    /// table
+    pub fn header_string_table_index(
+        headers: &Elf64Hdr,
+        section_headers: &[Elf64SHdr],
+    ) -> Option<usize> {
+        match headers.sh_str_ndx {
+            SHN_UNDEF => None,
+            SHN_XINDEX => section_headers.first().map(|sh| sh.link as usize),
+            idx => Some(idx as usize),
+        }
+    }
+
+    /// Kind of the string table at `idx`. Tables are told apart by the sections
+    /// referring to them, the one linked from the dynamic symbol table being `.dynstr`
+    pub fn string_table_type(
+        idx: usize,
+        headers: &Elf64Hdr,
+        section_headers: &[Elf64SHdr],
+    ) -> StringTableType {
+        if Some(idx) == ElfParser::header_string_table_index(headers, section_headers) {
+            StringTableType::ShStrTab
+        } else if section_headers.iter().any(|sh| {
+            sh.link as usize == idx && (sh.s_type == SHT_DYNSYM || sh.s_type == SHT_DYNAMIC)
+        }) {
+            StringTableType::DynStr
+        } else {
+            StringTableType::StrTab
+        }
+    }
+
+    /// Invokes every handler of `options` on the sections it matches. Sections that
+    /// don't fit in the file and handlers failing are reported in `warnings`
+    pub fn run_section_handlers(
+        data: &[u8],
+        ctx: &ParseContext,
+        headers: &Elf64Hdr,
+        section_headers: &[Elf64SHdr],
+        header_string_table: Option<&StringTable>,
+        handlers: &SectionHandlers,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Vec<SectionValue> {
+        let mut values = vec![];
+        if handlers.is_empty() {
+            return values;
+        }
+
+        for (idx, sh) in section_headers.iter().enumerate() {
+            let name = header_string_table.and_then(|st| ElfParser::get_sh_name(st, sh.name).ok());
+            for handler in handlers.matching(name.as_deref(), sh) {
+                let off = sh.offset as usize;
+                let result = data
+                    .get(off..off.saturating_add(sh.file_size() as usize))
+                    .ok_or(ParseError::InvalidLength)
+                    .and_then(|bytes| {
+                        handler(&SectionInput {
+                            idx,
+                            header: sh,
+                            data: bytes,
+                            ctx,
+                            headers,
+                            section_headers,
+                        })
+                    });
+
+                match result {
+                    Ok(value) => values.push(SectionValue {
+                        idx,
+                        value: Arc::from(value),
+                    }),
+                    Err(e) => {
+                        let warning = ParseWarning::SectionHandlerFailed {
+                            idx,
+                            error: format!("{:?}", e),
+                        };
+                        warn!("{}", warning);
+                        warnings.push(warning);
+                    }
+                }
+            }
+        }
+
+        debug!("section handlers decoded {} values", values.len());
+
+        values
+    }
+
+    /// Reads every `SHT_SYMTAB` and `SHT_DYNSYM` section. `deadline` is checked every
+    /// `DEADLINE_STRIDE` symbols, so a huge table can't outlast it by much
+    pub fn parse_symbol_tables(
+        data: &[u8],
+        ctx: &ParseContext,
+        section_headers: &[Elf64SHdr],
+        options: &ParseOptions,
+        header_string_table: Option<&StringTable>,
+        deadline: &Deadline,
+    ) -> Result<Vec<SymbolTable>, ParseError> {
+        let entry_size = layout::sym::for_class(ctx.class).size;
+
+        section_headers
             .iter()
             .enumerate()
-            .filter(|(_, sh)| sh.s_type == SHT_STRTAB)
-            .map(|(idx, str_sh)| {
-                Elf64SHdr::parse_str_table(&data, str_sh, idx == headers.sh_str_ndx as usize)
-                    .unwrap()
+            .filter(|(_, sh)| sh.s_type == SHT_SYMTAB || sh.s_type == SHT_DYNSYM)
+            .map(|(idx, sym_sh)| {
+                let in_section = |e: ParseError| {
+                    let name = header_string_table
+                        .and_then(|st| ElfParser::get_sh_name(st, sym_sh.name).ok());
+                    e.in_section(idx, name)
+                };
+                deadline.check()?;
+
+                let off = sym_sh.offset as usize;
+                let table = data
+                    .get(off..off.saturating_add(sym_sh.file_size() as usize))
+                    .ok_or(ParseError::InvalidLength)
+                    .map_err(in_section)?;
+
+                let count = options
+                    .check_entries_alloc::<Elf64Sym>(table.len() / entry_size)
+                    .map_err(in_section)?;
+                let mut symbols = Vec::with_capacity(count);
+                for chunk in table.chunks(DEADLINE_STRIDE * entry_size) {
+                    let entries = Elf64Sym::parse_entries(chunk, ctx, symbols.len());
+                    symbols.extend(entries.map_err(in_section)?);
+                    deadline.check()?;
+                }
+                debug!("parsed {} symbols from section [{}]", symbols.len(), idx);
+
+                Ok(SymbolTable {
+                    section_idx: idx,
+                    link: sym_sh.link,
+                    sh_type: sym_sh.s_type,
+                    symbols,
+                })
             })
-            .collect())
+            .collect()
     }
 
-    pub fn get_sh_name(str_table: &StringTable, idx: u32) -> Result<String, ()> {
-        str_from_u8(&str_table.table[idx as usize..])
+    pub fn get_sh_name(str_table: &StringTable, idx: u32) -> Result<String, ParseError> {
+        str_from_u8(
+            str_table
+                .table
+                .get(idx as usize..)
+                .ok_or(ParseError::InvalidLength)?,
+        )
     }
 
-    pub fn parse(data: Vec<u8>) -> Result<Self, ParseError> {
-        let headers = *Elf64Hdr::parse(&data)?.validate();
-        let section_headers = Elf64SHdr::parse(&data, &headers)?;
-        let program_headers = Elf64PHdr::parse(&data, &headers)?;
+    /// Returns the first string table of the given kind
+    pub fn string_table(&self, kind: StringTableType) -> Option<&StringTable> {
+        self.string_tables.iter().find(|st| st.sh_type == kind)
+    }
 
-        let string_tables =
-            ElfParser::parse_string_tables(&data, &headers, &section_headers).unwrap();
-        let header_string_table_idx = string_tables
+    /// Returns the string table read from the section header at `section_idx`
+    pub fn string_table_at(&self, section_idx: usize) -> Option<&StringTable> {
+        self.string_tables
+            .iter()
+            .find(|st| st.section_idx == section_idx)
+    }
+
+    /// Returns the section header named `name`, along with its index
+    pub fn section_by_name(&self, name: &str) -> Option<(usize, &Elf64SHdr)> {
+        self.section_headers
             .iter()
             .enumerate()
-            .filter(|(_, sh)| sh.sh_type == StringTableType::ShStrTab)
-            .map(|(idx, _)| idx)
-            .nth(0)
-            .unwrap();
+            .find(|(_, sh)| self.section_name(sh).is_ok_and(|n| n == name))
+    }
+
+    /// Returns the bytes of the file image of the section at `idx`, which is empty for
+    /// sections that occupy no file space
+    pub fn section_data(&self, idx: usize) -> Result<&[u8], ParseError> {
+        let sh = self
+            .section_headers
+            .get(idx)
+            .ok_or(ParseError::InvalidLength)?;
+        let end = sh
+            .offset
+            .checked_add(sh.file_size())
+            .ok_or(ParseError::InvalidLength)?;
+
+        self.data
+            .get(sh.offset as usize..end as usize)
+            .ok_or(ParseError::InvalidLength)
+    }
+
+    /// Returns the bytes of the file image of the segment at `idx`
+    pub fn segment_data(&self, idx: usize) -> Result<&[u8], ParseError> {
+        let ph = self
+            .program_headers
+            .get(idx)
+            .ok_or(ParseError::InvalidLength)?;
+        let end = ph
+            .offset
+            .checked_add(ph.filesz)
+            .ok_or(ParseError::InvalidLength)?;
+
+        self.data
+            .get(ph.offset as usize..end as usize)
+            .ok_or(ParseError::InvalidLength)
+    }
+
+    /// Returns the entries of the dynamic segment in file order, which is meaningful
+    /// and never sorted, if the file has one
+    pub fn dynamic_entries(&self) -> Option<&[ELF64Dyn]> {
+        self.program_headers
+            .iter()
+            .find_map(|ph| match &ph.section {
+                PTypeData::PtDynamicData(entries) => Some(entries.as_slice()),
+                _ => None,
+            })
+    }
+
+    /// Returns the `PT_PHDR` segment, which tells the loader where the program header
+    /// table is mapped. Static executables usually have none
+    pub fn phdr_segment(&self) -> Option<&Elf64PHdr> {
+        self.program_headers
+            .iter()
+            .find(|ph| ph.p_type == PType::PtPhdr)
+    }
+
+    /// Returns the first dynamic entry tagged `d_tag`
+    pub fn dynamic_entry(&self, d_tag: i64) -> Option<&ELF64Dyn> {
+        self.dynamic_entries()?.iter().find(|d| d.d_tag == d_tag)
+    }
+
+    pub fn dyn_flags(&self) -> Option<DynFlags> {
+        self.dynamic_entry(DT_FLAGS).map(|d| DynFlags(d.value()))
+    }
 
-        Ok(ElfParser {
+    pub fn dyn_flags_1(&self) -> Option<DynFlags1> {
+        self.dynamic_entry(DT_FLAGS_1).map(|d| DynFlags1(d.value()))
+    }
+
+    /// Decodes the unwinding search table of the `.eh_frame_hdr` section, or the
+    /// `PT_GNU_EH_FRAME` segment if the file has no section headers
+    pub fn eh_frame_hdr(&self) -> Result<Option<EhFrameHdr>, ParseError> {
+        let (data, vaddr) = if let Some((idx, sh)) = self.section_by_name(".eh_frame_hdr") {
+            (self.section_data(idx)?, sh.addr.0)
+        } else if let Some(idx) = self
+            .program_headers
+            .iter()
+            .position(|ph| ph.p_type == PType::PtGnuEhFrame)
+        {
+            (self.segment_data(idx)?, self.program_headers[idx].vaddr.0)
+        } else {
+            return Ok(None);
+        };
+
+        EhFrameHdr::parse(data, &self.ctx, vaddr).map(Some)
+    }
+
+    /// Resolves the name of a section through the section header string table
+    pub fn section_name(&self, section_header: &Elf64SHdr) -> Result<String, ParseError> {
+        let str_table = self
+            .header_string_table_idx
+            .and_then(|idx| self.string_tables.get(idx))
+            .ok_or(ParseError::NameUnavailable)?;
+
+        ElfParser::get_sh_name(str_table, section_header.name)
+    }
+
+    /// Resolves the name of a symbol through the string table linked from its table
+    pub fn symbol_name(
+        &self,
+        table: &SymbolTable,
+        symbol: &Elf64Sym,
+    ) -> Result<String, ParseError> {
+        let str_table = self
+            .linked_string_table(table.section_idx)?
+            .ok_or(ParseError::NameUnavailable)?;
+
+        ElfParser::get_sh_name(str_table, symbol.name)
+    }
+
+    /// Name of a symbol as readelf lists it: unnamed section symbols take the name of
+    /// their section, the others are resolved as `symbol_name` does
+    pub fn symbol_display_name(
+        &self,
+        table: &SymbolTable,
+        symbol: &Elf64Sym,
+    ) -> Result<String, ParseError> {
+        match self.section_headers.get(symbol.shndx as usize) {
+            Some(sh) if symbol.name == 0 && symbol.sym_type() == STT_SECTION => {
+                self.section_name(sh)
+            }
+            _ => self.symbol_name(table, symbol),
+        }
+    }
+
+    /// Parses the file held by `data`, borrowing it for the lifetime of the parser
+    pub fn parse(data: &'a [u8]) -> Result<Self, ParseError> {
+        ElfParser::parse_with(data, &ParseOptions::default())
+    }
+
+    /// Parses the file held by `data`, taking every buffer sized by the file from
+    /// `arena`, see `ParseOptions::arena`
+    pub fn parse_in(arena: &Arena, data: &'a [u8]) -> Result<Self, ParseError> {
+        ElfParser::parse_with(data, &ParseOptions::default().arena(arena.clone()))
+    }
+
+    /// Parses the file held by `data` reading as much of it as `options` allow
+    pub fn parse_with(data: &'a [u8], options: &ParseOptions) -> Result<Self, ParseError> {
+        ElfParser::parse_data(FileData::Borrowed(data), options)
+    }
+
+    /// Parses the file held by `data`, taking ownership of the buffer so the parser
+    /// isn't tied to the caller's lifetime
+    pub fn from_owned(data: Vec<u8>) -> Result<ElfParser<'static>, ParseError> {
+        ElfParser::parse_data(FileData::Owned(data), &ParseOptions::default())
+    }
+
+    /// Parses the file held by `data` as `parse_with` does, taking ownership of the
+    /// buffer as `from_owned` does
+    pub fn from_owned_with(
+        data: Vec<u8>,
+        options: &ParseOptions,
+    ) -> Result<ElfParser<'static>, ParseError> {
+        ElfParser::parse_data(FileData::Owned(data), options)
+    }
+
+    /// Parses the file held by `data`, sharing the buffer among the parser and its
+    /// clones, which can be handed to other threads without copying the file. The
+    /// contents of loadable segments aren't copied into `PTypeData::PtLoadData`
+    /// either, `segment_data` reads them from the shared buffer instead
+    pub fn parse_shared(data: Arc<[u8]>) -> Result<ElfParser<'static>, ParseError> {
+        ElfParser::parse_data(
+            FileData::Shared(data),
+            &ParseOptions::default().load_segment_data(false),
+        )
+    }
+
+    /// Returns the contents of the whole parsed file
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Replaces the contents of the file, which must keep the layout the headers
+    /// describe
+    pub(crate) fn replace_data(&mut self, data: Vec<u8>) {
+        self.data = FileData::Owned(data);
+    }
+
+    /// Collects the anomalies of the parsed headers
+    fn collect_warnings(
+        headers: &Elf64Hdr,
+        section_headers: &[Elf64SHdr],
+        program_headers: &[Elf64PHdr],
+        header_string_table_idx: Option<usize>,
+        options: &ParseOptions,
+        mut warnings: Vec<ParseWarning>,
+    ) -> Vec<ParseWarning> {
+        let ident = &headers.ident;
+        if options.overrides_ident() {
+            warnings.push(ParseWarning::IdentOverridden {
+                class: ident.class,
+                endianness: ident.data,
+                skipped_validation: options.skip_ident_validation,
+            });
+        }
+
+        if !ident.is_padding_clean() {
+            warnings.push(ParseWarning::IdentPadding {
+                padding: *ident.padding(),
+            });
+        }
+
+        if ident.version != ElfHVersion::ElfEvCurr || headers.version.value() != 1 {
+            warnings.push(ParseWarning::VersionMismatch {
+                ident: ident.version,
+                e_version: headers.version.value(),
+            });
+        }
+
+        if ident.os_abi == 0 && ident.abi_version != 0 {
+            warnings.push(ParseWarning::AbiVersionWithoutOsAbi {
+                abi_version: ident.abi_version,
+            });
+        }
+
+        let expected = layout::ehdr::for_class(ident.class).size as u16;
+        if headers.eh_size > expected {
+            warnings.push(ParseWarning::OversizedHeader {
+                eh_size: headers.eh_size,
+                expected,
+            });
+        }
+        if headers.eh_size < expected {
+            warnings.push(ParseWarning::UndersizedHeader {
+                eh_size: headers.eh_size,
+                expected,
+            });
+        }
+
+        let ph_table = headers.ph_off
+            ..headers
+                .ph_off
+                .saturating_add(program_headers.len() as u64 * headers.ph_ent_size as u64);
+        let sh_table = headers.sh_off
+            ..headers
+                .sh_off
+                .saturating_add(section_headers.len() as u64 * headers.sh_ent_size as u64);
+        if headers.overlaps_header(ph_table.start, ph_table.end - ph_table.start) {
+            warnings.push(ParseWarning::ProgramHeadersOverlapHeader {
+                ph_off: headers.ph_off,
+                header_end: headers.header_end(),
+            });
+        }
+        if ranges_overlap(&ph_table, &sh_table) {
+            warnings.push(ParseWarning::HeaderTablesOverlap {
+                ph_off: headers.ph_off,
+                sh_off: headers.sh_off,
+            });
+        }
+
+        let sh_str_ndx = ElfParser::header_string_table_index(headers, section_headers);
+        let out_of_range = sh_str_ndx.filter(|idx| *idx >= section_headers.len());
+        if let (Some(sh_str_ndx), false) = (out_of_range, section_headers.is_empty()) {
+            warnings.push(ParseWarning::HeaderStringTableOutOfRange {
+                sh_str_ndx: sh_str_ndx as u32,
+                sh_num: section_headers.len(),
+            });
+        } else if options.parse_string_tables
+            && header_string_table_idx.is_none()
+            && !section_headers.is_empty()
+        {
+            warnings.push(ParseWarning::MissingHeaderStringTable {
+                sh_str_ndx: headers.sh_str_ndx,
+            });
+        }
+
+        if let Some(initial) = section_headers.first() {
+            for (field, value) in initial.initial_entry_anomalies(headers) {
+                warnings.push(ParseWarning::NonNullInitialSection { field, value });
+            }
+        }
+
+        for (idx, sh) in section_headers.iter().enumerate() {
+            if sh.has_unknown_type() {
+                warnings.push(ParseWarning::UnknownSectionType {
+                    idx,
+                    s_type: sh.s_type,
+                });
+            }
+        }
+        warnings.extend(ElfParser::lint_section_headers(section_headers));
+
+        if let Some(idx) = program_headers
+            .iter()
+            .position(|ph| ph.p_type == PType::PtPhdr)
+        {
+            if let Some(reason) = phdr_segment_anomaly(headers, program_headers, idx) {
+                warnings.push(ParseWarning::InvalidPhdrSegment { idx, reason });
+            }
+        }
+
+        for (idx, ph) in program_headers.iter().enumerate() {
+            if ph.is_misaligned() {
+                warnings.push(ParseWarning::MisalignedSegment {
+                    idx,
+                    vaddr: ph.vaddr.0,
+                    offset: ph.offset,
+                    align: ph.align,
+                });
+            }
+        }
+
+        warnings
+    }
+
+    fn parse_data(data: FileData<'a>, options: &ParseOptions) -> Result<Self, ParseError> {
+        // checked before anything is decoded, as a file in the wrong encoding rarely
+        // gets past its header
+        let mismatch = match options.detect_encoding_mismatch {
+            true => Elf64Hdr::parse_ident_raw(&data, options.force_class, options.force_endianness)
+                .ok()
+                .and_then(|ident| detect_encoding_mismatch(&data, ParseContext::from(&ident))),
+            false => None,
+        };
+        let warnings = mismatch
+            .iter()
+            .map(|m| {
+                let warning = ParseWarning::EncodingMismatch {
+                    declared: m.declared,
+                    declared_score: m.declared_score,
+                    suspected: m.suspected,
+                    suspected_score: m.suspected_score,
+                };
+                warn!("{}", warning);
+                warning
+            })
+            .collect();
+
+        ElfParser::decode(data, options, warnings).map_err(|e| match mismatch {
+            Some(mismatch) if !matches!(e, ParseError::StrictViolation(_)) => {
+                ParseError::InMismatchedEncoding {
+                    mismatch,
+                    source: Box::new(e),
+                }
+            }
+            _ => e,
+        })
+    }
+
+    /// Parses `data`, starting from the anomalies already found in `warnings`
+    fn decode(
+        data: FileData<'a>,
+        options: &ParseOptions,
+        mut warnings: Vec<ParseWarning>,
+    ) -> Result<Self, ParseError> {
+        let start = Instant::now();
+        let deadline = Deadline::after(start, options.deadline);
+        let mut phases = PhaseDurations::default();
+        // time spent since the previous phase ended, failing past the deadline
+        let mut phase_end = start;
+        let mut end_phase = |phase: &mut std::time::Duration| {
+            let now = Instant::now();
+            *phase = now - phase_end;
+            phase_end = now;
+            deadline.check()
+        };
+
+        let headers = Elf64Hdr::parse_with(&data, options)?;
+        end_phase(&mut phases.header)?;
+        debug!(
+            "parsed ELF header: {} bytes, entry {:?}",
+            headers.eh_size, headers.entry
+        );
+
+        let ctx = ParseContext::from(&headers.ident);
+
+        // a loader only needs the program headers, so a section header table that
+        // doesn't fit in the file or overlaps the ELF header is dropped rather than failing the whole parse
+        let mut section_headers =
+            match Elf64SHdr::parse(&data, &ctx, &headers, options, &mut warnings) {
+                Err(ParseError::SectionHeadersOutOfBounds) => {
+                    let warning = ParseWarning::SectionHeadersOutOfBounds {
+                        sh_off: headers.sh_off,
+                        sh_num: headers.sh_num,
+                        sh_ent_size: headers.sh_ent_size,
+                        file_size: data.len() as u64,
+                    };
+                    warn!("{}", warning);
+                    warnings.push(warning);
+                    vec![]
+                }
+                Err(ParseError::TableOverlapsHeader { offset, .. }) => {
+                    let warning = ParseWarning::SectionHeadersOverlapHeader {
+                        sh_off: offset,
+                        header_end: headers.header_end(),
+                    };
+                    warn!("{}", warning);
+                    warnings.push(warning);
+                    vec![]
+                }
+                result => result?,
+            };
+        if let Some(load_bias) = options.load_bias {
+            mapped::map_sections(&mut section_headers, load_bias);
+        }
+        end_phase(&mut phases.section_table)?;
+        let program_headers = Elf64PHdr::parse(&data, &ctx, &headers, options, &mut warnings)?;
+        end_phase(&mut phases.segment_data)?;
+
+        let string_tables = match options.parse_string_tables {
+            true => ElfParser::parse_string_tables(
+                &data,
+                &headers,
+                &section_headers,
+                options,
+                &deadline,
+                &mut warnings,
+            )?,
+            false => vec![],
+        };
+        end_phase(&mut phases.string_tables)?;
+        let header_string_table_idx = string_tables
+            .iter()
+            .position(|st| st.sh_type == StringTableType::ShStrTab);
+        let symbol_tables = match options.parse_symbols {
+            true => ElfParser::parse_symbol_tables(
+                &data,
+                &ctx,
+                &section_headers,
+                options,
+                header_string_table_idx.and_then(|idx| string_tables.get(idx)),
+                &deadline,
+            )?,
+            false => vec![],
+        };
+        end_phase(&mut phases.symbols)?;
+
+        let provenance = options.track_provenance.then(|| {
+            Provenance::record(
+                &ctx,
+                &headers,
+                &section_headers,
+                &program_headers,
+                &symbol_tables,
+            )
+        });
+        let parsed_sections = ElfParser::run_section_handlers(
+            &data,
+            &ctx,
+            &headers,
+            &section_headers,
+            header_string_table_idx.and_then(|idx| string_tables.get(idx)),
+            &options.section_handlers,
+            &mut warnings,
+        );
+
+        let warnings = ElfParser::collect_warnings(
+            &headers,
+            &section_headers,
+            &program_headers,
+            header_string_table_idx,
+            options,
+            warnings,
+        );
+        let mut parser = ElfParser {
             headers,
+            ctx,
             program_headers,
             section_headers,
             string_tables,
             header_string_table_idx,
-        })
+            symbol_tables,
+            warnings,
+            parsed_sections,
+            provenance,
+            symbol_index: OnceLock::new(),
+            sorted_symbols: OnceLock::new(),
+            stats: ParseStats::default(),
+            options: options.clone(),
+            source_info: None,
+            data,
+        };
+        // checks needing the symbol names, which are read through the parser
+        let tls_anomalies = parser.tls_anomalies();
+        parser.warnings.extend(tls_anomalies);
+        let dynamic_anomalies = parser.verify_dynamic();
+        parser.warnings.extend(dynamic_anomalies);
+        if options.strict && !parser.warnings.is_empty() {
+            return Err(ParseError::StrictViolation(parser.warnings));
+        }
+
+        parser.stats = ParseStats::count(&parser, phases);
+
+        Ok(parser)
     }
 }
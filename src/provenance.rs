@@ -0,0 +1,145 @@
+use crate::bytes::ParseContext;
+use crate::elf::ehdr::Elf64Hdr;
+use crate::elf::phdr::{Elf64PHdr, PTypeData};
+use crate::elf::shdr::Elf64SHdr;
+use crate::elf::sym::SymbolTable;
+use crate::layout;
+use crate::parser::{ranges_overlap, ElfParser, ParseWarning};
+use std::ops::Range;
+
+/// A structure decoded from the file, by its position in the tables of `ElfParser`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Structure {
+    FileHeader,
+    SectionHeader(usize),
+    ProgramHeader(usize),
+    /// Entry `idx` of `ElfParser::symbol_tables[table]`
+    Symbol {
+        table: usize,
+        idx: usize,
+    },
+    /// Entry of the dynamic segment, indexed as in `ElfParser::dynamic_entries`
+    DynamicEntry(usize),
+}
+
+/// Byte ranges of the input every structure was decoded from, kept aside from the
+/// structures so they don't grow when provenance isn't tracked
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Provenance {
+    file_header: Range<u64>,
+    section_headers: Vec<Range<u64>>,
+    program_headers: Vec<Range<u64>>,
+    symbols: Vec<Vec<Range<u64>>>,
+    dynamic_entries: Vec<Range<u64>>,
+}
+
+/// Ranges of `count` entries of `size` bytes laid out every `stride` bytes from `start`
+fn entries(start: u64, count: usize, stride: u64, size: u64) -> Vec<Range<u64>> {
+    (0..count as u64)
+        .map(|idx| {
+            let off = start + idx * stride;
+            off..off + size
+        })
+        .collect()
+}
+
+impl Provenance {
+    /// Locates the entries of the tables read while parsing, as the parsers of each
+    /// table walk them
+    pub fn record(
+        ctx: &ParseContext,
+        headers: &Elf64Hdr,
+        section_headers: &[Elf64SHdr],
+        program_headers: &[Elf64PHdr],
+        symbol_tables: &[SymbolTable],
+    ) -> Self {
+        let sh_size = headers.sh_ent_size as u64;
+        let ph_size = headers.ph_ent_size as u64;
+        let sym_size = layout::sym::for_class(ctx.class).size as u64;
+        let dyn_size = 2 * ctx.word_size() as u64;
+
+        let dynamic_entries = program_headers
+            .iter()
+            .find_map(|ph| match &ph.section {
+                PTypeData::PtDynamicData(d) => {
+                    Some(entries(ph.offset, d.len(), dyn_size, dyn_size))
+                }
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        Provenance {
+            file_header: 0..headers.header_end(),
+            section_headers: entries(headers.sh_off, section_headers.len(), sh_size, sh_size),
+            program_headers: entries(headers.ph_off, program_headers.len(), ph_size, ph_size),
+            symbols: symbol_tables
+                .iter()
+                .map(|table| {
+                    let offset = section_headers[table.section_idx].offset;
+                    entries(offset, table.symbols.len(), sym_size, sym_size)
+                })
+                .collect(),
+            dynamic_entries,
+        }
+    }
+
+    pub fn get(&self, structure: Structure) -> Option<Range<u64>> {
+        match structure {
+            Structure::FileHeader => Some(self.file_header.clone()),
+            Structure::SectionHeader(idx) => self.section_headers.get(idx).cloned(),
+            Structure::ProgramHeader(idx) => self.program_headers.get(idx).cloned(),
+            Structure::Symbol { table, idx } => self.symbols.get(table)?.get(idx).cloned(),
+            Structure::DynamicEntry(idx) => self.dynamic_entries.get(idx).cloned(),
+        }
+    }
+}
+
+impl ElfParser<'_> {
+    /// Byte range of the input `structure` was decoded from, when parsing with
+    /// `ParseOptions::track_provenance`
+    pub fn provenance(&self, structure: Structure) -> Option<Range<u64>> {
+        self.provenance.as_ref()?.get(structure)
+    }
+
+    /// Byte range of the section or program header `warning` is about, or of the ELF
+    /// header for anomalies of the file as a whole, see `provenance`
+    pub fn warning_provenance(&self, warning: &ParseWarning) -> Option<Range<u64>> {
+        let structure = match (warning.section(), warning.segment()) {
+            (Some(idx), _) => Structure::SectionHeader(idx),
+            (_, Some(idx)) => Structure::ProgramHeader(idx),
+            _ => Structure::FileHeader,
+        };
+
+        self.provenance(structure)
+    }
+
+    /// Whether `structure` was decoded from bytes also holding another table: program
+    /// headers within the ELF header or the section header table, and section headers
+    /// within the program header table. These are the entries `ProgramHeadersOverlapHeader`
+    /// and `HeaderTablesOverlap` warn about, which don't need provenance to be tracked
+    pub fn is_suspicious(&self, structure: Structure) -> bool {
+        let h = &self.headers;
+        let ph_table = h.ph_off
+            ..h.ph_off
+                .saturating_add(self.program_headers.len() as u64 * h.ph_ent_size as u64);
+        let sh_table = h.sh_off
+            ..h.sh_off
+                .saturating_add(self.section_headers.len() as u64 * h.sh_ent_size as u64);
+        let entry = |table: &Range<u64>, idx: usize, size: u16| {
+            let start = table.start.saturating_add(idx as u64 * size as u64);
+            start..start.saturating_add(size as u64)
+        };
+
+        match structure {
+            Structure::ProgramHeader(idx) if idx < self.program_headers.len() => {
+                let range = entry(&ph_table, idx, h.ph_ent_size);
+                h.overlaps_header(range.start, range.end - range.start)
+                    || ranges_overlap(&range, &sh_table)
+            }
+            Structure::SectionHeader(idx) if idx < self.section_headers.len() => {
+                ranges_overlap(&entry(&sh_table, idx, h.sh_ent_size), &ph_table)
+            }
+            _ => false,
+        }
+    }
+}
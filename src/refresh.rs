@@ -0,0 +1,269 @@
+//! Update of a parsed file after it was rewritten, reading again only what's backed
+//! by bytes that changed.
+//!
+//! The new contents are compared with the old ones over the header, the two header
+//! tables and then the file image of every section and segment. When the header or
+//! the header tables changed, or the file changed size, the layout can't be trusted
+//! and the file is parsed again as a whole. Otherwise only the parts read from the
+//! sections and segments that changed are read again:
+//!
+//! - the program headers, whose segment data is copied from the file, when any
+//!   segment changed
+//! - the string tables, when any of them changed
+//! - the symbol tables, when any of them or the string tables changed, dropping the
+//!   name index and the address order
+//! - the values of the section handlers, when any section changed
+
+use crate::elf::phdr::Elf64PHdr;
+use crate::elf::shdr::StringTableType;
+use crate::parser::{ElfParser, ParseError, ParseWarning};
+use crate::stats::{Deadline, ParseStats};
+use log::debug;
+use std::ops::Range;
+use std::sync::OnceLock;
+
+/// What `ElfParser::refresh` read again, for callers to drop what they derived from it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RefreshReport {
+    /// The layout changed and the whole file was parsed again, invalidating
+    /// everything. The other fields are left empty
+    pub full: bool,
+    /// Indices of the sections whose file image changed
+    pub changed_sections: Vec<usize>,
+    /// Indices of the segments whose file image changed
+    pub changed_segments: Vec<usize>,
+    pub program_headers: bool,
+    pub string_tables: bool,
+    /// Symbols were read again, so `SymbolRef`s into them may point elsewhere
+    pub symbol_tables: bool,
+    pub parsed_sections: bool,
+}
+
+impl RefreshReport {
+    /// Whether the new contents are the same as the old ones
+    pub fn is_unchanged(&self) -> bool {
+        !self.full && self.changed_sections.is_empty() && self.changed_segments.is_empty()
+    }
+}
+
+/// Whether `old` and `new` differ over `range`, which counts as a change when it runs
+/// past the end of either
+fn range_changed(old: &[u8], new: &[u8], range: Range<u64>) -> bool {
+    let range = range.start as usize..range.end as usize;
+
+    match (old.get(range.clone()), new.get(range)) {
+        (Some(old), Some(new)) => old != new,
+        _ => true,
+    }
+}
+
+impl ElfParser<'_> {
+    /// Ranges holding the ELF header and the two header tables
+    fn layout_ranges(&self) -> [Range<u64>; 3] {
+        let headers = &self.headers;
+        let table = |off: u64, count: usize, ent_size: u16| {
+            off..off.saturating_add(count as u64 * ent_size as u64)
+        };
+
+        [
+            0..headers.header_end(),
+            table(
+                headers.ph_off,
+                self.program_headers.len(),
+                headers.ph_ent_size,
+            ),
+            table(
+                headers.sh_off,
+                self.section_headers.len(),
+                headers.sh_ent_size,
+            ),
+        ]
+    }
+
+    /// Replaces the parsed file with `new_data`, the new contents of the same file,
+    /// reading again only what changed as the module describes. The options the file
+    /// was first parsed with are used. When reading fails the parser is left as it
+    /// was, but with `ParseOptions::strict` the anomalies of the new contents fail
+    /// the refresh once the parser holds them
+    pub fn refresh(&mut self, new_data: &[u8]) -> Result<RefreshReport, ParseError> {
+        let old_data = self.data();
+
+        let relayout = old_data.len() != new_data.len()
+            || self
+                .layout_ranges()
+                .into_iter()
+                .any(|range| range_changed(old_data, new_data, range));
+        if relayout {
+            debug!("layout changed, parsing the whole file again");
+            let source_info = self.source_info.take();
+            *self = ElfParser::from_owned_with(new_data.to_vec(), &self.options)?;
+            self.source_info = source_info;
+
+            return Ok(RefreshReport {
+                full: true,
+                ..RefreshReport::default()
+            });
+        }
+
+        let changed_sections = self
+            .section_headers
+            .iter()
+            .enumerate()
+            .filter(|(_, sh)| {
+                range_changed(old_data, new_data, sh.offset..sh.offset + sh.file_size())
+            })
+            .map(|(idx, _)| idx)
+            .collect::<Vec<_>>();
+        let changed_segments = self
+            .program_headers
+            .iter()
+            .enumerate()
+            .filter(|(_, ph)| range_changed(old_data, new_data, ph.offset..ph.offset + ph.filesz))
+            .map(|(idx, _)| idx)
+            .collect::<Vec<_>>();
+
+        let is_changed = |idx: usize| changed_sections.contains(&idx);
+        let string_tables = self
+            .string_tables
+            .iter()
+            .any(|st| is_changed(st.section_idx));
+        let symbol_tables = string_tables
+            || self
+                .symbol_tables
+                .iter()
+                .any(|st| is_changed(st.section_idx));
+        let report = RefreshReport {
+            full: false,
+            program_headers: !changed_segments.is_empty(),
+            string_tables,
+            symbol_tables,
+            parsed_sections: !changed_sections.is_empty()
+                && !self.options.section_handlers.is_empty(),
+            changed_sections,
+            changed_segments,
+        };
+        if report.is_unchanged() {
+            return Ok(report);
+        }
+
+        self.reparse(new_data, &report)?;
+
+        debug!(
+            "refreshed {} sections and {} segments",
+            report.changed_sections.len(),
+            report.changed_segments.len()
+        );
+
+        Ok(report)
+    }
+
+    /// Reads again the parts of the file `report` flags from `data`, the new
+    /// contents, only replacing the parsed ones once all of them were read
+    fn reparse(&mut self, data: &[u8], report: &RefreshReport) -> Result<(), ParseError> {
+        let options = self.options.clone();
+        let deadline = Deadline::none();
+        let section_headers = &self.section_headers;
+        // anomalies found while reading the contents of the sections and segments, the
+        // others being tied to the headers that didn't change
+        let mut warnings = vec![];
+
+        let program_headers = match report.program_headers {
+            true => Some(Elf64PHdr::parse(
+                data,
+                &self.ctx,
+                &self.headers,
+                &options,
+                &mut warnings,
+            )?),
+            false => None,
+        };
+        let string_tables = match report.string_tables && options.parse_string_tables {
+            true => Some(ElfParser::parse_string_tables(
+                data,
+                &self.headers,
+                section_headers,
+                &options,
+                &deadline,
+                &mut warnings,
+            )?),
+            false => None,
+        };
+        let header_string_table_idx = match &string_tables {
+            Some(tables) => tables
+                .iter()
+                .position(|st| st.sh_type == StringTableType::ShStrTab),
+            None => self.header_string_table_idx,
+        };
+        let header_string_table = header_string_table_idx.and_then(|idx| {
+            string_tables
+                .as_ref()
+                .unwrap_or(&self.string_tables)
+                .get(idx)
+        });
+        let symbol_tables = match report.symbol_tables && options.parse_symbols {
+            true => Some(ElfParser::parse_symbol_tables(
+                data,
+                &self.ctx,
+                section_headers,
+                &options,
+                header_string_table,
+                &deadline,
+            )?),
+            false => None,
+        };
+        let parsed_sections = match report.parsed_sections {
+            true => Some(ElfParser::run_section_handlers(
+                data,
+                &self.ctx,
+                &self.headers,
+                section_headers,
+                header_string_table,
+                &options.section_handlers,
+                &mut warnings,
+            )),
+            false => None,
+        };
+
+        self.replace_data(data.to_vec());
+        if let Some(program_headers) = program_headers {
+            self.program_headers = program_headers;
+        }
+        if let Some(string_tables) = string_tables {
+            self.string_tables = string_tables;
+            self.header_string_table_idx = header_string_table_idx;
+        }
+        if let Some(symbol_tables) = symbol_tables {
+            self.symbol_tables = symbol_tables;
+            self.symbol_index = OnceLock::new();
+            self.sorted_symbols = OnceLock::new();
+        }
+        if let Some(parsed_sections) = parsed_sections {
+            self.parsed_sections = parsed_sections;
+        }
+
+        self.warnings.retain(|w| match w {
+            ParseWarning::MalformedStringTable { .. }
+            | ParseWarning::HeaderStringTableWrongType { .. } => !report.string_tables,
+            ParseWarning::SectionHandlerFailed { .. } => !report.parsed_sections,
+            ParseWarning::SegmentOutOfBounds { .. }
+            | ParseWarning::UnknownSegmentType { .. }
+            | ParseWarning::SegmentFileSizeExceedsMemSize { .. } => !report.program_headers,
+            ParseWarning::TlsSymbolOutOfBlock { .. }
+            | ParseWarning::DynamicMismatch { .. }
+            | ParseWarning::DynamicUnmapped { .. } => false,
+            _ => true,
+        });
+        self.warnings.extend(warnings);
+        let tls_anomalies = self.tls_anomalies();
+        self.warnings.extend(tls_anomalies);
+        let dynamic_anomalies = self.verify_dynamic();
+        self.warnings.extend(dynamic_anomalies);
+        if options.strict && !self.warnings.is_empty() {
+            return Err(ParseError::StrictViolation(self.warnings.clone()));
+        }
+
+        self.stats = ParseStats::count(self, self.stats.phases);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use crate::elf::phdr::PType;
+use crate::elf::shdr::SHF_ALLOC;
+use crate::parser::ElfParser;
+
+/// Memory range occupied by an allocated section, as a linker script would describe it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    /// Index of the section header
+    pub section: usize,
+    pub name: String,
+    /// Virtual address of the first byte of the section
+    pub start: u64,
+    /// Virtual address one past the last byte of the section
+    pub end: u64,
+    /// Load address, where the section lies in the memory image before startup code
+    /// copies it to `start`, e.g. from ROM to RAM
+    pub lma: u64,
+    /// Index of the loadable segment holding the section, if any
+    pub segment: Option<usize>,
+    /// Whether the section contents come from the file, rather than being zeroed as
+    /// `SHT_NOBITS` sections are
+    pub initialized: bool,
+}
+
+impl ElfParser<'_> {
+    /// Lists the `SHF_ALLOC` sections sorted by virtual address. The load address of a
+    /// section is the physical address of its loadable segment plus the offset of the
+    /// section within the segment, and matches its virtual address for sections outside
+    /// of every loadable segment
+    pub fn region_map(&self) -> Vec<Region> {
+        let mut regions: Vec<Region> = self
+            .section_headers
+            .iter()
+            .enumerate()
+            .filter(|(_, sh)| sh.flags & SHF_ALLOC != 0)
+            .map(|(idx, sh)| {
+                let start = sh.addr.0;
+                let end = start.saturating_add(sh.size);
+                let segment = self.program_headers.iter().position(|ph| {
+                    ph.p_type == PType::PtLoad
+                        && start >= ph.vaddr.0
+                        && end <= ph.vaddr.0.saturating_add(ph.memsz)
+                });
+                let lma = match segment {
+                    Some(ph) => {
+                        let ph = &self.program_headers[ph];
+                        ph.paddr.0.wrapping_add(start - ph.vaddr.0)
+                    }
+                    None => start,
+                };
+
+                Region {
+                    section: idx,
+                    name: self.section_name(sh).unwrap_or_default(),
+                    start,
+                    end,
+                    lma,
+                    segment,
+                    initialized: sh.occupies_file_space(),
+                }
+            })
+            .collect();
+        regions.sort_by_key(|r| (r.start, r.section));
+
+        regions
+    }
+}
@@ -0,0 +1,132 @@
+use crate::bytes::Address;
+use crate::display::TableRow;
+use crate::elf::reloc::{type_name, Elf64Rela};
+use crate::elf::shdr::{SHT_DYNSYM, SHT_REL, SHT_RELA, SHT_SYMTAB};
+use crate::elf::sym::STT_SECTION;
+use crate::parser::{ElfParser, ParseError};
+use log::debug;
+use std::fmt;
+
+/// A relocation joined with the symbol it refers to
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamedRelocation {
+    pub rela: Elf64Rela,
+    /// Name of the relocation type, see `elf::reloc::type_name`
+    pub type_name: String,
+    /// Value and name of the symbol, None for relocations against symbol 0, which only
+    /// apply their addend. Section symbols are named after their section, and symbols
+    /// missing from the linked table are named `<corrupt>`
+    pub symbol: Option<(Address, String)>,
+}
+
+/// Relocations of a `SHT_RELA` or `SHT_REL` section
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelocationSection {
+    /// Index of the section header of the relocations
+    pub section_idx: usize,
+    pub name: String,
+    pub relocations: Vec<NamedRelocation>,
+}
+
+impl TableRow for NamedRelocation {
+    const HEADER: &'static str =
+        "Offset           Info             Type                 Sym.Value        Sym.Name + Addend";
+}
+
+impl fmt::Display for NamedRelocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rela = &self.rela;
+        write!(
+            f,
+            "{:016x} {:016x} {:<20} ",
+            rela.offset, rela.info, self.type_name
+        )?;
+
+        let sign = if rela.addend < 0 { '-' } else { '+' };
+        match &self.symbol {
+            Some((value, name)) => write!(
+                f,
+                "{:016x} {} {} {:x}",
+                value,
+                name,
+                sign,
+                rela.addend.unsigned_abs()
+            ),
+            None if rela.addend < 0 => write!(f, "{:16} -{:x}", "", rela.addend.unsigned_abs()),
+            None => write!(f, "{:16} {:x}", "", rela.addend),
+        }
+    }
+}
+
+impl ElfParser<'_> {
+    /// Name and value of the symbol `sym` of the symbol table read from the section at
+    /// `link`, the one the relocations link to if any. None for symbol 0
+    fn relocation_symbol(&self, link: Option<usize>, sym: u32) -> Option<(Address, String)> {
+        if sym == 0 {
+            return None;
+        }
+
+        let resolved = self
+            .symbol_tables
+            .iter()
+            .find(|table| Some(table.section_idx) == link)
+            .and_then(|table| {
+                let symbol = table.symbols.get(sym as usize)?;
+                let name = match symbol.sym_type() {
+                    STT_SECTION => self
+                        .section_headers
+                        .get(symbol.shndx as usize)
+                        .and_then(|sh| self.section_name(sh).ok()),
+                    _ => self.symbol_name(table, symbol).ok(),
+                };
+
+                Some((symbol.value, name?))
+            });
+
+        Some(resolved.unwrap_or((Address(0), "<corrupt>".to_string())))
+    }
+
+    /// Reads every `SHT_RELA` and `SHT_REL` section, joining each relocation with the
+    /// symbol it refers to in the table linked by the section, either `.symtab` or
+    /// `.dynsym`
+    pub fn relocations_named(&self) -> Result<Vec<RelocationSection>, ParseError> {
+        let mut sections = vec![];
+
+        for (idx, sh) in self.section_headers.iter().enumerate() {
+            if sh.s_type != SHT_RELA && sh.s_type != SHT_REL {
+                continue;
+            }
+
+            let in_section = |e: ParseError| e.in_section(idx, self.section_name(sh).ok());
+            let symtab = self
+                .resolve_link(idx, &[SHT_SYMTAB, SHT_DYNSYM])
+                .map_err(in_section)?;
+            let data = self.section_data(idx).map_err(in_section)?;
+            let table = Elf64Rela::parse_table(data, &self.ctx, sh.s_type == SHT_RELA)
+                .map_err(in_section)?;
+            let relocations = table
+                .into_iter()
+                .map(|rela| NamedRelocation {
+                    rela,
+                    type_name: type_name(self.headers.machine, rela.r_type),
+                    symbol: self.relocation_symbol(symtab, rela.sym),
+                })
+                .collect::<Vec<_>>();
+
+            debug!(
+                "joined {} relocations of section [{}] with the symbols of [{}]",
+                relocations.len(),
+                idx,
+                sh.link
+            );
+
+            sections.push(RelocationSection {
+                section_idx: idx,
+                name: self.section_name(sh).unwrap_or_default(),
+                relocations,
+            });
+        }
+
+        Ok(sections)
+    }
+}
@@ -0,0 +1,182 @@
+//! Parsing of files read through an `ElfSource`, fetching only what's asked for.
+//!
+//! `RemoteElf::parse` reads the ELF header, then the program header table and the
+//! section header table, and nothing else. Section contents and names are fetched
+//! the first time they're requested and cached, so a query about the headers of a
+//! large file costs a handful of small reads. The contents of the segments aren't
+//! decoded, they're `PTypeData::Skipped`.
+
+use crate::bytes::{str_from_u8, ParseContext};
+use crate::elf::ehdr::Elf64Hdr;
+use crate::elf::phdr::{Elf64PHdr, PTypeData, PN_XNUM};
+use crate::elf::shdr::Elf64SHdr;
+use crate::layout;
+use crate::options::ParseOptions;
+use crate::parser::{ElfParser, ParseError};
+use crate::source::ElfSource;
+use log::debug;
+use std::cell::RefCell;
+use std::ops::Range;
+
+/// Size of the largest ELF header, read before the class is known
+const HEADER_SIZE: u64 = 64;
+
+/// Headers of a file read through `source`, which is read again for the rest
+#[derive(Debug)]
+pub struct RemoteElf<S> {
+    pub headers: Elf64Hdr,
+    /// Encoding of the file, used to decode every multi-byte field
+    pub ctx: ParseContext,
+    /// Program headers, in table order. Their contents are `PTypeData::Skipped`
+    pub program_headers: Vec<Elf64PHdr>,
+    pub section_headers: Vec<Elf64SHdr>,
+    source: CachedSource<S>,
+}
+
+/// An `ElfSource` along with the ranges already read from it
+#[derive(Debug)]
+struct CachedSource<S> {
+    source: S,
+    options: ParseOptions,
+    /// Ranges already read from `source`, by offset
+    fetched: RefCell<Vec<(u64, Vec<u8>)>>,
+}
+
+impl<S: ElfSource> CachedSource<S> {
+    /// Reads the bytes in `range`, from the ranges already fetched when one holds them
+    fn fetch(&self, range: Range<u64>) -> Result<Vec<u8>, ParseError> {
+        let len = self.options.check_alloc(range.end - range.start)?;
+
+        let fetched = self.fetched.borrow();
+        let cached = fetched.iter().find(|(start, bytes)| {
+            range.start >= *start && range.end <= start + bytes.len() as u64
+        });
+        if let Some((start, bytes)) = cached {
+            let from = (range.start - start) as usize;
+            return Ok(bytes[from..from + len].to_vec());
+        }
+        drop(fetched);
+
+        let mut bytes = vec![0; len];
+        self.source.read_exact_at(range.start, &mut bytes)?;
+        self.fetched.borrow_mut().push((range.start, bytes.clone()));
+
+        Ok(bytes)
+    }
+}
+
+impl<S: ElfSource> RemoteElf<S> {
+    pub fn parse(source: S) -> Result<Self, ParseError> {
+        RemoteElf::parse_with(source, &ParseOptions::default())
+    }
+
+    /// Reads the header and both header tables from `source`, checked as
+    /// `ElfParser::parse_with` does with `options`
+    pub fn parse_with(source: S, options: &ParseOptions) -> Result<Self, ParseError> {
+        let source = CachedSource {
+            source,
+            options: options.clone(),
+            fetched: RefCell::new(vec![]),
+        };
+        let len = source.source.len();
+
+        let headers = Elf64Hdr::parse_with(&source.fetch(0..len.min(HEADER_SIZE))?, options)?;
+        let ctx = ParseContext::from(&headers.ident);
+
+        // larger counts are held by the initial section header, as `Elf64PHdr::count`
+        let count = match headers.ph_num {
+            PN_XNUM if headers.sh_off == 0 => return Err(ParseError::NoSectionHeaderTable),
+            PN_XNUM => {
+                let size = layout::shdr::for_class(ctx.class).size as u64;
+                let initial = source.fetch(headers.sh_off..headers.sh_off.saturating_add(size))?;
+                Elf64SHdr::parse_entry(&initial, &ctx)?.info as usize
+            }
+            ph_num => ph_num as usize,
+        };
+
+        let mut program_headers = vec![];
+        if let Some(table) = Elf64PHdr::table_range(&headers, count, options, len)? {
+            options.check_entries_alloc::<Elf64PHdr>(count)?;
+            program_headers = source
+                .fetch(table)?
+                .chunks_exact(headers.ph_ent_size as usize)
+                .enumerate()
+                .map(|(idx, ph)| {
+                    Elf64PHdr::parse_entry(ph, &ctx)
+                        .map(|ph| Elf64PHdr {
+                            section: PTypeData::Skipped,
+                            ..ph
+                        })
+                        .map_err(|e| e.in_segment(idx))
+                })
+                .collect::<Result<_, _>>()?;
+        }
+
+        let mut section_headers = vec![];
+        if let Some(table) = Elf64SHdr::table_range(&headers, options, len)? {
+            let ent_size = headers.sh_ent_size as usize;
+            options.check_entries_alloc::<Elf64SHdr>(headers.sh_num as usize)?;
+            section_headers = Elf64SHdr::parse_table(&source.fetch(table)?, ent_size, &ctx)?;
+        }
+
+        debug!(
+            "remote file of {} bytes, {} program headers and {} section headers",
+            len,
+            program_headers.len(),
+            section_headers.len()
+        );
+
+        Ok(RemoteElf {
+            headers,
+            ctx,
+            program_headers,
+            section_headers,
+            source,
+        })
+    }
+
+    /// Returns the source the file is read from
+    pub fn source(&self) -> &S {
+        &self.source.source
+    }
+
+    /// Returns the contents of the section at `idx`, fetching them on first use. Empty
+    /// for sections without contents in the file, such as `SHT_NOBITS` ones
+    pub fn section_data(&self, idx: usize) -> Result<Vec<u8>, ParseError> {
+        let sh = self
+            .section_headers
+            .get(idx)
+            .ok_or(ParseError::InvalidLength)?;
+
+        self.source
+            .fetch(sh.offset..sh.offset.saturating_add(sh.file_size()))
+            .map_err(|e| e.in_section(idx, None))
+    }
+
+    /// Reads the string at `offset` of the string table held by the section at `idx`
+    pub fn string_at(&self, idx: usize, offset: u32) -> Result<String, ParseError> {
+        let table = self.section_data(idx)?;
+
+        str_from_u8(
+            table
+                .get(offset as usize..)
+                .ok_or(ParseError::InvalidLength)?,
+        )
+    }
+
+    /// Resolves the name of a section through the section header string table
+    pub fn section_name(&self, section_header: &Elf64SHdr) -> Result<String, ParseError> {
+        let idx = ElfParser::header_string_table_index(&self.headers, &self.section_headers)
+            .ok_or(ParseError::NameUnavailable)?;
+
+        self.string_at(idx, section_header.name)
+    }
+
+    /// Returns the first section named `name`, along with its index
+    pub fn section_by_name(&self, name: &str) -> Option<(usize, &Elf64SHdr)> {
+        self.section_headers
+            .iter()
+            .enumerate()
+            .find(|(_, sh)| self.section_name(sh).is_ok_and(|n| n == name))
+    }
+}
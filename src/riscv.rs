@@ -0,0 +1,72 @@
+use crate::bytes::Address;
+use crate::elf::ehdr::EM_RISCV;
+use crate::parser::ElfParser;
+use log::debug;
+
+/// Symbols a bare-metal RISC-V loader needs to set up the hart before jumping to the
+/// entry point, each one None if the file doesn't define it
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct RiscvStartupSymbols {
+    /// Value `gp` is expected to hold, for the linker to relax accesses around it
+    pub global_pointer: Option<Address>,
+    /// Initial value of `sp`
+    pub stack_top: Option<Address>,
+    pub start: Option<Address>,
+    /// Bounds of the zero-initialized data the startup code clears
+    pub bss_start: Option<Address>,
+    pub bss_end: Option<Address>,
+}
+
+/// Names given to each symbol by the default linker scripts, newlib, picolibc and
+/// common hand-written scripts, by preference
+const GLOBAL_POINTER: &[&str] = &["__global_pointer$", "__global_pointer", "_gp"];
+const STACK_TOP: &[&str] = &[
+    "__stack_pointer",
+    "_stack_top",
+    "__stack_top",
+    "__stack",
+    "_estack",
+    "_sp",
+];
+const START: &[&str] = &["_start", "__start", "_reset", "reset_handler"];
+const BSS_START: &[&str] = &["__bss_start", "_bss_start", "__bss_start__", "_sbss"];
+// `_end` also covers the heap start, so it's only used when the bss end isn't marked
+const BSS_END: &[&str] = &[
+    "__bss_end",
+    "_bss_end",
+    "__bss_end__",
+    "__BSS_END__",
+    "_ebss",
+    "_end",
+];
+
+impl ElfParser<'_> {
+    /// Resolves the symbols needed to start a bare-metal RISC-V program from the
+    /// symbol tables, accepting the names used by the usual C libraries and linker
+    /// scripts. None if the file isn't for RISC-V
+    pub fn riscv_startup_symbols(&self) -> Option<RiscvStartupSymbols> {
+        if self.headers.machine != EM_RISCV {
+            return None;
+        }
+
+        let lookup = |aliases: &[&str]| {
+            aliases.iter().find_map(|alias| {
+                self.symbols_by_name(alias)
+                    .into_iter()
+                    .find(|(_, sym)| !sym.is_undefined())
+                    .map(|(_, sym)| sym.value)
+            })
+        };
+        let symbols = RiscvStartupSymbols {
+            global_pointer: lookup(GLOBAL_POINTER),
+            stack_top: lookup(STACK_TOP),
+            start: lookup(START),
+            bss_start: lookup(BSS_START),
+            bss_end: lookup(BSS_END),
+        };
+
+        debug!("RISC-V startup symbols: {:?}", symbols);
+
+        Some(symbols)
+    }
+}
@@ -0,0 +1,225 @@
+//! Library search paths held by `DT_RPATH` and `DT_RUNPATH`, split into their
+//! entries with the dynamic string tokens the loader expands recognized.
+//!
+//! Tokens are written either as `$NAME` or `${NAME}`: `$ORIGIN` is the directory of
+//! the file, `$LIB` the name of the system library directory, such as `lib64`, and
+//! `$PLATFORM` the name of the processor type. A `$` not starting one of these is
+//! kept as is, as the loader doesn't expand it either.
+
+use crate::elf::phdr::{DT_RPATH, DT_RUNPATH};
+use crate::parser::{ElfParser, ParseError};
+use std::fmt;
+
+/// Directories anyone can write to, searching which lets any user inject libraries
+const WORLD_WRITABLE_DIRS: &[&str] = &["/tmp", "/var/tmp", "/dev/shm"];
+
+/// Dynamic entry a search path was read from
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RpathTag {
+    /// `DT_RPATH`, searched before `LD_LIBRARY_PATH`
+    Rpath,
+    /// `DT_RUNPATH`, searched after `LD_LIBRARY_PATH`
+    Runpath,
+}
+
+/// Dynamic string token, substituted by the loader when searching a path
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RpathToken {
+    Origin,
+    Lib,
+    Platform,
+}
+
+/// Piece of a search path entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RpathComponent {
+    Literal(String),
+    Token(RpathToken),
+}
+
+/// Anomaly of a search path entry that may let libraries be loaded from places the
+/// file author didn't intend
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RpathIssue {
+    /// The entry is empty, which the loader takes as the current directory
+    Empty,
+    /// The entry doesn't start with `/` nor `$ORIGIN`, so it's resolved from the
+    /// current directory
+    Relative,
+    /// The entry is within the given directory, writable by any user
+    WorldWritable(&'static str),
+}
+
+/// An entry of `DT_RPATH` or `DT_RUNPATH`, one per `:` separated path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpathEntry {
+    pub tag: RpathTag,
+    /// The entry as written in the file
+    pub raw: String,
+    pub components: Vec<RpathComponent>,
+}
+
+/// Splits `raw` into literals and tokens
+fn tokenize(raw: &str) -> Vec<RpathComponent> {
+    const TOKENS: [(&str, RpathToken); 3] = [
+        ("ORIGIN", RpathToken::Origin),
+        ("LIB", RpathToken::Lib),
+        ("PLATFORM", RpathToken::Platform),
+    ];
+
+    let mut components = vec![];
+    let mut literal = String::new();
+    let mut rest = raw;
+
+    while let Some(pos) = rest.find('$') {
+        literal.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+
+        // `${NAME}`, or `$NAME` not followed by more of an identifier
+        let token = TOKENS.iter().find_map(|(name, token)| {
+            if let Some(tail) = after
+                .strip_prefix('{')
+                .and_then(|s| s.strip_prefix(name))
+                .and_then(|s| s.strip_prefix('}'))
+            {
+                return Some((*token, tail));
+            }
+            after
+                .strip_prefix(name)
+                .filter(|s| !s.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_'))
+                .map(|tail| (*token, tail))
+        });
+
+        match token {
+            Some((token, tail)) => {
+                if !literal.is_empty() {
+                    components.push(RpathComponent::Literal(std::mem::take(&mut literal)));
+                }
+                components.push(RpathComponent::Token(token));
+                rest = tail;
+            }
+            None => {
+                literal.push('$');
+                rest = after;
+            }
+        }
+    }
+
+    literal.push_str(rest);
+    if !literal.is_empty() {
+        components.push(RpathComponent::Literal(literal));
+    }
+
+    components
+}
+
+impl RpathEntry {
+    pub fn parse(tag: RpathTag, raw: &str) -> RpathEntry {
+        RpathEntry {
+            tag,
+            raw: raw.to_string(),
+            components: tokenize(raw),
+        }
+    }
+
+    /// Whether the entry holds the given token
+    pub fn has_token(&self, token: RpathToken) -> bool {
+        self.components.contains(&RpathComponent::Token(token))
+    }
+
+    /// The concrete path the loader searches, given the directory of the file and
+    /// the values of `$LIB` and `$PLATFORM`
+    pub fn expand(&self, origin_dir: &str, lib: &str, platform: &str) -> String {
+        self.components
+            .iter()
+            .map(|c| match c {
+                RpathComponent::Literal(s) => s.as_str(),
+                RpathComponent::Token(RpathToken::Origin) => origin_dir,
+                RpathComponent::Token(RpathToken::Lib) => lib,
+                RpathComponent::Token(RpathToken::Platform) => platform,
+            })
+            .collect()
+    }
+
+    /// Anomalies of the entry, see `RpathIssue`
+    pub fn issues(&self) -> Vec<RpathIssue> {
+        let Some(first) = self.components.first() else {
+            return vec![RpathIssue::Empty];
+        };
+
+        let mut issues = vec![];
+        match first {
+            RpathComponent::Token(RpathToken::Origin) => {}
+            RpathComponent::Literal(s) if s.starts_with('/') => {
+                let dir = WORLD_WRITABLE_DIRS.iter().find(|dir| {
+                    s.strip_prefix(**dir)
+                        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+                });
+                if let Some(dir) = dir {
+                    issues.push(RpathIssue::WorldWritable(dir));
+                }
+            }
+            _ => issues.push(RpathIssue::Relative),
+        }
+
+        issues
+    }
+}
+
+impl fmt::Display for RpathTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            RpathTag::Rpath => "RPATH",
+            RpathTag::Runpath => "RUNPATH",
+        })
+    }
+}
+
+impl fmt::Display for RpathIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RpathIssue::Empty => write!(f, "empty entry, searching the current directory"),
+            RpathIssue::Relative => {
+                write!(f, "relative entry, resolved from the current directory")
+            }
+            RpathIssue::WorldWritable(dir) => {
+                write!(f, "entry within the world-writable {}", dir)
+            }
+        }
+    }
+}
+
+impl ElfParser<'_> {
+    /// Entries of the `DT_RPATH` then `DT_RUNPATH` search paths, in the order they're
+    /// written. Files without dynamic segment have none
+    pub fn rpath_entries(&self) -> Result<Vec<RpathEntry>, ParseError> {
+        let mut entries = vec![];
+
+        for (d_tag, tag) in [(DT_RPATH, RpathTag::Rpath), (DT_RUNPATH, RpathTag::Runpath)] {
+            let paths = self
+                .dynamic_entries()
+                .unwrap_or_default()
+                .iter()
+                .filter(|d| d.d_tag == d_tag);
+            for path in paths {
+                let path = self.dynamic_string(path.value() as u32)?;
+                entries.extend(path.split(':').map(|raw| RpathEntry::parse(tag, raw)));
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// Every entry of the search paths having anomalies, along with them
+    pub fn rpath_issues(&self) -> Result<Vec<(RpathEntry, Vec<RpathIssue>)>, ParseError> {
+        Ok(self
+            .rpath_entries()?
+            .into_iter()
+            .map(|entry| {
+                let issues = entry.issues();
+                (entry, issues)
+            })
+            .filter(|(_, issues)| !issues.is_empty())
+            .collect())
+    }
+}
@@ -0,0 +1,310 @@
+use crate::elf::shdr::{
+    flags_name, type_name, Elf64SHdr, SType, StringTableType, SHF_ALLOC, SHF_EXECINSTR, SHF_TLS,
+    SHF_WRITE, SHT_DYNAMIC, SHT_DYNSYM, SHT_FINI_ARRAY, SHT_GNU_HASH, SHT_GNU_VERDEF,
+    SHT_GNU_VERNEED, SHT_GNU_VERSYM, SHT_HASH, SHT_INIT_ARRAY, SHT_NOBITS, SHT_NULL,
+    SHT_PREINIT_ARRAY, SHT_PROGBITS, SHT_RISCV_ATTRIBUTES, SHT_STRTAB, SHT_SYMTAB,
+};
+use crate::parser::{ElfParser, ParseError};
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum GlobToken {
+    /// `*`, any run of characters
+    Any,
+    /// `?`, a single character
+    One,
+    Char(char),
+}
+
+/// Matches `name` against a glob `pattern`, where `*` matches any run of characters,
+/// `?` matches a single one and `\` makes the next character literal. An empty pattern
+/// only matches the empty name
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let mut tokens = vec![];
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        tokens.push(match c {
+            '\\' => GlobToken::Char(chars.next().unwrap_or('\\')),
+            '*' => GlobToken::Any,
+            '?' => GlobToken::One,
+            c => GlobToken::Char(c),
+        });
+    }
+    let name: Vec<char> = name.chars().collect();
+
+    // Token following the last `*` along with the name position it was last tried at,
+    // to backtrack to when the tokens after it stop matching
+    let mut star: Option<(usize, usize)> = None;
+    let (mut t, mut n) = (0, 0);
+
+    while n < name.len() {
+        match tokens.get(t) {
+            Some(GlobToken::Any) => {
+                star = Some((t + 1, n));
+                t += 1;
+            }
+            Some(GlobToken::One) => {
+                t += 1;
+                n += 1;
+            }
+            Some(GlobToken::Char(c)) if *c == name[n] => {
+                t += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((star_t, star_n)) => {
+                    star = Some((star_t, star_n + 1));
+                    t = star_t;
+                    n = star_n + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    tokens[t..].iter().all(|t| *t == GlobToken::Any)
+}
+
+/// Name of a section, telling apart the ones read from the section header string table
+/// from the ones made up when it's damaged
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SectionName {
+    Exact(String),
+    /// Canonical name of the kind of section, such as `.bss`, or a label describing it
+    /// such as `progbits@0x10000(AX)`
+    Guessed(String),
+    /// The name can't be resolved and the section describes nothing to guess it from
+    Unknown,
+}
+
+impl SectionName {
+    pub fn is_exact(&self) -> bool {
+        matches!(self, SectionName::Exact(_))
+    }
+}
+
+/// Guessed names are rendered in brackets, so they can't be mistaken for exact ones
+impl fmt::Display for SectionName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SectionName::Exact(name) => write!(f, "{}", name),
+            SectionName::Guessed(name) => write!(f, "[{}]", name),
+            SectionName::Unknown => write!(f, "<unknown>"),
+        }
+    }
+}
+
+/// A section header bundled with its index and resolved name
+#[derive(Debug, Clone)]
+pub struct Section<'a> {
+    parser: &'a ElfParser<'a>,
+    index: usize,
+    name: String,
+    header: &'a Elf64SHdr,
+}
+
+impl<'a> Section<'a> {
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Name of the section, empty if it can't be resolved
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn header(&self) -> &'a Elf64SHdr {
+        self.header
+    }
+
+    pub fn sh_type(&self) -> SType {
+        self.header.sh_type()
+    }
+
+    /// Name of the section, guessed when it can't be resolved, see
+    /// `ElfParser::section_name_or_guess`
+    pub fn name_or_guess(&self) -> SectionName {
+        self.parser.section_name_or_guess(self.index)
+    }
+
+    /// Reads the file image of the section, see `ElfParser::section_data`
+    pub fn data(&self) -> Result<&'a [u8], ParseError> {
+        self.parser.section_data(self.index)
+    }
+}
+
+/// Iterator over the sections of a file, which can be narrowed down with the filtering
+/// helpers
+pub struct Sections<'a> {
+    iter: Box<dyn Iterator<Item = Section<'a>> + 'a>,
+}
+
+impl<'a> Sections<'a> {
+    /// Keeps the sections with every bit of `flag` set
+    pub fn with_flag(self, flag: u64) -> Self {
+        Sections {
+            iter: Box::new(self.iter.filter(move |s| s.header.flags & flag == flag)),
+        }
+    }
+
+    /// Leaves out the initial entry of the section header table, which describes no
+    /// section
+    pub fn without_null(self) -> Self {
+        Sections {
+            iter: Box::new(self.iter.filter(|s| s.index != 0)),
+        }
+    }
+
+    pub fn by_type(self, sh_type: SType) -> Self {
+        Sections {
+            iter: Box::new(self.iter.filter(move |s| s.sh_type() == sh_type)),
+        }
+    }
+
+    pub fn named(self, name: &'a str) -> Self {
+        Sections {
+            iter: Box::new(self.iter.filter(move |s| s.name == name)),
+        }
+    }
+
+    pub fn named_ignore_case(self, name: &'a str) -> Self {
+        Sections {
+            iter: Box::new(self.iter.filter(move |s| s.name.eq_ignore_ascii_case(name))),
+        }
+    }
+
+    /// Keeps the sections whose name starts with `prefix`, such as `.text` for
+    /// `.text.hot` and `.text.unlikely`
+    pub fn with_prefix(self, prefix: &'a str) -> Self {
+        Sections {
+            iter: Box::new(self.iter.filter(move |s| s.name.starts_with(prefix))),
+        }
+    }
+
+    /// Keeps the sections whose name matches the glob `pattern`, see `glob_match`
+    pub fn matching(self, pattern: &'a str) -> Self {
+        Sections {
+            iter: Box::new(self.iter.filter(move |s| glob_match(pattern, &s.name))),
+        }
+    }
+}
+
+impl<'a> Iterator for Sections<'a> {
+    type Item = Section<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+}
+
+impl ElfParser<'_> {
+    /// Iterates over every section, resolving their names eagerly
+    pub fn sections(&self) -> Sections<'_> {
+        let parser: &ElfParser = self;
+
+        Sections {
+            iter: Box::new(parser.section_headers.iter().enumerate().map(
+                move |(index, header)| Section {
+                    parser,
+                    index,
+                    name: parser.section_name(header).unwrap_or_default(),
+                    header,
+                },
+            )),
+        }
+    }
+
+    /// Name of the section resolved from the section header string table, if it looks
+    /// like one: printable and only empty for null sections
+    fn plausible_name(&self, sh: &Elf64SHdr) -> Option<String> {
+        let name = self.section_name(sh).ok()?;
+        let printable = name.chars().all(|c| c.is_ascii_graphic());
+
+        (printable && (!name.is_empty() || sh.s_type == SHT_NULL)).then_some(name)
+    }
+
+    /// Name the linkers give to sections of the type and flags of the one at `idx`
+    fn canonical_name(&self, idx: usize, sh: &Elf64SHdr) -> Option<&'static str> {
+        let alloc_write = SHF_ALLOC | SHF_WRITE;
+
+        Some(match sh.s_type {
+            SHT_PROGBITS if sh.flags & SHF_EXECINSTR != 0 => ".text",
+            SHT_NOBITS if sh.flags & alloc_write == alloc_write => match sh.flags & SHF_TLS {
+                0 => ".bss",
+                _ => ".tbss",
+            },
+            SHT_STRTAB => {
+                match ElfParser::string_table_type(idx, &self.headers, &self.section_headers) {
+                    StringTableType::ShStrTab => ".shstrtab",
+                    StringTableType::DynStr => ".dynstr",
+                    StringTableType::StrTab => ".strtab",
+                }
+            }
+            SHT_SYMTAB => ".symtab",
+            SHT_DYNSYM => ".dynsym",
+            SHT_DYNAMIC => ".dynamic",
+            SHT_HASH => ".hash",
+            SHT_GNU_HASH => ".gnu.hash",
+            SHT_GNU_VERSYM => ".gnu.version",
+            SHT_GNU_VERNEED => ".gnu.version_r",
+            SHT_GNU_VERDEF => ".gnu.version_d",
+            SHT_INIT_ARRAY => ".init_array",
+            SHT_FINI_ARRAY => ".fini_array",
+            SHT_PREINIT_ARRAY => ".preinit_array",
+            SHT_RISCV_ATTRIBUTES => ".riscv.attributes",
+            _ => return None,
+        })
+    }
+
+    /// Name of the section at `idx`, guessed from its header when it can't be resolved
+    /// or doesn't look like a name, as happens with a damaged section header string
+    /// table. A section gets the canonical name of its kind, such as `.text` for the
+    /// first executable `SHT_PROGBITS` section, unless an earlier section could take
+    /// it or another one already has it. Otherwise it gets a label made of its type,
+    /// address and flags, such as `progbits@0x10000(AX)`
+    pub fn section_name_or_guess(&self, idx: usize) -> SectionName {
+        let Some(sh) = self.section_headers.get(idx) else {
+            return SectionName::Unknown;
+        };
+        if let Some(name) = self.plausible_name(sh) {
+            return SectionName::Exact(name);
+        }
+        if sh.s_type == SHT_NULL {
+            return SectionName::Unknown;
+        }
+
+        let canonical = self.canonical_name(idx, sh).filter(|&name| {
+            let taken_before = self.section_headers[..idx]
+                .iter()
+                .enumerate()
+                .any(|(i, other)| self.canonical_name(i, other) == Some(name));
+            let taken = self
+                .section_headers
+                .iter()
+                .any(|other| self.plausible_name(other).is_some_and(|n| n == name));
+
+            !taken_before && !taken
+        });
+        if let Some(name) = canonical {
+            return SectionName::Guessed(name.to_string());
+        }
+
+        let mut label = format!("{}@{:#x}", type_name(sh.s_type).to_lowercase(), sh.addr.0);
+        let flags = flags_name(sh.flags);
+        if !flags.is_empty() {
+            label.push_str(&format!("({})", flags));
+        }
+
+        SectionName::Guessed(label)
+    }
+
+    /// Returns the index and header of every section whose name matches the glob
+    /// `pattern`, see `glob_match`
+    pub fn sections_matching(&self, pattern: &str) -> Vec<(usize, &Elf64SHdr)> {
+        self.section_headers
+            .iter()
+            .enumerate()
+            .filter(|(_, sh)| glob_match(pattern, &self.section_name(sh).unwrap_or_default()))
+            .collect()
+    }
+}
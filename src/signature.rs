@@ -0,0 +1,89 @@
+//! Signatures embedded into a section of the file, made over its allocated sections.
+//!
+//! The signed message is the concatenation of the file bytes of every `SHF_ALLOC`
+//! section, in section header table order, with nothing between them nor around them:
+//!
+//! - sections without file bytes, as `SHT_NOBITS` ones, add nothing
+//! - non-allocated sections, the section headers and the ELF header aren't part of it,
+//!   so adding the signature, or any other non-allocated section, leaves it unchanged
+//! - the section holding the signature is left out even when it's allocated
+//!
+//! No signature algorithm is implemented here: the caller verifies the message and
+//! the signature read from the file, and produces the signature `ElfEditor::sign`
+//! embeds.
+
+use crate::elf::shdr::{Elf64SHdr, SHF_ALLOC};
+use crate::parser::{ElfParser, ParseError};
+use log::debug;
+
+/// Outcome of `ElfParser::verify_embedded_signature`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SignatureStatus {
+    /// The verifier accepted the signature over the allocated sections
+    Valid,
+    /// The verifier rejected the signature, the file or the signature were altered
+    Invalid,
+    /// No section holds a signature under the requested name
+    Unsigned,
+}
+
+/// Builds the message signed over the sections described by `section_headers`, whose
+/// contents are read from `data`, leaving out the section at `signature_idx`. See the
+/// module documentation for its layout
+pub fn signed_message(
+    section_headers: &[Elf64SHdr],
+    data: &[u8],
+    signature_idx: Option<usize>,
+) -> Result<Vec<u8>, ParseError> {
+    let mut message = vec![];
+
+    for (idx, sh) in section_headers.iter().enumerate() {
+        if sh.flags & SHF_ALLOC == 0 || Some(idx) == signature_idx {
+            continue;
+        }
+
+        let contents = sh
+            .offset
+            .checked_add(sh.file_size())
+            .and_then(|end| data.get(sh.offset as usize..end as usize))
+            .ok_or_else(|| ParseError::InvalidLength.in_section(idx, None))?;
+        message.extend_from_slice(contents);
+    }
+
+    Ok(message)
+}
+
+impl ElfParser<'_> {
+    /// The message signed by the signature held in the section at `signature_idx`,
+    /// see `signed_message`
+    pub fn signed_message(&self, signature_idx: Option<usize>) -> Result<Vec<u8>, ParseError> {
+        signed_message(&self.section_headers, self.data(), signature_idx)
+    }
+
+    /// Checks the signature held by the section named `section` against the allocated
+    /// sections of the file. `verifier` is handed the signed message and the contents
+    /// of the section, and tells whether the signature is valid
+    pub fn verify_embedded_signature(
+        &self,
+        section: &str,
+        verifier: impl Fn(&[u8], &[u8]) -> bool,
+    ) -> Result<SignatureStatus, ParseError> {
+        let Some((idx, _)) = self.section_by_name(section) else {
+            return Ok(SignatureStatus::Unsigned);
+        };
+        let signature = self.section_data(idx)?;
+        let message = self.signed_message(Some(idx))?;
+
+        debug!(
+            "verifying {} bytes of signature in section [{}] over {} bytes",
+            signature.len(),
+            idx,
+            message.len()
+        );
+
+        match verifier(&message, signature) {
+            true => Ok(SignatureStatus::Valid),
+            false => Ok(SignatureStatus::Invalid),
+        }
+    }
+}
@@ -0,0 +1,328 @@
+use crate::debug_info::DebugInfoSummary;
+use crate::elf::ehdr::ElfHType;
+use crate::elf::phdr::PType;
+use crate::elf::shdr::{SHF_ALLOC, SHT_SYMTAB};
+use crate::elf::sym::{STT_FUNC, STT_OBJECT};
+use crate::parser::ElfParser;
+use std::cmp::Reverse;
+use std::ops::Range;
+
+/// A named byte range of the file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Extent {
+    pub name: String,
+    pub range: Range<u64>,
+}
+
+impl Extent {
+    pub fn size(&self) -> u64 {
+        self.range.end - self.range.start
+    }
+}
+
+/// Accounting of which structures reference each byte of the file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SizeBreakdown {
+    /// Size of the whole file
+    pub file_size: u64,
+    /// Bytes covered by the ELF header
+    pub header: Extent,
+    /// Bytes covered by the program header table, if there's one
+    pub program_header_table: Option<Extent>,
+    /// Bytes covered by the section header table, if there's one
+    pub section_header_table: Option<Extent>,
+    /// File extent of every section that occupies file space, sorted by offset then
+    /// by section index
+    pub sections: Vec<Extent>,
+    /// File extent of every segment with a file image, sorted by offset then by
+    /// segment index
+    pub segments: Vec<Extent>,
+    /// Number of bytes referenced by at least one of the above, overlaps counted once
+    pub covered: u64,
+    /// Ranges of bytes not referenced by anything, sorted by offset
+    pub residual: Vec<Range<u64>>,
+}
+
+impl SizeBreakdown {
+    /// Number of bytes not referenced by anything
+    pub fn residual_size(&self) -> u64 {
+        self.residual.iter().map(|r| r.end - r.start).sum()
+    }
+}
+
+/// A function or object, sized by the bytes of its section attributed to it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolSize {
+    pub name: String,
+    pub address: u64,
+    /// Bytes attributed to the symbol, which leave out the ones of an earlier
+    /// overlapping symbol and those past the end of the section, so it can be below
+    /// `st_size`
+    pub size: u64,
+    pub sym_type: u8,
+}
+
+/// The functions and objects of an allocated section, largest first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionSizes {
+    pub section: usize,
+    pub name: String,
+    pub size: u64,
+    pub symbols: Vec<SymbolSize>,
+}
+
+impl SectionSizes {
+    /// Bytes attributed to the symbols of the section
+    pub fn symbols_size(&self) -> u64 {
+        self.symbols.iter().map(|s| s.size).sum()
+    }
+
+    /// Bytes of the section no symbol accounts for, such as padding and anonymous data
+    pub fn residual(&self) -> u64 {
+        self.size - self.symbols_size()
+    }
+}
+
+/// Sizes of the functions and objects of the file, grouped by section, largest first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SizeReport {
+    pub sections: Vec<SectionSizes>,
+    /// Debug sections of the file, which aren't allocated and so hold no symbol
+    pub debug_info: DebugInfoSummary,
+}
+
+impl SizeReport {
+    /// The `n` largest symbols of the file, along with their section
+    pub fn largest(&self, n: usize) -> Vec<(&SectionSizes, &SymbolSize)> {
+        let mut symbols: Vec<_> = self
+            .sections
+            .iter()
+            .flat_map(|section| section.symbols.iter().map(move |sym| (section, sym)))
+            .collect();
+        symbols.sort_by_key(|(_, sym)| (Reverse(sym.size), sym.address));
+        symbols.truncate(n);
+
+        symbols
+    }
+}
+
+/// Sorts `ranges` and merges the overlapping and adjacent ones, dropping empty ranges
+pub fn merge_ranges(mut ranges: Vec<Range<u64>>) -> Vec<Range<u64>> {
+    ranges.retain(|r| r.start < r.end);
+    ranges.sort_by_key(|r| (r.start, r.end));
+
+    let mut merged: Vec<Range<u64>> = vec![];
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+
+    merged
+}
+
+/// Returns the ranges of `0..total` not covered by `merged`, which must be sorted and
+/// non-overlapping, as returned by `merge_ranges`
+pub fn uncovered_ranges(merged: &[Range<u64>], total: u64) -> Vec<Range<u64>> {
+    let mut gaps = vec![];
+    let mut cursor = 0;
+
+    for range in merged {
+        if range.start > cursor {
+            gaps.push(cursor..range.start.min(total));
+        }
+        cursor = cursor.max(range.end);
+    }
+
+    if cursor < total {
+        gaps.push(cursor..total);
+    }
+
+    gaps.retain(|r| r.start < r.end);
+    gaps
+}
+
+impl ElfParser<'_> {
+    /// Clamps `off..off + size` to the file, so that bogus headers can't inflate the
+    /// accounting past the end of the file
+    fn file_extent(&self, name: String, off: u64, size: u64) -> Extent {
+        let file_size = self.data().len() as u64;
+        let start = off.min(file_size);
+        let end = off.saturating_add(size).min(file_size);
+
+        Extent {
+            name,
+            range: start..end,
+        }
+    }
+
+    /// Groups the defined functions and objects by the allocated section holding them,
+    /// from the static symbol table or the dynamic one if the file was stripped.
+    /// Each byte of a section is attributed to a single symbol: overlapping symbols,
+    /// such as aliases, only get the bytes not taken by an earlier one, the one
+    /// starting first and then the largest, and symbols left without bytes are dropped
+    pub fn size_report(&self) -> SizeReport {
+        let has_symtab = self.symbol_tables.iter().any(|t| t.sh_type == SHT_SYMTAB);
+        // symbols of relocatable objects hold offsets into their section
+        let relative = self.headers.e_type == ElfHType::Relocatable;
+
+        let mut sections: Vec<SectionSizes> = self
+            .section_headers
+            .iter()
+            .enumerate()
+            .filter(|(_, sh)| sh.flags & SHF_ALLOC != 0 && sh.size > 0)
+            .map(|(idx, sh)| SectionSizes {
+                section: idx,
+                name: self
+                    .section_name(sh)
+                    .unwrap_or_else(|_| format!("section [{}]", idx)),
+                size: sh.size,
+                symbols: vec![],
+            })
+            .collect();
+
+        // candidate range of every symbol, keyed by the position of its section
+        let mut candidates: Vec<Vec<(Range<u64>, String, u8)>> = vec![vec![]; sections.len()];
+        let tables = self
+            .symbol_tables
+            .iter()
+            .filter(|t| !has_symtab || t.sh_type == SHT_SYMTAB);
+        for table in tables {
+            for sym in &table.symbols {
+                if ![STT_FUNC, STT_OBJECT].contains(&sym.sym_type()) || sym.size == 0 {
+                    continue;
+                }
+                let Some(pos) = sections
+                    .iter()
+                    .position(|s| s.section == sym.shndx as usize)
+                else {
+                    continue;
+                };
+
+                let sh = &self.section_headers[sections[pos].section];
+                let start = if relative {
+                    sh.addr.0.saturating_add(sym.value.0)
+                } else {
+                    sym.value.0
+                };
+                let end = start
+                    .saturating_add(sym.size)
+                    .min(sh.addr.0.saturating_add(sh.size));
+                let start = start.max(sh.addr.0);
+                if start < end {
+                    let name = self.symbol_name(table, sym).unwrap_or_default();
+                    candidates[pos].push((start..end, name, sym.sym_type()));
+                }
+            }
+        }
+
+        for (section, mut symbols) in sections.iter_mut().zip(candidates) {
+            symbols.sort_by(|(a, a_name, _), (b, b_name, _)| {
+                (a.start, Reverse(a.end), a_name).cmp(&(b.start, Reverse(b.end), b_name))
+            });
+
+            let mut covered = 0;
+            for (range, name, sym_type) in symbols {
+                let start = range.start.max(covered);
+                if start < range.end {
+                    section.symbols.push(SymbolSize {
+                        name,
+                        address: range.start,
+                        size: range.end - start,
+                        sym_type,
+                    });
+                }
+                covered = covered.max(range.end);
+            }
+            section
+                .symbols
+                .sort_by_key(|s| (Reverse(s.size), s.address));
+        }
+        sections.sort_by_key(|s| (Reverse(s.size), s.section));
+
+        SizeReport {
+            sections,
+            debug_info: self.debug_info_summary(),
+        }
+    }
+
+    /// Breaks down the file into the byte ranges covered by the ELF header, the header
+    /// tables, the sections and the segments, and the residual slack not referenced by
+    /// any of them
+    pub fn size_breakdown(&self) -> SizeBreakdown {
+        let file_size = self.data().len() as u64;
+        let headers = &self.headers;
+
+        let header = self.file_extent("ELF header".to_string(), 0, headers.eh_size as u64);
+
+        // tables that don't fit in the file weren't read, so they cover nothing
+        let program_header_table = headers
+            .phdr_table_range_of(self.program_headers.len(), file_size)
+            .ok()
+            .flatten()
+            .map(|range| Extent {
+                name: "program header table".to_string(),
+                range,
+            });
+
+        let section_header_table = headers
+            .shdr_table_range(file_size)
+            .ok()
+            .flatten()
+            .filter(|_| !self.section_headers.is_empty())
+            .map(|range| Extent {
+                name: "section header table".to_string(),
+                range,
+            });
+
+        let mut sections: Vec<Extent> = self
+            .section_headers
+            .iter()
+            .enumerate()
+            .filter(|(_, sh)| sh.file_size() != 0)
+            .map(|(idx, sh)| {
+                let name = self
+                    .section_name(sh)
+                    .unwrap_or_else(|_| format!("section [{}]", idx));
+                self.file_extent(name, sh.offset, sh.file_size())
+            })
+            .collect();
+
+        let mut segments: Vec<Extent> = self
+            .program_headers
+            .iter()
+            .enumerate()
+            .filter(|(_, ph)| ph.filesz != 0 && ph.p_type != PType::PtNull)
+            .map(|(idx, ph)| {
+                self.file_extent(format!("{}[{}]", ph.p_type, idx), ph.offset, ph.filesz)
+            })
+            .collect();
+        // stable, so extents at the same offset keep their table order
+        sections.sort_by_key(|e| e.range.start);
+        segments.sort_by_key(|e| e.range.start);
+
+        let ranges = std::iter::once(&header)
+            .chain(program_header_table.iter())
+            .chain(section_header_table.iter())
+            .chain(sections.iter())
+            .chain(segments.iter())
+            .map(|e| e.range.clone())
+            .collect();
+
+        let merged = merge_ranges(ranges);
+        let covered = merged.iter().map(|r| r.end - r.start).sum();
+        let residual = uncovered_ranges(&merged, file_size);
+
+        SizeBreakdown {
+            file_size,
+            header,
+            program_header_table,
+            section_header_table,
+            sections,
+            segments,
+            covered,
+            residual,
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! Symbols in address order, for walking the functions of a file or finding every
+//! symbol within a range of addresses.
+//!
+//! The view holds positions into `ElfParser::symbol_tables` sorted by symbol value.
+//! The default view is built on its first use and kept by the parser, views with other
+//! options are built on demand through `ElfParser::sort_symbols`.
+
+use crate::elf::shdr::SHT_SYMTAB;
+use crate::elf::sym::{STT_FUNC, STT_OBJECT};
+use crate::parser::ElfParser;
+use crate::symbol_index::SymbolRef;
+use log::debug;
+
+/// Which symbols `ElfParser::sort_symbols` keeps. Undefined symbols and those valued 0
+/// are always left out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortOptions {
+    /// Symbol types kept, see the `STT_*` constants
+    pub types: Vec<u8>,
+    /// Interleave the symbols of every symbol table, instead of only reading `.symtab`
+    /// or, for stripped files, `.dynsym`. Symbols present in both tables are then
+    /// listed twice
+    pub merge_tables: bool,
+}
+
+impl Default for SortOptions {
+    fn default() -> Self {
+        SortOptions {
+            types: vec![STT_FUNC, STT_OBJECT],
+            merge_tables: false,
+        }
+    }
+}
+
+impl SortOptions {
+    pub fn new() -> Self {
+        SortOptions::default()
+    }
+
+    pub fn types(mut self, types: &[u8]) -> Self {
+        self.types = types.to_vec();
+        self
+    }
+
+    pub fn merge_tables(mut self, merge_tables: bool) -> Self {
+        self.merge_tables = merge_tables;
+        self
+    }
+}
+
+/// Symbols sorted by address. Symbols sharing an address, as aliases do, keep the
+/// order of the symbol tables and of the symbols within them
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SortedSymbols {
+    symbols: Vec<SymbolRef>,
+    /// Value of each symbol of `symbols`
+    addresses: Vec<u64>,
+}
+
+impl SortedSymbols {
+    pub fn symbols(&self) -> &[SymbolRef] {
+        &self.symbols
+    }
+
+    /// Symbols whose value is within `start..end`
+    pub fn in_range(&self, start: u64, end: u64) -> &[SymbolRef] {
+        let from = self.addresses.partition_point(|addr| *addr < start);
+        let to = self.addresses.partition_point(|addr| *addr < end);
+
+        &self.symbols[from..to.max(from)]
+    }
+
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+}
+
+impl ElfParser<'_> {
+    /// Sorts the symbols kept by `options` by address
+    pub fn sort_symbols(&self, options: &SortOptions) -> SortedSymbols {
+        let has_symtab = self.symbol_tables.iter().any(|t| t.sh_type == SHT_SYMTAB);
+
+        let mut symbols = self
+            .symbol_tables
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| options.merge_tables || !has_symtab || t.sh_type == SHT_SYMTAB)
+            .flat_map(|(t, table)| {
+                table.symbols.iter().enumerate().map(move |(i, symbol)| {
+                    (symbol.value.0, SymbolRef { table: t, index: i }, symbol)
+                })
+            })
+            .filter(|(value, _, symbol)| {
+                *value != 0 && !symbol.is_undefined() && options.types.contains(&symbol.sym_type())
+            })
+            .map(|(value, r, _)| (value, r))
+            .collect::<Vec<_>>();
+        // stable, so aliases keep the order of the tables
+        symbols.sort_by_key(|(value, _)| *value);
+
+        debug!("sorted {} symbols by address", symbols.len());
+
+        let (addresses, symbols) = symbols.into_iter().unzip();
+        SortedSymbols { symbols, addresses }
+    }
+
+    /// The functions and objects of `.symtab`, or of `.dynsym` for stripped files,
+    /// sorted by address. Built on the first call
+    pub fn symbols_sorted(&self) -> &[SymbolRef] {
+        self.sorted_symbols().symbols()
+    }
+
+    /// The symbols of `symbols_sorted` whose value is within `start..end`
+    pub fn symbols_in_range(&self, start: u64, end: u64) -> &[SymbolRef] {
+        self.sorted_symbols().in_range(start, end)
+    }
+
+    /// The function or object of `symbols_sorted` covering `addr`, along with the
+    /// offset of `addr` into it. A symbol without a size only covers its own address.
+    /// Of overlapping symbols, the one starting closest to `addr` is returned
+    pub fn symbolize(&self, addr: u64) -> Option<(SymbolRef, u64)> {
+        self.symbols_in_range(0, addr.saturating_add(1))
+            .iter()
+            .rev()
+            .find_map(|r| {
+                let symbol = self.symbol(*r)?;
+                let offset = addr - symbol.value.0;
+                (offset < symbol.size.max(1)).then_some((*r, offset))
+            })
+    }
+
+    fn sorted_symbols(&self) -> &SortedSymbols {
+        self.sorted_symbols
+            .get_or_init(|| self.sort_symbols(&SortOptions::default()))
+    }
+}
@@ -0,0 +1,114 @@
+//! Sources of file bytes read by offset, for files that aren't held in memory.
+//!
+//! An `ElfSource` only has to answer reads of a range of bytes, so it can be backed by
+//! a local file, a buffer, or ranged requests against remote storage. `RemoteElf`
+//! parses a file through one, reading only the parts it's asked about.
+
+use std::fmt;
+use std::fs::File;
+use std::io;
+
+/// Failure of an `ElfSource` to provide the requested bytes
+#[derive(Debug)]
+pub enum SourceError {
+    /// The underlying reader failed
+    Io(io::Error),
+    /// The source ends before the `len` bytes at `offset`
+    Truncated { offset: u64, len: u64 },
+}
+
+/// Bytes of a file, read by offset
+pub trait ElfSource {
+    /// Reads up to `buf.len()` bytes at `offset` into `buf`, returning how many were
+    /// read. Reads at or past the end of the source return 0
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<usize, SourceError>;
+
+    /// Size of the source in bytes
+    fn len(&self) -> u64;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Fills `buf` with the bytes at `offset`, failing with `SourceError::Truncated`
+    /// when the source ends first
+    fn read_exact_at(&self, offset: u64, buf: &mut [u8]) -> Result<(), SourceError> {
+        let len = buf.len() as u64;
+        let mut read = 0;
+
+        while read < buf.len() {
+            match self.read_at(offset + read as u64, &mut buf[read..])? {
+                0 => return Err(SourceError::Truncated { offset, len }),
+                n => read += n,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ElfSource for [u8] {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<usize, SourceError> {
+        let Some(bytes) = usize::try_from(offset).ok().and_then(|off| self.get(off..)) else {
+            return Ok(0);
+        };
+        let n = bytes.len().min(buf.len());
+        buf[..n].copy_from_slice(&bytes[..n]);
+
+        Ok(n)
+    }
+
+    fn len(&self) -> u64 {
+        <[u8]>::len(self) as u64
+    }
+}
+
+#[cfg(any(unix, windows))]
+impl ElfSource for File {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<usize, SourceError> {
+        #[cfg(unix)]
+        let read = std::os::unix::fs::FileExt::read_at(self, buf, offset);
+        #[cfg(windows)]
+        let read = std::os::windows::fs::FileExt::seek_read(self, buf, offset);
+
+        read.map_err(SourceError::Io)
+    }
+
+    /// Size of the file as given by its metadata, 0 when it can't be read
+    fn len(&self) -> u64 {
+        self.metadata().map(|m| m.len()).unwrap_or(0)
+    }
+}
+
+impl<S: ElfSource + ?Sized> ElfSource for &S {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<usize, SourceError> {
+        (**self).read_at(offset, buf)
+    }
+
+    fn len(&self) -> u64 {
+        (**self).len()
+    }
+}
+
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceError::Io(e) => write!(f, "{}", e),
+            SourceError::Truncated { offset, len } => write!(
+                f,
+                "source ends before {:#x}..{:#x}",
+                offset,
+                offset.saturating_add(*len)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SourceError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SourceError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
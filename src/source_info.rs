@@ -0,0 +1,144 @@
+//! Where a parsed file was read from, recorded by `ElfParser::open` so results can be
+//! traced back to their file, and checked again by `reopen_check` to tell whether the
+//! file changed since.
+
+use crate::options::ParseOptions;
+use crate::parser::{ElfParser, ParseError};
+use crate::refresh::RefreshReport;
+use crate::source::SourceError;
+use log::debug;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Path and metadata of the file a parser was opened from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceInfo {
+    /// Canonical path of the file, with symbolic links resolved
+    pub path: PathBuf,
+    /// Size of the file in bytes
+    pub size: u64,
+    /// Last modification time of the file, when the platform provides it
+    pub modified: Option<SystemTime>,
+}
+
+impl SourceInfo {
+    /// Reads the metadata of the file at `path`
+    pub fn stat(path: impl AsRef<Path>) -> Result<Self, SourceError> {
+        let path = path.as_ref().canonicalize().map_err(SourceError::Io)?;
+        let metadata = std::fs::metadata(&path).map_err(SourceError::Io)?;
+
+        Ok(SourceInfo {
+            path,
+            size: metadata.len(),
+            modified: metadata.modified().ok(),
+        })
+    }
+
+    /// Whether the size or the modification time differ, as they do once the file
+    /// was written to
+    pub fn differs_from(&self, other: &SourceInfo) -> bool {
+        self.size != other.size || self.modified != other.modified
+    }
+}
+
+impl fmt::Display for SourceInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({} bytes", self.path.display(), self.size)?;
+        if let Some(secs) = self
+            .modified
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+        {
+            write!(f, ", modified {}", utc_time(secs))?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Formats `secs` seconds after the Unix epoch as a UTC date and time
+fn utc_time(secs: u64) -> String {
+    // civil date of a day count, after Howard Hinnant's `civil_from_days`
+    let days = (secs / 86400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        secs % 86400 / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+impl ElfParser<'_> {
+    /// Reads and parses the file at `path`, recording where it was read from, see
+    /// `source`
+    pub fn open(path: impl AsRef<Path>) -> Result<ElfParser<'static>, ParseError> {
+        ElfParser::open_with(path, &ParseOptions::default())
+    }
+
+    /// Reads and parses the file at `path` as `from_owned_with` does, recording where
+    /// it was read from
+    pub fn open_with(
+        path: impl AsRef<Path>,
+        options: &ParseOptions,
+    ) -> Result<ElfParser<'static>, ParseError> {
+        // taken before reading, so a file changed while it's read looks changed
+        let source = SourceInfo::stat(&path)?;
+        let data = std::fs::read(&source.path).map_err(SourceError::Io)?;
+
+        let mut parser = ElfParser::from_owned_with(data, options)?;
+        parser.source_info = Some(source);
+
+        Ok(parser)
+    }
+
+    /// The file the parser was opened from, None when it was parsed from bytes
+    pub fn source(&self) -> Option<&SourceInfo> {
+        self.source_info.as_ref()
+    }
+
+    /// Reads the metadata of the file the parser was opened from again, telling
+    /// whether its size or modification time changed since. Parsers built from bytes
+    /// have no file to check and return None
+    pub fn reopen_check(&self) -> Option<Result<bool, ParseError>> {
+        let source = self.source_info.as_ref()?;
+
+        Some(
+            SourceInfo::stat(&source.path)
+                .map(|current| current.differs_from(source))
+                .map_err(ParseError::from),
+        )
+    }
+
+    /// Reads the file the parser was opened from again when it changed, as
+    /// `reopen_check` tells, handing the new contents to `refresh`. Returns None when
+    /// the file didn't change or the parser was built from bytes
+    pub fn reload(&mut self) -> Result<Option<RefreshReport>, ParseError> {
+        let Some(source) = &self.source_info else {
+            return Ok(None);
+        };
+        let current = SourceInfo::stat(&source.path)?;
+        if !current.differs_from(source) {
+            return Ok(None);
+        }
+
+        debug!("{} changed, refreshing it", current.path.display());
+        let data = std::fs::read(&current.path).map_err(SourceError::Io)?;
+        let report = self.refresh(&data)?;
+        self.source_info = Some(current);
+
+        Ok(Some(report))
+    }
+}
@@ -0,0 +1,199 @@
+//! Counters and timings of a parse, for callers parsing untrusted files on a budget.
+//!
+//! `ElfParser::stats` reports how much of the file was read and how long each phase of
+//! the parse took. `ParseOptions::deadline` bounds the whole parse, which is checked
+//! between phases and between the entries of the larger tables. `ParseOptions::arena`
+//! bounds the bytes it allocates, which are taken from an `Arena` before every buffer
+//! sized by the file is allocated.
+
+use crate::elf::phdr::PTypeData;
+use crate::elf::shdr::{SHT_REL, SHT_RELA};
+use crate::parser::{ElfParser, ParseError};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Time spent in each phase of the parse
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct PhaseDurations {
+    /// Reading the ELF header
+    pub header: Duration,
+    /// Reading the section header table
+    pub section_table: Duration,
+    /// Reading the program header table, along with the contents of the loadable and
+    /// dynamic segments
+    pub segment_data: Duration,
+    pub string_tables: Duration,
+    pub symbols: Duration,
+}
+
+impl PhaseDurations {
+    pub fn total(&self) -> Duration {
+        self.header + self.section_table + self.segment_data + self.string_tables + self.symbols
+    }
+}
+
+/// What a parse read from the file, and how long it took
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ParseStats {
+    pub sections: usize,
+    pub segments: usize,
+    /// Entries of every symbol table read
+    pub symbols: usize,
+    /// Entries of the `SHT_REL` and `SHT_RELA` sections, which aren't decoded while
+    /// parsing but counted from their headers
+    pub relocations: usize,
+    /// Size of every string table read
+    pub string_table_bytes: usize,
+    /// Bytes of the file read while parsing: the header tables, the segment contents,
+    /// the string tables and the symbol tables
+    pub bytes_touched: u64,
+    pub phases: PhaseDurations,
+}
+
+/// Point in time past which the parse fails with `ParseError::DeadlineExceeded`
+#[derive(Debug, Copy, Clone)]
+pub struct Deadline(Option<Instant>);
+
+impl Deadline {
+    /// Deadline `budget` after `start`, or none without a budget
+    pub fn after(start: Instant, budget: Option<Duration>) -> Self {
+        Deadline(budget.and_then(|budget| start.checked_add(budget)))
+    }
+
+    /// Deadline never reached
+    pub fn none() -> Self {
+        Deadline(None)
+    }
+
+    pub fn check(&self) -> Result<(), ParseError> {
+        match self.0 {
+            Some(at) if Instant::now() >= at => Err(ParseError::DeadlineExceeded),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Fixed number of bytes the parses given it may allocate between them, see
+/// `ParseOptions::arena`. Clones share the same budget
+#[derive(Debug, Clone)]
+pub struct Arena {
+    capacity: usize,
+    used: Arc<AtomicUsize>,
+}
+
+impl Arena {
+    pub fn new(capacity: usize) -> Self {
+        Arena {
+            capacity,
+            used: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Bytes taken from the arena so far
+    pub fn used(&self) -> usize {
+        self.used.load(Ordering::Relaxed)
+    }
+
+    /// Gives every byte back, once the parsers the arena served were dropped
+    pub fn reset(&self) {
+        self.used.store(0, Ordering::Relaxed);
+    }
+
+    /// Takes `size` bytes from the arena, failing with `ParseError::ArenaExhausted`
+    /// and taking nothing when fewer remain
+    pub fn take(&self, size: u64) -> Result<(), ParseError> {
+        self.used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                let size = usize::try_from(size).ok()?;
+                used.checked_add(size).filter(|used| *used <= self.capacity)
+            })
+            .map(|_| ())
+            .map_err(|used| ParseError::ArenaExhausted {
+                requested: size,
+                remaining: self.capacity.saturating_sub(used),
+            })
+    }
+}
+
+/// Arenas are equal when they share the same budget
+impl PartialEq for Arena {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.used, &other.used)
+    }
+}
+
+impl Eq for Arena {}
+
+impl ParseStats {
+    /// Counts what `parser` holds, leaving the durations of `phases` untouched
+    pub(crate) fn count(parser: &ElfParser, phases: PhaseDurations) -> Self {
+        let headers = &parser.headers;
+        let string_table_bytes = parser
+            .string_tables
+            .iter()
+            .map(|st| st.table.len())
+            .fold(0, usize::saturating_add);
+        let symbol_bytes = parser
+            .symbol_tables
+            .iter()
+            .filter_map(|st| parser.section_headers.get(st.section_idx))
+            .map(|sh| sh.file_size())
+            .fold(0, u64::saturating_add);
+        // only the segments whose contents were read, the sizes of the others being
+        // whatever the file claims
+        let segment_bytes = parser
+            .program_headers
+            .iter()
+            .filter(|ph| {
+                matches!(
+                    ph.section,
+                    PTypeData::PtLoadData { .. } | PTypeData::PtDynamicData(_)
+                )
+            })
+            .map(|ph| ph.filesz)
+            .fold(0, u64::saturating_add);
+        let table_bytes =
+            |count: usize, ent_size: u16| (count as u64).saturating_mul(ent_size as u64);
+
+        ParseStats {
+            sections: parser.section_headers.len(),
+            segments: parser.program_headers.len(),
+            symbols: parser
+                .symbol_tables
+                .iter()
+                .map(|st| st.symbols.len())
+                .fold(0, usize::saturating_add),
+            relocations: parser
+                .section_headers
+                .iter()
+                .filter(|sh| sh.s_type == SHT_REL || sh.s_type == SHT_RELA)
+                .filter(|sh| sh.ent_size > 0)
+                .map(|sh| (sh.file_size() / sh.ent_size) as usize)
+                .fold(0, usize::saturating_add),
+            string_table_bytes,
+            bytes_touched: [
+                headers.class_size() as u64,
+                table_bytes(parser.section_headers.len(), headers.sh_ent_size),
+                table_bytes(parser.program_headers.len(), headers.ph_ent_size),
+                segment_bytes,
+                string_table_bytes as u64,
+                symbol_bytes,
+            ]
+            .into_iter()
+            .fold(0, u64::saturating_add),
+            phases,
+        }
+    }
+}
+
+impl ElfParser<'_> {
+    /// Counts of what the parse read along with the time each of its phases took
+    pub fn stats(&self) -> &ParseStats {
+        &self.stats
+    }
+}
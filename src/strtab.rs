@@ -0,0 +1,130 @@
+//! Builder of string tables, as held by `.strtab`, `.dynstr` and `.shstrtab`.
+//!
+//! Offsets returned by `StringTableBuilder::insert` are final: strings are only ever
+//! appended, so a table can be written out, grown and written again without the
+//! offsets handed out earlier going stale. A string already in the table is never
+//! stored twice, and with tail merging a string that ends another one, such as `bar`
+//! for `foobar`, points into it instead. Only strings inserted earlier can be reused
+//! this way, so inserting the longer strings first merges more of them.
+
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub struct StringTableBuilder {
+    /// Contents of the table, always ending with a NUL byte, and starting with one
+    /// unless it was built from a table that didn't
+    bytes: Vec<u8>,
+    /// Offset of every string known to start in the table
+    offsets: HashMap<String, u32>,
+    tail_merge: bool,
+}
+
+impl Default for StringTableBuilder {
+    fn default() -> Self {
+        StringTableBuilder {
+            bytes: vec![0],
+            offsets: HashMap::from([(String::new(), 0)]),
+            tail_merge: false,
+        }
+    }
+}
+
+impl StringTableBuilder {
+    /// An empty table, holding only the NUL byte that names at offset 0 resolve to
+    pub fn new() -> Self {
+        StringTableBuilder::default()
+    }
+
+    /// A table starting with the contents of `table`, so the offsets into it remain
+    /// valid. A missing trailing NUL byte is added, but a table that doesn't start
+    /// with one is kept as is, as adding it would shift every string
+    pub fn from_table(table: &[u8]) -> Self {
+        let mut bytes = table.to_vec();
+        if bytes.last() != Some(&0) {
+            bytes.push(0);
+        }
+
+        let mut offsets = HashMap::new();
+        let mut start = 0;
+        for (idx, _) in bytes.iter().enumerate().filter(|(_, b)| **b == 0) {
+            let s = String::from_utf8_lossy(&bytes[start..idx]).into_owned();
+            offsets.entry(s).or_insert(start as u32);
+            start = idx + 1;
+        }
+
+        StringTableBuilder {
+            bytes,
+            offsets,
+            tail_merge: false,
+        }
+    }
+
+    /// Lets strings ending a string already in the table point into it
+    pub fn tail_merge(mut self, tail_merge: bool) -> Self {
+        self.tail_merge = tail_merge;
+        self
+    }
+
+    /// Offset of `s` in the table, adding it unless it's already there. `s` is cut at
+    /// its first NUL byte, as the table can't hold anything past it
+    pub fn insert(&mut self, s: &str) -> u32 {
+        let s = s.split('\0').next().unwrap_or_default();
+        if let Some(off) = self.offsets.get(s) {
+            return *off;
+        }
+
+        let off = match self.tail_merge {
+            true => self.find_tail(s),
+            false => None,
+        };
+        let off = off.unwrap_or_else(|| {
+            let off = self.bytes.len() as u32;
+            self.bytes.extend_from_slice(s.as_bytes());
+            self.bytes.push(0);
+            off
+        });
+
+        self.offsets.insert(s.to_string(), off);
+        off
+    }
+
+    /// Offset of an occurrence of `s` directly followed by a NUL byte
+    fn find_tail(&self, s: &str) -> Option<u32> {
+        let len = s.len() + 1;
+
+        self.bytes
+            .windows(len)
+            .position(|w| w[len - 1] == 0 && &w[..len - 1] == s.as_bytes())
+            .map(|off| off as u32)
+    }
+
+    /// String at `offset`, running up to the next NUL byte. None if the offset is out
+    /// of the table or the bytes aren't UTF-8
+    pub fn lookup(&self, offset: u32) -> Option<&str> {
+        let bytes = self.bytes.get(offset as usize..)?;
+        let end = bytes.iter().position(|b| *b == 0)?;
+
+        std::str::from_utf8(&bytes[..end]).ok()
+    }
+
+    /// Size of the table in bytes
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Whether the table holds no string but the empty one
+    pub fn is_empty(&self) -> bool {
+        self.bytes.len() == 1
+    }
+
+    /// Contents of the table as built so far
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Contents of the table, ending with a NUL byte and, unless built from a table
+    /// that didn't, starting with one
+    pub fn build(self) -> Vec<u8> {
+        self.bytes
+    }
+}
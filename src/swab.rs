@@ -0,0 +1,118 @@
+//! Conversion of a file to the opposite byte order, for files generated on a host of
+//! one endianness to run on a target of the other.
+//!
+//! Only the structures described by `layout` are re-encoded: the ELF header, whose
+//! `ident.data` is updated, and the program and section header tables. Section and
+//! segment contents are copied as is, so the symbol and relocation tables, the dynamic
+//! section, notes and code keep their original byte order and must be converted
+//! separately.
+
+use crate::bytes::ParseContext;
+use crate::elf::ehdr::{Elf64Hdr, ElfHData};
+use crate::elf::phdr::Elf64PHdr;
+use crate::elf::shdr::Elf64SHdr;
+use crate::layout::{self, FieldSpec};
+use crate::parser::ParseError;
+use log::debug;
+
+/// Offset of the data encoding within `e_ident`
+const EI_DATA: usize = 5;
+
+/// Re-encodes the `fields` of the structure at `base` from the byte order of `from`
+/// to the one of `to`
+fn swap_fields(
+    out: &mut [u8],
+    base: usize,
+    size: usize,
+    fields: &[FieldSpec],
+    from: &ParseContext,
+    to: &ParseContext,
+) -> Result<(), ParseError> {
+    let end = base.checked_add(size).ok_or(ParseError::InvalidLength)?;
+    let entry = out.get_mut(base..end).ok_or(ParseError::InvalidLength)?;
+
+    for spec in fields {
+        let value = from.read_field(entry, *spec)?;
+        to.write_field(entry, *spec, value)?;
+    }
+
+    Ok(())
+}
+
+/// Re-encodes the `count` entries of `size` bytes of the table at `offset`, every
+/// `stride` bytes
+fn swap_table(
+    out: &mut [u8],
+    (offset, count, stride): (u64, usize, u16),
+    (size, fields): (usize, &[FieldSpec]),
+    from: &ParseContext,
+    to: &ParseContext,
+) -> Result<(), ParseError> {
+    if count > 0 && (stride as usize) < size {
+        return Err(ParseError::InvalidLength);
+    }
+
+    for idx in 0..count {
+        let base = usize::try_from(offset)
+            .ok()
+            .and_then(|offset| offset.checked_add(idx.checked_mul(stride as usize)?))
+            .ok_or(ParseError::InvalidLength)?;
+        swap_fields(out, base, size, fields, from, to)?;
+    }
+
+    Ok(())
+}
+
+/// Copy of the file held by `data` with its header structures encoded in the byte
+/// order `target`, see the module documentation for what's left untouched. Files
+/// already in that byte order are copied as is
+pub fn convert_endianness(data: &[u8], target: ElfHData) -> Result<Vec<u8>, ParseError> {
+    let headers = Elf64Hdr::parse(data)?;
+    let from = ParseContext::from(&headers.ident);
+    let to = ParseContext::new(target, from.class);
+
+    let mut out = data.to_vec();
+    if from.endianness == target {
+        return Ok(out);
+    }
+
+    let ehdr = layout::ehdr::for_class(from.class);
+    swap_fields(&mut out, 0, ehdr.size, &ehdr.fields(), &from, &to)?;
+    out[EI_DATA] = target as u8;
+
+    // the counts may be held by the initial section header, which must be read
+    // before the table is converted
+    let sh_num = match (headers.sh_num, headers.sh_off) {
+        (_, 0) => 0,
+        (0, _) => Elf64SHdr::parse_initial(data, &from, &headers)?.size as usize,
+        (sh_num, _) => sh_num as usize,
+    };
+    let ph_num = match headers.ph_off {
+        0 => 0,
+        _ => Elf64PHdr::count(data, &from, &headers)?,
+    };
+
+    let shdr = layout::shdr::for_class(from.class);
+    swap_table(
+        &mut out,
+        (headers.sh_off, sh_num, headers.sh_ent_size),
+        (shdr.size, &shdr.fields()),
+        &from,
+        &to,
+    )?;
+    let phdr = layout::phdr::for_class(from.class);
+    swap_table(
+        &mut out,
+        (headers.ph_off, ph_num, headers.ph_ent_size),
+        (phdr.size, &phdr.fields()),
+        &from,
+        &to,
+    )?;
+
+    debug!(
+        "converted the headers of {} sections and {} segments to {}",
+        sh_num, ph_num, target
+    );
+
+    Ok(out)
+}
@@ -0,0 +1,107 @@
+//! Lookup of symbols by name through a hash index over every symbol table, built on
+//! the first lookup and kept by the parser.
+//!
+//! The index doesn't hold the names: it maps the hash of each name to the symbols
+//! bearing it, and lookups compare the candidates against the names borrowed from the
+//! string tables, so building it copies no string.
+
+use crate::elf::sym::{Elf64Sym, SymbolTable};
+use crate::parser::ElfParser;
+use log::debug;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+/// Position of a symbol in `ElfParser::symbol_tables`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SymbolRef {
+    /// Index into `ElfParser::symbol_tables`
+    pub table: usize,
+    /// Index of the symbol within its table
+    pub index: usize,
+}
+
+/// Symbols keyed by the hash of their name, in the order of the symbol tables and of
+/// the symbols within them
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SymbolIndex {
+    hasher: RandomState,
+    buckets: HashMap<u64, Vec<SymbolRef>>,
+}
+
+impl ElfParser<'_> {
+    /// Bytes of the name of `symbol`, up to the terminating NUL byte
+    fn symbol_name_bytes(&self, table: &SymbolTable, symbol: &Elf64Sym) -> Option<&[u8]> {
+        let strings = &self.linked_string_table(table.section_idx).ok()??.table;
+        let name = strings.get(symbol.name as usize..)?;
+
+        name.split(|b| *b == 0).next()
+    }
+
+    fn symbol_index(&self) -> &SymbolIndex {
+        self.symbol_index.get_or_init(|| {
+            let mut index = SymbolIndex::default();
+
+            for (t, table) in self.symbol_tables.iter().enumerate() {
+                for (i, symbol) in table.symbols.iter().enumerate().skip(1) {
+                    let Some(name) = self.symbol_name_bytes(table, symbol) else {
+                        continue;
+                    };
+                    if name.is_empty() {
+                        continue;
+                    }
+
+                    let hash = index.hasher.hash_one(name);
+                    index
+                        .buckets
+                        .entry(hash)
+                        .or_default()
+                        .push(SymbolRef { table: t, index: i });
+                }
+            }
+
+            debug!("indexed {} distinct symbol names", index.buckets.len());
+            index
+        })
+    }
+
+    /// Every symbol named `name`, from the symbol tables in section order, so `.symtab`
+    /// symbols usually come before `.dynsym` ones
+    pub fn symbols_by_name(&self, name: &str) -> Vec<(SymbolRef, &Elf64Sym)> {
+        let index = self.symbol_index();
+        let Some(candidates) = index.buckets.get(&index.hasher.hash_one(name.as_bytes())) else {
+            return vec![];
+        };
+
+        candidates
+            .iter()
+            .filter_map(|r| {
+                let table = self.symbol_tables.get(r.table)?;
+                let symbol = table.symbols.get(r.index)?;
+
+                (self.symbol_name_bytes(table, symbol)? == name.as_bytes()).then_some((*r, symbol))
+            })
+            .collect()
+    }
+
+    /// The symbol `r` refers to
+    pub fn symbol(&self, r: SymbolRef) -> Option<&Elf64Sym> {
+        self.symbol_tables.get(r.table)?.symbols.get(r.index)
+    }
+
+    /// The first symbol named `name`, see `symbols_by_name`
+    pub fn find_symbol(&self, name: &str) -> Option<&Elf64Sym> {
+        self.symbols_by_name(name)
+            .into_iter()
+            .next()
+            .map(|(_, symbol)| symbol)
+    }
+
+    /// The symbol tables, for editing them in place. The name index and the address
+    /// order are dropped, to be built again from the edited tables on the next lookup
+    pub fn symbol_tables_mut(&mut self) -> &mut Vec<SymbolTable> {
+        self.symbol_index.take();
+        self.sorted_symbols.take();
+        &mut self.symbol_tables
+    }
+}
@@ -0,0 +1,156 @@
+//! Symbols of each table split by whether the file defines them, for telling what an
+//! object still needs from the ones it's linked with.
+//!
+//! Every named symbol falls in exactly one `SymbolBucket`, from its section index
+//! first and its binding second:
+//! + `SHN_COMMON` symbols are `Common`, whatever their binding, as the linker has yet
+//!   to allocate them
+//! + `SHN_UNDEF` symbols are `Undefined`, weak ones included: a weak reference doesn't
+//!   have to be satisfied, but it's still something the object asks for
+//! + defined symbols are `DefinedGlobal`, `DefinedWeak` or `DefinedLocal` after their
+//!   binding, `STB_GNU_UNIQUE` counting as global
+//!
+//! The type only matters to leave out `STT_SECTION` and `STT_FILE` symbols, which
+//! name no entity. `STT_GNU_IFUNC` symbols are bucketed as any function: the resolver
+//! they point to is defined where they are, only the final address is picked at load
+//! time.
+
+use crate::elf::shdr::{SHT_DYNSYM, SHT_SYMTAB};
+use crate::elf::sym::{
+    Elf64Sym, SymbolTable, SHN_COMMON, STB_GLOBAL, STB_GNU_UNIQUE, STB_LOCAL, STB_WEAK, STT_FILE,
+    STT_SECTION,
+};
+use crate::parser::{ElfParser, ParseError};
+
+/// Which part of a `TableSummary` a symbol is listed in
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SymbolBucket {
+    DefinedGlobal,
+    DefinedWeak,
+    DefinedLocal,
+    Undefined,
+    Common,
+}
+
+impl SymbolBucket {
+    /// Bucket `symbol` falls in, None for the ones that aren't listed: section and file
+    /// symbols, and those with a binding other than local, global, weak or unique
+    pub fn of(symbol: &Elf64Sym) -> Option<SymbolBucket> {
+        if [STT_SECTION, STT_FILE].contains(&symbol.sym_type()) {
+            return None;
+        }
+        if symbol.shndx == SHN_COMMON {
+            return Some(SymbolBucket::Common);
+        }
+        if symbol.is_undefined() {
+            return Some(SymbolBucket::Undefined);
+        }
+
+        match symbol.bind() {
+            STB_GLOBAL | STB_GNU_UNIQUE => Some(SymbolBucket::DefinedGlobal),
+            STB_WEAK => Some(SymbolBucket::DefinedWeak),
+            STB_LOCAL => Some(SymbolBucket::DefinedLocal),
+            _ => None,
+        }
+    }
+}
+
+/// Names of the symbols of one table by bucket, each list in table order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableSummary {
+    /// Index of the section header the table was read from
+    pub section_idx: usize,
+    pub defined_global: Vec<String>,
+    pub defined_weak: Vec<String>,
+    pub defined_local: Vec<String>,
+    pub undefined: Vec<String>,
+    pub common: Vec<String>,
+}
+
+impl TableSummary {
+    /// Names of the symbols in `bucket`
+    pub fn names(&self, bucket: SymbolBucket) -> &[String] {
+        match bucket {
+            SymbolBucket::DefinedGlobal => &self.defined_global,
+            SymbolBucket::DefinedWeak => &self.defined_weak,
+            SymbolBucket::DefinedLocal => &self.defined_local,
+            SymbolBucket::Undefined => &self.undefined,
+            SymbolBucket::Common => &self.common,
+        }
+    }
+
+    /// Number of symbols in `bucket`
+    pub fn count(&self, bucket: SymbolBucket) -> usize {
+        self.names(bucket).len()
+    }
+
+    fn names_mut(&mut self, bucket: SymbolBucket) -> &mut Vec<String> {
+        match bucket {
+            SymbolBucket::DefinedGlobal => &mut self.defined_global,
+            SymbolBucket::DefinedWeak => &mut self.defined_weak,
+            SymbolBucket::DefinedLocal => &mut self.defined_local,
+            SymbolBucket::Undefined => &mut self.undefined,
+            SymbolBucket::Common => &mut self.common,
+        }
+    }
+}
+
+/// Summaries of the first `SHT_SYMTAB` and the first `SHT_DYNSYM` tables, None for the
+/// ones the file doesn't have
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SymbolSummary {
+    pub symtab: Option<TableSummary>,
+    pub dynsym: Option<TableSummary>,
+}
+
+impl SymbolSummary {
+    /// Names of the undefined symbols of both tables, sorted and without duplicates
+    pub fn undefined(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = [&self.symtab, &self.dynsym]
+            .into_iter()
+            .flatten()
+            .flat_map(|table| table.undefined.iter().map(String::as_str))
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+
+        names
+    }
+}
+
+impl ElfParser<'_> {
+    fn summarize_table(&self, table: &SymbolTable) -> Result<TableSummary, ParseError> {
+        let mut summary = TableSummary {
+            section_idx: table.section_idx,
+            ..TableSummary::default()
+        };
+
+        for symbol in table.symbols.iter().skip(1) {
+            let Some(bucket) = SymbolBucket::of(symbol) else {
+                continue;
+            };
+            let name = self.symbol_name(table, symbol)?;
+            if !name.is_empty() {
+                summary.names_mut(bucket).push(name);
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Splits the symbols of `.symtab` and `.dynsym` by bucket, see `SymbolBucket`
+    pub fn symbol_summary(&self) -> Result<SymbolSummary, ParseError> {
+        let summarize = |sh_type| {
+            self.symbol_tables
+                .iter()
+                .find(|table| table.sh_type == sh_type)
+                .map(|table| self.summarize_table(table))
+                .transpose()
+        };
+
+        Ok(SymbolSummary {
+            symtab: summarize(SHT_SYMTAB)?,
+            dynsym: summarize(SHT_DYNSYM)?,
+        })
+    }
+}
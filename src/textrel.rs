@@ -0,0 +1,38 @@
+use crate::bytes::Address;
+use crate::elf::phdr::{PType, DT_TEXTREL, PF_WRITE};
+use crate::parser::ElfParser;
+use log::debug;
+
+impl ElfParser<'_> {
+    /// Addresses of the dynamic relocations landing in a loadable segment that isn't
+    /// writable, which the dynamic linker has to remap writable to apply. Empty if the
+    /// relocations can't be read
+    pub fn text_relocation_targets(&self) -> Vec<Address> {
+        let read_only = self
+            .program_headers
+            .iter()
+            .filter(|ph| ph.p_type == PType::PtLoad && ph.flags & PF_WRITE == 0)
+            .map(|ph| ph.vaddr.0..ph.vaddr.0.saturating_add(ph.memsz))
+            .collect::<Vec<_>>();
+
+        let targets = self
+            .dynamic_relocations()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|r| r.offset)
+            .filter(|offset| read_only.iter().any(|r| r.contains(&offset.0)))
+            .collect::<Vec<_>>();
+
+        debug!("{} relocations land in read-only segments", targets.len());
+
+        targets
+    }
+
+    /// Whether the file has text relocations, either declared through `DT_TEXTREL` or
+    /// the `DF_TEXTREL` flag, or found by `text_relocation_targets`
+    pub fn has_text_relocations(&self) -> bool {
+        self.dynamic_entry(DT_TEXTREL).is_some()
+            || self.dyn_flags().is_some_and(|f| f.textrel())
+            || !self.text_relocation_targets().is_empty()
+    }
+}
@@ -0,0 +1,131 @@
+//! Layout of the thread-local storage of a file: the TLS template described by
+//! `PT_TLS`, made of the `.tdata` initialization image followed by the zeros of
+//! `.tbss`, and the offsets of the `STT_TLS` symbols within it.
+//!
+//! RISC-V follows TLS variant I: the thread pointer `tp` points right past the thread
+//! control block, and the TLS block of the executable starts at `tp`. The offset of a
+//! symbol from `tp` is then its offset within the block, with no bias. The 0x800
+//! `TLS_DTV_OFFSET` of the RISC-V psABI only applies to the offsets handed to
+//! `__tls_get_addr` for the dynamic models, not to `tp`-relative addresses.
+
+use crate::elf::ehdr::EM_RISCV;
+use crate::elf::phdr::{Elf64PHdr, PType};
+use crate::elf::shdr::SHT_SYMTAB;
+use crate::elf::sym::STT_TLS;
+use crate::parser::{ElfParser, ParseError, ParseWarning};
+
+/// Thread-local storage template of the file and where it lands relative to `tp`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TlsLayout {
+    /// Size of the initialization image, `.tdata`, copied into each TLS block
+    pub template_size: u64,
+    /// Size of each TLS block, `.tdata` along with `.tbss`
+    pub memsz: u64,
+    pub align: u64,
+    /// Offset of the start of the TLS block from `tp`, 0 on RISC-V
+    pub tp_offset_bias: u64,
+}
+
+impl TlsLayout {
+    /// Offset from `tp` of the variable at `offset` within the TLS block
+    pub fn tp_offset(&self, offset: u64) -> u64 {
+        self.tp_offset_bias + offset
+    }
+}
+
+/// A thread-local variable, located by its offset within the TLS block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlsSymbol {
+    pub name: String,
+    pub offset: u64,
+    pub size: u64,
+}
+
+impl ElfParser<'_> {
+    fn tls_segment(&self) -> Option<&Elf64PHdr> {
+        self.program_headers
+            .iter()
+            .find(|ph| ph.p_type == PType::PtTls)
+    }
+
+    /// Layout of the TLS template, from the `PT_TLS` segment. None for files without
+    /// thread-local storage, and `UnsupportedMachine` for machines other than RISC-V,
+    /// whose TLS block may start past `tp`
+    pub fn tls_layout(&self) -> Result<Option<TlsLayout>, ParseError> {
+        let Some(tls) = self.tls_segment() else {
+            return Ok(None);
+        };
+        if self.headers.machine != EM_RISCV {
+            return Err(ParseError::UnsupportedMachine(self.headers.machine));
+        }
+
+        Ok(Some(TlsLayout {
+            template_size: tls.filesz,
+            memsz: tls.memsz,
+            align: tls.align,
+            tp_offset_bias: 0,
+        }))
+    }
+
+    /// Defined `STT_TLS` symbols of `.symtab`, or of `.dynsym` for stripped files, as
+    /// symbol table index, symbol index and symbol. Their value is the offset within
+    /// the TLS block in linked files
+    fn tls_symbol_entries(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let has_symtab = self.symbol_tables.iter().any(|t| t.sh_type == SHT_SYMTAB);
+
+        self.symbol_tables
+            .iter()
+            .enumerate()
+            .filter(move |(_, t)| !has_symtab || t.sh_type == SHT_SYMTAB)
+            .flat_map(|(t, table)| {
+                table
+                    .symbols
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, s)| s.sym_type() == STT_TLS && !s.is_undefined())
+                    .map(move |(i, _)| (t, i))
+            })
+    }
+
+    /// Thread-local variables of the file, with their offset within the TLS block, in
+    /// symbol table order
+    pub fn tls_symbols(&self) -> Result<Vec<TlsSymbol>, ParseError> {
+        self.tls_symbol_entries()
+            .map(|(t, i)| {
+                let table = &self.symbol_tables[t];
+                let symbol = &table.symbols[i];
+
+                Ok(TlsSymbol {
+                    name: self.symbol_name(table, symbol)?,
+                    offset: symbol.value.0,
+                    size: symbol.size,
+                })
+            })
+            .collect()
+    }
+
+    /// TLS symbols that don't fit within the `PT_TLS` segment
+    pub(crate) fn tls_anomalies(&self) -> Vec<ParseWarning> {
+        let Some(tls) = self.tls_segment() else {
+            return vec![];
+        };
+
+        self.tls_symbol_entries()
+            .filter_map(|(t, i)| {
+                let table = &self.symbol_tables[t];
+                let symbol = &table.symbols[i];
+                let end = symbol.value.0.checked_add(symbol.size);
+
+                (end.is_none_or(|end| end > tls.memsz)).then_some(
+                    ParseWarning::TlsSymbolOutOfBlock {
+                        section: table.section_idx,
+                        idx: i,
+                        offset: symbol.value.0,
+                        size: symbol.size,
+                        memsz: tls.memsz,
+                    },
+                )
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,85 @@
+use crate::elf::note::{AbiTag, Elf64Note, GnuProperty, NT_GNU_ABI_TAG, NT_GNU_PROPERTY_TYPE_0};
+use crate::elf::phdr::PType;
+use crate::parser::{ElfParser, ParseError};
+
+/// Provenance information about the toolchain that produced a file and the
+/// system it targets
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolchainInfo {
+    /// Strings found in the `.comment` section, e.g. "GCC: (GNU) 13.2.0", or None
+    /// if the file has no such section
+    pub comments: Option<Vec<String>>,
+    /// Target OS and minimum kernel version declared by `.note.ABI-tag`, or None if
+    /// the file has no such note
+    pub abi_tag: Option<AbiTag>,
+}
+
+impl ElfParser<'_> {
+    /// Reads the `.comment` section, a sequence of NUL-terminated strings, usually
+    /// one per compiler or linker that touched the file
+    pub fn comments(&self) -> Result<Option<Vec<String>>, ParseError> {
+        let Some((idx, _)) = self.section_by_name(".comment") else {
+            return Ok(None);
+        };
+
+        Ok(Some(
+            self.section_data(idx)?
+                .split(|&b| b == 0)
+                .filter(|s| !s.is_empty())
+                .map(|s| String::from_utf8_lossy(s).into_owned())
+                .collect(),
+        ))
+    }
+
+    /// Reads the `NT_GNU_ABI_TAG` note held by `.note.ABI-tag`
+    pub fn abi_tag(&self) -> Result<Option<AbiTag>, ParseError> {
+        let Some((idx, sh)) = self.section_by_name(".note.ABI-tag") else {
+            return Ok(None);
+        };
+
+        let notes = Elf64Note::parse(self.section_data(idx)?, &self.ctx, sh.addr_align as usize)?;
+
+        notes
+            .iter()
+            .find(|n| n.name == "GNU" && n.n_type == NT_GNU_ABI_TAG)
+            .map(|n| AbiTag::parse(n, &self.ctx))
+            .transpose()
+    }
+
+    /// Reads the program properties of the file, from `.note.gnu.property` or, for
+    /// files without section headers, from the `PT_GNU_PROPERTY` segment
+    pub fn gnu_properties(&self) -> Result<Vec<GnuProperty>, ParseError> {
+        let notes = if let Some((idx, sh)) = self.section_by_name(".note.gnu.property") {
+            Elf64Note::parse(self.section_data(idx)?, &self.ctx, sh.addr_align as usize)?
+        } else if let Some(idx) = self
+            .program_headers
+            .iter()
+            .position(|ph| ph.p_type == PType::PtGnuProperty)
+        {
+            Elf64Note::parse(
+                self.segment_data(idx)?,
+                &self.ctx,
+                self.program_headers[idx].align as usize,
+            )?
+        } else {
+            return Ok(vec![]);
+        };
+
+        let mut properties = vec![];
+        for note in notes
+            .iter()
+            .filter(|n| n.name == "GNU" && n.n_type == NT_GNU_PROPERTY_TYPE_0)
+        {
+            properties.extend(GnuProperty::parse(&note.desc, &self.ctx)?);
+        }
+
+        Ok(properties)
+    }
+
+    pub fn toolchain_info(&self) -> Result<ToolchainInfo, ParseError> {
+        Ok(ToolchainInfo {
+            comments: self.comments()?,
+            abi_tag: self.abi_tag()?,
+        })
+    }
+}
@@ -0,0 +1,121 @@
+use crate::options::ParseOptions;
+use crate::parser::{ElfParser, ParseError};
+use log::debug;
+use std::collections::HashMap;
+
+/// Handle to a string interned by a `Workspace`, only meaningful to the workspace
+/// that handed it out
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NameId(pub u32);
+
+/// Parses many files sharing a single copy of every section and symbol name among
+/// them, for batch processing where names such as `.text` or `memcpy` repeat in
+/// every file
+#[derive(Debug, Default)]
+pub struct Workspace {
+    ids: HashMap<Box<str>, u32>,
+    /// Interned strings, indexed by their `NameId`
+    strings: Vec<Box<str>>,
+}
+
+/// A parsed file whose names are resolved into `NameId` handles of the `Workspace`
+/// that parsed it
+#[derive(Debug)]
+pub struct ElfParserInterned<'a> {
+    pub parser: ElfParser<'a>,
+    /// Name of every section, indexed like `ElfParser::section_headers`. Names that
+    /// can't be resolved are interned as the empty string
+    pub section_names: Vec<NameId>,
+    /// Names of the symbols of every table, indexed like `ElfParser::symbol_tables`
+    pub symbol_names: Vec<Vec<NameId>>,
+}
+
+impl ElfParserInterned<'_> {
+    pub fn section_name(&self, idx: usize) -> Option<NameId> {
+        self.section_names.get(idx).copied()
+    }
+
+    /// Name of the symbol at `idx` of the symbol table at `table`
+    pub fn symbol_name(&self, table: usize, idx: usize) -> Option<NameId> {
+        self.symbol_names.get(table)?.get(idx).copied()
+    }
+}
+
+impl Workspace {
+    pub fn new() -> Self {
+        Workspace::default()
+    }
+
+    /// Returns the handle of `s`, storing it if it wasn't interned yet
+    pub fn intern(&mut self, s: &str) -> NameId {
+        if let Some(id) = self.ids.get(s) {
+            return NameId(*id);
+        }
+
+        let id = self.strings.len() as u32;
+        self.strings.push(s.into());
+        self.ids.insert(s.into(), id);
+
+        NameId(id)
+    }
+
+    /// Returns the handle of `s` without interning it
+    pub fn get(&self, s: &str) -> Option<NameId> {
+        self.ids.get(s).map(|id| NameId(*id))
+    }
+
+    /// Returns the string behind `id`, panicking if it wasn't handed out by this
+    /// workspace
+    pub fn resolve(&self, id: NameId) -> &str {
+        &self.strings[id.0 as usize]
+    }
+
+    /// Number of distinct strings interned
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    pub fn parse<'a>(&mut self, data: &'a [u8]) -> Result<ElfParserInterned<'a>, ParseError> {
+        self.parse_with(data, &ParseOptions::default())
+    }
+
+    /// Parses `data` as `ElfParser::parse_with` does, interning the name of every
+    /// section and symbol
+    pub fn parse_with<'a>(
+        &mut self,
+        data: &'a [u8],
+        options: &ParseOptions,
+    ) -> Result<ElfParserInterned<'a>, ParseError> {
+        let parser = ElfParser::parse_with(data, options)?;
+
+        let section_names = parser
+            .section_headers
+            .iter()
+            .map(|sh| self.intern(&parser.section_name(sh).unwrap_or_default()))
+            .collect();
+
+        let symbol_names = parser
+            .symbol_tables
+            .iter()
+            .map(|table| {
+                table
+                    .symbols
+                    .iter()
+                    .map(|sym| self.intern(&parser.symbol_name(table, sym).unwrap_or_default()))
+                    .collect()
+            })
+            .collect();
+
+        debug!("workspace holds {} interned names", self.strings.len());
+
+        Ok(ElfParserInterned {
+            parser,
+            section_names,
+            symbol_names,
+        })
+    }
+}
@@ -0,0 +1,153 @@
+mod fixtures;
+
+use elf_parser::abi::{abi_diff, SymbolChange};
+use elf_parser::bytes::Address;
+use elf_parser::elf::ehdr::{ElfHClass, ElfHData, ElfHType};
+use elf_parser::elf::phdr::{
+    PType, DT_HASH, DT_NULL, DT_STRSZ, DT_STRTAB, DT_SYMENT, DT_SYMTAB, PF_EXEC, PF_READ, PF_WRITE,
+};
+use elf_parser::elf::shdr::{
+    SHF_ALLOC, SHF_EXECINSTR, SHF_WRITE, SHT_DYNAMIC, SHT_DYNSYM, SHT_HASH, SHT_PROGBITS,
+    SHT_STRTAB,
+};
+use elf_parser::elf::sym::{Elf64Sym, STB_GLOBAL, STT_FUNC};
+use elf_parser::exports::Export;
+use elf_parser::layout;
+use fixtures::{Image, ImageBuilder, ENCODINGS};
+
+/// A shared object exporting the functions of `exports`, given by name and size, laid
+/// out one after the other in `.text`
+fn library(class: ElfHClass, endianness: ElfHData, exports: &[(&str, u64)]) -> Image {
+    let sym_size = layout::sym::for_class(class).size;
+    let text_size: u64 = exports.iter().map(|(_, size)| size).sum();
+    let build = |first: Option<&Image>| {
+        let builder = ImageBuilder::new(class, endianness).e_type(ElfHType::SharedObject);
+        let ctx = builder.ctx();
+        let addr = |name| first.map_or(0, |image: &Image| image.section(name).addr.0);
+        let text_idx = first.map_or(0, |image: &Image| image.section_idx(".text"));
+
+        let mut hash = vec![];
+        for word in [1, exports.len() as u32 + 1, 0, 0, 0, 0] {
+            ctx.put_u32(&mut hash, word);
+        }
+        hash.resize(hash.len() + 4 * exports.len(), 0);
+
+        let mut dynstr = b"\0".to_vec();
+        let mut dynsym = vec![0; sym_size];
+        let mut value = addr(".text");
+        for (name, size) in exports {
+            let symbol = Elf64Sym {
+                name: dynstr.len() as u32,
+                info: STB_GLOBAL << 4 | STT_FUNC,
+                other: 0,
+                shndx: text_idx as u16,
+                value: Address(value),
+                size: *size,
+            };
+            symbol.write(&ctx, &mut dynsym);
+            dynstr.extend(name.as_bytes());
+            dynstr.push(0);
+            value += size;
+        }
+
+        let mut dynamic = vec![];
+        for (tag, value) in [
+            (DT_HASH, addr(".hash")),
+            (DT_SYMTAB, addr(".dynsym")),
+            (DT_STRTAB, addr(".dynstr")),
+            (DT_STRSZ, dynstr.len() as u64),
+            (DT_SYMENT, sym_size as u64),
+            (DT_NULL, 0),
+        ] {
+            ctx.put_uword(&mut dynamic, tag as u64);
+            ctx.put_uword(&mut dynamic, value);
+        }
+
+        builder
+            .section(".hash", SHT_HASH, SHF_ALLOC, &hash)
+            .section(".dynsym", SHT_DYNSYM, SHF_ALLOC, &dynsym)
+            .section(".dynstr", SHT_STRTAB, SHF_ALLOC, &dynstr)
+            .section(
+                ".text",
+                SHT_PROGBITS,
+                SHF_ALLOC | SHF_EXECINSTR,
+                &vec![0x13; text_size as usize],
+            )
+            .section(".dynamic", SHT_DYNAMIC, SHF_ALLOC | SHF_WRITE, &dynamic)
+            .segment(
+                PType::PtLoad,
+                PF_READ | PF_EXEC,
+                &[".hash", ".dynsym", ".dynstr", ".text"],
+            )
+            .segment(PType::PtLoad, PF_READ | PF_WRITE, &[".dynamic"])
+            .segment(PType::PtDynamic, PF_READ | PF_WRITE, &[".dynamic"])
+            .build()
+    };
+
+    build(Some(&build(None)))
+}
+
+fn names(exports: &[Export]) -> Vec<&str> {
+    exports.iter().map(|e| e.name.as_str()).collect()
+}
+
+/// The second build of the library drops `lib_free` and grows `lib_alloc`
+#[test]
+fn function_removed_and_grown() {
+    for (class, endianness) in ENCODINGS {
+        let old = library(
+            class,
+            endianness,
+            &[("lib_init", 0x10), ("lib_alloc", 0x20), ("lib_free", 0x8)],
+        );
+        let new = library(
+            class,
+            endianness,
+            &[("lib_init", 0x10), ("lib_alloc", 0x40)],
+        );
+        let (old, new) = (old.parse().unwrap(), new.parse().unwrap());
+        assert_eq!(
+            names(&old.exports().unwrap()),
+            ["lib_alloc", "lib_free", "lib_init"]
+        );
+
+        let diff = abi_diff(&old, &new).unwrap();
+        assert_eq!(names(&diff.removed), ["lib_free"]);
+        assert!(diff.added.is_empty());
+        assert!(matches!(
+            &diff.changed[..],
+            [SymbolChange { old, new }]
+                if old.name == "lib_alloc" && old.size == 0x20 && new.size == 0x40
+        ));
+        assert!(diff.removed_versions.is_empty() && diff.added_versions.is_empty());
+        assert!(diff.has_removals());
+
+        // going the other way the function comes back and shrinks
+        let diff = abi_diff(&new, &old).unwrap();
+        assert!(diff.removed.is_empty());
+        assert_eq!(names(&diff.added), ["lib_free"]);
+        assert_eq!(diff.changed[0].new.size, 0x20);
+        assert!(!diff.has_removals());
+    }
+}
+
+/// Moving a function to another address isn't a change of the interface
+#[test]
+fn identical_interfaces() {
+    for (class, endianness) in ENCODINGS {
+        let old = library(
+            class,
+            endianness,
+            &[("lib_init", 0x10), ("lib_alloc", 0x20)],
+        );
+        let new = library(
+            class,
+            endianness,
+            &[("lib_alloc", 0x20), ("lib_init", 0x10)],
+        );
+        let (old, new) = (old.parse().unwrap(), new.parse().unwrap());
+
+        assert!(abi_diff(&old, &old).unwrap().is_empty());
+        assert!(abi_diff(&old, &new).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,149 @@
+mod fixtures;
+
+use elf_parser::alignment::AlignmentIssue;
+use elf_parser::elf::ehdr::{ElfHClass, ElfHData};
+use fixtures::{Image, ImageBuilder, ENCODINGS, PAGE_SIZE};
+
+/// Minimal image whose data segment is moved to the page after the text, keeping its
+/// offset within the page, as linkers lay out segments of other permissions
+fn linked(class: ElfHClass, endianness: ElfHData) -> Image {
+    let mut image = ImageBuilder::minimal(class, endianness).build();
+    let vaddr = image.parse().unwrap().program_headers[1].vaddr.0;
+    image.set_segment(1, |l| l.p_vaddr, vaddr + PAGE_SIZE);
+    image
+}
+
+fn issues(image: &Image) -> Vec<AlignmentIssue> {
+    image.parse().unwrap().check_load_alignment(PAGE_SIZE)
+}
+
+#[test]
+fn clean() {
+    for (class, endianness) in ENCODINGS {
+        assert_eq!(issues(&linked(class, endianness)), []);
+    }
+}
+
+#[test]
+fn align_below_page_size() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = linked(class, endianness);
+        image.set_segment(0, |l| l.p_align, 0x10);
+
+        assert_eq!(
+            issues(&image),
+            [AlignmentIssue::AlignBelowPageSize {
+                segment: 0,
+                align: 0x10
+            }]
+        );
+        // pages that small are fine
+        assert_eq!(image.parse().unwrap().check_load_alignment(0x10), []);
+    }
+}
+
+#[test]
+fn offset_not_congruent() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = linked(class, endianness);
+        let ph = image.parse().unwrap().program_headers[1].clone();
+        image.set_segment(1, |l| l.p_vaddr, ph.vaddr.0 + 8);
+
+        let issues = issues(&image);
+        assert_eq!(
+            issues,
+            [AlignmentIssue::OffsetNotCongruent {
+                segment: 1,
+                vaddr: ph.vaddr.0 + 8,
+                offset: ph.offset,
+            }]
+        );
+        assert_eq!(issues[0].segment(), 1);
+    }
+}
+
+#[test]
+fn conflicting_permissions() {
+    for (class, endianness) in ENCODINGS {
+        // both segments in the same page
+        let image = ImageBuilder::minimal(class, endianness).build();
+        let text = image.parse().unwrap().program_headers[0].vaddr.0;
+
+        let issues = issues(&image);
+        assert_eq!(
+            issues,
+            [AlignmentIssue::ConflictingPermissions {
+                segment: 1,
+                other: 0,
+                page: text - text % PAGE_SIZE,
+            }]
+        );
+        assert_eq!(
+            issues[0].to_string(),
+            format!(
+                "segment [1] shares the page at {:#x} with segment [0] with other permissions",
+                text - text % PAGE_SIZE
+            )
+        );
+    }
+}
+
+#[test]
+fn memsz_below_filesz() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = linked(class, endianness);
+        image.set_segment(1, |l| l.p_memsz, 4);
+
+        assert_eq!(
+            issues(&image),
+            [AlignmentIssue::MemszBelowFilesz {
+                segment: 1,
+                filesz: 8,
+                memsz: 4,
+            }]
+        );
+    }
+}
+
+#[test]
+fn beyond_last_file_page() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = linked(class, endianness);
+        let offset = image.parse().unwrap().program_headers[1].offset;
+        image
+            .set_segment(1, |l| l.p_filesz, 2 * PAGE_SIZE)
+            .set_segment(1, |l| l.p_memsz, 2 * PAGE_SIZE);
+
+        assert_eq!(
+            issues(&image),
+            [AlignmentIssue::BeyondLastFilePage {
+                segment: 1,
+                end: offset + 2 * PAGE_SIZE,
+                file_size: image.bytes.len() as u64,
+            }]
+        );
+    }
+}
+
+/// Only 64-bit addresses can be rounded up past the address space
+#[test]
+fn address_overflow() {
+    for (class, endianness) in ENCODINGS {
+        if class != ElfHClass::ElfClass64 {
+            continue;
+        }
+        let mut image = linked(class, endianness);
+        let offset = image.parse().unwrap().program_headers[1].offset;
+        let vaddr = u64::MAX - (PAGE_SIZE - 1) + offset % PAGE_SIZE;
+        image.set_segment(1, |l| l.p_vaddr, vaddr);
+
+        assert_eq!(
+            issues(&image),
+            [AlignmentIssue::AddressOverflow {
+                segment: 1,
+                vaddr,
+                memsz: 8,
+            }]
+        );
+    }
+}
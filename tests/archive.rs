@@ -0,0 +1,229 @@
+mod fixtures;
+
+use elf_parser::archive::{Archive, AR_MAGIC, AR_THIN_MAGIC};
+use elf_parser::elf::ehdr::{ElfHClass, ElfHData, ElfHType};
+use elf_parser::elf::shdr::{SHF_ALLOC, SHF_EXECINSTR, SHT_PROGBITS};
+use elf_parser::parser::ParseError;
+use fixtures::{ImageBuilder, TEXT};
+
+/// A relocatable object whose `.text` holds `len` bytes. An odd `len` is carried
+/// over to the size of the file, so the member storing it needs padding
+fn object(len: usize) -> Vec<u8> {
+    let mut bytes = ImageBuilder::new(ElfHClass::ElfClass64, ElfHData::ElfData2Lsb)
+        .e_type(ElfHType::Relocatable)
+        .section(
+            ".text",
+            SHT_PROGBITS,
+            SHF_ALLOC | SHF_EXECINSTR,
+            &TEXT[..len],
+        )
+        .build()
+        .bytes;
+    bytes.resize(bytes.len() + len % 2, 0);
+
+    bytes
+}
+
+/// Appends a member header and `data`, padded to an even offset
+fn member(ar: &mut Vec<u8>, name: &str, data: &[u8]) -> usize {
+    let offset = ar.len();
+    ar.extend(
+        format!(
+            "{:<16}{:<12}{:<6}{:<6}{:<8}{:<10}`\n",
+            name,
+            0,
+            0,
+            0,
+            644,
+            data.len()
+        )
+        .as_bytes(),
+    );
+    ar.extend(data);
+    if ar.len() % 2 == 1 {
+        ar.push(b'\n');
+    }
+
+    offset
+}
+
+/// A System V archive of a short-named and a long-named member, with a symbol index
+/// defining `short` in the first one and `long` in the second
+fn gnu_archive() -> Vec<u8> {
+    let mut index = vec![0u8; 12];
+    index.extend(b"short\0long\0");
+
+    let mut ar = AR_MAGIC.to_vec();
+    let index_off = member(&mut ar, "/", &index) + 60;
+    member(&mut ar, "//", b"a_member_with_a_long_file_name.o/\n");
+    let first = member(&mut ar, "short.o/", &object(15));
+    let second = member(&mut ar, "/0", &object(16));
+
+    // the offsets of the members are only known once they're laid out
+    for (idx, value) in [2, first, second].into_iter().enumerate() {
+        let off = index_off + idx * 4;
+        ar[off..off + 4].copy_from_slice(&(value as u32).to_be_bytes());
+    }
+
+    ar
+}
+
+/// A BSD archive, whose names are stored at the start of the member data when they
+/// don't fit in the header
+fn bsd_archive() -> Vec<u8> {
+    let name = b"a_member_with_a_long_file_name.o";
+    let symdef = b"__.SYMDEF\0\0\0";
+    let strings = b"long\0\0\0\0";
+
+    let mut index = symdef.to_vec();
+    index.extend(8u32.to_le_bytes());
+    // name offset and member header offset of the only symbol
+    index.extend([0; 8]);
+    index.extend((strings.len() as u32).to_le_bytes());
+    index.extend(strings);
+
+    let mut long = name.to_vec();
+    long.extend(object(16));
+
+    let mut ar = AR_MAGIC.to_vec();
+    let index_off = member(&mut ar, &format!("#1/{}", symdef.len()), &index) + 60;
+    member(&mut ar, "short.o", &object(15));
+    let second = member(&mut ar, &format!("#1/{}", name.len()), &long);
+
+    let off = index_off + symdef.len() + 8;
+    ar[off..off + 4].copy_from_slice(&(second as u32).to_le_bytes());
+
+    ar
+}
+
+#[test]
+fn gnu_members() {
+    let data = gnu_archive();
+    let archive = Archive::parse(&data).unwrap();
+
+    let names = archive
+        .members()
+        .iter()
+        .map(|m| m.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["short.o", "a_member_with_a_long_file_name.o"]);
+    assert_eq!(archive.member_data(0).unwrap(), object(15));
+    assert_eq!(archive.member_data(1).unwrap(), object(16));
+
+    let member = archive.parse_member(1).unwrap();
+    assert_eq!(member.headers.e_type, ElfHType::Relocatable);
+    assert_eq!(member.section_by_name(".text").unwrap().1.size, 16);
+}
+
+#[test]
+fn gnu_symbol_index() {
+    let data = gnu_archive();
+    let archive = Archive::parse(&data).unwrap();
+
+    assert_eq!(
+        archive.symbols(),
+        [("short".to_string(), 0), ("long".to_string(), 1)]
+    );
+    assert_eq!(archive.find_symbol("long"), Some(1));
+    assert_eq!(archive.find_symbol("missing"), None);
+}
+
+#[test]
+fn bsd_members() {
+    let data = bsd_archive();
+    let archive = Archive::parse(&data).unwrap();
+
+    let names = archive
+        .members()
+        .iter()
+        .map(|m| m.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["short.o", "a_member_with_a_long_file_name.o"]);
+    assert_eq!(archive.member_data(1).unwrap(), object(16));
+    assert_eq!(archive.find_symbol("long"), Some(1));
+    assert!(archive.parse_member(0).is_ok());
+}
+
+#[test]
+fn thin_archive_refused() {
+    let mut data = AR_THIN_MAGIC.to_vec();
+    member(&mut data, "short.o/", &[]);
+
+    assert!(matches!(
+        Archive::parse(&data),
+        Err(ParseError::InvalidArchive(_))
+    ));
+}
+
+#[test]
+fn truncated_member() {
+    let mut data = gnu_archive();
+    data.truncate(data.len() - 20);
+
+    assert!(matches!(
+        Archive::parse(&data),
+        Err(ParseError::InvalidArchive("member runs past the end"))
+    ));
+}
+
+/// The same two RISC-V objects archived by GNU ar, and by llvm-ar in both formats
+const TOOL_ARCHIVES: [(&str, &[u8]); 3] = [
+    ("gnu", include_bytes!("golden/librv64-gnu.a")),
+    ("llvm", include_bytes!("golden/librv64-llvm.a")),
+    ("llvm-bsd", include_bytes!("golden/librv64-llvm-bsd.a")),
+];
+const RV64_OBJECT: &[u8] = include_bytes!("golden/rv64-object.o");
+
+#[test]
+fn tool_archives() {
+    for (tool, data) in TOOL_ARCHIVES {
+        assert!(Archive::is_archive(data), "{}", tool);
+        let archive = Archive::parse(data).unwrap();
+
+        let names = archive
+            .members()
+            .iter()
+            .map(|m| m.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            ["rv64-object.o", "rv64_archive_member_with_a_long_name.o"],
+            "{}",
+            tool
+        );
+        assert_eq!(archive.member_data(0).unwrap(), RV64_OBJECT, "{}", tool);
+
+        let member = archive.parse_member(1).unwrap();
+        assert_eq!(member.headers.e_type, ElfHType::Relocatable);
+        assert!(member.find_symbol("archive_entry").is_some());
+        assert!(archive.member_data(2).is_err());
+    }
+}
+
+/// The index lists the defined global symbols, local and undefined ones are left out
+#[test]
+fn tool_symbol_indexes() {
+    for (tool, data) in TOOL_ARCHIVES {
+        let archive = Archive::parse(data).unwrap();
+
+        let mut symbols = archive.symbols().to_vec();
+        symbols.sort();
+        assert_eq!(
+            symbols,
+            [
+                ("archive_entry".to_string(), 1),
+                ("archive_table".to_string(), 1),
+                ("buffer".to_string(), 0),
+                ("hook".to_string(), 0),
+                ("main".to_string(), 0),
+            ],
+            "{}",
+            tool
+        );
+        assert_eq!(archive.find_symbol("main"), Some(0));
+        assert_eq!(archive.find_symbol("archive_table"), Some(1));
+        for missing in ["helper", "local_step", "puts", ""] {
+            assert_eq!(archive.find_symbol(missing), None, "{}: {}", tool, missing);
+        }
+    }
+}
@@ -0,0 +1,278 @@
+mod fixtures;
+
+use elf_parser::elf::ehdr::ElfHClass;
+use elf_parser::elf::phdr::{PTypeData, PN_XNUM};
+use elf_parser::elf::shdr::SHN_LORESERVE;
+use elf_parser::options::ParseOptions;
+use elf_parser::parser::{ElfParser, ParseError, ParseWarning};
+use fixtures::{ImageBuilder, ENCODINGS};
+use std::time::{Duration, Instant};
+
+#[test]
+fn oversized_section_entries() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        image
+            .set_header(|l| l.e_shentsize, 0x8000)
+            .set_header(|l| l.e_shnum, 0xfefe);
+
+        let parser = image.parse().unwrap();
+        assert!(parser
+            .warnings
+            .iter()
+            .any(|w| matches!(w, ParseWarning::SectionHeadersOutOfBounds { .. })));
+        assert!(parser.section_headers.is_empty());
+    }
+}
+
+#[test]
+fn zero_entry_size() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        image.set_header(|l| l.e_phentsize, 0);
+        assert!(matches!(
+            image.parse(),
+            Err(ParseError::ZeroEntrySize { table: "program" })
+        ));
+
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        image.set_header(|l| l.e_shentsize, 0);
+        assert!(matches!(
+            image.parse(),
+            Err(ParseError::ZeroEntrySize { table: "section" })
+        ));
+    }
+}
+
+#[test]
+fn program_headers_past_the_end() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        image.set_header(|l| l.e_phnum, 0xfff0);
+
+        assert!(matches!(
+            image.parse(),
+            Err(ParseError::ProgramHeadersOutOfBounds)
+        ));
+    }
+}
+
+/// A file cut in the middle of its section header table keeps none of the entries
+#[test]
+fn truncated_section_header_table() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        let sh_off = image.sh_off;
+        image.bytes.truncate(image.bytes.len() - 5);
+        let file_size = image.bytes.len() as u64;
+
+        let parser = image.parse().unwrap();
+        assert!(parser.section_headers.is_empty());
+        assert!(matches!(
+            parser.warnings[..],
+            [ParseWarning::SectionHeadersOutOfBounds { sh_off: off, file_size: size, .. }]
+                if off == sh_off && size == file_size
+        ));
+        // the segments lie before the table and are read as usual
+        assert_eq!(parser.program_headers.len(), 2);
+        assert!(parser
+            .program_headers
+            .iter()
+            .all(|ph| matches!(ph.section, PTypeData::PtLoadData { .. })));
+
+        assert!(matches!(
+            ElfParser::parse_with(&image.bytes, &ParseOptions::default().strict(true)),
+            Err(ParseError::StrictViolation(_))
+        ));
+    }
+}
+
+/// A file cut before its section header table, and into the contents of its last
+/// segment, is still parsed, skipping the contents that are gone
+#[test]
+fn missing_section_header_table() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        let data = image.section(".data").offset;
+        image.bytes.truncate(data as usize + 4);
+        let file_size = image.bytes.len() as u64;
+
+        let parser = image.parse().unwrap();
+        assert!(parser.section_headers.is_empty());
+        assert!(parser
+            .warnings
+            .iter()
+            .any(|w| matches!(w, ParseWarning::SectionHeadersOutOfBounds { .. })));
+        assert!(parser.warnings.iter().any(|w| matches!(
+            w,
+            ParseWarning::SegmentOutOfBounds { idx: 1, offset, file_size: size, .. }
+                if *offset == data && *size == file_size
+        )));
+        assert!(matches!(
+            parser.program_headers[0].section,
+            PTypeData::PtLoadData { .. }
+        ));
+        assert!(matches!(
+            parser.program_headers[1].section,
+            PTypeData::Skipped
+        ));
+
+        assert!(matches!(
+            ElfParser::parse_with(&image.bytes, &ParseOptions::default().strict(true)),
+            Err(ParseError::InSegment { index: 1, .. })
+        ));
+    }
+}
+
+#[test]
+fn entry_count_limit() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+
+        let options = ParseOptions::default().max_table_entries(1);
+        let err = ElfParser::parse_with(&image.bytes, &options).unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::TooManyEntries {
+                table: "section",
+                count: 4,
+                max: 1
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "section header table has 4 entries, over the limit of 1"
+        );
+
+        let options = ParseOptions::default().max_table_entries(4);
+        assert!(matches!(
+            ElfParser::parse_with(&image.bytes, &options),
+            Ok(parser) if parser.program_headers.len() == 2
+        ));
+    }
+}
+
+#[test]
+fn reserved_section_count() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        image.set_header(|l| l.e_shnum, SHN_LORESERVE as u64);
+
+        assert!(matches!(
+            image.parse(),
+            Err(ParseError::TooManyEntries {
+                table: "section",
+                ..
+            })
+        ));
+    }
+}
+
+/// xorshift64, enough to spread the header fields without pulling in a dependency
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A value of the field, either anything it can hold or one of the values
+    /// parsers tend to get wrong
+    fn field(&mut self, max: u64, edges: &[u64]) -> u64 {
+        match self.next() % 3 {
+            0 => self.next() & max,
+            1 => edges[self.next() as usize % edges.len()],
+            _ => self.next() % 0x100,
+        }
+    }
+}
+
+#[test]
+fn randomized_table_fields_parse_in_bounded_time() {
+    let mut rng = Rng(0x9e3779b97f4a7c15);
+    let budget = Duration::from_secs(10);
+    let start = Instant::now();
+
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+        let word = match class {
+            ElfHClass::ElfClass32 => u32::MAX as u64,
+            _ => u64::MAX,
+        };
+        let options = ParseOptions::default();
+
+        for _ in 0..500 {
+            let mut image = image.clone();
+            let len = image.bytes.len() as u64;
+            image
+                .set_header(|l| l.e_phoff, rng.field(word, &[0, 8, len, word]))
+                .set_header(|l| l.e_shoff, rng.field(word, &[0, 8, len, word]))
+                .set_header(|l| l.e_phnum, rng.field(0xffff, &[0, 1, PN_XNUM as u64]))
+                .set_header(|l| l.e_shnum, rng.field(0xffff, &[0, 1, 0xfefe, 0xffff]))
+                .set_header(|l| l.e_phentsize, rng.field(0xffff, &[0, 1, 0x8000]))
+                .set_header(|l| l.e_shentsize, rng.field(0xffff, &[0, 1, 0x8000]));
+
+            let _ = ElfParser::parse_with(&image.bytes, &options);
+        }
+    }
+
+    assert!(start.elapsed() < budget, "took {:?}", start.elapsed());
+}
+
+#[test]
+fn header_table_ranges() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+        let headers = image.parse().unwrap().headers;
+        let len = image.bytes.len() as u64;
+
+        // the builder ends the file with the section header table
+        let shdrs = headers.shdr_table_range(len).unwrap().unwrap();
+        assert_eq!(shdrs.start, image.sh_off);
+        assert_eq!(shdrs.end, len);
+        assert!(matches!(
+            headers.shdr_table_range(len - 1),
+            Err(ParseError::SectionHeadersOutOfBounds)
+        ));
+
+        let phdrs = headers.phdr_table_range(len).unwrap().unwrap();
+        assert_eq!(phdrs.start, image.ph_off);
+        assert_eq!(
+            phdrs.end - phdrs.start,
+            headers.ph_num as u64 * headers.ph_ent_size as u64
+        );
+        assert_eq!(
+            headers.phdr_table_range(phdrs.end).unwrap(),
+            Some(phdrs.clone())
+        );
+        assert!(matches!(
+            headers.phdr_table_range(phdrs.end - 1),
+            Err(ParseError::ProgramHeadersOutOfBounds)
+        ));
+
+        let breakdown = image.parse().unwrap().size_breakdown();
+        assert_eq!(breakdown.section_header_table.unwrap().range, shdrs);
+        assert_eq!(breakdown.program_header_table.unwrap().range, phdrs);
+    }
+}
+
+#[test]
+fn absent_and_overflowing_table_ranges() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        image
+            .set_header(|l| l.e_shnum, 0)
+            .set_header(|l| l.e_phnum, 0);
+        let headers = image.parse().unwrap().headers;
+        assert_eq!(headers.shdr_table_range(u64::MAX).unwrap(), None);
+        assert_eq!(headers.phdr_table_range(u64::MAX).unwrap(), None);
+
+        let mut headers = headers;
+        headers.sh_num = 2;
+        headers.sh_off = u64::MAX - 8;
+        assert!(headers.shdr_table_range(u64::MAX).is_err());
+    }
+}
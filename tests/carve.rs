@@ -0,0 +1,83 @@
+mod fixtures;
+
+use elf_parser::elf::ehdr::{ElfHClass, ElfHData, ELF_MAGIC};
+use elf_parser::elf::shdr::SHT_PROGBITS;
+use elf_parser::parser::ElfParser;
+use elf_parser::scan_blob;
+use fixtures::{ImageBuilder, ENCODINGS};
+
+#[test]
+fn concatenated_images() {
+    for (class, endianness) in ENCODINGS {
+        let first = ImageBuilder::minimal(class, endianness).build();
+        let second = ImageBuilder::minimal(class, endianness)
+            .section(".rodata", SHT_PROGBITS, 0, &[0x55; 0x40])
+            .build();
+
+        let mut blob = vec![0xff; 0x10];
+        blob.extend(&first.bytes);
+        // padding holding the magic number, as the data of some other format could
+        blob.extend([0; 0x20]);
+        blob.extend(ELF_MAGIC);
+        blob.extend([0xaa; 0x40]);
+        let second_offset = blob.len() as u64;
+        blob.extend(&second.bytes);
+        blob.extend([0; 0x10]);
+
+        let found = scan_blob(&blob);
+        assert_eq!(found.len(), 2, "{:?}", found);
+
+        assert_eq!(found[0].offset, 0x10);
+        assert_eq!(found[0].size_estimate, first.bytes.len() as u64);
+        assert_eq!(found[0].header.sh_off, first.sh_off);
+        assert_eq!(found[1].offset, second_offset);
+        assert_eq!(found[1].size_estimate, second.bytes.len() as u64);
+        assert_eq!(found[1].header.ident.class, class);
+
+        // each candidate carves out to a file that parses on its own
+        for elf in &found {
+            let start = elf.offset as usize;
+            let carved = &blob[start..start + elf.size_estimate as usize];
+            let parser = ElfParser::parse(carved).unwrap();
+            assert_eq!(parser.headers.sh_off, elf.header.sh_off);
+            assert_eq!(parser.headers.ph_num, elf.header.ph_num);
+        }
+    }
+}
+
+#[test]
+fn truncated_image() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+        let len = image.bytes.len();
+
+        // the section header table is cut off, the estimate still reaches its end
+        let found = scan_blob(&image.bytes[..len - 8]);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].size_estimate, len as u64);
+
+        // not enough bytes left for a header
+        assert!(scan_blob(&image.bytes[..0x20]).is_empty());
+    }
+}
+
+#[test]
+fn false_positives() {
+    let mut image = ImageBuilder::minimal(ElfHClass::ElfClass64, ElfHData::ElfData2Lsb).build();
+
+    // magic at the very end of the blob
+    let mut blob = vec![0; 0x100];
+    blob.extend(ELF_MAGIC);
+    assert!(scan_blob(&blob).is_empty());
+    assert!(scan_blob(&ELF_MAGIC).is_empty());
+    assert!(scan_blob(&[]).is_empty());
+
+    // a header whose ident holds an unknown class
+    let mut bad_class = image.bytes.clone();
+    bad_class[4] = 7;
+    assert!(scan_blob(&bad_class).is_empty());
+
+    // a header whose entry sizes don't match its class
+    image.set_header(|l| l.e_shentsize, 0x20);
+    assert!(scan_blob(&image.bytes).is_empty());
+}
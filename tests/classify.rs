@@ -0,0 +1,106 @@
+mod fixtures;
+
+use elf_parser::classify::{ClassifyOptions, ContentClass};
+use elf_parser::elf::ehdr::{ElfHClass, ElfHData, EM_X86_64};
+use elf_parser::elf::shdr::{SHF_ALLOC, SHF_WRITE, SHT_NOBITS, SHT_PROGBITS};
+use fixtures::{ImageBuilder, ENCODINGS};
+
+const STRINGS: &[u8] = b"usage: %s <file>\n\0cannot open %s\0out of memory\0\0";
+
+/// Bytes of a xorshift generator, as evenly distributed as compressed data
+fn noise(len: usize) -> Vec<u8> {
+    let mut state = 0x2545f4914f6cdd1du64;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect()
+}
+
+/// The minimal image, along with a section of each kind of content
+fn builder(class: ElfHClass, endianness: ElfHData) -> ImageBuilder {
+    ImageBuilder::minimal(class, endianness)
+        .section(".rodata", SHT_PROGBITS, SHF_ALLOC, STRINGS)
+        .section(".bss", SHT_NOBITS, SHF_ALLOC | SHF_WRITE, &[0; 64])
+        .section(".compressed", SHT_PROGBITS, 0, &noise(4096))
+}
+
+#[test]
+fn section_classes() {
+    for (class, endianness) in ENCODINGS {
+        let image = builder(class, endianness).build();
+        let parser = image.parse().unwrap();
+        let classify = |name| parser.classify_section(image.section_idx(name)).unwrap();
+
+        assert_eq!(classify(".text"), ContentClass::Code);
+        assert_eq!(classify(".rodata"), ContentClass::AsciiStrings);
+        assert_eq!(classify(".bss"), ContentClass::ZeroFill);
+        assert_eq!(classify(".compressed"), ContentClass::HighEntropy);
+        assert_eq!(classify(".data"), ContentClass::Mixed);
+    }
+}
+
+#[test]
+fn pointer_table() {
+    for (class, endianness) in ENCODINGS {
+        // the addresses of `.text`, which lies right after the program headers
+        let text = builder(class, endianness).build().section(".text").addr.0;
+        let ctx = builder(class, endianness).ctx();
+        let mut table = vec![];
+        (0..4).for_each(|idx| ctx.put_uword(&mut table, text + idx * 4));
+
+        let image = builder(class, endianness)
+            .section(".init_array", SHT_PROGBITS, SHF_ALLOC | SHF_WRITE, &table)
+            .build();
+        assert_eq!(image.section(".text").addr.0, text);
+
+        let parser = image.parse().unwrap();
+        assert_eq!(
+            parser
+                .classify_section(image.section_idx(".init_array"))
+                .unwrap(),
+            ContentClass::PointerTable {
+                stride: ctx.word_size()
+            }
+        );
+    }
+}
+
+#[test]
+fn code_only_for_risc_v() {
+    for (class, endianness) in ENCODINGS {
+        let image = builder(class, endianness).machine(EM_X86_64).build();
+        let parser = image.parse().unwrap();
+
+        assert_ne!(
+            parser.classify_section(image.section_idx(".text")).unwrap(),
+            ContentClass::Code
+        );
+    }
+}
+
+#[test]
+fn tunable_thresholds() {
+    for (class, endianness) in ENCODINGS {
+        let image = builder(class, endianness).build();
+        let parser = image.parse().unwrap();
+        let classify = |name, options: &ClassifyOptions| {
+            parser
+                .classify_section_with(image.section_idx(name), options)
+                .unwrap()
+        };
+
+        // `.text` is mostly zeros, as the upper bytes of `nop`s
+        let options = ClassifyOptions::new().zero_ratio(0.7);
+        assert_eq!(classify(".text", &options), ContentClass::ZeroFill);
+
+        let options = ClassifyOptions::new().entropy(8.1);
+        assert_eq!(classify(".compressed", &options), ContentClass::Mixed);
+
+        let options = ClassifyOptions::new().entropy(2.0);
+        assert_eq!(classify(".data", &options), ContentClass::HighEntropy);
+    }
+}
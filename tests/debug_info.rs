@@ -0,0 +1,144 @@
+mod fixtures;
+
+use elf_parser::bytes::Address;
+use elf_parser::debug_info::Compression;
+use elf_parser::elf::chdr::{ELFCOMPRESS_ZLIB, ELFCOMPRESS_ZSTD};
+use elf_parser::elf::ehdr::ElfHClass;
+use elf_parser::elf::shdr::{SHF_COMPRESSED, SHT_PROGBITS};
+use elf_parser::elf::sym::{Elf64Sym, STB_GLOBAL, STT_FUNC};
+use fixtures::{ImageBuilder, ENCODINGS};
+
+/// Contents of a `SHF_COMPRESSED` section, its header followed by `stream`
+fn compressed(builder: &ImageBuilder, ch_type: u32, size: u64, stream: &[u8]) -> Vec<u8> {
+    let ctx = builder.ctx();
+    let mut data = vec![];
+    ctx.put_u32(&mut data, ch_type);
+    if ctx.class == ElfHClass::ElfClass64 {
+        ctx.put_u32(&mut data, 0);
+    }
+    ctx.put_uword(&mut data, size);
+    ctx.put_uword(&mut data, 1);
+    data.extend_from_slice(stream);
+    data
+}
+
+fn function() -> Elf64Sym {
+    Elf64Sym {
+        name: 0,
+        info: STB_GLOBAL << 4 | STT_FUNC,
+        other: 0,
+        shndx: 1,
+        value: Address(0x1000),
+        size: 4,
+    }
+}
+
+#[test]
+fn without_debug_info() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+        let summary = image.parse().unwrap().debug_info_summary();
+
+        assert!(!summary.has_debug_info());
+        assert!(!summary.has_symtab);
+        assert_eq!(summary.debuglink, None);
+        assert_eq!(summary.file_size(), 0);
+        assert_eq!(summary.uncompressed_size(), 0);
+    }
+}
+
+#[test]
+fn uncompressed_sections() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness)
+            .section(".debug_info", SHT_PROGBITS, 0, &[1; 40])
+            .section(".debug_str", SHT_PROGBITS, 0, b"main\0int\0")
+            .section(".debuglink", SHT_PROGBITS, 0, &[2; 8])
+            .symbols(&[("main", function())])
+            .build();
+        let parser = image.parse().unwrap();
+        let summary = parser.debug_info_summary();
+
+        let names = summary
+            .sections
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, [".debug_info", ".debug_str"]);
+        assert_eq!(
+            summary.sections[0].section,
+            image.section_idx(".debug_info")
+        );
+        assert!(summary.has_debug_info());
+        assert!(summary.has_symtab);
+        assert!(!summary.is_compressed());
+        assert_eq!(summary.file_size(), 49);
+        assert_eq!(summary.uncompressed_size(), 49);
+        assert_eq!(parser.size_report().debug_info, summary);
+    }
+}
+
+#[test]
+fn compressed_sections() {
+    for (class, endianness) in ENCODINGS {
+        let builder = ImageBuilder::minimal(class, endianness);
+        let zlib = compressed(&builder, ELFCOMPRESS_ZLIB, 0x1000, &[0x78; 16]);
+        let zstd = compressed(&builder, ELFCOMPRESS_ZSTD, 0x800, &[0x28; 8]);
+        let unknown = compressed(&builder, 9, 0x10, &[]);
+        let mut zdebug = b"ZLIB".to_vec();
+        zdebug.extend_from_slice(&0x400u64.to_be_bytes());
+        zdebug.extend_from_slice(&[0x78; 4]);
+
+        let image = builder
+            .section(".debug_info", SHT_PROGBITS, SHF_COMPRESSED, &zlib)
+            .section(".debug_line", SHT_PROGBITS, SHF_COMPRESSED, &zstd)
+            .section(".debug_str", SHT_PROGBITS, SHF_COMPRESSED, &unknown)
+            .section(".zdebug_abbrev", SHT_PROGBITS, 0, &zdebug)
+            .section(".debug_frame", SHT_PROGBITS, SHF_COMPRESSED, &[0; 2])
+            .build();
+        let summary = image.parse().unwrap().debug_info_summary();
+
+        let sections = summary
+            .sections
+            .iter()
+            .map(|s| (s.compression, s.uncompressed_size))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sections,
+            [
+                (Some(Compression::Zlib), Some(0x1000)),
+                (Some(Compression::Zstd), Some(0x800)),
+                (Some(Compression::Unknown(9)), Some(0x10)),
+                (Some(Compression::GnuZlib), Some(0x400)),
+                // too short for its header
+                (None, None),
+            ]
+        );
+        assert!(summary.is_compressed());
+        assert_eq!(
+            summary.file_size(),
+            (zlib.len() + zstd.len() + unknown.len() + zdebug.len() + 2) as u64
+        );
+        assert_eq!(
+            summary.uncompressed_size(),
+            0x1000 + 0x800 + 0x10 + 0x400 + 2
+        );
+    }
+}
+
+#[test]
+fn split_debug_info() {
+    for (class, endianness) in ENCODINGS {
+        let builder = ImageBuilder::minimal(class, endianness);
+        let mut data = b"app.debug\0\0\0".to_vec();
+        builder.ctx().put_u32(&mut data, 0x1234);
+
+        let image = builder
+            .section(".gnu_debuglink", SHT_PROGBITS, 0, &data)
+            .build();
+        let summary = image.parse().unwrap().debug_info_summary();
+
+        assert!(!summary.has_debug_info());
+        assert_eq!(summary.debuglink.as_deref(), Some("app.debug"));
+    }
+}
@@ -0,0 +1,89 @@
+mod fixtures;
+
+use elf_parser::debuglink::crc32;
+use elf_parser::elf::shdr::SHT_PROGBITS;
+use fixtures::{ImageBuilder, ENCODINGS};
+
+#[test]
+fn crc32_vectors() {
+    assert_eq!(crc32(b""), 0);
+    assert_eq!(crc32(b"a"), 0xe8b7be43);
+    assert_eq!(crc32(b"123456789"), 0xcbf43926);
+    assert_eq!(
+        crc32(b"The quick brown fox jumps over the lazy dog"),
+        0x414fa339
+    );
+}
+
+#[test]
+fn debuglink() {
+    let debug_file = b"debug info".as_slice();
+
+    // names ending right before and right on a 4 bytes boundary
+    for name in ["app.debug", "prog.debug", "a.dbg", "ab.dbg"] {
+        for (class, endianness) in ENCODINGS {
+            let builder = ImageBuilder::minimal(class, endianness);
+            let mut data = name.as_bytes().to_vec();
+            data.push(0);
+            data.resize(data.len().next_multiple_of(4), 0);
+            builder.ctx().put_u32(&mut data, crc32(debug_file));
+
+            let image = builder
+                .section(".gnu_debuglink", SHT_PROGBITS, 0, &data)
+                .build();
+            let parser = image.parse().unwrap();
+
+            assert_eq!(
+                parser.gnu_debuglink(),
+                Some((name.to_string(), crc32(debug_file)))
+            );
+            assert!(parser.matches_debuglink(debug_file));
+            assert!(!parser.matches_debuglink(b"other file"));
+        }
+    }
+}
+
+#[test]
+fn truncated_debuglink() {
+    for (class, endianness) in ENCODINGS {
+        for data in [b"app.debug\0\0\0".as_slice(), b"app.debug", b""] {
+            let image = ImageBuilder::minimal(class, endianness)
+                .section(".gnu_debuglink", SHT_PROGBITS, 0, data)
+                .build();
+
+            assert_eq!(image.parse().unwrap().gnu_debuglink(), None);
+        }
+
+        let image = ImageBuilder::minimal(class, endianness).build();
+        let parser = image.parse().unwrap();
+        assert_eq!(parser.gnu_debuglink(), None);
+        assert!(!parser.matches_debuglink(b""));
+    }
+}
+
+#[test]
+fn linker_warnings() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness)
+            .section(
+                ".gnu.warning.gets",
+                SHT_PROGBITS,
+                0,
+                b"the `gets' function is dangerous and should not be used.\0",
+            )
+            .section(".gnu.warning", SHT_PROGBITS, 0, b"linked against a stub\0")
+            .section(".gnu.warning.tmpnam", SHT_PROGBITS, 0, b"use mkstemp")
+            .build();
+
+        assert_eq!(
+            image.parse().unwrap().linker_warnings(),
+            [
+                (
+                    "gets".to_string(),
+                    "the `gets' function is dangerous and should not be used.".to_string()
+                ),
+                ("tmpnam".to_string(), "use mkstemp".to_string()),
+            ]
+        );
+    }
+}
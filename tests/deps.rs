@@ -0,0 +1,121 @@
+mod fixtures;
+
+use elf_parser::deps::DependencyGraph;
+use elf_parser::elf::ehdr::{ElfHClass, ElfHData, ElfHType};
+use elf_parser::elf::phdr::{
+    PType, DT_NEEDED, DT_NULL, DT_SONAME, DT_STRSZ, DT_STRTAB, PF_READ, PF_WRITE,
+};
+use elf_parser::elf::shdr::{SHF_ALLOC, SHF_WRITE, SHT_DYNAMIC, SHT_STRTAB};
+use elf_parser::parser::ElfParser;
+use fixtures::{Image, ImageBuilder};
+
+/// Builds a shared object named `soname` needing `needed`. `.dynstr` is only placed
+/// once the image is laid out, so it's built a first time to find its address
+fn library(soname: Option<&str>, needed: &[&str]) -> Image {
+    let build = |strtab_addr: u64| {
+        let builder = ImageBuilder::new(ElfHClass::ElfClass64, ElfHData::ElfData2Lsb)
+            .e_type(ElfHType::SharedObject);
+        let ctx = builder.ctx();
+
+        let mut strtab = vec![0u8];
+        let mut add = |s: &str| {
+            let off = strtab.len() as u64;
+            strtab.extend(s.as_bytes());
+            strtab.push(0);
+            off
+        };
+        let mut entries = needed
+            .iter()
+            .map(|n| (DT_NEEDED, add(n)))
+            .collect::<Vec<_>>();
+        if let Some(soname) = soname {
+            entries.push((DT_SONAME, add(soname)));
+        }
+        entries.extend([
+            (DT_STRTAB, strtab_addr),
+            (DT_STRSZ, strtab.len() as u64),
+            (DT_NULL, 0),
+        ]);
+
+        let mut dynamic = vec![];
+        for (tag, value) in entries {
+            ctx.put_uword(&mut dynamic, tag as u64);
+            ctx.put_uword(&mut dynamic, value);
+        }
+
+        builder
+            .section(".dynstr", SHT_STRTAB, SHF_ALLOC, &strtab)
+            .section(".dynamic", SHT_DYNAMIC, SHF_ALLOC | SHF_WRITE, &dynamic)
+            .segment(PType::PtLoad, PF_READ, &[".dynstr"])
+            .segment(PType::PtLoad, PF_READ | PF_WRITE, &[".dynamic"])
+            .segment(PType::PtDynamic, PF_READ | PF_WRITE, &[".dynamic"])
+            .build()
+    };
+
+    build(build(0).section(".dynstr").addr.0)
+}
+
+fn graph(images: &[(&str, Image)]) -> DependencyGraph {
+    let files = images
+        .iter()
+        .map(|(name, image)| (name.to_string(), image.parse().unwrap()))
+        .collect::<Vec<(String, ElfParser)>>();
+
+    DependencyGraph::build(&files)
+}
+
+#[test]
+fn resolves_needed_libraries() {
+    let graph = graph(&[
+        ("bin/app", library(None, &["libfoo.so.1", "libc.so.6"])),
+        ("lib/libfoo.so.1.2.3", library(None, &["libbar.so"])),
+        ("lib/libbar-1.0.so", library(Some("libbar.so"), &[])),
+        ("lib/libfoo.so.10", library(None, &[])),
+    ]);
+
+    let names = graph
+        .nodes
+        .iter()
+        .map(|n| n.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        ["app", "libfoo.so.1.2.3", "libbar.so", "libfoo.so.10"]
+    );
+
+    let edges = graph
+        .edges
+        .iter()
+        .map(|e| (e.from, e.needed.as_str(), e.to))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        edges,
+        [
+            (0, "libfoo.so.1", Some(1)),
+            (0, "libc.so.6", None),
+            (1, "libbar.so", Some(2)),
+        ]
+    );
+
+    assert!(graph.cycles().is_empty());
+    assert_eq!(graph.load_order().unwrap(), [2, 1, 0, 3]);
+
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("digraph dependencies {\n"));
+    assert!(dot.contains("  \"app\" -> \"libfoo.so.1.2.3\";\n"));
+    assert!(dot.contains("  \"libc.so.6\" [style=dashed, color=red];\n"));
+    assert!(dot.contains("  \"app\" -> \"libc.so.6\" [style=dashed, color=red];\n"));
+}
+
+#[test]
+fn cycles() {
+    let graph = graph(&[
+        ("a", library(Some("liba.so"), &["libb.so"])),
+        ("b", library(Some("libb.so"), &["liba.so"])),
+        ("c", library(Some("libc.so"), &["libc.so", "liba.so"])),
+        ("d", library(Some("libd.so"), &["libc.so"])),
+    ]);
+
+    assert_eq!(graph.cycles(), [vec![0, 1], vec![2]]);
+    assert_eq!(graph.load_order(), Err(vec![vec![0, 1], vec![2]]));
+}
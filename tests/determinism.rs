@@ -0,0 +1,106 @@
+mod fixtures;
+
+use elf_parser::bytes::Address;
+use elf_parser::elf::ehdr::{ElfHClass, ElfHData};
+use elf_parser::elf::sym::{Elf64Sym, STB_GLOBAL, STT_FUNC};
+use elf_parser::parser::ElfParser;
+use fixtures::ImageBuilder;
+use std::fmt::Write;
+
+/// RISC-V executable built with `-g`
+const RV64I_TEST: &[u8] = include_bytes!("../out/rv64i-test");
+
+/// Number of times each file is parsed
+const RUNS: usize = 50;
+
+/// Renders the output of every aggregate API of `parser`, the way a tool printing
+/// them would
+fn render(parser: &ElfParser) -> String {
+    let mut out = String::new();
+
+    writeln!(out, "{:?}", parser.headers).unwrap();
+    for (idx, sh) in parser.section_headers.iter().enumerate() {
+        writeln!(out, "[{}] {:?} {:?}", idx, parser.section_name(sh).ok(), sh).unwrap();
+    }
+    for ph in &parser.program_headers {
+        writeln!(out, "{:?} {:?} {:#x}", ph.p_type, ph.vaddr, ph.memsz).unwrap();
+    }
+    for st in &parser.string_tables {
+        writeln!(out, "{:?}", st.iter(true).collect::<Vec<_>>()).unwrap();
+    }
+    for table in &parser.symbol_tables {
+        for sym in &table.symbols {
+            let name = parser.symbol_name(table, sym).unwrap_or_default();
+            let by_name = parser
+                .symbols_by_name(&name)
+                .into_iter()
+                .map(|(r, _)| r)
+                .collect::<Vec<_>>();
+            writeln!(out, "{} {:?} {:?}", name, sym, by_name).unwrap();
+        }
+    }
+    writeln!(out, "{:?}", parser.dynamic_entries()).unwrap();
+    writeln!(out, "{:?}", parser.needed_libraries().ok()).unwrap();
+    writeln!(out, "{:?}", parser.exports().ok()).unwrap();
+    writeln!(out, "{:?}", parser.imports().ok()).unwrap();
+    writeln!(out, "{:?}", parser.init_array().ok()).unwrap();
+    writeln!(out, "{:?}", parser.notes_by_owner().ok()).unwrap();
+    writeln!(out, "{:?}", parser.region_map()).unwrap();
+    writeln!(out, "{:?}", parser.size_breakdown()).unwrap();
+    writeln!(out, "{:?}", parser.size_report()).unwrap();
+    writeln!(out, "{:?}", parser.warnings).unwrap();
+
+    out
+}
+
+fn assert_deterministic(data: &[u8]) {
+    let expected = render(&ElfParser::parse(data).unwrap());
+
+    for run in 1..RUNS {
+        let parser = ElfParser::parse(data).unwrap();
+        assert_eq!(render(&parser), expected, "run {} differs", run);
+    }
+}
+
+#[test]
+fn toolchain_binary_is_rendered_identically() {
+    assert_deterministic(RV64I_TEST);
+}
+
+#[test]
+fn fixture_is_rendered_identically() {
+    let symbols = (0..64)
+        .map(|i| {
+            let sym = Elf64Sym {
+                name: 0,
+                info: STB_GLOBAL << 4 | STT_FUNC,
+                other: 0,
+                shndx: 1,
+                value: Address(0x10000 + i * 4),
+                size: 4,
+            };
+            (format!("fn_{}", i), sym)
+        })
+        .collect::<Vec<_>>();
+    let symbols = symbols
+        .iter()
+        .map(|(name, sym)| (name.as_str(), *sym))
+        .collect::<Vec<_>>();
+    let image = ImageBuilder::minimal(ElfHClass::ElfClass64, ElfHData::ElfData2Lsb)
+        .symbols(&symbols)
+        .build();
+
+    assert_deterministic(&image.bytes);
+}
+
+#[test]
+fn layout_extents_are_sorted_by_offset() {
+    let parser = ElfParser::parse(RV64I_TEST).unwrap();
+    let breakdown = parser.size_breakdown();
+
+    for extents in [&breakdown.sections, &breakdown.segments] {
+        assert!(extents
+            .windows(2)
+            .all(|w| w[0].range.start <= w[1].range.start));
+    }
+}
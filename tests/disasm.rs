@@ -0,0 +1,142 @@
+#![cfg(feature = "disasm")]
+
+mod fixtures;
+
+use elf_parser::elf::ehdr::{ElfHData, EM_X86_64};
+use elf_parser::elf::phdr::{PType, PF_EXEC, PF_READ, PF_WRITE};
+use elf_parser::elf::shdr::{SHF_ALLOC, SHF_EXECINSTR, SHF_WRITE, SHT_PROGBITS};
+use elf_parser::parser::{ElfParser, ParseError};
+use fixtures::{Image, ImageBuilder, DATA, ENCODINGS, PAGE_SIZE};
+
+/// `addi a0,a0,1`, `c.li a1,5`, `ret`, then the first half of `addi a0,a0,1`
+const CODE: [u8; 12] = [
+    0x13, 0x05, 0x15, 0x00, 0x95, 0x45, 0x67, 0x80, 0x00, 0x00, 0x13, 0x05,
+];
+
+/// An image whose code segment ends halfway through its last instruction, followed in
+/// the file by the data segment, which is loaded a page away from the code
+fn image(endianness: ElfHData) -> Image {
+    let (class, _) = ENCODINGS[0];
+    let mut image = ImageBuilder::new(class, endianness)
+        .section(".text", SHT_PROGBITS, SHF_ALLOC | SHF_EXECINSTR, &CODE)
+        .section(".data", SHT_PROGBITS, SHF_ALLOC | SHF_WRITE, &DATA)
+        .segment(PType::PtLoad, PF_READ | PF_EXEC, &[".text"])
+        .segment(PType::PtLoad, PF_READ | PF_WRITE, &[".data"])
+        .build();
+    let addr = image.section(".data").addr.0;
+    image.set_segment(1, |l| l.p_vaddr, addr + PAGE_SIZE);
+
+    image
+}
+
+fn listing(parser: &ElfParser, addr: u64, n_insns: usize) -> Vec<(u64, usize, String)> {
+    parser
+        .read_code(addr, n_insns)
+        .unwrap()
+        .into_iter()
+        .map(|i| (i.address, i.bytes.len(), i.mnemonic))
+        .collect()
+}
+
+#[test]
+fn decodes_mixed_lengths() {
+    for (class, _) in ENCODINGS {
+        let image = ImageBuilder::new(class, ElfHData::ElfData2Lsb)
+            .section(
+                ".text",
+                SHT_PROGBITS,
+                SHF_ALLOC | SHF_EXECINSTR,
+                &CODE[..10],
+            )
+            .segment(PType::PtLoad, PF_READ | PF_EXEC, &[".text"])
+            .build();
+        let parser = image.parse().unwrap();
+        let text = image.section(".text").addr.0;
+
+        let code = parser.read_code(text, 3).unwrap();
+        assert_eq!(code[0].bytes, CODE[..4]);
+        assert_eq!(code[1].bytes, CODE[4..6]);
+        assert_eq!(
+            code.iter()
+                .map(|i| (i.address - text, i.mnemonic.as_str(), i.operands.as_str()))
+                .collect::<Vec<_>>(),
+            [(0, "addi", "a0,a0,1"), (4, "li", "a1,5"), (6, "ret", "")]
+        );
+        // fewer instructions when asked for fewer, no more than the segment holds
+        assert_eq!(listing(&parser, text, 1).len(), 1);
+        assert_eq!(listing(&parser, text, 100).len(), 3);
+        assert!(listing(&parser, text, 0).is_empty());
+    }
+}
+
+/// Decoding stops at an instruction the segment only holds part of, rather than
+/// reading the rest from the bytes following it in the file
+#[test]
+fn instruction_straddling_segment_end() {
+    let image = image(ElfHData::ElfData2Lsb);
+    let parser = image.parse().unwrap();
+    let text = image.section(".text");
+    assert_eq!(
+        image.section(".data").offset,
+        text.offset + CODE.len() as u64
+    );
+
+    let code = listing(&parser, text.addr.0, 10);
+    assert_eq!(
+        code.iter().map(|(_, len, _)| *len).collect::<Vec<_>>(),
+        [4, 2, 4]
+    );
+    assert!(listing(&parser, text.addr.0 + 10, 1).is_empty());
+}
+
+#[test]
+fn unmapped_and_overflowing_addresses() {
+    for (_, endianness) in ENCODINGS {
+        let image = image(endianness);
+        let parser = image.parse().unwrap();
+        let text = image.section(".text").addr.0;
+
+        for addr in [
+            0,
+            text - 2,
+            text + CODE.len() as u64,
+            u64::MAX - 1,
+            u64::MAX,
+        ] {
+            assert!(listing(&parser, addr, 4).is_empty(), "{:#x}", addr);
+        }
+    }
+}
+
+/// Segments describing more bytes than the file holds only decode what's there
+#[test]
+fn segment_past_end_of_file() {
+    for (_, endianness) in ENCODINGS {
+        let mut image = image(endianness);
+        let (offset, addr) = (image.section(".text").offset, image.section(".text").addr.0);
+        image.bytes.truncate(offset as usize + 6);
+
+        let mut parser = ElfParser::parse(&image.bytes).unwrap();
+        assert_eq!(parser.program_headers[0].filesz, CODE.len() as u64);
+        let code = parser.read_code(addr, 10).unwrap();
+        assert!(code
+            .iter()
+            .all(|i| i.address + i.bytes.len() as u64 <= addr + 6));
+
+        parser.program_headers[0].offset = u64::MAX - 4;
+        assert!(parser.read_code(addr, 10).unwrap().is_empty());
+    }
+}
+
+#[test]
+fn other_machines() {
+    let image = ImageBuilder::minimal(ENCODINGS[0].0, ElfHData::ElfData2Lsb)
+        .machine(EM_X86_64)
+        .build();
+    let parser = image.parse().unwrap();
+
+    assert!(matches!(
+        parser.read_code(image.section(".text").addr.0, 1),
+        Err(ParseError::UnsupportedMachine(EM_X86_64))
+    ));
+}
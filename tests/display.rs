@@ -0,0 +1,129 @@
+mod fixtures;
+
+use elf_parser::bytes::Address;
+use elf_parser::display::{DisplayTable, Named};
+use elf_parser::elf::ehdr::{ElfHClass, ElfHData};
+use elf_parser::elf::phdr::{DynValue, ELF64Dyn, DT_FLAGS_1, DT_NEEDED, DT_STRTAB};
+use elf_parser::elf::sym::{Elf64Sym, STB_GLOBAL, STT_FUNC};
+use fixtures::ImageBuilder;
+
+// the outputs below are pinned, as tools embed them in their own reports: a change
+// of wording or column width must show up here
+
+#[test]
+fn file_header() {
+    let image = ImageBuilder::minimal(ElfHClass::ElfClass64, ElfHData::ElfData2Lsb).build();
+    let parser = image.parse().unwrap();
+
+    assert_eq!(
+        parser.headers.to_string(),
+        "\
+ELF Header:
+  Magic:   7f 45 4c 46 02 01 01 00 00 00 00 00 00 00 00 00
+  Class:                             ELF64
+  Data:                              2's complement, little endian
+  Version:                           1 (current)
+  OS/ABI:                            UNIX - System V
+  ABI Version:                       0
+  Type:                              EXEC (Executable file)
+  Machine:                           RISC-V
+  Version:                           0x1
+  Entry point address:               0x100b0
+  Start of program headers:          64 (bytes into file)
+  Start of section headers:          224 (bytes into file)
+  Flags:                             0x0
+  Size of this header:               64 (bytes)
+  Size of program headers:           56 (bytes)
+  Number of program headers:         2
+  Size of section headers:           64 (bytes)
+  Number of section headers:         4
+  Section header string table index: 3"
+    );
+}
+
+#[test]
+fn section_table() {
+    let image = ImageBuilder::minimal(ElfHClass::ElfClass64, ElfHData::ElfData2Lsb).build();
+    let parser = image.parse().unwrap();
+    let rows = parser
+        .section_headers
+        .iter()
+        .zip(&image.names)
+        .map(|(sh, name)| Named::new(name, sh))
+        .collect();
+
+    assert_eq!(
+        DisplayTable::new("Section Headers", rows).to_string(),
+        "\
+Section Headers:
+  [Nr] Name              Type             Address          Off      Size             EntSize          Flg  Lk  Inf  Al
+  [ 0]                   NULL             0000000000000000 00000000 0000000000000000 0000000000000000       0    0   0
+  [ 1] .text             PROGBITS         00000000000100b0 000000b0 0000000000000010 0000000000000000  AX   0    0   8
+  [ 2] .data             PROGBITS         00000000000100c0 000000c0 0000000000000008 0000000000000000  WA   0    0   8
+  [ 3] .shstrtab         STRTAB           0000000000000000 000000c8 0000000000000017 0000000000000000       0    0   1
+"
+    );
+}
+
+#[test]
+fn program_table() {
+    let image = ImageBuilder::minimal(ElfHClass::ElfClass64, ElfHData::ElfData2Lsb).build();
+    let parser = image.parse().unwrap();
+
+    assert_eq!(
+        DisplayTable::new("Program Headers", parser.program_headers.iter().collect())
+            .to_string(),
+        "\
+Program Headers:
+  [Nr] Type           Offset             VirtAddr           PhysAddr           FileSiz            MemSiz             Flg Align
+  [ 0] LOAD           0x00000000000000b0 0x00000000000100b0 0x00000000000100b0 0x0000000000000010 0x0000000000000010 R E 0x1000
+  [ 1] LOAD           0x00000000000000c0 0x00000000000100c0 0x00000000000100c0 0x0000000000000008 0x0000000000000008 RW  0x1000
+"
+    );
+}
+
+#[test]
+fn symbol_row() {
+    let sym = Elf64Sym {
+        name: 1,
+        info: (STB_GLOBAL << 4) | STT_FUNC,
+        other: 0,
+        shndx: 1,
+        value: Address(0x10078),
+        size: 16,
+    };
+
+    assert_eq!(
+        Named::new("_start", &sym).to_string(),
+        "0000000000010078    16 FUNC    GLOBAL DEFAULT    1 _start"
+    );
+    assert_eq!(
+        sym.to_string(),
+        "0000000000010078    16 FUNC    GLOBAL DEFAULT    1 "
+    );
+}
+
+#[test]
+fn dynamic_rows() {
+    let rows = [
+        (DT_NEEDED, DynValue::DVal(1), "(NEEDED)             0x1"),
+        (
+            DT_STRTAB,
+            DynValue::DPtr(Address(0x10200)),
+            "(STRTAB)             0x10200",
+        ),
+        (
+            DT_FLAGS_1,
+            DynValue::DVal(0x8000001),
+            "(FLAGS_1)            Flags: NOW PIE",
+        ),
+        (0x6ffff123, DynValue::Raw(7), "(0x6ffff123)         raw 0x7"),
+    ];
+
+    for (d_tag, d_un, expected) in rows {
+        assert_eq!(
+            ELF64Dyn { d_tag, d_un }.to_string(),
+            format!("0x{:016x} {}", d_tag, expected)
+        );
+    }
+}
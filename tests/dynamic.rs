@@ -0,0 +1,330 @@
+mod fixtures;
+
+use elf_parser::bytes::{Address, ParseContext};
+use elf_parser::elf::ehdr::{ElfHClass, ElfHData};
+use elf_parser::elf::phdr::{
+    dyn_tag_kind, DynKind, DynValue, ELF64Dyn, PType, DT_GNU_HASH, DT_HASH, DT_JMPREL, DT_NEEDED,
+    DT_NULL, DT_PLTREL, DT_PLTRELSZ, DT_RELA, DT_RELACOUNT, DT_RELAENT, DT_RELASZ,
+    DT_RISCV_VARIANT_CC, DT_STRSZ, DT_STRTAB, DT_SYMENT, DT_SYMTAB, DT_VERNEED, DT_VERNEEDNUM,
+    DT_VERSYM, PF_READ, PF_WRITE,
+};
+use elf_parser::elf::shdr::{
+    SHF_ALLOC, SHF_WRITE, SHT_DYNAMIC, SHT_DYNSYM, SHT_HASH, SHT_PROGBITS, SHT_RELA, SHT_STRTAB,
+};
+use elf_parser::elf::sym::{Elf64Sym, STB_GLOBAL, STT_FUNC};
+use elf_parser::layout;
+use elf_parser::options::ParseOptions;
+use elf_parser::parser::{ElfParser, ParseError};
+use fixtures::{Image, ImageBuilder, ENCODINGS};
+
+/// RISC-V executable linked against glibc
+const RV64I_TEST: &[u8] = include_bytes!("../out/rv64i-test");
+
+#[test]
+fn glibc_linked_entries() {
+    let parser = ElfParser::parse(RV64I_TEST).unwrap();
+    let entry = |tag| &parser.dynamic_entry(tag).unwrap().d_un;
+
+    assert_eq!(*entry(DT_NEEDED), DynValue::DVal(0x13));
+    assert_eq!(*entry(DT_PLTRELSZ), DynValue::DVal(24));
+    assert_eq!(*entry(DT_GNU_HASH), DynValue::DPtr(Address(0x102d0)));
+    assert_eq!(*entry(DT_VERSYM), DynValue::DPtr(Address(0x1037a)));
+    assert_eq!(*entry(DT_VERNEED), DynValue::DPtr(Address(0x10380)));
+    assert_eq!(*entry(DT_VERNEEDNUM), DynValue::DVal(1));
+    assert!(parser
+        .dynamic_entries()
+        .unwrap()
+        .iter()
+        .all(|d| !matches!(d.d_un, DynValue::Raw(_))));
+}
+
+#[test]
+fn tag_kinds() {
+    for (tag, kind) in [
+        (DT_PLTRELSZ, Some(DynKind::Val)),
+        (DT_RELACOUNT, Some(DynKind::Val)),
+        (DT_RISCV_VARIANT_CC, Some(DynKind::Val)),
+        (DT_GNU_HASH, Some(DynKind::Ptr)),
+        // unknown tags below `DT_LOOS` follow the even and odd rule
+        (0x40, Some(DynKind::Ptr)),
+        (0x41, Some(DynKind::Val)),
+        (0x6ffffef6, None),
+        (0x7000abcd, None),
+        (-1, None),
+    ] {
+        assert_eq!(dyn_tag_kind(tag), kind, "{:#x}", tag);
+    }
+}
+
+#[test]
+fn vendor_tags_stay_raw() {
+    for (class, endianness) in ENCODINGS {
+        let builder = ImageBuilder::minimal(class, endianness);
+        let ctx = builder.ctx();
+        let mut dynamic = vec![];
+        for (tag, value) in [(0x6ffffef6, 0x1000), (0x7000abcd, 7), (DT_NULL, 0)] {
+            ctx.put_uword(&mut dynamic, tag as u64);
+            ctx.put_uword(&mut dynamic, value);
+        }
+        let image = builder
+            .section(".dynamic", SHT_DYNAMIC, SHF_ALLOC | SHF_WRITE, &dynamic)
+            .segment(PType::PtDynamic, PF_READ | PF_WRITE, &[".dynamic"])
+            .build();
+
+        let parser = image.parse().unwrap();
+        let entries = parser.dynamic_entries().unwrap();
+        assert_eq!(
+            entries[..2],
+            [
+                ELF64Dyn {
+                    d_tag: 0x6ffffef6,
+                    d_un: DynValue::Raw(0x1000),
+                },
+                ELF64Dyn {
+                    d_tag: 0x7000abcd,
+                    d_un: DynValue::Raw(7),
+                },
+            ]
+        );
+        assert_eq!(entries[1].value(), 7);
+        assert!(entries[0].to_string().ends_with("raw 0x1000"));
+    }
+}
+
+/// Dynamic entries of `linked`, in the order `set_entry` indexes them
+const ENTRY_HASH: usize = 0;
+const ENTRY_STRTAB: usize = 2;
+const ENTRY_STRSZ: usize = 3;
+const ENTRY_SYMENT: usize = 4;
+const ENTRY_RELASZ: usize = 6;
+
+const IMPORTS: [&str; 2] = ["malloc", "free"];
+const R_RISCV_64: u32 = 2;
+const R_RISCV_JUMP_SLOT: u32 = 5;
+
+fn relocation(ctx: &ParseContext, out: &mut Vec<u8>, offset: u64, sym: u32, r_type: u32) {
+    let info = match ctx.class {
+        ElfHClass::ElfClass64 => (sym as u64) << 32 | r_type as u64,
+        _ => (sym as u64) << 8 | r_type as u64,
+    };
+    ctx.put_uword(out, offset);
+    ctx.put_uword(out, info);
+    ctx.put_uword(out, 8);
+}
+
+/// An executable importing `IMPORTS`, whose tables the dynamic segment locates by
+/// address: one data relocation and one PLT relocation
+fn linked(class: ElfHClass, endianness: ElfHData) -> Image {
+    let sym_size = layout::sym::for_class(class).size;
+    let rela_size = layout::rela::for_class(class).size;
+    let build = |first: Option<&Image>| {
+        let builder = ImageBuilder::new(class, endianness);
+        let ctx = builder.ctx();
+        let addr = |name| first.map_or(0, |image: &Image| image.section(name).addr.0);
+
+        let mut hash = vec![];
+        for word in [1, IMPORTS.len() as u32 + 1, 0, 0, 0, 0] {
+            ctx.put_u32(&mut hash, word);
+        }
+
+        let mut dynstr = b"\0".to_vec();
+        let mut dynsym = vec![0; sym_size];
+        for name in IMPORTS {
+            let symbol = Elf64Sym {
+                name: dynstr.len() as u32,
+                info: STB_GLOBAL << 4 | STT_FUNC,
+                other: 0,
+                shndx: 0,
+                value: Address(0),
+                size: 0,
+            };
+            symbol.write(&ctx, &mut dynsym);
+            dynstr.extend(name.as_bytes());
+            dynstr.push(0);
+        }
+
+        let mut rela_dyn = vec![];
+        relocation(&ctx, &mut rela_dyn, addr(".data"), 1, R_RISCV_64);
+        let mut rela_plt = vec![];
+        relocation(&ctx, &mut rela_plt, addr(".data") + 8, 2, R_RISCV_JUMP_SLOT);
+
+        let mut dynamic = vec![];
+        for (tag, value) in [
+            (DT_HASH, addr(".hash")),
+            (DT_SYMTAB, addr(".dynsym")),
+            (DT_STRTAB, addr(".dynstr")),
+            (DT_STRSZ, dynstr.len() as u64),
+            (DT_SYMENT, sym_size as u64),
+            (DT_RELA, addr(".rela.dyn")),
+            (DT_RELASZ, rela_size as u64),
+            (DT_RELAENT, rela_size as u64),
+            (DT_JMPREL, addr(".rela.plt")),
+            (DT_PLTRELSZ, rela_size as u64),
+            (DT_PLTREL, DT_RELA as u64),
+            (DT_NULL, 0),
+        ] {
+            ctx.put_uword(&mut dynamic, tag as u64);
+            ctx.put_uword(&mut dynamic, value);
+        }
+
+        builder
+            .section(".hash", SHT_HASH, SHF_ALLOC, &hash)
+            .section(".dynsym", SHT_DYNSYM, SHF_ALLOC, &dynsym)
+            .section(".dynstr", SHT_STRTAB, SHF_ALLOC, &dynstr)
+            .section(".rela.dyn", SHT_RELA, SHF_ALLOC, &rela_dyn)
+            .section(".rela.plt", SHT_RELA, SHF_ALLOC, &rela_plt)
+            .section(".data", SHT_PROGBITS, SHF_ALLOC | SHF_WRITE, &[0; 16])
+            .section(".dynamic", SHT_DYNAMIC, SHF_ALLOC | SHF_WRITE, &dynamic)
+            .segment(
+                PType::PtLoad,
+                PF_READ,
+                &[".hash", ".dynsym", ".dynstr", ".rela.dyn", ".rela.plt"],
+            )
+            .segment(PType::PtLoad, PF_READ | PF_WRITE, &[".data", ".dynamic"])
+            .segment(PType::PtDynamic, PF_READ | PF_WRITE, &[".dynamic"])
+            .build()
+    };
+
+    build(Some(&build(None)))
+}
+
+/// Overwrites the value of dynamic entry `idx`
+fn set_entry(image: &mut Image, idx: usize, value: u64) {
+    let word = image.ctx.word_size();
+    let off = image.section(".dynamic").offset as usize + (2 * idx + 1) * word;
+    let mut bytes = vec![];
+    image.ctx.put_uword(&mut bytes, value);
+    image.set_bytes(off, &bytes);
+}
+
+fn assert_tables_resolved(image: &Image, parser: &ElfParser) {
+    let strtab = &parser.dynamic_entries().unwrap()[ENTRY_STRTAB];
+    let dynstr = image.section(".dynstr");
+    assert_eq!(parser.resolve_dyn_ptr(strtab).unwrap(), dynstr.offset);
+    assert_eq!(
+        parser.dynamic_string_table().unwrap(),
+        &image.bytes[dynstr.offset as usize..][..dynstr.size as usize]
+    );
+
+    let symbols = parser.dynamic_symbol_table().unwrap();
+    let names = symbols
+        .iter()
+        .map(|s| parser.dynamic_string(s.name).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["", "malloc", "free"]);
+
+    let data = image.section(".data").addr;
+    let relocations = parser.dynamic_relocations().unwrap();
+    assert_eq!(
+        relocations
+            .iter()
+            .map(|r| (r.offset, r.sym, r.r_type, r.addend))
+            .collect::<Vec<_>>(),
+        [
+            (data, 1, R_RISCV_64, 8),
+            (Address(data.0 + 8), 2, R_RISCV_JUMP_SLOT, 8),
+        ]
+    );
+}
+
+#[test]
+fn tables_located_by_address() {
+    for (class, endianness) in ENCODINGS {
+        let image = linked(class, endianness);
+        assert_tables_resolved(&image, &image.parse().unwrap());
+    }
+}
+
+#[test]
+fn tables_located_without_section_headers() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = linked(class, endianness);
+        let expected = linked(class, endianness);
+        image
+            .set_header(|l| l.e_shoff, 0)
+            .set_header(|l| l.e_shnum, 0)
+            .set_header(|l| l.e_shstrndx, 0);
+
+        let parser = image.parse().unwrap();
+        assert!(parser.section_headers.is_empty());
+        assert_tables_resolved(&expected, &parser);
+    }
+}
+
+/// Debug rendering of the root cause of the error of `result`
+fn root<T>(result: Result<T, ParseError>) -> String {
+    match result {
+        Ok(_) => panic!("resolved"),
+        Err(e) => format!("{:?}", e.root_cause()),
+    }
+}
+
+/// Sizes and addresses running past the file, or past the largest address, are
+/// reported rather than overflowing
+#[test]
+fn malformed_pointers() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = linked(class, endianness);
+        set_entry(&mut image, ENTRY_STRSZ, u64::MAX);
+        let parser = image.parse().unwrap();
+        assert_eq!(root(parser.dynamic_string_table()), "InvalidLength");
+        assert_eq!(root(parser.dynamic_string(1)), "InvalidLength");
+
+        let mut image = linked(class, endianness);
+        set_entry(&mut image, ENTRY_STRTAB, 1);
+        let parser = image.parse().unwrap();
+        assert_eq!(root(parser.dynamic_string_table()), "UnmappedAddress(1)");
+
+        let mut image = linked(class, endianness);
+        set_entry(&mut image, ENTRY_RELASZ, u64::MAX);
+        let parser = image.parse().unwrap();
+        assert_eq!(root(parser.dynamic_relocations()), "InvalidLength");
+
+        let mut image = linked(class, endianness);
+        set_entry(&mut image, ENTRY_SYMENT, 7);
+        let parser = image.parse().unwrap();
+        assert_eq!(root(parser.dynamic_symbol_table()), "InvalidLength");
+
+        // a symbol count too large for the file
+        let mut image = linked(class, endianness);
+        let hash = image.section(".hash").offset as usize;
+        let mut nchain = vec![];
+        image.ctx.put_u32(&mut nchain, u32::MAX);
+        image.set_bytes(hash + 4, &nchain);
+        let parser = image.parse().unwrap();
+        assert_eq!(root(parser.dynamic_symbol_table()), "InvalidLength");
+
+        // a hash table at the very end of the file
+        let mut image = linked(class, endianness);
+        let len = image.bytes.len();
+        let vaddr =
+            image.section(".hash").addr.0 + (len - image.section(".hash").offset as usize) as u64;
+        set_entry(&mut image, ENTRY_HASH, vaddr);
+        let parser = image.parse().unwrap();
+        assert!(parser.dynamic_symbol_table().is_err());
+    }
+}
+
+/// A loadable segment whose file image starts near the largest offset, left unread
+#[test]
+fn segment_offset_overflow() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = linked(class, endianness);
+        let max = match class {
+            ElfHClass::ElfClass64 => u64::MAX,
+            _ => u32::MAX as u64,
+        };
+        image.set_segment(0, |l| l.p_offset, max - 4);
+
+        let options = ParseOptions::default().load_segment_data(false);
+        let parser = ElfParser::parse_with(&image.bytes, &options).unwrap();
+        assert!(matches!(
+            parser.segment_data(0),
+            Err(ParseError::InvalidLength)
+        ));
+        let strtab = &parser.dynamic_entries().unwrap()[ENTRY_STRTAB];
+        assert!(parser.resolve_dyn_ptr(strtab).is_err() || parser.dynamic_string_table().is_err());
+        assert!(parser.dynamic_symbol_table().is_err());
+        assert!(parser.dynamic_relocations().is_err());
+    }
+}
@@ -0,0 +1,289 @@
+mod fixtures;
+
+use elf_parser::bytes::Address;
+use elf_parser::editor::{EditError, ElfEditor, SymtabEditor};
+use elf_parser::elf::shdr::{SHF_ALLOC, SHF_EXECINSTR, SHT_PROGBITS, SHT_SYMTAB};
+use elf_parser::elf::sym::{Elf64Sym, STB_GLOBAL, STB_LOCAL, STB_WEAK, STT_FUNC, STT_NOTYPE};
+use elf_parser::layout;
+use elf_parser::parser::ElfParser;
+use fixtures::{Image, ImageBuilder, ENCODINGS};
+use std::ops::Range;
+
+fn image(builder: ImageBuilder) -> Image {
+    builder
+        .section(
+            ".text.secret",
+            SHT_PROGBITS,
+            SHF_ALLOC | SHF_EXECINSTR,
+            &[0x13; 16],
+        )
+        .section(".comment", SHT_PROGBITS, 0, b"GCC\0")
+        .build()
+}
+
+/// Checks `edited` only differs from `image` in the ELF header and `shstrtab`, past the
+/// bytes appended to it
+fn assert_untouched(image: &Image, edited: &[u8], shstrtab: Range<usize>) {
+    let ehdr = layout::ehdr::for_class(image.ctx.class).size;
+    for (off, (old, new)) in image.bytes.iter().zip(edited).enumerate().skip(ehdr) {
+        assert!(old == new || shstrtab.contains(&off), "byte {:#x}", off);
+    }
+}
+
+#[test]
+fn rename_in_place() {
+    for (class, endianness) in ENCODINGS {
+        let image = image(ImageBuilder::minimal(class, endianness));
+        let shstrtab = image.section(".shstrtab");
+        let range = shstrtab.offset as usize..(shstrtab.offset + shstrtab.size) as usize;
+
+        let mut editor = ElfEditor::new(&image.parse().unwrap());
+        editor.rename_section(".text.secret", ".text.a").unwrap();
+        assert_eq!(
+            editor.section_index(".text.a"),
+            Some(image.section_idx(".text.secret"))
+        );
+        assert_eq!(editor.section_index(".text.secret"), None);
+        let edited = editor.save().unwrap();
+
+        assert_untouched(&image, &edited, range.clone());
+        let parser = ElfParser::parse(&edited).unwrap();
+        let (idx, sh) = parser.section_by_name(".text.a").unwrap();
+        assert_eq!(idx, image.section_idx(".text.secret"));
+        assert_eq!(sh.offset, image.section(".text.secret").offset);
+        assert!(parser.section_by_name(".text.secret").is_none());
+        assert!(parser.section_by_name(".comment").is_some());
+
+        let (_, sh) = parser.section_by_name(".shstrtab").unwrap();
+        assert_eq!(sh.offset as usize, range.start);
+        assert!(sh.size as usize <= range.len());
+    }
+}
+
+#[test]
+fn batched_renames_grow_the_table() {
+    for (class, endianness) in ENCODINGS {
+        let image = image(ImageBuilder::minimal(class, endianness));
+        let shstrtab = image.section(".shstrtab");
+        let range = shstrtab.offset as usize..(shstrtab.offset + shstrtab.size) as usize;
+        let long = ".text.".to_string() + &"x".repeat(range.len());
+
+        let mut editor = ElfEditor::new(&image.parse().unwrap());
+        editor.rename_section(".text.secret", ".text.tmp").unwrap();
+        editor.rename_section(".text.tmp", &long).unwrap();
+        editor.rename_section(".comment", ".note.build").unwrap();
+        let edited = editor.save().unwrap();
+
+        // the old table is left as is, the new one is appended
+        assert_untouched(&image, &edited, 0..0);
+        let parser = ElfParser::parse(&edited).unwrap();
+        let (_, sh) = parser.section_by_name(".shstrtab").unwrap();
+        assert!(sh.offset as usize >= image.bytes.len());
+        let names = parser
+            .section_headers
+            .iter()
+            .map(|sh| parser.section_name(sh).unwrap())
+            .collect::<Vec<_>>();
+        assert!(names.contains(&long));
+        assert!(names.contains(&".note.build".to_string()));
+        assert!(!names.contains(&".text.secret".to_string()));
+    }
+}
+
+#[test]
+fn rename_missing_section() {
+    let (class, endianness) = ENCODINGS[0];
+    let image = image(ImageBuilder::minimal(class, endianness));
+
+    let mut editor = ElfEditor::new(&image.parse().unwrap());
+    assert!(matches!(
+        editor.rename_section(".text.public", ".text"),
+        Err(EditError::SectionNotFound(name)) if name == ".text.public"
+    ));
+}
+
+#[test]
+fn nop_sled() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+        let text = image.section(".text");
+        let addr = text.addr.0 + 4;
+
+        let mut editor = ElfEditor::new(&image.parse().unwrap());
+        editor.write_vaddr_exec(addr, &[0x90; 8]).unwrap();
+        assert_eq!(editor.read_vaddr(addr, 8).unwrap(), [0x90; 8]);
+        let edited = editor.save().unwrap();
+
+        let off = text.offset as usize;
+        assert_eq!(edited[off..off + 4], image.bytes[off..off + 4]);
+        assert_eq!(edited[off + 4..off + 12], [0x90; 8]);
+        assert_eq!(edited[off + 12..off + 16], image.bytes[off + 12..off + 16]);
+    }
+}
+
+#[test]
+fn refused_writes() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        let text = *image.section(".text");
+        let data = *image.section(".data");
+        let (text_end, data_addr) = (text.addr.0 + text.size, data.addr.0);
+        // a zero-filled tail past the contents of `.data`
+        image.set_segment(1, |l| l.p_memsz, data.size + 0x100);
+
+        let parser = image.parse().unwrap();
+        let untouched = ElfEditor::new(&parser).save().unwrap();
+        let mut editor = ElfEditor::new(&parser);
+        for (addr, len) in [(text_end - 4, 8), (0, 4), (u64::MAX - 2, 4)] {
+            assert!(matches!(
+                editor.write_vaddr(addr, &vec![0x90; len]),
+                Err(EditError::UnmappedRange { .. })
+            ));
+        }
+        assert!(matches!(
+            editor.write_vaddr(data_addr + data.size - 4, &[0; 8]),
+            Err(EditError::BeyondFileImage { len: 8, .. })
+        ));
+        assert!(matches!(
+            editor.read_vaddr(data_addr + data.size, 1),
+            Err(EditError::BeyondFileImage { .. })
+        ));
+        assert!(matches!(
+            editor.write_vaddr_exec(data_addr, &[0x90; 4]),
+            Err(EditError::NotExecutable(addr)) if addr == data_addr
+        ));
+
+        // nothing was written by the refused writes
+        assert_eq!(editor.save().unwrap(), untouched);
+    }
+}
+
+/// Names, bindings and values of the `.symtab` of `parser`, null symbol included
+fn symtab(parser: &ElfParser) -> Vec<(String, u8, u64)> {
+    let table = parser
+        .symbol_tables
+        .iter()
+        .find(|t| t.sh_type == SHT_SYMTAB)
+        .unwrap();
+    table
+        .symbols
+        .iter()
+        .map(|s| (parser.symbol_name(table, s).unwrap(), s.bind(), s.value.0))
+        .collect()
+}
+
+#[test]
+fn symbols_injected_into_stripped_file() {
+    for (class, endianness) in ENCODINGS {
+        let image = image(ImageBuilder::minimal(class, endianness));
+        let parser = image.parse().unwrap();
+        let text = image.section_idx(".text") as u16;
+        let addr = image.section(".text").addr.0;
+
+        let mut symbols = SymtabEditor::new();
+        symbols
+            .add("main", addr, 8, STB_GLOBAL, STT_FUNC, text)
+            .add("loop", addr + 8, 4, STB_LOCAL, STT_FUNC, text)
+            .add("handler", addr + 12, 4, STB_WEAK, STT_FUNC, text)
+            .add("loop", addr + 12, 0, STB_LOCAL, STT_NOTYPE, text);
+        let mut editor = ElfEditor::new(&parser);
+        symbols.apply(&mut editor).unwrap();
+        let edited = editor.save().unwrap();
+
+        let parser = ElfParser::parse(&edited).unwrap();
+        // null symbol first, then the locals in the order they were added
+        assert_eq!(
+            symtab(&parser),
+            [
+                (String::new(), STB_LOCAL, 0),
+                ("loop".to_string(), STB_LOCAL, addr + 8),
+                ("loop".to_string(), STB_LOCAL, addr + 12),
+                ("main".to_string(), STB_GLOBAL, addr),
+                ("handler".to_string(), STB_WEAK, addr + 12),
+            ]
+        );
+
+        let (idx, sh) = parser.section_by_name(".symtab").unwrap();
+        assert_eq!(sh.info, 3);
+        let (strtab, _) = parser.section_by_name(".strtab").unwrap();
+        assert_eq!(sh.link as usize, strtab);
+        // each name stored once
+        assert_eq!(
+            parser.section_data(strtab).unwrap(),
+            b"\0loop\0main\0handler\0"
+        );
+        let table = parser
+            .symbol_tables
+            .iter()
+            .find(|t| t.section_idx == idx)
+            .unwrap();
+        assert_eq!(table.symbols[1].name, table.symbols[2].name);
+        assert_eq!(table.symbols[3].sym_type(), STT_FUNC);
+        assert_eq!(table.symbols[3].size, 8);
+        assert_eq!(table.symbols[3].shndx, text);
+    }
+}
+
+#[test]
+fn symbols_renamed_in_place_of_the_table() {
+    for (class, endianness) in ENCODINGS {
+        let builder = ImageBuilder::minimal(class, endianness);
+        let addr = builder.clone().build().section(".text").addr.0;
+        let sym = |bind: u8, value: u64| Elf64Sym {
+            name: 0,
+            info: (bind << 4) | STT_FUNC,
+            other: 0,
+            shndx: 1,
+            value: Address(value),
+            size: 4,
+        };
+        let image = builder
+            .symbols(&[
+                ("helper", sym(STB_LOCAL, addr + 4)),
+                ("_start", sym(STB_GLOBAL, addr)),
+            ])
+            .build();
+        let parser = image.parse().unwrap();
+        let sections = parser.section_headers.len();
+
+        let mut symbols = SymtabEditor::from_parser(&parser).unwrap();
+        assert_eq!(symbols.symbols().len(), 2);
+        assert!(matches!(
+            symbols.rename("missing", "other"),
+            Err(EditError::SymbolNotFound(name)) if name == "missing"
+        ));
+        symbols
+            .rename("helper", "checksum")
+            .unwrap()
+            .add("entry", addr, 0, STB_GLOBAL, STT_NOTYPE, 1);
+
+        let mut editor = ElfEditor::new(&parser);
+        symbols.apply(&mut editor).unwrap();
+        let edited = editor.save().unwrap();
+        let parser = ElfParser::parse(&edited).unwrap();
+
+        // both tables replaced rather than added
+        assert_eq!(parser.section_headers.len(), sections);
+        assert_eq!(
+            symtab(&parser),
+            [
+                (String::new(), STB_LOCAL, 0),
+                ("checksum".to_string(), STB_LOCAL, addr + 4),
+                ("_start".to_string(), STB_GLOBAL, addr),
+                ("entry".to_string(), STB_GLOBAL, addr),
+            ]
+        );
+        assert_eq!(parser.section_by_name(".symtab").unwrap().1.info, 2);
+    }
+}
+
+#[test]
+fn symbol_table_image() {
+    for (class, endianness) in ENCODINGS {
+        let ctx = ImageBuilder::minimal(class, endianness).ctx();
+        let empty = SymtabEditor::new().build(&ctx);
+        assert_eq!(empty.symtab, vec![0; layout::sym::for_class(class).size]);
+        assert_eq!(empty.strtab, b"\0");
+        assert_eq!(empty.first_global, 1);
+    }
+}
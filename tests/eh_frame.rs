@@ -0,0 +1,180 @@
+mod fixtures;
+
+use elf_parser::bytes::{Address, ParseContext};
+use elf_parser::elf::eh_frame::{
+    EhFrameHdr, DW_EH_PE_ABSPTR, DW_EH_PE_DATAREL, DW_EH_PE_OMIT, DW_EH_PE_PCREL, DW_EH_PE_SDATA2,
+    DW_EH_PE_SDATA4, DW_EH_PE_SDATA8, DW_EH_PE_UDATA2, DW_EH_PE_UDATA4, DW_EH_PE_UDATA8,
+};
+use elf_parser::elf::shdr::{SHF_ALLOC, SHT_PROGBITS};
+use elf_parser::parser::ParseError;
+use fixtures::{ImageBuilder, ENCODINGS};
+
+/// Address the sections are decoded as loaded at
+const VADDR: u64 = 0x1000;
+
+fn header(eh_frame_ptr_enc: u8, fde_count_enc: u8, table_enc: u8) -> Vec<u8> {
+    vec![1, eh_frame_ptr_enc, fde_count_enc, table_enc]
+}
+
+/// `.eh_frame_hdr` as emitted by GNU ld: `.eh_frame` located relative to the field,
+/// and a table of three entries relative to the section
+fn gnu_eh_frame_hdr(ctx: &ParseContext) -> Vec<u8> {
+    let mut data = header(
+        DW_EH_PE_PCREL | DW_EH_PE_SDATA4,
+        DW_EH_PE_UDATA4,
+        DW_EH_PE_DATAREL | DW_EH_PE_SDATA4,
+    );
+    ctx.put_u32(&mut data, -0x104i32 as u32);
+    ctx.put_u32(&mut data, 3);
+    for (loc, fde) in [(-0x800i32, 0x200), (0x10, 0x220), (0x80, 0x240)] {
+        ctx.put_u32(&mut data, loc as u32);
+        ctx.put_u32(&mut data, fde);
+    }
+    data
+}
+
+#[test]
+fn relative_encodings() {
+    for (class, endianness) in ENCODINGS {
+        let ctx = ImageBuilder::minimal(class, endianness).ctx();
+        let hdr = EhFrameHdr::parse(&gnu_eh_frame_hdr(&ctx), &ctx, VADDR).unwrap();
+
+        assert_eq!(hdr.version, 1);
+        // relative to the field, at offset 4 of the section
+        assert_eq!(hdr.eh_frame_ptr, Address(VADDR + 4 - 0x104));
+        assert_eq!(
+            hdr.table,
+            [
+                (Address(VADDR - 0x800), Address(VADDR + 0x200)),
+                (Address(VADDR + 0x10), Address(VADDR + 0x220)),
+                (Address(VADDR + 0x80), Address(VADDR + 0x240)),
+            ]
+        );
+    }
+}
+
+#[test]
+fn absolute_encodings() {
+    for (class, endianness) in ENCODINGS {
+        let ctx = ImageBuilder::minimal(class, endianness).ctx();
+
+        // pointer-sized `.eh_frame` address, and a table of 8-byte values
+        let mut data = header(DW_EH_PE_ABSPTR, DW_EH_PE_UDATA2, DW_EH_PE_UDATA8);
+        ctx.put_uword(&mut data, 0x4000);
+        ctx.put_u16(&mut data, 1);
+        ctx.put_u64(&mut data, 0x1_0000_0000);
+        ctx.put_u64(&mut data, 0x4010);
+        let hdr = EhFrameHdr::parse(&data, &ctx, VADDR).unwrap();
+        assert_eq!(hdr.eh_frame_ptr, Address(0x4000));
+        assert_eq!(hdr.table, [(Address(0x1_0000_0000), Address(0x4010))]);
+
+        // signed values are sign-extended
+        let mut data = header(DW_EH_PE_SDATA8, DW_EH_PE_UDATA8, DW_EH_PE_SDATA2);
+        ctx.put_u64(&mut data, -8i64 as u64);
+        ctx.put_u64(&mut data, 1);
+        ctx.put_u16(&mut data, -2i16 as u16);
+        ctx.put_u16(&mut data, 0x7fff);
+        let hdr = EhFrameHdr::parse(&data, &ctx, VADDR).unwrap();
+        assert_eq!(hdr.eh_frame_ptr, Address(-8i64 as u64));
+        assert_eq!(hdr.table, [(Address(-2i64 as u64), Address(0x7fff))]);
+    }
+}
+
+#[test]
+fn omitted_table() {
+    for (class, endianness) in ENCODINGS {
+        let ctx = ImageBuilder::minimal(class, endianness).ctx();
+
+        for (fde_count_enc, table_enc) in [
+            (DW_EH_PE_OMIT, DW_EH_PE_UDATA4),
+            (DW_EH_PE_UDATA4, DW_EH_PE_OMIT),
+        ] {
+            let mut data = header(DW_EH_PE_UDATA4, fde_count_enc, table_enc);
+            ctx.put_u32(&mut data, 0x4000);
+            let hdr = EhFrameHdr::parse(&data, &ctx, VADDR).unwrap();
+
+            assert_eq!(hdr.eh_frame_ptr, Address(0x4000));
+            assert!(hdr.table.is_empty());
+            assert_eq!(hdr.lookup(0x4000), None);
+        }
+    }
+}
+
+#[test]
+fn unsupported_encodings() {
+    for (class, endianness) in ENCODINGS {
+        let ctx = ImageBuilder::minimal(class, endianness).ctx();
+
+        // an unknown value format, and values aligned in the section
+        for enc in [0x05, 0x50 | DW_EH_PE_UDATA4] {
+            let mut data = header(enc, DW_EH_PE_OMIT, DW_EH_PE_OMIT);
+            ctx.put_u64(&mut data, 0);
+
+            assert!(matches!(
+                EhFrameHdr::parse(&data, &ctx, VADDR),
+                Err(ParseError::UnsupportedPointerEncoding(e)) if e == enc
+            ));
+        }
+    }
+}
+
+/// A count of entries the section can't hold is refused before any is read
+#[test]
+fn bogus_fde_count() {
+    for (class, endianness) in ENCODINGS {
+        let ctx = ImageBuilder::minimal(class, endianness).ctx();
+
+        let mut data = gnu_eh_frame_hdr(&ctx);
+        data[8..12].copy_from_slice(&[0xff; 4]);
+        assert!(matches!(
+            EhFrameHdr::parse(&data, &ctx, VADDR),
+            Err(ParseError::InvalidLength)
+        ));
+
+        // one entry short
+        let data = gnu_eh_frame_hdr(&ctx);
+        assert!(EhFrameHdr::parse(&data[..data.len() - 8], &ctx, VADDR).is_err());
+    }
+}
+
+#[test]
+fn lookup() {
+    for (class, endianness) in ENCODINGS {
+        let ctx = ImageBuilder::minimal(class, endianness).ctx();
+        let hdr = EhFrameHdr::parse(&gnu_eh_frame_hdr(&ctx), &ctx, VADDR).unwrap();
+
+        assert_eq!(hdr.lookup(VADDR - 0x801), None);
+        assert_eq!(hdr.lookup(VADDR - 0x800), Some(Address(VADDR + 0x200)));
+        assert_eq!(hdr.lookup(VADDR + 0x10), Some(Address(VADDR + 0x220)));
+        assert_eq!(hdr.lookup(VADDR + 0x7f), Some(Address(VADDR + 0x220)));
+        assert_eq!(hdr.lookup(VADDR + 0x80), Some(Address(VADDR + 0x240)));
+        // whether the last function covers addresses past it isn't known
+        assert_eq!(hdr.lookup(u64::MAX), Some(Address(VADDR + 0x240)));
+    }
+}
+
+#[test]
+fn eh_frame_hdr_section() {
+    for (class, endianness) in ENCODINGS {
+        let builder = ImageBuilder::minimal(class, endianness);
+        assert_eq!(
+            builder.build().parse().unwrap().eh_frame_hdr().unwrap(),
+            None
+        );
+
+        let ctx = builder.ctx();
+        let image = builder
+            .section(
+                ".eh_frame_hdr",
+                SHT_PROGBITS,
+                SHF_ALLOC,
+                &gnu_eh_frame_hdr(&ctx),
+            )
+            .build();
+        let addr = image.section(".eh_frame_hdr").addr.0;
+        let hdr = image.parse().unwrap().eh_frame_hdr().unwrap().unwrap();
+
+        assert_eq!(hdr.eh_frame_ptr, Address(addr + 4 - 0x104));
+        assert_eq!(hdr.lookup(addr + 0x10), Some(Address(addr + 0x220)));
+    }
+}
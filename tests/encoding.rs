@@ -0,0 +1,101 @@
+mod fixtures;
+
+use elf_parser::bytes::ParseContext;
+use elf_parser::elf::ehdr::ElfHData;
+use elf_parser::encoding::{detect_encoding_mismatch, plausibility, MAX_SCORE};
+use elf_parser::options::ParseOptions;
+use elf_parser::parser::{ElfParser, ParseError, ParseWarning};
+use fixtures::{ImageBuilder, ENCODINGS};
+
+/// Offset of `EI_DATA` in the ident
+const EI_DATA: usize = 5;
+
+fn swapped(endianness: ElfHData) -> ElfHData {
+    match endianness {
+        ElfHData::ElfData2Lsb => ElfHData::ElfData2Msb,
+        _ => ElfHData::ElfData2Lsb,
+    }
+}
+
+#[test]
+fn consistent_fixtures() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+        let ctx = ParseContext::new(endianness, class);
+
+        assert_eq!(plausibility(&image.bytes, ctx), MAX_SCORE);
+        assert_eq!(detect_encoding_mismatch(&image.bytes, ctx), None);
+
+        let options = ParseOptions::default().detect_encoding_mismatch(true);
+        let parser = ElfParser::parse_with(&image.bytes, &options).unwrap();
+        assert_eq!(parser.detect_encoding_mismatch(), None);
+        assert!(!parser
+            .warnings
+            .iter()
+            .any(|w| matches!(w, ParseWarning::EncodingMismatch { .. })));
+    }
+}
+
+#[test]
+fn swapped_byte_order() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        image.bytes[EI_DATA] = swapped(endianness) as u8;
+        let declared = ParseContext::new(swapped(endianness), class);
+
+        let mismatch = detect_encoding_mismatch(&image.bytes, declared).unwrap();
+        assert_eq!(mismatch.declared, declared);
+        assert_eq!(mismatch.suspected, ParseContext::new(endianness, class));
+        assert_eq!(mismatch.suspected_score, MAX_SCORE);
+        assert!(mismatch.declared_score < MAX_SCORE - 1);
+
+        // off by default, the header fails to decode as declared
+        let options = ParseOptions::default();
+        assert!(!matches!(
+            ElfParser::parse_with(&image.bytes, &options),
+            Err(ParseError::InMismatchedEncoding { .. })
+        ));
+
+        let options = options.detect_encoding_mismatch(true);
+        match ElfParser::parse_with(&image.bytes, &options) {
+            Err(err @ ParseError::InMismatchedEncoding { .. }) => {
+                assert!(matches!(
+                    err,
+                    ParseError::InMismatchedEncoding { mismatch: m, .. } if m == mismatch
+                ));
+            }
+            Ok(parser) => assert!(parser.warnings.contains(&ParseWarning::EncodingMismatch {
+                declared,
+                declared_score: mismatch.declared_score,
+                suspected: mismatch.suspected,
+                suspected_score: mismatch.suspected_score,
+            })),
+            Err(err) => panic!("unexpected error {:?}", err),
+        }
+
+        // parsing again with the suspected encoding recovers the file, once the header
+        // decoded as declared is no longer validated
+        let options = options
+            .force_endianness(endianness)
+            .skip_ident_validation(true);
+        let parser = ElfParser::parse_with(&image.bytes, &options).unwrap();
+        assert_eq!(parser.section_headers.len(), 4);
+        assert_eq!(parser.detect_encoding_mismatch(), None);
+    }
+}
+
+#[test]
+fn warning_and_error_display() {
+    let (class, endianness) = ENCODINGS[0];
+    let mut image = ImageBuilder::minimal(class, endianness).build();
+    image.bytes[EI_DATA] = swapped(endianness) as u8;
+
+    let options = ParseOptions::default().detect_encoding_mismatch(true);
+    let err = ElfParser::parse_with(&image.bytes, &options).unwrap_err();
+    let message = err.to_string();
+    assert!(message.starts_with("header looks "), "{}", message);
+    assert!(!matches!(
+        err.root_cause(),
+        ParseError::InMismatchedEncoding { .. }
+    ));
+}
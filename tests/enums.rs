@@ -0,0 +1,197 @@
+mod fixtures;
+
+use elf_parser::elf::ehdr::{
+    Elf64Hdr, Elf64Ident, ElfHClass, ElfHData, ElfHType, ElfHVersion, ELF_MAGIC,
+};
+use elf_parser::elf::phdr::PType;
+use elf_parser::parser::{ParseError, ParseWarning};
+use fixtures::{ImageBuilder, ENCODINGS};
+
+#[test]
+fn ident_round_trips() {
+    for class in ElfHClass::VARIANTS {
+        assert_eq!(ElfHClass::try_from(class as u8), Ok(class));
+    }
+    for data in ElfHData::VARIANTS {
+        assert_eq!(ElfHData::try_from(data as u8), Ok(data));
+    }
+    for version in ElfHVersion::VARIANTS {
+        assert_eq!(ElfHVersion::try_from(version as u8), Ok(version));
+    }
+
+    assert_eq!(ElfHClass::try_from(3), Err(()));
+    assert_eq!(ElfHData::try_from(0), Err(()));
+    assert_eq!(ElfHVersion::try_from(2), Err(()));
+}
+
+#[test]
+fn file_type_round_trips() {
+    for e_type in ElfHType::VARIANTS {
+        assert_eq!(ElfHType::try_from(e_type as u16), Ok(e_type));
+    }
+
+    assert_eq!(ElfHType::try_from(5), Err(()));
+}
+
+#[test]
+fn segment_type_round_trips() {
+    for p_type in PType::VARIANTS {
+        assert_eq!(PType::from(p_type.value()), p_type);
+    }
+
+    // types this crate doesn't know keep their value, whatever their range
+    for value in [
+        8, 0x5fffffff, 0x60000010, 0x6474e554, 0x70000010, 0x80000000,
+    ] {
+        assert_eq!(PType::from(value), PType::Other(value));
+        assert_eq!(PType::from(value).value(), value);
+    }
+    assert!(PType::Other(8).is_unknown());
+    assert!(PType::Other(0x5fffffff).is_unknown());
+    assert!(!PType::Other(0x60000010).is_unknown());
+    assert!(!PType::Other(0x70000010).is_unknown());
+    assert!(PType::Other(0x80000000).is_unknown());
+
+    assert_eq!(PType::PtGnuStack.to_string(), "GNU_STACK");
+    assert_eq!(PType::Other(0x6474e554).to_string(), "LOOS+0x474e554");
+    assert_eq!(PType::Other(0x70000010).to_string(), "LOPROC+0x10");
+    assert_eq!(PType::Other(8).to_string(), "0x8");
+}
+
+#[test]
+fn segment_type_written_back() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+        let parser = image.parse().unwrap();
+
+        for p_type in PType::VARIANTS.into_iter().chain([
+            PType::Other(8),
+            PType::Other(0x6474e554),
+            PType::Other(0x70000010),
+        ]) {
+            let mut ph = parser.program_headers[0].clone();
+            ph.p_type = p_type;
+
+            let mut bytes = vec![];
+            ph.write(&parser.ctx, &mut bytes);
+            assert_eq!(parser.ctx.read_u32(&bytes, 0).unwrap(), p_type.value());
+        }
+    }
+}
+
+/// Segments of types this crate doesn't know are kept, those outside of the OS and
+/// processor-specific ranges are reported
+#[test]
+fn unknown_segment_type() {
+    for (class, endianness) in ENCODINGS {
+        for (p_type, reported) in [
+            (0x1234, true),
+            (0x6474e554, false),
+            (0x70000010, false),
+            (0x80000000, true),
+        ] {
+            let mut image = ImageBuilder::minimal(class, endianness).build();
+            image.set_segment(1, |l| l.p_type, p_type);
+
+            let parser = image.parse().unwrap();
+            assert_eq!(
+                parser.program_headers[1].p_type,
+                PType::Other(p_type as u32)
+            );
+            assert_eq!(
+                parser.warnings,
+                match reported {
+                    true => vec![ParseWarning::UnknownSegmentType {
+                        idx: 1,
+                        p_type: p_type as u32,
+                    }],
+                    false => vec![],
+                }
+            );
+
+            // and written back as read
+            let mut bytes = vec![];
+            parser.program_headers[1].write(&parser.ctx, &mut bytes);
+            let ph_size = bytes.len();
+            let offset = image.ph_off as usize + ph_size;
+            assert_eq!(bytes, image.bytes[offset..offset + ph_size]);
+        }
+    }
+}
+
+#[test]
+fn file_without_type() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        image.set_header(|l| l.e_type, 0);
+
+        assert!(matches!(
+            Elf64Hdr::parse(&image.bytes),
+            Err(ParseError::UnsupportedFileType)
+        ));
+    }
+}
+
+#[test]
+fn invalid_ident() {
+    let image = ImageBuilder::minimal(ElfHClass::ElfClass64, ElfHData::ElfData2Lsb).build();
+
+    for (off, value, error) in [
+        (4, 3, ParseError::UnsupportedClass(3)),
+        (5, 0, ParseError::UnsupportedEncoding(0)),
+        (6, 2, ParseError::UnsupportedVersion(2)),
+    ] {
+        let mut bytes = image.bytes.clone();
+        bytes[off] = value;
+
+        assert_eq!(
+            format!("{:?}", Elf64Hdr::parse_ident(&bytes).unwrap_err()),
+            format!("{:?}", error)
+        );
+    }
+}
+
+#[test]
+fn ident_builder() {
+    let ident = Elf64Ident::new(
+        ElfHClass::ElfClass32,
+        ElfHData::ElfData2Msb,
+        ElfHVersion::ElfEvCurr,
+        3,
+        1,
+    );
+    let bytes = ident.to_bytes();
+    assert_eq!(bytes[..4], ELF_MAGIC);
+    assert_eq!(bytes[4..9], [1, 2, 1, 3, 1]);
+    assert!(ident.is_padding_clean());
+    assert!(ident.validate().is_ok());
+
+    let decoded = Elf64Ident::from_bytes(&bytes).unwrap();
+    assert_eq!(decoded.to_bytes(), bytes);
+    assert_eq!(Elf64Hdr::parse_ident(&bytes).unwrap().to_bytes(), bytes);
+
+    let padded = ident.with_padding([0, 0, 0, 0, 0, 0, 7]);
+    assert_eq!(padded.to_bytes()[15], 7);
+    assert!(!padded.is_padding_clean());
+}
+
+#[test]
+fn ident_validation() {
+    let ident = |class, version| Elf64Ident::new(class, ElfHData::ElfData2Lsb, version, 0, 0);
+
+    assert!(matches!(
+        ident(ElfHClass::_ElfClassIn, ElfHVersion::ElfEvCurr).validate(),
+        Err(ParseError::UnsupportedClass(0))
+    ));
+    assert!(matches!(
+        ident(ElfHClass::ElfClass64, ElfHVersion::ElfEvNone).validate(),
+        Err(ParseError::UnsupportedVersion(0))
+    ));
+
+    let mut bytes = ident(ElfHClass::ElfClass64, ElfHVersion::ElfEvCurr).to_bytes();
+    bytes[0] = 0;
+    assert!(matches!(
+        Elf64Ident::from_bytes(&bytes),
+        Err(ParseError::InvalidMagic)
+    ));
+}
@@ -0,0 +1,112 @@
+mod fixtures;
+
+use elf_parser::bytes::Address;
+use elf_parser::elf::shdr::SHT_RELA;
+use elf_parser::elf::sym::{Elf64Sym, STB_GLOBAL, STT_FUNC};
+use elf_parser::options::ParseOptions;
+use elf_parser::parser::{ElfParser, ParseError};
+use fixtures::{ImageBuilder, ENCODINGS};
+use std::error::Error;
+
+fn function(value: u64) -> Elf64Sym {
+    Elf64Sym {
+        name: 0,
+        info: STB_GLOBAL << 4 | STT_FUNC,
+        other: 0,
+        shndx: 1,
+        value: Address(value),
+        size: 4,
+    }
+}
+
+#[test]
+fn symbol_table_past_the_end() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness)
+            .symbols(&[("main", function(0x1000))])
+            .build();
+        let idx = image.section_idx(".symtab");
+        let len = image.bytes.len() as u64;
+        image.set_section(idx, |l| l.sh_offset, len);
+
+        let err = image.parse().unwrap_err();
+        let ParseError::InSection { index, name, .. } = &err else {
+            panic!("error wasn't located: {:?}", err);
+        };
+        assert_eq!(*index, idx);
+        assert_eq!(name.as_deref(), Some(".symtab"));
+        assert!(matches!(err.root_cause(), ParseError::InvalidLength));
+        assert!(matches!(
+            err.source().and_then(|e| e.downcast_ref::<ParseError>()),
+            Some(ParseError::InvalidLength)
+        ));
+        assert_eq!(
+            err.to_string(),
+            format!("section [{}] (.symtab): data is too short", idx)
+        );
+    }
+}
+
+#[test]
+fn relocation_section_past_the_end() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness)
+            .section(".rela.text", SHT_RELA, 0, &[0; 24])
+            .build();
+        let idx = image.section_idx(".rela.text");
+        let len = image.bytes.len() as u64;
+        image.set_section(idx, |l| l.sh_offset, len);
+
+        // relocations are only read on demand, so the file still parses
+        let parser = image.parse().unwrap();
+        let err = parser.relocations_named().unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with(&format!("section [{}] (.rela.text): ", idx)));
+    }
+}
+
+#[test]
+fn segment_error_chain() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        let filesz = image.section(".data").size;
+        image.set_segment(1, |l| l.p_offset, 0x100000);
+
+        let options = ParseOptions::default().strict(true);
+        let err = ElfParser::parse_with(&image.bytes, &options).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "segment [1]: file image of {:#x} bytes at 0x100000 runs past the end of the file",
+                filesz
+            )
+        );
+        assert!(err.source().unwrap().source().is_none());
+    }
+}
+
+#[test]
+fn nested_frames() {
+    let err = ParseError::InvalidLength
+        .in_entry(203)
+        .in_section(14, Some(".rela.text".to_string()));
+
+    assert_eq!(
+        err.to_string(),
+        "section [14] (.rela.text): entry 203: data is too short"
+    );
+    assert!(matches!(err.root_cause(), ParseError::InvalidLength));
+    assert_eq!(
+        ParseError::InvalidLength.in_section(3, None).to_string(),
+        "section [3]: data is too short"
+    );
+}
+
+#[test]
+fn global_errors_arent_located() {
+    let err = ParseError::DeadlineExceeded.in_entry(4).in_section(2, None);
+
+    assert!(matches!(err, ParseError::DeadlineExceeded));
+    assert!(err.source().is_none());
+}
@@ -0,0 +1,83 @@
+mod fixtures;
+
+use elf_parser::elf::ehdr::{ElfHClass, ElfHData};
+use elf_parser::explain::{Explanation, Verdict};
+use elf_parser::parser::ElfParser;
+use fixtures::{ImageBuilder, ENCODINGS};
+
+fn field<'e>(explanations: &'e [Explanation], name: &str) -> &'e Explanation {
+    explanations.iter().find(|e| e.field == name).unwrap()
+}
+
+#[test]
+fn valid_header() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+        let explanations = ElfParser::explain(&image.bytes);
+
+        assert_eq!(explanations.len(), 20);
+        assert!(explanations.windows(2).all(|w| w[0].offset < w[1].offset));
+        for explanation in &explanations {
+            assert!(explanation.is_valid(), "{}", explanation);
+        }
+        assert_eq!(field(&explanations, "e_machine").decoded, "RISC-V");
+    }
+}
+
+#[test]
+fn invalid_encoding() {
+    let mut image = ImageBuilder::minimal(ElfHClass::ElfClass64, ElfHData::ElfData2Lsb).build();
+    image.bytes[5] = 3;
+    assert!(ElfParser::parse(&image.bytes).is_err());
+
+    // the fields past the identification can't be decoded without an encoding
+    let explanations = ElfParser::explain(&image.bytes);
+    assert_eq!(explanations.len(), 7);
+    let data = field(&explanations, "e_ident[EI_DATA]");
+    assert_eq!(data.verdict, Verdict::Invalid);
+    assert_eq!(
+        data.to_string(),
+        "e_ident[EI_DATA] = 0x03 (invalid encoding): invalid, must be 1 (LSB) or 2 (MSB)"
+    );
+}
+
+#[test]
+fn rejected_header_fields() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        let len = image.bytes.len() as u64;
+        image
+            .set_header(|l| l.e_type, 0)
+            .set_header(|l| l.e_shoff, len)
+            .set_header(|l| l.e_phentsize, 0);
+        assert!(image.parse().is_err());
+
+        let explanations = ElfParser::explain(&image.bytes);
+        let invalid = explanations
+            .iter()
+            .filter(|e| e.verdict == Verdict::Invalid)
+            .map(|e| e.field)
+            .collect::<Vec<_>>();
+        assert_eq!(invalid, ["e_type", "e_shoff", "e_phentsize"]);
+        assert!(field(&explanations, "e_shoff")
+            .note
+            .as_ref()
+            .unwrap()
+            .contains("within the"));
+    }
+}
+
+#[test]
+fn truncated_file() {
+    let image = ImageBuilder::minimal(ElfHClass::ElfClass64, ElfHData::ElfData2Lsb).build();
+    let explanations = ElfParser::explain(&image.bytes[..20]);
+
+    assert_eq!(explanations.len(), 20);
+    assert!(field(&explanations, "e_machine").is_valid());
+    let version = field(&explanations, "e_version");
+    assert_eq!(version.raw.len(), 0);
+    assert_eq!(version.verdict, Verdict::Invalid);
+    assert!(explanations[9..].iter().all(|e| !e.is_valid()));
+
+    assert!(ElfParser::explain(&[]).iter().all(|e| !e.is_valid()));
+}
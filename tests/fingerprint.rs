@@ -0,0 +1,109 @@
+mod fixtures;
+
+use elf_parser::bytes::Address;
+use elf_parser::elf::note::NT_GNU_BUILD_ID;
+use elf_parser::elf::shdr::{SHF_ALLOC, SHT_NOTE, SHT_PROGBITS};
+use elf_parser::elf::sym::{Elf64Sym, STB_GLOBAL, STB_LOCAL, STT_FILE, STT_FUNC};
+use elf_parser::fingerprint::sha256;
+use fixtures::{Image, ImageBuilder, SectionSpec, BASE_ADDR, ENCODINGS};
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Links the objects of `ImageBuilder::minimal` as done from `build_dir`, which ends
+/// up in the build id, the compiler banner, the debug info and the file symbol
+fn linked_from(builder: ImageBuilder, build_dir: &str) -> Image {
+    let ctx = builder.ctx();
+    let mut build_id = vec![];
+    ctx.put_u32(&mut build_id, 4);
+    ctx.put_u32(&mut build_id, 8);
+    ctx.put_u32(&mut build_id, NT_GNU_BUILD_ID);
+    build_id.extend_from_slice(b"GNU\0");
+    build_id.extend_from_slice(&sha256(build_dir.as_bytes())[..8]);
+
+    let symbol = |info, value| Elf64Sym {
+        name: 0,
+        info,
+        other: 0,
+        shndx: 1,
+        value: Address(value),
+        size: 4,
+    };
+    let source = format!("{}/src/main.c", build_dir);
+
+    builder
+        .section_with(SectionSpec {
+            name: ".note.gnu.build-id".to_string(),
+            s_type: SHT_NOTE,
+            flags: SHF_ALLOC,
+            align: 4,
+            link: 0,
+            info: 0,
+            ent_size: 0,
+            data: build_id,
+        })
+        .section(
+            ".comment",
+            SHT_PROGBITS,
+            0,
+            format!("GCC: ({}) 13.2.0\0", build_dir).as_bytes(),
+        )
+        .section(".debug_str", SHT_PROGBITS, 0, source.as_bytes())
+        .symbols(&[
+            (source.as_str(), symbol(STB_LOCAL << 4 | STT_FILE, 0)),
+            ("main", symbol(STB_GLOBAL << 4 | STT_FUNC, BASE_ADDR)),
+        ])
+        .build()
+}
+
+#[test]
+fn sha256_vectors() {
+    assert_eq!(
+        hex(&sha256(b"")),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    assert_eq!(
+        hex(&sha256(b"abc")),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    // the padding spills into a second block
+    assert_eq!(
+        hex(&sha256(&[b'a'; 56])),
+        "b35439a4ac6f0948b6d6f9e3c6af0f5f590ce20f1bde7090ef7970686ec6738a"
+    );
+}
+
+#[test]
+fn rebuilt_elsewhere() {
+    for (class, endianness) in ENCODINGS {
+        let builder = ImageBuilder::minimal(class, endianness);
+        let one = linked_from(builder.clone(), "/home/alice/project");
+        let other = linked_from(builder, "/var/lib/ci/builds/1234/project");
+        assert_ne!(one.bytes, other.bytes);
+
+        let fingerprint = one.parse().unwrap().fingerprint().unwrap();
+        assert_eq!(fingerprint, other.parse().unwrap().fingerprint().unwrap());
+        assert_eq!(fingerprint.to_string().len(), 64);
+    }
+}
+
+#[test]
+fn changed_instruction() {
+    for (class, endianness) in ENCODINGS {
+        let image = linked_from(ImageBuilder::minimal(class, endianness), "/build");
+        let fingerprint = image.parse().unwrap().fingerprint().unwrap();
+
+        let mut patched = image.clone();
+        let off = image.section(".text").offset as usize;
+        // `ebreak` in place of the first `nop`
+        patched.bytes[off..off + 4].copy_from_slice(&[0x73, 0x00, 0x10, 0x00]);
+        assert_ne!(patched.parse().unwrap().fingerprint().unwrap(), fingerprint);
+
+        let mut renamed = image.clone();
+        let strtab = image.section(".strtab");
+        let name = strtab.offset as usize + strtab.size as usize - 5;
+        renamed.bytes[name..name + 4].copy_from_slice(b"init");
+        assert_ne!(renamed.parse().unwrap().fingerprint().unwrap(), fingerprint);
+    }
+}
@@ -0,0 +1,491 @@
+//! Builder of small ELF images for the integration tests, so they don't depend on
+//! binaries built by a toolchain. Images are laid out as the ELF header, the program
+//! header table, the contents of every section, the section header string table and
+//! the section header table, for either class and byte order.
+//!
+//! Test crates pull it in with `mod fixtures;`. `ImageBuilder::minimal` gives an
+//! executable with a `.text` and a `.data` section, each mapped by its own `PT_LOAD`
+//! segment, and `Image::set_header`, `set_section` and `set_segment` overwrite any
+//! field of the built image to corrupt it.
+#![allow(dead_code)]
+
+use elf_parser::bytes::{Address, ParseContext};
+use elf_parser::elf::ehdr::{
+    Elf64Hdr, Elf64Ident, ElfHClass, ElfHData, ElfHType, ElfHVersion, ObjectVersion, ELF_MAGIC,
+    EM_RISCV,
+};
+use elf_parser::elf::phdr::{Elf64PHdr, PType, PTypeData, PF_EXEC, PF_READ, PF_WRITE};
+use elf_parser::elf::shdr::{
+    Elf64SHdr, SHF_ALLOC, SHF_EXECINSTR, SHF_WRITE, SHT_NOBITS, SHT_PROGBITS, SHT_STRTAB,
+    SHT_SYMTAB,
+};
+use elf_parser::elf::sym::{Elf64Sym, STB_LOCAL};
+use elf_parser::layout::ehdr::EhdrLayout;
+use elf_parser::layout::phdr::PhdrLayout;
+use elf_parser::layout::shdr::ShdrLayout;
+use elf_parser::layout::{self, FieldSpec};
+use elf_parser::parser::{ElfParser, ParseError};
+
+/// Address the first byte of the file is mapped at. Allocated sections get this plus
+/// their file offset, so every segment is congruent with its offset
+pub const BASE_ADDR: u64 = 0x10000;
+/// Alignment of the `PT_LOAD` segments
+pub const PAGE_SIZE: u64 = 0x1000;
+
+/// Instructions of the `.text` section of `ImageBuilder::minimal`, RISC-V `nop`s
+pub const TEXT: [u8; 16] = [
+    0x13, 0x00, 0x00, 0x00, 0x13, 0x00, 0x00, 0x00, 0x13, 0x00, 0x00, 0x00, 0x13, 0x00, 0x00, 0x00,
+];
+/// Contents of the `.data` section of `ImageBuilder::minimal`
+pub const DATA: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+/// Every class and byte order an image can be built with
+pub const ENCODINGS: [(ElfHClass, ElfHData); 4] = [
+    (ElfHClass::ElfClass32, ElfHData::ElfData2Lsb),
+    (ElfHClass::ElfClass32, ElfHData::ElfData2Msb),
+    (ElfHClass::ElfClass64, ElfHData::ElfData2Lsb),
+    (ElfHClass::ElfClass64, ElfHData::ElfData2Msb),
+];
+
+#[derive(Debug, Clone)]
+pub struct SectionSpec {
+    pub name: String,
+    pub s_type: u32,
+    pub flags: u64,
+    pub align: u64,
+    pub link: u32,
+    pub info: u32,
+    pub ent_size: u64,
+    /// Contents of the section, or only its size for `SHT_NOBITS` sections
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SegmentSpec {
+    pub p_type: PType,
+    pub flags: u32,
+    /// Names of the sections the segment spans, from the first to the last of them
+    pub sections: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ImageBuilder {
+    pub class: ElfHClass,
+    pub endianness: ElfHData,
+    pub e_type: ElfHType,
+    pub machine: u16,
+    /// Entry point, the address of the first executable section when None
+    pub entry: Option<u64>,
+    pub sections: Vec<SectionSpec>,
+    pub segments: Vec<SegmentSpec>,
+}
+
+impl ImageBuilder {
+    /// An executable with no section but the section header string table
+    pub fn new(class: ElfHClass, endianness: ElfHData) -> Self {
+        ImageBuilder {
+            class,
+            endianness,
+            e_type: ElfHType::Executable,
+            machine: EM_RISCV,
+            entry: None,
+            sections: vec![],
+            segments: vec![],
+        }
+    }
+
+    /// An executable holding `TEXT` in `.text` and `DATA` in `.data`, loaded by a
+    /// read-execute and a read-write `PT_LOAD` segment
+    pub fn minimal(class: ElfHClass, endianness: ElfHData) -> Self {
+        ImageBuilder::new(class, endianness)
+            .section(".text", SHT_PROGBITS, SHF_ALLOC | SHF_EXECINSTR, &TEXT)
+            .section(".data", SHT_PROGBITS, SHF_ALLOC | SHF_WRITE, &DATA)
+            .segment(PType::PtLoad, PF_READ | PF_EXEC, &[".text"])
+            .segment(PType::PtLoad, PF_READ | PF_WRITE, &[".data"])
+    }
+
+    pub fn e_type(mut self, e_type: ElfHType) -> Self {
+        self.e_type = e_type;
+        self
+    }
+
+    pub fn machine(mut self, machine: u16) -> Self {
+        self.machine = machine;
+        self
+    }
+
+    pub fn entry(mut self, entry: u64) -> Self {
+        self.entry = Some(entry);
+        self
+    }
+
+    /// Appends a section aligned to the word size of the class
+    pub fn section(self, name: &str, s_type: u32, flags: u64, data: &[u8]) -> Self {
+        let align = match self.class {
+            ElfHClass::ElfClass32 => 4,
+            _ => 8,
+        };
+
+        self.section_with(SectionSpec {
+            name: name.to_string(),
+            s_type,
+            flags,
+            align,
+            link: 0,
+            info: 0,
+            ent_size: 0,
+            data: data.to_vec(),
+        })
+    }
+
+    pub fn section_with(mut self, section: SectionSpec) -> Self {
+        self.sections.push(section);
+        self
+    }
+
+    /// Appends a `.symtab` holding `symbols` after the null symbol, followed by the
+    /// `.strtab` of their names. The `name` field of the symbols is overwritten
+    pub fn symbols(self, symbols: &[(&str, Elf64Sym)]) -> Self {
+        self.symbol_table(".symtab", SHT_SYMTAB, ".strtab", symbols)
+    }
+
+    /// Appends a symbol table of type `s_type`, see `symbols`
+    pub fn symbol_table(
+        self,
+        name: &str,
+        s_type: u32,
+        strtab_name: &str,
+        symbols: &[(&str, Elf64Sym)],
+    ) -> Self {
+        let ctx = self.ctx();
+        let null = Elf64Sym {
+            name: 0,
+            info: 0,
+            other: 0,
+            shndx: 0,
+            value: Address(0),
+            size: 0,
+        };
+
+        let mut strtab = vec![0u8];
+        let mut symtab = vec![];
+        null.write(&ctx, &mut symtab);
+        for (name, symbol) in symbols {
+            let symbol = Elf64Sym {
+                name: strtab.len() as u32,
+                ..*symbol
+            };
+            strtab.extend(name.as_bytes());
+            strtab.push(0);
+            symbol.write(&ctx, &mut symtab);
+        }
+
+        let locals = symbols
+            .iter()
+            .take_while(|(_, s)| s.bind() == STB_LOCAL)
+            .count();
+        let symtab_idx = self.sections.len() as u32 + 1;
+        self.section_with(SectionSpec {
+            name: name.to_string(),
+            s_type,
+            flags: 0,
+            align: ctx.word_size() as u64,
+            link: symtab_idx + 1,
+            info: locals as u32 + 1,
+            ent_size: layout::sym::for_class(ctx.class).size as u64,
+            data: symtab,
+        })
+        .section(strtab_name, SHT_STRTAB, 0, &strtab)
+    }
+
+    /// Adds a segment spanning `sections`. Without sections, a `PT_PHDR` segment spans
+    /// the program header table and any other one the headers, from the start of the
+    /// file to the end of the program header table
+    pub fn segment(mut self, p_type: PType, flags: u32, sections: &[&str]) -> Self {
+        self.segments.push(SegmentSpec {
+            p_type,
+            flags,
+            sections: sections.iter().map(|s| s.to_string()).collect(),
+        });
+        self
+    }
+
+    pub fn ctx(&self) -> ParseContext {
+        ParseContext::new(self.endianness, self.class)
+    }
+
+    /// Lays the image out and serializes every structure of it
+    pub fn build(&self) -> Image {
+        let ctx = self.ctx();
+        let eh_size = layout::ehdr::for_class(self.class).size;
+        let ph_size = layout::phdr::for_class(self.class).size;
+        let sh_size = layout::shdr::for_class(self.class).size;
+        let align = |off: usize, align: u64| off.next_multiple_of(align.max(1) as usize);
+
+        // the null section, the requested ones and the section header string table
+        let mut shstrtab = vec![0u8];
+        let mut name_offsets = vec![0u32];
+        for name in self
+            .sections
+            .iter()
+            .map(|s| s.name.as_str())
+            .chain([".shstrtab"])
+        {
+            name_offsets.push(shstrtab.len() as u32);
+            shstrtab.extend(name.as_bytes());
+            shstrtab.push(0);
+        }
+
+        let ph_off = eh_size;
+        let mut off = ph_off + self.segments.len() * ph_size;
+        let mut section_headers = vec![Elf64SHdr {
+            name: 0,
+            s_type: 0,
+            flags: 0,
+            addr: Address(0),
+            offset: 0,
+            size: 0,
+            link: 0,
+            info: 0,
+            addr_align: 0,
+            ent_size: 0,
+        }];
+        for (idx, section) in self.sections.iter().enumerate() {
+            off = align(off, section.align);
+            let addr = match section.flags & SHF_ALLOC {
+                0 => 0,
+                _ => BASE_ADDR + off as u64,
+            };
+
+            section_headers.push(Elf64SHdr {
+                name: name_offsets[idx + 1],
+                s_type: section.s_type,
+                flags: section.flags,
+                addr: Address(addr),
+                offset: off as u64,
+                size: section.data.len() as u64,
+                link: section.link,
+                info: section.info,
+                addr_align: section.align,
+                ent_size: section.ent_size,
+            });
+
+            if section.s_type != SHT_NOBITS {
+                off += section.data.len();
+            }
+        }
+
+        section_headers.push(Elf64SHdr {
+            name: *name_offsets.last().unwrap(),
+            s_type: SHT_STRTAB,
+            flags: 0,
+            addr: Address(0),
+            offset: off as u64,
+            size: shstrtab.len() as u64,
+            link: 0,
+            info: 0,
+            addr_align: 1,
+            ent_size: 0,
+        });
+        let sh_off = align(off + shstrtab.len(), 8);
+
+        let names = section_headers
+            .iter()
+            .enumerate()
+            .map(|(idx, _)| match idx {
+                0 => String::new(),
+                idx if idx > self.sections.len() => ".shstrtab".to_string(),
+                idx => self.sections[idx - 1].name.clone(),
+            })
+            .collect::<Vec<_>>();
+        let find = |name: &str| {
+            let idx = names.iter().position(|n| n == name);
+            &section_headers[idx.unwrap_or_else(|| panic!("no section {}", name))]
+        };
+
+        let program_headers = self
+            .segments
+            .iter()
+            .map(|segment| {
+                if segment.sections.is_empty() {
+                    let start = match segment.p_type {
+                        PType::PtPhdr => ph_off as u64,
+                        _ => 0,
+                    };
+                    let end = (ph_off + self.segments.len() * ph_size) as u64;
+                    return Elf64PHdr {
+                        p_type: segment.p_type,
+                        flags: segment.flags,
+                        offset: start,
+                        vaddr: Address(BASE_ADDR + start),
+                        paddr: Address(BASE_ADDR + start),
+                        filesz: end - start,
+                        memsz: end - start,
+                        align: PAGE_SIZE,
+                        section: PTypeData::Ignorable,
+                    };
+                }
+
+                let spanned = segment.sections.iter().map(|name| find(name));
+                let start = spanned.clone().map(|sh| sh.offset).min().unwrap_or(0);
+                let file_end = spanned
+                    .clone()
+                    .map(|sh| sh.offset + sh.file_size())
+                    .max()
+                    .unwrap_or(0);
+                let mem_end = spanned.map(|sh| sh.offset + sh.size).max().unwrap_or(0);
+
+                Elf64PHdr {
+                    p_type: segment.p_type,
+                    flags: segment.flags,
+                    offset: start,
+                    vaddr: Address(BASE_ADDR + start),
+                    paddr: Address(BASE_ADDR + start),
+                    filesz: file_end - start,
+                    memsz: mem_end - start,
+                    align: PAGE_SIZE,
+                    section: PTypeData::Ignorable,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let entry = self.entry.unwrap_or_else(|| {
+            section_headers
+                .iter()
+                .find(|sh| sh.flags & SHF_EXECINSTR != 0)
+                .map_or(0, |sh| sh.addr.0)
+        });
+
+        // the identification is written out by hand, so it can be checked against
+        // what `Elf64Hdr::write` encodes
+        let mut ident = ELF_MAGIC.to_vec();
+        ident.extend([self.class as u8, self.endianness as u8, 1, 0, 0]);
+        ident.resize(16, 0);
+        let headers = Elf64Hdr {
+            ident: Elf64Ident::new(self.class, self.endianness, ElfHVersion::ElfEvCurr, 0, 0),
+            e_type: self.e_type,
+            machine: self.machine,
+            version: ObjectVersion::Known(ElfHVersion::ElfEvCurr),
+            entry: Address(entry),
+            ph_off: if program_headers.is_empty() {
+                0
+            } else {
+                ph_off as u64
+            },
+            sh_off: sh_off as u64,
+            flags: 0,
+            eh_size: eh_size as u16,
+            ph_ent_size: ph_size as u16,
+            ph_num: program_headers.len() as u16,
+            sh_ent_size: sh_size as u16,
+            sh_num: section_headers.len() as u16,
+            sh_str_ndx: (section_headers.len() - 1) as u16,
+        };
+
+        let mut bytes = vec![];
+        headers.write(&mut bytes);
+        assert_eq!(bytes[..16], ident[..]);
+        program_headers
+            .iter()
+            .for_each(|ph| ph.write(&ctx, &mut bytes));
+        for (section, sh) in self.sections.iter().zip(&section_headers[1..]) {
+            if section.s_type != SHT_NOBITS {
+                bytes.resize(sh.offset as usize, 0);
+                bytes.extend(&section.data);
+            }
+        }
+        bytes.resize(off, 0);
+        bytes.extend(&shstrtab);
+        bytes.resize(sh_off, 0);
+        section_headers
+            .iter()
+            .for_each(|sh| sh.write(&ctx, &mut bytes));
+
+        Image {
+            bytes,
+            ctx,
+            ph_off: headers.ph_off,
+            sh_off: headers.sh_off,
+            entry,
+            names,
+            section_headers,
+        }
+    }
+}
+
+/// A built image, along with where its structures were laid out
+#[derive(Debug, Clone)]
+pub struct Image {
+    pub bytes: Vec<u8>,
+    pub ctx: ParseContext,
+    pub ph_off: u64,
+    pub sh_off: u64,
+    pub entry: u64,
+    /// Name of every section, indexed like the section header table
+    pub names: Vec<String>,
+    /// Section headers as they were written
+    pub section_headers: Vec<Elf64SHdr>,
+}
+
+impl Image {
+    pub fn parse(&self) -> Result<ElfParser<'_>, ParseError> {
+        ElfParser::parse(&self.bytes)
+    }
+
+    pub fn section_idx(&self, name: &str) -> usize {
+        self.names
+            .iter()
+            .position(|n| n == name)
+            .unwrap_or_else(|| panic!("no section {}", name))
+    }
+
+    pub fn section(&self, name: &str) -> &Elf64SHdr {
+        &self.section_headers[self.section_idx(name)]
+    }
+
+    /// Overwrites a field of the ELF header, picked from the layout of the class
+    pub fn set_header(&mut self, field: fn(&EhdrLayout) -> FieldSpec, value: u64) -> &mut Self {
+        let spec = field(layout::ehdr::for_class(self.ctx.class));
+        self.set_field(0, spec, value)
+    }
+
+    /// Overwrites a field of the header of section `idx`
+    pub fn set_section(
+        &mut self,
+        idx: usize,
+        field: fn(&ShdrLayout) -> FieldSpec,
+        value: u64,
+    ) -> &mut Self {
+        let layout = layout::shdr::for_class(self.ctx.class);
+        let off = self.sh_off as usize + idx * layout.size;
+        self.set_field(off, field(layout), value)
+    }
+
+    /// Overwrites a field of the header of segment `idx`
+    pub fn set_segment(
+        &mut self,
+        idx: usize,
+        field: fn(&PhdrLayout) -> FieldSpec,
+        value: u64,
+    ) -> &mut Self {
+        let layout = layout::phdr::for_class(self.ctx.class);
+        let off = self.ph_off as usize + idx * layout.size;
+        self.set_field(off, field(layout), value)
+    }
+
+    /// Overwrites the bytes at `off`, growing the image if needed
+    pub fn set_bytes(&mut self, off: usize, bytes: &[u8]) -> &mut Self {
+        if self.bytes.len() < off + bytes.len() {
+            self.bytes.resize(off + bytes.len(), 0);
+        }
+        self.bytes[off..off + bytes.len()].copy_from_slice(bytes);
+        self
+    }
+
+    fn set_field(&mut self, off: usize, spec: FieldSpec, value: u64) -> &mut Self {
+        self.ctx
+            .write_field(&mut self.bytes[off..], spec, value)
+            .unwrap();
+        self
+    }
+}
@@ -0,0 +1,98 @@
+mod fixtures;
+
+use elf_parser::elf::phdr::{PType, PF_EXEC, PF_READ, PF_WRITE};
+use elf_parser::elf::shdr::{SHF_ALLOC, SHF_EXECINSTR, SHF_WRITE, SHT_NOBITS, SHT_PROGBITS};
+use elf_parser::flat::FlatOptions;
+use elf_parser::parser::ParseError;
+use fixtures::{Image, ImageBuilder, DATA, ENCODINGS, TEXT};
+
+fn image(builder: ImageBuilder) -> Image {
+    builder
+        .section(".text", SHT_PROGBITS, SHF_ALLOC | SHF_EXECINSTR, &TEXT)
+        .section(".data", SHT_PROGBITS, SHF_ALLOC | SHF_WRITE, &DATA)
+        .section(".bss", SHT_NOBITS, SHF_ALLOC | SHF_WRITE, &[0; 0x20])
+        .segment(PType::PtLoad, PF_READ | PF_EXEC, &[".text"])
+        .segment(PType::PtLoad, PF_READ | PF_WRITE, &[".data", ".bss"])
+        .build()
+}
+
+#[test]
+fn segments_placed_by_physical_address() {
+    for (class, endianness) in ENCODINGS {
+        let image = image(ImageBuilder::new(class, endianness));
+        let parser = image.parse().unwrap();
+        let (text, data) = (&parser.program_headers[0], &parser.program_headers[1]);
+
+        let flat = parser
+            .to_flat_binary_with(&FlatOptions::new().gap_fill(0xff))
+            .unwrap();
+        assert_eq!(flat.base, text.paddr.0);
+        // the zeros of `.bss` aren't part of the image
+        assert_eq!(
+            flat.base + flat.data.len() as u64,
+            data.paddr.0 + data.filesz
+        );
+
+        let at = |addr: u64| (addr - flat.base) as usize;
+        assert_eq!(flat.data[..TEXT.len()], TEXT);
+        assert_eq!(flat.data[at(data.paddr.0)..], DATA);
+        assert!(flat.data[TEXT.len()..at(data.paddr.0)]
+            .iter()
+            .all(|b| *b == 0xff));
+    }
+}
+
+#[test]
+fn clipped_to_range() {
+    for (class, endianness) in ENCODINGS {
+        let image = image(ImageBuilder::new(class, endianness));
+        let parser = image.parse().unwrap();
+        let data = parser.program_headers[1].paddr.0;
+
+        let flat = parser
+            .to_flat_binary_with(&FlatOptions::new().range(data + 2..u64::MAX))
+            .unwrap();
+        assert_eq!(flat.base, data + 2);
+        assert_eq!(flat.data, DATA[2..]);
+
+        let flat = parser
+            .to_flat_binary_with(&FlatOptions::new().range(0..1))
+            .unwrap();
+        assert_eq!(flat.base, 0);
+        assert!(flat.data.is_empty());
+    }
+}
+
+#[test]
+fn too_large() {
+    for (class, endianness) in ENCODINGS {
+        let image = image(ImageBuilder::new(class, endianness));
+        let parser = image.parse().unwrap();
+
+        assert!(matches!(
+            parser.to_flat_binary_with(&FlatOptions::new().max_size(4)),
+            Err(ParseError::FlatImageTooLarge { .. })
+        ));
+    }
+}
+
+/// Segments whose file image would end past the largest offset are refused, whether
+/// they're clipped or not
+#[test]
+fn offset_near_end_of_address_space() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = image(ImageBuilder::new(class, endianness));
+        image.set_segment(1, |l| l.p_offset, u64::MAX - 4);
+        let parser = image.parse().unwrap();
+        let data = parser.program_headers[1].paddr.0;
+
+        assert!(matches!(
+            parser.to_flat_binary(),
+            Err(ParseError::InvalidLength)
+        ));
+        assert!(matches!(
+            parser.to_flat_binary_with(&FlatOptions::new().range(data + 6..u64::MAX)),
+            Err(ParseError::InvalidLength)
+        ));
+    }
+}
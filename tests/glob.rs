@@ -0,0 +1,100 @@
+mod fixtures;
+
+use elf_parser::elf::shdr::{SHF_ALLOC, SHT_PROGBITS};
+use elf_parser::parser::ElfParser;
+use elf_parser::section::glob_match;
+use fixtures::{ImageBuilder, ENCODINGS};
+
+#[test]
+fn empty_pattern() {
+    assert!(glob_match("", ""));
+    assert!(!glob_match("", ".text"));
+    assert!(!glob_match(".text", ""));
+}
+
+#[test]
+fn any_run() {
+    for name in ["", ".", ".text", ".text.hot", "*"] {
+        assert!(glob_match("*", name), "{:?}", name);
+        assert!(glob_match("**", name), "{:?}", name);
+    }
+    assert!(glob_match(".text*", ".text"));
+    assert!(glob_match(".text*", ".text.unlikely"));
+    assert!(!glob_match(".text*", ".rela.text"));
+    assert!(glob_match("*.text", ".rela.text"));
+    assert!(glob_match(".debug_*info", ".debug_info"));
+    assert!(glob_match(".debug_*info", ".debug_typeinfo"));
+    assert!(!glob_match(".debug_*info", ".debug_info.dwo"));
+    // backtracking past a partial match of the tokens after the `*`
+    assert!(glob_match("*.a.b", ".a.a.a.b"));
+    assert!(!glob_match("*.a.b", ".a.a.a.c"));
+}
+
+#[test]
+fn single_character() {
+    assert!(glob_match("?", "a"));
+    assert!(!glob_match("?", ""));
+    assert!(!glob_match("?", "ab"));
+    assert!(glob_match(".rel?.text", ".rela.text"));
+    assert!(!glob_match(".rel?.text", ".rel.text"));
+    assert!(glob_match("?*", "a"));
+    assert!(!glob_match("?*", ""));
+    // characters are matched whole, not byte by byte
+    assert!(glob_match(".é?", ".éé"));
+}
+
+#[test]
+fn escaped_characters() {
+    assert!(glob_match("\\*", "*"));
+    assert!(!glob_match("\\*", ".text"));
+    assert!(glob_match("\\?", "?"));
+    assert!(!glob_match("\\?", "a"));
+    assert!(glob_match("\\\\", "\\"));
+    assert!(glob_match("\\.text", ".text"));
+    assert!(glob_match(".a\\*b*", ".a*b.c"));
+    assert!(!glob_match(".a\\*b*", ".axb.c"));
+    // a trailing backslash stands for itself
+    assert!(glob_match(".text\\", ".text\\"));
+    assert!(!glob_match(".text\\", ".text"));
+}
+
+#[test]
+fn sections_with_glob_characters_in_their_name() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness)
+            .section(".rodata*", SHT_PROGBITS, SHF_ALLOC, b"star")
+            .section(".rodata?", SHT_PROGBITS, SHF_ALLOC, b"mark")
+            .section(".rodata.str", SHT_PROGBITS, SHF_ALLOC, b"str\0")
+            .build();
+        let parser = ElfParser::parse(&image.bytes).unwrap();
+        let matching = |pattern: &str| {
+            parser
+                .sections_matching(pattern)
+                .into_iter()
+                .map(|(idx, _)| idx)
+                .collect::<Vec<_>>()
+        };
+        let star = image.section_idx(".rodata*");
+        let mark = image.section_idx(".rodata?");
+        let str = image.section_idx(".rodata.str");
+
+        assert_eq!(matching(".rodata*"), [star, mark, str]);
+        assert_eq!(matching(".rodata?"), [star, mark]);
+        assert_eq!(matching(".rodata\\*"), [star]);
+        assert_eq!(matching(".rodata\\?"), [mark]);
+        assert_eq!(
+            matching("*"),
+            (0..parser.section_headers.len()).collect::<Vec<_>>()
+        );
+        // only the null section has an empty name
+        assert_eq!(matching(""), [0]);
+        assert!(matching(".rodata").is_empty());
+
+        let names: Vec<_> = parser
+            .sections()
+            .matching(".rodata\\*")
+            .map(|s| s.name().to_string())
+            .collect();
+        assert_eq!(names, [".rodata*"]);
+    }
+}
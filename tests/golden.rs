@@ -0,0 +1,535 @@
+//! Parses of real images checked against what GNU readelf reports about them.
+//!
+//! The images and their `readelf -W -hSlsdr` output are kept under `tests/golden`,
+//! along with the sources they were built from and `capture.sh`, which rebuilds them.
+//! readelf is only needed to capture its output again, never to run these tests.
+//! Every field readelf prints is read back from its output and compared with the
+//! same field of the parse, and a mismatch names the image and the field along with
+//! both values.
+
+use elf_parser::elf::dynamic::{DynFlags, DynFlags1};
+use elf_parser::elf::ehdr::ElfHType;
+use elf_parser::elf::phdr::{
+    dyn_tag_name, DT_FLAGS, DT_FLAGS_1, DT_NEEDED, DT_NULL, DT_RPATH, DT_RUNPATH, DT_SONAME,
+};
+use elf_parser::elf::shdr::SHT_DYNSYM;
+use elf_parser::elf::sym::{bind_name, shndx_name, type_name, visibility_name};
+use elf_parser::elf::{phdr, shdr};
+use elf_parser::parser::ElfParser;
+use std::fmt::{Debug, Display};
+
+/// Images along with the readelf output captured from them
+const IMAGES: [(&str, &[u8], &str); 6] = [
+    (
+        "rv64-object.o",
+        include_bytes!("golden/rv64-object.o"),
+        include_str!("golden/rv64-object.o.readelf"),
+    ),
+    (
+        "rv64-static",
+        include_bytes!("golden/rv64-static"),
+        include_str!("golden/rv64-static.readelf"),
+    ),
+    (
+        "rv64-pie",
+        include_bytes!("golden/rv64-pie"),
+        include_str!("golden/rv64-pie.readelf"),
+    ),
+    (
+        "rv64-shared",
+        include_bytes!("golden/rv64-shared"),
+        include_str!("golden/rv64-shared.readelf"),
+    ),
+    (
+        "rv32-be",
+        include_bytes!("golden/rv32-be"),
+        include_str!("golden/rv32-be.readelf"),
+    ),
+    (
+        "rv64i-test",
+        include_bytes!("../out/rv64i-test"),
+        include_str!("golden/rv64i-test.readelf"),
+    ),
+];
+
+struct SectionRow {
+    name: String,
+    s_type: String,
+    addr: u64,
+    offset: u64,
+    size: u64,
+    ent_size: u64,
+    flags: String,
+    link: u32,
+    info: u32,
+    addr_align: u64,
+}
+
+struct SegmentRow {
+    p_type: String,
+    offset: u64,
+    vaddr: u64,
+    paddr: u64,
+    filesz: u64,
+    memsz: u64,
+    flags: String,
+    align: u64,
+}
+
+struct DynamicRow {
+    d_tag: i64,
+    name: String,
+    value: String,
+}
+
+struct RelocationRow {
+    offset: u64,
+    info: u64,
+    type_name: String,
+    symbol: Option<(u64, String)>,
+    addend: i64,
+}
+
+struct SymbolRow {
+    value: u64,
+    size: u64,
+    sym_type: String,
+    bind: String,
+    visibility: String,
+    shndx: String,
+    name: String,
+}
+
+/// Tables of a `readelf -W -hSlsdr` output, in the order readelf prints them
+#[derive(Default)]
+struct Readelf {
+    header: Vec<(String, String)>,
+    sections: Vec<SectionRow>,
+    segments: Vec<SegmentRow>,
+    dynamic: Vec<DynamicRow>,
+    relocations: Vec<(String, Vec<RelocationRow>)>,
+    symbols: Vec<(String, Vec<SymbolRow>)>,
+}
+
+fn hex(s: &str) -> u64 {
+    u64::from_str_radix(s.trim_start_matches("0x"), 16)
+        .unwrap_or_else(|_| panic!("{:?} isn't a hexadecimal number", s))
+}
+
+/// Numbers readelf prints in decimal, unless they're too large for their column
+fn number(s: &str) -> u64 {
+    match s.strip_prefix("0x") {
+        Some(_) => hex(s),
+        None => s.parse().unwrap(),
+    }
+}
+
+/// Name of a symbol of the table at `idx` as its string table holds it, without the
+/// version readelf appends to dynamic symbols. Static symbol tables may hold versioned
+/// names themselves
+fn unversioned(parser: &ElfParser, idx: usize, name: &str) -> String {
+    match parser.section_headers[idx].s_type {
+        SHT_DYNSYM => name.split('@').next().unwrap().to_string(),
+        _ => name.to_string(),
+    }
+}
+
+/// Quoted name of a table in titles such as `Symbol table '.dynsym' contains 4 entries:`
+fn quoted(title: &str) -> String {
+    title.split('\'').nth(1).unwrap().to_string()
+}
+
+/// Indented `key: value` lines, as the ELF header is printed
+fn fields(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .filter(|line| line.starts_with(' '))
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+fn parse_section(line: &str) -> SectionRow {
+    let (_, row) = line.split_once(']').unwrap();
+    let tokens: Vec<&str> = row.split_whitespace().collect();
+
+    // names and flags may be empty, the address is the first field in a fixed format
+    let addr = tokens
+        .iter()
+        .position(|t| [8, 16].contains(&t.len()) && t.chars().all(|c| c.is_ascii_hexdigit()))
+        .unwrap();
+    let (name, s_type) = match addr {
+        1 => (String::new(), tokens[0].to_string()),
+        _ => (tokens[0].to_string(), tokens[1..addr].join(" ")),
+    };
+    let rest = &tokens[addr + 4..];
+    let flags = match rest.len() {
+        4 => rest[0].to_string(),
+        _ => String::new(),
+    };
+    let rest = &rest[rest.len() - 3..];
+
+    SectionRow {
+        name,
+        s_type,
+        addr: hex(tokens[addr]),
+        offset: hex(tokens[addr + 1]),
+        size: hex(tokens[addr + 2]),
+        ent_size: hex(tokens[addr + 3]),
+        flags,
+        link: rest[0].parse().unwrap(),
+        info: rest[1].parse().unwrap(),
+        addr_align: rest[2].parse().unwrap(),
+    }
+}
+
+fn parse_segment(line: &str) -> SegmentRow {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let last = tokens.len() - 1;
+
+    SegmentRow {
+        p_type: tokens[0].to_string(),
+        offset: hex(tokens[1]),
+        vaddr: hex(tokens[2]),
+        paddr: hex(tokens[3]),
+        filesz: hex(tokens[4]),
+        memsz: hex(tokens[5]),
+        // `R E` is split by the whitespace
+        flags: tokens[6..last].join(" "),
+        align: hex(tokens[last]),
+    }
+}
+
+fn parse_dynamic(line: &str) -> DynamicRow {
+    let (tag, rest) = line.trim().split_once(' ').unwrap();
+    let (name, value) = rest.trim().split_once(')').unwrap();
+
+    DynamicRow {
+        d_tag: hex(tag) as i64,
+        name: name.trim_start_matches('(').to_string(),
+        value: value.trim().to_string(),
+    }
+}
+
+fn parse_relocation(line: &str) -> RelocationRow {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+
+    // relocations against symbol 0 only print their addend, with a leading `-` when
+    // it's negative, and the others a `+` or `-` before it
+    let (symbol, addend) = match &tokens[3..] {
+        [addend] => (None, addend.to_string()),
+        [value, name @ .., sign, addend] => (
+            Some((hex(value), name.join(" "))),
+            format!("{}{}", sign.trim_start_matches('+'), addend),
+        ),
+        _ => panic!("{:?} isn't a relocation", line),
+    };
+    let addend = match addend.strip_prefix('-') {
+        Some(abs) => -(hex(abs) as i64),
+        None => hex(&addend) as i64,
+    };
+
+    RelocationRow {
+        offset: hex(tokens[0]),
+        info: hex(tokens[1]),
+        type_name: tokens[2].to_string(),
+        symbol,
+        addend,
+    }
+}
+
+fn parse_symbol(line: &str) -> SymbolRow {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+
+    SymbolRow {
+        value: hex(tokens[1]),
+        size: number(tokens[2]),
+        sym_type: tokens[3].to_string(),
+        bind: tokens[4].to_string(),
+        visibility: tokens[5].to_string(),
+        shndx: tokens[6].to_string(),
+        name: tokens[7..].join(" "),
+    }
+}
+
+impl Readelf {
+    fn parse(output: &str) -> Readelf {
+        let mut readelf = Readelf::default();
+        let mut lines = output.lines().filter(|line| !line.starts_with('#'));
+
+        while let Some(line) = lines.next() {
+            // each table runs up to the next blank line, below a line of column names
+            let mut rows = |skip| {
+                lines
+                    .by_ref()
+                    .skip(skip)
+                    .take_while(|line| !line.trim().is_empty())
+                    .collect::<Vec<_>>()
+            };
+
+            if line == "ELF Header:" {
+                readelf.header = fields(&rows(0).join("\n"));
+            } else if line == "Section Headers:" {
+                readelf.sections = rows(1)
+                    .into_iter()
+                    .take_while(|row| row.starts_with("  ["))
+                    .map(parse_section)
+                    .collect();
+            } else if line == "Program Headers:" {
+                readelf.segments = rows(1)
+                    .into_iter()
+                    .filter(|row| !row.trim_start().starts_with('['))
+                    .map(parse_segment)
+                    .collect();
+            } else if line.starts_with("Dynamic section at offset") {
+                readelf.dynamic = rows(1).into_iter().map(parse_dynamic).collect();
+            } else if line.starts_with("Relocation section") {
+                let table = rows(1).into_iter().map(parse_relocation).collect();
+                readelf.relocations.push((quoted(line), table));
+            } else if line.starts_with("Symbol table") {
+                let table = rows(1).into_iter().map(parse_symbol).collect();
+                readelf.symbols.push((quoted(line), table));
+            }
+        }
+
+        readelf
+    }
+}
+
+/// Fails on a field that differs between readelf and the parser
+fn agree<T: PartialEq + Debug>(image: &str, field: impl Display, readelf: T, parsed: T) {
+    assert!(
+        readelf == parsed,
+        "{}: {} is {:?} to readelf but parsed as {:?}",
+        image,
+        field,
+        readelf,
+        parsed
+    );
+}
+
+fn parse(data: &[u8]) -> ElfParser<'_> {
+    ElfParser::parse(data).unwrap()
+}
+
+fn check_header(image: &str, parser: &ElfParser, readelf: &Readelf) {
+    let mut parsed = fields(&parser.headers.to_string());
+
+    // readelf tells position-independent executables from shared objects
+    if parser.headers.e_type == ElfHType::SharedObject
+        && parser.dyn_flags_1().is_some_and(|flags| flags.pie())
+    {
+        let (_, e_type) = parsed.iter_mut().find(|(key, _)| key == "Type").unwrap();
+        *e_type = "DYN (Position-Independent Executable file)".to_string();
+    }
+
+    agree(image, "header", readelf.header.len(), parsed.len());
+    for ((key, theirs), (_, ours)) in readelf.header.iter().zip(&parsed) {
+        agree(image, key, theirs, ours);
+    }
+}
+
+fn check_sections(image: &str, parser: &ElfParser, readelf: &Readelf) {
+    let sections = &parser.section_headers;
+    agree(image, "sections", readelf.sections.len(), sections.len());
+
+    for (idx, (row, sh)) in readelf.sections.iter().zip(sections).enumerate() {
+        let name = parser.section_name(sh).unwrap();
+        let field = |field| format!("section [{}] {} {}", idx, name, field);
+
+        agree(image, field("name"), &row.name, &name);
+        agree(
+            image,
+            field("type"),
+            &row.s_type,
+            &shdr::type_name(sh.s_type),
+        );
+        agree(image, field("address"), row.addr, sh.addr.0);
+        agree(image, field("offset"), row.offset, sh.offset);
+        agree(image, field("size"), row.size, sh.size);
+        agree(image, field("entry size"), row.ent_size, sh.ent_size);
+        agree(
+            image,
+            field("flags"),
+            &row.flags,
+            &shdr::flags_name(sh.flags),
+        );
+        agree(image, field("link"), row.link, sh.link);
+        agree(image, field("info"), row.info, sh.info);
+        agree(image, field("alignment"), row.addr_align, sh.addr_align);
+    }
+}
+
+fn check_segments(image: &str, parser: &ElfParser, readelf: &Readelf) {
+    let segments = &parser.program_headers;
+    agree(image, "segments", readelf.segments.len(), segments.len());
+
+    for (idx, (row, ph)) in readelf.segments.iter().zip(segments).enumerate() {
+        let field = |field| format!("segment {} {}", idx, field);
+        let flags = phdr::flags_name(ph.flags);
+
+        agree(image, field("type"), &row.p_type, &ph.p_type.to_string());
+        agree(image, field("offset"), row.offset, ph.offset);
+        agree(image, field("virtual address"), row.vaddr, ph.vaddr.0);
+        agree(image, field("physical address"), row.paddr, ph.paddr.0);
+        agree(image, field("file size"), row.filesz, ph.filesz);
+        agree(image, field("memory size"), row.memsz, ph.memsz);
+        agree(image, field("flags"), row.flags.as_str(), flags.trim_end());
+        agree(image, field("alignment"), row.align, ph.align);
+    }
+}
+
+fn check_dynamic(image: &str, parser: &ElfParser, readelf: &Readelf) {
+    // readelf stops at the terminating entry, the ones after it are padding the
+    // linker reserved
+    let entries = parser.dynamic_entries().unwrap_or_default();
+    let entries = match entries.iter().position(|d| d.d_tag == DT_NULL) {
+        Some(end) => &entries[..=end],
+        None => entries,
+    };
+    agree(
+        image,
+        "dynamic entries",
+        readelf.dynamic.len(),
+        entries.len(),
+    );
+
+    for (idx, (row, entry)) in readelf.dynamic.iter().zip(entries).enumerate() {
+        let field = |field| format!("dynamic entry {} {}", idx, field);
+        let value = entry.value();
+
+        // rendered the way readelf renders each kind of entry
+        let string = |kind| {
+            format!(
+                "{}: [{}]",
+                kind,
+                parser.dynamic_string(value as u32).unwrap()
+            )
+        };
+        let parsed = match entry.d_tag {
+            DT_NEEDED => string("Shared library"),
+            DT_SONAME => string("Library soname"),
+            DT_RPATH => string("Library rpath"),
+            DT_RUNPATH => string("Library runpath"),
+            DT_FLAGS => DynFlags(value).to_string(),
+            DT_FLAGS_1 => format!("Flags: {}", DynFlags1(value)),
+            _ if row.value.ends_with("(bytes)") => format!("{} (bytes)", value),
+            _ if row.value.starts_with("0x") => format!("{:#x}", value),
+            _ if row.value.parse::<u64>().is_ok() => value.to_string(),
+            _ => dyn_tag_name(value as i64),
+        };
+
+        agree(image, field("tag"), row.d_tag, entry.d_tag);
+        agree(image, field("name"), &row.name, &dyn_tag_name(entry.d_tag));
+        agree(image, field("value"), &row.value, &parsed);
+    }
+}
+
+fn check_relocations(image: &str, parser: &ElfParser, readelf: &Readelf) {
+    let sections = parser.relocations_named().unwrap();
+    agree(
+        image,
+        "relocation sections",
+        readelf.relocations.len(),
+        sections.len(),
+    );
+
+    for ((name, rows), section) in readelf.relocations.iter().zip(&sections) {
+        agree(image, "relocation section", name, &section.name);
+        agree(image, name, rows.len(), section.relocations.len());
+
+        for (idx, (row, named)) in rows.iter().zip(&section.relocations).enumerate() {
+            let field = |field| format!("relocation {} of {} {}", idx, name, field);
+            let link = parser.section_headers[section.section_idx].link as usize;
+            let theirs = row
+                .symbol
+                .as_ref()
+                .map(|(value, name)| (*value, unversioned(parser, link, name)));
+            let symbol = named
+                .symbol
+                .as_ref()
+                .map(|(value, name)| (value.0, name.clone()));
+
+            agree(image, field("offset"), row.offset, named.rela.offset.0);
+            agree(image, field("info"), row.info, named.rela.info);
+            agree(image, field("type"), &row.type_name, &named.type_name);
+            agree(image, field("symbol"), &theirs, &symbol);
+            agree(image, field("addend"), row.addend, named.rela.addend);
+        }
+    }
+}
+
+fn check_symbols(image: &str, parser: &ElfParser, readelf: &Readelf) {
+    let tables = &parser.symbol_tables;
+    agree(image, "symbol tables", readelf.symbols.len(), tables.len());
+
+    for ((name, rows), table) in readelf.symbols.iter().zip(tables) {
+        let section = &parser.section_headers[table.section_idx];
+        agree(
+            image,
+            "symbol table",
+            name,
+            &parser.section_name(section).unwrap(),
+        );
+        agree(image, name, rows.len(), table.symbols.len());
+
+        for (idx, (row, sym)) in rows.iter().zip(&table.symbols).enumerate() {
+            let field = |field| format!("symbol {} of {} {}", idx, name, field);
+
+            agree(image, field("value"), row.value, sym.value.0);
+            agree(image, field("size"), row.size, sym.size);
+            agree(
+                image,
+                field("type"),
+                &row.sym_type,
+                &type_name(sym.sym_type()),
+            );
+            agree(image, field("binding"), &row.bind, &bind_name(sym.bind()));
+            agree(
+                image,
+                field("visibility"),
+                row.visibility.as_str(),
+                visibility_name(sym.visibility()),
+            );
+            agree(image, field("index"), &row.shndx, &shndx_name(sym.shndx));
+            agree(
+                image,
+                field("name"),
+                &unversioned(parser, table.section_idx, &row.name),
+                &parser.symbol_display_name(table, sym).unwrap(),
+            );
+        }
+    }
+}
+
+#[test]
+fn matches_readelf() {
+    for (image, data, output) in IMAGES {
+        let readelf = Readelf::parse(output);
+        let parser = parse(data);
+
+        check_header(image, &parser, &readelf);
+        check_sections(image, &parser, &readelf);
+        check_segments(image, &parser, &readelf);
+        check_dynamic(image, &parser, &readelf);
+        check_relocations(image, &parser, &readelf);
+        check_symbols(image, &parser, &readelf);
+    }
+}
+
+#[test]
+fn captures_are_complete() {
+    // a capture readelf choked on would compare nothing
+    for (image, _, output) in IMAGES {
+        let readelf = Readelf::parse(output);
+
+        assert!(!readelf.header.is_empty(), "{}", image);
+        assert!(!readelf.sections.is_empty(), "{}", image);
+        assert!(!readelf.symbols.is_empty(), "{}", image);
+    }
+
+    let readelf = Readelf::parse(IMAGES[3].2);
+    assert_eq!(readelf.dynamic.len(), 15);
+    assert_eq!(readelf.relocations[0].1[2].type_name, "R_RISCV_IRELATIVE");
+    assert_eq!(readelf.symbols[0].1[3].sym_type, "IFUNC");
+    assert_eq!(readelf.symbols[0].1[3].name, "memcpy@@RV64_1.0");
+}
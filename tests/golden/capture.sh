@@ -0,0 +1,34 @@
+#!/bin/sh
+# Rebuilds the golden images from their sources and captures what GNU readelf
+# reports about them. Needs llvm-mc, llvm-ar and yaml2obj from LLVM along with ar
+# and readelf from binutils; the tests only ever read the files written here.
+set -e
+cd "$(dirname "$0")"
+
+llvm-mc -triple=riscv64 -mattr=+c,+relax -filetype=obj rv64-object.s -o rv64-object.o
+
+# the same two objects archived by each tool, the second one under a name too long
+# for the member header
+members="$(mktemp -d)"
+cp rv64-object.o "$members"
+llvm-mc -triple=riscv64 -mattr=+c,+relax -filetype=obj rv64-archive-member.s \
+    -o "$members/rv64_archive_member_with_a_long_name.o"
+rm -f librv64-gnu.a librv64-llvm.a librv64-llvm-bsd.a
+(
+    cd "$members"
+    ar rcs "$OLDPWD/librv64-gnu.a" rv64-object.o rv64_archive_member_with_a_long_name.o
+    llvm-ar rcs --format=gnu "$OLDPWD/librv64-llvm.a" rv64-object.o \
+        rv64_archive_member_with_a_long_name.o
+    llvm-ar rcs --format=bsd "$OLDPWD/librv64-llvm-bsd.a" rv64-object.o \
+        rv64_archive_member_with_a_long_name.o
+)
+rm -r "$members"
+for source in *.yaml; do
+    yaml2obj "$source" -o "${source%.yaml}"
+done
+
+for image in rv64-object.o rv64-static rv64-pie rv64-shared rv32-be ../../out/rv64i-test; do
+    output="$(basename "$image").readelf"
+    readelf --version | head -n 1 | sed 's/^/# /' > "$output"
+    LC_ALL=C readelf -W -hSlsdr "$image" >> "$output" 2> /dev/null
+done
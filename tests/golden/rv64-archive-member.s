@@ -0,0 +1,22 @@
+# Second member of the archives, under a name too long for the member header: a
+# global function and object, and a local function left out of the symbol index
+	.text
+	.globl	archive_entry
+	.type	archive_entry, @function
+archive_entry:
+	call	local_step
+	ret
+	.size	archive_entry, .-archive_entry
+
+	.type	local_step, @function
+local_step:
+	li	a0, 42
+	ret
+	.size	local_step, .-local_step
+
+	.data
+	.globl	archive_table
+	.type	archive_table, @object
+archive_table:
+	.word	1, 2, 3, 4
+	.size	archive_table, .-archive_table
@@ -0,0 +1,45 @@
+# Relocatable object: calls, absolute and PC-relative addressing, weak, local and
+# common symbols, and an .init_array entry
+	.text
+	.globl	main
+	.type	main, @function
+main:
+	addi	sp, sp, -16
+	sd	ra, 8(sp)
+	lui	a0, %hi(message)
+	addi	a0, a0, %lo(message)
+	call	puts
+1:	auipc	a1, %pcrel_hi(counter)
+	ld	a2, %pcrel_lo(1b)(a1)
+	call	hook
+	ld	ra, 8(sp)
+	addi	sp, sp, 16
+	ret
+	.size	main, .-main
+
+	.type	helper, @function
+helper:
+	li	a0, 0
+	ret
+	.size	helper, .-helper
+
+	.weak	hook
+	.type	hook, @function
+hook:
+	ret
+	.size	hook, .-hook
+
+	.comm	buffer, 64, 8
+
+	.section	.rodata.str1.1, "aMS", @progbits, 1
+message:
+	.string	"hello"
+
+	.data
+	.type	counter, @object
+counter:
+	.dword	1
+	.size	counter, 8
+
+	.section	.init_array, "aw"
+	.dword	helper
@@ -0,0 +1,168 @@
+mod fixtures;
+
+use elf_parser::elf::shdr::{SHT_NOTE, SHT_PROGBITS};
+use elf_parser::handlers::{ParsedSection, SectionHandlers, SectionInput, SectionMatcher};
+use elf_parser::options::ParseOptions;
+use elf_parser::parser::{ElfParser, ParseError, ParseWarning};
+use fixtures::{Image, ImageBuilder, ENCODINGS};
+
+/// Value of the `.metadata` blob a build system injects: a version then a payload
+#[derive(Debug, PartialEq, Eq)]
+struct Metadata {
+    version: u32,
+    payload: Vec<u8>,
+}
+
+fn metadata_handler(input: &SectionInput) -> Result<ParsedSection, ParseError> {
+    Ok(Box::new(Metadata {
+        version: input.ctx.read_u32(input.data, 0)?,
+        payload: input.data.get(4..).unwrap_or_default().to_vec(),
+    }))
+}
+
+fn size_handler(input: &SectionInput) -> Result<ParsedSection, ParseError> {
+    Ok(Box::new(input.header.size))
+}
+
+fn image(builder: ImageBuilder) -> Image {
+    let ctx = builder.ctx();
+    let mut metadata = vec![];
+    ctx.put_u32(&mut metadata, 3);
+    metadata.extend_from_slice(b"abcd");
+
+    builder
+        .section(".metadata", SHT_PROGBITS, 0, &metadata)
+        // too short to hold a version
+        .section(".metadata.old", SHT_PROGBITS, 0, &[1, 2])
+        .build()
+}
+
+#[test]
+fn matchers() {
+    let image = image(ImageBuilder::minimal(ENCODINGS[0].0, ENCODINGS[0].1));
+    let metadata = image.section(".metadata");
+
+    assert!(SectionMatcher::Name(".metadata*".to_string()).matches(Some(".metadata"), metadata));
+    assert!(!SectionMatcher::Name(".meta".to_string()).matches(Some(".metadata"), metadata));
+    // sections whose name doesn't resolve only match by type
+    assert!(!SectionMatcher::Name("*".to_string()).matches(None, metadata));
+    assert!(SectionMatcher::Type(SHT_PROGBITS).matches(None, metadata));
+    assert!(!SectionMatcher::Type(SHT_NOTE).matches(Some(".metadata"), metadata));
+
+    let handlers = SectionHandlers::new()
+        .register(
+            SectionMatcher::Name(".metadata".to_string()),
+            metadata_handler,
+        )
+        .register(SectionMatcher::Type(SHT_PROGBITS), size_handler);
+    assert!(SectionHandlers::new().is_empty());
+    assert!(!handlers.is_empty());
+    assert_eq!(handlers.matching(Some(".metadata"), metadata).count(), 2);
+    assert_eq!(
+        handlers.matching(Some(".metadata.old"), metadata).count(),
+        1
+    );
+
+    // handlers are told apart by their matchers only
+    let same = SectionHandlers::new()
+        .register(SectionMatcher::Name(".metadata".to_string()), size_handler)
+        .register(SectionMatcher::Type(SHT_PROGBITS), size_handler);
+    assert_eq!(handlers, same);
+    assert_ne!(
+        handlers,
+        SectionHandlers::new().register(SectionMatcher::Type(SHT_PROGBITS), size_handler)
+    );
+}
+
+#[test]
+fn parsed_sections_downcast() {
+    for (class, endianness) in ENCODINGS {
+        let image = image(ImageBuilder::minimal(class, endianness));
+        let options = ParseOptions::default()
+            .register_section_handler(
+                SectionMatcher::Name(".metadata".to_string()),
+                metadata_handler,
+            )
+            .register_section_handler(SectionMatcher::Type(SHT_PROGBITS), size_handler);
+        let parser = ElfParser::parse_with(&image.bytes, &options).unwrap();
+        let idx = image.section_idx(".metadata");
+
+        assert_eq!(
+            parser.parsed_section::<Metadata>(idx),
+            Some(&Metadata {
+                version: 3,
+                payload: b"abcd".to_vec(),
+            })
+        );
+        assert_eq!(parser.parsed_section::<u64>(idx), Some(&8));
+        // values of another type, or of another section, aren't returned
+        assert_eq!(parser.parsed_section::<String>(idx), None);
+        assert_eq!(
+            parser.parsed_section::<Metadata>(image.section_idx(".text")),
+            None
+        );
+
+        let sizes: Vec<_> = parser.parsed_sections_of::<u64>().collect();
+        assert_eq!(
+            sizes,
+            [
+                (image.section_idx(".text"), &(image.section(".text").size)),
+                (image.section_idx(".data"), &(image.section(".data").size)),
+                (idx, &8),
+                (image.section_idx(".metadata.old"), &2),
+            ]
+        );
+    }
+}
+
+#[test]
+fn failing_handler() {
+    for (class, endianness) in ENCODINGS {
+        let image = image(ImageBuilder::minimal(class, endianness));
+        let options = ParseOptions::default().register_section_handler(
+            SectionMatcher::Name(".metadata*".to_string()),
+            metadata_handler,
+        );
+        let parser = ElfParser::parse_with(&image.bytes, &options).unwrap();
+        let idx = image.section_idx(".metadata.old");
+
+        let failures: Vec<_> = parser
+            .warnings
+            .iter()
+            .filter(|w| w.code() == "W0016_SECTION_HANDLER_FAILED")
+            .collect();
+        assert_eq!(
+            failures,
+            [&ParseWarning::SectionHandlerFailed {
+                idx,
+                error: format!("{:?}", ParseError::InvalidLength),
+            }]
+        );
+        assert_eq!(failures[0].section(), Some(idx));
+        assert_eq!(parser.parsed_section::<Metadata>(idx), None);
+        assert!(parser
+            .parsed_section::<Metadata>(image.section_idx(".metadata"))
+            .is_some());
+    }
+}
+
+/// Handlers registered on a parsed file are invoked right away
+#[test]
+fn registered_after_parse() {
+    for (class, endianness) in ENCODINGS {
+        let image = image(ImageBuilder::minimal(class, endianness));
+        let mut parser = image.parse().unwrap();
+        let idx = image.section_idx(".metadata");
+        assert_eq!(parser.parsed_section::<Metadata>(idx), None);
+
+        parser.register_section_handler(
+            SectionMatcher::Name(".metadata*".to_string()),
+            metadata_handler,
+        );
+        assert_eq!(parser.parsed_section::<Metadata>(idx).unwrap().version, 3);
+        assert!(parser
+            .warnings
+            .iter()
+            .any(|w| matches!(w, ParseWarning::SectionHandlerFailed { .. })));
+    }
+}
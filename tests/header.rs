@@ -0,0 +1,304 @@
+mod fixtures;
+
+use elf_parser::elf::ehdr::{ElfHClass, ElfHData, ElfHType, ElfHVersion, ObjectVersion};
+use elf_parser::elf::shdr::{SHF_ALLOC, SHF_EXECINSTR, SHN_XINDEX, SHT_PROGBITS};
+use elf_parser::options::ParseOptions;
+use elf_parser::parser::{ElfParser, ParseError, ParseWarning};
+use elf_parser::provenance::Structure;
+use fixtures::{Image, ImageBuilder, ENCODINGS, TEXT};
+
+/// A relocatable object, whose `.text` directly follows the header as there's no
+/// program header table
+fn object(class: ElfHClass, endianness: ElfHData) -> Image {
+    ImageBuilder::new(class, endianness)
+        .e_type(ElfHType::Relocatable)
+        .section(".text", SHT_PROGBITS, SHF_ALLOC | SHF_EXECINSTR, &TEXT)
+        .build()
+}
+
+/// Warnings of `parser` about a header table overlapping something it shouldn't
+fn overlap_warnings(parser: &ElfParser) -> Vec<ParseWarning> {
+    parser
+        .warnings
+        .iter()
+        .filter(|w| {
+            matches!(
+                w,
+                ParseWarning::ProgramHeadersOverlapHeader { .. }
+                    | ParseWarning::HeaderTablesOverlap { .. }
+                    | ParseWarning::SectionHeadersOverlapHeader { .. }
+            )
+        })
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn program_headers_overlapping_header() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        image.set_header(|l| l.e_phoff, 0);
+
+        let parser = image.parse().unwrap();
+        assert_eq!(
+            overlap_warnings(&parser),
+            [ParseWarning::ProgramHeadersOverlapHeader {
+                ph_off: 0,
+                header_end: parser.headers.header_end(),
+            }]
+        );
+        assert_eq!(parser.program_headers.len(), 2);
+        assert!(parser.is_suspicious(Structure::ProgramHeader(0)));
+        assert!(!parser.is_suspicious(Structure::SectionHeader(1)));
+
+        // accessors going through the segments get by with the bogus entries
+        for idx in 0..parser.program_headers.len() {
+            let _ = parser.segment_data(idx);
+        }
+        let _ = parser.dynamic_entries();
+        let _ = parser.interpreter();
+        let _ = parser.region_map();
+        let _ = parser.size_report();
+        let _ = parser.text_relocation_targets();
+        let _ = parser.tls_layout();
+        let _ = parser.phdr_segment();
+        let _ = parser.note_groups();
+        let _ = parser.stats();
+    }
+}
+
+#[test]
+fn program_headers_overlapping_header_in_strict_mode() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        image.set_header(|l| l.e_phoff, 0);
+
+        let options = ParseOptions::default().strict(true);
+        let Err(ParseError::StrictViolation(warnings)) =
+            ElfParser::parse_with(&image.bytes, &options)
+        else {
+            panic!("strict parse didn't fail");
+        };
+        assert!(warnings
+            .iter()
+            .any(|w| matches!(w, ParseWarning::ProgramHeadersOverlapHeader { .. })));
+    }
+}
+
+#[test]
+fn program_headers_overlapping_section_headers() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        let sh_off = image.sh_off;
+        image.set_header(|l| l.e_phoff, sh_off);
+
+        let parser = image.parse().unwrap();
+        assert_eq!(
+            overlap_warnings(&parser),
+            [ParseWarning::HeaderTablesOverlap {
+                ph_off: sh_off,
+                sh_off,
+            }]
+        );
+        assert_eq!(
+            ParseWarning::HeaderTablesOverlap {
+                ph_off: sh_off,
+                sh_off
+            }
+            .offset(),
+            Some(sh_off)
+        );
+        assert!(parser.is_suspicious(Structure::ProgramHeader(0)));
+        assert!(parser.is_suspicious(Structure::SectionHeader(0)));
+        assert!(!parser.is_suspicious(Structure::SectionHeader(3)));
+        assert!(!parser.is_suspicious(Structure::FileHeader));
+    }
+}
+
+#[test]
+fn separate_header_tables_arent_suspicious() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+        let parser = image.parse().unwrap();
+
+        assert!(overlap_warnings(&parser).is_empty());
+        assert!(!parser.is_suspicious(Structure::ProgramHeader(0)));
+        assert!(!parser.is_suspicious(Structure::SectionHeader(0)));
+    }
+}
+
+#[test]
+fn version_cross_check() {
+    for (class, endianness) in ENCODINGS {
+        for (ident, e_version, warns) in [(1, 0, true), (0, 1, true), (1, 1, false)] {
+            let mut image = object(class, endianness);
+            image
+                .set_bytes(6, &[ident])
+                .set_header(|l| l.e_version, e_version);
+
+            let parser = image.parse().unwrap();
+            let version = ObjectVersion::from(e_version as u32);
+            assert_eq!(parser.headers.version, version);
+            assert_eq!(version.value(), e_version as u32);
+            let expected = ParseWarning::VersionMismatch {
+                ident: ElfHVersion::try_from(ident).unwrap(),
+                e_version: e_version as u32,
+            };
+            assert_eq!(
+                parser.warnings.contains(&expected),
+                warns,
+                "ident {}, e_version {}",
+                ident,
+                e_version
+            );
+
+            let options = ParseOptions::default().strict(true);
+            match (ElfParser::parse_with(&image.bytes, &options), warns) {
+                (Ok(_), false) => {}
+                (Err(ParseError::StrictViolation(warnings)), true) => {
+                    assert_eq!(warnings, [expected])
+                }
+                (result, _) => panic!("{:?}", result.err()),
+            }
+        }
+    }
+
+    let warning = ParseWarning::VersionMismatch {
+        ident: ElfHVersion::ElfEvCurr,
+        e_version: 2,
+    };
+    assert_eq!(warning.code(), "W0026_VERSION_MISMATCH");
+    assert_eq!(
+        warning.to_string(),
+        "ELF version 1 in the identification and 0x2 in e_version, expected 1 in both"
+    );
+    assert_eq!(ObjectVersion::from(2), ObjectVersion::Unknown(2));
+    assert_eq!(format!("{:?}", ObjectVersion::from(1)), "Known(ElfEvCurr)");
+}
+
+fn initial_section_warnings(parser: &ElfParser) -> Vec<ParseWarning> {
+    parser
+        .warnings
+        .iter()
+        .filter(|w| matches!(w, ParseWarning::NonNullInitialSection { .. }))
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn non_null_initial_section() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = object(class, endianness);
+        assert_eq!(initial_section_warnings(&image.parse().unwrap()), []);
+
+        image
+            .set_section(0, |l| l.sh_type, SHT_PROGBITS as u64)
+            .set_section(0, |l| l.sh_addr, 0x1000)
+            .set_section(0, |l| l.sh_size, 0x20)
+            .set_section(0, |l| l.sh_addralign, 8);
+        let parser = image.parse().unwrap();
+        let warning = |field, value| ParseWarning::NonNullInitialSection { field, value };
+
+        let warnings = initial_section_warnings(&parser);
+        assert_eq!(
+            warnings,
+            [
+                warning("type", SHT_PROGBITS as u64),
+                warning("addr", 0x1000),
+                warning("size", 0x20),
+                warning("addralign", 8),
+            ]
+        );
+        assert_eq!(warnings[0].code(), "W0005_NON_NULL_SECTION_0");
+        assert_eq!(warnings[0].section(), Some(0));
+    }
+}
+
+/// `link` holds the index of the section header string table when `e_shstrndx` defers
+/// to it, so it isn't reported then
+#[test]
+fn extended_fields_of_initial_section() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = object(class, endianness);
+        let shstrtab = image.section_idx(".shstrtab") as u64;
+        image.set_section(0, |l| l.sh_link, shstrtab);
+        assert_eq!(
+            initial_section_warnings(&image.parse().unwrap()),
+            [ParseWarning::NonNullInitialSection {
+                field: "link",
+                value: shstrtab,
+            }]
+        );
+
+        image.set_header(|l| l.e_shstrndx, SHN_XINDEX as u64);
+        let parser = image.parse().unwrap();
+        assert_eq!(initial_section_warnings(&parser), []);
+        assert_eq!(
+            parser.section_name(&parser.section_headers[1]).unwrap(),
+            ".text"
+        );
+    }
+}
+
+fn ident_warnings(parser: &ElfParser) -> Vec<ParseWarning> {
+    parser
+        .warnings
+        .iter()
+        .filter(|w| {
+            matches!(
+                w,
+                ParseWarning::IdentPadding { .. } | ParseWarning::AbiVersionWithoutOsAbi { .. }
+            )
+        })
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn dirty_ident_padding() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = object(class, endianness);
+        let parser = image.parse().unwrap();
+        assert!(parser.headers.ident.is_padding_clean());
+        assert_eq!(ident_warnings(&parser), []);
+
+        image.set_bytes(9, &[0, 0, 0xde, 0xad, 0, 0, 1]);
+        let parser = image.parse().unwrap();
+        assert!(!parser.headers.ident.is_padding_clean());
+        assert_eq!(parser.headers.ident.padding(), &[0, 0, 0xde, 0xad, 0, 0, 1]);
+
+        let warnings = ident_warnings(&parser);
+        assert_eq!(
+            warnings,
+            [ParseWarning::IdentPadding {
+                padding: [0, 0, 0xde, 0xad, 0, 0, 1],
+            }]
+        );
+        assert_eq!(warnings[0].code(), "W0009_IDENT_PADDING");
+        assert_eq!(warnings[0].offset(), Some(9));
+    }
+}
+
+#[test]
+fn abi_version_without_os_abi() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = object(class, endianness);
+        // EI_OSABI left as ELFOSABI_SYSV
+        image.set_bytes(8, &[2]);
+
+        let parser = image.parse().unwrap();
+        let warnings = ident_warnings(&parser);
+        assert_eq!(
+            warnings,
+            [ParseWarning::AbiVersionWithoutOsAbi { abi_version: 2 }]
+        );
+        assert_eq!(warnings[0].code(), "W0010_ABI_VERSION_WITHOUT_OSABI");
+        assert_eq!(warnings[0].offset(), Some(8));
+        // the ABI version isn't part of the padding
+        assert!(parser.headers.ident.is_padding_clean());
+
+        // ELFOSABI_GNU gives the version a meaning
+        image.set_bytes(7, &[3]);
+        assert_eq!(ident_warnings(&image.parse().unwrap()), []);
+    }
+}
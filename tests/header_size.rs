@@ -0,0 +1,156 @@
+mod fixtures;
+
+use elf_parser::elf::ehdr::{ElfHClass, ElfHData, ElfHType};
+use elf_parser::elf::shdr::{SHF_ALLOC, SHF_EXECINSTR, SHT_PROGBITS};
+use elf_parser::parser::ParseWarning;
+use fixtures::{Image, ImageBuilder, ENCODINGS, TEXT};
+
+/// A relocatable object, whose `.text` directly follows the header as there's no
+/// program header table
+fn object(class: ElfHClass, endianness: ElfHData) -> Image {
+    ImageBuilder::new(class, endianness)
+        .e_type(ElfHType::Relocatable)
+        .section(".text", SHT_PROGBITS, SHF_ALLOC | SHF_EXECINSTR, &TEXT)
+        .build()
+}
+
+#[test]
+fn undersized_header() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = object(class, endianness);
+        let expected = image.parse().unwrap().headers.class_size() as u16;
+        image.set_header(|l| l.e_ehsize, expected as u64 - 12);
+
+        let parser = image.parse().unwrap();
+        assert!(parser.warnings.contains(&ParseWarning::UndersizedHeader {
+            eh_size: expected - 12,
+            expected,
+        }));
+        // every field is decoded anyway
+        assert_eq!(parser.headers.header_end(), expected as u64);
+        assert_eq!(parser.section_headers.len(), 3);
+    }
+}
+
+#[test]
+fn oversized_header() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = object(class, endianness);
+        let expected = image.parse().unwrap().headers.class_size() as u16;
+        image.set_header(|l| l.e_ehsize, expected as u64 + 16);
+
+        let parser = image.parse().unwrap();
+        assert!(parser.warnings.contains(&ParseWarning::OversizedHeader {
+            eh_size: expected + 16,
+            expected,
+        }));
+        assert_eq!(parser.headers.header_end(), expected as u64 + 16);
+        assert_eq!(parser.headers.extra_bytes(&image.bytes), TEXT);
+    }
+}
+
+#[test]
+fn exact_header() {
+    for (class, endianness) in ENCODINGS {
+        let image = object(class, endianness);
+        let parser = image.parse().unwrap();
+
+        assert!(parser.headers.extra_bytes(&image.bytes).is_empty());
+        assert!(!parser.warnings.iter().any(|w| matches!(
+            w,
+            ParseWarning::OversizedHeader { .. } | ParseWarning::UndersizedHeader { .. }
+        )));
+    }
+}
+
+/// The header sizes of both classes and of an extended header. Whatever `e_ehsize`
+/// says, every field of the class is decoded from the start of the file: a smaller
+/// size is reported, the bytes of a larger one past the fields are `extra_bytes`, and
+/// the tables are looked for past the larger of both
+#[test]
+fn header_sizes() {
+    for (class, endianness) in ENCODINGS {
+        let class_size: u16 = match class {
+            ElfHClass::ElfClass32 => 52,
+            _ => 64,
+        };
+
+        for eh_size in [52u16, 64, 80] {
+            let mut image = object(class, endianness);
+            let sh_off = image.sh_off;
+            image.set_header(|l| l.e_ehsize, eh_size as u64);
+
+            let parser = image.parse().unwrap();
+            let headers = &parser.headers;
+            assert_eq!(headers.class_size(), class_size as usize);
+            assert_eq!(headers.eh_size, eh_size);
+            assert_eq!(headers.header_end(), eh_size.max(class_size) as u64);
+            assert_eq!(headers.sh_off, sh_off);
+            assert_eq!(parser.section_headers.len(), 3);
+
+            let size_warnings = parser
+                .warnings
+                .iter()
+                .filter(|w| {
+                    matches!(
+                        w,
+                        ParseWarning::OversizedHeader { .. }
+                            | ParseWarning::UndersizedHeader { .. }
+                    )
+                })
+                .collect::<Vec<_>>();
+            let extra = headers.extra_bytes(&image.bytes);
+            match eh_size.cmp(&class_size) {
+                std::cmp::Ordering::Less => {
+                    assert_eq!(
+                        size_warnings,
+                        [&ParseWarning::UndersizedHeader {
+                            eh_size,
+                            expected: class_size,
+                        }]
+                    );
+                    assert!(extra.is_empty());
+                }
+                std::cmp::Ordering::Equal => {
+                    assert!(size_warnings.is_empty());
+                    assert!(extra.is_empty());
+                }
+                std::cmp::Ordering::Greater => {
+                    assert_eq!(
+                        size_warnings,
+                        [&ParseWarning::OversizedHeader {
+                            eh_size,
+                            expected: class_size,
+                        }]
+                    );
+                    assert_eq!(extra, &image.bytes[class_size as usize..eh_size as usize]);
+                }
+            }
+
+            // the same bytes always decode the same way
+            let again = image.parse().unwrap();
+            assert_eq!(
+                format!("{:?}", again.headers),
+                format!("{:?}", parser.headers)
+            );
+            assert_eq!(again.warnings, parser.warnings);
+        }
+    }
+}
+
+#[test]
+fn section_headers_overlapping_header() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = object(class, endianness);
+        image.set_header(|l| l.e_shoff, 16);
+
+        let parser = image.parse().unwrap();
+        assert!(parser
+            .warnings
+            .contains(&ParseWarning::SectionHeadersOverlapHeader {
+                sh_off: 16,
+                header_end: parser.headers.header_end(),
+            }));
+        assert!(parser.section_headers.is_empty());
+    }
+}
@@ -0,0 +1,237 @@
+mod fixtures;
+
+use elf_parser::elf::phdr::{PType, PF_READ};
+use elf_parser::elf::shdr::{SHF_ALLOC, SHT_PROGBITS};
+use fixtures::{ImageBuilder, DATA, ENCODINGS, TEXT};
+use std::collections::BTreeMap;
+
+fn hex_bytes(line: &str) -> Vec<u8> {
+    (0..line.len())
+        .step_by(2)
+        .map(|idx| u8::from_str_radix(&line[idx..idx + 2], 16).unwrap())
+        .collect()
+}
+
+/// Memory image and start address described by Intel HEX records, checking the
+/// checksum of every record
+fn decode_ihex(ihex: &str) -> (BTreeMap<u64, u8>, u32) {
+    let mut memory = BTreeMap::new();
+    let (mut upper, mut start) = (0u64, 0);
+
+    for line in ihex.lines() {
+        let record = hex_bytes(line.strip_prefix(':').unwrap());
+        assert_eq!(record.iter().fold(0u8, |sum, b| sum.wrapping_add(*b)), 0);
+
+        let len = record[0] as usize;
+        let address = u16::from_be_bytes([record[1], record[2]]) as u64;
+        let data = &record[4..4 + len];
+        match record[3] {
+            0x00 => data.iter().enumerate().for_each(|(idx, b)| {
+                memory.insert(upper + address + idx as u64, *b);
+            }),
+            0x01 => break,
+            0x04 => upper = (u16::from_be_bytes([data[0], data[1]]) as u64) << 16,
+            0x05 => start = u32::from_be_bytes(data.try_into().unwrap()),
+            r_type => panic!("unexpected record type {}", r_type),
+        }
+    }
+
+    (memory, start)
+}
+
+/// Memory image and start address described by S-records, checking the checksum and
+/// count of every record
+fn decode_srec(srec: &str) -> (BTreeMap<u64, u8>, u64) {
+    let mut memory = BTreeMap::new();
+    let (mut count, mut start) = (0, 0);
+
+    for line in srec.lines() {
+        let s_type = line.as_bytes()[1] - b'0';
+        let record = hex_bytes(&line[2..]);
+        assert_eq!(record[0] as usize, record.len() - 1);
+        assert_eq!(!record.iter().fold(0u8, |sum, b| sum.wrapping_add(*b)), 0);
+
+        let addr_len = match s_type {
+            0 | 1 | 5 | 9 => 2,
+            2 | 6 | 8 => 3,
+            _ => 4,
+        };
+        let address = record[1..1 + addr_len]
+            .iter()
+            .fold(0u64, |v, b| v << 8 | *b as u64);
+        let data = &record[1 + addr_len..record.len() - 1];
+        match s_type {
+            1..=3 => {
+                count += 1;
+                data.iter().enumerate().for_each(|(idx, b)| {
+                    memory.insert(address + idx as u64, *b);
+                });
+            }
+            5 | 6 => assert_eq!(address, count),
+            7..=9 => start = address,
+            _ => {}
+        }
+    }
+
+    (memory, start)
+}
+
+/// Memory image the loadable segments of the file describe
+fn expected(parser: &elf_parser::parser::ElfParser) -> BTreeMap<u64, u8> {
+    let mut memory = BTreeMap::new();
+    for (idx, ph) in parser.program_headers.iter().enumerate() {
+        if ph.p_type == PType::PtLoad {
+            let data = parser.segment_data(idx).unwrap();
+            data.iter().enumerate().for_each(|(off, b)| {
+                memory.insert(ph.paddr.0 + off as u64, *b);
+            });
+        }
+    }
+
+    memory
+}
+
+#[test]
+fn ihex_round_trip() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+        let parser = image.parse().unwrap();
+
+        for record_len in [1, 5, 16, 255] {
+            let (memory, start) = decode_ihex(&parser.to_ihex_with(record_len));
+            assert_eq!(memory, expected(&parser));
+            assert_eq!(start as u64, image.entry);
+        }
+        assert_eq!(
+            expected(&parser).values().copied().collect::<Vec<_>>(),
+            [TEXT.as_slice(), DATA.as_slice()].concat()
+        );
+    }
+}
+
+#[test]
+fn srec_round_trip() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+        let parser = image.parse().unwrap();
+
+        for record_len in [1, 7, 16, 252] {
+            let (memory, start) = decode_srec(&parser.to_srec_with(record_len));
+            assert_eq!(memory, expected(&parser));
+            assert_eq!(start, image.entry);
+        }
+    }
+}
+
+#[test]
+fn ihex_crosses_64k_boundary() {
+    for (class, endianness) in ENCODINGS {
+        // the segment spans 0x1fff0..0x20010, once mapped at `BASE_ADDR`
+        let filler = vec![0u8; 0xff00];
+        let data = (0..=0xff).collect::<Vec<u8>>();
+        let mut image = ImageBuilder::new(class, endianness)
+            .section(".filler", SHT_PROGBITS, 0, &filler)
+            .section(".rodata", SHT_PROGBITS, SHF_ALLOC, &data)
+            .segment(PType::PtLoad, PF_READ, &[".rodata"])
+            .build();
+        let addr = 0x1fff0;
+        image
+            .set_segment(0, |l| l.p_paddr, addr)
+            .set_segment(0, |l| l.p_vaddr, addr)
+            .set_segment(0, |l| l.p_align, 1);
+        let parser = image.parse().unwrap();
+
+        let (memory, _) = decode_ihex(&parser.to_ihex());
+        assert_eq!(memory.len(), data.len());
+        assert_eq!(memory.keys().next(), Some(&addr));
+        assert_eq!(memory.values().copied().collect::<Vec<_>>(), data);
+    }
+}
+
+/// Segments are emitted at their load address, which firmware often keeps in flash
+/// apart from the address they run at
+#[test]
+fn physical_addresses() {
+    const FLASH: u64 = 0x8000_0000;
+
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        let text = image.section(".text").addr.0;
+        let data = image.section(".data").addr.0;
+        image
+            .set_segment(0, |l| l.p_paddr, FLASH)
+            .set_segment(1, |l| l.p_paddr, FLASH + 0x100);
+        let parser = image.parse().unwrap();
+        assert_eq!(parser.program_headers[0].vaddr.0, text);
+
+        let memory = expected(&parser);
+        assert_eq!(memory.keys().next(), Some(&FLASH));
+        assert_eq!(
+            memory.keys().last(),
+            Some(&(FLASH + 0x100 + DATA.len() as u64 - 1))
+        );
+        assert!(!memory.contains_key(&text) && !memory.contains_key(&data));
+
+        assert_eq!(decode_ihex(&parser.to_ihex()).0, memory);
+        assert_eq!(decode_srec(&parser.to_srec()).0, memory);
+    }
+}
+
+/// S-records use the smallest address form holding every address, the entry point
+/// included
+#[test]
+fn srec_address_forms() {
+    for (class, endianness) in ENCODINGS {
+        for (paddr, entry, data_type, end_type) in [
+            (0x100, 0x100, '1', '9'),
+            (0x100, 0x10000, '2', '8'),
+            (0x10000, 0x100, '2', '8'),
+            (0xfff0, 0x100, '2', '8'),
+            (0x100_0000, 0x100, '3', '7'),
+        ] {
+            let mut image = ImageBuilder::minimal(class, endianness).build();
+            image
+                .set_segment(0, |l| l.p_paddr, paddr)
+                .set_segment(1, |l| l.p_paddr, paddr + 0x100)
+                .set_header(|l| l.e_entry, entry);
+            let parser = image.parse().unwrap();
+
+            let srec = parser.to_srec();
+            let types = srec
+                .lines()
+                .map(|line| line.chars().nth(1).unwrap())
+                .collect::<String>();
+            let expected_types = format!("0{}5{}", data_type.to_string().repeat(2), end_type);
+            assert_eq!(types, expected_types, "{:#x}, {:#x}", paddr, entry);
+
+            let (memory, start) = decode_srec(&srec);
+            assert_eq!(memory, expected(&parser));
+            assert_eq!(start, entry);
+        }
+    }
+}
+
+#[test]
+fn record_length_limits() {
+    let (class, endianness) = ENCODINGS[0];
+    let data = vec![0xa5; 0x300];
+    let image = ImageBuilder::new(class, endianness)
+        .section(".rodata", SHT_PROGBITS, SHF_ALLOC, &data)
+        .segment(PType::PtLoad, PF_READ, &[".rodata"])
+        .build();
+    let parser = image.parse().unwrap();
+
+    // no records of zero bytes, and no more bytes than the length field counts
+    assert_eq!(parser.to_ihex_with(0), parser.to_ihex_with(1));
+    assert_eq!(parser.to_ihex_with(1000), parser.to_ihex_with(0xff));
+    assert_eq!(parser.to_srec_with(0), parser.to_srec_with(1));
+    assert_eq!(parser.to_srec_with(1000), parser.to_srec_with(0xff - 3 - 1));
+
+    for ihex in [parser.to_ihex_with(1), parser.to_ihex_with(1000)] {
+        assert_eq!(decode_ihex(&ihex).0, expected(&parser));
+    }
+    for srec in [parser.to_srec_with(1), parser.to_srec_with(1000)] {
+        assert!(srec.lines().all(|line| line.len() <= 2 + 2 * 0x100));
+        assert_eq!(decode_srec(&srec).0, expected(&parser));
+    }
+}
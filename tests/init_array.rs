@@ -0,0 +1,236 @@
+mod fixtures;
+
+use elf_parser::bytes::Address;
+use elf_parser::elf::ehdr::{ElfHClass, ElfHData, ElfHType};
+use elf_parser::elf::phdr::{
+    PType, DT_INIT_ARRAY, DT_INIT_ARRAYSZ, DT_NULL, DT_RELA, DT_RELAENT, DT_RELASZ, PF_READ,
+    PF_WRITE,
+};
+use elf_parser::elf::shdr::{
+    SHF_ALLOC, SHF_WRITE, SHT_DYNAMIC, SHT_FINI_ARRAY, SHT_INIT_ARRAY, SHT_PREINIT_ARRAY, SHT_RELA,
+};
+use elf_parser::parser::{ParseError, ParseWarning};
+use fixtures::{Image, ImageBuilder, ENCODINGS};
+
+/// Function the relative relocation of the second entry of `.init_array` points to
+const RELOCATED: u64 = 0x2000;
+
+/// Array of a first function, a slot filled by a relative relocation, then padding of
+/// both kinds
+fn init_array(class: ElfHClass, endianness: ElfHData) -> Vec<u8> {
+    let ctx = ImageBuilder::minimal(class, endianness).ctx();
+    let all_ones = u64::MAX >> (64 - 8 * ctx.word_size());
+    let mut array = vec![];
+    for entry in [0x1000, 0, all_ones, 0] {
+        ctx.put_uword(&mut array, entry);
+    }
+    array
+}
+
+/// Builds a shared object whose `.init_array` is located by the dynamic segment as
+/// well, its size being declared as `entries` entries. Addresses are only known once
+/// the image is laid out, so it's built a first time to find them, then again with them
+fn shared_object(class: ElfHClass, endianness: ElfHData, entries: u64) -> Image {
+    let build = |array_addr: u64, rela_addr: u64| {
+        let builder = ImageBuilder::minimal(class, endianness).e_type(ElfHType::SharedObject);
+        let ctx = builder.ctx();
+        let word = ctx.word_size() as u64;
+
+        let mut rela = vec![];
+        ctx.put_uword(&mut rela, array_addr + word);
+        // R_RISCV_RELATIVE
+        ctx.put_uword(&mut rela, 3);
+        ctx.put_uword(&mut rela, RELOCATED);
+
+        let mut dynamic = vec![];
+        for (tag, value) in [
+            (DT_RELA, rela_addr),
+            (DT_RELASZ, 3 * word),
+            (DT_RELAENT, 3 * word),
+            (DT_INIT_ARRAY, array_addr),
+            (DT_INIT_ARRAYSZ, entries * word),
+            (DT_NULL, 0),
+        ] {
+            ctx.put_uword(&mut dynamic, tag as u64);
+            ctx.put_uword(&mut dynamic, value);
+        }
+
+        let array = init_array(class, endianness);
+        builder
+            .section(".rela.dyn", SHT_RELA, SHF_ALLOC, &rela)
+            .section(".init_array", SHT_INIT_ARRAY, SHF_ALLOC | SHF_WRITE, &array)
+            .section(".dynamic", SHT_DYNAMIC, SHF_ALLOC | SHF_WRITE, &dynamic)
+            .segment(PType::PtLoad, PF_READ, &[".rela.dyn"])
+            .segment(
+                PType::PtLoad,
+                PF_READ | PF_WRITE,
+                &[".init_array", ".dynamic"],
+            )
+            .segment(PType::PtDynamic, PF_READ | PF_WRITE, &[".dynamic"])
+            .build()
+    };
+
+    let layout = build(0, 0);
+    build(
+        layout.section(".init_array").addr.0,
+        layout.section(".rela.dyn").addr.0,
+    )
+}
+
+fn array_mismatches(warnings: &[ParseWarning]) -> Vec<&ParseWarning> {
+    warnings
+        .iter()
+        .filter(|w| {
+            matches!(
+                w,
+                ParseWarning::DynamicMismatch {
+                    tag: DT_INIT_ARRAY | DT_INIT_ARRAYSZ,
+                    ..
+                }
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn arrays_decoded_without_padding() {
+    for (class, endianness) in ENCODINGS {
+        let builder = ImageBuilder::minimal(class, endianness);
+        let ctx = builder.ctx();
+        let array = |entries: &[u64]| {
+            let mut array = vec![];
+            for entry in entries {
+                ctx.put_uword(&mut array, *entry);
+            }
+            array
+        };
+
+        let image = builder
+            .section(
+                ".preinit_array",
+                SHT_PREINIT_ARRAY,
+                SHF_ALLOC | SHF_WRITE,
+                &array(&[0x1100]),
+            )
+            .section(
+                ".init_array",
+                SHT_INIT_ARRAY,
+                SHF_ALLOC | SHF_WRITE,
+                &init_array(class, endianness),
+            )
+            .section(
+                ".fini_array",
+                SHT_FINI_ARRAY,
+                SHF_ALLOC | SHF_WRITE,
+                &array(&[0x1300, 0, 0x1200]),
+            )
+            .build();
+        let parser = image.parse().unwrap();
+
+        assert_eq!(parser.preinit_array().unwrap(), [Address(0x1100)]);
+        // without dynamic relocations, the zero slot is padding like the others
+        assert_eq!(parser.init_array().unwrap(), [Address(0x1000)]);
+        assert_eq!(
+            parser.fini_array().unwrap(),
+            [Address(0x1300), Address(0x1200)]
+        );
+    }
+}
+
+#[test]
+fn no_array() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+
+        assert!(image.parse().unwrap().init_array().unwrap().is_empty());
+    }
+}
+
+#[test]
+fn truncated_entry() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness)
+            .section(".init_array", SHT_INIT_ARRAY, SHF_ALLOC, &[0; 3])
+            .build();
+
+        assert!(matches!(
+            image.parse().unwrap().init_array(),
+            Err(ParseError::InvalidLength)
+        ));
+    }
+}
+
+#[test]
+fn relocated_entries() {
+    for (class, endianness) in ENCODINGS {
+        let image = shared_object(class, endianness, 4);
+        let parser = image.parse().unwrap();
+
+        assert_eq!(
+            parser.init_array().unwrap(),
+            [Address(0x1000), Address(RELOCATED)]
+        );
+        assert!(array_mismatches(&parser.warnings).is_empty());
+    }
+}
+
+#[test]
+fn located_without_section_headers() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = shared_object(class, endianness, 4);
+        image
+            .set_header(|l| l.e_shoff, 0)
+            .set_header(|l| l.e_shnum, 0)
+            .set_header(|l| l.e_shstrndx, 0);
+        let parser = image.parse().unwrap();
+
+        assert!(parser.section_headers.is_empty());
+        assert_eq!(
+            parser.init_array().unwrap(),
+            [Address(0x1000), Address(RELOCATED)]
+        );
+    }
+}
+
+/// The dynamic segment declaring a shorter array than the section is reported, the
+/// section being trusted
+#[test]
+fn dynamic_mismatch() {
+    for (class, endianness) in ENCODINGS {
+        let image = shared_object(class, endianness, 1);
+        let parser = image.parse().unwrap();
+        let word = parser.ctx.word_size() as u64;
+
+        assert_eq!(
+            array_mismatches(&parser.warnings),
+            [&ParseWarning::DynamicMismatch {
+                tag: DT_INIT_ARRAYSZ,
+                found: word,
+                expected: 4 * word,
+                against: "size of .init_array",
+            }]
+        );
+        assert_eq!(
+            parser.init_array().unwrap(),
+            [Address(0x1000), Address(RELOCATED)]
+        );
+    }
+}
+
+/// An array ending past the largest address can't be checked against the relocations
+#[test]
+fn array_end_overflows() {
+    for (class, endianness) in ENCODINGS {
+        if class != ElfHClass::ElfClass64 {
+            continue;
+        }
+        let mut image = shared_object(class, endianness, 4);
+        let idx = image.section_idx(".init_array");
+        image.set_section(idx, |l| l.sh_addr, u64::MAX - 8);
+
+        assert!(matches!(
+            image.parse().unwrap().init_array(),
+            Err(ParseError::InvalidLength)
+        ));
+    }
+}
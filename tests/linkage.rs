@@ -0,0 +1,214 @@
+mod fixtures;
+
+use elf_parser::bytes::Address;
+use elf_parser::elf::ehdr::ElfHType;
+use elf_parser::elf::phdr::{PType, DT_NEEDED, DT_NULL, DT_STRSZ, DT_STRTAB, PF_READ, PF_WRITE};
+use elf_parser::elf::shdr::{SHF_ALLOC, SHF_WRITE, SHT_DYNAMIC, SHT_PROGBITS, SHT_STRTAB};
+use elf_parser::elf::sym::{Elf64Sym, STB_GLOBAL, STT_FUNC};
+use elf_parser::linkage::{LibcFlavor, Linkage};
+use fixtures::{Image, ImageBuilder, ENCODINGS};
+
+/// Adds a dynamic segment holding only the terminating `DT_NULL` entry
+fn with_dynamic(builder: ImageBuilder) -> ImageBuilder {
+    let ctx = builder.ctx();
+    let mut entries = vec![];
+    ctx.put_uword(&mut entries, DT_NULL as u64);
+    ctx.put_uword(&mut entries, 0);
+
+    builder
+        .section(".dynamic", SHT_DYNAMIC, SHF_ALLOC | SHF_WRITE, &entries)
+        .segment(PType::PtDynamic, PF_READ | PF_WRITE, &[".dynamic"])
+}
+
+#[test]
+fn static_executable() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+
+        assert_eq!(image.parse().unwrap().linkage().unwrap(), Linkage::Static);
+    }
+}
+
+#[test]
+fn static_pie() {
+    for (class, endianness) in ENCODINGS {
+        let builder = ImageBuilder::minimal(class, endianness).e_type(ElfHType::SharedObject);
+        let image = with_dynamic(builder).build();
+        let parser = image.parse().unwrap();
+
+        assert!(parser.dynamic_entries().is_some());
+        assert_eq!(parser.linkage().unwrap(), Linkage::StaticPie);
+    }
+}
+
+#[test]
+fn executable_with_dynamic_segment() {
+    for (class, endianness) in ENCODINGS {
+        let image = with_dynamic(ImageBuilder::minimal(class, endianness)).build();
+
+        assert_eq!(image.parse().unwrap().linkage().unwrap(), Linkage::Static);
+    }
+}
+
+#[test]
+fn interpreter() {
+    for (class, endianness) in ENCODINGS {
+        let builder = ImageBuilder::minimal(class, endianness)
+            .e_type(ElfHType::SharedObject)
+            .section(".interp", SHT_PROGBITS, SHF_ALLOC, b"/lib/ld.so.1\0")
+            .segment(PType::PtInterp, PF_READ, &[".interp"]);
+        let image = with_dynamic(builder).build();
+
+        assert_eq!(
+            image.parse().unwrap().linkage().unwrap(),
+            Linkage::Dynamic {
+                interpreter: "/lib/ld.so.1".to_string(),
+                needed: vec![],
+            }
+        );
+    }
+}
+
+/// Adds a dynamic segment listing `needed`, whose names are read from a `.dynstr`
+/// loaded along with it, to the images `builder` starts
+fn with_needed(builder: impl Fn() -> ImageBuilder, needed: &[&str]) -> Image {
+    let build = |first: Option<&Image>| {
+        let builder = builder();
+        let ctx = builder.ctx();
+        let addr = first.map_or(0, |image: &Image| image.section(".dynstr").addr.0);
+
+        let mut dynstr = b"\0".to_vec();
+        let mut entries = vec![];
+        for name in needed {
+            ctx.put_uword(&mut entries, DT_NEEDED as u64);
+            ctx.put_uword(&mut entries, dynstr.len() as u64);
+            dynstr.extend(name.as_bytes());
+            dynstr.push(0);
+        }
+        for (tag, value) in [
+            (DT_STRTAB, addr),
+            (DT_STRSZ, dynstr.len() as u64),
+            (DT_NULL, 0),
+        ] {
+            ctx.put_uword(&mut entries, tag as u64);
+            ctx.put_uword(&mut entries, value);
+        }
+
+        builder
+            .section(".dynstr", SHT_STRTAB, SHF_ALLOC, &dynstr)
+            .section(".dynamic", SHT_DYNAMIC, SHF_ALLOC | SHF_WRITE, &entries)
+            .segment(PType::PtLoad, PF_READ | PF_WRITE, &[".dynstr", ".dynamic"])
+            .segment(PType::PtDynamic, PF_READ | PF_WRITE, &[".dynamic"])
+            .build()
+    };
+
+    build(Some(&build(None)))
+}
+
+fn with_interpreter(builder: ImageBuilder, interpreter: &str) -> ImageBuilder {
+    let mut path = interpreter.as_bytes().to_vec();
+    path.push(0);
+
+    builder
+        .section(".interp", SHT_PROGBITS, SHF_ALLOC, &path)
+        .segment(PType::PtInterp, PF_READ, &[".interp"])
+}
+
+#[test]
+fn flavor_from_interpreter() {
+    for (class, endianness) in ENCODINGS {
+        for (interpreter, flavor) in [
+            ("/lib/ld-linux-riscv64-lp64d.so.1", Some(LibcFlavor::Glibc)),
+            ("/lib/ld-musl-riscv64.so.1", Some(LibcFlavor::Musl)),
+            ("/system/bin/linker64", None),
+        ] {
+            let image = with_needed(
+                || with_interpreter(ImageBuilder::minimal(class, endianness), interpreter),
+                &[],
+            );
+            let parser = image.parse().unwrap();
+
+            assert_eq!(
+                parser.linkage().unwrap(),
+                Linkage::Dynamic {
+                    interpreter: interpreter.to_string(),
+                    needed: vec![],
+                }
+            );
+            assert_eq!(parser.libc_flavor().unwrap(), flavor, "{}", interpreter);
+        }
+    }
+}
+
+/// Shared objects request no interpreter, the libraries they need tell the flavor
+#[test]
+fn flavor_from_needed_libraries() {
+    for (class, endianness) in ENCODINGS {
+        for (needed, flavor) in [
+            (["libm.so.6", "libc.so.6"], Some(LibcFlavor::Glibc)),
+            (
+                ["libz.so.1", "libc.musl-riscv64.so.1"],
+                Some(LibcFlavor::Musl),
+            ),
+            (["libz.so.1", "libfoo.so"], None),
+        ] {
+            let image = with_needed(
+                || ImageBuilder::minimal(class, endianness).e_type(ElfHType::SharedObject),
+                &needed,
+            );
+            let parser = image.parse().unwrap();
+
+            assert_eq!(parser.needed_libraries().unwrap(), needed);
+            assert_eq!(
+                parser.linkage().unwrap(),
+                Linkage::Dynamic {
+                    interpreter: String::new(),
+                    needed: needed.map(String::from).to_vec(),
+                }
+            );
+            assert_eq!(parser.libc_flavor().unwrap(), flavor, "{:?}", needed);
+        }
+    }
+}
+
+/// Static files keep the symbols of the start files of their C library
+#[test]
+fn flavor_from_static_symbols() {
+    let function = Elf64Sym {
+        name: 0,
+        info: STB_GLOBAL << 4 | STT_FUNC,
+        other: 0,
+        shndx: 1,
+        value: Address(0),
+        size: 0,
+    };
+
+    for (class, endianness) in ENCODINGS {
+        for (symbol, flavor) in [
+            ("__libc_setup_tls", Some(LibcFlavor::Glibc)),
+            ("__init_libc", Some(LibcFlavor::Musl)),
+            ("main", None),
+        ] {
+            let image = ImageBuilder::minimal(class, endianness)
+                .symbols(&[(symbol, function)])
+                .build();
+            let parser = image.parse().unwrap();
+
+            assert_eq!(parser.linkage().unwrap(), Linkage::Static);
+            assert_eq!(parser.libc_flavor().unwrap(), flavor, "{}", symbol);
+        }
+
+        // a static PIE is told apart the same way
+        let image = with_needed(
+            || {
+                ImageBuilder::minimal(class, endianness)
+                    .e_type(ElfHType::SharedObject)
+                    .symbols(&[("__dls2", function)])
+            },
+            &[],
+        );
+        let parser = image.parse().unwrap();
+        assert_eq!(parser.linkage().unwrap(), Linkage::StaticPie);
+        assert_eq!(parser.libc_flavor().unwrap(), Some(LibcFlavor::Musl));
+    }
+}
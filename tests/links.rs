@@ -0,0 +1,248 @@
+mod fixtures;
+
+use elf_parser::bytes::Address;
+use elf_parser::elf::ehdr::{ElfHClass, ElfHData};
+use elf_parser::elf::shdr::{
+    SHF_INFO_LINK, SHT_DYNSYM, SHT_GNU_VERNEED, SHT_GNU_VERSYM, SHT_RELA, SHT_STRTAB,
+};
+use elf_parser::elf::sym::{Elf64Sym, STB_GLOBAL, STT_FUNC};
+use elf_parser::links::{resolve_link, LinkInfo};
+use elf_parser::parser::{ParseError, ParseWarning};
+use fixtures::{Image, ImageBuilder, BASE_ADDR, ENCODINGS};
+
+fn symbol() -> Elf64Sym {
+    Elf64Sym {
+        name: 0,
+        info: STB_GLOBAL << 4 | STT_FUNC,
+        other: 0,
+        shndx: 1,
+        value: Address(BASE_ADDR),
+        size: 4,
+    }
+}
+
+/// `ImageBuilder::minimal` with both symbol tables and the version sections
+fn builder(class: ElfHClass, endianness: ElfHData) -> ImageBuilder {
+    ImageBuilder::minimal(class, endianness)
+        .symbols(&[("main", symbol())])
+        .symbol_table(".dynsym", SHT_DYNSYM, ".dynstr", &[("puts", symbol())])
+        .section(".gnu.version", SHT_GNU_VERSYM, 0, &[0, 0, 1, 0])
+        .section(".gnu.version_r", SHT_GNU_VERNEED, 0, &[])
+}
+
+#[test]
+fn symtab_cycle() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = builder(class, endianness).build();
+        let symtab = image.section_idx(".symtab");
+        let dynsym = image.section_idx(".dynsym");
+        image
+            .set_section(symtab, |l| l.sh_link, dynsym as u64)
+            .set_section(dynsym, |l| l.sh_link, symtab as u64);
+
+        let parser = image.parse().unwrap();
+        let table = &parser.symbol_tables[0];
+        let err = parser.symbol_name(table, &table.symbols[1]).unwrap_err();
+        assert!(
+            matches!(&err, ParseError::LinkCycle { path } if *path == [symtab, dynsym, symtab]),
+            "{:?}",
+            err
+        );
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "section links loop: [{0}] -> [{1}] -> [{0}]",
+                symtab, dynsym
+            )
+        );
+        assert!(parser.symbols_by_name("main").is_empty());
+    }
+}
+
+#[test]
+fn verneed_cycle() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = builder(class, endianness).build();
+        let dynsym = image.section_idx(".dynsym");
+        let versym = image.section_idx(".gnu.version");
+        let verneed = image.section_idx(".gnu.version_r");
+
+        // `.gnu.version` reaches `.dynstr` through `.dynsym`
+        image.set_section(versym, |l| l.sh_link, dynsym as u64);
+        let parser = image.parse().unwrap();
+        let dynstr = parser.linked_string_table(versym).unwrap().unwrap();
+        assert_eq!(dynstr.section_idx, image.section_idx(".dynstr"));
+
+        image
+            .set_section(versym, |l| l.sh_link, verneed as u64)
+            .set_section(verneed, |l| l.sh_link, versym as u64);
+        let parser = image.parse().unwrap();
+        assert!(matches!(
+            parser.linked_string_table(versym),
+            Err(ParseError::LinkCycle { path }) if path == [versym, verneed, versym]
+        ));
+    }
+}
+
+#[test]
+fn dead_ends() {
+    let (class, endianness) = ENCODINGS[0];
+    let mut image = builder(class, endianness).build();
+    let symtab = image.section_idx(".symtab");
+    let sections = &image.parse().unwrap().section_headers;
+
+    // the null section and sections without links end the traversal
+    assert_eq!(resolve_link(sections, 0, &[SHT_STRTAB]).unwrap(), None);
+    let text = image.section_idx(".text");
+    assert_eq!(resolve_link(sections, text, &[SHT_STRTAB]).unwrap(), None);
+
+    image.set_section(symtab, |l| l.sh_link, 0xffff);
+    let parser = image.parse().unwrap();
+    assert_eq!(parser.resolve_link(symtab, &[SHT_STRTAB]).unwrap(), None);
+    assert!(matches!(
+        parser.symbol_name(
+            &parser.symbol_tables[0],
+            &parser.symbol_tables[0].symbols[1]
+        ),
+        Err(ParseError::NameUnavailable)
+    ));
+}
+
+/// `builder` along with relocations of `.text`, every link and info pointing at the
+/// section of the expected type
+fn linked(class: ElfHClass, endianness: ElfHData) -> Image {
+    let mut image = builder(class, endianness)
+        .section(".rela.text", SHT_RELA, SHF_INFO_LINK, &[])
+        .build();
+    let rela = image.section_idx(".rela.text");
+    let symtab = image.section_idx(".symtab") as u64;
+    let text = image.section_idx(".text") as u64;
+    let versym = image.section_idx(".gnu.version");
+    let dynsym = image.section_idx(".dynsym") as u64;
+    let verneed = image.section_idx(".gnu.version_r");
+    let dynstr = image.section_idx(".dynstr") as u64;
+    image
+        .set_section(rela, |l| l.sh_link, symtab)
+        .set_section(rela, |l| l.sh_info, text)
+        .set_section(versym, |l| l.sh_link, dynsym)
+        .set_section(verneed, |l| l.sh_link, dynstr);
+    image
+}
+
+#[test]
+fn valid_links() {
+    for (class, endianness) in ENCODINGS {
+        let image = linked(class, endianness);
+        let report = image.parse().unwrap().section_links();
+        let link = |name: &str| report.links[image.section_idx(name)];
+
+        assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+        assert_eq!(report.links.len(), image.section_headers.len());
+        assert_eq!(
+            link(".symtab"),
+            LinkInfo::Symtab {
+                strtab: image.section_idx(".strtab"),
+                first_global: 1,
+            }
+        );
+        assert_eq!(
+            link(".rela.text"),
+            LinkInfo::Rela {
+                symtab: image.section_idx(".symtab"),
+                target: image.section_idx(".text"),
+            }
+        );
+        assert_eq!(
+            link(".gnu.version"),
+            LinkInfo::Versym {
+                dynsym: image.section_idx(".dynsym"),
+            }
+        );
+        assert_eq!(
+            link(".gnu.version_r"),
+            LinkInfo::Version {
+                strtab: image.section_idx(".dynstr"),
+                count: 0,
+            }
+        );
+        assert_eq!(link(".text"), LinkInfo::None);
+        assert_eq!(report.links[0], LinkInfo::None);
+    }
+}
+
+#[test]
+fn out_of_range_links() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = linked(class, endianness);
+        let rela = image.section_idx(".rela.text");
+        let symtab = image.section_idx(".symtab");
+        image
+            .set_section(rela, |l| l.sh_link, 0xffff)
+            .set_section(rela, |l| l.sh_info, 0xfffe)
+            // more locals than the table holds symbols
+            .set_section(symtab, |l| l.sh_info, 3);
+
+        let report = image.parse().unwrap().section_links();
+        assert_eq!(
+            report.warnings,
+            [
+                ParseWarning::InvalidLink {
+                    idx: symtab,
+                    field: "info",
+                    value: 3,
+                },
+                ParseWarning::InvalidLink {
+                    idx: rela,
+                    field: "link",
+                    value: 0xffff,
+                },
+                ParseWarning::InvalidLink {
+                    idx: rela,
+                    field: "info",
+                    value: 0xfffe,
+                },
+            ]
+        );
+        // the fields are decoded all the same
+        assert_eq!(
+            report.links[rela],
+            LinkInfo::Rela {
+                symtab: 0xffff,
+                target: 0xfffe,
+            }
+        );
+        assert_eq!(report.warnings[1].code(), "W0015_BAD_LINK");
+        assert_eq!(report.warnings[1].section(), Some(rela));
+    }
+}
+
+#[test]
+fn wrong_type_links() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = linked(class, endianness);
+        let symtab = image.section_idx(".symtab");
+        let versym = image.section_idx(".gnu.version");
+        let text = image.section_idx(".text");
+        image
+            .set_section(symtab, |l| l.sh_link, text as u64)
+            // `.gnu.version` links to the dynamic symbol table, not the static one
+            .set_section(versym, |l| l.sh_link, symtab as u64);
+
+        let report = image.parse().unwrap().section_links();
+        assert_eq!(
+            report.warnings,
+            [
+                ParseWarning::InvalidLink {
+                    idx: symtab,
+                    field: "link",
+                    value: text as u32,
+                },
+                ParseWarning::InvalidLink {
+                    idx: versym,
+                    field: "link",
+                    value: symtab as u32,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,275 @@
+mod fixtures;
+
+use elf_parser::bytes::Address;
+use elf_parser::elf::ehdr::{ElfHClass, ElfHData, ElfHType};
+use elf_parser::elf::phdr::{
+    PType, DT_HASH, DT_JMPREL, DT_NULL, DT_PLTRELSZ, DT_RELAENT, DT_RELASZ, DT_STRSZ, DT_STRTAB,
+    DT_SYMENT, PF_READ, PF_WRITE,
+};
+use elf_parser::elf::shdr::{
+    Elf64SHdr, SHF_ALLOC, SHF_WRITE, SHT_DYNAMIC, SHT_NOTE, SHT_PROGBITS, SHT_RELA, SHT_STRTAB,
+};
+use elf_parser::options::ParseOptions;
+use elf_parser::parser::{ElfParser, ParseError, ParseWarning};
+use fixtures::{Image, ImageBuilder, ENCODINGS};
+
+fn header(s_type: u32, flags: u64, addr: u64, align: u64, ent_size: u64) -> Elf64SHdr {
+    Elf64SHdr {
+        name: 0,
+        s_type,
+        flags,
+        addr: Address(addr),
+        offset: 0x100,
+        size: 0x30,
+        link: 0,
+        info: 0,
+        addr_align: align,
+        ent_size,
+    }
+}
+
+#[test]
+fn header_helpers() {
+    assert!(header(SHT_PROGBITS, 0, 0, 0, 0x10).has_table());
+    assert!(!header(SHT_PROGBITS, 0, 0, 0, 0).has_table());
+
+    for (align, constrained, valid) in [
+        (0, false, true),
+        (1, false, true),
+        (8, true, true),
+        (6, true, false),
+    ] {
+        let sh = header(SHT_PROGBITS, SHF_ALLOC, 0x1000, align, 0);
+        assert_eq!(sh.has_align_constraints(), constrained, "{}", align);
+        assert_eq!(sh.has_valid_alignment(), valid, "{}", align);
+    }
+
+    assert!(header(SHT_PROGBITS, SHF_ALLOC, 0x1004, 8, 0).is_misaligned());
+    assert!(!header(SHT_PROGBITS, SHF_ALLOC, 0x1008, 8, 0).is_misaligned());
+    assert!(!header(SHT_PROGBITS, SHF_ALLOC, 0x1003, 1, 0).is_misaligned());
+}
+
+#[test]
+fn header_lint() {
+    assert!(header(SHT_PROGBITS, SHF_ALLOC, 0x1000, 8, 0x10)
+        .lint()
+        .is_empty());
+    assert_eq!(
+        header(SHT_PROGBITS, SHF_ALLOC, 0x1000, 8, 0x20).lint(),
+        ["size isn't a multiple of the entry size"]
+    );
+    assert_eq!(
+        header(SHT_NOTE, 0, 0x1000, 12, 0).lint(),
+        [
+            "alignment isn't a power of two",
+            "non-allocated section has an address"
+        ]
+    );
+
+    let mut sh = header(SHT_PROGBITS, 0, 0, 0, 0);
+    sh.offset = 0;
+    assert_eq!(
+        sh.lint(),
+        ["PROGBITS section at offset 0, over the ELF header"]
+    );
+    sh.s_type = SHT_NOTE;
+    assert!(sh.lint().is_empty());
+}
+
+#[test]
+fn lint_warnings() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        let text = image.section_idx(".text");
+        let data = image.section_idx(".data");
+        let shstrtab = image.section_idx(".shstrtab");
+        image
+            .set_section(text, |l| l.sh_entsize, 3)
+            .set_section(data, |l| l.sh_addralign, 0x1000)
+            .set_section(shstrtab, |l| l.sh_addr, 0x2000);
+
+        let parser = image.parse().unwrap();
+        let data_addr = image.section(".data").addr.0;
+        let expected = [
+            ParseWarning::SectionLint {
+                idx: text,
+                reason: "size isn't a multiple of the entry size",
+            },
+            ParseWarning::MisalignedSection {
+                idx: data,
+                addr: data_addr,
+                align: 0x1000,
+            },
+            ParseWarning::SectionLint {
+                idx: shstrtab,
+                reason: "non-allocated section has an address",
+            },
+        ];
+
+        assert_eq!(parser.lint_sections(), expected);
+        assert!(expected.iter().all(|w| parser.warnings.contains(w)));
+        assert_eq!(expected[0].code(), "W0019_SECTION_LINT");
+        assert_eq!(expected[0].section(), Some(text));
+        assert_eq!(
+            expected[0].to_string(),
+            format!(
+                "section [{}]: size isn't a multiple of the entry size",
+                text
+            )
+        );
+    }
+}
+
+#[test]
+fn clean_image() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+
+        assert!(image.parse().unwrap().lint_sections().is_empty());
+    }
+}
+
+/// Symbol and relocation entry sizes of `class`
+fn entry_sizes(class: ElfHClass) -> (u64, u64) {
+    match class {
+        ElfHClass::ElfClass32 => (16, 12),
+        _ => (24, 24),
+    }
+}
+
+/// Builds an executable whose dynamic entries describe `.dynstr` and `.rela.plt`, with
+/// `patch` changing the value of any of them. The sections are only placed once the
+/// image is laid out, so it's built a first time to find their addresses
+fn linked(class: ElfHClass, endianness: ElfHData, patch: impl Fn(i64, u64) -> u64) -> Image {
+    let (sym_size, rela_size) = entry_sizes(class);
+    let build = |dynstr: &Elf64SHdr, plt: &Elf64SHdr| {
+        let builder = ImageBuilder::new(class, endianness).e_type(ElfHType::Executable);
+        let ctx = builder.ctx();
+
+        let mut dynamic = vec![];
+        for (tag, value) in [
+            (DT_HASH, dynstr.addr.0),
+            (DT_STRTAB, dynstr.addr.0),
+            (DT_STRSZ, dynstr.size),
+            (DT_SYMENT, sym_size),
+            (DT_RELASZ, 2 * rela_size),
+            (DT_RELAENT, rela_size),
+            (DT_JMPREL, plt.addr.0),
+            (DT_PLTRELSZ, plt.size),
+            (DT_NULL, 0),
+        ] {
+            ctx.put_uword(&mut dynamic, tag as u64);
+            ctx.put_uword(&mut dynamic, patch(tag, value));
+        }
+
+        builder
+            .section(".dynstr", SHT_STRTAB, SHF_ALLOC, b"\0libc.so.6\0")
+            .section(
+                ".rela.plt",
+                SHT_RELA,
+                SHF_ALLOC,
+                &vec![0; 2 * rela_size as usize],
+            )
+            .section(".dynamic", SHT_DYNAMIC, SHF_ALLOC | SHF_WRITE, &dynamic)
+            .segment(PType::PtLoad, PF_READ, &[".dynstr", ".rela.plt"])
+            .segment(PType::PtLoad, PF_READ | PF_WRITE, &[".dynamic"])
+            .segment(PType::PtDynamic, PF_READ | PF_WRITE, &[".dynamic"])
+            .build()
+    };
+
+    let first = build(&header(0, 0, 0, 0, 0), &header(0, 0, 0, 0, 0));
+    build(first.section(".dynstr"), first.section(".rela.plt"))
+}
+
+#[test]
+fn consistent_dynamic() {
+    for (class, endianness) in ENCODINGS {
+        let image = linked(class, endianness, |_, value| value);
+        let parser = image.parse().unwrap();
+
+        assert!(parser.verify_dynamic().is_empty());
+        assert!(!parser.warnings.iter().any(|w| matches!(
+            w,
+            ParseWarning::DynamicMismatch { .. } | ParseWarning::DynamicUnmapped { .. }
+        )));
+    }
+}
+
+#[test]
+fn dynamic_mismatches() {
+    for (class, endianness) in ENCODINGS {
+        let (sym_size, rela_size) = entry_sizes(class);
+        let image = linked(class, endianness, |tag, value| match tag {
+            DT_STRSZ | DT_PLTRELSZ => value + 4,
+            DT_SYMENT => 8,
+            DT_RELASZ => value + 1,
+            _ => value,
+        });
+        let dynstr = image.section(".dynstr");
+        let plt = image.section(".rela.plt");
+
+        let warnings = image.parse().unwrap().verify_dynamic();
+        assert_eq!(
+            warnings,
+            [
+                ParseWarning::DynamicMismatch {
+                    tag: DT_STRSZ,
+                    found: dynstr.size + 4,
+                    expected: dynstr.size,
+                    against: "size of .dynstr",
+                },
+                ParseWarning::DynamicMismatch {
+                    tag: DT_SYMENT,
+                    found: 8,
+                    expected: sym_size,
+                    against: "size of a symbol of the class",
+                },
+                ParseWarning::DynamicMismatch {
+                    tag: DT_RELASZ,
+                    found: 2 * rela_size + 1,
+                    expected: 2 * rela_size,
+                    against: "a multiple of DT_RELAENT",
+                },
+                ParseWarning::DynamicMismatch {
+                    tag: DT_PLTRELSZ,
+                    found: plt.size + 4,
+                    expected: plt.size,
+                    against: "size of the PLT relocations",
+                },
+            ]
+        );
+        assert_eq!(warnings[0].code(), "W0024_DYNAMIC_MISMATCH");
+        assert_eq!(
+            warnings[1].to_string(),
+            format!(
+                "dynamic entry DT_SYMENT holds 0x8, expected {:#x} (size of a symbol of the class)",
+                sym_size
+            )
+        );
+
+        assert!(matches!(
+            ElfParser::parse_with(&image.bytes, &ParseOptions::default().strict(true)),
+            Err(ParseError::StrictViolation(w)) if w.ends_with(&warnings)
+        ));
+    }
+}
+
+#[test]
+fn unmapped_hash_table() {
+    for (class, endianness) in ENCODINGS {
+        let image = linked(class, endianness, |tag, value| match tag {
+            DT_HASH => 0x10,
+            _ => value,
+        });
+
+        let warnings = image.parse().unwrap().verify_dynamic();
+        assert_eq!(
+            warnings,
+            [ParseWarning::DynamicUnmapped {
+                tag: DT_HASH,
+                addr: 0x10
+            }]
+        );
+        assert_eq!(warnings[0].code(), "W0025_DYNAMIC_UNMAPPED");
+    }
+}
@@ -0,0 +1,329 @@
+mod fixtures;
+
+use elf_parser::parser::ParseWarning;
+use fixtures::{ImageBuilder, ENCODINGS};
+use std::collections::BTreeMap;
+
+/// The JSON values the reports are made of
+#[derive(Debug, Clone, PartialEq)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(u64),
+    String(String),
+    Array(Vec<Json>),
+    Object(BTreeMap<String, Json>),
+}
+
+impl Json {
+    fn get(&self, key: &str) -> &Json {
+        match self {
+            Json::Object(members) => members
+                .get(key)
+                .unwrap_or_else(|| panic!("no member {:?} in {:?}", key, self)),
+            _ => panic!("{:?} isn't an object", self),
+        }
+    }
+
+    fn has(&self, key: &str) -> bool {
+        matches!(self, Json::Object(members) if members.contains_key(key))
+    }
+
+    fn str(&self) -> &str {
+        match self {
+            Json::String(s) => s,
+            _ => panic!("{:?} isn't a string", self),
+        }
+    }
+
+    fn array(&self) -> &[Json] {
+        match self {
+            Json::Array(values) => values,
+            _ => panic!("{:?} isn't an array", self),
+        }
+    }
+
+    fn number(&self) -> Option<u64> {
+        match self {
+            Json::Number(n) => Some(*n),
+            Json::Null => None,
+            _ => panic!("{:?} isn't a number", self),
+        }
+    }
+}
+
+/// Parser of the subset of JSON the reports use, with unsigned numbers only
+struct JsonParser<'a> {
+    s: &'a [u8],
+    pos: usize,
+}
+
+impl JsonParser<'_> {
+    fn parse(s: &str) -> Json {
+        let mut parser = JsonParser {
+            s: s.as_bytes(),
+            pos: 0,
+        };
+        let value = parser.value();
+        parser.skip_whitespace();
+        assert_eq!(parser.pos, s.len(), "trailing bytes in {}", s);
+
+        value
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.s.get(self.pos).is_some_and(u8::is_ascii_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, token: &str) {
+        self.skip_whitespace();
+        assert!(
+            self.s[self.pos..].starts_with(token.as_bytes()),
+            "expected {:?} at {}",
+            token,
+            self.pos
+        );
+        self.pos += token.len();
+    }
+
+    fn peek(&mut self) -> u8 {
+        self.skip_whitespace();
+        self.s[self.pos]
+    }
+
+    fn value(&mut self) -> Json {
+        match self.peek() {
+            b'n' => {
+                self.expect("null");
+                Json::Null
+            }
+            b't' => {
+                self.expect("true");
+                Json::Bool(true)
+            }
+            b'f' => {
+                self.expect("false");
+                Json::Bool(false)
+            }
+            b'"' => Json::String(self.string()),
+            b'[' => {
+                self.expect("[");
+                let mut values = vec![];
+                while self.peek() != b']' {
+                    if !values.is_empty() {
+                        self.expect(",");
+                    }
+                    values.push(self.value());
+                }
+                self.expect("]");
+                Json::Array(values)
+            }
+            b'{' => {
+                self.expect("{");
+                let mut members = BTreeMap::new();
+                while self.peek() != b'}' {
+                    if !members.is_empty() {
+                        self.expect(",");
+                    }
+                    self.skip_whitespace();
+                    let key = self.string();
+                    self.expect(":");
+                    assert!(members.insert(key, self.value()).is_none());
+                }
+                self.expect("}");
+                Json::Object(members)
+            }
+            _ => {
+                let start = self.pos;
+                while self.s.get(self.pos).is_some_and(u8::is_ascii_digit) {
+                    self.pos += 1;
+                }
+                let digits = std::str::from_utf8(&self.s[start..self.pos]).unwrap();
+                Json::Number(digits.parse().unwrap())
+            }
+        }
+    }
+
+    fn string(&mut self) -> String {
+        self.expect("\"");
+        let mut out = String::new();
+        loop {
+            let rest = std::str::from_utf8(&self.s[self.pos..]).unwrap();
+            let c = rest.chars().next().unwrap();
+            self.pos += c.len_utf8();
+            match c {
+                '"' => return out,
+                '\\' => {
+                    let escaped = self.s[self.pos];
+                    self.pos += 1;
+                    match escaped {
+                        b'"' => out.push('"'),
+                        b'\\' => out.push('\\'),
+                        b'n' => out.push('\n'),
+                        b'u' => {
+                            let hex = std::str::from_utf8(&self.s[self.pos..self.pos + 4]);
+                            let code = u32::from_str_radix(hex.unwrap(), 16).unwrap();
+                            out.push(char::from_u32(code).unwrap());
+                            self.pos += 4;
+                        }
+                        e => panic!("unexpected escape {}", e as char),
+                    }
+                }
+                c => {
+                    assert!(c as u32 >= 0x20, "unescaped control character");
+                    out.push(c);
+                }
+            }
+        }
+    }
+}
+
+/// Structural check of the SARIF subset documented in `lint_report`: the members the
+/// format requires are present with the right types, and every result refers to a
+/// listed rule
+fn check_sarif(log: &Json) {
+    assert_eq!(log.get("version").str(), "2.1.0");
+    assert!(log.get("$schema").str().contains("sarif-2.1.0"));
+
+    let runs = log.get("runs").array();
+    assert_eq!(runs.len(), 1);
+    let run = &runs[0];
+
+    let driver = run.get("tool").get("driver");
+    assert_eq!(driver.get("name").str(), "elf-parser");
+    assert!(!driver.get("version").str().is_empty());
+    let rules = driver
+        .get("rules")
+        .array()
+        .iter()
+        .map(|rule| rule.get("id").str())
+        .collect::<Vec<_>>();
+
+    let artifacts = run.get("artifacts").array();
+    assert_eq!(artifacts.len(), 1);
+    let uri = artifacts[0].get("location").get("uri").str();
+
+    for result in run.get("results").array() {
+        assert!(rules.contains(&result.get("ruleId").str()));
+        assert_eq!(result.get("level").str(), "warning");
+        assert!(!result.get("message").get("text").str().is_empty());
+
+        for location in result.get("locations").array() {
+            let physical = location.get("physicalLocation");
+            assert_eq!(physical.get("artifactLocation").get("uri").str(), uri);
+            if physical.has("region") {
+                let region = physical.get("region");
+                assert!(region.get("byteOffset").number().is_some());
+                if region.has("byteLength") {
+                    assert!(region.get("byteLength").number().is_some());
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn sarif_report() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        let text = image.section_idx(".text");
+        let data = image.section_idx(".data");
+        image
+            .set_section(text, |l| l.sh_entsize, 3)
+            .set_section(data, |l| l.sh_addralign, 0x1000);
+        let parser = image.parse().unwrap();
+        let uri = "build/\"odd\\name.elf";
+
+        let report = parser.lint_report(uri);
+        assert_eq!(report.findings.len(), parser.warnings.len());
+        let sarif = JsonParser::parse(&report.to_sarif());
+        check_sarif(&sarif);
+
+        let run = &sarif.get("runs").array()[0];
+        assert_eq!(
+            run.get("artifacts").array()[0].get("location").get("uri"),
+            &Json::String(uri.to_string())
+        );
+        let results = run.get("results").array();
+        assert_eq!(results.len(), parser.warnings.len());
+
+        // section anomalies are located at the section header
+        let lint = ParseWarning::SectionLint {
+            idx: text,
+            reason: "size isn't a multiple of the entry size",
+        };
+        let result = results
+            .iter()
+            .find(|r| r.get("message").get("text").str() == lint.to_string())
+            .unwrap();
+        assert_eq!(result.get("ruleId").str(), "W0019_SECTION_LINT");
+        let region = result.get("locations").array()[0]
+            .get("physicalLocation")
+            .get("region");
+        let ent_size = parser.headers.sh_ent_size as u64;
+        assert_eq!(
+            region.get("byteOffset").number(),
+            Some(parser.headers.sh_off + text as u64 * ent_size)
+        );
+        assert_eq!(region.get("byteLength").number(), Some(ent_size));
+
+        // each rule is listed once
+        let rules = run.get("tool").get("driver").get("rules").array();
+        let mut ids = rules.iter().map(|r| r.get("id").str()).collect::<Vec<_>>();
+        ids.dedup();
+        assert_eq!(ids.len(), rules.len());
+        assert!(ids.contains(&"W0007_BAD_ALIGN"));
+    }
+}
+
+#[test]
+fn json_report() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        let data = image.section_idx(".data");
+        image.set_section(data, |l| l.sh_addralign, 0x1000);
+        let parser = image.parse().unwrap();
+
+        let report = parser.lint_report("a.out");
+        let json = JsonParser::parse(&report.to_json());
+        assert_eq!(json.get("uri").str(), "a.out");
+        assert_eq!(json.get("tool").get("name").str(), "elf-parser");
+
+        let findings = json.get("findings").array();
+        assert_eq!(findings.len(), report.findings.len());
+        for (json, finding) in findings.iter().zip(&report.findings) {
+            assert_eq!(json.get("code").str(), finding.code);
+            assert_eq!(json.get("message").str(), finding.message);
+            assert_eq!(json.get("offset").number(), finding.offset);
+            assert_eq!(json.get("length").number(), finding.length);
+            assert_eq!(
+                json.get("section").number(),
+                finding.section.map(|idx| idx as u64)
+            );
+        }
+        assert!(findings
+            .iter()
+            .any(|f| f.get("code").str() == "W0007_BAD_ALIGN"
+                && f.get("section").number() == Some(data as u64)));
+    }
+}
+
+#[test]
+fn clean_report() {
+    let (class, endianness) = ENCODINGS[0];
+    let image = ImageBuilder::minimal(class, endianness).build();
+    let parser = image.parse().unwrap();
+
+    let sarif = JsonParser::parse(&parser.lint_report("a.out").to_sarif());
+    check_sarif(&sarif);
+    let run = &sarif.get("runs").array()[0];
+    assert!(run.get("results").array().is_empty());
+    assert!(run
+        .get("tool")
+        .get("driver")
+        .get("rules")
+        .array()
+        .is_empty());
+}
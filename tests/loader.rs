@@ -0,0 +1,102 @@
+mod fixtures;
+
+use elf_parser::elf::phdr::{PType, PTypeData, DT_NULL, DT_SONAME, PF_READ, PF_WRITE};
+use elf_parser::elf::shdr::{SHF_ALLOC, SHF_WRITE, SHT_DYNAMIC};
+use elf_parser::loader::LoaderView;
+use elf_parser::parser::{ElfParser, ParseError};
+use fixtures::{Image, ImageBuilder, DATA, ENCODINGS, TEXT};
+
+fn view(image: &Image) -> LoaderView<'_> {
+    ElfParser::parse_loader_view(&image.bytes).unwrap()
+}
+
+#[test]
+fn matches_the_full_parse() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+        let parser = image.parse().unwrap();
+        let view = view(&image);
+
+        assert_eq!(view.entry(), parser.headers.entry.0);
+        assert_eq!(view.program_headers.len(), parser.program_headers.len());
+        assert!(view
+            .program_headers
+            .iter()
+            .all(|ph| ph.section == PTypeData::Skipped));
+        assert_eq!(view.memory_image().unwrap(), parser.memory_image().unwrap());
+        assert!(view.dynamic_entries().is_none());
+
+        let text = image.section(".text");
+        assert_eq!(view.vaddr_to_offset(text.addr.0).unwrap(), text.offset);
+        assert_eq!(view.offset_to_vaddr(text.offset), Some(text.addr.0));
+        assert!(matches!(
+            view.vaddr_to_offset(0),
+            Err(ParseError::UnmappedAddress(0))
+        ));
+    }
+}
+
+#[test]
+fn sections_are_never_read() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        image.set_header(|l| l.e_shentsize, 0);
+        assert!(image.parse().is_err());
+
+        assert_eq!(view(&image).program_headers.len(), 2);
+    }
+}
+
+#[test]
+fn dynamic_entries() {
+    let (class, endianness) = ENCODINGS[0];
+    let builder = ImageBuilder::minimal(class, endianness);
+    let ctx = builder.ctx();
+    let mut dynamic = vec![];
+    for (tag, value) in [(DT_SONAME, 1), (DT_NULL, 0)] {
+        ctx.put_uword(&mut dynamic, tag as u64);
+        ctx.put_uword(&mut dynamic, value);
+    }
+    let image = builder
+        .section(".dynamic", SHT_DYNAMIC, SHF_ALLOC | SHF_WRITE, &dynamic)
+        .segment(PType::PtDynamic, PF_READ | PF_WRITE, &[".dynamic"])
+        .build();
+    let view = view(&image);
+
+    assert_eq!(
+        view.dynamic_entries(),
+        image.parse().unwrap().dynamic_entries()
+    );
+    assert_eq!(view.dynamic_entry(DT_SONAME).unwrap().value(), 1);
+}
+
+#[test]
+fn load_into() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+        let view = view(&image);
+        let base = view.program_headers[0].vaddr.0;
+        let data = image.section(".data").addr.0 - base;
+
+        let mut memory = vec![0xaa; (data as usize) + DATA.len() + 8];
+        view.load_into(&mut memory, base).unwrap();
+        assert_eq!(&memory[..TEXT.len()], TEXT);
+        assert_eq!(&memory[data as usize..][..DATA.len()], DATA);
+        // bytes no segment maps are left as they were
+        assert_eq!(memory.last(), Some(&0xaa));
+
+        // nothing is written unless every segment fits
+        let mut short = vec![0xaa; data as usize];
+        assert!(matches!(
+            view.load_into(&mut short, base),
+            Err(ParseError::UnmappedAddress(_))
+        ));
+        assert!(short.iter().all(|b| *b == 0xaa));
+        assert!(view.load_into(&mut memory, base + 1).is_err());
+
+        let parser = image.parse().unwrap();
+        let mut loaded = vec![0xaa; memory.len()];
+        parser.load_into(&mut loaded, base).unwrap();
+        assert_eq!(loaded, memory);
+    }
+}
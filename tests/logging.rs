@@ -0,0 +1,139 @@
+mod fixtures;
+
+use elf_parser::elf::shdr::SHT_PROGBITS;
+use fixtures::{ImageBuilder, ENCODINGS};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::cell::RefCell;
+
+thread_local! {
+    /// Records logged by the current thread, so tests running at once don't see the
+    /// records of each other
+    static RECORDS: RefCell<Vec<(Level, String)>> = const { RefCell::new(vec![]) };
+}
+
+/// Logger keeping every record in `RECORDS`
+struct CapturingLogger;
+
+impl Log for CapturingLogger {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        RECORDS.with(|records| {
+            records
+                .borrow_mut()
+                .push((record.level(), record.args().to_string()))
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: CapturingLogger = CapturingLogger;
+
+/// Runs `f`, returning the records it logged
+fn capture(f: impl FnOnce()) -> Vec<(Level, String)> {
+    // the logger can only be installed once per process, later calls fail harmlessly
+    let _ = log::set_logger(&LOGGER);
+    log::set_max_level(LevelFilter::Trace);
+    RECORDS.with(|records| records.borrow_mut().clear());
+
+    f();
+
+    RECORDS.with(|records| records.take())
+}
+
+fn messages(records: &[(Level, String)], level: Level) -> Vec<&str> {
+    records
+        .iter()
+        .filter(|(l, _)| *l == level)
+        .map(|(_, message)| message.as_str())
+        .collect()
+}
+
+#[test]
+fn malformed_file_warnings() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness)
+            .section(".quirk", 0x13, 0, &[0; 8])
+            .build();
+        let data = image.section_idx(".data");
+        let addr = image.section(".data").addr.0;
+        image
+            .set_section(data, |l| l.sh_addr, addr + 1)
+            .set_segment(1, |l| l.p_vaddr, addr + 1)
+            .set_header(|l| l.e_shstrndx, 0);
+
+        let records = capture(|| {
+            image.parse().unwrap();
+        });
+        let warnings = messages(&records, Level::Warn);
+        let quirk = image.section_idx(".quirk");
+
+        for expected in [
+            format!("section [{}] has unknown type 0x13", quirk),
+            format!(
+                "section [{}] address {:#x} is not aligned to",
+                data,
+                addr + 1
+            ),
+            "segment [1] vaddr".to_string(),
+            "section header string table not found (sh_str_ndx = 0)".to_string(),
+        ] {
+            assert!(
+                warnings.iter().any(|w| w.starts_with(&expected)),
+                "{:?}: no {:?} in {:?}",
+                class,
+                expected,
+                warnings
+            );
+        }
+    }
+}
+
+#[test]
+fn table_progress() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness)
+            .section(".rodata", SHT_PROGBITS, 0, b"ro")
+            .build();
+
+        let records = capture(|| {
+            image.parse().unwrap();
+        });
+        let debug = messages(&records, Level::Debug);
+        let starts_with = |prefix: String| debug.iter().any(|m| m.starts_with(&prefix));
+        assert!(
+            starts_with(format!(
+                "parsed 5 section headers from {:#x}..",
+                image.sh_off
+            )),
+            "{:?}",
+            debug
+        );
+        assert!(starts_with(format!(
+            "parsed 2 program headers from {:#x}..",
+            image.ph_off
+        )));
+        assert!(debug.contains(&"parsed 1 string tables"));
+
+        // one record per entry, and none of the warnings a well-formed file doesn't get
+        let trace = messages(&records, Level::Trace);
+        assert_eq!(
+            trace
+                .iter()
+                .filter(|m| m.starts_with("section header ["))
+                .count(),
+            5
+        );
+        assert_eq!(
+            trace
+                .iter()
+                .filter(|m| m.starts_with("program header ["))
+                .count(),
+            2
+        );
+        assert!(messages(&records, Level::Warn).is_empty());
+    }
+}
@@ -0,0 +1,159 @@
+mod fixtures;
+
+use elf_parser::bytes::Address;
+use elf_parser::elf::ehdr::{ElfHClass, ElfHData, ElfHType};
+use elf_parser::elf::phdr::{
+    PType, DT_HASH, DT_NULL, DT_SONAME, DT_STRSZ, DT_STRTAB, DT_SYMENT, DT_SYMTAB, PF_EXEC,
+    PF_READ, PF_WRITE,
+};
+use elf_parser::elf::shdr::{
+    SHF_ALLOC, SHF_EXECINSTR, SHF_WRITE, SHT_DYNAMIC, SHT_DYNSYM, SHT_HASH, SHT_PROGBITS,
+    SHT_STRTAB,
+};
+use elf_parser::elf::sym::{Elf64Sym, STB_GLOBAL, STT_FUNC};
+use elf_parser::layout;
+use elf_parser::object::ElfObject;
+use elf_parser::options::ParseOptions;
+use elf_parser::parser::{ElfParser, ParseError};
+use fixtures::{Image, ImageBuilder, BASE_ADDR, ENCODINGS, TEXT};
+
+const EXPORTS: [&str; 2] = ["__vdso_clock_gettime", "__vdso_gettimeofday"];
+
+/// A shared object laid out like the vDSO, whose file image is its memory image
+/// mapped at `BASE_ADDR`
+fn vdso(class: ElfHClass, endianness: ElfHData) -> Image {
+    let sym_size = layout::sym::for_class(class).size;
+    let build = |first: Option<&Image>| {
+        let builder = ImageBuilder::new(class, endianness).e_type(ElfHType::SharedObject);
+        let ctx = builder.ctx();
+        let addr = |name| first.map_or(0, |image: &Image| image.section(name).addr.0);
+
+        let mut hash = vec![];
+        for word in [1, EXPORTS.len() as u32 + 1, 1, 0, 0, 0] {
+            ctx.put_u32(&mut hash, word);
+        }
+
+        let mut dynstr = b"\0linux-vdso.so.1\0".to_vec();
+        let mut dynsym = vec![0; sym_size];
+        for (idx, name) in EXPORTS.into_iter().enumerate() {
+            let symbol = Elf64Sym {
+                name: dynstr.len() as u32,
+                info: STB_GLOBAL << 4 | STT_FUNC,
+                other: 0,
+                shndx: 4,
+                value: Address(addr(".text") + 4 * idx as u64),
+                size: 4,
+            };
+            symbol.write(&ctx, &mut dynsym);
+            dynstr.extend(name.as_bytes());
+            dynstr.push(0);
+        }
+
+        let mut dynamic = vec![];
+        for (tag, value) in [
+            (DT_SONAME, 1),
+            (DT_HASH, addr(".hash")),
+            (DT_SYMTAB, addr(".dynsym")),
+            (DT_STRTAB, addr(".dynstr")),
+            (DT_STRSZ, dynstr.len() as u64),
+            (DT_SYMENT, sym_size as u64),
+            (DT_NULL, 0),
+        ] {
+            ctx.put_uword(&mut dynamic, tag as u64);
+            ctx.put_uword(&mut dynamic, value);
+        }
+
+        builder
+            .section(".hash", SHT_HASH, SHF_ALLOC, &hash)
+            .section(".dynsym", SHT_DYNSYM, SHF_ALLOC, &dynsym)
+            .section(".dynstr", SHT_STRTAB, SHF_ALLOC, &dynstr)
+            .section(".text", SHT_PROGBITS, SHF_ALLOC | SHF_EXECINSTR, &TEXT)
+            .section(".dynamic", SHT_DYNAMIC, SHF_ALLOC | SHF_WRITE, &dynamic)
+            .segment(
+                PType::PtLoad,
+                PF_READ | PF_EXEC,
+                &[".hash", ".dynsym", ".dynstr", ".text"],
+            )
+            .segment(PType::PtLoad, PF_READ | PF_WRITE, &[".dynamic"])
+            .segment(PType::PtDynamic, PF_READ | PF_WRITE, &[".dynamic"])
+            .build()
+    };
+
+    let mut image = build(Some(&build(None)));
+    let dynstr = image.section_idx(".dynstr");
+    image.set_section(2, |l| l.sh_link, dynstr as u64);
+    image
+}
+
+fn parse_mapped(image: &Image) -> Result<ElfParser<'_>, ParseError> {
+    ElfParser::parse_with(&image.bytes, &ParseOptions::default().load_bias(BASE_ADDR))
+}
+
+fn names(symbols: Vec<(String, Elf64Sym)>) -> Vec<String> {
+    symbols.into_iter().map(|(name, _)| name).collect()
+}
+
+#[test]
+fn located_by_address() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = vdso(class, endianness);
+        let text = image.section(".text").addr.0;
+
+        // offsets a capture doesn't preserve, only the addresses locate anything
+        for idx in 0..3 {
+            image.set_segment(idx, |l| l.p_offset, 0xdead0000);
+        }
+        for name in [".hash", ".dynsym", ".dynstr", ".text", ".dynamic"] {
+            let idx = image.section_idx(name);
+            image.set_section(idx, |l| l.sh_offset, 0xdead0000);
+        }
+
+        let parser = parse_mapped(&image).unwrap();
+        let exports = parser.exports().unwrap();
+        assert_eq!(
+            exports.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(),
+            EXPORTS
+        );
+        assert_eq!(exports[1].address, text + 4);
+        assert_eq!(names(parser.symbols().unwrap()), EXPORTS);
+
+        let (idx, _) = parser.section_by_name(".text").unwrap();
+        assert_eq!(parser.section_data(idx).unwrap(), TEXT);
+        assert_eq!(parser.vaddr_to_offset(text).unwrap(), text - BASE_ADDR);
+    }
+}
+
+#[test]
+fn without_section_headers() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = vdso(class, endianness);
+        image
+            .set_header(|l| l.e_shoff, 0)
+            .set_header(|l| l.e_shnum, 0)
+            .set_header(|l| l.e_shstrndx, 0);
+
+        let parser = parse_mapped(&image).unwrap();
+        assert!(parser.section_headers.is_empty());
+        assert_eq!(names(parser.symbols().unwrap()), EXPORTS);
+        assert_eq!(parser.exports().unwrap().len(), EXPORTS.len());
+    }
+}
+
+#[test]
+fn truncated_capture() {
+    let (class, endianness) = ENCODINGS[0];
+    let image = vdso(class, endianness);
+    let dynamic = image.section(".dynamic");
+    let len = dynamic.offset as usize;
+
+    let parser = ElfParser::parse_memory_image(&image.bytes, BASE_ADDR).unwrap();
+    assert_eq!(parser.exports().unwrap().len(), EXPORTS.len());
+
+    // a capture ending before the writable segment
+    let err = ElfParser::parse_memory_image(&image.bytes[..len], BASE_ADDR).unwrap_err();
+    assert!(matches!(
+        err,
+        ParseError::InSegment { index: 1, ref source }
+            if matches!(**source, ParseError::UnmappedAddress(addr) if addr == dynamic.addr.0)
+    ));
+}
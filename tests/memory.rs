@@ -0,0 +1,58 @@
+mod fixtures;
+
+use elf_parser::elf::phdr::{PType, PF_EXEC, PF_READ, PF_WRITE};
+use elf_parser::elf::shdr::{SHF_ALLOC, SHF_EXECINSTR, SHF_WRITE, SHT_NOBITS, SHT_PROGBITS};
+use elf_parser::options::ParseOptions;
+use elf_parser::parser::{ElfParser, ParseError};
+use fixtures::{ImageBuilder, BASE_ADDR, DATA, ENCODINGS, TEXT};
+
+#[test]
+fn segments_with_bss() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::new(class, endianness)
+            .section(".text", SHT_PROGBITS, SHF_ALLOC | SHF_EXECINSTR, &TEXT)
+            .section(".data", SHT_PROGBITS, SHF_ALLOC | SHF_WRITE, &DATA)
+            .section(".bss", SHT_NOBITS, SHF_ALLOC | SHF_WRITE, &[0; 0x20])
+            .segment(PType::PtLoad, PF_READ | PF_EXEC, &[".text"])
+            .segment(PType::PtLoad, PF_READ | PF_WRITE, &[".data", ".bss"])
+            .build();
+        let parser = image.parse().unwrap();
+        let memory = parser.memory_image().unwrap();
+
+        assert_eq!(memory.entry, parser.headers.entry.0);
+        assert_eq!(memory.segments.len(), 2);
+        let text = image.section(".text").addr.0;
+        assert_eq!(memory.read(text, TEXT.len()), Some(&TEXT[..]));
+        assert!(memory.segment_at(text).unwrap().is_executable());
+
+        let data = &memory.segments[1];
+        let ph = &parser.program_headers[1];
+        assert_eq!(data.vaddr, ph.vaddr.0);
+        assert_eq!(data.data.len() as u64, ph.memsz);
+        assert!(data.is_writable() && !data.is_executable());
+        assert_eq!(memory.read(data.vaddr, DATA.len()), Some(&DATA[..]));
+        let bss = image.section(".bss").addr.0;
+        assert_eq!(memory.read(bss, 0x20), Some(&[0; 0x20][..]));
+
+        // reads can't run past the end of a segment
+        assert_eq!(memory.read(data.end() - 1, 2), None);
+        assert_eq!(memory.segment_at(BASE_ADDR - 1), None);
+        assert_eq!(memory.size(), (TEXT.len() as u64) + ph.memsz);
+    }
+}
+
+#[test]
+fn size_limit() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        image.set_segment(1, |l| l.p_memsz, 1 << 30);
+        let options = ParseOptions::default().load_segment_data(false);
+        let parser = ElfParser::parse_with(&image.bytes, &options).unwrap();
+
+        assert!(matches!(
+            parser.memory_image(),
+            Err(ParseError::AllocationTooLarge(_))
+        ));
+        assert!(parser.memory_image_with(0x1000).is_err());
+    }
+}
@@ -0,0 +1,57 @@
+mod fixtures;
+
+use elf_parser::elf::shdr::{SHF_ALLOC, SHF_WRITE, SHT_NOBITS, SHT_PROGBITS};
+use fixtures::{ImageBuilder, ENCODINGS};
+
+const COMMENT: &[u8] = b"GCC: (GNU) 13.2.0\0";
+
+/// `.bss` followed by `.comment`, which starts at the very offset of `.bss` as the
+/// latter occupies no file space
+#[test]
+fn bss_sharing_offset_with_comment() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness)
+            .section(".bss", SHT_NOBITS, SHF_ALLOC | SHF_WRITE, &[0; 0x40])
+            .section(".comment", SHT_PROGBITS, 0, COMMENT)
+            .build();
+        let bss = image.section(".bss");
+        let comment = image.section(".comment");
+        assert_eq!(bss.offset, comment.offset);
+
+        let parser = image.parse().unwrap();
+        let bss_idx = image.section_idx(".bss");
+        let sh = &parser.section_headers[bss_idx];
+        assert!(!sh.occupies_file_space());
+        assert_eq!(sh.file_size(), 0);
+        assert_eq!(sh.mem_size(), 0x40);
+        assert!(parser.section_data(bss_idx).unwrap().is_empty());
+
+        let section = parser.sections().named(".bss").next().unwrap();
+        assert!(section.data().unwrap().is_empty());
+
+        let comment_idx = image.section_idx(".comment");
+        let sh = &parser.section_headers[comment_idx];
+        assert!(sh.occupies_file_space());
+        assert_eq!(sh.file_size(), COMMENT.len() as u64);
+        // not loaded, so no memory at all
+        assert_eq!(sh.mem_size(), 0);
+        assert_eq!(parser.section_data(comment_idx).unwrap(), COMMENT);
+    }
+}
+
+/// A `.bss` whose size runs past the end of the file still parses, as none of it is
+/// read from the file
+#[test]
+fn bss_larger_than_file() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness)
+            .section(".bss", SHT_NOBITS, SHF_ALLOC | SHF_WRITE, &[0; 0x40])
+            .build();
+        let idx = image.section_idx(".bss");
+        image.set_section(idx, |l| l.sh_size, 0x100000);
+
+        let parser = image.parse().unwrap();
+        assert!(parser.section_data(idx).unwrap().is_empty());
+        assert_eq!(parser.section_headers[idx].mem_size(), 0x100000);
+    }
+}
@@ -0,0 +1,216 @@
+mod fixtures;
+
+use elf_parser::elf::ehdr::{ElfHClass, ElfHData, ElfHType};
+use elf_parser::elf::note::{AndroidIdent, AuxKey, AuxvEntry, NT_AUXV, NT_GNU_BUILD_ID};
+use elf_parser::elf::phdr::{PType, PF_READ};
+use elf_parser::elf::shdr::{SHF_ALLOC, SHT_NOTE};
+use elf_parser::notes::NoteSource;
+use fixtures::{ImageBuilder, SectionSpec, ENCODINGS};
+
+/// `.note.android.ident` of a RISC-V executable built by NDK r27 for API level 35: the
+/// API level followed by the NDK version and build number, NUL-padded to 64 bytes each
+fn android_ident_blob() -> Vec<u8> {
+    let mut note = b"\x08\0\0\0\x84\0\0\0\x01\0\0\0Android\0\x23\0\0\0".to_vec();
+    for s in ["r27", "12077973"] {
+        let mut field = s.as_bytes().to_vec();
+        field.resize(64, 0);
+        note.extend_from_slice(&field);
+    }
+    note
+}
+
+fn note_section(name: &str, data: Vec<u8>) -> SectionSpec {
+    SectionSpec {
+        name: name.to_string(),
+        s_type: SHT_NOTE,
+        flags: SHF_ALLOC,
+        align: 4,
+        link: 0,
+        info: 0,
+        ent_size: 0,
+        data,
+    }
+}
+
+#[test]
+fn build_id() {
+    for (class, endianness) in ENCODINGS {
+        let builder = ImageBuilder::minimal(class, endianness);
+        assert_eq!(builder.build().parse().unwrap().build_id().unwrap(), None);
+
+        let ctx = builder.ctx();
+        let mut note = vec![];
+        ctx.put_u32(&mut note, 4);
+        ctx.put_u32(&mut note, 8);
+        ctx.put_u32(&mut note, NT_GNU_BUILD_ID);
+        note.extend_from_slice(b"GNU\0");
+        note.extend_from_slice(&[0xde, 0xad, 0xbe, 0xef, 0, 1, 2, 3]);
+
+        let image = builder
+            .section_with(note_section(".note.gnu.build-id", note))
+            .build();
+        assert_eq!(
+            image.parse().unwrap().build_id().unwrap(),
+            Some(vec![0xde, 0xad, 0xbe, 0xef, 0, 1, 2, 3])
+        );
+    }
+}
+
+#[test]
+fn android_ident() {
+    let image = ImageBuilder::minimal(ElfHClass::ElfClass64, ElfHData::ElfData2Lsb)
+        .section_with(note_section(".note.android.ident", android_ident_blob()))
+        .build();
+    let parser = image.parse().unwrap();
+
+    assert_eq!(parser.android_api_level(), Some(35));
+    assert_eq!(
+        parser.android_ident().unwrap(),
+        Some(AndroidIdent {
+            api_level: 35,
+            ndk_version: Some("r27".to_string()),
+            ndk_build_number: Some("12077973".to_string()),
+        })
+    );
+
+    let groups = parser.note_groups().unwrap();
+    assert_eq!(
+        groups[0].source,
+        NoteSource::Section(".note.android.ident".to_string())
+    );
+    assert_eq!(
+        groups[0].describe(&groups[0].notes[0]),
+        "API level: 35, NDK: r27 (12077973)"
+    );
+}
+
+#[test]
+fn android_ident_without_ndk_strings() {
+    for (class, endianness) in ENCODINGS {
+        let builder = ImageBuilder::minimal(class, endianness);
+        assert_eq!(builder.build().parse().unwrap().android_api_level(), None);
+
+        // NDK releases before r14 only wrote the API level
+        let ctx = builder.ctx();
+        let mut note = vec![];
+        ctx.put_u32(&mut note, 8);
+        ctx.put_u32(&mut note, 4);
+        ctx.put_u32(&mut note, 1);
+        note.extend_from_slice(b"Android\0");
+        ctx.put_u32(&mut note, 21);
+
+        let image = builder
+            .section_with(note_section(".note.android.ident", note))
+            .build();
+        let ident = image.parse().unwrap().android_ident().unwrap().unwrap();
+        assert_eq!(ident.api_level, 21);
+        assert_eq!(ident.ndk_version, None);
+        assert_eq!(ident.ndk_build_number, None);
+    }
+}
+
+#[test]
+fn notes_grouped_by_owner() {
+    let builder = ImageBuilder::minimal(ElfHClass::ElfClass64, ElfHData::ElfData2Lsb);
+    let ctx = builder.ctx();
+    let mut build_id = vec![];
+    ctx.put_u32(&mut build_id, 4);
+    ctx.put_u32(&mut build_id, 4);
+    ctx.put_u32(&mut build_id, NT_GNU_BUILD_ID);
+    build_id.extend_from_slice(b"GNU\0\x01\x02\x03\x04");
+
+    let image = builder
+        .section_with(note_section(".note.android.ident", android_ident_blob()))
+        .section_with(note_section(".note.gnu.build-id", build_id))
+        .build();
+    let owners = image.parse().unwrap().notes_by_owner().unwrap();
+
+    assert_eq!(owners.keys().collect::<Vec<_>>(), ["Android", "GNU"]);
+    assert_eq!(owners["Android"].len(), 1);
+    assert_eq!(owners["GNU"][0].desc, [1, 2, 3, 4]);
+}
+
+#[test]
+fn core_auxv() {
+    for (class, endianness) in ENCODINGS {
+        let builder = ImageBuilder::minimal(class, endianness);
+        assert_eq!(builder.build().parse().unwrap().core_info().unwrap(), None);
+
+        let ctx = builder.ctx();
+        let mut auxv = vec![];
+        for (key, value) in [
+            (3, 0x10040),
+            (6, 0x1000),
+            (9, 0x10078),
+            (0x99, 1),
+            (31, 0x7ffff000),
+        ] {
+            ctx.put_uword(&mut auxv, key);
+            ctx.put_uword(&mut auxv, value);
+        }
+        // entries past `AT_NULL` aren't part of the vector
+        ctx.put_uword(&mut auxv, 0);
+        ctx.put_uword(&mut auxv, 0);
+        ctx.put_uword(&mut auxv, 7);
+        ctx.put_uword(&mut auxv, 0x40000000);
+
+        let mut note = vec![];
+        ctx.put_u32(&mut note, 5);
+        ctx.put_u32(&mut note, auxv.len() as u32);
+        ctx.put_u32(&mut note, NT_AUXV);
+        note.extend_from_slice(b"CORE\0\0\0\0");
+        note.extend_from_slice(&auxv);
+
+        let image = builder
+            .e_type(ElfHType::Core)
+            .section_with(note_section(".note.core", note))
+            .segment(PType::PtNote, PF_READ, &[".note.core"])
+            .build();
+        let core = image.parse().unwrap().core_info().unwrap().unwrap();
+
+        let entries = core.auxv().unwrap();
+        assert_eq!(entries.len(), 5);
+        assert_eq!(
+            entries[1],
+            AuxvEntry {
+                key: 6,
+                value: 0x1000
+            }
+        );
+        assert_eq!(entries[3].kind(), AuxKey::Unknown(0x99));
+        assert_eq!(core.auxv_get(AuxKey::Phdr), Some(0x10040));
+        assert_eq!(core.auxv_get(AuxKey::Entry), Some(0x10078));
+        assert_eq!(core.auxv_get(AuxKey::Execfn), Some(0x7ffff000));
+        assert_eq!(core.auxv_get(AuxKey::Base), None);
+        assert_eq!(u64::from(AuxKey::Execfn), 31);
+    }
+}
+
+/// Note segments whose bytes would end past the largest offset fail to be read, the
+/// notes of the sections before them being checked against them without overflowing
+#[test]
+fn note_segment_near_end_of_address_space() {
+    for (class, endianness) in ENCODINGS {
+        let builder = ImageBuilder::minimal(class, endianness);
+        let ctx = builder.ctx();
+        let mut note = vec![];
+        ctx.put_u32(&mut note, 4);
+        ctx.put_u32(&mut note, 4);
+        ctx.put_u32(&mut note, NT_GNU_BUILD_ID);
+        note.extend_from_slice(b"GNU\0\x01\x02\x03\x04");
+
+        let mut image = builder
+            .section_with(note_section(".note.gnu.build-id", note))
+            .segment(PType::PtNote, PF_READ, &[".note.gnu.build-id"])
+            .segment(PType::PtNote, PF_READ, &[".note.gnu.build-id"])
+            .build();
+        let parser = image.parse().unwrap();
+        assert_eq!(parser.note_groups().unwrap().len(), 1);
+
+        image.set_segment(3, |l| l.p_offset, u64::MAX - 4);
+        let parser = image.parse().unwrap();
+        assert!(parser.note_groups().is_err());
+        assert!(parser.build_id().is_err());
+        assert!(parser.notes_by_owner().is_err());
+    }
+}
@@ -0,0 +1,72 @@
+mod fixtures;
+
+use elf_parser::bytes::Address;
+use elf_parser::elf::ehdr::{ElfHClass, ElfHData, ElfHType, EM_RISCV};
+use elf_parser::elf::phdr::PType;
+use elf_parser::elf::sym::{Elf64Sym, STB_GLOBAL, STT_FUNC};
+use elf_parser::ElfObject;
+use fixtures::{Image, ImageBuilder, ENCODINGS};
+
+fn image(class: ElfHClass, endianness: ElfHData) -> Image {
+    let main = Elf64Sym {
+        name: 0,
+        info: STB_GLOBAL << 4 | STT_FUNC,
+        other: 0,
+        shndx: 1,
+        value: Address(0),
+        size: 16,
+    };
+
+    ImageBuilder::minimal(class, endianness)
+        .symbols(&[("main", main)])
+        .build()
+}
+
+/// Assertions shared by every class and byte order, only going through the trait
+fn check(object: &dyn ElfObject, image: &Image) {
+    assert_eq!(object.e_type(), ElfHType::Executable);
+    assert_eq!(object.machine(), EM_RISCV);
+    assert_eq!(object.entry(), image.section(".text").addr);
+
+    let names = object
+        .sections()
+        .without_null()
+        .map(|s| s.name().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(names, [".text", ".data", ".symtab", ".strtab", ".shstrtab"]);
+
+    let segments = object.segments();
+    assert_eq!(segments.len(), 2);
+    assert!(segments.iter().all(|ph| ph.p_type == PType::PtLoad));
+
+    let symbols = object.symbols().unwrap();
+    assert_eq!(symbols.len(), 1);
+    assert_eq!(symbols[0].0, "main");
+    assert_eq!(symbols[0].1.size, 16);
+
+    assert!(object.needed_libraries().unwrap().is_empty());
+}
+
+#[test]
+fn every_encoding_through_the_trait() {
+    for (class, endianness) in ENCODINGS {
+        let image = image(class, endianness);
+        let parser = image.parse().unwrap();
+        let object: &dyn ElfObject = &parser;
+
+        assert_eq!(object.class(), class);
+        assert_eq!(object.endianness(), endianness);
+        check(object, &image);
+    }
+}
+
+#[test]
+fn parse_detects_the_class() {
+    for class in [ElfHClass::ElfClass32, ElfHClass::ElfClass64] {
+        let image = image(class, ElfHData::ElfData2Lsb);
+        let object = elf_parser::parse(&image.bytes).unwrap();
+
+        assert_eq!(object.class(), class);
+        check(object.as_ref(), &image);
+    }
+}
@@ -0,0 +1,108 @@
+#![cfg(feature = "object-compat")]
+
+mod fixtures;
+
+use elf_parser::elf::ehdr::ElfHType;
+use elf_parser::elf::phdr::PType;
+use elf_parser::elf::shdr::SHT_SYMTAB;
+use elf_parser::object_compat::{section_kind, symbol_kind};
+use elf_parser::parser::ElfParser;
+use fixtures::{ImageBuilder, ENCODINGS};
+use object::{Object, ObjectSection, ObjectSegment, ObjectSymbol};
+
+/// RISC-V executable built with `-g`
+const RV64I_TEST: &[u8] = include_bytes!("../out/rv64i-test");
+
+/// Checks everything this crate and `object` both decode from `parser` agree
+fn assert_conforms(parser: &ElfParser) {
+    let file = parser.to_object_file().unwrap();
+
+    assert_eq!(parser.object_architecture(), file.architecture());
+    assert_eq!(parser.object_endianness(), file.endianness());
+    assert_eq!(parser.object_kind(), file.kind());
+    assert_eq!(parser.headers.entry.0, file.entry());
+
+    let sections = file
+        .sections()
+        .map(|s| {
+            (
+                s.index().0,
+                s.name().unwrap().to_string(),
+                s.address(),
+                s.kind(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let expected = parser
+        .section_headers
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(idx, sh)| {
+            let name = parser.section_name(sh).unwrap();
+            (idx, name, sh.addr.0, section_kind(sh))
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(sections, expected);
+
+    let segments = file
+        .segments()
+        .map(|s| (s.address(), s.size(), s.file_range()))
+        .collect::<Vec<_>>();
+    let expected = parser
+        .program_headers
+        .iter()
+        .filter(|ph| ph.p_type == PType::PtLoad)
+        .map(|ph| (ph.vaddr.0, ph.memsz, (ph.offset, ph.filesz)))
+        .collect::<Vec<_>>();
+    assert_eq!(segments, expected);
+
+    for table in &parser.symbol_tables {
+        let symbols = match table.sh_type {
+            SHT_SYMTAB => file.symbols().collect::<Vec<_>>(),
+            _ => file.dynamic_symbols().collect(),
+        };
+        // `object` leaves out the null symbol
+        assert_eq!(symbols.len() + 1, table.symbols.len());
+        for (symbol, sym) in symbols.iter().zip(&table.symbols[1..]) {
+            assert_eq!(
+                symbol.name().unwrap(),
+                parser.symbol_name(table, sym).unwrap()
+            );
+            assert_eq!(symbol.address(), sym.value.0);
+            assert_eq!(symbol.size(), sym.size);
+            assert_eq!(symbol.kind(), symbol_kind(sym));
+        }
+    }
+}
+
+#[test]
+fn debug_executable_conforms() {
+    let parser = ElfParser::parse(RV64I_TEST).unwrap();
+    assert_conforms(&parser);
+
+    // the DWARF sections reach `object` consumers untouched
+    let file = parser.to_object_file().unwrap();
+    let debug_info = file.section_by_name(".debug_info").unwrap();
+    let (idx, _) = parser.section_by_name(".debug_info").unwrap();
+    assert_eq!(
+        debug_info.data().unwrap(),
+        parser.section_data(idx).unwrap()
+    );
+    assert!(file.has_debug_symbols());
+}
+
+#[test]
+fn fixtures_conform() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+        let parser = ElfParser::parse(&image.bytes).unwrap();
+        assert_conforms(&parser);
+
+        let image = ImageBuilder::minimal(class, endianness)
+            .e_type(ElfHType::SharedObject)
+            .build();
+        let parser = ElfParser::parse(&image.bytes).unwrap();
+        assert_conforms(&parser);
+    }
+}
@@ -0,0 +1,240 @@
+mod fixtures;
+
+use elf_parser::elf::phdr::{PType, PTypeData, PF_EXEC, PF_READ, PF_WRITE};
+use elf_parser::elf::shdr::{SHF_ALLOC, SHF_EXECINSTR, SHF_WRITE, SHT_DYNAMIC, SHT_PROGBITS};
+use elf_parser::options::ParseOptions;
+use elf_parser::parser::{ElfParser, ParseError, ParseWarning};
+use fixtures::{ImageBuilder, DATA, ENCODINGS, TEXT};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// Allocator recording the largest allocation of each thread, so tests running at
+/// once don't see the allocations of each other
+struct CountingAllocator;
+
+thread_local! {
+    static LARGEST: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        // the slot may already be gone while the thread exits
+        let _ = LARGEST.try_with(|largest| largest.set(largest.get().max(layout.size())));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Runs `f`, returning the size of the largest allocation it made
+fn largest_allocation(f: impl FnOnce()) -> usize {
+    LARGEST.with(|largest| largest.set(0));
+    f();
+    LARGEST.with(|largest| largest.get())
+}
+
+#[test]
+fn segment_data_disabled_allocates_nothing_large() {
+    const FILESZ: usize = 4 << 20;
+
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness)
+            .section(".blob", SHT_PROGBITS, SHF_ALLOC, &vec![0xa5; FILESZ])
+            .segment(PType::PtLoad, PF_READ, &[".blob"])
+            .build();
+
+        let largest = largest_allocation(|| {
+            let parser = image.parse().unwrap();
+            assert!(matches!(
+                parser.program_headers[2].section,
+                PTypeData::PtLoadData { ref bytes, .. } if bytes.len() == FILESZ
+            ));
+        });
+        assert!(largest >= FILESZ);
+
+        let options = ParseOptions::default().load_segment_data(false);
+        let largest = largest_allocation(|| {
+            let parser = ElfParser::parse_with(&image.bytes, &options).unwrap();
+            assert!(parser
+                .program_headers
+                .iter()
+                .all(|ph| matches!(ph.section, PTypeData::Skipped)));
+            assert_eq!(parser.program_headers[2].filesz, FILESZ as u64);
+        });
+        assert!(largest < 1 << 16, "{:#x} bytes allocated", largest);
+    }
+}
+
+/// Only the file image of a loadable segment is copied while parsing, the zeros up to
+/// its memory size being left to the consumers, which produce them within their own
+/// limits
+#[test]
+fn memory_image_tail_not_allocated() {
+    const MEMSZ: u64 = 1 << 30;
+
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::new(class, endianness)
+            .section(".text", SHT_PROGBITS, SHF_ALLOC | SHF_EXECINSTR, &TEXT)
+            .section(".data", SHT_PROGBITS, SHF_ALLOC | SHF_WRITE, &[0x5a; 16])
+            .segment(PType::PtLoad, PF_READ | PF_EXEC, &[".text"])
+            .segment(PType::PtLoad, PF_READ | PF_WRITE, &[".data"])
+            .build();
+        image.set_segment(1, |l| l.p_memsz, MEMSZ);
+
+        let largest = largest_allocation(|| {
+            let parser = image.parse().unwrap();
+            let ph = &parser.program_headers[1];
+            assert_eq!((ph.filesz, ph.memsz), (16, MEMSZ));
+            assert!(matches!(
+                ph.section,
+                PTypeData::PtLoadData { ref bytes, memsz: MEMSZ, .. } if bytes[..] == [0x5a; 16]
+            ));
+            assert!(parser.memory_image().is_err());
+        });
+        assert!(largest < 1 << 16, "{:#x} bytes allocated", largest);
+
+        // the budget is only drawn for the file image
+        let options = ParseOptions::default().max_alloc(1 << 10);
+        assert!(ElfParser::parse_with(&image.bytes, &options).is_ok());
+    }
+}
+
+#[test]
+fn segment_contents_variants() {
+    for (class, endianness) in ENCODINGS {
+        let builder = ImageBuilder::minimal(class, endianness);
+        let ctx = builder.ctx();
+        let mut dynamic = vec![];
+        for word in [0x1e, 0x8, 0, 0] {
+            ctx.put_uword(&mut dynamic, word);
+        }
+        let mut image = builder
+            .section(".dynamic", SHT_DYNAMIC, SHF_ALLOC | SHF_WRITE, &dynamic)
+            .segment(PType::PtDynamic, PF_READ | PF_WRITE, &[".dynamic"])
+            .segment(PType::PtNote, PF_READ, &[".text"])
+            .segment(PType::PtLoad, PF_READ | PF_WRITE, &[])
+            .build();
+        // a loadable segment without a file image
+        image.set_segment(4, |l| l.p_filesz, 0);
+
+        let parser = image.parse().unwrap();
+        let sections: Vec<_> = parser
+            .program_headers
+            .iter()
+            .map(|ph| &ph.section)
+            .collect();
+        assert!(matches!(
+            sections[0],
+            PTypeData::PtLoadData { bytes, flags, .. } if bytes[..] == TEXT && *flags == parser.program_headers[0].flags
+        ));
+        assert!(matches!(
+            sections[1],
+            PTypeData::PtLoadData { bytes, .. } if bytes[..] == DATA
+        ));
+        match sections[2] {
+            PTypeData::PtDynamicData(entries) => {
+                assert_eq!(entries.len(), 2);
+                assert_eq!((entries[0].d_tag, entries[1].d_tag), (0x1e, 0));
+            }
+            other => panic!("{:?}: {:?}", class, other),
+        }
+        assert!(matches!(sections[3], PTypeData::Ignorable));
+        let ph = &parser.program_headers[4];
+        assert!(matches!(
+            sections[4],
+            PTypeData::ZeroFill { memsz, vaddr, .. } if *memsz == ph.memsz && *vaddr == ph.vaddr
+        ));
+
+        let options = ParseOptions::default().load_segment_data(false);
+        let parser = ElfParser::parse_with(&image.bytes, &options).unwrap();
+        let sections: Vec<_> = parser
+            .program_headers
+            .iter()
+            .map(|ph| &ph.section)
+            .collect();
+        // only the contents of the loadable segments are skipped
+        assert!(matches!(sections[0], PTypeData::Skipped));
+        assert!(matches!(sections[1], PTypeData::Skipped));
+        assert!(matches!(sections[2], PTypeData::PtDynamicData(_)));
+        assert!(matches!(sections[3], PTypeData::Ignorable));
+        assert!(matches!(sections[4], PTypeData::Skipped));
+    }
+}
+
+/// The file image is checked to lie in the file before its buffer is allocated
+#[test]
+fn segment_past_end_of_file() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        let len = image.bytes.len() as u64;
+        image
+            .set_segment(1, |l| l.p_offset, len - 4)
+            .set_segment(1, |l| l.p_memsz, 1 << 30);
+
+        let options = ParseOptions::default().max_alloc(1 << 20);
+        let parser = ElfParser::parse_with(&image.bytes, &options).unwrap();
+        assert!(matches!(
+            parser.program_headers[1].section,
+            PTypeData::Skipped
+        ));
+        assert!(parser.warnings.contains(&ParseWarning::SegmentOutOfBounds {
+            idx: 1,
+            offset: len - 4,
+            filesz: 8,
+            file_size: len,
+        }));
+
+        let err = ElfParser::parse_with(&image.bytes, &options.strict(true)).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                ParseError::InSegment { index: 1, ref source }
+                    if matches!(**source, ParseError::SegmentOutOfBounds { offset, filesz: 8 } if offset == len - 4)
+            ),
+            "{:?}",
+            err
+        );
+    }
+}
+
+/// A file image larger than the memory image it's copied into is reported, the
+/// segment being kept with no contents
+#[test]
+fn file_image_larger_than_memory_image() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        image.set_segment(1, |l| l.p_memsz, 4);
+
+        let parser = image.parse().unwrap();
+        assert_eq!(parser.program_headers[1].filesz, 8);
+        assert!(matches!(
+            parser.program_headers[1].section,
+            PTypeData::Skipped
+        ));
+        assert_eq!(
+            parser.warnings,
+            [ParseWarning::SegmentFileSizeExceedsMemSize {
+                idx: 1,
+                filesz: 8,
+                memsz: 4,
+            }]
+        );
+
+        let options = ParseOptions::default().strict(true);
+        let err = ElfParser::parse_with(&image.bytes, &options).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                ParseError::InSegment { index: 1, ref source }
+                    if matches!(**source, ParseError::SegmentFileSizeExceedsMemSize { filesz: 8, memsz: 4 })
+            ),
+            "{:?}",
+            err
+        );
+    }
+}
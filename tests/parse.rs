@@ -0,0 +1,182 @@
+mod fixtures;
+
+use elf_parser::bytes::Address;
+use elf_parser::elf::ehdr::{ElfHType, EM_RISCV, IDENT_SZ};
+use elf_parser::elf::phdr::{PType, PF_EXEC, PF_READ, PF_WRITE};
+use elf_parser::elf::shdr::{SHF_ALLOC, SHF_EXECINSTR, SHT_PROGBITS, SHT_STRTAB};
+use elf_parser::options::ParseOptions;
+use elf_parser::parser::{ElfParser, ParseError, ParseWarning};
+use fixtures::{ImageBuilder, BASE_ADDR, DATA, ENCODINGS, TEXT};
+
+#[test]
+fn file_header() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+        let parser = image.parse().unwrap();
+        let headers = &parser.headers;
+
+        assert_eq!(headers.ident.class, class);
+        assert_eq!(headers.ident.data, endianness);
+        assert_eq!(headers.e_type, ElfHType::Executable);
+        assert_eq!(headers.machine, EM_RISCV);
+        assert_eq!(headers.entry, image.section(".text").addr);
+        assert_eq!(headers.entry, Address(image.entry));
+        assert_eq!(headers.eh_size as usize, headers.class_size());
+        assert!(parser.warnings.is_empty(), "{:?}", parser.warnings);
+    }
+}
+
+#[test]
+fn header_write_round_trips() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+        let headers = image.parse().unwrap().headers;
+
+        let mut bytes = vec![];
+        headers.write(&mut bytes);
+        assert_eq!(bytes, image.bytes[..headers.class_size()]);
+    }
+}
+
+#[test]
+fn sections() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+        let parser = image.parse().unwrap();
+
+        assert_eq!(parser.section_headers.len(), 4);
+        assert_eq!(parser.headers.sh_str_ndx, 3);
+        let names = parser
+            .section_headers
+            .iter()
+            .map(|sh| parser.section_name(sh).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["", ".text", ".data", ".shstrtab"]);
+
+        let (idx, text) = parser.section_by_name(".text").unwrap();
+        assert_eq!(text.s_type, SHT_PROGBITS);
+        assert_eq!(text.flags, SHF_ALLOC | SHF_EXECINSTR);
+        assert_eq!(parser.section_data(idx).unwrap(), TEXT);
+
+        let (idx, data) = parser.section_by_name(".data").unwrap();
+        assert_eq!(data.addr, Address(BASE_ADDR + data.offset));
+        assert_eq!(parser.section_data(idx).unwrap(), DATA);
+
+        assert_eq!(parser.section_headers[3].s_type, SHT_STRTAB);
+    }
+}
+
+#[test]
+fn segments() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+        let parser = image.parse().unwrap();
+
+        assert_eq!(parser.program_headers.len(), 2);
+        for (idx, (section, flags)) in [(".text", PF_READ | PF_EXEC), (".data", PF_READ | PF_WRITE)]
+            .into_iter()
+            .enumerate()
+        {
+            let ph = &parser.program_headers[idx];
+            let sh = image.section(section);
+            assert_eq!(ph.p_type, PType::PtLoad);
+            assert_eq!(ph.flags, flags);
+            assert_eq!(ph.offset, sh.offset);
+            assert_eq!(ph.vaddr, sh.addr);
+            assert_eq!(ph.filesz, sh.size);
+            assert!(!ph.is_misaligned());
+        }
+
+        assert_eq!(parser.segment_data(0).unwrap(), TEXT);
+        assert_eq!(parser.segment_data(1).unwrap(), DATA);
+    }
+}
+
+#[test]
+fn relocatable_without_segments() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::new(class, endianness)
+            .e_type(ElfHType::Relocatable)
+            .section(".text", SHT_PROGBITS, SHF_ALLOC | SHF_EXECINSTR, &TEXT)
+            .build();
+        let parser = image.parse().unwrap();
+
+        assert_eq!(parser.headers.e_type, ElfHType::Relocatable);
+        assert_eq!(parser.headers.ph_num, 0);
+        assert!(parser.program_headers.is_empty());
+        assert_eq!(parser.section_headers.len(), 3);
+    }
+}
+
+#[test]
+fn corrupted_entry() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        image.set_header(|l| l.e_entry, 0xdead);
+
+        assert_eq!(image.parse().unwrap().headers.entry, Address(0xdead));
+    }
+}
+
+#[test]
+fn corrupted_section_name() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        let idx = image.section_idx(".data");
+        // `.text` without its leading dot
+        image.set_section(idx, |l| l.sh_name, 2);
+
+        let parser = image.parse().unwrap();
+        let name = parser.section_name(&parser.section_headers[idx]).unwrap();
+        assert_eq!(name, "text");
+    }
+}
+
+#[test]
+fn either_byte_order_without_options() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        let parser = ElfParser::parse(&image.bytes).unwrap();
+        assert_eq!(parser.headers.ident.data, endianness);
+        assert!(parser.warnings.is_empty(), "{:?}", parser.warnings);
+
+        for data in [0, 3] {
+            image.bytes[5] = data;
+            assert!(matches!(
+                ElfParser::parse(&image.bytes),
+                Err(ParseError::UnsupportedEncoding(d)) if d == data
+            ));
+        }
+    }
+}
+
+/// A zeroed identification is only read with the class and encoding given by the
+/// options
+#[test]
+fn overridden_zeroed_ident() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        image.set_bytes(0, &[0; IDENT_SZ]);
+        assert!(ElfParser::parse(&image.bytes).is_err());
+
+        let options = ParseOptions::default()
+            .force_class(class)
+            .force_endianness(endianness)
+            .skip_ident_validation(true);
+        let parser = ElfParser::parse_with(&image.bytes, &options).unwrap();
+        assert_eq!(parser.headers.ident.class, class);
+        assert_eq!(parser.headers.ident.data, endianness);
+        assert_eq!(parser.headers.sh_off, image.sh_off);
+        assert_eq!(parser.section_headers.len(), image.section_headers.len());
+        for (idx, name) in image.names.iter().enumerate() {
+            let sh = &parser.section_headers[idx];
+            assert_eq!(parser.section_name(sh).unwrap(), *name);
+            assert_eq!(sh.offset, image.section_headers[idx].offset);
+        }
+        assert!(parser.warnings.contains(&ParseWarning::IdentOverridden {
+            class,
+            endianness,
+            skipped_validation: true,
+        }));
+    }
+}
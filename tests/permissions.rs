@@ -0,0 +1,93 @@
+mod fixtures;
+
+use elf_parser::alignment::Perms;
+use elf_parser::elf::phdr::{PF_EXEC, PF_READ, PF_WRITE};
+use fixtures::{ImageBuilder, ENCODINGS};
+use std::ops::Range;
+
+const PAGE: u64 = 0x1000;
+
+const RX: Perms = Perms {
+    read: true,
+    write: false,
+    execute: true,
+    write_execute: false,
+};
+const RW: Perms = Perms {
+    read: true,
+    write: true,
+    execute: false,
+    write_execute: false,
+};
+const RWX: Perms = Perms {
+    read: true,
+    write: true,
+    execute: true,
+    write_execute: true,
+};
+
+/// Permission map of the `.text` and `.data` segments of the minimal image moved to
+/// `text` and `data`, each being (vaddr, memsz, flags)
+fn map(text: (u64, u64, u32), data: (u64, u64, u32)) -> Vec<Vec<(Range<u64>, Perms)>> {
+    ENCODINGS
+        .into_iter()
+        .map(|(class, endianness)| {
+            let mut image = ImageBuilder::minimal(class, endianness).build();
+            for (idx, (vaddr, memsz, flags)) in [text, data].into_iter().enumerate() {
+                image
+                    .set_segment(idx, |l| l.p_vaddr, vaddr)
+                    .set_segment(idx, |l| l.p_memsz, memsz)
+                    .set_segment(idx, |l| l.p_flags, flags as u64);
+            }
+
+            image.parse().unwrap().permission_map(PAGE)
+        })
+        .collect()
+}
+
+#[test]
+fn shared_page() {
+    for map in map(
+        (0x10000, 0x800, PF_READ | PF_EXEC),
+        (0x10800, 0x1000, PF_READ | PF_WRITE),
+    ) {
+        assert_eq!(map, [(0x10000..0x11000, RWX), (0x11000..0x12000, RW)]);
+    }
+}
+
+#[test]
+fn adjacent() {
+    for map in map(
+        (0x10000, 0x1000, PF_READ | PF_EXEC),
+        (0x11000, 0x1000, PF_READ | PF_WRITE),
+    ) {
+        assert_eq!(map, [(0x10000..0x11000, RX), (0x11000..0x12000, RW)]);
+    }
+}
+
+#[test]
+fn nested() {
+    for map in map(
+        (0x10000, 0x3000, PF_READ | PF_EXEC),
+        (0x11100, 0x100, PF_READ | PF_WRITE),
+    ) {
+        assert_eq!(
+            map,
+            [
+                (0x10000..0x11000, RX),
+                (0x11000..0x12000, RWX),
+                (0x12000..0x13000, RX)
+            ]
+        );
+    }
+}
+
+#[test]
+fn same_permissions_merge() {
+    for map in map(
+        (0x20000, 0x1000, PF_READ | PF_EXEC),
+        (0x10000, 0x10800, PF_READ | PF_EXEC),
+    ) {
+        assert_eq!(map, [(0x10000..0x21000, RX)]);
+    }
+}
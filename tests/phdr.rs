@@ -0,0 +1,187 @@
+mod fixtures;
+
+use elf_parser::elf::phdr::{Elf64PHdr, PType, PTypeData, PF_EXEC, PF_READ, PF_WRITE, PN_XNUM};
+use elf_parser::elf::shdr::{SHF_ALLOC, SHF_EXECINSTR, SHF_WRITE, SHT_NOBITS, SHT_PROGBITS};
+use elf_parser::options::ParseOptions;
+use elf_parser::parser::{ElfParser, ParseError, ParseWarning};
+use fixtures::{ImageBuilder, ENCODINGS, TEXT};
+
+/// Index of the `PT_PHDR` segment in the images built by `with_phdr`
+const PHDR: usize = 2;
+
+fn with_phdr(builder: ImageBuilder) -> ImageBuilder {
+    builder
+        .segment(PType::PtPhdr, PF_READ, &[])
+        .segment(PType::PtLoad, PF_READ, &[])
+}
+
+fn phdr_warnings(warnings: &[ParseWarning]) -> Vec<&'static str> {
+    warnings
+        .iter()
+        .filter_map(|w| match w {
+            ParseWarning::InvalidPhdrSegment { idx: PHDR, reason } => Some(*reason),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn phdr_segment() {
+    for (class, endianness) in ENCODINGS {
+        let image = with_phdr(ImageBuilder::minimal(class, endianness)).build();
+        let parser = image.parse().unwrap();
+
+        let phdr = parser.phdr_segment().unwrap();
+        assert_eq!(phdr.offset, parser.headers.ph_off);
+        assert_eq!(
+            phdr.filesz,
+            parser.headers.ph_num as u64 * parser.headers.ph_ent_size as u64
+        );
+        assert!(phdr_warnings(&parser.warnings).is_empty());
+    }
+}
+
+#[test]
+fn static_executable_has_none() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+
+        assert!(image.parse().unwrap().phdr_segment().is_none());
+    }
+}
+
+#[test]
+fn corrupted_vaddr() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = with_phdr(ImageBuilder::minimal(class, endianness)).build();
+        image.set_segment(PHDR, |l| l.p_vaddr, 0xdead0);
+
+        let parser = image.parse().unwrap();
+        assert_eq!(
+            phdr_warnings(&parser.warnings),
+            ["vaddr doesn't match the loadable segment mapping it"]
+        );
+        let warning = parser
+            .warnings
+            .iter()
+            .find(|w| w.code() == "W0017_BAD_PHDR_SEGMENT")
+            .unwrap();
+        assert_eq!(warning.segment(), Some(PHDR));
+    }
+}
+
+#[test]
+fn corrupted_size() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = with_phdr(ImageBuilder::minimal(class, endianness)).build();
+        image.set_segment(PHDR, |l| l.p_filesz, 8);
+
+        assert_eq!(
+            phdr_warnings(&image.parse().unwrap().warnings),
+            ["size doesn't match the program header table"]
+        );
+    }
+}
+
+#[test]
+fn unmapped() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness)
+            .segment(PType::PtPhdr, PF_READ, &[])
+            .build();
+
+        assert_eq!(
+            phdr_warnings(&image.parse().unwrap().warnings),
+            ["isn't mapped by a loadable segment"]
+        );
+    }
+}
+
+#[test]
+fn pure_bss_segment_is_not_allocated() {
+    const MEMSZ: u64 = 1 << 30;
+
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::new(class, endianness)
+            .section(".text", SHT_PROGBITS, SHF_ALLOC | SHF_EXECINSTR, &TEXT)
+            .section(".bss", SHT_NOBITS, SHF_ALLOC | SHF_WRITE, &[0; 0x10])
+            .segment(PType::PtLoad, PF_READ | PF_EXEC, &[".text"])
+            .segment(PType::PtLoad, PF_READ | PF_WRITE, &[".bss"])
+            .build();
+        image.set_segment(1, |l| l.p_memsz, MEMSZ);
+
+        // a buffer of the segment size would be refused, so parsing only allocates
+        // the metadata of the segment
+        let options = ParseOptions::default().max_alloc(1 << 20);
+        let parser = ElfParser::parse_with(&image.bytes, &options).unwrap();
+        let bss = &parser.program_headers[1];
+
+        assert_eq!(bss.filesz, 0);
+        assert!(matches!(
+            bss.section,
+            PTypeData::ZeroFill { memsz: MEMSZ, flags, .. } if flags == PF_READ | PF_WRITE
+        ));
+        assert!(matches!(
+            parser.program_headers[0].section,
+            PTypeData::PtLoadData { .. }
+        ));
+
+        // consumers produce the zeros when asked for them, within their own limits
+        assert!(parser.memory_image().is_err());
+        assert!(parser.to_flat_binary().unwrap().data.len() < 0x1000);
+    }
+}
+
+/// The real number of program headers is held by section 0 when `ph_num` is `PN_XNUM`
+#[test]
+fn extended_program_header_count() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = with_phdr(ImageBuilder::minimal(class, endianness)).build();
+        // the segment loading the ELF header sees the new `ph_num`, compare the entries only
+        let entries = |headers: &[Elf64PHdr]| {
+            headers
+                .iter()
+                .map(|ph| (ph.p_type, ph.offset, ph.vaddr, ph.filesz, ph.memsz))
+                .collect::<Vec<_>>()
+        };
+        let expected = entries(&image.parse().unwrap().program_headers);
+        assert_eq!(expected.len(), 4);
+
+        image.set_header(|l| l.e_phnum, PN_XNUM as u64).set_section(
+            0,
+            |l| l.sh_info,
+            expected.len() as u64,
+        );
+
+        let parser = image.parse().unwrap();
+        assert_eq!(
+            Elf64PHdr::count(&image.bytes, &parser.ctx, &parser.headers).unwrap(),
+            expected.len()
+        );
+        assert_eq!(parser.headers.ph_num, PN_XNUM);
+        assert_eq!(entries(&parser.program_headers), expected);
+        assert_eq!(parser.program_headers[PHDR].p_type, PType::PtPhdr);
+        assert!(phdr_warnings(&parser.warnings).is_empty());
+
+        // the count doesn't leave room for the last entries of the table
+        image.set_section(0, |l| l.sh_info, 2);
+        let parser = image.parse().unwrap();
+        assert_eq!(entries(&parser.program_headers), expected[..2]);
+    }
+}
+
+#[test]
+fn extended_program_header_count_without_sections() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        image
+            .set_header(|l| l.e_phnum, PN_XNUM as u64)
+            .set_header(|l| l.e_shoff, 0)
+            .set_header(|l| l.e_shnum, 0);
+
+        assert!(matches!(
+            image.parse(),
+            Err(ParseError::NoSectionHeaderTable)
+        ));
+    }
+}
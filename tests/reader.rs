@@ -0,0 +1,185 @@
+mod fixtures;
+
+use elf_parser::bytes::{Address, ParseContext, Reader};
+use elf_parser::elf::ehdr::{ElfHClass, ElfHData, IDENT_SZ};
+use elf_parser::elf::phdr::PTypeData;
+use elf_parser::layout;
+use elf_parser::options::ParseOptions;
+use elf_parser::parser::{ElfParser, ParseError, ParseWarning};
+use fixtures::{ImageBuilder, ENCODINGS};
+
+#[test]
+fn fields_in_sequence() {
+    let bytes = [
+        0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c,
+    ];
+
+    let ctx = ParseContext::new(ElfHData::ElfData2Lsb, ElfHClass::ElfClass64);
+    let mut reader = ctx.reader(&bytes);
+    assert_eq!(reader.u8().unwrap(), 0x01);
+    assert_eq!(reader.u16().unwrap(), 0x0302);
+    assert_eq!(reader.u8().unwrap(), 0x04);
+    assert_eq!(reader.position(), 4);
+    assert_eq!(reader.u64().unwrap(), 0x0c0b0a0908070605);
+    assert_eq!(reader.remaining(), 0);
+
+    let ctx = ParseContext::new(ElfHData::ElfData2Msb, ElfHClass::ElfClass32);
+    let mut reader = Reader::new(&bytes, ctx);
+    assert_eq!(reader.u32().unwrap(), 0x01020304);
+    assert_eq!(reader.addr().unwrap(), Address(0x05060708));
+    assert_eq!(reader.uword().unwrap(), 0x090a0b0c);
+
+    // random access, from the start of the bytes whatever the cursor
+    let reader = reader.at(2);
+    assert_eq!(reader.at(8).u16().unwrap(), 0x090a);
+    assert_eq!(reader.clone().i64().unwrap(), 0x030405060708090a);
+}
+
+#[test]
+fn short_reads() {
+    let bytes = [0xff; 6];
+    let ctx = ParseContext::new(ElfHData::ElfData2Lsb, ElfHClass::ElfClass64);
+
+    let mut reader = ctx.reader(&bytes);
+    assert!(matches!(reader.u64(), Err(ParseError::InvalidLength)));
+    assert!(matches!(reader.uword(), Err(ParseError::InvalidLength)));
+    assert_eq!(reader.position(), 0);
+    assert_eq!(reader.u32().unwrap(), u32::MAX);
+    assert!(matches!(reader.u32(), Err(ParseError::InvalidLength)));
+    assert!(matches!(reader.bytes(3), Err(ParseError::InvalidLength)));
+    assert_eq!(reader.bytes(2).unwrap(), [0xff; 2]);
+    assert!(matches!(reader.u8(), Err(ParseError::InvalidLength)));
+
+    // padding must be held by the bytes
+    let mut reader = reader.at(5);
+    assert!(matches!(reader.align(8), Err(ParseError::InvalidLength)));
+    assert_eq!(reader.position(), 5);
+    reader.align(6).unwrap();
+    assert_eq!(reader.position(), 6);
+
+    let mut reader = reader.at(usize::MAX);
+    assert_eq!(reader.remaining(), 0);
+    assert!(matches!(reader.bytes(1), Err(ParseError::InvalidLength)));
+}
+
+/// Every truncation of each header fails to decode, down to no bytes at all, and the
+/// whole headers decode as the builder laid them out
+#[test]
+fn truncated_headers() {
+    let options = ParseOptions::default();
+
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+
+        let size = layout::ehdr::for_class(class).size;
+        for len in 0..size {
+            let result = ElfParser::parse_with(&image.bytes[..len], &options);
+            match len < IDENT_SZ {
+                true => assert!(result.is_err(), "{:?}: {} bytes", class, len),
+                false => assert!(
+                    matches!(result, Err(ParseError::InvalidLength)),
+                    "{:?}: {} bytes",
+                    class,
+                    len
+                ),
+            }
+        }
+        let parser = ElfParser::parse_with(&image.bytes, &options).unwrap();
+        assert_eq!(parser.headers.sh_off, image.sh_off);
+        assert_eq!(
+            format!("{:?}", parser.section_headers),
+            format!("{:?}", image.section_headers)
+        );
+
+        // entries shorter than their fields, which the table is walked by
+        let size = layout::shdr::for_class(class).size;
+        for len in 1..size {
+            let mut image = image.clone();
+            image.set_header(|l| l.e_shentsize, len as u64);
+
+            let parser = ElfParser::parse_with(&image.bytes, &options).unwrap();
+            let undecodable = parser
+                .warnings
+                .iter()
+                .filter_map(|w| match w {
+                    ParseWarning::UndecodableSection { idx, error, .. } => Some((*idx, error)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                undecodable.len(),
+                image.section_headers.len(),
+                "{:?}: section headers of {} bytes",
+                class,
+                len
+            );
+            assert!(undecodable
+                .iter()
+                .all(|(_, error)| **error == ParseError::InvalidLength.to_string()));
+        }
+
+        let size = layout::phdr::for_class(class).size;
+        for len in 1..size {
+            let mut image = image.clone();
+            image.set_header(|l| l.e_phentsize, len as u64);
+
+            let err = ElfParser::parse_with(&image.bytes, &options).unwrap_err();
+            assert!(
+                matches!(
+                    err,
+                    ParseError::InSegment { index: 0, ref source }
+                        if matches!(**source, ParseError::InvalidLength)
+                ),
+                "{:?}: program headers of {} bytes: {:?}",
+                class,
+                len,
+                err
+            );
+        }
+    }
+}
+
+/// Program headers whose fields would have the contents of the segment read out of
+/// bounds are reported, and their contents left out
+#[test]
+fn segment_fields_out_of_bounds() {
+    for (class, endianness) in ENCODINGS {
+        let max_offset = match class {
+            ElfHClass::ElfClass32 => u32::MAX as u64 - 4,
+            _ => u64::MAX - 4,
+        };
+
+        for (field, value, code) in [
+            ("p_memsz", 4, "W0030_SEGMENT_FILESZ_OVER_MEMSZ"),
+            ("p_offset", 0x100000, "W0028_SEGMENT_OUT_OF_BOUNDS"),
+            ("p_offset", max_offset, "W0028_SEGMENT_OUT_OF_BOUNDS"),
+        ] {
+            let mut image = ImageBuilder::minimal(class, endianness).build();
+            match field {
+                "p_memsz" => image.set_segment(1, |l| l.p_memsz, value),
+                _ => image.set_segment(1, |l| l.p_offset, value),
+            };
+
+            let parser = ElfParser::parse(&image.bytes).unwrap();
+            assert!(matches!(
+                parser.program_headers[1].section,
+                PTypeData::Skipped
+            ));
+            assert!(
+                parser.warnings.iter().any(|w| w.code() == code),
+                "{} = {:#x}: {:?}",
+                field,
+                value,
+                parser.warnings
+            );
+            // the file image itself is still there to read when only the sizes disagree
+            assert_eq!(parser.segment_data(1).is_err(), field == "p_offset");
+
+            let options = ParseOptions::default().strict(true);
+            assert!(matches!(
+                ElfParser::parse_with(&image.bytes, &options),
+                Err(ParseError::InSegment { index: 1, .. })
+            ));
+        }
+    }
+}
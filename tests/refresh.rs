@@ -0,0 +1,119 @@
+mod fixtures;
+
+use elf_parser::bytes::Address;
+use elf_parser::elf::sym::{Elf64Sym, STB_GLOBAL, STT_FUNC};
+use elf_parser::layout::shdr::ShdrLayout;
+use fixtures::{Image, ImageBuilder, ENCODINGS};
+
+fn with_main(builder: ImageBuilder) -> Image {
+    let main = Elf64Sym {
+        name: 0,
+        info: STB_GLOBAL << 4 | STT_FUNC,
+        other: 0,
+        shndx: 1,
+        value: Address(fixtures::BASE_ADDR),
+        size: 4,
+    };
+
+    builder.symbols(&[("main", main)]).build()
+}
+
+#[test]
+fn unchanged() {
+    for (class, endianness) in ENCODINGS {
+        let image = with_main(ImageBuilder::minimal(class, endianness));
+        let mut parser = image.parse().unwrap();
+
+        let report = parser.refresh(&image.bytes.clone()).unwrap();
+        assert!(report.is_unchanged(), "{:?}", report);
+    }
+}
+
+#[test]
+fn patched_text() {
+    for (class, endianness) in ENCODINGS {
+        let image = with_main(ImageBuilder::minimal(class, endianness));
+        let mut parser = image.parse().unwrap();
+        // the name index is built, and must survive the refresh
+        assert!(parser.find_symbol("main").is_some());
+
+        let text = image.section_idx(".text");
+        let off = image.section(".text").offset as usize;
+        let mut patched = image.bytes.clone();
+        patched[off..off + 4].copy_from_slice(&[0x73, 0x00, 0x10, 0x00]);
+
+        let report = parser.refresh(&patched).unwrap();
+        assert!(!report.full);
+        assert_eq!(report.changed_sections, [text]);
+        assert_eq!(report.changed_segments, [0]);
+        assert!(report.program_headers);
+        assert!(!report.string_tables && !report.symbol_tables);
+
+        assert_eq!(
+            &parser.section_data(text).unwrap()[..4],
+            [0x73, 0x00, 0x10, 0x00]
+        );
+        assert_eq!(parser.data(), &patched[..]);
+        assert!(parser.find_symbol("main").is_some());
+    }
+}
+
+#[test]
+fn renamed_symbol() {
+    for (class, endianness) in ENCODINGS {
+        let image = with_main(ImageBuilder::minimal(class, endianness));
+        let mut parser = image.parse().unwrap();
+        assert!(parser.find_symbol("main").is_some());
+
+        let off = image.section(".strtab").offset as usize;
+        let mut patched = image.bytes.clone();
+        let name = patched[off..].iter().position(|b| *b == b'm').unwrap() + off;
+        patched[name..name + 4].copy_from_slice(b"init");
+
+        let report = parser.refresh(&patched).unwrap();
+        assert_eq!(report.changed_sections, [image.section_idx(".strtab")]);
+        assert!(report.changed_segments.is_empty());
+        assert!(report.string_tables && report.symbol_tables);
+        assert!(!report.program_headers);
+
+        assert!(parser.find_symbol("main").is_none());
+        assert!(parser.find_symbol("init").is_some());
+    }
+}
+
+#[test]
+fn moved_section_reparses_everything() {
+    for (class, endianness) in ENCODINGS {
+        let image = with_main(ImageBuilder::minimal(class, endianness));
+        let mut parser = image.parse().unwrap();
+
+        let mut moved = image.clone();
+        let data = image.section_idx(".data");
+        moved.set_section(data, |l: &ShdrLayout| l.sh_size, 4);
+
+        let report = parser.refresh(&moved.bytes).unwrap();
+        assert!(report.full);
+        assert_eq!(parser.section_headers[data].size, 4);
+
+        // so does a file of another size
+        let mut longer = moved.bytes.clone();
+        longer.push(0);
+        assert!(parser.refresh(&longer).unwrap().full);
+        assert_eq!(parser.data().len(), longer.len());
+    }
+}
+
+#[test]
+fn failure_leaves_the_parser() {
+    for (class, endianness) in ENCODINGS {
+        let image = with_main(ImageBuilder::minimal(class, endianness));
+        let mut parser = image.parse().unwrap();
+
+        let mut broken = image.clone();
+        broken.set_header(|l| l.e_phentsize, 0);
+
+        assert!(parser.refresh(&broken.bytes).is_err());
+        assert_eq!(parser.data(), &image.bytes[..]);
+        assert!(parser.find_symbol("main").is_some());
+    }
+}
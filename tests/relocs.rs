@@ -0,0 +1,112 @@
+mod fixtures;
+
+use elf_parser::bytes::{Address, ParseContext};
+use elf_parser::elf::ehdr::{ElfHClass, ElfHType};
+use elf_parser::elf::shdr::{SHT_DYNSYM, SHT_REL, SHT_RELA};
+use elf_parser::elf::sym::{Elf64Sym, STB_GLOBAL, STB_LOCAL, STT_FUNC, STT_SECTION};
+use fixtures::{ImageBuilder, SectionSpec, ENCODINGS};
+
+const R_RISCV_64: u32 = 2;
+const R_RISCV_CALL_PLT: u32 = 19;
+
+fn symbol(info: u8, shndx: u16, value: u64) -> Elf64Sym {
+    Elf64Sym {
+        name: 0,
+        info,
+        other: 0,
+        shndx,
+        value: Address(value),
+        size: 0,
+    }
+}
+
+/// Encodes relocations of (offset, symbol, type, addend), with addends if `rela`
+fn relocations(ctx: &ParseContext, rela: bool, entries: &[(u64, u32, u32, i64)]) -> Vec<u8> {
+    let mut out = vec![];
+    for (offset, sym, r_type, addend) in entries {
+        let info = match ctx.class {
+            ElfHClass::ElfClass64 => (*sym as u64) << 32 | *r_type as u64,
+            _ => (*sym as u64) << 8 | *r_type as u64,
+        };
+        ctx.put_uword(&mut out, *offset);
+        ctx.put_uword(&mut out, info);
+        if rela {
+            ctx.put_uword(&mut out, *addend as u64);
+        }
+    }
+    out
+}
+
+fn section(name: &str, s_type: u32, link: u32, data: Vec<u8>) -> SectionSpec {
+    SectionSpec {
+        name: name.to_string(),
+        s_type,
+        flags: 0,
+        align: 4,
+        link,
+        info: 1,
+        ent_size: 0,
+        data,
+    }
+}
+
+#[test]
+fn relocations_named() {
+    for (class, endianness) in ENCODINGS {
+        let builder = ImageBuilder::minimal(class, endianness)
+            .e_type(ElfHType::Relocatable)
+            .symbols(&[
+                ("", symbol(STB_LOCAL << 4 | STT_SECTION, 2, 0)),
+                ("main", symbol(STB_GLOBAL << 4 | STT_FUNC, 1, 0x10)),
+            ])
+            .symbol_table(
+                ".dynsym",
+                SHT_DYNSYM,
+                ".dynstr",
+                &[("puts", symbol(STB_GLOBAL << 4 | STT_FUNC, 0, 0))],
+            );
+        let ctx = builder.ctx();
+        let rela = relocations(
+            &ctx,
+            true,
+            &[
+                (0x4, 2, R_RISCV_CALL_PLT, 0),
+                (0x8, 1, R_RISCV_64, -4),
+                (0xc, 0, R_RISCV_64, 0x20),
+                (0x10, 9, R_RISCV_64, 0),
+                (0x14, 0, 0x30, 0),
+            ],
+        );
+        let rel = relocations(&ctx, false, &[(0x0, 1, R_RISCV_64, 0)]);
+        let image = builder
+            .section_with(section(".rela.text", SHT_RELA, 3, rela))
+            .section_with(section(".rel.dyn", SHT_REL, 5, rel))
+            .build();
+        let parser = image.parse().unwrap();
+
+        let sections = parser.relocations_named().unwrap();
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].name, ".rela.text");
+        assert_eq!(sections[1].section_idx, image.section_idx(".rel.dyn"));
+
+        let rows = sections
+            .iter()
+            .flat_map(|s| &s.relocations)
+            .map(|r| {
+                let row = r.to_string();
+                row[34..].split_whitespace().collect::<Vec<_>>().join(" ")
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            [
+                "R_RISCV_CALL_PLT 0000000000000010 main + 0",
+                "R_RISCV_64 0000000000000000 .data - 4",
+                "R_RISCV_64 20",
+                "R_RISCV_64 0000000000000000 <corrupt> + 0",
+                "UNKNOWN(0x30) 0",
+                "R_RISCV_64 0000000000000000 puts + 0",
+            ]
+        );
+    }
+}
@@ -0,0 +1,112 @@
+mod fixtures;
+
+use elf_parser::elf::phdr::PTypeData;
+use elf_parser::elf::shdr::SHT_PROGBITS;
+use elf_parser::parser::ParseError;
+use elf_parser::remote::RemoteElf;
+use elf_parser::source::{ElfSource, SourceError};
+use fixtures::{Image, ImageBuilder, DATA, ENCODINGS, TEXT};
+use std::cell::RefCell;
+use std::ops::Range;
+
+/// Serves `data` as remote storage would, recording the ranges requested
+struct Recording {
+    data: Vec<u8>,
+    requests: RefCell<Vec<Range<u64>>>,
+}
+
+impl Recording {
+    fn new(data: &[u8]) -> Self {
+        Recording {
+            data: data.to_vec(),
+            requests: RefCell::new(vec![]),
+        }
+    }
+
+    fn requested(&self) -> u64 {
+        self.requests.borrow().iter().map(|r| r.end - r.start).sum()
+    }
+}
+
+impl ElfSource for Recording {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<usize, SourceError> {
+        self.requests
+            .borrow_mut()
+            .push(offset..offset + buf.len() as u64);
+        self.data.as_slice().read_at(offset, buf)
+    }
+
+    fn len(&self) -> u64 {
+        self.data.len() as u64
+    }
+}
+
+/// `ImageBuilder::minimal` carrying a few megabytes of contents no query needs
+fn large(builder: ImageBuilder) -> Image {
+    builder
+        .section(".blob", SHT_PROGBITS, 0, &vec![0x5a; 4 << 20])
+        .build()
+}
+
+#[test]
+fn headers_only_queries() {
+    for (class, endianness) in ENCODINGS {
+        let image = large(ImageBuilder::minimal(class, endianness));
+        let parser = image.parse().unwrap();
+        let remote = RemoteElf::parse(Recording::new(&image.bytes)).unwrap();
+
+        assert_eq!(remote.headers.entry, parser.headers.entry);
+        assert_eq!(remote.section_headers.len(), parser.section_headers.len());
+        assert_eq!(remote.program_headers.len(), parser.program_headers.len());
+        assert!(remote
+            .program_headers
+            .iter()
+            .all(|ph| ph.section == PTypeData::Skipped));
+
+        let (idx, _) = remote.section_by_name(".text").unwrap();
+        assert_eq!(remote.section_data(idx).unwrap(), TEXT);
+        let (idx, _) = remote.section_by_name(".data").unwrap();
+        assert_eq!(remote.section_data(idx).unwrap(), DATA);
+        assert!(remote.source().requested() < 64 << 10);
+
+        // fetched ranges are served again without reading the source
+        let requests = remote.source().requests.borrow().len();
+        remote.section_data(idx).unwrap();
+        assert!(remote.section_by_name(".blob").is_some());
+        assert_eq!(remote.source().requests.borrow().len(), requests);
+    }
+}
+
+#[test]
+fn slice_and_file_sources() {
+    let (class, endianness) = ENCODINGS[0];
+    let image = ImageBuilder::minimal(class, endianness).build();
+
+    let remote = RemoteElf::parse(image.bytes.as_slice()).unwrap();
+    let (idx, _) = remote.section_by_name(".text").unwrap();
+    assert_eq!(remote.section_data(idx).unwrap(), TEXT);
+
+    let path = std::env::temp_dir().join(format!("elf-parser-remote-{}", std::process::id()));
+    std::fs::write(&path, &image.bytes).unwrap();
+    let remote = RemoteElf::parse(std::fs::File::open(&path).unwrap()).unwrap();
+    let (idx, _) = remote.section_by_name(".data").unwrap();
+    assert_eq!(remote.section_data(idx).unwrap(), DATA);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn truncated_source() {
+    let (class, endianness) = ENCODINGS[0];
+    let image = ImageBuilder::minimal(class, endianness).build();
+
+    let mut buf = [0; 8];
+    let err = image.bytes[..16].read_exact_at(12, &mut buf).unwrap_err();
+    assert!(matches!(err, SourceError::Truncated { offset: 12, len: 8 }));
+
+    // the tables are checked against the size of the source before being fetched
+    let len = image.bytes.len() - 1;
+    assert!(matches!(
+        RemoteElf::parse(&image.bytes[..len]),
+        Err(ParseError::SectionHeadersOutOfBounds)
+    ));
+}
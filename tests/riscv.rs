@@ -0,0 +1,96 @@
+mod fixtures;
+
+use elf_parser::bytes::Address;
+use elf_parser::elf::ehdr::EM_X86_64;
+use elf_parser::elf::sym::{Elf64Sym, SHN_ABS, SHN_UNDEF, STB_GLOBAL, STT_FUNC, STT_NOTYPE};
+use elf_parser::riscv::RiscvStartupSymbols;
+use fixtures::{ImageBuilder, ENCODINGS};
+
+fn symbol(info: u8, shndx: u16, value: u64) -> Elf64Sym {
+    Elf64Sym {
+        name: 0,
+        info,
+        other: 0,
+        shndx,
+        value: Address(value),
+        size: 0,
+    }
+}
+
+fn marker(value: u64) -> Elf64Sym {
+    symbol(STB_GLOBAL << 4 | STT_NOTYPE, SHN_ABS, value)
+}
+
+/// Symbols of a program linked by a hand-written script, under the names of newlib and
+/// picolibc rather than the ones of the default scripts
+fn startup_symbols() -> [(&'static str, Elf64Sym); 7] {
+    [
+        // undefined references to the preferred names are passed over
+        (
+            "__global_pointer$",
+            symbol(STB_GLOBAL << 4 | STT_NOTYPE, SHN_UNDEF, 0),
+        ),
+        ("_gp", marker(0x10800)),
+        ("_estack", marker(0x80000)),
+        ("_start", symbol(STB_GLOBAL << 4 | STT_FUNC, 1, 0x10074)),
+        ("__bss_start", marker(0x10200)),
+        // `_end` only stands in for the bss end when it isn't marked
+        ("_end", marker(0x10400)),
+        ("_ebss", marker(0x10300)),
+    ]
+}
+
+#[test]
+fn aliases_resolved() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness)
+            .symbols(&startup_symbols())
+            .build();
+
+        assert_eq!(
+            image.parse().unwrap().riscv_startup_symbols(),
+            Some(RiscvStartupSymbols {
+                global_pointer: Some(Address(0x10800)),
+                stack_top: Some(Address(0x80000)),
+                start: Some(Address(0x10074)),
+                bss_start: Some(Address(0x10200)),
+                bss_end: Some(Address(0x10300)),
+            })
+        );
+    }
+}
+
+#[test]
+fn missing_symbols() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness)
+            .symbols(&[
+                (
+                    "__global_pointer$",
+                    symbol(STB_GLOBAL << 4 | STT_NOTYPE, SHN_UNDEF, 0),
+                ),
+                ("_end", marker(0x10400)),
+            ])
+            .build();
+
+        assert_eq!(
+            image.parse().unwrap().riscv_startup_symbols(),
+            Some(RiscvStartupSymbols {
+                bss_end: Some(Address(0x10400)),
+                ..RiscvStartupSymbols::default()
+            })
+        );
+    }
+}
+
+#[test]
+fn other_machine() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness)
+            .machine(EM_X86_64)
+            .symbols(&startup_symbols())
+            .build();
+
+        assert_eq!(image.parse().unwrap().riscv_startup_symbols(), None);
+    }
+}
@@ -0,0 +1,130 @@
+mod fixtures;
+
+use elf_parser::elf::ehdr::{ElfHClass, ElfHData, ElfHType};
+use elf_parser::elf::phdr::{
+    PType, DT_NULL, DT_RPATH, DT_RUNPATH, DT_STRSZ, DT_STRTAB, PF_READ, PF_WRITE,
+};
+use elf_parser::elf::shdr::{SHF_ALLOC, SHF_WRITE, SHT_DYNAMIC, SHT_STRTAB};
+use elf_parser::rpath::{RpathComponent, RpathEntry, RpathIssue, RpathTag, RpathToken};
+use fixtures::{Image, ImageBuilder};
+
+/// Builds an executable with the given search paths, as linked with
+/// `-Wl,-rpath,<path>`. `.dynstr` is only placed once the image is laid out, so it's
+/// built a first time to find its address
+fn linked_with(paths: &[(i64, &str)]) -> Image {
+    let build = |strtab_addr: u64| {
+        let builder = ImageBuilder::new(ElfHClass::ElfClass64, ElfHData::ElfData2Lsb)
+            .e_type(ElfHType::Executable);
+        let ctx = builder.ctx();
+
+        let mut strtab = vec![0u8];
+        let mut entries = vec![];
+        for (tag, path) in paths {
+            entries.push((*tag, strtab.len() as u64));
+            strtab.extend(path.as_bytes());
+            strtab.push(0);
+        }
+        entries.extend([
+            (DT_STRTAB, strtab_addr),
+            (DT_STRSZ, strtab.len() as u64),
+            (DT_NULL, 0),
+        ]);
+
+        let mut dynamic = vec![];
+        for (tag, value) in entries {
+            ctx.put_uword(&mut dynamic, tag as u64);
+            ctx.put_uword(&mut dynamic, value);
+        }
+
+        builder
+            .section(".dynstr", SHT_STRTAB, SHF_ALLOC, &strtab)
+            .section(".dynamic", SHT_DYNAMIC, SHF_ALLOC | SHF_WRITE, &dynamic)
+            .segment(PType::PtLoad, PF_READ, &[".dynstr"])
+            .segment(PType::PtLoad, PF_READ | PF_WRITE, &[".dynamic"])
+            .segment(PType::PtDynamic, PF_READ | PF_WRITE, &[".dynamic"])
+            .build()
+    };
+
+    build(build(0).section(".dynstr").addr.0)
+}
+
+#[test]
+fn origin_relative_and_tmp() {
+    let image = linked_with(&[(DT_RUNPATH, "$ORIGIN/../lib:/tmp")]);
+    let parser = image.parse().unwrap();
+
+    let entries = parser.rpath_entries().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].tag, RpathTag::Runpath);
+    assert_eq!(
+        entries[0].components,
+        [
+            RpathComponent::Token(RpathToken::Origin),
+            RpathComponent::Literal("/../lib".to_string()),
+        ]
+    );
+    assert_eq!(
+        entries[0].expand("/opt/app/bin", "lib64", "x86_64"),
+        "/opt/app/bin/../lib"
+    );
+    assert!(entries[0].issues().is_empty());
+    assert_eq!(entries[1].issues(), [RpathIssue::WorldWritable("/tmp")]);
+
+    let issues = parser.rpath_issues().unwrap();
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].0.raw, "/tmp");
+}
+
+#[test]
+fn rpath_comes_before_runpath() {
+    let image = linked_with(&[(DT_RUNPATH, "/usr/local/lib"), (DT_RPATH, "/opt/lib")]);
+    let entries = image.parse().unwrap().rpath_entries().unwrap();
+
+    let raw = entries
+        .iter()
+        .map(|e| (e.tag, e.raw.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        raw,
+        [
+            (RpathTag::Rpath, "/opt/lib"),
+            (RpathTag::Runpath, "/usr/local/lib")
+        ]
+    );
+}
+
+#[test]
+fn tokens() {
+    let entry = RpathEntry::parse(
+        RpathTag::Rpath,
+        "${ORIGIN}/$LIB/${PLATFORM}/$ORIGINAL/$HOME",
+    );
+
+    assert!(entry.has_token(RpathToken::Origin));
+    assert!(entry.has_token(RpathToken::Lib));
+    assert!(entry.has_token(RpathToken::Platform));
+    assert_eq!(
+        entry.expand("/o", "lib64", "haswell"),
+        "/o/lib64/haswell/$ORIGINAL/$HOME"
+    );
+}
+
+#[test]
+fn insecure_entries() {
+    let issues = |raw| RpathEntry::parse(RpathTag::Runpath, raw).issues();
+
+    assert_eq!(issues(""), [RpathIssue::Empty]);
+    assert_eq!(issues("lib"), [RpathIssue::Relative]);
+    assert_eq!(issues("$LIB/foo"), [RpathIssue::Relative]);
+    assert_eq!(
+        issues("/var/tmp/x"),
+        [RpathIssue::WorldWritable("/var/tmp")]
+    );
+    assert!(issues("/tmpfs/lib").is_empty());
+    assert!(issues("/usr/lib").is_empty());
+    assert!(issues("$ORIGIN").is_empty());
+
+    let image = linked_with(&[(DT_RPATH, "::/usr/lib")]);
+    let parser = image.parse().unwrap();
+    assert_eq!(parser.rpath_issues().unwrap().len(), 2);
+}
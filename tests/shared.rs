@@ -0,0 +1,104 @@
+mod fixtures;
+
+use elf_parser::elf::shdr::{SHF_ALLOC, SHT_PROGBITS};
+use elf_parser::parser::ElfParser;
+use fixtures::{ImageBuilder, ENCODINGS};
+use std::sync::{Arc, Barrier};
+use std::thread;
+
+const THREADS: usize = 8;
+const ROUNDS: usize = 200;
+
+/// Names and contents of every section, and contents of every segment
+type Contents = (Vec<(String, Vec<u8>)>, Vec<Vec<u8>>);
+
+fn contents(parser: &ElfParser) -> Contents {
+    let sections = (0..parser.section_headers.len())
+        .map(|idx| {
+            let sh = &parser.section_headers[idx];
+            (
+                parser.section_name(sh).unwrap_or_default(),
+                parser.section_data(idx).unwrap().to_vec(),
+            )
+        })
+        .collect();
+    let segments = (0..parser.program_headers.len())
+        .map(|idx| parser.segment_data(idx).unwrap().to_vec())
+        .collect();
+
+    (sections, segments)
+}
+
+/// Clones of a shared parser, read from several threads at once, see the same file
+/// as a parser owning its own copy, without copying the buffer
+#[test]
+fn clones_read_concurrently() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness)
+            .section(".rodata", SHT_PROGBITS, SHF_ALLOC, b"shared\0")
+            .build();
+        let expected = contents(&ElfParser::parse(&image.bytes).unwrap());
+
+        let data: Arc<[u8]> = image.bytes.clone().into();
+        let parser = ElfParser::parse_shared(data.clone()).unwrap();
+        let barrier = Arc::new(Barrier::new(THREADS));
+
+        let handles: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let parser = parser.clone();
+                let barrier = barrier.clone();
+                thread::spawn(move || {
+                    barrier.wait();
+                    let mut seen = vec![];
+                    for _ in 0..ROUNDS {
+                        // clones of clones share the buffer as well
+                        let clone = parser.clone();
+                        assert_eq!(clone.data().as_ptr(), parser.data().as_ptr());
+                        seen.push(contents(&clone));
+                    }
+                    seen
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            let seen = handle.join().unwrap();
+            assert_eq!(seen.len(), ROUNDS);
+            assert!(seen.iter().all(|c| *c == expected));
+        }
+        assert_eq!(parser.data().as_ptr(), data.as_ptr());
+        assert_eq!(contents(&parser), expected);
+    }
+}
+
+/// Threads parsing the same shared buffer at once each get a parser reading it in
+/// place
+#[test]
+fn concurrent_shared_parses() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+        let expected = contents(&ElfParser::parse(&image.bytes).unwrap());
+        let data: Arc<[u8]> = image.bytes.clone().into();
+
+        let handles: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let data = data.clone();
+                thread::spawn(move || {
+                    (0..ROUNDS)
+                        .map(|_| {
+                            let parser = ElfParser::parse_shared(data.clone()).unwrap();
+                            assert_eq!(parser.data().as_ptr(), data.as_ptr());
+                            contents(&parser)
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            assert!(handle.join().unwrap().iter().all(|c| *c == expected));
+        }
+        // every parser is gone along with its reference to the buffer
+        assert_eq!(Arc::strong_count(&data), 1);
+    }
+}
@@ -0,0 +1,95 @@
+mod fixtures;
+
+use ed25519_compact::{KeyPair, Seed, Signature};
+use elf_parser::editor::ElfEditor;
+use elf_parser::elf::shdr::{SHF_ALLOC, SHF_WRITE, SHT_NOBITS, SHT_PROGBITS};
+use elf_parser::options::ParseOptions;
+use elf_parser::parser::ElfParser;
+use elf_parser::signature::SignatureStatus;
+use fixtures::{ImageBuilder, DATA, ENCODINGS, TEXT};
+
+/// Fixed key the test images are signed with
+fn keypair() -> KeyPair {
+    KeyPair::from_seed(Seed::new([0x42; 32]))
+}
+
+fn verify(message: &[u8], signature: &[u8]) -> bool {
+    Signature::from_slice(signature)
+        .is_ok_and(|signature| keypair().pk.verify(message, &signature).is_ok())
+}
+
+fn sign(message: &[u8]) -> Vec<u8> {
+    keypair().sk.sign(message, None).to_vec()
+}
+
+#[test]
+fn canonical_message() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness)
+            .section(".bss", SHT_NOBITS, SHF_ALLOC | SHF_WRITE, &[0xaa; 16])
+            .section(".comment", SHT_PROGBITS, 0, b"GCC\0")
+            .section(".rodata", SHT_PROGBITS, SHF_ALLOC, b"ro")
+            .build();
+        let parser = image.parse().unwrap();
+
+        // allocated sections in index order, without the zero-filled ones nor the
+        // non-allocated ones
+        let expected = [&TEXT[..], &DATA, b"ro"].concat();
+        assert_eq!(parser.signed_message(None).unwrap(), expected);
+
+        // the signature section is left out, even when allocated
+        let rodata = image.section_idx(".rodata");
+        assert_eq!(
+            parser.signed_message(Some(rodata)).unwrap(),
+            [&TEXT[..], &DATA].concat()
+        );
+
+        let editor = ElfEditor::new(&parser);
+        assert_eq!(editor.signed_message(".signature").unwrap(), expected);
+    }
+}
+
+#[test]
+fn sign_and_verify() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+        let parser = image.parse().unwrap();
+        assert_eq!(
+            parser
+                .verify_embedded_signature(".signature", verify)
+                .unwrap(),
+            SignatureStatus::Unsigned
+        );
+
+        let mut editor = ElfEditor::new(&parser);
+        let idx = editor.sign(".signature", sign).unwrap();
+        let signed = editor.save().unwrap();
+
+        let options = ParseOptions::default();
+        let parser = ElfParser::parse_with(&signed, &options).unwrap();
+        assert_eq!(parser.section_by_name(".signature").unwrap().0, idx);
+        assert_eq!(parser.section_data(idx).unwrap().len(), Signature::BYTES);
+        assert_eq!(
+            parser
+                .verify_embedded_signature(".signature", verify)
+                .unwrap(),
+            SignatureStatus::Valid
+        );
+
+        // signing again replaces the signature in place
+        let mut editor = ElfEditor::new(&parser);
+        assert_eq!(editor.sign(".signature", sign).unwrap(), idx);
+
+        // any altered byte of the allocated sections breaks the signature
+        let text = parser.section_by_name(".text").unwrap().1.offset as usize;
+        let mut tampered = signed.clone();
+        tampered[text] ^= 1;
+        let parser = ElfParser::parse_with(&tampered, &options).unwrap();
+        assert_eq!(
+            parser
+                .verify_embedded_signature(".signature", verify)
+                .unwrap(),
+            SignatureStatus::Invalid
+        );
+    }
+}
@@ -0,0 +1,250 @@
+mod fixtures;
+
+use elf_parser::bytes::Address;
+use elf_parser::elf::ehdr::{ElfHClass, ElfHData, ElfHType};
+use elf_parser::elf::shdr::{SHF_ALLOC, SHF_EXECINSTR, SHF_WRITE, SHT_NOBITS, SHT_PROGBITS};
+use elf_parser::elf::sym::{Elf64Sym, STB_GLOBAL, STT_FUNC, STT_NOTYPE, STT_OBJECT};
+use elf_parser::size::{merge_ranges, uncovered_ranges, SizeBreakdown};
+use fixtures::{Image, ImageBuilder, ENCODINGS};
+
+const COMMENT: &[u8] = b"0123456789abcdef";
+
+#[test]
+#[allow(clippy::single_range_in_vec_init, clippy::reversed_empty_ranges)]
+fn merged_ranges() {
+    // disjoint, out of order
+    assert_eq!(merge_ranges(vec![8..10, 0..4]), [0..4, 8..10]);
+    // overlapping, nested, adjacent
+    assert_eq!(merge_ranges(vec![0..4, 2..6]), [0..6]);
+    assert_eq!(merge_ranges(vec![0..10, 2..4, 3..5]), [0..10]);
+    assert_eq!(merge_ranges(vec![4..8, 0..4]), [0..8]);
+    // empty ranges cover nothing, even inside a hole
+    assert_eq!(merge_ranges(vec![0..2, 5..5, 8..8, 8..9]), [0..2, 8..9]);
+    assert_eq!(merge_ranges(vec![6..3, 0..1]), [0..1]);
+    assert!(merge_ranges(vec![]).is_empty());
+}
+
+#[test]
+#[allow(clippy::single_range_in_vec_init)]
+fn uncovered() {
+    // holes at the start, in between and at the end
+    assert_eq!(uncovered_ranges(&[2..4, 6..8], 10), [0..2, 4..6, 8..10]);
+    assert_eq!(uncovered_ranges(&[0..10], 10), []);
+    assert_eq!(uncovered_ranges(&[], 10), [0..10]);
+    // ranges past the end don't make up holes
+    assert_eq!(uncovered_ranges(&[0..2, 12..16], 10), [2..10]);
+    assert_eq!(uncovered_ranges(&[0..2, 8..16], 10), [2..8]);
+    assert_eq!(uncovered_ranges(&[], 0), []);
+}
+
+fn with_comment((class, endianness): (ElfHClass, ElfHData)) -> Image {
+    ImageBuilder::minimal(class, endianness)
+        .section(".comment", SHT_PROGBITS, 0, COMMENT)
+        .build()
+}
+
+/// Overlaps counted once, so the covered and residual bytes make up the whole file
+fn assert_accounted(breakdown: &SizeBreakdown) {
+    assert_eq!(
+        breakdown.covered + breakdown.residual_size(),
+        breakdown.file_size
+    );
+    assert!(breakdown.residual.windows(2).all(|w| w[0].end < w[1].start));
+}
+
+#[test]
+fn breakdown_with_holes() {
+    for encoding in ENCODINGS {
+        let mut image = with_comment(encoding);
+        let baseline = image.parse().unwrap().size_breakdown();
+        assert_accounted(&baseline);
+
+        // a section shrunk in place, and trailing bytes past every structure
+        let comment = image.section(".comment").offset;
+        let idx = image.section_idx(".comment");
+        let end = image.bytes.len() as u64;
+        image
+            .set_section(idx, |l| l.sh_size, 4)
+            .set_bytes(end as usize, &[0xcc; 8]);
+
+        let breakdown = image.parse().unwrap().size_breakdown();
+        assert_accounted(&breakdown);
+        assert_eq!(breakdown.file_size, baseline.file_size + 8);
+        assert_eq!(breakdown.covered, baseline.covered - 12);
+
+        let mut expected = baseline.residual.clone();
+        expected.extend([comment + 4..comment + 16, end..end + 8]);
+        assert_eq!(merge_ranges(expected), breakdown.residual);
+    }
+}
+
+#[test]
+fn breakdown_with_overlaps() {
+    for encoding in ENCODINGS {
+        let mut image = with_comment(encoding);
+        let baseline = image.parse().unwrap().size_breakdown();
+
+        // `.comment` pointed at the bytes of `.text`, which its segment covers too
+        let text = image.section(".text");
+        let comment = image.section(".comment").offset;
+        let idx = image.section_idx(".comment");
+        image.set_section(idx, |l| l.sh_offset, text.offset);
+
+        let breakdown = image.parse().unwrap().size_breakdown();
+        assert_accounted(&breakdown);
+        let extent = |name: &str| {
+            breakdown
+                .sections
+                .iter()
+                .find(|e| e.name == name)
+                .unwrap()
+                .range
+                .clone()
+        };
+        assert_eq!(extent(".comment"), extent(".text"));
+        assert_eq!(breakdown.covered, baseline.covered - COMMENT.len() as u64);
+        assert!(breakdown.residual.contains(&(comment..comment + 16)));
+
+        // sorted by offset, the sections sharing one in table order
+        let names: Vec<_> = breakdown.sections.iter().map(|e| e.name.as_str()).collect();
+        let text_pos = names.iter().position(|n| *n == ".text").unwrap();
+        assert_eq!(names[text_pos + 1], ".comment");
+    }
+}
+
+/// Header and table extents, and bogus extents clamped to the file
+#[test]
+fn breakdown_extents() {
+    for encoding in ENCODINGS {
+        let mut image = with_comment(encoding);
+        let breakdown = image.parse().unwrap().size_breakdown();
+        let parser = image.parse().unwrap();
+        let headers = &parser.headers;
+
+        assert_eq!(breakdown.header.range, 0..headers.eh_size as u64);
+        assert_eq!(
+            breakdown.program_header_table.unwrap().range,
+            image.ph_off..image.ph_off + headers.ph_num as u64 * headers.ph_ent_size as u64
+        );
+        assert_eq!(
+            breakdown.section_header_table.unwrap().range,
+            image.sh_off..image.sh_off + headers.sh_num as u64 * headers.sh_ent_size as u64
+        );
+        assert_eq!(breakdown.segments.len(), 2);
+
+        let idx = image.section_idx(".comment");
+        image.set_section(idx, |l| l.sh_size, 0x100000);
+        let breakdown = image.parse().unwrap().size_breakdown();
+        assert_accounted(&breakdown);
+        let comment = breakdown
+            .sections
+            .iter()
+            .find(|e| e.name == ".comment")
+            .unwrap();
+        assert_eq!(comment.range.end, breakdown.file_size);
+    }
+}
+
+fn symbol(info: u8, shndx: u16, value: u64, size: u64) -> Elf64Sym {
+    Elf64Sym {
+        name: 0,
+        info,
+        other: 0,
+        shndx,
+        value: Address(value),
+        size,
+    }
+}
+
+/// Relocatable object whose symbols overlap, alias each other, run past their section
+/// or have no size, their values being offsets into their section
+fn with_symbols((class, endianness): (ElfHClass, ElfHData)) -> Image {
+    let func = STB_GLOBAL << 4 | STT_FUNC;
+    let object = STB_GLOBAL << 4 | STT_OBJECT;
+
+    ImageBuilder::new(class, endianness)
+        .e_type(ElfHType::Relocatable)
+        .section(".text", SHT_PROGBITS, SHF_ALLOC | SHF_EXECINSTR, &[0; 0x40])
+        .section(".data", SHT_PROGBITS, SHF_ALLOC | SHF_WRITE, &[0; 0x20])
+        .section(".bss", SHT_NOBITS, SHF_ALLOC | SHF_WRITE, &[0; 0x10])
+        .section(".comment", SHT_PROGBITS, 0, COMMENT)
+        .symbols(&[
+            ("main", symbol(func, 1, 0, 0x10)),
+            ("main_alias", symbol(func, 1, 0, 0x10)),
+            ("helper", symbol(func, 1, 0x10, 0x20)),
+            ("inner", symbol(func, 1, 0x18, 0x10)),
+            ("tail", symbol(func, 1, 0x38, 0x18)),
+            ("zero", symbol(func, 1, 0x30, 0)),
+            ("label", symbol(STB_GLOBAL << 4 | STT_NOTYPE, 1, 0x30, 8)),
+            ("table", symbol(object, 2, 0, 0x18)),
+            ("counter", symbol(object, 2, 0x14, 0xc)),
+            ("buf", symbol(object, 3, 0, 0x10)),
+        ])
+        .build()
+}
+
+#[test]
+fn size_report() {
+    for encoding in ENCODINGS {
+        let image = with_symbols(encoding);
+        let report = image.parse().unwrap().size_report();
+
+        // allocated sections only, largest first
+        let sections: Vec<_> = report
+            .sections
+            .iter()
+            .map(|s| (s.name.as_str(), s.section, s.size))
+            .collect();
+        assert_eq!(
+            sections,
+            [(".text", 1, 0x40), (".data", 2, 0x20), (".bss", 3, 0x10)]
+        );
+
+        // aliases and nested symbols get no bytes and are dropped, like the symbols
+        // without size or type, and symbols running past the section are cut short
+        let symbols = |idx: usize| -> Vec<_> {
+            report.sections[idx]
+                .symbols
+                .iter()
+                .map(|s| (s.name.as_str(), s.size))
+                .collect()
+        };
+        assert_eq!(symbols(0), [("helper", 0x20), ("main", 0x10), ("tail", 8)]);
+        assert_eq!(symbols(1), [("table", 0x18), ("counter", 8)]);
+        assert_eq!(symbols(2), [("buf", 0x10)]);
+
+        let text = &report.sections[0];
+        assert_eq!(text.symbols[0].sym_type, STT_FUNC);
+        assert_eq!(
+            text.symbols[2].address,
+            image.section(".text").addr.0 + 0x38
+        );
+        assert_eq!(text.symbols_size(), 0x38);
+        assert_eq!(text.residual(), 8);
+        assert_eq!(report.sections[1].residual(), 0);
+
+        let largest: Vec<_> = report
+            .largest(2)
+            .into_iter()
+            .map(|(section, sym)| (section.name.as_str(), sym.name.as_str()))
+            .collect();
+        assert_eq!(largest, [(".text", "helper"), (".data", "table")]);
+        assert_eq!(report.largest(100).len(), 6);
+        assert!(report.largest(0).is_empty());
+    }
+}
+
+#[test]
+fn size_report_without_symbols() {
+    for encoding in ENCODINGS {
+        let report = with_comment(encoding).parse().unwrap().size_report();
+
+        assert!(!report.sections.is_empty());
+        assert!(report.sections.iter().all(|s| s.symbols.is_empty()));
+        assert!(report
+            .sections
+            .iter()
+            .all(|s| s.residual() == s.size && s.name != ".comment"));
+        assert!(report.largest(10).is_empty());
+    }
+}
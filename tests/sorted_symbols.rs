@@ -0,0 +1,173 @@
+mod fixtures;
+
+use elf_parser::bytes::Address;
+use elf_parser::elf::shdr::SHT_DYNSYM;
+use elf_parser::elf::sym::{Elf64Sym, STB_GLOBAL, STB_LOCAL, STT_FUNC, STT_NOTYPE, STT_OBJECT};
+use elf_parser::parser::ElfParser;
+use elf_parser::sorted_symbols::SortOptions;
+use elf_parser::symbol_index::SymbolRef;
+use fixtures::{Image, ImageBuilder, ENCODINGS};
+
+fn symbol(info: u8, shndx: u16, value: u64) -> Elf64Sym {
+    Elf64Sym {
+        name: 0,
+        info,
+        other: 0,
+        shndx,
+        value: Address(value),
+        size: 0,
+    }
+}
+
+fn images() -> Vec<Image> {
+    let func = STB_GLOBAL << 4 | STT_FUNC;
+
+    ENCODINGS
+        .into_iter()
+        .map(|(class, endianness)| {
+            ImageBuilder::minimal(class, endianness)
+                .symbols(&[
+                    ("helper", symbol(STB_LOCAL << 4 | STT_FUNC, 1, 0x10)),
+                    ("data", symbol(STB_GLOBAL << 4 | STT_OBJECT, 2, 0x40)),
+                    ("__libc_malloc", symbol(func, 1, 0x30)),
+                    ("label", symbol(STB_GLOBAL << 4 | STT_NOTYPE, 1, 0x20)),
+                    ("malloc", symbol(func, 1, 0x30)),
+                    ("puts", symbol(func, 0, 0)),
+                    ("zero", symbol(func, 1, 0)),
+                ])
+                .symbol_table(
+                    ".dynsym",
+                    SHT_DYNSYM,
+                    ".dynstr",
+                    &[
+                        ("malloc", symbol(func, 1, 0x30)),
+                        ("printf", symbol(func, 1, 0x50)),
+                        ("puts", symbol(func, 0, 0)),
+                    ],
+                )
+                .build()
+        })
+        .collect()
+}
+
+fn names(parser: &ElfParser, symbols: &[SymbolRef]) -> Vec<String> {
+    symbols
+        .iter()
+        .map(|r| {
+            let table = &parser.symbol_tables[r.table];
+            parser.symbol_name(table, &table.symbols[r.index]).unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn sorted_by_address() {
+    for image in images() {
+        let parser = image.parse().unwrap();
+
+        // aliases keep their order in the table
+        assert_eq!(
+            names(&parser, parser.symbols_sorted()),
+            ["helper", "__libc_malloc", "malloc", "data"]
+        );
+        assert_eq!(parser.symbols_sorted()[1], SymbolRef { table: 0, index: 3 });
+    }
+}
+
+#[test]
+fn range_queries() {
+    for image in images() {
+        let parser = image.parse().unwrap();
+
+        assert_eq!(
+            names(&parser, parser.symbols_in_range(0x10, 0x40)),
+            ["helper", "__libc_malloc", "malloc"]
+        );
+        assert_eq!(
+            names(&parser, parser.symbols_in_range(0x30, 0x31)),
+            ["__libc_malloc", "malloc"]
+        );
+        assert_eq!(
+            names(&parser, parser.symbols_in_range(0x40, 0x1000)),
+            ["data"]
+        );
+        assert!(parser.symbols_in_range(0x11, 0x30).is_empty());
+        assert!(parser.symbols_in_range(0x40, 0x10).is_empty());
+    }
+}
+
+#[test]
+fn options() {
+    for image in images() {
+        let parser = image.parse().unwrap();
+
+        let functions = parser.sort_symbols(&SortOptions::new().types(&[STT_FUNC, STT_NOTYPE]));
+        assert_eq!(
+            names(&parser, functions.symbols()),
+            ["helper", "label", "__libc_malloc", "malloc"]
+        );
+
+        // `.symtab` symbols come first at equal addresses
+        let merged = parser.sort_symbols(&SortOptions::new().merge_tables(true));
+        assert_eq!(
+            names(&parser, merged.symbols()),
+            [
+                "helper",
+                "__libc_malloc",
+                "malloc",
+                "malloc",
+                "data",
+                "printf"
+            ]
+        );
+        assert_eq!(merged.symbols()[3], SymbolRef { table: 1, index: 1 });
+        assert_eq!(
+            names(&parser, merged.in_range(0x40, 0x60)),
+            ["data", "printf"]
+        );
+    }
+}
+
+#[test]
+fn stripped_files_read_dynsym() {
+    for image in images() {
+        let mut parser = image.parse().unwrap();
+        assert_eq!(parser.symbols_sorted().len(), 4);
+
+        parser.symbol_tables_mut().remove(0);
+        assert_eq!(
+            names(&parser, parser.symbols_sorted()),
+            ["malloc", "printf"]
+        );
+    }
+}
+
+#[test]
+fn symbolize_addresses() {
+    for (class, endianness) in ENCODINGS {
+        let mut sized = symbol(STB_GLOBAL << 4 | STT_FUNC, 1, 0x10);
+        sized.size = 0x20;
+        let image = ImageBuilder::minimal(class, endianness)
+            .symbols(&[
+                ("outer", sized),
+                ("inner", symbol(STB_LOCAL << 4 | STT_FUNC, 1, 0x18)),
+                ("data", symbol(STB_GLOBAL << 4 | STT_OBJECT, 2, 0x40)),
+            ])
+            .build();
+        let parser = image.parse().unwrap();
+        let symbolize = |addr| {
+            parser
+                .symbolize(addr)
+                .map(|(r, offset)| (names(&parser, &[r]).remove(0), offset))
+        };
+
+        assert_eq!(symbolize(0x10), Some(("outer".to_string(), 0)));
+        assert_eq!(symbolize(0x14), Some(("outer".to_string(), 4)));
+        // a symbol without a size only covers its own address
+        assert_eq!(symbolize(0x18), Some(("inner".to_string(), 0)));
+        assert_eq!(symbolize(0x1c), Some(("outer".to_string(), 0xc)));
+        assert_eq!(symbolize(0x30), None);
+        assert_eq!(symbolize(0x40), Some(("data".to_string(), 0)));
+        assert_eq!(symbolize(0x8), None);
+    }
+}
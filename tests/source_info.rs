@@ -0,0 +1,92 @@
+mod fixtures;
+
+use elf_parser::parser::{ElfParser, ParseError};
+use elf_parser::source_info::SourceInfo;
+use fixtures::{ImageBuilder, DATA, ENCODINGS};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("elf-parser-{}-{}", name, std::process::id()))
+}
+
+#[test]
+fn opened_file() {
+    let (class, endianness) = ENCODINGS[0];
+    let image = ImageBuilder::minimal(class, endianness).build();
+    let path = temp_path("open");
+    std::fs::write(&path, &image.bytes).unwrap();
+
+    let mut parser = ElfParser::open(&path).unwrap();
+    let source = parser.source().unwrap().clone();
+    assert_eq!(source.path, path.canonicalize().unwrap());
+    assert_eq!(source.size, image.bytes.len() as u64);
+    assert!(source.modified.is_some());
+    assert!(matches!(parser.reopen_check(), Some(Ok(false))));
+    assert!(parser.reload().unwrap().is_none());
+
+    // the same layout with other contents, written later
+    let data = image.section(".data");
+    let mut bytes = image.bytes.clone();
+    bytes[data.offset as usize] ^= 0xff;
+    std::fs::write(&path, &bytes).unwrap();
+    let file = std::fs::File::options().write(true).open(&path).unwrap();
+    file.set_modified(SystemTime::now() + Duration::from_secs(10))
+        .unwrap();
+    assert!(matches!(parser.reopen_check(), Some(Ok(true))));
+
+    let report = parser.reload().unwrap().unwrap();
+    assert_eq!(report.changed_sections, [image.section_idx(".data")]);
+    assert_eq!(
+        parser.section_data(image.section_idx(".data")).unwrap()[0],
+        DATA[0] ^ 0xff
+    );
+    assert!(parser.source().unwrap().modified > source.modified);
+    assert!(matches!(parser.reopen_check(), Some(Ok(false))));
+
+    // the metadata is carried into the reports
+    let json = parser.lint_report("a.out").to_json();
+    assert!(json.contains(&format!("\"size\":{}", image.bytes.len())));
+
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(
+        parser.reopen_check(),
+        Some(Err(ParseError::Source(_)))
+    ));
+    assert!(matches!(ElfParser::open(&path), Err(ParseError::Source(_))));
+}
+
+#[test]
+fn parsed_from_bytes() {
+    let (class, endianness) = ENCODINGS[0];
+    let image = ImageBuilder::minimal(class, endianness).build();
+    let mut parser = image.parse().unwrap();
+
+    assert!(parser.source().is_none());
+    assert!(parser.reopen_check().is_none());
+    assert!(parser.reload().unwrap().is_none());
+
+    let report = parser.lint_report("a.out");
+    assert!(report.source.is_none());
+    assert!(report.to_json().contains("\"source\":null"));
+    assert!(!report.to_sarif().contains("\"length\""));
+}
+
+#[test]
+fn display() {
+    let source = SourceInfo {
+        path: PathBuf::from("/bin/true"),
+        size: 0x1234,
+        modified: Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+    };
+    assert_eq!(
+        source.to_string(),
+        "/bin/true (4660 bytes, modified 2023-11-14 22:13:20 UTC)"
+    );
+
+    let source = SourceInfo {
+        modified: None,
+        ..source
+    };
+    assert_eq!(source.to_string(), "/bin/true (4660 bytes)");
+}
@@ -0,0 +1,235 @@
+mod fixtures;
+
+use elf_parser::bytes::Address;
+use elf_parser::elf::ehdr::{ElfHClass, ElfHData};
+use elf_parser::elf::phdr::{Elf64PHdr, PType, PTypeData, PF_READ, PF_WRITE, PN_XNUM};
+use elf_parser::elf::shdr::{
+    Elf64SHdr, SHN_LORESERVE, SHT_PROGBITS, SHT_RELA, SHT_STRTAB, SHT_SYMTAB,
+};
+use elf_parser::elf::sym::{Elf64Sym, STB_GLOBAL, STT_FUNC};
+use elf_parser::layout;
+use elf_parser::options::ParseOptions;
+use elf_parser::parser::{ElfParser, ParseError, ParseWarning};
+use elf_parser::stats::Arena;
+use fixtures::{ImageBuilder, SectionSpec, DATA, ENCODINGS, PAGE_SIZE, TEXT};
+use std::time::Duration;
+
+#[test]
+fn counts() {
+    let main = Elf64Sym {
+        name: 0,
+        info: STB_GLOBAL << 4 | STT_FUNC,
+        other: 0,
+        shndx: 1,
+        value: Address(0x10),
+        size: 0,
+    };
+
+    for (class, endianness) in ENCODINGS {
+        let builder = ImageBuilder::minimal(class, endianness);
+        let rela_size = 3 * builder.ctx().word_size() as u64;
+        let image = builder
+            .section_with(SectionSpec {
+                name: ".rela.text".to_string(),
+                s_type: SHT_RELA,
+                flags: 0,
+                align: 8,
+                link: 0,
+                info: 1,
+                ent_size: rela_size,
+                data: vec![0; 2 * rela_size as usize],
+            })
+            .symbols(&[("main", main), ("start", main)])
+            .build();
+        let parser = image.parse().unwrap();
+        let stats = parser.stats();
+
+        assert_eq!(stats.sections, 7);
+        assert_eq!(stats.segments, 2);
+        // along with the null symbol
+        assert_eq!(stats.symbols, 3);
+        assert_eq!(stats.relocations, 2);
+
+        let strtab = image.section(".strtab").size;
+        let shstrtab = image.section(".shstrtab").size;
+        assert_eq!(stats.string_table_bytes as u64, strtab + shstrtab);
+
+        let tables = parser.headers.class_size() as u64
+            + 7 * parser.headers.sh_ent_size as u64
+            + 2 * parser.headers.ph_ent_size as u64;
+        let contents = (TEXT.len() + DATA.len()) as u64 + strtab + shstrtab;
+        let symtab = image.section(".symtab").size;
+        assert_eq!(stats.bytes_touched, tables + contents + symtab);
+
+        assert!(stats.phases.total() > Duration::ZERO);
+    }
+}
+
+#[test]
+fn deadline() {
+    let builder = ImageBuilder::minimal(ElfHClass::ElfClass64, ElfHData::ElfData2Lsb);
+    // link to the `.strtab` following the table
+    let link = builder.sections.len() as u32 + 2;
+    let image = builder
+        .section_with(SectionSpec {
+            name: ".symtab".to_string(),
+            s_type: SHT_SYMTAB,
+            flags: 0,
+            align: 8,
+            link,
+            info: 1,
+            ent_size: 24,
+            data: vec![0; 24 * 4_000_000],
+        })
+        .section(".strtab", SHT_STRTAB, 0, &[0])
+        .build();
+
+    let parser = image.parse().unwrap();
+    assert_eq!(parser.stats().symbols, 4_000_000);
+
+    let options = ParseOptions::default().deadline(Duration::from_millis(1));
+    assert!(matches!(
+        ElfParser::parse_with(&image.bytes, &options),
+        Err(ParseError::DeadlineExceeded)
+    ));
+
+    let options = ParseOptions::default().deadline(Duration::from_secs(3600));
+    assert!(ElfParser::parse_with(&image.bytes, &options).is_ok());
+}
+
+#[test]
+fn arena_budget() {
+    let image = ImageBuilder::minimal(ElfHClass::ElfClass64, ElfHData::ElfData2Lsb).build();
+    let arena = Arena::new(4 << 20);
+
+    ElfParser::parse_in(&arena, &image.bytes).unwrap();
+    let used = arena.used();
+    assert!(used > 0);
+
+    // clones share the budget, which parses keep drawing from until reset
+    let options = ParseOptions::default().arena(arena.clone());
+    ElfParser::parse_with(&image.bytes, &options).unwrap();
+    assert_eq!(arena.used(), 2 * used);
+    arena.reset();
+    assert_eq!(arena.used(), 0);
+
+    let small = Arena::new(used - 1);
+    assert!(matches!(
+        ElfParser::parse_in(&small, &image.bytes),
+        Err(ParseError::ArenaExhausted { .. })
+    ));
+    assert!(small.used() < used);
+}
+
+/// A segment whose file image lies past the end of the file is refused before its
+/// memory image is taken from the arena, however large it claims to be
+#[test]
+fn arena_untouched_by_segment_past_end_of_file() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        let arena = Arena::new(4 << 20);
+        ElfParser::parse_in(&arena, &image.bytes).unwrap();
+        let used = arena.used();
+
+        // a page further, which keeps the segment aligned
+        let offset = image.section(".data").offset + PAGE_SIZE;
+        image
+            .set_segment(1, |l| l.p_offset, offset)
+            .set_segment(1, |l| l.p_memsz, 1 << 30);
+
+        // only the memory image of the segment is left out
+        let arena = Arena::new(used);
+        let parser = ElfParser::parse_in(&arena, &image.bytes).unwrap();
+        assert!(matches!(
+            &parser.warnings[..],
+            [ParseWarning::SegmentOutOfBounds { idx: 1, .. }]
+        ));
+        assert_eq!(arena.used(), used - DATA.len());
+
+        let arena = Arena::new(used);
+        let options = ParseOptions::default().strict(true).arena(arena.clone());
+        let err = ElfParser::parse_with(&image.bytes, &options).unwrap_err();
+        assert!(matches!(
+            err.root_cause(),
+            ParseError::SegmentOutOfBounds { filesz: 8, .. }
+        ));
+        assert!(arena.used() < used);
+    }
+}
+
+/// Segments whose contents weren't read don't count towards the bytes touched,
+/// whatever size they claim
+#[test]
+fn huge_dynamic_segment() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness)
+            .segment(PType::PtDynamic, PF_READ | PF_WRITE, &[".data"])
+            .build();
+        let touched = image.parse().unwrap().stats().bytes_touched;
+
+        image.set_segment(2, |l| l.p_filesz, u64::MAX);
+        let parser = image.parse().unwrap();
+        assert!(matches!(
+            parser.program_headers[2].section,
+            PTypeData::Skipped
+        ));
+        assert_eq!(parser.stats().bytes_touched, touched - DATA.len() as u64);
+    }
+}
+
+#[test]
+fn malicious_section_count() {
+    const CAPACITY: usize = 1 << 20;
+    let count = SHN_LORESERVE as usize - 1;
+
+    for (class, endianness) in ENCODINGS {
+        let ent_size = layout::shdr::for_class(class).size;
+        let mut image = ImageBuilder::minimal(class, endianness)
+            .section(".blob", SHT_PROGBITS, 0, &vec![0; count * ent_size])
+            .build();
+        // the largest count the header can hold, over a table of zeros that fits in
+        // the file
+        let blob = image.section(".blob").offset;
+        image
+            .set_header(|l| l.e_shoff, blob)
+            .set_header(|l| l.e_shnum, count as u64)
+            .set_header(|l| l.e_shstrndx, 0);
+
+        let arena = Arena::new(CAPACITY);
+        let options = ParseOptions::default().arena(arena.clone());
+        let headers = (count * std::mem::size_of::<Elf64SHdr>()) as u64;
+        assert!(matches!(
+            ElfParser::parse_with(&image.bytes, &options),
+            Err(ParseError::ArenaExhausted { requested, remaining: CAPACITY }) if requested == headers
+        ));
+        assert_eq!(arena.used(), 0);
+    }
+}
+
+#[test]
+fn malicious_program_header_count() {
+    const CAPACITY: usize = 4 << 20;
+    let count = CAPACITY / std::mem::size_of::<Elf64PHdr>() + 1;
+
+    for (class, endianness) in ENCODINGS {
+        let ent_size = layout::phdr::for_class(class).size;
+        let mut image = ImageBuilder::minimal(class, endianness)
+            .section(".blob", SHT_PROGBITS, 0, &vec![0; count * ent_size])
+            .build();
+        // a count escaping to the initial section header through `PN_XNUM`, over a
+        // table of `PT_NULL` entries that fits in the file
+        let blob = image.section(".blob").offset;
+        image
+            .set_header(|l| l.e_phoff, blob)
+            .set_header(|l| l.e_phnum, PN_XNUM as u64)
+            .set_section(0, |l| l.sh_info, count as u64);
+
+        let arena = Arena::new(CAPACITY);
+        let options = ParseOptions::default().arena(arena.clone());
+        assert!(matches!(
+            ElfParser::parse_with(&image.bytes, &options),
+            Err(ParseError::ArenaExhausted { .. })
+        ));
+        assert!(arena.used() <= CAPACITY);
+    }
+}
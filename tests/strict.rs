@@ -0,0 +1,178 @@
+mod fixtures;
+
+use elf_parser::elf::ehdr::{ElfHClass, ElfHData, ElfHType};
+use elf_parser::elf::shdr::{SHF_ALLOC, SHF_EXECINSTR, SHT_PROGBITS};
+use elf_parser::options::ParseOptions;
+use elf_parser::parser::{ElfParser, ParseError};
+use fixtures::{Image, ImageBuilder, ENCODINGS, TEXT};
+use std::collections::BTreeMap;
+
+/// A relocatable object, whose `.text` directly follows the header as there's no
+/// program header table
+fn object(class: ElfHClass, endianness: ElfHData) -> Image {
+    ImageBuilder::new(class, endianness)
+        .e_type(ElfHType::Relocatable)
+        .section(".text", SHT_PROGBITS, SHF_ALLOC | SHF_EXECINSTR, &TEXT)
+        .build()
+}
+
+/// Images of both kinds the builder makes, each anomaly-free
+#[test]
+fn clean_images_pass() {
+    for (class, endianness) in ENCODINGS {
+        for image in [
+            object(class, endianness),
+            ImageBuilder::minimal(class, endianness).build(),
+        ] {
+            let options = ParseOptions::default().strict(true);
+            let parser = ElfParser::parse_with(&image.bytes, &options).unwrap();
+            assert!(parser.warnings.is_empty());
+        }
+    }
+}
+
+#[test]
+fn strict_fails_with_every_warning() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = object(class, endianness);
+        let expected = image.parse().unwrap().headers.class_size() as u16;
+        image
+            .set_header(|l| l.e_ehsize, expected as u64 + 16)
+            .set_header(|l| l.e_shstrndx, 1);
+
+        let options = ParseOptions::default().strict(true);
+        let Err(ParseError::StrictViolation(warnings)) =
+            ElfParser::parse_with(&image.bytes, &options)
+        else {
+            panic!("strict parse didn't fail");
+        };
+
+        let codes = warnings.iter().map(|w| w.code()).collect::<Vec<_>>();
+        assert!(codes.contains(&"W0011_OVERSIZED_HEADER"), "{:?}", codes);
+        assert!(codes.contains(&"W0003_SHSTRTAB_WRONG_TYPE"), "{:?}", codes);
+        // a lenient parse records the same warnings
+        assert_eq!(image.parse().unwrap().warnings, warnings);
+    }
+}
+
+/// Location of an anomaly: offset, section and segment
+type Location = (Option<u64>, Option<usize>, Option<usize>);
+
+/// Images with one anomaly each, along with the code of the warning it raises and
+/// where the warning locates it
+fn anomalies(class: ElfHClass, endianness: ElfHData) -> Vec<(Image, &'static str, Location)> {
+    let mut oversized = object(class, endianness);
+    let size = oversized.parse().unwrap().headers.class_size() as u64;
+    oversized.set_header(|l| l.e_ehsize, size + 16);
+
+    let mut misaligned = ImageBuilder::minimal(class, endianness).build();
+    let data = misaligned.section_idx(".data");
+    let addr = misaligned.section(".data").addr.0;
+    misaligned.set_section(data, |l| l.sh_addr, addr + 1);
+
+    let mut no_names = ImageBuilder::minimal(class, endianness).build();
+    no_names.set_header(|l| l.e_shstrndx, 200);
+
+    let mut past_end = ImageBuilder::minimal(class, endianness).build();
+    let len = past_end.bytes.len() as u64;
+    past_end.set_segment(1, |l| l.p_offset, len - 4);
+
+    let mut unknown_type = ImageBuilder::minimal(class, endianness).build();
+    unknown_type.set_segment(1, |l| l.p_type, 0x1234);
+
+    let mut overlong = ImageBuilder::minimal(class, endianness).build();
+    overlong.set_segment(1, |l| l.p_memsz, 4);
+
+    vec![
+        (
+            oversized,
+            "W0011_OVERSIZED_HEADER",
+            (Some(size), None, None),
+        ),
+        (misaligned, "W0007_BAD_ALIGN", (None, Some(data), None)),
+        // a field of the header, which is only told by the message
+        (no_names, "W0018_SHSTRNDX_OUT_OF_RANGE", (None, None, None)),
+        (
+            past_end,
+            "W0028_SEGMENT_OUT_OF_BOUNDS",
+            (Some(len - 4), None, Some(1)),
+        ),
+        (
+            unknown_type,
+            "W0029_UNKNOWN_SEGMENT_TYPE",
+            (None, None, Some(1)),
+        ),
+        (
+            overlong,
+            "W0030_SEGMENT_FILESZ_OVER_MEMSZ",
+            (None, None, Some(1)),
+        ),
+    ]
+}
+
+/// The codes tooling keys on are stable, and a lenient parse locates the anomalies
+/// the strict one fails with
+#[test]
+fn diagnostics() {
+    for (class, endianness) in ENCODINGS {
+        for (image, code, location) in anomalies(class, endianness) {
+            let lenient = image.parse().unwrap();
+            let warning = lenient
+                .warnings
+                .iter()
+                .find(|w| w.code() == code)
+                .unwrap_or_else(|| panic!("no {} in {:?}", code, lenient.warnings));
+            assert_eq!(
+                (warning.offset(), warning.section(), warning.segment()),
+                location,
+                "{}",
+                code
+            );
+
+            let options = ParseOptions::default().strict(true);
+            match ElfParser::parse_with(&image.bytes, &options) {
+                Err(ParseError::StrictViolation(warnings)) => {
+                    assert_eq!(warnings, lenient.warnings);
+                    let err = ParseError::StrictViolation(warnings);
+                    assert!(err.to_string().ends_with(" warnings in strict mode"));
+                }
+                // anomalies that keep contents from being read fail on the spot
+                Err(err) => assert!(
+                    matches!(
+                        err.root_cause(),
+                        ParseError::SegmentOutOfBounds { .. }
+                            | ParseError::SegmentFileSizeExceedsMemSize { .. }
+                    ),
+                    "{}: {:?}",
+                    code,
+                    err
+                ),
+                Ok(_) => panic!("strict parse with {} succeeded", code),
+            }
+        }
+    }
+}
+
+#[test]
+fn codes_are_unique() {
+    let mut codes = BTreeMap::new();
+    for (class, endianness) in ENCODINGS {
+        for (image, _, _) in anomalies(class, endianness) {
+            for warning in image.parse().unwrap().warnings {
+                let code = warning.code();
+                let (number, name) = code.split_once('_').unwrap();
+                assert!(number.len() == 5 && number.starts_with('W'), "{}", code);
+                assert!(number[1..].bytes().all(|b| b.is_ascii_digit()), "{}", code);
+                assert!(
+                    name.bytes()
+                        .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit() || b == b'_'),
+                    "{}",
+                    code
+                );
+                // one name per number
+                assert_eq!(*codes.entry(number.to_string()).or_insert(code), code);
+            }
+        }
+    }
+    assert!(codes.len() >= 6);
+}
@@ -0,0 +1,305 @@
+mod fixtures;
+
+use elf_parser::elf::ehdr::{ElfHClass, ElfHData};
+use elf_parser::elf::shdr::{
+    StringTableType, SHF_ALLOC, SHF_WRITE, SHN_XINDEX, SHT_NOBITS, SHT_PROGBITS, SHT_STRTAB,
+};
+use elf_parser::options::ParseOptions;
+use elf_parser::parser::{ElfParser, ParseError, ParseWarning};
+use fixtures::{ImageBuilder, ENCODINGS};
+
+fn name(parser: &ElfParser, idx: usize) -> Option<String> {
+    parser.section_name(&parser.section_headers[idx]).ok()
+}
+
+#[test]
+fn empty_table() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        let idx = image.section_idx(".shstrtab");
+        image.set_section(idx, |l| l.sh_size, 0);
+
+        let parser = image.parse().unwrap();
+        assert_eq!(parser.string_tables.len(), 1);
+        assert!(parser.string_tables[0].table.is_empty());
+        assert_eq!(name(&parser, 1), None);
+        assert_eq!(name(&parser, 0), Some(String::new()));
+    }
+}
+
+/// GNU strip can leave string tables without contents, whose lookups all fail or
+/// find the empty string
+#[test]
+fn empty_secondary_table() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness)
+            .section(".strtab", SHT_STRTAB, 0, &[])
+            .build();
+        let idx = image.section_idx(".strtab");
+
+        let parser = image.parse().unwrap();
+        assert!(parser.warnings.is_empty(), "{:?}", parser.warnings);
+        let table = parser.string_table_at(idx).unwrap();
+        assert!(table.table.is_empty());
+        assert_eq!(table.sh_type, StringTableType::StrTab);
+        assert_eq!(table.len_strings(), 0);
+        assert_eq!(table.iter(true).count(), 0);
+        assert_eq!(table.contains(""), None);
+        for off in [0, 1, u32::MAX] {
+            let name = ElfParser::get_sh_name(table, off);
+            assert!(
+                name.as_deref().map_or(true, str::is_empty),
+                "{}: {:?}",
+                off,
+                name
+            );
+        }
+        // the other sections are named as usual
+        assert_eq!(name(&parser, idx).as_deref(), Some(".strtab"));
+    }
+}
+
+/// Several tables of the same kind, even with the same name, are all kept in the
+/// order of the section header table
+#[test]
+fn duplicate_tables() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness)
+            .section(".strtab", SHT_STRTAB, 0, b"\0first\0")
+            .section(".strtab", SHT_STRTAB, 0, b"\0second\0")
+            .build();
+        let first = image.section_idx(".strtab");
+        let second = first + 1;
+        assert_eq!(image.names[second], ".strtab");
+
+        let parser = image.parse().unwrap();
+        assert!(parser.warnings.is_empty(), "{:?}", parser.warnings);
+        assert_eq!(parser.string_tables.len(), 3);
+        for (idx, expected) in [(first, "first"), (second, "second")] {
+            let table = parser.string_table_at(idx).unwrap();
+            assert_eq!(ElfParser::get_sh_name(table, 1).unwrap(), expected);
+            assert_eq!(name(&parser, idx).as_deref(), Some(".strtab"));
+        }
+        let table = parser.string_table(StringTableType::StrTab).unwrap();
+        assert_eq!(table.section_idx, first);
+
+        // a copy of the header string table doesn't replace it
+        let shstrtab = image.section_idx(".shstrtab");
+        assert_eq!(
+            parser
+                .string_table(StringTableType::ShStrTab)
+                .unwrap()
+                .section_idx,
+            shstrtab
+        );
+    }
+}
+
+#[test]
+fn missing_trailing_nul() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        let idx = image.section_idx(".shstrtab");
+        let size = image.section(".shstrtab").size;
+        image.set_section(idx, |l| l.sh_size, size - 1);
+
+        let parser = image.parse().unwrap();
+        assert!(parser
+            .warnings
+            .contains(&ParseWarning::MalformedStringTable {
+                idx,
+                reason: "doesn't end with a NUL byte",
+            }));
+        // names before the unterminated one still resolve
+        assert_eq!(name(&parser, 2).as_deref(), Some(".data"));
+    }
+}
+
+#[test]
+fn missing_leading_nul() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        let idx = image.section_idx(".shstrtab");
+        let sh = *image.section(".shstrtab");
+        image
+            .set_section(idx, |l| l.sh_offset, sh.offset + 1)
+            .set_section(idx, |l| l.sh_size, sh.size - 1);
+
+        let parser = image.parse().unwrap();
+        assert!(parser
+            .warnings
+            .contains(&ParseWarning::MalformedStringTable {
+                idx,
+                reason: "doesn't start with a NUL byte",
+            }));
+    }
+}
+
+#[test]
+fn table_past_the_end() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        let idx = image.section_idx(".shstrtab");
+        let len = image.bytes.len() as u64;
+        image.set_section(idx, |l| l.sh_offset, len);
+
+        let parser = image.parse().unwrap();
+        assert!(parser
+            .warnings
+            .contains(&ParseWarning::MalformedStringTable {
+                idx,
+                reason: "runs past the end of the file",
+            }));
+        assert!(parser.string_tables.is_empty());
+        assert_eq!(name(&parser, 1), None);
+    }
+}
+
+#[test]
+fn header_table_of_wrong_type() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        let idx = image.section_idx(".data");
+        image.set_header(|l| l.e_shstrndx, idx as u64);
+
+        let parser = image.parse().unwrap();
+        assert!(parser
+            .warnings
+            .contains(&ParseWarning::HeaderStringTableWrongType {
+                sh_str_ndx: idx as u16,
+                s_type: SHT_PROGBITS,
+            }));
+        // read as a string table anyway
+        assert_eq!(parser.header_string_table_idx, Some(0));
+    }
+}
+
+#[test]
+fn header_table_without_contents() {
+    let mut image = ImageBuilder::minimal(ElfHClass::ElfClass64, ElfHData::ElfData2Lsb)
+        .section(".bss", SHT_NOBITS, SHF_ALLOC | SHF_WRITE, &[0; 32])
+        .build();
+    let idx = image.section_idx(".bss");
+    image.set_header(|l| l.e_shstrndx, idx as u64);
+
+    let parser = image.parse().unwrap();
+    assert!(parser
+        .warnings
+        .contains(&ParseWarning::HeaderStringTableWrongType {
+            sh_str_ndx: idx as u16,
+            s_type: SHT_NOBITS,
+        }));
+    assert_eq!(parser.header_string_table_idx, None);
+    assert_eq!(name(&parser, 1), None);
+}
+
+#[test]
+fn header_table_index_out_of_range() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        image.set_header(|l| l.e_shstrndx, 200);
+
+        let parser = image.parse().unwrap();
+        assert!(parser
+            .warnings
+            .contains(&ParseWarning::HeaderStringTableOutOfRange {
+                sh_str_ndx: 200,
+                sh_num: 4,
+            }));
+        assert!(!parser
+            .warnings
+            .iter()
+            .any(|w| matches!(w, ParseWarning::MissingHeaderStringTable { .. })));
+        assert!(matches!(
+            parser.section_name(&parser.section_headers[1]),
+            Err(ParseError::NameUnavailable)
+        ));
+        assert!(parser.section_by_name(".text").is_none());
+
+        // sections are still listed, under guessed names
+        let names = parser
+            .sections()
+            .map(|s| s.name_or_guess().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(names[1], "[.text]");
+
+        let strict = ElfParser::parse_with(&image.bytes, &ParseOptions::default().strict(true));
+        let Err(ParseError::StrictViolation(warnings)) = strict else {
+            panic!("strict parse of an out of range sh_str_ndx succeeded");
+        };
+        assert!(warnings
+            .iter()
+            .any(|w| w.code() == "W0018_SHSTRNDX_OUT_OF_RANGE"));
+    }
+}
+
+#[test]
+fn header_table_index_escaped() {
+    for (class, endianness) in ENCODINGS {
+        let mut image = ImageBuilder::minimal(class, endianness).build();
+        image
+            .set_header(|l| l.e_shstrndx, SHN_XINDEX as u64)
+            .set_section(0, |l| l.sh_link, 3);
+
+        let parser = image.parse().unwrap();
+        assert_eq!(name(&parser, 1).as_deref(), Some(".text"));
+        assert!(!parser.warnings.iter().any(|w| matches!(
+            w,
+            ParseWarning::HeaderStringTableOutOfRange { .. }
+                | ParseWarning::MissingHeaderStringTable { .. }
+        )));
+    }
+}
+
+#[test]
+fn iterate_strings() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness)
+            .section(
+                ".names",
+                SHT_STRTAB,
+                0,
+                b"\0foo\0\0bar\xff\0libc.so.6\0tail",
+            )
+            .build();
+        let parser = image.parse().unwrap();
+        let table = parser.string_table_at(image.section_idx(".names")).unwrap();
+
+        let strict = table
+            .iter(false)
+            .map(|(off, s)| (off, s.into_owned()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            strict,
+            [(1, "foo".to_string()), (11, "libc.so.6".to_string())]
+        );
+
+        let lossy = table.iter(true).map(|(off, _)| off).collect::<Vec<_>>();
+        assert_eq!(lossy, [1, 6, 11]);
+        assert_eq!(table.iter(true).nth(1).unwrap().1, "bar\u{fffd}");
+        assert_eq!(table.len_strings(), 3);
+
+        assert_eq!(table.contains("libc.so.6"), Some(11));
+        // tails of strings can be pointed at too
+        assert_eq!(table.contains("so.6"), Some(16));
+        assert_eq!(table.contains("tail"), None);
+        assert_eq!(table.contains("fo"), None);
+    }
+}
+
+#[test]
+fn iterate_header_string_table() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+        let parser = image.parse().unwrap();
+        let table = parser
+            .string_table_at(image.section_idx(".shstrtab"))
+            .unwrap();
+
+        let names = table.iter(false).map(|(_, s)| s).collect::<Vec<_>>();
+        assert_eq!(names, [".text", ".data", ".shstrtab"]);
+        for (off, s) in table.iter(false) {
+            assert_eq!(ElfParser::get_sh_name(table, off).unwrap(), s);
+        }
+    }
+}
@@ -0,0 +1,106 @@
+mod fixtures;
+
+use elf_parser::elf::shdr::SHT_STRTAB;
+use elf_parser::parser::{ElfParser, ParseWarning};
+use elf_parser::strtab::StringTableBuilder;
+use fixtures::{ImageBuilder, ENCODINGS};
+
+/// Strings drawn from a small alphabet, so that many of them are equal or end one
+/// another, generated by a xorshift seeded with `seed`
+fn strings(seed: u64, count: usize) -> Vec<String> {
+    let mut state = seed;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    (0..count)
+        .map(|_| {
+            let len = next() % 8;
+            (0..len)
+                .map(|_| (b'a' + (next() % 3) as u8) as char)
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn round_trips_through_the_parser() {
+    for (class, endianness) in ENCODINGS {
+        for seed in 1..20 {
+            for tail_merge in [false, true] {
+                let strings = strings(seed, 64);
+                let mut builder = StringTableBuilder::new().tail_merge(tail_merge);
+                let offsets = strings
+                    .iter()
+                    .map(|s| builder.insert(s))
+                    .collect::<Vec<_>>();
+
+                let image = ImageBuilder::minimal(class, endianness)
+                    .section(".names", SHT_STRTAB, 0, &builder.build())
+                    .build();
+                let parser = image.parse().unwrap();
+                assert!(!parser
+                    .warnings
+                    .iter()
+                    .any(|w| matches!(w, ParseWarning::MalformedStringTable { .. })));
+
+                let table = parser.string_table_at(image.section_idx(".names")).unwrap();
+                for (s, off) in strings.iter().zip(offsets) {
+                    assert_eq!(&ElfParser::get_sh_name(table, off).unwrap(), s);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn deduplicates() {
+    let mut builder = StringTableBuilder::new();
+    let foo = builder.insert("foo");
+
+    assert_eq!(builder.insert(""), 0);
+    assert_eq!(builder.insert("foo"), foo);
+    assert_eq!(builder.build(), b"\0foo\0");
+}
+
+#[test]
+fn tail_merging() {
+    let mut builder = StringTableBuilder::new().tail_merge(true);
+    let foobar = builder.insert("foobar");
+    assert_eq!(builder.insert("bar"), foobar + 3);
+    assert_eq!(builder.insert("r"), foobar + 5);
+    assert_eq!(builder.lookup(foobar + 3), Some("bar"));
+
+    // an earlier string can't move into a later one
+    let baz = builder.insert("baz");
+    let abaz = builder.insert("abaz");
+    assert_ne!(abaz + 1, baz);
+    assert_eq!(builder.build(), b"\0foobar\0baz\0abaz\0");
+
+    let mut builder = StringTableBuilder::new();
+    let foobar = builder.insert("foobar");
+    assert_ne!(builder.insert("bar"), foobar + 3);
+}
+
+#[test]
+fn offsets_are_stable() {
+    let mut builder = StringTableBuilder::new();
+    let first = builder.insert("first");
+    let table = builder.as_bytes().to_vec();
+    let second = builder.insert("second");
+
+    assert_eq!(&builder.as_bytes()[..table.len()], table);
+    assert_eq!(builder.lookup(first), Some("first"));
+    assert_eq!(builder.lookup(second), Some("second"));
+    assert_eq!(builder.lookup(100), None);
+
+    // offsets into an existing table survive growing it, even without its final NUL
+    let mut builder = StringTableBuilder::from_table(b"\0.text\0.data");
+    assert_eq!(builder.insert(".data"), 7);
+    assert_eq!(builder.insert(".bss"), 13);
+    assert_eq!(builder.lookup(1), Some(".text"));
+    assert_eq!(builder.build(), b"\0.text\0.data\0.bss\0");
+}
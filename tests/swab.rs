@@ -0,0 +1,101 @@
+mod fixtures;
+
+use elf_parser::bytes::{convert, encode, Address};
+use elf_parser::elf::ehdr::ElfHData;
+use elf_parser::parser::ElfParser;
+use elf_parser::swab::convert_endianness;
+use fixtures::{ImageBuilder, ENCODINGS};
+
+fn opposite(endianness: ElfHData) -> ElfHData {
+    if endianness == ElfHData::ElfData2Lsb {
+        ElfHData::ElfData2Msb
+    } else {
+        ElfHData::ElfData2Lsb
+    }
+}
+
+#[test]
+fn encode_round_trips() {
+    for endianness in [ElfHData::ElfData2Lsb, ElfHData::ElfData2Msb] {
+        assert_eq!(
+            convert::<u16, 2>(encode(0x1234u16, endianness), endianness),
+            0x1234
+        );
+        assert_eq!(
+            convert::<u32, 4>(encode(0x12345678u32, endianness), endianness),
+            0x12345678
+        );
+        assert_eq!(convert::<i64, 8>(encode(-2i64, endianness), endianness), -2);
+        assert_eq!(
+            convert::<Address, 8>(encode(Address(0x1000), endianness), endianness),
+            Address(0x1000)
+        );
+    }
+
+    assert_eq!(encode(0x1234u16, ElfHData::ElfData2Lsb), [0x34, 0x12]);
+    assert_eq!(encode(0x1234u16, ElfHData::ElfData2Msb), [0x12, 0x34]);
+}
+
+#[test]
+fn converted_headers() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+        let parser = image.parse().unwrap();
+
+        let target = opposite(endianness);
+        let converted = convert_endianness(&image.bytes, target).unwrap();
+        let swapped = ElfParser::parse(&converted).unwrap();
+
+        assert_eq!(swapped.headers.ident.data, target);
+        assert_eq!(swapped.headers.entry, parser.headers.entry);
+        assert_eq!(swapped.headers.sh_num, parser.headers.sh_num);
+
+        // the tables of both files, encoded in the byte order of the converted one
+        let tables = |parser: &ElfParser| {
+            let mut bytes = vec![];
+            for sh in &parser.section_headers {
+                sh.write(&swapped.ctx, &mut bytes);
+            }
+            for ph in &parser.program_headers {
+                ph.write(&swapped.ctx, &mut bytes);
+            }
+            bytes
+        };
+        assert_eq!(tables(&swapped), tables(&parser));
+
+        // contents are left in the original byte order
+        let names = parser.section_by_name(".shstrtab").unwrap().0;
+        assert_eq!(
+            swapped.section_data(names).unwrap(),
+            parser.section_data(names).unwrap()
+        );
+    }
+}
+
+#[test]
+fn round_trip() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+
+        let converted = convert_endianness(&image.bytes, opposite(endianness)).unwrap();
+        assert_ne!(converted, image.bytes);
+        assert_eq!(
+            convert_endianness(&converted, endianness).unwrap(),
+            image.bytes
+        );
+        assert_eq!(
+            convert_endianness(&image.bytes, endianness).unwrap(),
+            image.bytes
+        );
+    }
+}
+
+#[test]
+fn truncated_table() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+        let sh_off = image.sh_off as usize;
+
+        assert!(convert_endianness(&image.bytes[..sh_off + 1], opposite(endianness)).is_err());
+    }
+}
@@ -0,0 +1,92 @@
+mod fixtures;
+
+use elf_parser::bytes::Address;
+use elf_parser::elf::shdr::SHT_DYNSYM;
+use elf_parser::elf::sym::{Elf64Sym, STB_GLOBAL, STB_LOCAL, STT_FUNC, STT_OBJECT};
+use elf_parser::symbol_index::SymbolRef;
+use fixtures::{Image, ImageBuilder, ENCODINGS};
+
+fn symbol(info: u8, shndx: u16, value: u64) -> Elf64Sym {
+    Elf64Sym {
+        name: 0,
+        info,
+        other: 0,
+        shndx,
+        value: Address(value),
+        size: 0,
+    }
+}
+
+fn images() -> Vec<Image> {
+    ENCODINGS
+        .into_iter()
+        .map(|(class, endianness)| {
+            ImageBuilder::minimal(class, endianness)
+                .symbols(&[
+                    ("helper", symbol(STB_LOCAL << 4 | STT_FUNC, 1, 0x10)),
+                    ("main", symbol(STB_GLOBAL << 4 | STT_FUNC, 1, 0x20)),
+                    ("helper", symbol(STB_GLOBAL << 4 | STT_FUNC, 1, 0x30)),
+                ])
+                .symbol_table(
+                    ".dynsym",
+                    SHT_DYNSYM,
+                    ".dynstr",
+                    &[
+                        ("environ", symbol(STB_GLOBAL << 4 | STT_OBJECT, 0, 0)),
+                        ("main", symbol(STB_GLOBAL << 4 | STT_FUNC, 1, 0x20)),
+                    ],
+                )
+                .build()
+        })
+        .collect()
+}
+
+#[test]
+fn duplicates_in_table_order() {
+    for image in images() {
+        let parser = image.parse().unwrap();
+
+        let helpers = parser.symbols_by_name("helper");
+        let refs = helpers.iter().map(|(r, _)| *r).collect::<Vec<_>>();
+        assert_eq!(
+            refs,
+            [
+                SymbolRef { table: 0, index: 1 },
+                SymbolRef { table: 0, index: 3 }
+            ]
+        );
+        assert_eq!(helpers[1].1.value, Address(0x30));
+
+        let mains = parser.symbols_by_name("main");
+        assert_eq!(mains.len(), 2);
+        assert_eq!(mains[1].0, SymbolRef { table: 1, index: 2 });
+    }
+}
+
+#[test]
+fn find_symbol() {
+    for image in images() {
+        let parser = image.parse().unwrap();
+
+        assert_eq!(parser.find_symbol("helper").unwrap().value, Address(0x10));
+        assert!(parser.find_symbol("environ").unwrap().is_undefined());
+        assert!(parser.find_symbol("missing").is_none());
+        assert!(parser.find_symbol("").is_none());
+        assert!(parser.clone().find_symbol("main").is_some());
+    }
+}
+
+#[test]
+fn editing_drops_the_index() {
+    for image in images() {
+        let mut parser = image.parse().unwrap();
+        assert_eq!(parser.symbols_by_name("main").len(), 2);
+
+        // point the local `helper` at the name of `main`
+        let tables = parser.symbol_tables_mut();
+        tables[0].symbols[1].name = tables[0].symbols[2].name;
+
+        assert_eq!(parser.symbols_by_name("main").len(), 3);
+        assert_eq!(parser.symbols_by_name("helper").len(), 1);
+    }
+}
@@ -0,0 +1,135 @@
+mod fixtures;
+
+use elf_parser::bytes::Address;
+use elf_parser::elf::shdr::SHT_DYNSYM;
+use elf_parser::elf::sym::{
+    Elf64Sym, SHN_COMMON, SHN_UNDEF, STB_GLOBAL, STB_GNU_UNIQUE, STB_LOCAL, STB_WEAK, STT_FILE,
+    STT_FUNC, STT_GNU_IFUNC, STT_NOTYPE, STT_OBJECT, STT_SECTION,
+};
+use elf_parser::symbol_summary::{SymbolBucket, SymbolSummary};
+use fixtures::{ImageBuilder, BASE_ADDR, ENCODINGS};
+
+fn symbol(bind: u8, sym_type: u8, shndx: u16) -> Elf64Sym {
+    Elf64Sym {
+        name: 0,
+        info: bind << 4 | sym_type,
+        other: 0,
+        shndx,
+        value: Address(if shndx == SHN_UNDEF { 0 } else { BASE_ADDR }),
+        size: 4,
+    }
+}
+
+#[test]
+fn buckets() {
+    for (sym, bucket) in [
+        (
+            symbol(STB_GLOBAL, STT_FUNC, 1),
+            Some(SymbolBucket::DefinedGlobal),
+        ),
+        (
+            symbol(STB_GNU_UNIQUE, STT_OBJECT, 1),
+            Some(SymbolBucket::DefinedGlobal),
+        ),
+        (
+            symbol(STB_WEAK, STT_FUNC, 1),
+            Some(SymbolBucket::DefinedWeak),
+        ),
+        (
+            symbol(STB_LOCAL, STT_OBJECT, 1),
+            Some(SymbolBucket::DefinedLocal),
+        ),
+        // weak references are still references
+        (
+            symbol(STB_WEAK, STT_NOTYPE, SHN_UNDEF),
+            Some(SymbolBucket::Undefined),
+        ),
+        (
+            symbol(STB_GLOBAL, STT_FUNC, SHN_UNDEF),
+            Some(SymbolBucket::Undefined),
+        ),
+        (
+            symbol(STB_GLOBAL, STT_OBJECT, SHN_COMMON),
+            Some(SymbolBucket::Common),
+        ),
+        (
+            symbol(STB_WEAK, STT_OBJECT, SHN_COMMON),
+            Some(SymbolBucket::Common),
+        ),
+        // indirect functions define their resolver, whatever their binding
+        (
+            symbol(STB_GLOBAL, STT_GNU_IFUNC, 1),
+            Some(SymbolBucket::DefinedGlobal),
+        ),
+        (
+            symbol(STB_LOCAL, STT_GNU_IFUNC, 1),
+            Some(SymbolBucket::DefinedLocal),
+        ),
+        (
+            symbol(STB_GLOBAL, STT_GNU_IFUNC, SHN_UNDEF),
+            Some(SymbolBucket::Undefined),
+        ),
+        (symbol(STB_LOCAL, STT_SECTION, 1), None),
+        (symbol(STB_LOCAL, STT_FILE, 0xfff1), None),
+        (symbol(5, STT_FUNC, 1), None),
+    ] {
+        assert_eq!(SymbolBucket::of(&sym), bucket, "{:?}", sym);
+    }
+}
+
+#[test]
+fn summary_per_table() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness)
+            .symbols(&[
+                ("crt.c", symbol(STB_LOCAL, STT_FILE, 0xfff1)),
+                ("helper", symbol(STB_LOCAL, STT_FUNC, 1)),
+                ("main", symbol(STB_GLOBAL, STT_FUNC, 1)),
+                ("memcpy", symbol(STB_GLOBAL, STT_GNU_IFUNC, 1)),
+                ("hook", symbol(STB_WEAK, STT_FUNC, 1)),
+                ("puts", symbol(STB_GLOBAL, STT_FUNC, SHN_UNDEF)),
+                ("__gmon_start__", symbol(STB_WEAK, STT_NOTYPE, SHN_UNDEF)),
+                ("buffer", symbol(STB_GLOBAL, STT_OBJECT, SHN_COMMON)),
+            ])
+            .symbol_table(
+                ".dynsym",
+                SHT_DYNSYM,
+                ".dynstr",
+                &[
+                    ("puts", symbol(STB_GLOBAL, STT_FUNC, SHN_UNDEF)),
+                    ("abort", symbol(STB_GLOBAL, STT_FUNC, SHN_UNDEF)),
+                    ("main", symbol(STB_GLOBAL, STT_FUNC, 1)),
+                ],
+            )
+            .build();
+        let parser = image.parse().unwrap();
+
+        let summary = parser.symbol_summary().unwrap();
+        let symtab = summary.symtab.as_ref().unwrap();
+        assert_eq!(symtab.section_idx, image.section_idx(".symtab"));
+        assert_eq!(symtab.defined_global, ["main", "memcpy"]);
+        assert_eq!(symtab.defined_weak, ["hook"]);
+        assert_eq!(symtab.defined_local, ["helper"]);
+        assert_eq!(symtab.undefined, ["puts", "__gmon_start__"]);
+        assert_eq!(symtab.common, ["buffer"]);
+        assert_eq!(symtab.count(SymbolBucket::Undefined), 2);
+
+        let dynsym = summary.dynsym.as_ref().unwrap();
+        assert_eq!(dynsym.section_idx, image.section_idx(".dynsym"));
+        assert_eq!(dynsym.names(SymbolBucket::Undefined), ["puts", "abort"]);
+        assert_eq!(dynsym.count(SymbolBucket::DefinedGlobal), 1);
+        assert_eq!(dynsym.count(SymbolBucket::Common), 0);
+
+        // sorted and merged across the tables, as the CLI lists them
+        assert_eq!(summary.undefined(), ["__gmon_start__", "abort", "puts"]);
+    }
+}
+
+#[test]
+fn no_symbol_tables() {
+    let (class, endianness) = ENCODINGS[0];
+    let image = ImageBuilder::minimal(class, endianness).build();
+    let parser = image.parse().unwrap();
+
+    assert_eq!(parser.symbol_summary().unwrap(), SymbolSummary::default());
+}
@@ -0,0 +1,92 @@
+mod fixtures;
+
+use elf_parser::elf::dynamic::DF_TEXTREL;
+use elf_parser::elf::ehdr::{ElfHClass, ElfHData, ElfHType};
+use elf_parser::elf::phdr::{
+    PType, DT_FLAGS, DT_NULL, DT_RELA, DT_RELAENT, DT_RELASZ, DT_TEXTREL, PF_READ, PF_WRITE,
+};
+use elf_parser::elf::shdr::{SHF_ALLOC, SHF_WRITE, SHT_DYNAMIC, SHT_RELA};
+use fixtures::{Image, ImageBuilder, ENCODINGS};
+
+/// Builds a shared object with a single relative relocation of `target`, the section
+/// it lands in, and `extra` dynamic entries. Addresses are only known once the image
+/// is laid out, so it's built a first time to find them, then again with them
+fn image(class: ElfHClass, endianness: ElfHData, target: &str, extra: &[(i64, u64)]) -> Image {
+    let build = |target_addr: u64, rela_addr: u64| {
+        let builder = ImageBuilder::minimal(class, endianness).e_type(ElfHType::SharedObject);
+        let ctx = builder.ctx();
+        let word = ctx.word_size() as u64;
+
+        let mut rela = vec![];
+        ctx.put_uword(&mut rela, target_addr);
+        // R_RISCV_RELATIVE
+        ctx.put_uword(&mut rela, 3);
+        ctx.put_uword(&mut rela, 0);
+
+        let mut dynamic = vec![];
+        for (tag, value) in [
+            (DT_RELA, rela_addr),
+            (DT_RELASZ, 3 * word),
+            (DT_RELAENT, 3 * word),
+        ]
+        .iter()
+        .chain(extra)
+        .chain(&[(DT_NULL, 0)])
+        {
+            ctx.put_uword(&mut dynamic, *tag as u64);
+            ctx.put_uword(&mut dynamic, *value);
+        }
+
+        builder
+            .section(".rela.dyn", SHT_RELA, SHF_ALLOC, &rela)
+            .section(".dynamic", SHT_DYNAMIC, SHF_ALLOC | SHF_WRITE, &dynamic)
+            .segment(PType::PtLoad, PF_READ, &[".rela.dyn"])
+            .segment(PType::PtLoad, PF_READ | PF_WRITE, &[".dynamic"])
+            .segment(PType::PtDynamic, PF_READ | PF_WRITE, &[".dynamic"])
+            .build()
+    };
+
+    let layout = build(0, 0);
+    build(
+        layout.section(target).addr.0,
+        layout.section(".rela.dyn").addr.0,
+    )
+}
+
+#[test]
+fn relocation_in_text() {
+    for (class, endianness) in ENCODINGS {
+        let image = image(class, endianness, ".text", &[]);
+        let parser = image.parse().unwrap();
+
+        assert_eq!(
+            parser.text_relocation_targets(),
+            [image.section(".text").addr]
+        );
+        assert!(parser.has_text_relocations());
+    }
+}
+
+#[test]
+fn relocation_in_data() {
+    for (class, endianness) in ENCODINGS {
+        let image = image(class, endianness, ".data", &[]);
+        let parser = image.parse().unwrap();
+
+        assert!(parser.text_relocation_targets().is_empty());
+        assert!(!parser.has_text_relocations());
+    }
+}
+
+#[test]
+fn declared_text_relocations() {
+    for (class, endianness) in ENCODINGS {
+        for extra in [(DT_TEXTREL, 0), (DT_FLAGS, DF_TEXTREL)] {
+            let image = image(class, endianness, ".data", &[extra]);
+            let parser = image.parse().unwrap();
+
+            assert!(parser.text_relocation_targets().is_empty());
+            assert!(parser.has_text_relocations());
+        }
+    }
+}
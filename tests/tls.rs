@@ -0,0 +1,150 @@
+mod fixtures;
+
+use elf_parser::bytes::Address;
+use elf_parser::elf::ehdr::EM_X86_64;
+use elf_parser::elf::phdr::{PType, PF_EXEC, PF_READ, PF_WRITE};
+use elf_parser::elf::shdr::{
+    SHF_ALLOC, SHF_EXECINSTR, SHF_TLS, SHF_WRITE, SHT_NOBITS, SHT_PROGBITS,
+};
+use elf_parser::elf::sym::{Elf64Sym, STB_GLOBAL, STT_TLS};
+use elf_parser::parser::{ParseError, ParseWarning};
+use elf_parser::tls::{TlsLayout, TlsSymbol};
+use fixtures::{ImageBuilder, SectionSpec, ENCODINGS, TEXT};
+
+fn tls_section(name: &str, s_type: u32, size: usize) -> SectionSpec {
+    SectionSpec {
+        name: name.to_string(),
+        s_type,
+        flags: SHF_ALLOC | SHF_WRITE | SHF_TLS,
+        align: 8,
+        link: 0,
+        info: 0,
+        ent_size: 0,
+        data: vec![0x2a; size],
+    }
+}
+
+fn variable(shndx: u16, offset: u64, size: u64) -> Elf64Sym {
+    Elf64Sym {
+        name: 0,
+        info: STB_GLOBAL << 4 | STT_TLS,
+        other: 0,
+        shndx,
+        value: Address(offset),
+        size,
+    }
+}
+
+/// A threaded program with `__thread int counter = 42` in `.tdata` and
+/// `__thread char buffer[16]` in `.tbss`, along with the symbols of `symbols`
+fn builders(symbols: &[(&str, Elf64Sym)]) -> Vec<ImageBuilder> {
+    let mut all = vec![
+        ("counter", variable(2, 0, 4)),
+        ("buffer", variable(3, 8, 16)),
+    ];
+    all.extend_from_slice(symbols);
+
+    ENCODINGS
+        .into_iter()
+        .map(|(class, endianness)| {
+            ImageBuilder::new(class, endianness)
+                .section(".text", SHT_PROGBITS, SHF_ALLOC | SHF_EXECINSTR, &TEXT)
+                .section_with(tls_section(".tdata", SHT_PROGBITS, 8))
+                .section_with(tls_section(".tbss", SHT_NOBITS, 16))
+                .symbols(&all)
+                .segment(PType::PtLoad, PF_READ | PF_EXEC, &[".text"])
+                .segment(PType::PtLoad, PF_READ | PF_WRITE, &[".tdata", ".tbss"])
+                .segment(PType::PtTls, PF_READ, &[".tdata", ".tbss"])
+        })
+        .collect()
+}
+
+#[test]
+fn layout() {
+    for builder in builders(&[]) {
+        let mut image = builder.build();
+        image.set_segment(2, |l| l.p_align, 8);
+        let parser = image.parse().unwrap();
+
+        let layout = parser.tls_layout().unwrap().unwrap();
+        assert_eq!(
+            layout,
+            TlsLayout {
+                template_size: 8,
+                memsz: 24,
+                align: 8,
+                tp_offset_bias: 0,
+            }
+        );
+
+        let symbols = parser.tls_symbols().unwrap();
+        assert_eq!(
+            symbols,
+            [
+                TlsSymbol {
+                    name: "counter".to_string(),
+                    offset: 0,
+                    size: 4,
+                },
+                TlsSymbol {
+                    name: "buffer".to_string(),
+                    offset: 8,
+                    size: 16,
+                },
+            ]
+        );
+        assert_eq!(layout.tp_offset(symbols[1].offset), 8);
+        assert!(parser
+            .warnings
+            .iter()
+            .all(|w| !matches!(w, ParseWarning::TlsSymbolOutOfBlock { .. })));
+    }
+}
+
+#[test]
+fn symbol_out_of_block() {
+    for builder in builders(&[("overflow", variable(3, 20, 8))]) {
+        let image = builder.build();
+        let parser = image.parse().unwrap();
+        let symtab = image.section_idx(".symtab");
+
+        let warning = ParseWarning::TlsSymbolOutOfBlock {
+            section: symtab,
+            idx: 3,
+            offset: 20,
+            size: 8,
+            memsz: 24,
+        };
+        assert!(parser.warnings.contains(&warning), "{:?}", parser.warnings);
+        assert_eq!(warning.code(), "W0020_TLS_SYMBOL_OUT_OF_BLOCK");
+        assert_eq!(warning.section(), Some(symtab));
+        assert_eq!(
+            warning.to_string(),
+            format!(
+                "TLS symbol [3] of section [{}] spans 0x14..0x1c, past the 0x18 bytes of the \
+                 TLS block",
+                symtab
+            )
+        );
+        assert_eq!(parser.tls_symbols().unwrap().len(), 3);
+    }
+}
+
+#[test]
+fn without_tls() {
+    for mut builder in builders(&[]) {
+        builder.segments.pop();
+        let image = builder.build();
+        let parser = image.parse().unwrap();
+
+        assert_eq!(parser.tls_layout().unwrap(), None);
+        // the symbols are still listed, but can't be checked against the block
+        assert_eq!(parser.tls_symbols().unwrap().len(), 2);
+    }
+
+    let image = builders(&[])[0].clone().machine(EM_X86_64).build();
+    assert!(matches!(
+        image.parse().unwrap().tls_layout(),
+        Err(ParseError::UnsupportedMachine(EM_X86_64))
+    ));
+}
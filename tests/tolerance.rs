@@ -0,0 +1,155 @@
+mod fixtures;
+
+use elf_parser::elf::ehdr::ElfHClass;
+use elf_parser::elf::shdr::{
+    SType, SHF_ALLOC, SHT_GNU_HASH, SHT_HIOS, SHT_HIPROC, SHT_LOOS, SHT_LOPROC, SHT_LOUSER,
+    SHT_PROGBITS, SHT_SYMTAB_SHNDX,
+};
+use elf_parser::options::ParseOptions;
+use elf_parser::parser::{ElfParser, ParseError, ParseWarning};
+use fixtures::{ImageBuilder, SectionSpec, ENCODINGS};
+
+/// `SHT_SUNW_move`, `SHT_SUNW_syminfo` and `SHT_MIPS_REGINFO`, `SHT_MIPS_DWARF`
+const SUNW_MOVE: u32 = 0x6ffffffa;
+const SUNW_SYMINFO: u32 = 0x6ffffffc;
+const MIPS_REGINFO: u32 = 0x70000006;
+const MIPS_DWARF: u32 = 0x7000001e;
+
+fn section_types() -> Vec<u32> {
+    (0..=20)
+        .chain([
+            SHT_LOOS,
+            SHT_GNU_HASH,
+            SUNW_MOVE,
+            SUNW_SYMINFO,
+            SHT_HIOS,
+            SHT_LOPROC,
+            MIPS_REGINFO,
+            MIPS_DWARF,
+            SHT_HIPROC,
+            SHT_LOUSER,
+            u32::MAX,
+        ])
+        .collect()
+}
+
+#[test]
+fn section_type_ranges() {
+    assert_eq!(SType::from(SUNW_MOVE), SType::OsSpecific(SUNW_MOVE));
+    assert_eq!(SType::from(SHT_HIOS), SType::GnuVersym);
+    assert_eq!(SType::from(MIPS_REGINFO), SType::ProcSpecific(MIPS_REGINFO));
+    assert_eq!(SType::from(SHT_HIPROC), SType::ProcSpecific(SHT_HIPROC));
+    assert_eq!(SType::from(SHT_LOUSER), SType::Unknown(SHT_LOUSER));
+    assert_eq!(SType::from(20), SType::Unknown(20));
+}
+
+#[test]
+fn any_section_type() {
+    // bytes that make no sense as any table, with entry sizes that don't divide them.
+    // They're framed by NUL bytes as string tables must be
+    let mut data = (0..45u8).map(|b| b.wrapping_mul(37)).collect::<Vec<_>>();
+    data.push(0);
+
+    for (class, endianness) in ENCODINGS {
+        for s_type in section_types() {
+            for ent_size in [0, 1, 7, 24, 0x1000] {
+                let image = ImageBuilder::minimal(class, endianness)
+                    .section_with(SectionSpec {
+                        name: ".quirk".to_string(),
+                        s_type,
+                        flags: 0,
+                        align: 1,
+                        link: 0,
+                        info: 0,
+                        ent_size,
+                        data: data.clone(),
+                    })
+                    .build();
+                let context = format!(
+                    "{:?} {:?} type {:#x} entsize {}",
+                    class, endianness, s_type, ent_size
+                );
+
+                let parser = image
+                    .parse()
+                    .unwrap_or_else(|e| panic!("{}: {:?}", context, e));
+                let idx = image.section_idx(".quirk");
+                assert_eq!(parser.section_headers[idx].sh_type(), SType::from(s_type));
+
+                let mut expected = vec![];
+                if s_type > SHT_SYMTAB_SHNDX && s_type < SHT_LOOS {
+                    expected.push(ParseWarning::UnknownSectionType { idx, s_type });
+                }
+                if ent_size != 0 && !(data.len() as u64).is_multiple_of(ent_size) {
+                    expected.push(ParseWarning::SectionLint {
+                        idx,
+                        reason: "size isn't a multiple of the entry size",
+                    });
+                }
+                assert_eq!(parser.warnings, expected, "{}", context);
+
+                // walking the file must not trip on the section either
+                for section in parser.sections() {
+                    let _ = (section.name_or_guess(), section.data());
+                }
+                let _ = parser.section_links();
+                let _ = parser.relocations_named();
+                let _ = parser.note_groups();
+                let _ = parser.toolchain_info();
+                let _ = parser.size_report();
+                let _ = parser.region_map();
+                let _ = parser.classify_section(idx);
+            }
+        }
+    }
+}
+
+#[test]
+fn undecodable_section_header() {
+    for (class, endianness) in ENCODINGS {
+        let mut builder = ImageBuilder::minimal(class, endianness);
+        for n in 0..6 {
+            builder = builder.section(&format!(".extra{}", n), SHT_PROGBITS, SHF_ALLOC, &[n; 8]);
+        }
+        let mut image = builder.build();
+        assert_eq!(image.section_headers.len(), 10);
+
+        // contents that would wrap around the offsets of the class can't be located
+        let wrapping = match class {
+            ElfHClass::ElfClass32 => u32::MAX as u64,
+            _ => u64::MAX,
+        };
+        image.set_section(3, |l| l.sh_offset, wrapping);
+
+        let parser = image.parse().unwrap();
+        assert!(parser.section_headers[3].is_null());
+        let warning = parser
+            .warnings
+            .iter()
+            .find(|w| matches!(w, ParseWarning::UndecodableSection { .. }))
+            .unwrap();
+        assert_eq!(warning.section(), Some(3));
+        assert_eq!(warning.code(), "W0027_UNDECODABLE_SECTION");
+
+        // the other entries keep their indices
+        for section in parser.sections().filter(|s| s.index() != 3) {
+            let idx = section.index();
+            let expected = &image.section_headers[idx];
+            let start = expected.offset as usize;
+            let end = start + expected.file_size() as usize;
+
+            assert_eq!(section.name(), image.names[idx]);
+            assert_eq!(section.data().unwrap(), &image.bytes[start..end]);
+        }
+
+        let strict = ParseOptions::default().strict(true);
+        match ElfParser::parse_with(&image.bytes, &strict) {
+            Err(ParseError::InSection {
+                index: 3, source, ..
+            }) => {
+                assert!(matches!(*source, ParseError::ContentsOverflow { .. }));
+            }
+            result => panic!("{:?}", result.map(|_| ())),
+        }
+    }
+}
@@ -0,0 +1,182 @@
+mod fixtures;
+
+use elf_parser::bytes::ParseContext;
+use elf_parser::elf::ehdr::{ElfHClass, EM_RISCV};
+use elf_parser::elf::note::{
+    GnuAbiOs, GnuPropertyKind, GNU_PROPERTY_NO_COPY_ON_PROTECTED, GNU_PROPERTY_RISCV_FEATURE_1_AND,
+    GNU_PROPERTY_RISCV_FEATURE_1_CFI_LP_UNLABELED, GNU_PROPERTY_RISCV_FEATURE_1_CFI_SS,
+    GNU_PROPERTY_STACK_SIZE, NT_GNU_ABI_TAG, NT_GNU_PROPERTY_TYPE_0,
+};
+use elf_parser::elf::shdr::{SHF_ALLOC, SHF_MERGE, SHF_STRINGS, SHT_NOTE, SHT_PROGBITS};
+use fixtures::{ImageBuilder, SectionSpec, ENCODINGS};
+
+/// `.comment` of a file compiled by gcc and linked by GNU ld, which adds nothing
+const GCC_COMMENT: &[u8] = b"GCC: (GNU) 13.2.0\0";
+/// `.comment` of a file compiled by clang and linked by lld, which starts the section
+/// with an empty string
+const CLANG_COMMENT: &[u8] = b"\0Linker: LLD 17.0.6\0clang version 17.0.6\0";
+
+fn comment(builder: ImageBuilder, data: &[u8]) -> ImageBuilder {
+    builder.section_with(SectionSpec {
+        name: ".comment".to_string(),
+        s_type: SHT_PROGBITS,
+        flags: SHF_MERGE | SHF_STRINGS,
+        align: 1,
+        link: 0,
+        info: 0,
+        ent_size: 1,
+        data: data.to_vec(),
+    })
+}
+
+/// `.note.ABI-tag` as glibc's crt1.o brings it: GNU/Linux, kernel 3.2.0 or later
+fn abi_tag(builder: ImageBuilder) -> ImageBuilder {
+    let ctx = builder.ctx();
+    let mut note = vec![];
+    ctx.put_u32(&mut note, 4);
+    ctx.put_u32(&mut note, 16);
+    ctx.put_u32(&mut note, NT_GNU_ABI_TAG);
+    note.extend_from_slice(b"GNU\0");
+    for word in [0, 3, 2, 0] {
+        ctx.put_u32(&mut note, word);
+    }
+
+    builder.section_with(SectionSpec {
+        name: ".note.ABI-tag".to_string(),
+        s_type: SHT_NOTE,
+        flags: SHF_ALLOC,
+        align: 4,
+        link: 0,
+        info: 0,
+        ent_size: 0,
+        data: note,
+    })
+}
+
+#[test]
+fn gcc_built() {
+    for (class, endianness) in ENCODINGS {
+        let builder = ImageBuilder::minimal(class, endianness);
+        let image = abi_tag(comment(builder, GCC_COMMENT)).build();
+        let info = image.parse().unwrap().toolchain_info().unwrap();
+
+        assert_eq!(info.comments.unwrap(), ["GCC: (GNU) 13.2.0"]);
+        let tag = info.abi_tag.unwrap();
+        assert_eq!(tag.os, GnuAbiOs::Linux);
+        assert_eq!(tag.kernel_version, (3, 2, 0));
+        assert_eq!(tag.os.to_string(), "GNU/Linux");
+    }
+}
+
+#[test]
+fn clang_built() {
+    for (class, endianness) in ENCODINGS {
+        let builder = ImageBuilder::minimal(class, endianness);
+        let image = comment(builder, CLANG_COMMENT).build();
+        let info = image.parse().unwrap().toolchain_info().unwrap();
+
+        // the leading empty string isn't one of the comments
+        assert_eq!(
+            info.comments.unwrap(),
+            ["Linker: LLD 17.0.6", "clang version 17.0.6"]
+        );
+        assert_eq!(info.abi_tag, None);
+    }
+}
+
+#[test]
+fn without_toolchain_sections() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+        let info = image.parse().unwrap().toolchain_info().unwrap();
+
+        assert_eq!(info.comments, None);
+        assert_eq!(info.abi_tag, None);
+    }
+}
+
+/// Appends a property to a `NT_GNU_PROPERTY_TYPE_0` descriptor, padding its data to
+/// the alignment of the class
+fn property(desc: &mut Vec<u8>, ctx: &ParseContext, pr_type: u32, data: &[u8]) {
+    ctx.put_u32(desc, pr_type);
+    ctx.put_u32(desc, data.len() as u32);
+    desc.extend_from_slice(data);
+    let align = match ctx.class {
+        ElfHClass::ElfClass64 => 8,
+        _ => 4,
+    };
+    desc.resize(desc.len().next_multiple_of(align), 0);
+}
+
+/// A single note holding several properties, as the linker merges them
+#[test]
+fn multiple_gnu_properties() {
+    for (class, endianness) in ENCODINGS {
+        let builder = ImageBuilder::minimal(class, endianness);
+        let ctx = builder.ctx();
+
+        let mut features = vec![];
+        ctx.put_u32(
+            &mut features,
+            GNU_PROPERTY_RISCV_FEATURE_1_CFI_LP_UNLABELED | GNU_PROPERTY_RISCV_FEATURE_1_CFI_SS,
+        );
+        let mut stack_size = vec![];
+        ctx.put_uword(&mut stack_size, 0x80000);
+
+        let mut desc = vec![];
+        property(&mut desc, &ctx, GNU_PROPERTY_STACK_SIZE, &stack_size);
+        property(&mut desc, &ctx, GNU_PROPERTY_NO_COPY_ON_PROTECTED, &[]);
+        property(&mut desc, &ctx, GNU_PROPERTY_RISCV_FEATURE_1_AND, &features);
+
+        let mut note = vec![];
+        ctx.put_u32(&mut note, 4);
+        ctx.put_u32(&mut note, desc.len() as u32);
+        ctx.put_u32(&mut note, NT_GNU_PROPERTY_TYPE_0);
+        note.extend_from_slice(b"GNU\0");
+        note.extend_from_slice(&desc);
+
+        let align = match class {
+            ElfHClass::ElfClass64 => 8,
+            _ => 4,
+        };
+        let image = builder
+            .section_with(SectionSpec {
+                name: ".note.gnu.property".to_string(),
+                s_type: SHT_NOTE,
+                flags: SHF_ALLOC,
+                align,
+                link: 0,
+                info: 0,
+                ent_size: 0,
+                data: note,
+            })
+            .build();
+        let parser = image.parse().unwrap();
+        assert_eq!(parser.headers.machine, EM_RISCV);
+
+        let properties = parser.gnu_properties().unwrap();
+        let kinds: Vec<_> = properties
+            .iter()
+            .map(|p| p.kind(parser.headers.machine, &parser.ctx))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                GnuPropertyKind::StackSize(0x80000),
+                GnuPropertyKind::NoCopyOnProtected,
+                GnuPropertyKind::RiscvFeature1And {
+                    lp_unlabeled: true,
+                    shadow_stack: true,
+                    lp_func_sig: false,
+                    raw: 0x3,
+                },
+            ],
+            "{:?}",
+            class
+        );
+        // the data is kept without its padding
+        assert_eq!(properties[0].data, stack_size);
+        assert!(properties[1].data.is_empty());
+        assert_eq!(properties[2].data.len(), 4);
+    }
+}
@@ -0,0 +1,262 @@
+mod fixtures;
+
+use elf_parser::bytes::{Address, ParseContext};
+use elf_parser::dynamic::SymbolVersion;
+use elf_parser::elf::ehdr::{ElfHClass, ElfHData, ElfHType};
+use elf_parser::elf::phdr::{
+    PType, DT_HASH, DT_NULL, DT_STRSZ, DT_STRTAB, DT_SYMENT, DT_SYMTAB, DT_VERDEF, DT_VERDEFNUM,
+    DT_VERNEED, DT_VERNEEDNUM, DT_VERSYM, PF_READ, PF_WRITE,
+};
+use elf_parser::elf::shdr::{
+    SHF_ALLOC, SHF_WRITE, SHT_DYNAMIC, SHT_DYNSYM, SHT_GNU_VERDEF, SHT_GNU_VERNEED, SHT_GNU_VERSYM,
+    SHT_HASH, SHT_STRTAB,
+};
+use elf_parser::elf::sym::{Elf64Sym, STB_GLOBAL, STT_FUNC};
+use elf_parser::elf::version::{
+    Verdef, Vernaux, Verneed, VERSYM_HIDDEN, VER_FLG_BASE, VER_FLG_WEAK,
+};
+use elf_parser::layout;
+use fixtures::{Image, ImageBuilder, ENCODINGS};
+
+/// Dynamic symbols of `library` along with their `.gnu.version` entry: a default
+/// version, a hidden older one, a version required from libc, and no version
+const SYMBOLS: [(&str, u16); 4] = [
+    ("foo", 2),
+    ("foo_old", 3 | VERSYM_HIDDEN),
+    ("puts", 4),
+    ("bar", 1),
+];
+
+/// Interns `s` in the string table `strtab`, returning its offset
+fn string(strtab: &mut Vec<u8>, s: &str) -> u32 {
+    let off = strtab.len() as u32;
+    strtab.extend(s.as_bytes());
+    strtab.push(0);
+    off
+}
+
+/// `.gnu.version_d` of `libfoo.so`: the file itself, `LIBFOO_1.0` inheriting from
+/// `LIBFOO_0.9`, and `LIBFOO_0.9`. Definitions and their names are chained through
+/// relative offsets, each definition being followed by its names
+fn verdef(ctx: &ParseContext, dynstr: &mut Vec<u8>) -> Vec<u8> {
+    let defs: [(u16, u16, u32, &[&str]); 3] = [
+        (VER_FLG_BASE, 1, 0x0a1b2c3d, &["libfoo.so"]),
+        (0, 2, 0x01020304, &["LIBFOO_1.0", "LIBFOO_0.9"]),
+        (0, 3, 0x05060708, &["LIBFOO_0.9"]),
+    ];
+    let mut data = vec![];
+    for (idx, (flags, ndx, hash, names)) in defs.iter().enumerate() {
+        let size = 20 + 8 * names.len() as u32;
+        ctx.put_u16(&mut data, 1);
+        ctx.put_u16(&mut data, *flags);
+        ctx.put_u16(&mut data, *ndx);
+        ctx.put_u16(&mut data, names.len() as u16);
+        ctx.put_u32(&mut data, *hash);
+        ctx.put_u32(&mut data, 20);
+        ctx.put_u32(&mut data, if idx + 1 < defs.len() { size } else { 0 });
+        for (name_idx, name) in names.iter().enumerate() {
+            let name = string(dynstr, name);
+            ctx.put_u32(&mut data, name);
+            ctx.put_u32(&mut data, if name_idx + 1 < names.len() { 8 } else { 0 });
+        }
+    }
+    data
+}
+
+/// `.gnu.version_r` requiring `GLIBC_2.2.5`, and weakly `GLIBC_2.34`, from `libc.so.6`
+fn verneed(ctx: &ParseContext, dynstr: &mut Vec<u8>) -> Vec<u8> {
+    let versions = [
+        (0x09691a75, 0, 4, "GLIBC_2.2.5"),
+        (0x069691b4, VER_FLG_WEAK, 5, "GLIBC_2.34"),
+    ];
+    let mut data = vec![];
+    ctx.put_u16(&mut data, 1);
+    ctx.put_u16(&mut data, versions.len() as u16);
+    let file = string(dynstr, "libc.so.6");
+    ctx.put_u32(&mut data, file);
+    ctx.put_u32(&mut data, 16);
+    ctx.put_u32(&mut data, 0);
+    for (idx, (hash, flags, other, name)) in versions.iter().enumerate() {
+        ctx.put_u32(&mut data, *hash);
+        ctx.put_u16(&mut data, *flags);
+        ctx.put_u16(&mut data, *other);
+        let name = string(dynstr, name);
+        ctx.put_u32(&mut data, name);
+        ctx.put_u32(&mut data, if idx + 1 < versions.len() { 16 } else { 0 });
+    }
+    data
+}
+
+/// `libfoo.so`, defining and requiring versions, whose tables the dynamic segment
+/// locates by address
+fn library(class: ElfHClass, endianness: ElfHData) -> Image {
+    let sym_size = layout::sym::for_class(class).size;
+    let build = |first: Option<&Image>| {
+        let builder = ImageBuilder::minimal(class, endianness).e_type(ElfHType::SharedObject);
+        let ctx = builder.ctx();
+        let addr = |name| first.map_or(0, |image: &Image| image.section(name).addr.0);
+
+        let mut hash = vec![];
+        for word in [1, SYMBOLS.len() as u32 + 1, 0, 0, 0, 0, 0, 0] {
+            ctx.put_u32(&mut hash, word);
+        }
+
+        let mut dynstr = b"\0".to_vec();
+        let mut dynsym = vec![0; sym_size];
+        let mut versym = vec![];
+        ctx.put_u16(&mut versym, 0);
+        for (name, version) in SYMBOLS {
+            let symbol = Elf64Sym {
+                name: string(&mut dynstr, name),
+                info: STB_GLOBAL << 4 | STT_FUNC,
+                other: 0,
+                shndx: if name == "puts" { 0 } else { 1 },
+                value: Address(0),
+                size: 0,
+            };
+            symbol.write(&ctx, &mut dynsym);
+            ctx.put_u16(&mut versym, version);
+        }
+        let verdef = verdef(&ctx, &mut dynstr);
+        let verneed = verneed(&ctx, &mut dynstr);
+
+        let mut dynamic = vec![];
+        for (tag, value) in [
+            (DT_HASH, addr(".hash")),
+            (DT_SYMTAB, addr(".dynsym")),
+            (DT_STRTAB, addr(".dynstr")),
+            (DT_STRSZ, dynstr.len() as u64),
+            (DT_SYMENT, sym_size as u64),
+            (DT_VERSYM, addr(".gnu.version")),
+            (DT_VERDEF, addr(".gnu.version_d")),
+            (DT_VERDEFNUM, 3),
+            (DT_VERNEED, addr(".gnu.version_r")),
+            (DT_VERNEEDNUM, 1),
+            (DT_NULL, 0),
+        ] {
+            ctx.put_uword(&mut dynamic, tag as u64);
+            ctx.put_uword(&mut dynamic, value);
+        }
+
+        builder
+            .section(".hash", SHT_HASH, SHF_ALLOC, &hash)
+            .section(".dynsym", SHT_DYNSYM, SHF_ALLOC, &dynsym)
+            .section(".dynstr", SHT_STRTAB, SHF_ALLOC, &dynstr)
+            .section(".gnu.version", SHT_GNU_VERSYM, SHF_ALLOC, &versym)
+            .section(".gnu.version_d", SHT_GNU_VERDEF, SHF_ALLOC, &verdef)
+            .section(".gnu.version_r", SHT_GNU_VERNEED, SHF_ALLOC, &verneed)
+            .section(".dynamic", SHT_DYNAMIC, SHF_ALLOC | SHF_WRITE, &dynamic)
+            .segment(
+                PType::PtLoad,
+                PF_READ,
+                &[
+                    ".hash",
+                    ".dynsym",
+                    ".dynstr",
+                    ".gnu.version",
+                    ".gnu.version_d",
+                    ".gnu.version_r",
+                ],
+            )
+            .segment(PType::PtLoad, PF_READ | PF_WRITE, &[".dynamic"])
+            .segment(PType::PtDynamic, PF_READ | PF_WRITE, &[".dynamic"])
+            .build()
+    };
+
+    build(Some(&build(None)))
+}
+
+#[test]
+fn version_definitions() {
+    for (class, endianness) in ENCODINGS {
+        let image = library(class, endianness);
+        let parser = image.parse().unwrap();
+        let defs = parser.version_definitions().unwrap();
+
+        assert_eq!(
+            defs.iter()
+                .map(|d| (d.flags, d.ndx, d.hash))
+                .collect::<Vec<_>>(),
+            [
+                (VER_FLG_BASE, 1, 0x0a1b2c3d),
+                (0, 2, 0x01020304),
+                (0, 3, 0x05060708)
+            ]
+        );
+        let names = |def: &Verdef| -> Vec<String> {
+            def.names
+                .iter()
+                .map(|name| parser.dynamic_string(*name).unwrap())
+                .collect()
+        };
+        assert_eq!(names(&defs[0]), ["libfoo.so"]);
+        assert_eq!(names(&defs[1]), ["LIBFOO_1.0", "LIBFOO_0.9"]);
+        assert_eq!(names(&defs[2]), ["LIBFOO_0.9"]);
+    }
+}
+
+#[test]
+fn version_dependencies() {
+    for (class, endianness) in ENCODINGS {
+        let image = library(class, endianness);
+        let parser = image.parse().unwrap();
+        let needs = parser.version_dependencies().unwrap();
+
+        assert_eq!(needs.len(), 1);
+        let Verneed { file, versions } = &needs[0];
+        assert_eq!(parser.dynamic_string(*file).unwrap(), "libc.so.6");
+        assert_eq!(
+            versions
+                .iter()
+                .map(|v: &Vernaux| (
+                    v.hash,
+                    v.flags,
+                    v.other,
+                    parser.dynamic_string(v.name).unwrap()
+                ))
+                .collect::<Vec<_>>(),
+            [
+                (0x09691a75, 0, 4, "GLIBC_2.2.5".to_string()),
+                (0x069691b4, VER_FLG_WEAK, 5, "GLIBC_2.34".to_string()),
+            ]
+        );
+    }
+}
+
+#[test]
+fn symbol_versions() {
+    for (class, endianness) in ENCODINGS {
+        let image = library(class, endianness);
+        let parser = image.parse().unwrap();
+        let version = |name: &str, library: Option<&str>, hidden| {
+            Some(SymbolVersion {
+                name: name.to_string(),
+                library: library.map(str::to_string),
+                hidden,
+            })
+        };
+
+        assert_eq!(
+            parser.symbol_versions().unwrap(),
+            [
+                None,
+                version("LIBFOO_1.0", None, false),
+                version("LIBFOO_0.9", None, true),
+                version("GLIBC_2.2.5", Some("libc.so.6"), false),
+                // bound to no version
+                None,
+            ]
+        );
+    }
+}
+
+#[test]
+fn unversioned() {
+    for (class, endianness) in ENCODINGS {
+        let image = ImageBuilder::minimal(class, endianness).build();
+        let parser = image.parse().unwrap();
+
+        assert!(parser.version_definitions().unwrap().is_empty());
+        assert!(parser.version_dependencies().unwrap().is_empty());
+    }
+}
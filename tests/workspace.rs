@@ -0,0 +1,101 @@
+mod fixtures;
+
+use elf_parser::bytes::Address;
+use elf_parser::elf::sym::{Elf64Sym, STB_GLOBAL, STT_FUNC};
+use elf_parser::workspace::{NameId, Workspace};
+use fixtures::{Image, ImageBuilder, ENCODINGS};
+
+fn function(value: u64) -> Elf64Sym {
+    Elf64Sym {
+        name: 0,
+        info: STB_GLOBAL << 4 | STT_FUNC,
+        other: 0,
+        shndx: 1,
+        value: Address(value),
+        size: 0,
+    }
+}
+
+fn images() -> Vec<Image> {
+    ENCODINGS
+        .into_iter()
+        .map(|(class, endianness)| {
+            ImageBuilder::minimal(class, endianness)
+                .symbols(&[("main", function(0x10074)), ("memcpy", function(0x10080))])
+                .build()
+        })
+        .collect()
+}
+
+#[test]
+fn interned_names() {
+    let mut workspace = Workspace::new();
+    assert!(workspace.is_empty());
+    assert_eq!(workspace.get(".text"), None);
+
+    let text = workspace.intern(".text");
+    let data = workspace.intern(".data");
+    assert_ne!(text, data);
+    assert_eq!(workspace.intern(".text"), text);
+    assert_eq!(workspace.get(".text"), Some(text));
+    assert_eq!(workspace.resolve(text), ".text");
+    assert_eq!(workspace.resolve(data), ".data");
+    assert_eq!(workspace.len(), 2);
+}
+
+#[test]
+fn parsed_names_resolve() {
+    let mut workspace = Workspace::new();
+
+    for image in images() {
+        let parsed = workspace.parse(&image.bytes).unwrap();
+
+        for (idx, sh) in parsed.parser.section_headers.iter().enumerate() {
+            let name = parsed.section_name(idx).unwrap();
+            assert_eq!(
+                workspace.resolve(name),
+                parsed.parser.section_name(sh).unwrap()
+            );
+        }
+        // the null symbol has an empty name
+        let names: Vec<_> = parsed.symbol_names[0]
+            .iter()
+            .map(|id| workspace.resolve(*id))
+            .collect();
+        assert_eq!(names, ["", "main", "memcpy"]);
+        assert_eq!(
+            parsed.section_name(parsed.parser.section_headers.len()),
+            None
+        );
+        assert_eq!(parsed.symbol_name(0, 3), None);
+        assert_eq!(parsed.symbol_name(1, 0), None);
+    }
+}
+
+/// Names repeating across files are stored once, so parsing more files with the same
+/// names doesn't grow the workspace
+#[test]
+fn names_shared_across_parses() {
+    let images = images();
+    let mut workspace = Workspace::new();
+
+    let first = workspace.parse(&images[0].bytes).unwrap();
+    let interned = workspace.len();
+    // the null section and the null symbol share the empty name
+    let mut names: Vec<NameId> = first.section_names.clone();
+    names.extend(&first.symbol_names[0]);
+    names.sort();
+    names.dedup();
+    assert_eq!(interned, names.len());
+
+    for image in &images {
+        let parsed = workspace.parse(&image.bytes).unwrap();
+
+        assert_eq!(parsed.section_names, first.section_names);
+        assert_eq!(parsed.symbol_names, first.symbol_names);
+    }
+    assert_eq!(workspace.len(), interned);
+
+    let main = workspace.get("main").unwrap();
+    assert_eq!(first.symbol_name(0, 1), Some(main));
+}